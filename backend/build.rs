@@ -0,0 +1,46 @@
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    // Migrations are embedded by `sqlx::migrate!`, so adding one has to trigger a rebuild
+    println!("cargo:rerun-if-changed=migrations");
+
+    // Same for the embedded frontend, whose new files aren't noticed otherwise
+    if std::env::var_os("CARGO_FEATURE_EMBED_FRONTEND").is_some() {
+        println!("cargo:rerun-if-changed=../frontend/build");
+    }
+
+    // Reported by `GET /api/version`. Docker builds have no `.git`, so they pass the commit
+    // as a build argument instead.
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/refs");
+    let commit = std::env::var("GIT_COMMIT")
+        .ok()
+        .filter(|commit| !commit.is_empty())
+        .or_else(|| {
+            let output = Command::new("git")
+                .args(["rev-parse", "HEAD"])
+                .output()
+                .ok()?;
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        });
+    if let Some(commit) = commit {
+        println!("cargo:rustc-env=GIT_COMMIT={commit}");
+    }
+
+    // Reproducible builds set the timestamp themselves
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    let built_at = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs())
+        });
+    println!("cargo:rustc-env=BUILD_TIMESTAMP={built_at}");
+}
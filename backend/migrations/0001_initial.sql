@@ -0,0 +1,69 @@
+-- Schema created on startup before schema changes were migrations. Databases from back then
+-- have exactly these tables; they're found by their missing migration history and get this
+-- migration recorded as applied instead of run.
+
+CREATE TABLE games (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    event_date TEXT NOT NULL,
+    organizer_email TEXT NOT NULL,
+    admin_token TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL,
+    drawn INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE participants (
+    id TEXT PRIMARY KEY,
+    game_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    email TEXT NOT NULL,
+    matched_with_id TEXT,
+    view_token TEXT NOT NULL UNIQUE,
+    has_viewed INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
+
+CREATE TABLE email_verifications (
+    id TEXT PRIMARY KEY,
+    email TEXT NOT NULL,
+    code TEXT NOT NULL,
+    game_name TEXT NOT NULL,
+    event_date TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    verified INTEGER NOT NULL DEFAULT 0,
+    attempts INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX idx_email_verifications_email ON email_verifications(email);
+CREATE INDEX idx_email_verifications_code ON email_verifications(code);
+
+CREATE TABLE email_resends (
+    id TEXT PRIMARY KEY,
+    game_id TEXT NOT NULL,
+    participant_id TEXT,
+    resend_type TEXT NOT NULL,
+    resent_at TEXT NOT NULL,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE,
+    FOREIGN KEY (participant_id) REFERENCES participants(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_email_resends_game_id ON email_resends(game_id);
+CREATE INDEX idx_email_resends_participant_id ON email_resends(participant_id);
+
+CREATE TABLE site_admin_password (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    password_hash TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE admin_sessions (
+    id TEXT PRIMARY KEY,
+    session_token TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_admin_sessions_token ON admin_sessions(session_token);
+CREATE INDEX idx_admin_sessions_expires ON admin_sessions(expires_at);
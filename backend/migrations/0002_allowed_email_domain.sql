@@ -0,0 +1,2 @@
+-- Domain participant emails must be at, when the organizer restricts it
+ALTER TABLE games ADD COLUMN allowed_email_domain TEXT;
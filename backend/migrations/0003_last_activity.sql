@@ -0,0 +1,3 @@
+-- Last time the organizer did something, to expire idle games that were never drawn. NULL
+-- for games created before activity tracking; created_at is used instead.
+ALTER TABLE games ADD COLUMN last_activity_at TEXT;
@@ -0,0 +1,23 @@
+-- Links emailed to organizers to log in without an admin link, and the sessions they start
+
+CREATE TABLE organizer_login_links (
+    id TEXT PRIMARY KEY,
+    token TEXT NOT NULL UNIQUE,
+    email TEXT NOT NULL,
+    game_id TEXT,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    used INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX idx_organizer_login_links_email ON organizer_login_links(email);
+
+CREATE TABLE organizer_sessions (
+    id TEXT PRIMARY KEY,
+    session_token TEXT NOT NULL UNIQUE,
+    email TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_organizer_sessions_expires ON organizer_sessions(expires_at);
@@ -0,0 +1,3 @@
+-- Games the site admin froze pending abuse review
+ALTER TABLE games ADD COLUMN frozen_at TEXT;
+ALTER TABLE games ADD COLUMN frozen_reason TEXT;
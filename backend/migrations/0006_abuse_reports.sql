@@ -0,0 +1,10 @@
+CREATE TABLE abuse_reports (
+    id TEXT PRIMARY KEY,
+    game_id TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    resolved INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_abuse_reports_game_id ON abuse_reports(game_id);
@@ -0,0 +1,2 @@
+-- Games deleted by their organizer, kept for a grace period before they're purged
+ALTER TABLE games ADD COLUMN deleted_at TEXT;
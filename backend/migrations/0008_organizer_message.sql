@@ -0,0 +1 @@
+ALTER TABLE games ADD COLUMN organizer_message TEXT;
@@ -0,0 +1,2 @@
+ALTER TABLE games ADD COLUMN event_location TEXT;
+ALTER TABLE email_verifications ADD COLUMN event_location TEXT;
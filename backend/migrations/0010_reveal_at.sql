@@ -0,0 +1,2 @@
+-- When reveal links start showing the match, if not right after the draw
+ALTER TABLE games ADD COLUMN reveal_at TEXT;
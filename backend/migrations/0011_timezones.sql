@@ -0,0 +1,2 @@
+ALTER TABLE games ADD COLUMN timezone TEXT NOT NULL DEFAULT 'America/Sao_Paulo';
+ALTER TABLE email_verifications ADD COLUMN timezone TEXT NOT NULL DEFAULT 'America/Sao_Paulo';
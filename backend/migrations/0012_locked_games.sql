@@ -0,0 +1,2 @@
+-- Games whose participant list the organizer locked before the draw
+ALTER TABLE games ADD COLUMN locked INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,10 @@
+-- Times the organizer looked at the list of matches
+CREATE TABLE match_accesses (
+    id TEXT PRIMARY KEY,
+    game_id TEXT NOT NULL,
+    access_type TEXT NOT NULL,
+    accessed_at TEXT NOT NULL,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_match_accesses_game_id ON match_accesses(game_id);
@@ -0,0 +1,4 @@
+-- Reveal links that expire after the event or work only once
+ALTER TABLE games ADD COLUMN reveal_expires_after_event INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE games ADD COLUMN reveal_single_use INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE participants ADD COLUMN view_token_used_at TEXT;
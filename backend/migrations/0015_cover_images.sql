@@ -0,0 +1,2 @@
+-- Key of the game's cover image in media storage
+ALTER TABLE games ADD COLUMN cover_image TEXT;
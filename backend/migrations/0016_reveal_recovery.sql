@@ -0,0 +1,12 @@
+-- Codes emailed to participants to recover their reveal links
+CREATE TABLE reveal_recovery (
+    id TEXT PRIMARY KEY,
+    email TEXT NOT NULL,
+    code TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    verified INTEGER NOT NULL DEFAULT 0,
+    attempts INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX idx_reveal_recovery_email ON reveal_recovery(email);
@@ -0,0 +1,12 @@
+CREATE TABLE reveal_events (
+    id TEXT PRIMARY KEY,
+    participant_id TEXT NOT NULL,
+    game_id TEXT NOT NULL,
+    viewed_at TEXT NOT NULL,
+    user_agent TEXT,
+    ip_prefix TEXT,
+    FOREIGN KEY (participant_id) REFERENCES participants(id) ON DELETE CASCADE,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_reveal_events_game_id ON reveal_events(game_id);
@@ -0,0 +1,15 @@
+-- Reminders to participants who haven't seen their match. NULL drawn_at for games drawn
+-- before draw times were recorded.
+ALTER TABLE games ADD COLUMN drawn_at TEXT;
+ALTER TABLE games ADD COLUMN reminders_enabled INTEGER NOT NULL DEFAULT 1;
+
+CREATE TABLE reminder_sends (
+    id TEXT PRIMARY KEY,
+    participant_id TEXT NOT NULL,
+    game_id TEXT NOT NULL,
+    reminder_kind TEXT NOT NULL,
+    sent_at TEXT NOT NULL,
+    UNIQUE (participant_id, reminder_kind),
+    FOREIGN KEY (participant_id) REFERENCES participants(id) ON DELETE CASCADE,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
@@ -0,0 +1,8 @@
+-- Devices each match was seen on, by a hash of their details
+CREATE TABLE reveal_devices (
+    participant_id TEXT NOT NULL,
+    device_hash TEXT NOT NULL,
+    first_seen_at TEXT NOT NULL,
+    PRIMARY KEY (participant_id, device_hash),
+    FOREIGN KEY (participant_id) REFERENCES participants(id) ON DELETE CASCADE
+);
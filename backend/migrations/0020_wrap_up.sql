@@ -0,0 +1,3 @@
+-- Email to the organizer with the game's statistics after the event
+ALTER TABLE games ADD COLUMN wrap_up_enabled INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE games ADD COLUMN wrap_up_sent_at TEXT;
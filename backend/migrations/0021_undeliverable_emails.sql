@@ -0,0 +1,3 @@
+-- Participant emails that bounced or were reported as spam
+ALTER TABLE participants ADD COLUMN email_undeliverable_at TEXT;
+ALTER TABLE participants ADD COLUMN email_undeliverable_reason TEXT;
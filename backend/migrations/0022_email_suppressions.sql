@@ -0,0 +1,13 @@
+-- Addresses that unsubscribed from optional emails, and the tokens of their unsubscribe links
+
+CREATE TABLE email_suppressions (
+    email TEXT PRIMARY KEY COLLATE NOCASE,
+    reason TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE unsubscribe_tokens (
+    token TEXT PRIMARY KEY,
+    email TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    created_at TEXT NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE games ADD COLUMN locale TEXT NOT NULL DEFAULT 'pt-BR';
+ALTER TABLE email_verifications ADD COLUMN locale TEXT NOT NULL DEFAULT 'pt-BR';
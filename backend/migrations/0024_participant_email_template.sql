@@ -0,0 +1,3 @@
+-- Subject and body the organizer wrote for the participant emails, if any
+ALTER TABLE games ADD COLUMN participant_email_subject TEXT;
+ALTER TABLE games ADD COLUMN participant_email_body TEXT;
@@ -0,0 +1,20 @@
+-- Draw emails waiting to be sent by the background job
+
+CREATE TABLE email_jobs (
+    id TEXT PRIMARY KEY,
+    game_id TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    finished_at TEXT,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_email_jobs_unfinished ON email_jobs(finished_at);
+
+CREATE TABLE email_job_recipients (
+    job_id TEXT NOT NULL,
+    participant_id TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    PRIMARY KEY (job_id, participant_id),
+    FOREIGN KEY (job_id) REFERENCES email_jobs(id) ON DELETE CASCADE,
+    FOREIGN KEY (participant_id) REFERENCES participants(id) ON DELETE CASCADE
+);
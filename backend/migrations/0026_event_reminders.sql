@@ -0,0 +1,2 @@
+-- Days before the event to remind participants, comma separated
+ALTER TABLE games ADD COLUMN event_reminder_days TEXT NOT NULL DEFAULT '';
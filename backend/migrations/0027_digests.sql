@@ -0,0 +1,3 @@
+-- Weekly digest emails to the organizer
+ALTER TABLE games ADD COLUMN digest_enabled INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE games ADD COLUMN digest_sent_at TEXT;
@@ -0,0 +1 @@
+ALTER TABLE games ADD COLUMN reply_to_organizer INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,11 @@
+-- Opt-in tracking of when participants open their email, through a pixel with a token
+
+ALTER TABLE games ADD COLUMN open_tracking_enabled INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE participants ADD COLUMN email_opened_at TEXT;
+
+CREATE TABLE email_open_tokens (
+    token TEXT PRIMARY KEY,
+    participant_id TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (participant_id) REFERENCES participants(id) ON DELETE CASCADE
+);
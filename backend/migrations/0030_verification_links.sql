@@ -0,0 +1,2 @@
+-- Link in verification emails that confirms the address without typing the code
+ALTER TABLE email_verifications ADD COLUMN link_token TEXT;
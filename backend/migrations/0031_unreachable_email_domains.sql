@@ -0,0 +1,2 @@
+-- Participant emails whose domain has no mail server
+ALTER TABLE participants ADD COLUMN email_domain_unreachable_at TEXT;
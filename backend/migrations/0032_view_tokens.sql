@@ -0,0 +1,51 @@
+-- View tokens move to their own table, so a participant can have several and later emails
+-- can carry new ones. They're stored hashed, with a plaintext prefix to find them by: the
+-- ones copied here are hashed on startup (`hash_plaintext_secrets`), as SQL can't.
+--
+-- Dropping the unique `participants.view_token` takes rebuilding the table, which replaces
+-- it under the same name. Migrations run with foreign keys off, so rows referring to
+-- participants are kept.
+
+CREATE TABLE participants_new (
+    id TEXT PRIMARY KEY,
+    game_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    email TEXT NOT NULL,
+    matched_with_id TEXT,
+    has_viewed INTEGER NOT NULL DEFAULT 0,
+    view_token_used_at TEXT,
+    created_at TEXT NOT NULL,
+    email_undeliverable_at TEXT,
+    email_undeliverable_reason TEXT,
+    email_opened_at TEXT,
+    email_domain_unreachable_at TEXT,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
+
+INSERT INTO participants_new (
+    id, game_id, name, email, matched_with_id, has_viewed, view_token_used_at, created_at,
+    email_undeliverable_at, email_undeliverable_reason, email_opened_at,
+    email_domain_unreachable_at
+)
+SELECT
+    id, game_id, name, email, matched_with_id, has_viewed, view_token_used_at, created_at,
+    email_undeliverable_at, email_undeliverable_reason, email_opened_at,
+    email_domain_unreachable_at
+FROM participants;
+
+CREATE TABLE view_tokens (
+    lookup_key TEXT NOT NULL,
+    token_hash TEXT NOT NULL,
+    participant_id TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (participant_id) REFERENCES participants(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_view_tokens_lookup_key ON view_tokens(lookup_key);
+CREATE INDEX idx_view_tokens_participant_id ON view_tokens(participant_id);
+
+INSERT INTO view_tokens (lookup_key, token_hash, participant_id, created_at)
+SELECT substr(view_token, 1, 8), view_token, id, created_at FROM participants;
+
+DROP TABLE participants;
+ALTER TABLE participants_new RENAME TO participants;
@@ -0,0 +1,12 @@
+-- Two-step verification for the site admin. The secret is set when enrollment starts, and
+-- only required at login once enabled.
+
+ALTER TABLE site_admin_password ADD COLUMN totp_secret TEXT;
+ALTER TABLE site_admin_password ADD COLUMN totp_enabled INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE site_admin_password ADD COLUMN totp_last_step INTEGER;
+
+CREATE TABLE site_admin_recovery_codes (
+    id TEXT PRIMARY KEY,
+    code_hash TEXT NOT NULL,
+    used_at TEXT
+);
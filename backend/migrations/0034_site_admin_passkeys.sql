@@ -0,0 +1,19 @@
+-- Passkeys are stored as the `Passkey` of webauthn-rs, as JSON holding the credential ID, COSE
+-- key, signature counter and flags together, and a started ceremony keeps its webauthn-rs
+-- state until the browser answers.
+
+CREATE TABLE site_admin_passkeys (
+    id TEXT PRIMARY KEY,
+    credential_id TEXT NOT NULL UNIQUE,
+    passkey TEXT NOT NULL,
+    name TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    last_used_at TEXT
+);
+
+CREATE TABLE webauthn_challenges (
+    challenge TEXT PRIMARY KEY,
+    ceremony TEXT NOT NULL,
+    state TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
@@ -0,0 +1,2 @@
+-- NULL until the session is first used after logging in
+ALTER TABLE admin_sessions ADD COLUMN last_used_at TEXT;
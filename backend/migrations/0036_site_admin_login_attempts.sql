@@ -0,0 +1,9 @@
+CREATE TABLE site_admin_login_attempts (
+    id TEXT PRIMARY KEY,
+    outcome TEXT NOT NULL,
+    ip_prefix TEXT,
+    user_agent TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_site_admin_login_attempts_created ON site_admin_login_attempts(created_at);
@@ -0,0 +1,18 @@
+-- Announcements from the site admin to the organizers of active games
+
+CREATE TABLE announcements (
+    id TEXT PRIMARY KEY,
+    subject TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    finished_at TEXT
+);
+
+CREATE TABLE announcement_recipients (
+    announcement_id TEXT NOT NULL,
+    email TEXT NOT NULL COLLATE NOCASE,
+    locale TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    PRIMARY KEY (announcement_id, email),
+    FOREIGN KEY (announcement_id) REFERENCES announcements(id) ON DELETE CASCADE
+);
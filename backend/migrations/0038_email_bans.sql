@@ -0,0 +1,7 @@
+-- Addresses and domains (with their subdomains) that can't create games
+CREATE TABLE email_bans (
+    id TEXT PRIMARY KEY,
+    value TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    reason TEXT,
+    created_at TEXT NOT NULL
+);
@@ -0,0 +1,6 @@
+-- Settings the site admin can change at runtime
+CREATE TABLE site_settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
@@ -0,0 +1,8 @@
+-- Latest run of each background task
+CREATE TABLE job_runs (
+    job TEXT PRIMARY KEY,
+    started_at TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    processed INTEGER,
+    error TEXT
+);
@@ -0,0 +1,12 @@
+-- Indexes for the queries run on most requests and by the background tasks.
+-- `amigo-oculto-backend explain` prints the query plans of the hot queries.
+
+-- Participants of a game, in the order they joined
+CREATE INDEX idx_participants_game_id ON participants(game_id, created_at);
+
+-- Games due for cleanup, reminders and digests
+CREATE INDEX idx_games_event_date ON games(event_date);
+
+-- Bulk resend limits, which count a game's recent resends of that kind
+CREATE INDEX idx_email_resends_type
+    ON email_resends(game_id, resend_type, resent_at);
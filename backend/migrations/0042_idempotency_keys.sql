@@ -0,0 +1,13 @@
+-- Requests sent with an `Idempotency-Key` header, so that retries get the first response
+-- instead of creating or sending things twice. Keys are stored hashed, and responses
+-- encrypted with a key derived from the `Idempotency-Key`, as they can hold tokens.
+CREATE TABLE idempotency_keys (
+    key_hash TEXT PRIMARY KEY,
+    -- Hash of the method, path, credentials and body of the first request
+    request_hash TEXT NOT NULL,
+    -- NULL while the first request is being handled
+    response BLOB,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_idempotency_keys_created_at ON idempotency_keys(created_at);
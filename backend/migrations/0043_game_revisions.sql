@@ -0,0 +1,58 @@
+-- Revision of each game, bumped whenever what `GET /api/games/{game_id}` shows may have
+-- changed: the game, its participants, their reveals and devices, and resends. The status is
+-- only read again when it changed, so polling clients get a `304 Not Modified` otherwise.
+ALTER TABLE games ADD COLUMN revision INTEGER NOT NULL DEFAULT 0;
+
+-- Recursive triggers are off, so this doesn't fire itself again
+CREATE TRIGGER games_revision_update AFTER UPDATE ON games
+WHEN NEW.revision = OLD.revision
+BEGIN
+    UPDATE games SET revision = revision + 1 WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER participants_revision_insert AFTER INSERT ON participants
+BEGIN
+    UPDATE games SET revision = revision + 1 WHERE id = NEW.game_id;
+END;
+
+CREATE TRIGGER participants_revision_update AFTER UPDATE ON participants
+BEGIN
+    UPDATE games SET revision = revision + 1 WHERE id = NEW.game_id;
+END;
+
+CREATE TRIGGER participants_revision_delete AFTER DELETE ON participants
+BEGIN
+    UPDATE games SET revision = revision + 1 WHERE id = OLD.game_id;
+END;
+
+CREATE TRIGGER reveal_events_revision_insert AFTER INSERT ON reveal_events
+BEGIN
+    UPDATE games SET revision = revision + 1 WHERE id = NEW.game_id;
+END;
+
+CREATE TRIGGER reveal_events_revision_delete AFTER DELETE ON reveal_events
+BEGIN
+    UPDATE games SET revision = revision + 1 WHERE id = OLD.game_id;
+END;
+
+CREATE TRIGGER reveal_devices_revision_insert AFTER INSERT ON reveal_devices
+BEGIN
+    UPDATE games SET revision = revision + 1
+    WHERE id = (SELECT game_id FROM participants WHERE id = NEW.participant_id);
+END;
+
+CREATE TRIGGER reveal_devices_revision_delete AFTER DELETE ON reveal_devices
+BEGIN
+    UPDATE games SET revision = revision + 1
+    WHERE id = (SELECT game_id FROM participants WHERE id = OLD.participant_id);
+END;
+
+CREATE TRIGGER email_resends_revision_insert AFTER INSERT ON email_resends
+BEGIN
+    UPDATE games SET revision = revision + 1 WHERE id = NEW.game_id;
+END;
+
+CREATE TRIGGER email_resends_revision_delete AFTER DELETE ON email_resends
+BEGIN
+    UPDATE games SET revision = revision + 1 WHERE id = OLD.game_id;
+END;
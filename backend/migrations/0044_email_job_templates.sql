@@ -0,0 +1,7 @@
+-- Which email a job sends. Emails are rendered from the game and participants when they're
+-- sent, so retries use the latest template and data.
+ALTER TABLE email_jobs ADD COLUMN template TEXT NOT NULL DEFAULT 'participant_notification';
+
+-- Failed sends are retried a few times, after a delay, before the recipient counts as failed
+ALTER TABLE email_job_recipients ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE email_job_recipients ADD COLUMN retry_at TEXT;
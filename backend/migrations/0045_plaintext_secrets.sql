@@ -0,0 +1,18 @@
+-- Tokens and codes stored in plaintext by versions from before they were hashed. SQL can't
+-- hash them, so they're listed here for the server to hash on startup
+-- (`hash_plaintext_secrets`), which takes them off the list. Only this migration scans the
+-- tables for them.
+CREATE TABLE plaintext_secrets (
+    table_name TEXT NOT NULL,
+    row_id INTEGER NOT NULL,
+    PRIMARY KEY (table_name, row_id)
+);
+
+INSERT INTO plaintext_secrets (table_name, row_id)
+SELECT 'games', rowid FROM games WHERE instr(admin_token, '$') = 0;
+
+INSERT INTO plaintext_secrets (table_name, row_id)
+SELECT 'view_tokens', rowid FROM view_tokens WHERE instr(token_hash, '$') = 0;
+
+INSERT INTO plaintext_secrets (table_name, row_id)
+SELECT 'email_verifications', rowid FROM email_verifications WHERE instr(code, '$') = 0;
@@ -0,0 +1,234 @@
+//! Where site admin sessions are kept.
+//!
+//! Sessions live in the database, unless `REDIS_URL` is set: then they're kept in Redis, so
+//! every instance of the server accepts them. Behind the [`AdminSessionStore`] trait either
+//! way.
+
+use crate::clock::Clock;
+use crate::db::{ADMIN_SESSION_DURATION_HOURS, Database};
+use crate::models::AdminSession;
+use crate::redis::Redis;
+use crate::token::{AdminSessionId, AdminSessionToken};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+pub type SessionFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Site admin sessions, which expire after a period without use.
+pub trait AdminSessionStore: Send + Sync {
+    fn create(&self) -> SessionFuture<'_, AdminSessionToken>;
+
+    /// Whether the session is valid. Using a session extends it.
+    fn validate<'a>(&'a self, token: &'a AdminSessionToken) -> SessionFuture<'a, bool>;
+
+    /// Active sessions, most recently created first, marking the one making the request.
+    fn list<'a>(&'a self, current: &'a AdminSessionToken) -> SessionFuture<'a, Vec<AdminSession>>;
+
+    /// Revoke a session by ID. Returns false if there's no such session.
+    fn delete_by_id(&self, id: AdminSessionId) -> SessionFuture<'_, bool>;
+
+    /// End a session (logout).
+    fn delete<'a>(&'a self, token: &'a AdminSessionToken) -> SessionFuture<'a, ()>;
+
+    /// Forget expired sessions. Returns how many there were.
+    fn cleanup_expired(&self) -> SessionFuture<'_, u64>;
+}
+
+impl AdminSessionStore for Database {
+    fn create(&self) -> SessionFuture<'_, AdminSessionToken> {
+        Box::pin(self.create_admin_session())
+    }
+
+    fn validate<'a>(&'a self, token: &'a AdminSessionToken) -> SessionFuture<'a, bool> {
+        Box::pin(self.validate_admin_session(token))
+    }
+
+    fn list<'a>(&'a self, current: &'a AdminSessionToken) -> SessionFuture<'a, Vec<AdminSession>> {
+        Box::pin(self.list_admin_sessions(current))
+    }
+
+    fn delete_by_id(&self, id: AdminSessionId) -> SessionFuture<'_, bool> {
+        Box::pin(self.delete_admin_session_by_id(id))
+    }
+
+    fn delete<'a>(&'a self, token: &'a AdminSessionToken) -> SessionFuture<'a, ()> {
+        Box::pin(self.delete_admin_session(token))
+    }
+
+    fn cleanup_expired(&self) -> SessionFuture<'_, u64> {
+        Box::pin(self.cleanup_expired_admin_sessions())
+    }
+}
+
+/// Set of the keys of all sessions, to list them.
+const SESSION_INDEX_KEY: &str = "amigo-oculto:admin-sessions";
+
+/// A session as stored in Redis, under a hash of its token. Redis expires it.
+#[derive(Serialize, Deserialize)]
+struct StoredSession {
+    id: AdminSessionId,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+}
+
+impl StoredSession {
+    fn expires_at(&self) -> DateTime<Utc> {
+        self.last_used_at.unwrap_or(self.created_at) + Duration::hours(ADMIN_SESSION_DURATION_HOURS)
+    }
+}
+
+/// Keeps sessions in Redis, shared by all instances.
+pub struct RedisAdminSessions {
+    redis: Redis,
+    clock: Arc<dyn Clock>,
+}
+
+impl RedisAdminSessions {
+    pub fn new(redis: Redis, clock: Arc<dyn Clock>) -> Self {
+        Self { redis, clock }
+    }
+
+    /// Tokens aren't stored as is, so a leaked Redis snapshot doesn't expose working sessions.
+    fn key(token: &AdminSessionToken) -> String {
+        let digest = Sha256::digest(token.to_string());
+        let hash: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+        format!("amigo-oculto:admin-session:{hash}")
+    }
+
+    /// Stores a session until it expires. With `only_existing`, an expired session isn't
+    /// brought back; returns whether it was stored.
+    async fn store(&self, key: &str, session: &StoredSession, only_existing: bool) -> Result<bool> {
+        let value = serde_json::to_string(session)?;
+        let ttl = (session.expires_at() - self.clock.now())
+            .num_milliseconds()
+            .max(1);
+        let mut command = redis::cmd("SET");
+        command.arg(key).arg(value).arg("PX").arg(ttl);
+        if only_existing {
+            command.arg("XX");
+        }
+        let reply: Option<String> = command.query_async(&mut self.redis.connection()).await?;
+        Ok(reply.is_some())
+    }
+
+    /// Every stored session with its key, dropping keys of expired sessions from the index.
+    async fn sessions(&self) -> Result<Vec<(String, StoredSession)>> {
+        let mut redis = self.redis.connection();
+        let keys: Vec<String> = redis.smembers(SESSION_INDEX_KEY).await?;
+
+        let mut sessions = Vec::new();
+        for key in keys {
+            match redis.get::<_, Option<String>>(&key).await? {
+                Some(value) => {
+                    let session = serde_json::from_str(&value).context("parsing admin session")?;
+                    sessions.push((key, session));
+                }
+                None => {
+                    let _: () = redis.srem(SESSION_INDEX_KEY, &key).await?;
+                }
+            }
+        }
+        Ok(sessions)
+    }
+
+    async fn remove(&self, key: &str) -> Result<bool> {
+        let mut redis = self.redis.connection();
+        let deleted: u64 = redis.del(key).await?;
+        let _: () = redis.srem(SESSION_INDEX_KEY, key).await?;
+        Ok(deleted > 0)
+    }
+}
+
+impl AdminSessionStore for RedisAdminSessions {
+    fn create(&self) -> SessionFuture<'_, AdminSessionToken> {
+        Box::pin(async move {
+            let token = AdminSessionToken::generate();
+            let key = Self::key(&token);
+            let session = StoredSession {
+                id: AdminSessionId::new(),
+                created_at: self.clock.now(),
+                last_used_at: None,
+            };
+            self.store(&key, &session, false)
+                .await
+                .context("creating admin session")?;
+            let _: () = self
+                .redis
+                .connection()
+                .sadd(SESSION_INDEX_KEY, &key)
+                .await?;
+            Ok(token)
+        })
+    }
+
+    fn validate<'a>(&'a self, token: &'a AdminSessionToken) -> SessionFuture<'a, bool> {
+        Box::pin(async move {
+            let key = Self::key(token);
+            let value: Option<String> = self.redis.connection().get(&key).await?;
+            let Some(value) = value else {
+                return Ok(false);
+            };
+            let mut session: StoredSession =
+                serde_json::from_str(&value).context("parsing admin session")?;
+            session.last_used_at = Some(self.clock.now());
+            self.store(&key, &session, true)
+                .await
+                .context("validating admin session")
+        })
+    }
+
+    fn list<'a>(&'a self, current: &'a AdminSessionToken) -> SessionFuture<'a, Vec<AdminSession>> {
+        Box::pin(async move {
+            let current = Self::key(current);
+            let mut sessions: Vec<_> = self
+                .sessions()
+                .await
+                .context("fetching admin sessions")?
+                .into_iter()
+                .map(|(key, session)| AdminSession {
+                    id: session.id,
+                    created_at: session.created_at,
+                    last_used_at: session.last_used_at,
+                    expires_at: session.expires_at(),
+                    current: key == current,
+                })
+                .collect();
+            sessions.sort_by_key(|session| std::cmp::Reverse(session.created_at));
+            Ok(sessions)
+        })
+    }
+
+    fn delete_by_id(&self, id: AdminSessionId) -> SessionFuture<'_, bool> {
+        Box::pin(async move {
+            let sessions = self.sessions().await.context("revoking admin session")?;
+            match sessions.into_iter().find(|(_, session)| session.id == id) {
+                Some((key, _)) => self.remove(&key).await,
+                None => Ok(false),
+            }
+        })
+    }
+
+    fn delete<'a>(&'a self, token: &'a AdminSessionToken) -> SessionFuture<'a, ()> {
+        Box::pin(async move {
+            self.remove(&Self::key(token))
+                .await
+                .context("deleting admin session")?;
+            Ok(())
+        })
+    }
+
+    fn cleanup_expired(&self) -> SessionFuture<'_, u64> {
+        Box::pin(async move {
+            // Redis expires the sessions themselves; only the index needs cleaning up
+            let before: u64 = self.redis.connection().scard(SESSION_INDEX_KEY).await?;
+            let active = self.sessions().await?.len();
+            Ok(before.saturating_sub(u64::try_from(active).unwrap_or(0)))
+        })
+    }
+}
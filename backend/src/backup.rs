@@ -0,0 +1,370 @@
+//! Snapshots of the SQLite database uploaded to S3-compatible object storage.
+//!
+//! Where Litestream is set up it replicates every change, but a corrupted or wrongly deleted
+//! replica takes the history with it. These are independent, point-in-time copies: the
+//! database is copied with `VACUUM INTO` while the server keeps running, compressed with gzip
+//! and uploaded as a new object each time. Old backups are left to the bucket's lifecycle
+//! rules. The copy and its compressed version are kept in temporary files, and the upload is
+//! streamed from disk, so a large database isn't held in memory.
+
+use crate::clock::Clock;
+use crate::db::Database;
+use crate::sigv4;
+use anyhow::{Context, Result, bail};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use reqwest::header;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::io::ReaderStream;
+use ulid::Ulid;
+use url::Url;
+use utoipa::ToSchema;
+
+const CONTENT_TYPE: &str = "application/gzip";
+
+/// Largest database a compressed backup may expand to when restored.
+const MAX_RESTORE_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// Time allowed for an upload on top of the transfer itself, for connecting and for the
+/// storage service to answer.
+const UPLOAD_BASE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Slowest upload rate tolerated, in bytes per second. Uploads time out when they'd take
+/// longer than this rate allows, after [`UPLOAD_BASE_TIMEOUT`].
+const MIN_UPLOAD_RATE: u64 = 256 * 1024;
+
+/// Longest error response from the storage service kept in the error message.
+const MAX_ERROR_BODY_LENGTH: usize = 500;
+
+#[derive(Clone)]
+pub struct BackupConfig {
+    /// Endpoint of the storage service, e.g. `https://s3.eu-west-1.wasabisys.com`. Objects
+    /// are addressed path-style, with the bucket in the path.
+    pub endpoint: Url,
+    pub bucket: String,
+    pub region: String,
+    pub credentials: sigv4::Credentials,
+    /// Prepended to the object keys, e.g. `backups/`.
+    pub prefix: String,
+    /// Time between scheduled backups.
+    pub interval: Duration,
+}
+
+/// A backup that was uploaded.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BackupSummary {
+    pub key: String,
+    /// Size of the database copy, in bytes.
+    pub size: u64,
+    /// Size of the uploaded object, in bytes.
+    pub compressed_size: u64,
+}
+
+/// A backup that was restored.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RestoreSummary {
+    /// Size of the database copy, in bytes.
+    pub size: u64,
+    pub rows: u64,
+}
+
+#[derive(Clone)]
+pub struct Backups {
+    config: Arc<BackupConfig>,
+    db: Database,
+    /// Has no overall timeout: each upload gets one sized for its transfer.
+    client: reqwest::Client,
+    clock: Arc<dyn Clock>,
+}
+
+impl Backups {
+    pub fn new(config: BackupConfig, db: Database, clock: Arc<dyn Clock>) -> Result<Self> {
+        // Credentials are never sent without TLS, even after a redirect
+        let client = reqwest::Client::builder()
+            .user_agent("amigo-oculto")
+            .https_only(true)
+            .connect_timeout(UPLOAD_BASE_TIMEOUT)
+            .build()
+            .context("creating backup upload client")?;
+        Ok(Self {
+            config: Arc::new(config),
+            db,
+            client,
+            clock,
+        })
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.config.interval
+    }
+
+    /// Copies the database and uploads the compressed copy.
+    pub async fn run(&self) -> Result<BackupSummary> {
+        let now = self.clock.now();
+        let name = format!("amigo-oculto-backup-{}", Ulid::new());
+        let copy = std::env::temp_dir().join(format!("{name}.db"));
+        let compressed = std::env::temp_dir().join(format!("{name}.db.gz"));
+
+        let key = format!(
+            "{}amigo_oculto-{}.db.gz",
+            self.config.prefix,
+            now.format("%Y%m%dT%H%M%SZ")
+        );
+        let result = self.upload(&copy, &compressed, key, now).await;
+        for path in [&copy, &compressed] {
+            if let Err(e) = tokio::fs::remove_file(path).await
+                && e.kind() != std::io::ErrorKind::NotFound
+            {
+                tracing::warn!("failed to remove backup file {}: {}", path.display(), e);
+            }
+        }
+        result
+    }
+
+    async fn upload(
+        &self,
+        copy: &Path,
+        compressed: &Path,
+        key: String,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<BackupSummary> {
+        self.db
+            .snapshot_into(copy)
+            .await
+            .context("copying the database")?;
+        let (source, target) = (copy.to_path_buf(), compressed.to_path_buf());
+        let compressed_copy = tokio::task::spawn_blocking(move || compress(&source, &target))
+            .await?
+            .context("compressing the database copy")?;
+
+        let url = self.object_url(&key)?;
+        let headers = sigv4::sign(
+            &self.config.credentials,
+            &self.config.region,
+            "s3",
+            &sigv4::SignableRequest {
+                method: reqwest::Method::PUT.as_str(),
+                url: &url,
+                content_type: Some(CONTENT_TYPE),
+                body: sigv4::Payload::Sha256(compressed_copy.sha256),
+            },
+            now,
+        );
+
+        let file = tokio::fs::File::open(compressed)
+            .await
+            .context("opening the compressed database copy")?;
+        let mut request = self
+            .client
+            .put(url)
+            .timeout(upload_timeout(compressed_copy.compressed_size))
+            .header(header::CONTENT_TYPE, CONTENT_TYPE)
+            .header(header::CONTENT_LENGTH, compressed_copy.compressed_size)
+            .body(reqwest::Body::wrap_stream(ReaderStream::new(file)));
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("uploading backup {key}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let body: String = body.chars().take(MAX_ERROR_BODY_LENGTH).collect();
+            bail!("S3 responded with {status}: {}", body.trim());
+        }
+
+        Ok(BackupSummary {
+            key,
+            size: compressed_copy.size,
+            compressed_size: compressed_copy.compressed_size,
+        })
+    }
+
+    fn object_url(&self, key: &str) -> Result<Url> {
+        let mut url = self.config.endpoint.clone();
+        url.path_segments_mut()
+            .map_err(|()| anyhow::anyhow!("invalid backup endpoint"))?
+            .pop_if_empty()
+            .push(&self.config.bucket)
+            .extend(key.split('/'));
+        Ok(url)
+    }
+}
+
+/// How long uploading `size` bytes may take.
+fn upload_timeout(size: u64) -> Duration {
+    UPLOAD_BASE_TIMEOUT + Duration::from_secs(size / MIN_UPLOAD_RATE)
+}
+
+/// A database copy compressed by [`compress`].
+struct CompressedCopy {
+    /// Size of the copy, in bytes.
+    size: u64,
+    compressed_size: u64,
+    /// SHA-256 digest of the compressed copy, to sign the upload.
+    sha256: [u8; 32],
+}
+
+/// Compresses the file at `source` into `target`, hashing the output on the way.
+fn compress(source: &Path, target: &Path) -> Result<CompressedCopy> {
+    let mut input = std::fs::File::open(source)?;
+    let output = HashingWriter {
+        inner: std::io::BufWriter::new(std::fs::File::create(target)?),
+        hasher: Sha256::new(),
+        written: 0,
+    };
+    let mut encoder = GzEncoder::new(output, flate2::Compression::default());
+    let size = std::io::copy(&mut input, &mut encoder)?;
+    let mut output = encoder.finish()?;
+    output.inner.flush()?;
+    Ok(CompressedCopy {
+        size,
+        compressed_size: output.written,
+        sha256: output.hasher.finalize().into(),
+    })
+}
+
+/// Passes writes through, keeping a digest and count of the bytes written.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+    written: u64,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Replaces the data in the database with a backup: either a `.db.gz` object uploaded by
+/// [`Backups::run`], or an uncompressed copy of the database file.
+pub async fn restore(db: &Database, snapshot: Vec<u8>) -> Result<RestoreSummary> {
+    let snapshot = if snapshot.starts_with(&[0x1f, 0x8b]) {
+        tokio::task::spawn_blocking(move || decompress(&snapshot))
+            .await?
+            .context("decompressing the backup")?
+    } else {
+        snapshot
+    };
+    let size = u64::try_from(snapshot.len()).context("converting backup size to u64")?;
+    let rows = db.restore(&snapshot).await?;
+    Ok(RestoreSummary { size, rows })
+}
+
+/// Decompresses a gzip backup, refusing those larger than [`MAX_RESTORE_SIZE`] once
+/// decompressed.
+fn decompress(compressed: &[u8]) -> Result<Vec<u8>> {
+    let mut snapshot = Vec::new();
+    GzDecoder::new(compressed)
+        .take(MAX_RESTORE_SIZE + 1)
+        .read_to_end(&mut snapshot)?;
+    if snapshot.len() as u64 > MAX_RESTORE_SIZE {
+        bail!("backup is larger than {MAX_RESTORE_SIZE} bytes once decompressed");
+    }
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SystemClock;
+
+    #[tokio::test]
+    async fn test_object_url() {
+        let config = BackupConfig {
+            endpoint: "https://s3.eu-west-1.wasabisys.com".parse().unwrap(),
+            bucket: "amigo-oculto".to_string(),
+            region: "eu-west-1".to_string(),
+            credentials: sigv4::Credentials {
+                access_key_id: "key".to_string(),
+                secret_access_key: "secret".to_string(),
+                session_token: None,
+            },
+            prefix: "backups/".to_string(),
+            interval: Duration::from_secs(3600),
+        };
+        let db = Database::connect("sqlite::memory:", Arc::new(SystemClock))
+            .await
+            .unwrap();
+        let backups = Backups::new(config, db, Arc::new(SystemClock)).unwrap();
+
+        assert_eq!(
+            backups
+                .object_url("backups/amigo_oculto-20261016T120000Z.db.gz")
+                .unwrap()
+                .as_str(),
+            "https://s3.eu-west-1.wasabisys.com/amigo-oculto/backups/amigo_oculto-20261016T120000Z.db.gz"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_compressed_backup() {
+        // In-memory databases can't be copied with VACUUM INTO
+        let path = std::env::temp_dir().join(format!("amigo-oculto-{}.db", ulid::Ulid::new()));
+        let db = Database::connect(
+            &format!("sqlite://{}", path.display()),
+            Arc::new(SystemClock),
+        )
+        .await
+        .unwrap();
+        let snapshot = db.snapshot().await.unwrap();
+
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&snapshot).unwrap();
+        let summary = restore(&db, encoder.finish().unwrap()).await.unwrap();
+        assert_eq!(summary.size, snapshot.len() as u64);
+        assert_eq!(summary.rows, 0);
+
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+    }
+
+    #[test]
+    fn test_compress() {
+        let name = format!("amigo-oculto-{}", ulid::Ulid::new());
+        let source = std::env::temp_dir().join(format!("{name}.db"));
+        let target = std::env::temp_dir().join(format!("{name}.db.gz"));
+        let data = b"SQLite format 3\0".repeat(1000);
+        std::fs::write(&source, &data).unwrap();
+
+        let compressed_copy = compress(&source, &target).unwrap();
+        let compressed = std::fs::read(&target).unwrap();
+        assert_eq!(compressed_copy.size, data.len() as u64);
+        assert_eq!(compressed_copy.compressed_size, compressed.len() as u64);
+        assert!(compressed.len() < data.len());
+        assert_eq!(
+            compressed_copy.sha256,
+            <[u8; 32]>::from(Sha256::digest(&compressed))
+        );
+        assert_eq!(decompress(&compressed).unwrap(), data);
+
+        for path in [source, target] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn test_upload_timeout() {
+        assert_eq!(upload_timeout(0), UPLOAD_BASE_TIMEOUT);
+        assert_eq!(
+            upload_timeout(100 * 1024 * 1024),
+            UPLOAD_BASE_TIMEOUT + Duration::from_secs(400)
+        );
+    }
+}
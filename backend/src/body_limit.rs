@@ -0,0 +1,198 @@
+//! Request body limits, so a single client can't make the instance buffer or parse huge
+//! payloads.
+//!
+//! Each group of routes gets its own size limit (see `BodyLimits`), and larger bodies are
+//! refused with `413 Payload Too Large`: right away when their `Content-Length` says so,
+//! or once the limit is reached otherwise. JSON bodies are also refused when they nest
+//! arrays and objects deeper than `MAX_JSON_DEPTH`, before any handler parses them.
+
+use crate::routes::AppError;
+use axum::{
+    body::{Body, Bytes},
+    extract::{
+        FromRequest, Request, State,
+        rejection::{BytesRejection, FailedToBufferBody},
+    },
+    http::{HeaderMap, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// Deepest nesting of arrays and objects accepted in JSON bodies. Requests nest a few
+/// levels at most, so this is far more than they need.
+pub const MAX_JSON_DEPTH: usize = 32;
+
+/// Body size limits of each group of routes, in bytes (`MAX_BODY_BYTES`,
+/// `MAX_VERIFICATION_BODY_BYTES` and `MAX_IMPORT_BODY_BYTES`). Cover images and backup
+/// restores have their own, fixed limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyLimits {
+    /// Most routes, whose bodies are small forms.
+    pub default: usize,
+    /// Verification routes, which anyone can call and only take an email and a code.
+    pub verification: usize,
+    /// Site admin game imports, holding a whole game export.
+    pub import: usize,
+}
+
+impl Default for BodyLimits {
+    fn default() -> Self {
+        Self {
+            default: 256 * 1024,
+            verification: 8 * 1024,
+            import: 4 * 1024 * 1024,
+        }
+    }
+}
+
+/// Middleware refusing bodies larger than the limit, and JSON bodies nested too deeply.
+///
+/// Must run inside a `DefaultBodyLimit` with the same limit, which also applies to the
+/// handlers' extractors.
+pub async fn limit(State(max_bytes): State<usize>, request: Request, next: Next) -> Response {
+    let declared_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok()?.parse::<u64>().ok());
+    if declared_length.is_some_and(|length| length > max_bytes as u64) {
+        return AppError::PayloadTooLarge.into_response();
+    }
+    if !is_json(request.headers()) {
+        return next.run(request).await;
+    }
+
+    // JSON bodies are small, so they're read here to be checked before handlers parse them
+    let (parts, body) = request.into_parts();
+    let body = match Bytes::from_request(Request::from_parts(parts.clone(), body), &()).await {
+        Ok(body) => body,
+        Err(BytesRejection::FailedToBufferBody(FailedToBufferBody::LengthLimitError(_))) => {
+            return AppError::PayloadTooLarge.into_response();
+        }
+        Err(rejection) => return rejection.into_response(),
+    };
+    if json_depth(&body) > MAX_JSON_DEPTH {
+        return AppError::BadRequest("JSON aninhado demais".to_string()).into_response();
+    }
+    next.run(Request::from_parts(parts, Body::from(body))).await
+}
+
+/// Whether the body is JSON, as told by its `Content-Type`.
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|essence| essence.trim().to_ascii_lowercase())
+        .is_some_and(|essence| essence == "application/json" || essence.ends_with("+json"))
+}
+
+/// Deepest nesting of arrays and objects in `json`, without parsing it. Brackets inside
+/// strings don't count, and invalid JSON is left for the handler to refuse.
+fn json_depth(json: &[u8]) -> usize {
+    let mut depth = 0;
+    let mut deepest = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for &byte in json {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'[' | b'{' => {
+                depth += 1;
+                deepest = usize::max(deepest, depth);
+            }
+            b']' | b'}' => depth = usize::saturating_sub(depth, 1),
+            _ => {}
+        }
+    }
+    deepest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, extract::DefaultBodyLimit, http::StatusCode, middleware, routing::post};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_json_depth() {
+        assert_eq!(json_depth(b"\"text\""), 0);
+        assert_eq!(json_depth(b"{\"a\": [1, {\"b\": []}]}"), 4);
+        assert_eq!(json_depth(b"[[], [], []]"), 2);
+        assert_eq!(json_depth(b"{\"a\": \"[[[{{{\"}"), 1);
+        assert_eq!(json_depth(b"{\"a\": \"\\\"[[[\"}"), 1);
+        assert_eq!(json_depth(b"]]]{"), 1);
+    }
+
+    #[test]
+    fn test_is_json() {
+        let headers = |content_type: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::CONTENT_TYPE, content_type.parse().unwrap());
+            headers
+        };
+        assert!(is_json(&headers("application/json")));
+        assert!(is_json(&headers("Application/JSON; charset=utf-8")));
+        assert!(is_json(&headers("application/problem+json")));
+        assert!(!is_json(&headers("image/png")));
+        assert!(!is_json(&HeaderMap::new()));
+    }
+
+    #[tokio::test]
+    async fn test_limit() {
+        let app = Router::new()
+            .route("/", post(|body: String| async move { body }))
+            .layer(
+                tower::ServiceBuilder::new()
+                    .layer(DefaultBodyLimit::max(64))
+                    .layer(middleware::from_fn_with_state(64, limit)),
+            );
+        let status = |body: String, content_type: &str| {
+            let request = Request::post("/")
+                .header(header::CONTENT_TYPE, content_type)
+                .body(Body::from(body))
+                .unwrap();
+            let app = app.clone();
+            async move { app.oneshot(request).await.unwrap().status() }
+        };
+
+        assert_eq!(
+            status("{}".to_string(), "application/json").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status("x".repeat(65), "text/plain").await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            status(format!("[\"{}\"]", "x".repeat(64)), "application/json").await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            status("[".repeat(MAX_JSON_DEPTH + 1), "application/json").await,
+            StatusCode::BAD_REQUEST
+        );
+
+        // Without a Content-Length, the limit is reached while reading
+        let chunks = futures_util::stream::iter(
+            ["[\"", &"x".repeat(40), &"x".repeat(40), "\"]"]
+                .map(|chunk| Ok::<_, std::io::Error>(chunk.to_string())),
+        );
+        let request = Request::post("/")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from_stream(chunks))
+            .unwrap();
+        assert_eq!(
+            app.oneshot(request).await.unwrap().status(),
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! CAPTCHA checks on forms that send emails, so bots can't use up the email quota.
+//!
+//! hCaptcha and Cloudflare Turnstile share the same API: the widget on the page hands the
+//! browser a single-use token, which the server confirms with the provider's `siteverify`
+//! endpoint. Checks are disabled unless the site and secret keys are configured.
+
+use crate::email_transport::HttpsClient;
+use anyhow::{Context, Result};
+use axum::http::Method;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+use url::{Url, form_urlencoded};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptchaProvider {
+    HCaptcha,
+    Turnstile,
+}
+
+impl CaptchaProvider {
+    fn verify_url(self) -> Url {
+        let url = match self {
+            Self::HCaptcha => "https://api.hcaptcha.com/siteverify",
+            Self::Turnstile => "https://challenges.cloudflare.com/turnstile/v0/siteverify",
+        };
+        Url::parse(url).expect("static CAPTCHA URL is valid")
+    }
+}
+
+/// Error type for unknown `CAPTCHA_PROVIDER` values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidCaptchaProvider;
+
+impl fmt::Display for InvalidCaptchaProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected hcaptcha or turnstile")
+    }
+}
+
+impl std::error::Error for InvalidCaptchaProvider {}
+
+impl FromStr for CaptchaProvider {
+    type Err = InvalidCaptchaProvider;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hcaptcha" => Ok(Self::HCaptcha),
+            "turnstile" => Ok(Self::Turnstile),
+            _ => Err(InvalidCaptchaProvider),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CaptchaConfig {
+    pub provider: CaptchaProvider,
+    /// Public key the widget is rendered with.
+    pub site_key: String,
+    pub secret_key: String,
+}
+
+/// What the frontend needs to render the widget.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CaptchaWidget {
+    pub provider: CaptchaProvider,
+    pub site_key: String,
+}
+
+#[derive(Deserialize)]
+struct VerifyResponse {
+    success: bool,
+    #[serde(rename = "error-codes", default)]
+    error_codes: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct Captcha {
+    config: CaptchaConfig,
+    client: HttpsClient,
+}
+
+impl Captcha {
+    pub fn new(config: CaptchaConfig) -> Result<Self> {
+        Ok(Self {
+            config,
+            client: HttpsClient::new()?,
+        })
+    }
+
+    pub fn widget(&self) -> CaptchaWidget {
+        CaptchaWidget {
+            provider: self.config.provider,
+            site_key: self.config.site_key.clone(),
+        }
+    }
+
+    /// Confirms a token solved in the browser. Returns `false` if it's invalid, expired or
+    /// was already used.
+    pub async fn verify(&self, token: &str, remote_ip: Option<IpAddr>) -> Result<bool> {
+        let body = {
+            let mut form = form_urlencoded::Serializer::new(String::new());
+            form.append_pair("secret", &self.config.secret_key)
+                .append_pair("response", token);
+            if let Some(ip) = remote_ip {
+                form.append_pair("remoteip", &ip.to_string());
+            }
+            form.finish().into_bytes()
+        };
+
+        let response = self
+            .client
+            .request(
+                Method::POST,
+                &self.config.provider.verify_url(),
+                &[],
+                Some(("application/x-www-form-urlencoded", body)),
+            )
+            .await?
+            .ensure_success("CAPTCHA provider")?;
+        let result: VerifyResponse =
+            serde_json::from_str(&response.body).context("parsing CAPTCHA response")?;
+
+        if !result.success {
+            tracing::info!("CAPTCHA rejected: {}", result.error_codes.join(", "));
+        }
+        Ok(result.success)
+    }
+}
@@ -0,0 +1,197 @@
+//! Printable gift card shown to participants after they see their match.
+//!
+//! The card is a one-page PDF made with `printpdf`. It embeds the DejaVu Sans font
+//! (`assets/fonts`), so names in the scripts it covers, such as Cyrillic and Greek, print as
+//! written instead of only those in Latin-1. Only the regular weight is embedded, since the
+//! whole font goes into each card: bold text is drawn outlined instead.
+
+use crate::i18n::Locale;
+use anyhow::{Context, Result};
+use printpdf::path::PaintMode;
+use printpdf::{
+    Color, CustomPdfConformance, Mm, PdfConformance, PdfDocument, Pt, Rect, Rgb, TextRenderingMode,
+};
+
+const FONT: &[u8] = include_bytes!("../assets/fonts/DejaVuSans.ttf");
+
+/// Card size in points: A6, landscape.
+const WIDTH: f32 = 420.0;
+const HEIGHT: f32 = 298.0;
+const MARGIN: f32 = 28.0;
+
+/// Longest text that fits on one line of the card, in characters.
+const MAX_TITLE_LENGTH: usize = 28;
+const MAX_LINE_LENGTH: usize = 46;
+
+/// Renders the card as a PDF document, in the game's language.
+pub fn render_pdf(
+    game_name: &str,
+    event_date: &str,
+    matched_name: &str,
+    locale: Locale,
+) -> Result<Vec<u8>> {
+    let (doc, page, layer) = PdfDocument::new(
+        locale.tr("Amigo Oculto"),
+        points(WIDTH),
+        points(HEIGHT),
+        "Card",
+    );
+    // Without the ICC profile and XMP metadata of the default PDF/X conformance, which
+    // would only make the card bigger
+    let doc = doc.with_conformance(PdfConformance::Custom(CustomPdfConformance {
+        requires_icc_profile: false,
+        requires_xmp_metadata: false,
+        ..Default::default()
+    }));
+    let font = doc
+        .add_external_font(FONT)
+        .context("loading the card font")?;
+    let layer = doc.get_page(page).get_layer(layer);
+
+    let color = Color::Rgb(Rgb::new(0.29, 0.34, 0.35, None));
+    layer.set_fill_color(color.clone());
+    layer.set_outline_color(color);
+
+    // Border
+    layer.set_outline_thickness(2.0);
+    layer.add_rect(
+        Rect::new(
+            points(MARGIN / 2.0),
+            points(MARGIN / 2.0),
+            points(WIDTH - MARGIN / 2.0),
+            points(HEIGHT - MARGIN / 2.0),
+        )
+        .with_mode(PaintMode::Stroke),
+    );
+
+    let lines = [
+        (true, 22.0, 240.0, locale.tr("Amigo Oculto").to_string()),
+        (false, 13.0, 214.0, fit(game_name, MAX_LINE_LENGTH)),
+        (false, 12.0, 160.0, locale.tr("Para:").to_string()),
+        (true, 22.0, 132.0, fit(matched_name, MAX_TITLE_LENGTH)),
+        (
+            false,
+            12.0,
+            96.0,
+            locale.tr("De: seu amigo oculto").to_string(),
+        ),
+        (false, 11.0, 44.0, fit(event_date, MAX_LINE_LENGTH)),
+    ];
+    layer.set_outline_thickness(0.6);
+    for (bold, size, y, text) in lines {
+        layer.set_text_rendering_mode(if bold {
+            TextRenderingMode::FillStroke
+        } else {
+            TextRenderingMode::Fill
+        });
+        layer.use_text(text, size, points(MARGIN), points(y), &font);
+    }
+
+    doc.save_to_bytes().context("writing the card")
+}
+
+fn points(points: f32) -> Mm {
+    Pt(points).into()
+}
+
+/// Cuts text that is too long for a line, ending it with an ellipsis.
+fn fit(text: &str, max_length: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max_length {
+        return text;
+    }
+    let truncated: String = text.chars().take(max_length - 1).collect();
+    format!("{}…", truncated.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::lopdf;
+    use std::collections::HashMap;
+
+    /// The lines of text on the card, read back through the font's `ToUnicode` map.
+    fn text_lines(pdf: &[u8]) -> Vec<String> {
+        let doc = lopdf::Document::load_mem(pdf).unwrap();
+        let streams: Vec<String> = doc
+            .objects
+            .values()
+            .filter_map(|object| object.as_stream().ok())
+            .map(|stream| {
+                let content = stream
+                    .decompressed_content()
+                    .unwrap_or_else(|_| stream.content.clone());
+                String::from_utf8_lossy(&content).into_owned()
+            })
+            .collect();
+        let lines = || streams.iter().flat_map(|stream| stream.lines());
+
+        // `<glyph> <code point>` entries of the map
+        let chars: HashMap<String, char> = lines()
+            .filter_map(|line| {
+                let (glyph, code) = line
+                    .strip_prefix('<')?
+                    .strip_suffix('>')?
+                    .split_once("> <")?;
+                let code = char::from_u32(u32::from_str_radix(code, 16).ok()?)?;
+                Some((glyph.to_lowercase(), code))
+            })
+            .collect();
+        lines()
+            .filter_map(|line| line.strip_suffix("> Tj")?.rsplit_once('<'))
+            .map(|(_, glyphs)| {
+                glyphs
+                    .as_bytes()
+                    .chunks(4)
+                    .map(|glyph| chars[&String::from_utf8_lossy(glyph).to_lowercase()])
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_render_pdf() {
+        let pdf = render_pdf(
+            "Natal (Família)",
+            "24 de dezembro de 2025",
+            "João 🎅",
+            Locale::PtBr,
+        )
+        .unwrap();
+        assert!(pdf.starts_with(b"%PDF-"));
+        assert_eq!(
+            text_lines(&pdf),
+            [
+                "Amigo Oculto",
+                "Natal (Família)",
+                "Para:",
+                // The font has no emoji, so they're left out
+                "João ",
+                "De: seu amigo oculto",
+                "24 de dezembro de 2025",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_pdf_outside_latin_1() {
+        let pdf = render_pdf("Новый год", "31 декабря 2025", "Дмитрий Ωμέγα", Locale::En).unwrap();
+        let lines = text_lines(&pdf);
+        assert_eq!(lines[1], "Новый год");
+        assert_eq!(lines[3], "Дмитрий Ωμέγα");
+    }
+
+    #[test]
+    fn test_render_pdf_in_english() {
+        let pdf = render_pdf("Christmas", "December 24, 2025", "John", Locale::En).unwrap();
+        let lines = text_lines(&pdf);
+        assert_eq!(lines[0], "Secret Santa");
+        assert_eq!(lines[2], "To:");
+    }
+
+    #[test]
+    fn test_fit() {
+        assert_eq!(fit("  Maria   Clara ", 20), "Maria Clara");
+        assert_eq!(fit("Maria Clara de Souza", 10), "Maria Cla…");
+    }
+}
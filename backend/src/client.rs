@@ -0,0 +1,205 @@
+//! Information about the client making a request, for audit records.
+//!
+//! Only coarse data is kept: the user agent and the network the client connects from, never
+//! the full IP address. To count devices, even that is only kept hashed.
+
+use crate::routes::AppState;
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{HeaderMap, header, request::Parts},
+};
+use sha2::{Digest, Sha256};
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+/// Longest user agent kept, in characters.
+const MAX_USER_AGENT_LENGTH: usize = 256;
+
+/// The client's user agent and IP address, when known.
+///
+/// Extraction never fails. The IP address is found like the rate limiters find it (see
+/// [`client_ip`]), so clients can't make it up unless `TRUST_PROXY` is wrongly set.
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip: Option<IpAddr>,
+}
+
+impl FromRequestParts<Arc<AppState>> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_parts(parts, state.trust_proxy))
+    }
+}
+
+impl ClientInfo {
+    fn from_parts(parts: &Parts, trust_proxy: bool) -> Self {
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(MAX_USER_AGENT_LENGTH).collect());
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        Self {
+            user_agent,
+            ip: client_ip(&parts.headers, peer, trust_proxy),
+        }
+    }
+
+    /// The network the client connects from: a /24 for IPv4, a /48 for IPv6.
+    pub fn ip_prefix(&self) -> Option<String> {
+        self.ip.map(ip_prefix)
+    }
+
+    /// Identifies the client's device (user agent and IP prefix) without revealing either.
+    ///
+    /// The hash is salted, e.g. with a participant ID, so the same device can't be recognized
+    /// across salts.
+    pub fn device_hash(&self, salt: &str) -> String {
+        let mut hasher = Sha256::new();
+        for part in [
+            salt,
+            self.user_agent.as_deref().unwrap_or_default(),
+            self.ip_prefix().as_deref().unwrap_or_default(),
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+}
+
+/// The IP address of the client, from the connection's peer address.
+///
+/// Behind a trusted proxy, that's the last `X-Forwarded-For` entry, the one added by the
+/// proxy itself; earlier entries come from the client and could be made up.
+pub fn client_ip(headers: &HeaderMap, peer: Option<IpAddr>, trust_proxy: bool) -> Option<IpAddr> {
+    if !trust_proxy {
+        return peer;
+    }
+    headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .next_back()
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next())
+        .and_then(|ip| ip.trim().parse().ok())
+        .or(peer)
+}
+
+fn ip_prefix(ip: IpAddr) -> String {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            format!("{a}.{b}.{c}.0/24")
+        }
+        IpAddr::V6(ip) => {
+            let [a, b, c, ..] = ip.segments();
+            format!("{a:x}:{b:x}:{c:x}::/48")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_prefix() {
+        assert_eq!(ip_prefix("203.0.113.42".parse().unwrap()), "203.0.113.0/24");
+        assert_eq!(
+            ip_prefix("2001:db8:85a3:8d3:1319:8a2e:370:7348".parse().unwrap()),
+            "2001:db8:85a3::/48"
+        );
+        assert_eq!(
+            ip_prefix("::ffff:198.51.100.7".parse().unwrap()),
+            "198.51.100.0/24"
+        );
+    }
+
+    #[test]
+    fn test_device_hash() {
+        let client = ClientInfo {
+            user_agent: Some("Firefox".to_string()),
+            ip: Some("203.0.113.42".parse().unwrap()),
+        };
+        let same_network = ClientInfo {
+            ip: Some("203.0.113.7".parse().unwrap()),
+            ..client.clone()
+        };
+
+        let hash = client.device_hash("alice");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(same_network.device_hash("alice"), hash);
+        assert_ne!(client.device_hash("bob"), hash);
+        assert_ne!(ClientInfo::default().device_hash("alice"), hash);
+    }
+
+    fn parts(forwarded_for: &str) -> Parts {
+        let peer: SocketAddr = "192.0.2.10:41000".parse().unwrap();
+        let (mut parts, ()) = axum::http::Request::builder()
+            .header("x-forwarded-for", forwarded_for)
+            .body(())
+            .unwrap()
+            .into_parts();
+        parts.extensions.insert(ConnectInfo(peer));
+        parts
+    }
+
+    #[test]
+    fn test_spoofed_forwarded_for_keeps_the_lockout_key() {
+        // Login lockouts are keyed on the IP prefix, which a client changing the header on
+        // each attempt mustn't be able to change
+        let spoofed = ["203.0.113.1", "10.0.0.1", "2001:db8::1, 203.0.113.9"];
+        let prefixes = |trust_proxy: bool, proxy_entry: &str| {
+            spoofed
+                .iter()
+                .map(|spoofed| {
+                    let forwarded_for = format!("{spoofed}{proxy_entry}");
+                    ClientInfo::from_parts(&parts(&forwarded_for), trust_proxy).ip_prefix()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Without a proxy, the header is ignored
+        let direct = Some("192.0.2.0/24".to_string());
+        assert_eq!(prefixes(false, ""), vec![direct.clone(); 3]);
+        assert_eq!(prefixes(false, ", 198.51.100.7"), vec![direct; 3]);
+
+        // Behind one, only the entry it added counts
+        let proxied = Some("198.51.100.0/24".to_string());
+        assert_eq!(prefixes(true, ", 198.51.100.7"), vec![proxied; 3]);
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let peer = Some("192.0.2.10".parse().unwrap());
+        let mut headers = HeaderMap::new();
+        assert_eq!(client_ip(&headers, peer, true), peer);
+
+        headers.insert(
+            "x-forwarded-for",
+            "203.0.113.1, 198.51.100.7".parse().unwrap(),
+        );
+        assert_eq!(client_ip(&headers, peer, false), peer);
+        assert_eq!(
+            client_ip(&headers, peer, true),
+            Some("198.51.100.7".parse().unwrap())
+        );
+    }
+}
@@ -0,0 +1,1097 @@
+//! Server configuration, read from environment variables and the optional `config.toml`
+//! (see `config_file`) at startup. Environment variables take precedence over the file.
+//!
+//! Every setting is checked before the server starts, and all problems are reported
+//! together, so a misconfigured deployment can be fixed in one go.
+//!
+//! Any setting can instead be read from a file named by the same variable with a `_FILE`
+//! suffix (e.g. `SMTP_PASSWORD_FILE=/run/secrets/smtp_password`), so secrets mounted by
+//! Docker or Fly.io don't have to be exposed in the environment.
+
+use crate::backup::BackupConfig;
+use crate::body_limit::BodyLimits;
+use crate::captcha::CaptchaConfig;
+use crate::config_file::ConfigFile;
+use crate::email::EmailConfig;
+use crate::email_dispatch::DispatchConfig;
+use crate::email_transport::{EmailProvider, MailgunConfig, SesConfig, SmtpConfig, SmtpTls};
+use crate::error_reporting::ErrorReportingConfig;
+use crate::redis::RedisConfig;
+use crate::sigv4;
+use crate::static_files::StaticFiles;
+use crate::storage::{S3Config, StorageConfig};
+use crate::token::EmailDomain;
+use lettre::transport::smtp::authentication::Credentials;
+use sentry::types::{Dsn, Scheme};
+use std::cell::RefCell;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use url::Url;
+
+/// Exit code for configuration errors (`EX_CONFIG` from sysexits.h).
+pub const EXIT_CONFIG: i32 = 78;
+
+pub struct Config {
+    pub database_url: String,
+    pub port: u16,
+    pub base_url: Url,
+    /// The frontend build served, from `STATIC_DIR` or embedded in the binary.
+    pub static_files: StaticFiles,
+    /// Where uploaded media, such as cover images, is stored: in `MEDIA_DIR`, or in an
+    /// S3-compatible bucket if `MEDIA_S3_BUCKET` is set.
+    pub media: StorageConfig,
+    /// Only needed on first run, before a site admin password is stored in the database.
+    pub site_admin_password: Option<String>,
+    /// Whether admin tokens in URLs are accepted (and included in emails).
+    ///
+    /// Enabled unless `LEGACY_ADMIN_TOKENS` is set to `false` or `0`.
+    pub legacy_admin_tokens: bool,
+    pub email: EmailConfig,
+    pub email_dispatch: DispatchConfig,
+    /// Secret the email provider sends to the bounce and complaint webhook, as the HTTP Basic
+    /// password. The webhook is disabled when unset.
+    pub email_webhook_secret: Option<String>,
+    /// Token Prometheus sends as `Authorization: Bearer <token>` to read `GET /metrics`.
+    /// Metrics aren't recorded when unset.
+    pub metrics_token: Option<String>,
+    /// Checked before sending verification codes. Disabled unless `CAPTCHA_SITE_KEY` or
+    /// `CAPTCHA_SECRET_KEY` is set.
+    pub captcha: Option<CaptchaConfig>,
+    /// Disposable email domains refused for organizers, on top of the bundled list
+    /// (`BLOCKED_EMAIL_DOMAINS`, comma-separated).
+    pub blocked_email_domains: Vec<EmailDomain>,
+    /// Whether participants' email domains are checked for MX records when they're added
+    /// (`CHECK_EMAIL_DOMAINS`). Off by default, since it needs DNS access.
+    pub check_email_domains: bool,
+    /// Whether the server runs behind a reverse proxy whose `X-Forwarded-For` header gives
+    /// the client IP for rate limits, login lockouts and audit records (`TRUST_PROXY`). Off
+    /// by default, since clients could otherwise make up their IP.
+    pub trust_proxy: bool,
+    /// Redis server sharing rate limits and site admin sessions between instances
+    /// (`REDIS_URL`). Each instance keeps its own when unset.
+    pub redis: Option<RedisConfig>,
+    /// Where database backups are uploaded. Disabled unless `BACKUP_S3_BUCKET` is set.
+    pub backup: Option<BackupConfig>,
+    /// Where unexpected errors are reported. Disabled unless `SENTRY_DSN` is set.
+    pub error_reporting: Option<ErrorReportingConfig>,
+    /// Largest request bodies accepted by each group of routes.
+    pub body_limits: BodyLimits,
+}
+
+/// A single missing or invalid setting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    pub variable: &'static str,
+    pub message: String,
+}
+
+/// All the problems found while reading the configuration.
+#[derive(Debug)]
+pub struct ConfigError {
+    pub problems: Vec<ConfigProblem>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid configuration ({} problems):",
+            self.problems.len()
+        )?;
+        for problem in &self.problems {
+            write!(f, "\n  {}: {}", problem.variable, problem.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Reads the configuration from the environment and the file named by `CONFIG_FILE`
+    /// (`config.toml` by default, if it exists).
+    pub fn load() -> Result<Self, ConfigError> {
+        let path = std::env::var_os("CONFIG_FILE").map(PathBuf::from);
+        let file = ConfigFile::read(path.as_deref()).map_err(|message| ConfigError {
+            problems: vec![ConfigProblem {
+                variable: "CONFIG_FILE",
+                message,
+            }],
+        })?;
+        Self::from_sources(|name| std::env::var(name).ok(), &file)
+    }
+
+    /// Reads each setting from `env`, or else from `file`. Settings in the file that weren't
+    /// read are reported too, as they're likely typos.
+    fn from_sources(
+        env: impl Fn(&str) -> Option<String>,
+        file: &ConfigFile,
+    ) -> Result<Self, ConfigError> {
+        let read = RefCell::new(Vec::new());
+        let result = Self::from_lookup(|name| {
+            read.borrow_mut().push(name.to_string());
+            env(name)
+                .filter(|value| !value.trim().is_empty())
+                .or_else(|| file.get(name))
+        });
+
+        let unused = file.unused(&read.into_inner());
+        if unused.is_empty() {
+            return result;
+        }
+        let mut problems = result.err().map(|e| e.problems).unwrap_or_default();
+        problems.extend(unused.into_iter().map(|message| ConfigProblem {
+            variable: "CONFIG_FILE",
+            message,
+        }));
+        Err(ConfigError { problems })
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let mut env = EnvReader {
+            lookup,
+            problems: Vec::new(),
+        };
+
+        let database_url = env
+            .optional("DATABASE_URL")
+            .unwrap_or_else(|| "sqlite:///app/data/amigo_oculto.db".to_string());
+        let port = env.parse_or("PORT", 3000);
+        let base_url = env.required::<Url>("BASE_URL");
+        let static_files = StaticFiles::new(env.parse_optional("STATIC_DIR"));
+        let media = media_config(&mut env);
+        let site_admin_password = env.optional("SITE_ADMIN_PASSWORD");
+        let legacy_admin_tokens = env
+            .optional("LEGACY_ADMIN_TOKENS")
+            .is_none_or(|value| !matches!(value.as_str(), "false" | "0"));
+
+        let provider = email_mode(&mut env);
+        // The sender address for every provider, despite the name
+        let from_address = env.required("SMTP_FROM");
+        let site_admin_address = env.parse_optional("SITE_ADMIN_EMAIL");
+        let email_webhook_secret = env.optional("EMAIL_WEBHOOK_SECRET");
+        let metrics_token = env.optional("METRICS_TOKEN");
+        let captcha = captcha_config(&mut env);
+        let blocked_email_domains = env.parse_list("BLOCKED_EMAIL_DOMAINS");
+        let check_email_domains = env.parse_or("CHECK_EMAIL_DOMAINS", false);
+        let trust_proxy = env.parse_or("TRUST_PROXY", false);
+        let redis = env.parse_optional("REDIS_URL");
+        let backup = backup_config(&mut env);
+        let error_reporting = error_reporting_config(&mut env);
+        let dispatch_defaults = DispatchConfig::default();
+        let email_dispatch = DispatchConfig {
+            concurrency: env.parse_or("EMAIL_CONCURRENCY", dispatch_defaults.concurrency),
+            per_second: env.parse_or("EMAIL_RATE_LIMIT", dispatch_defaults.per_second),
+        };
+        let body_limit_defaults = BodyLimits::default();
+        let body_limits = BodyLimits {
+            default: env.parse_or("MAX_BODY_BYTES", body_limit_defaults.default),
+            verification: env.parse_or(
+                "MAX_VERIFICATION_BODY_BYTES",
+                body_limit_defaults.verification,
+            ),
+            import: env.parse_or("MAX_IMPORT_BODY_BYTES", body_limit_defaults.import),
+        };
+
+        let email = (|| {
+            Some(EmailConfig {
+                provider: provider?,
+                from_address: from_address?,
+                base_url: base_url.clone()?,
+                legacy_admin_tokens,
+                site_admin_address,
+            })
+        })();
+
+        // Any missing value has already been recorded as a problem
+        match (base_url, email, media) {
+            (Some(base_url), Some(email), Some(media)) if env.problems.is_empty() => Ok(Self {
+                database_url,
+                port,
+                base_url,
+                static_files,
+                media,
+                site_admin_password,
+                legacy_admin_tokens,
+                email,
+                email_dispatch,
+                email_webhook_secret,
+                metrics_token,
+                captcha,
+                blocked_email_domains,
+                check_email_domains,
+                trust_proxy,
+                redis,
+                backup,
+                error_reporting,
+                body_limits,
+            }),
+            _ => Err(ConfigError {
+                problems: env.problems,
+            }),
+        }
+    }
+}
+
+/// Reads `EMAIL_MODE`: `smtp` (the default) delivers emails through `EMAIL_PROVIDER`, while
+/// `log` and `file` only log them or write them to a maildir, for development and staging.
+fn email_mode<F: Fn(&str) -> Option<String>>(env: &mut EnvReader<F>) -> Option<EmailProvider> {
+    let mode = env.optional("EMAIL_MODE");
+    match mode.as_deref().map(str::trim) {
+        None | Some("smtp") => email_provider(env),
+        Some("log") => Some(EmailProvider::Log),
+        Some("file") => Some(EmailProvider::Maildir(
+            env.parse_or("EMAIL_MAILDIR", PathBuf::from("/app/data/maildir")),
+        )),
+        Some(other) => {
+            env.problem(
+                "EMAIL_MODE",
+                format!("unknown mode {other:?} (expected log, file or smtp)"),
+            );
+            None
+        }
+    }
+}
+
+/// Reads the settings of the provider chosen by `EMAIL_PROVIDER` (SMTP by default).
+fn email_provider<F: Fn(&str) -> Option<String>>(env: &mut EnvReader<F>) -> Option<EmailProvider> {
+    let provider = env.optional("EMAIL_PROVIDER");
+    match provider.as_deref().map(str::trim) {
+        None | Some("smtp") => {
+            let primary = smtp_config(env, &PRIMARY_SMTP);
+            let secondary = env
+                .optional(SECONDARY_SMTP.host)
+                .map(|_| smtp_config(env, &SECONDARY_SMTP));
+            Some(EmailProvider::Smtp {
+                primary: primary?,
+                secondary: match secondary {
+                    Some(secondary) => Some(secondary?),
+                    None => None,
+                },
+            })
+        }
+        Some("sendgrid") => Some(EmailProvider::SendGrid {
+            api_key: env.required("SENDGRID_API_KEY")?,
+        }),
+        Some("mailgun") => {
+            let api_key = env.required("MAILGUN_API_KEY");
+            let domain = env.required("MAILGUN_DOMAIN");
+            let api_base = env.parse_or(
+                "MAILGUN_API_BASE",
+                Url::parse("https://api.mailgun.net").expect("Mailgun API URL is valid"),
+            );
+            Some(EmailProvider::Mailgun(MailgunConfig {
+                api_key: api_key?,
+                domain: domain?,
+                api_base,
+            }))
+        }
+        Some("ses") => {
+            let region = env.required("AWS_REGION");
+            let access_key_id = env.required("AWS_ACCESS_KEY_ID");
+            let secret_access_key = env.required("AWS_SECRET_ACCESS_KEY");
+            let session_token = env.optional("AWS_SESSION_TOKEN");
+            let configuration_set = env.optional("SES_CONFIGURATION_SET");
+            Some(EmailProvider::Ses(SesConfig {
+                region: region?,
+                credentials: sigv4::Credentials {
+                    access_key_id: access_key_id?,
+                    secret_access_key: secret_access_key?,
+                    session_token,
+                },
+                configuration_set,
+            }))
+        }
+        Some(other) => {
+            env.problem(
+                "EMAIL_PROVIDER",
+                format!("unknown provider {other:?} (expected smtp, sendgrid, mailgun or ses)"),
+            );
+            None
+        }
+    }
+}
+
+/// Reads the CAPTCHA provider and keys, which all become required once either key is set.
+fn captcha_config<F: Fn(&str) -> Option<String>>(env: &mut EnvReader<F>) -> Option<CaptchaConfig> {
+    if env.optional("CAPTCHA_SITE_KEY").is_none() && env.optional("CAPTCHA_SECRET_KEY").is_none() {
+        return None;
+    }
+    let provider = env.required("CAPTCHA_PROVIDER");
+    let site_key = env.required("CAPTCHA_SITE_KEY");
+    let secret_key = env.required("CAPTCHA_SECRET_KEY");
+    Some(CaptchaConfig {
+        provider: provider?,
+        site_key: site_key?,
+        secret_key: secret_key?,
+    })
+}
+
+/// Reads the object storage settings for database backups, if `BACKUP_S3_BUCKET` is set.
+fn backup_config<F: Fn(&str) -> Option<String>>(env: &mut EnvReader<F>) -> Option<BackupConfig> {
+    let bucket = env.optional("BACKUP_S3_BUCKET")?;
+    let region = env.required::<String>("BACKUP_S3_REGION");
+    let endpoint = match (env.parse_optional::<Url>("BACKUP_S3_ENDPOINT"), &region) {
+        (Some(endpoint), _) => Some(endpoint),
+        (None, Some(region)) => Url::parse(&format!("https://s3.{region}.amazonaws.com")).ok(),
+        (None, None) => None,
+    };
+    if let Some(endpoint) = &endpoint
+        && endpoint.scheme() != "https"
+    {
+        env.problem("BACKUP_S3_ENDPOINT", "must be an https:// URL".to_string());
+    }
+    let access_key_id = env.required("BACKUP_S3_ACCESS_KEY_ID");
+    let secret_access_key = env.required("BACKUP_S3_SECRET_ACCESS_KEY");
+    let prefix = env
+        .optional("BACKUP_S3_PREFIX")
+        .unwrap_or_else(|| "backups/".to_string());
+    let interval_hours: u64 = env.parse_or("BACKUP_INTERVAL_HOURS", 24);
+    if interval_hours == 0 {
+        env.problem("BACKUP_INTERVAL_HOURS", "must be at least 1".to_string());
+    }
+    Some(BackupConfig {
+        endpoint: endpoint?,
+        bucket,
+        region: region?,
+        credentials: sigv4::Credentials {
+            access_key_id: access_key_id?,
+            secret_access_key: secret_access_key?,
+            session_token: None,
+        },
+        prefix,
+        interval: Duration::from_secs(interval_hours.saturating_mul(3600)),
+    })
+}
+
+/// Reads where uploaded media is stored: the object storage settings if `MEDIA_S3_BUCKET` is
+/// set, or else `MEDIA_DIR`.
+fn media_config<F: Fn(&str) -> Option<String>>(env: &mut EnvReader<F>) -> Option<StorageConfig> {
+    let Some(bucket) = env.optional("MEDIA_S3_BUCKET") else {
+        let root = env.parse_or("MEDIA_DIR", PathBuf::from("/app/data/media"));
+        return Some(StorageConfig::Local(root));
+    };
+    let region = env.required::<String>("MEDIA_S3_REGION");
+    let endpoint = match (env.parse_optional::<Url>("MEDIA_S3_ENDPOINT"), &region) {
+        (Some(endpoint), _) => Some(endpoint),
+        (None, Some(region)) => Url::parse(&format!("https://s3.{region}.amazonaws.com")).ok(),
+        (None, None) => None,
+    };
+    if let Some(endpoint) = &endpoint
+        && endpoint.scheme() != "https"
+    {
+        env.problem("MEDIA_S3_ENDPOINT", "must be an https:// URL".to_string());
+    }
+    let access_key_id = env.required("MEDIA_S3_ACCESS_KEY_ID");
+    let secret_access_key = env.required("MEDIA_S3_SECRET_ACCESS_KEY");
+    let prefix = env
+        .optional("MEDIA_S3_PREFIX")
+        .unwrap_or_else(|| "media/".to_string());
+    Some(StorageConfig::S3(Box::new(S3Config {
+        endpoint: endpoint?,
+        bucket,
+        region: region?,
+        credentials: sigv4::Credentials {
+            access_key_id: access_key_id?,
+            secret_access_key: secret_access_key?,
+            session_token: None,
+        },
+        prefix,
+    })))
+}
+
+/// Reads the error reporting settings, if `SENTRY_DSN` is set.
+fn error_reporting_config<F: Fn(&str) -> Option<String>>(
+    env: &mut EnvReader<F>,
+) -> Option<ErrorReportingConfig> {
+    env.optional("SENTRY_DSN")?;
+    let dsn: Option<Dsn> = env.required("SENTRY_DSN");
+    // The DSN's key is sent with every event
+    if dsn
+        .as_ref()
+        .is_some_and(|dsn| dsn.scheme() != Scheme::Https)
+    {
+        env.problem("SENTRY_DSN", "expected an https:// DSN".to_string());
+        return None;
+    }
+    let environment = env
+        .optional("SENTRY_ENVIRONMENT")
+        .unwrap_or_else(|| "production".to_string());
+    Some(ErrorReportingConfig {
+        dsn: dsn?,
+        environment,
+    })
+}
+
+/// Names of the variables configuring an SMTP server.
+struct SmtpVariables {
+    host: &'static str,
+    port: &'static str,
+    tls: &'static str,
+    username: &'static str,
+    password: &'static str,
+}
+
+const PRIMARY_SMTP: SmtpVariables = SmtpVariables {
+    host: "SMTP_HOST",
+    port: "SMTP_PORT",
+    tls: "SMTP_TLS",
+    username: "SMTP_USERNAME",
+    password: "SMTP_PASSWORD",
+};
+
+/// Server to fail over to when the primary one keeps failing, if `SMTP_SECONDARY_HOST` is set.
+const SECONDARY_SMTP: SmtpVariables = SmtpVariables {
+    host: "SMTP_SECONDARY_HOST",
+    port: "SMTP_SECONDARY_PORT",
+    tls: "SMTP_SECONDARY_TLS",
+    username: "SMTP_SECONDARY_USERNAME",
+    password: "SMTP_SECONDARY_PASSWORD",
+};
+
+fn smtp_config<F: Fn(&str) -> Option<String>>(
+    env: &mut EnvReader<F>,
+    vars: &SmtpVariables,
+) -> Option<SmtpConfig> {
+    let host = env.required(vars.host);
+    let port: Option<u16> = env.required(vars.port);
+    let tls = env.parse_optional(vars.tls);
+    let credentials = smtp_credentials(env, vars, tls);
+    let port = port?;
+    let tls = tls.unwrap_or(SmtpTls::default_for_port(port));
+    // Mismatches on the well-known ports hang until the timeout instead of failing
+    if port == 465 && tls != SmtpTls::Implicit {
+        env.problem(
+            vars.tls,
+            format!("port 465 expects implicit TLS (tls), not {tls}"),
+        );
+    } else if matches!(port, 25 | 587) && tls == SmtpTls::Implicit {
+        env.problem(
+            vars.tls,
+            format!("port {port} expects STARTTLS, not implicit TLS"),
+        );
+    }
+    Some(SmtpConfig {
+        host: host?,
+        port,
+        tls,
+        credentials: credentials?,
+    })
+}
+
+/// Reads the username and password, which are required unless TLS is `none`. Without TLS
+/// they'd be sent in plaintext, so they're refused instead.
+fn smtp_credentials<F: Fn(&str) -> Option<String>>(
+    env: &mut EnvReader<F>,
+    vars: &SmtpVariables,
+    tls: Option<SmtpTls>,
+) -> Option<Option<Credentials>> {
+    if tls == Some(SmtpTls::None) {
+        for variable in [vars.username, vars.password] {
+            if env.optional(variable).is_some() {
+                env.problem(
+                    variable,
+                    format!("can't be sent without TLS ({}=none)", vars.tls),
+                );
+            }
+        }
+        return Some(None);
+    }
+    let username = env.required(vars.username);
+    let password = env.required(vars.password);
+    Some(Some(Credentials::new(username?, password?)))
+}
+
+/// Reads settings and records every problem instead of stopping at the first one.
+struct EnvReader<F> {
+    lookup: F,
+    problems: Vec<ConfigProblem>,
+}
+
+impl<F: Fn(&str) -> Option<String>> EnvReader<F> {
+    fn problem(&mut self, variable: &'static str, message: String) {
+        let problem = ConfigProblem { variable, message };
+        // Settings are sometimes read more than once
+        if !self.problems.contains(&problem) {
+            self.problems.push(problem);
+        }
+    }
+
+    /// The value of `variable`, or the contents of the file named by `<variable>_FILE`.
+    fn optional(&mut self, variable: &'static str) -> Option<String> {
+        let value = (self.lookup)(variable).filter(|value| !value.trim().is_empty());
+        let file_variable = format!("{variable}_FILE");
+        let Some(path) = (self.lookup)(&file_variable).filter(|path| !path.trim().is_empty())
+        else {
+            return value;
+        };
+        if value.is_some() {
+            self.problem(
+                variable,
+                format!("can't be set together with {file_variable}"),
+            );
+            return None;
+        }
+        match std::fs::read_to_string(path.trim()) {
+            // Files usually end with a newline, which isn't part of the secret
+            Ok(contents) => Some(contents.trim_end_matches(['\r', '\n']).to_string())
+                .filter(|value| !value.trim().is_empty()),
+            Err(e) => {
+                self.problem(
+                    variable,
+                    format!("can't read {file_variable} ({path}): {e}"),
+                );
+                None
+            }
+        }
+    }
+
+    fn parse_optional<T>(&mut self, variable: &'static str) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let value = self.optional(variable)?;
+        match value.trim().parse() {
+            Ok(parsed) => Some(parsed),
+            Err(e) => {
+                self.problem(variable, format!("invalid value: {e}"));
+                None
+            }
+        }
+    }
+
+    /// Parses a comma-separated list, which is empty when unset.
+    fn parse_list<T>(&mut self, variable: &'static str) -> Vec<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let Some(value) = self.optional(variable) else {
+            return Vec::new();
+        };
+        let mut parsed = Vec::new();
+        for item in value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+        {
+            match item.parse() {
+                Ok(item) => parsed.push(item),
+                Err(e) => self.problem(variable, format!("invalid value {item:?}: {e}")),
+            }
+        }
+        parsed
+    }
+
+    fn parse_or<T>(&mut self, variable: &'static str, default: T) -> T
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.parse_optional(variable).unwrap_or(default)
+    }
+
+    fn required<T>(&mut self, variable: &'static str) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        if self.optional(variable).is_none() {
+            // Unless reading it already failed
+            if !self.problems.iter().any(|p| p.variable == variable) {
+                self.problem(variable, "not set".to_string());
+            }
+            return None;
+        }
+        self.parse_optional(variable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::captcha::CaptchaProvider;
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    fn config_from(vars: &[(&str, &str)]) -> Result<Config, ConfigError> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Config::from_lookup(|name| vars.get(name).cloned())
+    }
+
+    const VALID: &[(&str, &str)] = &[
+        ("BASE_URL", "https://amigooculto.app"),
+        ("SMTP_HOST", "smtp.example.com"),
+        ("SMTP_PORT", "587"),
+        ("SMTP_USERNAME", "user"),
+        ("SMTP_PASSWORD", "secret"),
+        ("SMTP_FROM", "noreply@amigooculto.app"),
+    ];
+
+    #[test]
+    fn test_config_file() {
+        let file = ConfigFile::from_text(
+            Path::new("config.toml"),
+            r#"
+            base_url = "https://amigooculto.app"
+            port = 8080
+
+            [smtp]
+            host = "smtp.example.com"
+            port = 587
+            username = "user"
+            password = "secret"
+            from = "noreply@amigooculto.app"
+            "#,
+        )
+        .unwrap();
+        let env = |name: &str| (name == "PORT").then(|| "9000".to_string());
+        let config = Config::from_sources(env, &file).unwrap();
+        assert_eq!(config.port, 9000);
+        assert_eq!(config.base_url.as_str(), "https://amigooculto.app/");
+
+        let file = ConfigFile::from_text(Path::new("config.toml"), "[smtp]\nhots = \"x\"").unwrap();
+        let vars: HashMap<_, _> = VALID.iter().copied().collect();
+        let errors = Config::from_sources(|name| vars.get(name).map(|v| v.to_string()), &file)
+            .err()
+            .unwrap();
+        assert_eq!(errors.problems.len(), 1);
+        assert_eq!(errors.problems[0].variable, "CONFIG_FILE");
+        assert!(errors.problems[0].message.contains("SMTP_HOTS"));
+    }
+
+    #[test]
+    fn test_valid_config_uses_defaults() {
+        let config = config_from(VALID).unwrap();
+        assert_eq!(config.port, 3000);
+        #[cfg(not(feature = "embed-frontend"))]
+        assert_eq!(
+            config.static_files,
+            StaticFiles::Dir(PathBuf::from("/app/public"))
+        );
+        #[cfg(feature = "embed-frontend")]
+        assert_eq!(config.static_files, StaticFiles::Embedded);
+        assert!(config.legacy_admin_tokens);
+        assert!(matches!(
+            config.email.provider,
+            EmailProvider::Smtp {
+                primary: SmtpConfig { port: 587, .. },
+                secondary: None,
+            }
+        ));
+        assert!(config.email.site_admin_address.is_none());
+        assert_eq!(config.email_dispatch.concurrency.get(), 5);
+        assert_eq!(config.email_dispatch.per_second.get(), 10);
+        assert!(!config.trust_proxy);
+        assert!(config.redis.is_none());
+        assert_eq!(config.body_limits, BodyLimits::default());
+    }
+
+    #[test]
+    fn test_body_limits() {
+        let mut vars = VALID.to_vec();
+        vars.extend_from_slice(&[
+            ("MAX_VERIFICATION_BODY_BYTES", "4096"),
+            ("MAX_IMPORT_BODY_BYTES", "lots"),
+        ]);
+        let errors = config_from(&vars).err().unwrap();
+        assert_eq!(errors.problems[0].variable, "MAX_IMPORT_BODY_BYTES");
+
+        vars.pop();
+        let body_limits = config_from(&vars).unwrap().body_limits;
+        assert_eq!(body_limits.verification, 4096);
+        assert_eq!(body_limits.import, BodyLimits::default().import);
+    }
+
+    #[test]
+    fn test_reports_all_problems() {
+        let errors = config_from(&[
+            ("BASE_URL", "not a url"),
+            ("SMTP_HOST", "smtp.example.com"),
+            ("SMTP_PORT", "smtp"),
+            ("SMTP_FROM", "noreply"),
+            ("PORT", "99999"),
+        ])
+        .err()
+        .unwrap();
+
+        let variables: Vec<_> = errors.problems.iter().map(|p| p.variable).collect();
+        assert_eq!(
+            variables,
+            [
+                "PORT",
+                "BASE_URL",
+                "SMTP_PORT",
+                "SMTP_USERNAME",
+                "SMTP_PASSWORD",
+                "SMTP_FROM"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backup_config() {
+        assert!(config_from(VALID).unwrap().backup.is_none());
+
+        let mut vars = VALID.to_vec();
+        vars.extend_from_slice(&[
+            ("BACKUP_S3_BUCKET", "amigo-oculto"),
+            ("BACKUP_S3_REGION", "sa-east-1"),
+            ("BACKUP_S3_ACCESS_KEY_ID", "key"),
+            ("BACKUP_S3_SECRET_ACCESS_KEY", "secret"),
+        ]);
+        let backup = config_from(&vars).unwrap().backup.unwrap();
+        assert_eq!(
+            backup.endpoint.as_str(),
+            "https://s3.sa-east-1.amazonaws.com/"
+        );
+        assert_eq!(backup.prefix, "backups/");
+        assert_eq!(backup.interval, Duration::from_secs(24 * 3600));
+
+        vars.push(("BACKUP_S3_ENDPOINT", "http://localhost:9000"));
+        let errors = config_from(&vars).err().unwrap();
+        assert_eq!(errors.problems[0].variable, "BACKUP_S3_ENDPOINT");
+
+        let mut vars = VALID.to_vec();
+        vars.push(("BACKUP_S3_BUCKET", "amigo-oculto"));
+        let errors = config_from(&vars).err().unwrap();
+        let variables: Vec<_> = errors.problems.iter().map(|p| p.variable).collect();
+        assert_eq!(
+            variables,
+            [
+                "BACKUP_S3_REGION",
+                "BACKUP_S3_ACCESS_KEY_ID",
+                "BACKUP_S3_SECRET_ACCESS_KEY"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_media_config() {
+        let config = config_from(VALID).unwrap();
+        assert!(
+            matches!(config.media, StorageConfig::Local(root) if root == Path::new("/app/data/media"))
+        );
+
+        let mut vars = VALID.to_vec();
+        vars.extend_from_slice(&[
+            ("MEDIA_S3_BUCKET", "amigo-oculto"),
+            ("MEDIA_S3_REGION", "sa-east-1"),
+            ("MEDIA_S3_ACCESS_KEY_ID", "key"),
+            ("MEDIA_S3_SECRET_ACCESS_KEY", "secret"),
+        ]);
+        let StorageConfig::S3(media) = config_from(&vars).unwrap().media else {
+            panic!("expected S3 media storage");
+        };
+        assert_eq!(
+            media.endpoint.as_str(),
+            "https://s3.sa-east-1.amazonaws.com/"
+        );
+        assert_eq!(media.prefix, "media/");
+
+        vars.push(("MEDIA_S3_ENDPOINT", "http://localhost:9000"));
+        let errors = config_from(&vars).err().unwrap();
+        assert_eq!(errors.problems[0].variable, "MEDIA_S3_ENDPOINT");
+
+        let mut vars = VALID.to_vec();
+        vars.push(("MEDIA_S3_BUCKET", "amigo-oculto"));
+        let errors = config_from(&vars).err().unwrap();
+        let variables: Vec<_> = errors.problems.iter().map(|p| p.variable).collect();
+        assert_eq!(
+            variables,
+            [
+                "MEDIA_S3_REGION",
+                "MEDIA_S3_ACCESS_KEY_ID",
+                "MEDIA_S3_SECRET_ACCESS_KEY"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_reporting_config() {
+        assert!(config_from(VALID).unwrap().error_reporting.is_none());
+
+        let mut vars = VALID.to_vec();
+        vars.push(("SENTRY_DSN", "https://key@sentry.example.com/1"));
+        let error_reporting = config_from(&vars).unwrap().error_reporting.unwrap();
+        assert_eq!(error_reporting.environment, "production");
+
+        vars.push(("SENTRY_ENVIRONMENT", "staging"));
+        let error_reporting = config_from(&vars).unwrap().error_reporting.unwrap();
+        assert_eq!(error_reporting.environment, "staging");
+
+        let mut vars = VALID.to_vec();
+        vars.push(("SENTRY_DSN", "http://key@sentry.example.com/1"));
+        let errors = config_from(&vars).err().unwrap();
+        assert_eq!(errors.problems[0].variable, "SENTRY_DSN");
+    }
+
+    #[test]
+    fn test_http_email_providers() {
+        let config = config_from(&[
+            ("BASE_URL", "https://amigooculto.app"),
+            ("EMAIL_PROVIDER", "mailgun"),
+            ("MAILGUN_API_KEY", "key"),
+            ("MAILGUN_DOMAIN", "mg.amigooculto.app"),
+            ("SMTP_FROM", "noreply@amigooculto.app"),
+        ])
+        .unwrap();
+        let EmailProvider::Mailgun(mailgun) = config.email.provider else {
+            panic!("expected Mailgun");
+        };
+        assert_eq!(mailgun.api_base.as_str(), "https://api.mailgun.net/");
+
+        let errors = config_from(&[
+            ("BASE_URL", "https://amigooculto.app"),
+            ("EMAIL_PROVIDER", "sendgrid"),
+            ("SMTP_FROM", "noreply@amigooculto.app"),
+        ])
+        .err()
+        .unwrap();
+        let variables: Vec<_> = errors.problems.iter().map(|p| p.variable).collect();
+        assert_eq!(variables, ["SENDGRID_API_KEY"]);
+
+        let errors = config_from(&[
+            ("BASE_URL", "https://amigooculto.app"),
+            ("EMAIL_PROVIDER", "ses"),
+            ("AWS_REGION", "sa-east-1"),
+            ("SMTP_FROM", "noreply@amigooculto.app"),
+        ])
+        .err()
+        .unwrap();
+        let variables: Vec<_> = errors.problems.iter().map(|p| p.variable).collect();
+        assert_eq!(variables, ["AWS_ACCESS_KEY_ID", "AWS_SECRET_ACCESS_KEY"]);
+
+        let errors = config_from(&[("EMAIL_PROVIDER", "postmark")])
+            .err()
+            .unwrap();
+        assert_eq!(errors.problems[1].variable, "EMAIL_PROVIDER");
+    }
+
+    #[test]
+    fn test_smtp_tls() {
+        let smtp = |vars: &[(&'static str, &'static str)]| {
+            let mut all = VALID.to_vec();
+            all.extend_from_slice(vars);
+            config_from(&all).map(|config| match config.email.provider {
+                EmailProvider::Smtp { primary, .. } => primary,
+                _ => panic!("expected SMTP"),
+            })
+        };
+        assert_eq!(smtp(&[]).unwrap().tls, SmtpTls::Starttls);
+        assert_eq!(
+            smtp(&[("SMTP_PORT", "465")]).unwrap().tls,
+            SmtpTls::Implicit
+        );
+        assert_eq!(
+            smtp(&[("SMTP_TLS", "opportunistic")]).unwrap().tls,
+            SmtpTls::Opportunistic
+        );
+
+        // A local MailHog needs neither TLS nor credentials
+        let config = config_from(&[
+            ("BASE_URL", "http://localhost:3000"),
+            ("SMTP_HOST", "mailhog"),
+            ("SMTP_PORT", "1025"),
+            ("SMTP_TLS", "none"),
+            ("SMTP_FROM", "noreply@amigooculto.app"),
+        ])
+        .unwrap();
+        let EmailProvider::Smtp {
+            primary: mailhog, ..
+        } = config.email.provider
+        else {
+            panic!("expected SMTP");
+        };
+        assert_eq!(mailhog.tls, SmtpTls::None);
+        assert!(mailhog.credentials.is_none());
+
+        let problems = |vars| {
+            smtp(vars)
+                .err()
+                .unwrap()
+                .problems
+                .iter()
+                .map(|p| p.variable)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(problems(&[("SMTP_TLS", "ssl")]), ["SMTP_TLS"]);
+        assert_eq!(
+            problems(&[("SMTP_PORT", "465"), ("SMTP_TLS", "starttls")]),
+            ["SMTP_TLS"]
+        );
+        assert_eq!(problems(&[("SMTP_TLS", "tls")]), ["SMTP_TLS"]);
+        assert_eq!(
+            problems(&[("SMTP_TLS", "none")]),
+            ["SMTP_USERNAME", "SMTP_PASSWORD"]
+        );
+    }
+
+    #[test]
+    fn test_secondary_smtp() {
+        let mut vars = VALID.to_vec();
+        vars.extend_from_slice(&[
+            ("SMTP_SECONDARY_HOST", "smtp.backup.example.com"),
+            ("SMTP_SECONDARY_PORT", "465"),
+            ("SMTP_SECONDARY_USERNAME", "backup"),
+            ("SMTP_SECONDARY_PASSWORD", "secret"),
+        ]);
+        let config = config_from(&vars).unwrap();
+        let EmailProvider::Smtp {
+            secondary: Some(secondary),
+            ..
+        } = config.email.provider
+        else {
+            panic!("expected a secondary SMTP server");
+        };
+        assert_eq!(secondary.host, "smtp.backup.example.com");
+        assert_eq!(secondary.tls, SmtpTls::Implicit);
+
+        // Checked like the primary server
+        vars.truncate(VALID.len() + 2);
+        vars.push(("SMTP_SECONDARY_TLS", "starttls"));
+        let variables: Vec<_> = config_from(&vars)
+            .err()
+            .unwrap()
+            .problems
+            .iter()
+            .map(|p| p.variable)
+            .collect();
+        assert_eq!(
+            variables,
+            [
+                "SMTP_SECONDARY_USERNAME",
+                "SMTP_SECONDARY_PASSWORD",
+                "SMTP_SECONDARY_TLS"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_captcha() {
+        assert!(config_from(VALID).unwrap().captcha.is_none());
+
+        let mut vars = VALID.to_vec();
+        vars.extend([
+            ("CAPTCHA_PROVIDER", "turnstile"),
+            ("CAPTCHA_SITE_KEY", "site"),
+            ("CAPTCHA_SECRET_KEY", "secret"),
+        ]);
+        let captcha = config_from(&vars).unwrap().captcha.unwrap();
+        assert_eq!(captcha.provider, CaptchaProvider::Turnstile);
+        assert_eq!(captcha.site_key, "site");
+
+        // A single key is a mistake, not a way to disable the check
+        let mut vars = VALID.to_vec();
+        vars.extend([
+            ("CAPTCHA_PROVIDER", "recaptcha"),
+            ("CAPTCHA_SITE_KEY", "site"),
+        ]);
+        let variables: Vec<_> = config_from(&vars)
+            .err()
+            .unwrap()
+            .problems
+            .iter()
+            .map(|p| p.variable)
+            .collect();
+        assert_eq!(variables, ["CAPTCHA_PROVIDER", "CAPTCHA_SECRET_KEY"]);
+    }
+
+    #[test]
+    fn test_blocked_email_domains() {
+        assert!(config_from(VALID).unwrap().blocked_email_domains.is_empty());
+
+        let mut vars = VALID.to_vec();
+        vars.push(("BLOCKED_EMAIL_DOMAINS", "descartavel.com.br, @Lixo.net,"));
+        let config = config_from(&vars).unwrap();
+        let domains: Vec<_> = config
+            .blocked_email_domains
+            .iter()
+            .map(EmailDomain::as_str)
+            .collect();
+        assert_eq!(domains, ["descartavel.com.br", "lixo.net"]);
+
+        vars.push(("BLOCKED_EMAIL_DOMAINS", "localhost"));
+        let errors = config_from(&vars).err().unwrap();
+        assert_eq!(errors.problems[0].variable, "BLOCKED_EMAIL_DOMAINS");
+    }
+
+    #[test]
+    fn test_secret_files() {
+        let dir = std::env::temp_dir().join(format!("amigo-oculto-secrets-{}", ulid::Ulid::new()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let password_file = dir.join("smtp_password");
+        std::fs::write(&password_file, "from-file\n").unwrap();
+        let admin_file = dir.join("site_admin_password");
+        std::fs::write(&admin_file, "admin secret").unwrap();
+        let password_path = password_file.to_str().unwrap();
+        let admin_path = admin_file.to_str().unwrap();
+
+        let mut vars: Vec<_> = VALID
+            .iter()
+            .copied()
+            .filter(|(name, _)| *name != "SMTP_PASSWORD")
+            .collect();
+        vars.push(("SMTP_PASSWORD_FILE", password_path));
+        vars.push(("SITE_ADMIN_PASSWORD_FILE", admin_path));
+        let config = config_from(&vars).unwrap();
+        let EmailProvider::Smtp { primary, .. } = config.email.provider else {
+            panic!("expected SMTP");
+        };
+        assert_eq!(
+            primary.credentials,
+            Some(Credentials::new(
+                "user".to_string(),
+                "from-file".to_string()
+            ))
+        );
+        assert_eq!(config.site_admin_password.as_deref(), Some("admin secret"));
+
+        // Either the value or the file, and the file must exist
+        vars.push(("SMTP_PASSWORD", "secret"));
+        vars.push(("EMAIL_WEBHOOK_SECRET_FILE", "/nonexistent/secret"));
+        let variables: Vec<_> = config_from(&vars)
+            .err()
+            .unwrap()
+            .problems
+            .iter()
+            .map(|p| p.variable)
+            .collect();
+        assert_eq!(variables, ["SMTP_PASSWORD", "EMAIL_WEBHOOK_SECRET"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_email_modes() {
+        let config = config_from(&[
+            ("BASE_URL", "http://localhost:3000"),
+            ("EMAIL_MODE", "file"),
+            ("EMAIL_MAILDIR", "./data/maildir"),
+            ("SMTP_FROM", "noreply@amigooculto.app"),
+        ])
+        .unwrap();
+        let EmailProvider::Maildir(dir) = config.email.provider else {
+            panic!("expected a maildir");
+        };
+        assert_eq!(dir, PathBuf::from("./data/maildir"));
+
+        // No provider settings are needed when emails are only logged
+        let config = config_from(&[
+            ("BASE_URL", "http://localhost:3000"),
+            ("EMAIL_MODE", "log"),
+            ("SMTP_FROM", "noreply@amigooculto.app"),
+        ])
+        .unwrap();
+        assert!(matches!(config.email.provider, EmailProvider::Log));
+
+        let errors = config_from(&[("EMAIL_MODE", "dry-run")]).err().unwrap();
+        assert_eq!(errors.problems[1].variable, "EMAIL_MODE");
+    }
+}
@@ -0,0 +1,292 @@
+//! The optional `config.toml`, holding the same settings as the environment variables.
+//!
+//! Each key names the variable it sets, in lower case, with tables standing for the
+//! prefixes: `base_url = "..."` sets `BASE_URL`, and `port` under `[smtp]` sets `SMTP_PORT`.
+//! Environment variables take precedence over the file.
+//!
+//! The file is parsed by the `toml` crate. Settings are strings, integers, booleans or
+//! arrays of strings (for lists such as `blocked_email_domains`); other values, such as
+//! dates, are reported with their line number.
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use toml::Spanned;
+
+/// File read when `CONFIG_FILE` isn't set. It's fine for it not to exist.
+pub const DEFAULT_PATH: &str = "config.toml";
+
+/// A setting from the file, by the name of the variable it sets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Setting {
+    value: String,
+    line: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct ConfigFile {
+    path: PathBuf,
+    settings: HashMap<String, Setting>,
+}
+
+/// Where the file isn't valid TOML, or has a value that can't be a setting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl ConfigFile {
+    /// Reads `path`, or the default file if it exists.
+    pub fn read(path: Option<&Path>) -> Result<Self, String> {
+        let (path, required) = match path {
+            Some(path) => (path, true),
+            None => (Path::new(DEFAULT_PATH), false),
+        };
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(e) => return Err(format!("can't read {}: {e}", path.display())),
+        };
+        Self::from_text(path, &text)
+    }
+
+    /// Parses the contents of the file at `path`.
+    pub fn from_text(path: &Path, text: &str) -> Result<Self, String> {
+        let settings = parse(text).map_err(|e| format!("{}: {e}", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            settings,
+        })
+    }
+
+    pub fn get(&self, variable: &str) -> Option<String> {
+        self.settings
+            .get(variable)
+            .map(|setting| setting.value.clone())
+    }
+
+    /// Settings in the file other than the `used` variables: typos, or settings that only
+    /// apply together with another one.
+    pub fn unused(&self, used: &[String]) -> Vec<String> {
+        let mut unknown: Vec<_> = self
+            .settings
+            .iter()
+            .filter(|(variable, _)| !used.contains(variable))
+            .map(|(variable, setting)| {
+                format!(
+                    "{}: line {}: {variable} isn't a setting, or isn't used with the others",
+                    self.path.display(),
+                    setting.line,
+                )
+            })
+            .collect();
+        unknown.sort();
+        unknown
+    }
+}
+
+/// A value in the file: a setting, or a table of them. Each is deserialized with its span,
+/// so settings can be reported with their line.
+enum Node {
+    Setting(String),
+    Table(BTreeMap<String, Spanned<Node>>),
+}
+
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(NodeVisitor)
+    }
+}
+
+/// Reads values as the text of environment variables.
+struct NodeVisitor;
+
+impl<'de> Visitor<'de> for NodeVisitor {
+    type Value = Node;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string, integer, boolean, array of strings or table")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Node, E> {
+        Ok(Node::Setting(value.to_string()))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Node, E> {
+        Ok(Node::Setting(value.to_string()))
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Node, E> {
+        Ok(Node::Setting(value.to_string()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Node, A::Error> {
+        // Lists are comma-separated in environment variables
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element::<String>()? {
+            items.push(item);
+        }
+        Ok(Node::Setting(items.join(",")))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Node, A::Error> {
+        let mut table = BTreeMap::new();
+        while let Some(key) = map.next_key::<String>()? {
+            // The toml crate passes dates as a map with a single private key
+            if key.starts_with("$__toml_private") {
+                return Err(de::Error::custom("dates aren't supported"));
+            }
+            table.insert(key, map.next_value()?);
+        }
+        Ok(Node::Table(table))
+    }
+}
+
+/// Reads the settings, by variable name.
+fn parse(text: &str) -> Result<HashMap<String, Setting>, ParseError> {
+    let table: BTreeMap<String, Spanned<Node>> = toml::from_str(text).map_err(|e| ParseError {
+        line: e.span().map_or(1, |span| line_at(text, span.start)),
+        message: e.message().to_string(),
+    })?;
+    let mut settings = HashMap::new();
+    flatten(text, "", table, &mut settings)?;
+    Ok(settings)
+}
+
+/// Adds the settings of `table`, whose variables start with `prefix`: `port` under `[smtp]`
+/// is `SMTP_PORT`.
+fn flatten(
+    text: &str,
+    prefix: &str,
+    table: BTreeMap<String, Spanned<Node>>,
+    settings: &mut HashMap<String, Setting>,
+) -> Result<(), ParseError> {
+    for (key, node) in table {
+        let variable = format!("{prefix}{}", key.replace('-', "_").to_uppercase());
+        let line = line_at(text, node.span().start);
+        match node.into_inner() {
+            Node::Table(table) => flatten(text, &format!("{variable}_"), table, settings)?,
+            Node::Setting(value) => {
+                // e.g. `smtp_port` at the top and `port` under `[smtp]`
+                if let Some(other) = settings.get(&variable) {
+                    return Err(ParseError {
+                        line: line.max(other.line),
+                        message: format!(
+                            "{} is already set on line {}",
+                            variable.to_lowercase(),
+                            line.min(other.line)
+                        ),
+                    });
+                }
+                settings.insert(variable, Setting { value, line });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The line of the byte at `offset` in `text`, from 1.
+fn line_at(text: &str, offset: usize) -> usize {
+    text[..offset.min(text.len())].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(text: &str) -> Vec<(String, String)> {
+        let mut values: Vec<_> = parse(text)
+            .unwrap()
+            .into_iter()
+            .map(|(variable, setting)| (variable, setting.value))
+            .collect();
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn test_parse() {
+        let text = r#"
+            # Where the site is served
+            base_url = "https://amigooculto.app"  # trailing comment
+            trust_proxy = true
+
+            [smtp]
+            host = 'smtp.example.com'
+            port = 587
+            password = "p\"ass\\word"
+
+            [backup.s3]
+            bucket = "amigo-oculto"
+
+            [email]
+            blocked-domains = [
+                "spam.example",
+                'junk.example',
+            ]
+        "#;
+        assert_eq!(
+            values(text),
+            [
+                ("BACKUP_S3_BUCKET", "amigo-oculto"),
+                ("BASE_URL", "https://amigooculto.app"),
+                ("EMAIL_BLOCKED_DOMAINS", "spam.example,junk.example"),
+                ("SMTP_HOST", "smtp.example.com"),
+                ("SMTP_PASSWORD", "p\"ass\\word"),
+                ("SMTP_PORT", "587"),
+                ("TRUST_PROXY", "true"),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+        );
+        assert_eq!(
+            values("smtp.port = 2_525"),
+            [("SMTP_PORT".into(), "2525".into())]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |text: &str| parse(text).unwrap_err();
+        assert_eq!(error("\nport = 3000\nport = 3001").line, 3);
+        assert_eq!(error("base_url = https://example.com").line, 1);
+        assert_eq!(error("[smtp\nhost = \"x\"").line, 1);
+        assert_eq!(error("\nname = \"unterminated").line, 2);
+        assert_eq!(error("port = 3000 3001").line, 1);
+        assert_eq!(error("\n\nports = [\n  3000,\n]").line, 4);
+        assert_eq!(error("[[servers]]\nname = \"a\"").line, 1);
+        assert_eq!(error("ratio = 0.5").line, 1);
+        assert_eq!(error("\nstart = 2024-12-25").line, 2);
+        assert_eq!(error("just a line").line, 1);
+
+        let error = error("smtp_port = 25\n[smtp]\nport = 587");
+        assert_eq!(error.line, 3);
+        assert_eq!(error.message, "smtp_port is already set on line 1");
+    }
+
+    #[test]
+    fn test_unused_settings() {
+        let file =
+            ConfigFile::from_text(Path::new("config.toml"), "[smtp]\nhots = \"x\"\nport = 587")
+                .unwrap();
+        assert_eq!(
+            file.unused(&["SMTP_PORT".to_string()]),
+            ["config.toml: line 2: SMTP_HOTS isn't a setting, or isn't used with the others"]
+        );
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let dir = std::env::temp_dir().join(format!("amigo-oculto-config-{}", ulid::Ulid::new()));
+        assert!(ConfigFile::read(Some(&dir.join("config.toml"))).is_err());
+    }
+}
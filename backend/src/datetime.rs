@@ -0,0 +1,209 @@
+//! Time zones and localized date formatting.
+//!
+//! Games record the IANA time zone where the event happens, so that "today" and scheduled
+//! times are interpreted in the organizer's local time rather than UTC, with daylight saving
+//! time as the tz database (`chrono-tz`) has it.
+
+use crate::i18n::Locale;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// Formats a date in a language, e.g. "14 de dezembro de 2025" in Portuguese or
+/// "December 14, 2025" in English.
+pub fn format_date(date: NaiveDate, locale: Locale) -> String {
+    let (format, chrono_locale) = match locale {
+        Locale::PtBr => ("%-d de %B de %Y", chrono::Locale::pt_BR),
+        Locale::En => ("%B %-d, %Y", chrono::Locale::en_US),
+        Locale::Es => ("%-d de %B de %Y", chrono::Locale::es_ES),
+    };
+    date.format_localized(format, chrono_locale).to_string()
+}
+
+// =============================================================================
+// GameTimezone Newtype (supported IANA zone name)
+// =============================================================================
+
+/// The IANA time zone of a game, such as `America/Sao_Paulo` (the default).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ToSchema)]
+#[schema(value_type = String, example = "America/Sao_Paulo")]
+pub struct GameTimezone(Tz);
+
+/// Error type for names missing from the tz database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidTimezone;
+
+impl fmt::Display for InvalidTimezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown time zone")
+    }
+}
+
+impl std::error::Error for InvalidTimezone {}
+
+impl GameTimezone {
+    /// The IANA name, e.g. `America/Sao_Paulo`.
+    pub fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    /// The date in this time zone at the given instant, e.g. today's date given the current time.
+    pub fn date_at(&self, instant: DateTime<Utc>) -> NaiveDate {
+        instant.with_timezone(&self.0).date_naive()
+    }
+
+    /// Formats an instant as local time in a language, e.g. "24 de dezembro de 2025 às
+    /// 20:00" in Portuguese or "December 24, 2025 at 8:00 PM" in English.
+    pub fn format_datetime(&self, instant: DateTime<Utc>, locale: Locale) -> String {
+        let local = instant.with_timezone(&self.0);
+        let (connector, time_format) = match locale {
+            Locale::PtBr => ("às", "%H:%M"),
+            Locale::En => ("at", "%-I:%M %p"),
+            Locale::Es => ("a las", "%H:%M"),
+        };
+        format!(
+            "{} {} {}",
+            format_date(local.date_naive(), locale),
+            connector,
+            local.format(time_format)
+        )
+    }
+}
+
+impl Default for GameTimezone {
+    fn default() -> Self {
+        Self(Tz::America__Sao_Paulo)
+    }
+}
+
+impl fmt::Debug for GameTimezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GameTimezone({})", self.name())
+    }
+}
+
+impl fmt::Display for GameTimezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for GameTimezone {
+    type Err = InvalidTimezone;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Tz::from_str(s).map(Self).map_err(|_| InvalidTimezone)
+    }
+}
+
+impl Serialize for GameTimezone {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for GameTimezone {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl sqlx::Type<sqlx::Sqlite> for GameTimezone {
+    fn type_info() -> sqlx::sqlite::SqliteTypeInfo {
+        <String as sqlx::Type<sqlx::Sqlite>>::type_info()
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Sqlite> for GameTimezone {
+    fn decode(value: sqlx::sqlite::SqliteValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let s = <String as sqlx::Decode<sqlx::Sqlite>>::decode(value)?;
+        Ok(Self::from_str(&s)?)
+    }
+}
+
+impl<'q> sqlx::Encode<'q, sqlx::Sqlite> for GameTimezone {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<sqlx::sqlite::SqliteArgumentValue<'q>>,
+    ) -> Result<sqlx::encode::IsNull, Box<dyn std::error::Error + Send + Sync>> {
+        <&str as sqlx::Encode<sqlx::Sqlite>>::encode_by_ref(&self.name(), args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_date() {
+        let date = NaiveDate::from_ymd_opt(2025, 12, 14).unwrap();
+        assert_eq!(format_date(date, Locale::PtBr), "14 de dezembro de 2025");
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        assert_eq!(format_date(date, Locale::PtBr), "1 de março de 2025");
+        assert_eq!(format_date(date, Locale::En), "March 1, 2025");
+        assert_eq!(format_date(date, Locale::Es), "1 de marzo de 2025");
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        let tz: GameTimezone = "America/Manaus".parse().unwrap();
+        assert_eq!(tz.to_string(), "America/Manaus");
+        let tz: GameTimezone = "Europe/Lisbon".parse().unwrap();
+        assert_eq!(tz.to_string(), "Europe/Lisbon");
+        assert!("Europe/Atlantis".parse::<GameTimezone>().is_err());
+        assert!("america/sao_paulo".parse::<GameTimezone>().is_err());
+        assert_eq!(GameTimezone::default().to_string(), "America/Sao_Paulo");
+    }
+
+    #[test]
+    fn test_format_datetime_in_timezone() {
+        let instant = "2025-12-24T23:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        let sao_paulo = GameTimezone::default();
+        assert_eq!(
+            sao_paulo.format_datetime(instant, Locale::PtBr),
+            "24 de dezembro de 2025 às 20:30"
+        );
+        assert_eq!(
+            sao_paulo.format_datetime(instant, Locale::En),
+            "December 24, 2025 at 8:30 PM"
+        );
+        assert_eq!(
+            sao_paulo.format_datetime(instant, Locale::Es),
+            "24 de diciembre de 2025 a las 20:30"
+        );
+        let noronha: GameTimezone = "America/Noronha".parse().unwrap();
+        assert_eq!(
+            noronha.format_datetime(instant, Locale::PtBr),
+            "24 de dezembro de 2025 às 21:30"
+        );
+    }
+
+    #[test]
+    fn test_daylight_saving_time() {
+        let lisbon: GameTimezone = "Europe/Lisbon".parse().unwrap();
+        let winter = "2025-12-24T23:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        let summer = "2025-07-24T23:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(
+            lisbon.format_datetime(winter, Locale::PtBr),
+            "24 de dezembro de 2025 às 23:30"
+        );
+        assert_eq!(
+            lisbon.format_datetime(summer, Locale::PtBr),
+            "25 de julho de 2025 às 00:30"
+        );
+        assert_eq!(
+            lisbon.date_at(summer),
+            NaiveDate::from_ymd_opt(2025, 7, 25).unwrap()
+        );
+    }
+}
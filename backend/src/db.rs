@@ -1,23 +1,96 @@
-use crate::models::{EmailVerification, Game, Participant};
+use crate::clock::Clock;
+use crate::datetime::GameTimezone;
+use crate::email_events::EmailEventKind;
+use crate::i18n::Locale;
+use crate::models::{
+    AbuseReportSummary, AdminSession, Announcement, AnnouncementRecipient, EmailBan, EmailJob,
+    EmailResend, EmailTemplate, EmailVerification, Game, GameStatus, IdempotencyKeyState, JobRun,
+    LoginOutcome, MatchAccess, Participant, ParticipantFilter, ParticipantStatus,
+    PasskeyCredential, QueryPlan, RecentRequests, RecoveredReveal, ReminderKind, RevealRecovery,
+    SiteAdminLoginAttempt, SiteAdminPasskey, SiteStatsDay, SiteStatsTotals, SuppressionReason,
+    WrapUpStats,
+};
+use crate::organizer_auth;
+use crate::storage::MediaKey;
 use crate::token::{
-    AdminSessionToken, AdminToken, EmailAddress, GameId, ParticipantId, VerificationCode,
-    VerificationId, ViewToken,
+    AdminSessionId, AdminSessionToken, AnnouncementId, EmailAddress, EmailBanId, EmailDomain,
+    EmailJobId, EmailOpenToken, GameId, OrganizerLoginToken, OrganizerSessionToken, ParticipantId,
+    PasskeyId, RecoveryId, ReportId, TokenHash, UnsubscribeToken, VerificationId, ViewToken,
 };
+use crate::totp;
+use crate::webauthn::{self, Ceremony};
 use anyhow::{Context, Result};
-use chrono::{DateTime, Duration, Utc};
+use base64::prelude::*;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use sqlx::{
-    Row, Sqlite,
-    sqlite::{SqliteConnectOptions, SqlitePool},
+    ConnectOptions, Connection, Row, Sqlite,
+    migrate::{Migrate, Migrator},
+    sqlite::{SqliteConnectOptions, SqlitePool, SqliteRow},
 };
+use std::path::Path;
 use std::str::FromStr;
+use std::sync::Arc;
 use ulid::Ulid;
+use webauthn_rs::prelude::Passkey;
+
+/// Number of days after event_date before a game is eligible for cleanup, unless the site
+/// admin changed it.
+pub const DEFAULT_GAME_RETENTION_DAYS: u32 = 90;
+
+/// Bounds of the game retention the site admin can set. Keeping games at least a week after
+/// the event leaves time to catch a mistake before anything is deleted.
+pub const GAME_RETENTION_DAYS_RANGE: std::ops::RangeInclusive<u32> = 7..=3650;
+
+/// Key of the game retention in `site_settings`.
+const GAME_RETENTION_DAYS_SETTING: &str = "game_retention_days";
+
+/// Number of days without organizer activity before an undrawn game is eligible for cleanup.
+pub const IDLE_GAME_RETENTION_DAYS: u32 = 30;
+
+/// Days after the draw before participants who haven't seen their match get a reminder.
+pub const REMINDER_DAYS_AFTER_DRAW: i64 = 3;
+
+/// Days before the event when participants who haven't seen their match get a last reminder.
+pub const REMINDER_DAYS_BEFORE_EVENT: i64 = 2;
+
+/// Number of days a deleted game can still be restored by the site admin before it is purged.
+pub const DELETED_GAME_RETENTION_DAYS: u32 = 7;
+
+/// Days between digests to the organizer.
+pub const DIGEST_INTERVAL_DAYS: i64 = 7;
+
+/// Hours a site admin session lasts after it was last used.
+pub const ADMIN_SESSION_DURATION_HOURS: i64 = 24;
+
+/// Hours failed site admin logins count towards a lockout, unless a login succeeds first.
+pub const LOGIN_FAILURE_WINDOW_HOURS: i64 = 24;
+
+/// Days site admin login attempts are kept for audit.
+const LOGIN_ATTEMPT_RETENTION_DAYS: i64 = 90;
+
+/// Times a queued participant email is tried before it counts as failed.
+pub const EMAIL_JOB_ATTEMPTS: i64 = 3;
+
+/// How long a queued participant email waits after failing before it's tried again.
+const EMAIL_JOB_RETRY_DELAY: Duration = Duration::minutes(5);
+
+/// How long an idempotency key is remembered: longer than any client keeps retrying.
+const IDEMPOTENCY_KEY_RETENTION: Duration = Duration::hours(24);
+
+/// How long a request holds its idempotency key before another may take it over, for
+/// requests that never finished.
+const IDEMPOTENCY_CLAIM_TIMEOUT: Duration = Duration::minutes(5);
 
-/// Number of days after event_date before a game is eligible for cleanup.
-pub const GAME_RETENTION_DAYS: u32 = 90;
+/// Migrations in `backend/migrations`, embedded at build time and applied on startup.
+///
+/// Files are numbered in the order they run. Applied migrations are checksummed, so never
+/// edit one that was released: add a new one instead.
+static MIGRATOR: Migrator = sqlx::migrate!();
 
 #[derive(Clone)]
 pub struct Database {
     pool: SqlitePool,
+    clock: Arc<dyn Clock>,
 }
 
 async fn init_db(database_url: &str) -> Result<SqlitePool> {
@@ -29,207 +102,861 @@ async fn init_db(database_url: &str) -> Result<SqlitePool> {
         // WAL mode is required by Litestream for replication.
         .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
         // NORMAL synchronous is safe in WAL mode and reduces fsync overhead.
-        .synchronous(sqlx::sqlite::SqliteSynchronous::Normal);
-    let pool = SqlitePool::connect_with(options).await?;
+        .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+        // Lets the maintenance task return free pages to the filesystem. Only new databases
+        // get it; existing ones keep their mode until a full VACUUM.
+        .auto_vacuum(sqlx::sqlite::SqliteAutoVacuum::Incremental);
+    // Opened first, as an in-memory database only lasts while it has a connection
+    let pool = SqlitePool::connect_with(options.clone()).await?;
+
+    migrate(&options).await?;
+    hash_plaintext_secrets(&pool)
+        .await
+        .context("hashing stored tokens and codes")?;
+
+    Ok(pool)
+}
+
+/// Brings the schema up to date.
+///
+/// Runs on a connection of its own with foreign keys off, so that a migration can rebuild a
+/// table (SQLite's way of changing most column definitions) without deleting the rows that
+/// refer to it along with the old one. The foreign keys are checked once it's done.
+async fn migrate(options: &SqliteConnectOptions) -> Result<()> {
+    let mut conn = options.clone().foreign_keys(false).connect().await?;
+
+    if table_exists(&mut conn, "_sqlx_migrations").await? {
+        let applied: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations")
+            .fetch_one(&mut conn)
+            .await
+            .context("reading applied migrations")?;
+        if let Some(applied) = applied.filter(|&v| v > latest_migration()) {
+            anyhow::bail!(
+                "database has migration {applied}, newer than the latest this binary has \
+                 ({}); refusing to start. Deploy a newer binary, or restore a backup taken \
+                 before the upgrade.",
+                latest_migration()
+            );
+        }
+    } else if table_exists(&mut conn, "games").await? {
+        // Databases from before migrations have the initial schema but no migration history
+        record_initial_migration(&mut conn)
+            .await
+            .context("recording the schema from before migrations")?;
+    }
+
+    MIGRATOR
+        .run_direct(&mut conn)
+        .await
+        .context("running database migrations")?;
+
+    let violations = sqlx::query("PRAGMA foreign_key_check")
+        .fetch_all(&mut conn)
+        .await
+        .context("checking foreign keys after migrations")?;
+    anyhow::ensure!(
+        violations.is_empty(),
+        "migrations left {} rows referring to missing ones",
+        violations.len()
+    );
+
+    conn.close().await?;
+    Ok(())
+}
+
+/// Version of the latest migration this binary has.
+fn latest_migration() -> i64 {
+    MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0)
+}
+
+async fn table_exists(conn: &mut sqlx::SqliteConnection, table: &str) -> Result<bool> {
+    let count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(table)
+            .fetch_one(conn)
+            .await
+            .with_context(|| format!("checking whether table {table} exists"))?;
+
+    Ok(count > 0)
+}
+
+/// Marks the initial migration as applied without running it, for a database whose tables
+/// were created on startup before there were migrations. The later ones then apply as usual.
+async fn record_initial_migration(conn: &mut sqlx::SqliteConnection) -> Result<()> {
+    let initial = MIGRATOR.iter().next().context("no migrations")?;
+    conn.ensure_migrations_table().await?;
+    sqlx::query(
+        "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) \
+         VALUES (?, ?, TRUE, ?, 0)",
+    )
+    .bind(initial.version)
+    .bind(&*initial.description)
+    .bind(&*initial.checksum)
+    .execute(conn)
+    .await?;
+    tracing::info!("recorded migration {} as applied", initial.version);
+
+    Ok(())
+}
+
+/// Reads a game with the status of each participant, see `Database::game_summary`.
+const GAME_SUMMARY_QUERY: &str = r#"
+    WITH last_views AS (
+        SELECT participant_id, MAX(viewed_at) as last_viewed_at
+        FROM reveal_events
+        WHERE game_id = ?1
+        GROUP BY participant_id
+    ),
+    devices AS (
+        SELECT d.participant_id, COUNT(*) as device_count
+        FROM reveal_devices d
+        JOIN participants p ON p.id = d.participant_id
+        WHERE p.game_id = ?1
+        GROUP BY d.participant_id
+    ),
+    resends AS (
+        SELECT participant_id, COUNT(*) as resend_count
+        FROM email_resends
+        WHERE game_id = ?1 AND participant_id IS NOT NULL
+        GROUP BY participant_id
+    ),
+    page AS (
+        SELECT id FROM participants
+        WHERE game_id = ?1 AND (?3 IS NULL OR has_viewed = ?3)
+        ORDER BY created_at ASC
+        LIMIT ?4 OFFSET ?5
+    )
+    SELECT g.id, g.name, g.event_date, g.organizer_email, g.admin_token, g.created_at,
+        g.drawn, g.allowed_email_domain, g.frozen_at, g.frozen_reason, g.deleted_at,
+        g.organizer_message, g.event_location, g.reveal_at, g.timezone, g.locale,
+        g.locked, g.reveal_expires_after_event, g.reveal_single_use, g.cover_image,
+        g.drawn_at, g.reminders_enabled, g.wrap_up_enabled, g.wrap_up_sent_at,
+        g.participant_email_subject, g.participant_email_body, g.event_reminder_days,
+        g.digest_enabled, g.digest_sent_at, g.reply_to_organizer,
+        g.open_tracking_enabled,
+        (SELECT COUNT(*) FROM email_resends WHERE game_id = g.id AND resend_type = 'bulk')
+            as bulk_resend_count,
+        (SELECT COUNT(*) FROM participants WHERE game_id = g.id) as participant_count,
+        (SELECT COUNT(*) FROM participants WHERE game_id = g.id AND has_viewed = 1)
+            as viewed_count,
+        (SELECT COUNT(*) FROM participants
+            WHERE game_id = g.id AND (?3 IS NULL OR has_viewed = ?3)) as matching_count,
+        p.id as participant_id, p.name as participant_name,
+        p.email as participant_email, p.has_viewed as participant_has_viewed,
+        p.created_at as participant_created_at,
+        CASE WHEN p.email_undeliverable_at IS NOT NULL
+            THEN p.email_undeliverable_reason END as email_undeliverable,
+        p.email_opened_at,
+        p.email_domain_unreachable_at IS NOT NULL as email_domain_unreachable,
+        v.last_viewed_at,
+        COALESCE(d.device_count, 0) as device_count,
+        COALESCE(r.resend_count, 0) as resend_count
+    FROM games g
+    LEFT JOIN participants p ON p.game_id = g.id AND p.id IN (SELECT id FROM page)
+    LEFT JOIN last_views v ON v.participant_id = p.id
+    LEFT JOIN devices d ON d.participant_id = p.id
+    LEFT JOIN resends r ON r.participant_id = p.id
+    WHERE g.id = ?1 AND (?2 OR g.deleted_at IS NULL)
+    ORDER BY p.created_at ASC
+"#;
+
+/// Queries run on most requests or by the background tasks, whose plans
+/// `Database::explain_hot_queries` shows. Parameters are left unbound.
+const HOT_QUERIES: &[(&str, &str)] = &[
+    (
+        "game",
+        "SELECT * FROM games WHERE id = ? AND deleted_at IS NULL",
+    ),
+    ("game summary", GAME_SUMMARY_QUERY),
+    (
+        "participants of a game",
+        "SELECT * FROM participants WHERE game_id = ? ORDER BY created_at ASC",
+    ),
+    (
+        "participant by view token",
+        "SELECT p.*, t.token_hash FROM view_tokens t \
+         JOIN participants p ON p.id = t.participant_id WHERE t.lookup_key = ?",
+    ),
+    (
+        "recent bulk resends",
+        "SELECT COUNT(*) FROM email_resends \
+         WHERE game_id = ? AND resent_at > ? AND resend_type = 'bulk'",
+    ),
+    (
+        "recent participant resends",
+        "SELECT COUNT(*) FROM email_resends \
+         WHERE participant_id = ? AND resent_at > ? AND resend_type = 'individual'",
+    ),
+    (
+        "games past retention",
+        "SELECT id FROM games WHERE event_date < ?",
+    ),
+    (
+        "upcoming drawn games",
+        "SELECT id FROM games WHERE drawn = 1 AND deleted_at IS NULL AND event_date >= ?",
+    ),
+];
+
+/// First bytes of every SQLite database file.
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// Tables whose rows are kept when restoring a snapshot: the migration history describes
+/// this binary's schema, and sessions and background task runs belong to the running server,
+/// so the site admin doing the restore stays logged in.
+const RESTORE_KEPT_TABLES: &[&str] = &["_sqlx_migrations", "admin_sessions", "job_runs"];
+
+/// Checks that a snapshot can be restored, and returns its latest migration.
+///
+/// Its applied migrations must all be ones this binary has, unchanged, so that data from a
+/// diverging or newer schema isn't copied into ours. Snapshots from before migrations have
+/// none and are upgraded like any database of that age.
+async fn check_snapshot(pool: &SqlitePool) -> Result<Option<i64>> {
+    let problems: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_all(pool)
+        .await
+        .context("checking snapshot integrity")?;
+    if problems != ["ok"] {
+        anyhow::bail!("snapshot is corrupted: {}", problems.join("; "));
+    }
+
+    let mut conn = pool.acquire().await?;
+    anyhow::ensure!(
+        table_exists(&mut conn, "games").await?,
+        "snapshot isn't an amigo oculto database"
+    );
+
+    let mut latest = None;
+    if table_exists(&mut conn, "_sqlx_migrations").await? {
+        let applied = sqlx::query("SELECT version, checksum, success FROM _sqlx_migrations")
+            .fetch_all(pool)
+            .await
+            .context("reading snapshot migrations")?;
+        for row in &applied {
+            let version: i64 = row.get("version");
+            let checksum: Vec<u8> = row.get("checksum");
+            let Some(migration) = MIGRATOR.iter().find(|m| m.version == version) else {
+                anyhow::bail!(
+                    "snapshot has migration {version}, which this binary doesn't know; restore \
+                     it with a newer binary"
+                );
+            };
+            anyhow::ensure!(
+                *migration.checksum == *checksum,
+                "migration {version} of the snapshot differs from this binary's"
+            );
+            anyhow::ensure!(
+                row.get::<bool, _>("success"),
+                "migration {version} of the snapshot didn't finish"
+            );
+            latest = latest.max(Some(version));
+        }
+    }
+
+    Ok(latest)
+}
+
+/// Replaces the rows of every table with those of the database attached as `snapshot`, which
+/// must have the same schema. Returns the number of rows copied.
+async fn copy_snapshot_tables(conn: &mut sqlx::SqliteConnection) -> Result<u64> {
+    // Not rolled back on failure: the caller closes the connection instead
+    sqlx::query("BEGIN IMMEDIATE").execute(&mut *conn).await?;
+    // Tables are emptied and filled in no particular order
+    sqlx::query("PRAGMA defer_foreign_keys = ON")
+        .execute(&mut *conn)
+        .await?;
+
+    let tables: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM main.sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' \
+         ORDER BY name",
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    let tables: Vec<String> = tables
+        .into_iter()
+        .filter(|t| !RESTORE_KEPT_TABLES.contains(&t.as_str()))
+        .collect();
+
+    for table in &tables {
+        sqlx::query(&format!("DELETE FROM main.\"{table}\""))
+            .execute(&mut *conn)
+            .await
+            .with_context(|| format!("emptying table {table}"))?;
+    }
+
+    let mut rows = 0;
+    for table in &tables {
+        // Named, so rows don't depend on the order columns were added in
+        let columns: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_info(?, 'main')")
+                .bind(table)
+                .fetch_all(&mut *conn)
+                .await?;
+        let columns = columns
+            .iter()
+            .map(|c| format!("\"{c}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        rows += sqlx::query(&format!(
+            "INSERT INTO main.\"{table}\" ({columns}) SELECT {columns} FROM snapshot.\"{table}\""
+        ))
+        .execute(&mut *conn)
+        .await
+        .with_context(|| format!("restoring table {table}"))?
+        .rows_affected();
+    }
+
+    sqlx::query("COMMIT")
+        .execute(&mut *conn)
+        .await
+        .context("committing restore (are the snapshot's foreign keys consistent?)")?;
+    Ok(rows)
+}
+
+/// Replaces the tokens and codes stored by versions from before hashing with their hashes, so
+/// a leaked database doesn't expose working links. A migration lists them in
+/// `plaintext_secrets`, so this only reads the rows listed there; it takes them off the list
+/// in the same transaction, so a start interrupted before then hashes them on the next one.
+async fn hash_plaintext_secrets(pool: &SqlitePool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    let listed = |table: &str, select: &str| {
+        format!(
+            "SELECT rowid, {select} FROM {table} WHERE rowid IN \
+             (SELECT row_id FROM plaintext_secrets WHERE table_name = '{table}')"
+        )
+    };
+
+    let rows = sqlx::query(&listed("games", "admin_token"))
+        .fetch_all(&mut *tx)
+        .await?;
+    for row in &rows {
+        sqlx::query(
+            "UPDATE games SET admin_token = ? WHERE rowid = ? AND instr(admin_token, '$') = 0",
+        )
+        .bind(TokenHash::new(row.get("admin_token")))
+        .bind(row.get::<i64, _>("rowid"))
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let rows = sqlx::query(&listed("view_tokens", "token_hash"))
+        .fetch_all(&mut *tx)
+        .await?;
+    for row in &rows {
+        sqlx::query(
+            "UPDATE view_tokens SET token_hash = ? WHERE rowid = ? AND instr(token_hash, '$') = 0",
+        )
+        .bind(TokenHash::new(row.get("token_hash")))
+        .bind(row.get::<i64, _>("rowid"))
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let rows = sqlx::query(&listed("email_verifications", "code, link_token"))
+        .fetch_all(&mut *tx)
+        .await?;
+    for row in &rows {
+        let link_token: Option<&str> = row.get("link_token");
+        sqlx::query(
+            "UPDATE email_verifications SET code = ?, link_token = ? \
+             WHERE rowid = ? AND instr(code, '$') = 0",
+        )
+        .bind(TokenHash::new(row.get("code")))
+        .bind(link_token.map(TokenHash::new))
+        .bind(row.get::<i64, _>("rowid"))
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query("DELETE FROM plaintext_secrets")
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
 
-    sqlx::raw_sql(
+async fn count_participants(conn: &mut sqlx::SqliteConnection, game_id: GameId) -> Result<u64> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM participants WHERE game_id = ?")
+        .bind(game_id)
+        .fetch_one(conn)
+        .await
+        .context("counting participants")?;
+    u64::try_from(count).context("converting participant count to u64")
+}
+
+async fn insert_game(conn: &mut sqlx::SqliteConnection, game: &Game) -> Result<()> {
+    sqlx::query(
         r#"
-        CREATE TABLE IF NOT EXISTS games (
-            id TEXT PRIMARY KEY,
-            name TEXT NOT NULL,
-            event_date TEXT NOT NULL,
-            organizer_email TEXT NOT NULL,
-            admin_token TEXT NOT NULL UNIQUE,
-            created_at TEXT NOT NULL,
-            drawn INTEGER NOT NULL DEFAULT 0
-        );
-
-        CREATE TABLE IF NOT EXISTS participants (
-            id TEXT PRIMARY KEY,
-            game_id TEXT NOT NULL,
-            name TEXT NOT NULL,
-            email TEXT NOT NULL,
-            matched_with_id TEXT,
-            view_token TEXT NOT NULL UNIQUE,
-            has_viewed INTEGER NOT NULL DEFAULT 0,
-            created_at TEXT NOT NULL,
-            FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
-        );
-
-        CREATE TABLE IF NOT EXISTS email_verifications (
-            id TEXT PRIMARY KEY,
-            email TEXT NOT NULL,
-            code TEXT NOT NULL,
-            game_name TEXT NOT NULL,
-            event_date TEXT NOT NULL,
-            created_at TEXT NOT NULL,
-            expires_at TEXT NOT NULL,
-            verified INTEGER NOT NULL DEFAULT 0,
-            attempts INTEGER NOT NULL DEFAULT 0
-        );
-
-        CREATE INDEX IF NOT EXISTS idx_email_verifications_email ON email_verifications(email);
-        CREATE INDEX IF NOT EXISTS idx_email_verifications_code ON email_verifications(code);
-
-        CREATE TABLE IF NOT EXISTS email_resends (
-            id TEXT PRIMARY KEY,
-            game_id TEXT NOT NULL,
-            participant_id TEXT,
-            resend_type TEXT NOT NULL,
-            resent_at TEXT NOT NULL,
-            FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE,
-            FOREIGN KEY (participant_id) REFERENCES participants(id) ON DELETE CASCADE
-        );
-
-        CREATE INDEX IF NOT EXISTS idx_email_resends_game_id ON email_resends(game_id);
-        CREATE INDEX IF NOT EXISTS idx_email_resends_participant_id ON email_resends(participant_id);
-
-        CREATE TABLE IF NOT EXISTS site_admin_password (
-            id INTEGER PRIMARY KEY CHECK (id = 1),
-            password_hash TEXT NOT NULL,
-            updated_at TEXT NOT NULL
-        );
-
-        CREATE TABLE IF NOT EXISTS admin_sessions (
-            id TEXT PRIMARY KEY,
-            session_token TEXT NOT NULL UNIQUE,
-            created_at TEXT NOT NULL,
-            expires_at TEXT NOT NULL
-        );
-
-        CREATE INDEX IF NOT EXISTS idx_admin_sessions_token ON admin_sessions(session_token);
-        CREATE INDEX IF NOT EXISTS idx_admin_sessions_expires ON admin_sessions(expires_at);
+        INSERT INTO games (
+            id,
+            name,
+            event_date,
+            organizer_email,
+            admin_token,
+            created_at,
+            drawn,
+            allowed_email_domain,
+            last_activity_at,
+            event_location,
+            timezone,
+            locale,
+            organizer_message,
+            reveal_at,
+            locked,
+            reveal_expires_after_event,
+            reveal_single_use,
+            drawn_at,
+            reminders_enabled,
+            wrap_up_enabled,
+            participant_email_subject,
+            participant_email_body,
+            event_reminder_days,
+            digest_enabled,
+            reply_to_organizer,
+            open_tracking_enabled
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
-    .execute(&pool)
+    .bind(game.id)
+    .bind(&game.name)
+    .bind(game.event_date)
+    .bind(&game.organizer_email)
+    .bind(&game.admin_token_hash)
+    .bind(game.created_at)
+    .bind(game.drawn)
+    .bind(&game.allowed_email_domain)
+    .bind(game.created_at)
+    .bind(&game.event_location)
+    .bind(game.timezone)
+    .bind(game.locale)
+    .bind(&game.organizer_message)
+    .bind(game.reveal_at)
+    .bind(game.locked)
+    .bind(game.reveal_expires_after_event)
+    .bind(game.reveal_single_use)
+    .bind(game.drawn_at)
+    .bind(game.reminders_enabled)
+    .bind(game.wrap_up_enabled)
+    .bind(&game.participant_email_subject)
+    .bind(&game.participant_email_body)
+    .bind(format_reminder_days(&game.event_reminder_days))
+    .bind(game.digest_enabled)
+    .bind(game.reply_to_organizer)
+    .bind(game.open_tracking_enabled)
+    .execute(conn)
     .await?;
 
-    Ok(pool)
+    Ok(())
+}
+
+async fn insert_participant(
+    conn: &mut sqlx::SqliteConnection,
+    participant: &Participant,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO participants (
+            id,
+            game_id,
+            name,
+            email,
+            matched_with_id,
+            has_viewed,
+            created_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(participant.id)
+    .bind(participant.game_id)
+    .bind(&participant.name)
+    .bind(&participant.email)
+    .bind(participant.matched_with_id)
+    .bind(participant.has_viewed)
+    .bind(participant.created_at)
+    .execute(conn)
+    .await
+    .context("inserting participant")?;
+
+    Ok(())
+}
+
+async fn insert_view_token(
+    conn: &mut sqlx::SqliteConnection,
+    participant_id: ParticipantId,
+    view_token: &ViewToken,
+    created_at: DateTime<Utc>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO view_tokens (lookup_key, token_hash, participant_id, created_at)
+        VALUES (?, ?, ?, ?)
+        "#,
+    )
+    .bind(view_token.lookup_key())
+    .bind(view_token.hash())
+    .bind(participant_id)
+    .bind(created_at)
+    .execute(conn)
+    .await
+    .context("inserting view token")?;
+
+    Ok(())
+}
+
+fn verification_from_row(r: &SqliteRow) -> EmailVerification {
+    EmailVerification {
+        id: r.get("id"),
+        email: r.get("email"),
+        code_hash: r.get("code"),
+        game_name: r.get("game_name"),
+        event_date: r.get("event_date"),
+        created_at: r.get("created_at"),
+        expires_at: r.get("expires_at"),
+        verified: r.get::<i32, _>("verified") != 0,
+        attempts: r.get("attempts"),
+        event_location: r.get("event_location"),
+        timezone: r.get("timezone"),
+        locale: r.get("locale"),
+        link_token_hash: r.get("link_token"),
+    }
+}
+
+fn game_from_row(r: &SqliteRow) -> Game {
+    Game {
+        id: r.get("id"),
+        name: r.get("name"),
+        event_date: r.get("event_date"),
+        organizer_email: r.get("organizer_email"),
+        admin_token_hash: r.get("admin_token"),
+        created_at: r.get("created_at"),
+        drawn: r.get::<i32, _>("drawn") != 0,
+        allowed_email_domain: r.get("allowed_email_domain"),
+        frozen_at: r.get("frozen_at"),
+        frozen_reason: r.get("frozen_reason"),
+        deleted_at: r.get("deleted_at"),
+        organizer_message: r.get("organizer_message"),
+        event_location: r.get("event_location"),
+        reveal_at: r.get("reveal_at"),
+        timezone: r.get("timezone"),
+        locale: r.get("locale"),
+        locked: r.get::<i32, _>("locked") != 0,
+        reveal_expires_after_event: r.get::<i32, _>("reveal_expires_after_event") != 0,
+        reveal_single_use: r.get::<i32, _>("reveal_single_use") != 0,
+        cover_image: r.get("cover_image"),
+        drawn_at: r.get("drawn_at"),
+        reminders_enabled: r.get::<i32, _>("reminders_enabled") != 0,
+        wrap_up_enabled: r.get::<i32, _>("wrap_up_enabled") != 0,
+        wrap_up_sent_at: r.get("wrap_up_sent_at"),
+        participant_email_subject: r.get("participant_email_subject"),
+        participant_email_body: r.get("participant_email_body"),
+        event_reminder_days: r
+            .get::<String, _>("event_reminder_days")
+            .split(',')
+            .filter_map(|days| days.parse().ok())
+            .collect(),
+        digest_enabled: r.get::<i32, _>("digest_enabled") != 0,
+        digest_sent_at: r.get("digest_sent_at"),
+        reply_to_organizer: r.get::<i32, _>("reply_to_organizer") != 0,
+        open_tracking_enabled: r.get::<i32, _>("open_tracking_enabled") != 0,
+    }
+}
+
+/// Identifies event reminders in `reminder_sends`, next to the `ReminderKind`s.
+fn event_reminder_kind(days_before: u32) -> String {
+    format!("event_{days_before}")
+}
+
+/// Stores reminder days as a comma-separated list, e.g. "7,1".
+fn format_reminder_days(days: &[u32]) -> String {
+    days.iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn email_job_from_row(r: &SqliteRow) -> EmailJob {
+    EmailJob {
+        id: r.get("id"),
+        game_id: r.get("game_id"),
+        template: EmailTemplate::from_name(r.get("template")),
+        total: r.get::<i64, _>("total") as u64,
+        sent: r.get::<i64, _>("sent") as u64,
+        failed: r.get::<i64, _>("failed") as u64,
+        created_at: r.get("created_at"),
+        finished_at: r.get("finished_at"),
+    }
+}
+
+fn announcement_from_row(r: &SqliteRow) -> Announcement {
+    Announcement {
+        id: r.get("id"),
+        subject: r.get("subject"),
+        message: r.get("message"),
+        total: r.get::<i64, _>("total") as u64,
+        sent: r.get::<i64, _>("sent") as u64,
+        failed: r.get::<i64, _>("failed") as u64,
+        created_at: r.get("created_at"),
+        finished_at: r.get("finished_at"),
+    }
+}
+
+fn job_run_from_row(r: &SqliteRow) -> JobRun {
+    JobRun {
+        job: r.get("job"),
+        started_at: r.get("started_at"),
+        duration_ms: u64::try_from(r.get::<i64, _>("duration_ms")).unwrap_or(0),
+        processed: r
+            .get::<Option<i64>, _>("processed")
+            .and_then(|n| u64::try_from(n).ok()),
+        error: r.get("error"),
+    }
+}
+
+fn email_ban_from_row(r: &SqliteRow) -> EmailBan {
+    EmailBan {
+        id: r.get("id"),
+        value: r.get("value"),
+        reason: r.get("reason"),
+        created_at: r.get("created_at"),
+    }
+}
+
+fn passkey_credential_from_row(r: &SqliteRow) -> Result<PasskeyCredential> {
+    Ok(PasskeyCredential {
+        id: r.get("id"),
+        passkey: serde_json::from_str(r.get("passkey")).context("parsing stored passkey")?,
+    })
+}
+
+fn participant_from_row(r: &SqliteRow) -> Participant {
+    Participant {
+        id: r.get("id"),
+        game_id: r.get("game_id"),
+        name: r.get("name"),
+        email: r.get("email"),
+        matched_with_id: r.get("matched_with_id"),
+        has_viewed: r.get::<i32, _>("has_viewed") != 0,
+        created_at: r.get("created_at"),
+    }
 }
 
 impl Database {
-    pub async fn from_env() -> Result<Self> {
-        let database_url = std::env::var("DATABASE_URL")
-            .unwrap_or_else(|_| "sqlite:///app/data/amigo_oculto.db".to_string());
+    pub async fn connect(database_url: &str, clock: Arc<dyn Clock>) -> Result<Self> {
         tracing::info!("connecting to database: {}", database_url);
-        let pool = init_db(&database_url).await?;
-        Ok(Self { pool })
+        let pool = init_db(database_url).await?;
+        Ok(Self { pool, clock })
+    }
+
+    /// Runs the cheapest possible query, to check the database can be reached.
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .context("pinging database")?;
+        Ok(())
+    }
+
+    /// A consistent copy of the whole database file, read into memory.
+    #[cfg(test)]
+    pub async fn snapshot(&self) -> Result<Vec<u8>> {
+        let path = std::env::temp_dir().join(format!("amigo-oculto-snapshot-{}.db", Ulid::new()));
+        self.snapshot_into(&path).await?;
+
+        let snapshot = tokio::fs::read(&path)
+            .await
+            .context("reading database copy");
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            tracing::warn!("failed to remove database copy {}: {}", path.display(), e);
+        }
+        snapshot
+    }
+
+    /// Writes a consistent copy of the whole database file to `path`, which must not exist,
+    /// for backups. `VACUUM INTO` writes it without blocking other connections.
+    pub async fn snapshot_into(&self, path: &Path) -> Result<()> {
+        let path = path
+            .to_str()
+            .context("temporary directory path isn't valid UTF-8")?;
+        sqlx::query("VACUUM INTO ?")
+            .bind(path)
+            .execute(&self.pool)
+            .await
+            .context("copying database")?;
+        Ok(())
+    }
+
+    /// Replaces all data with that of a snapshot taken by [`Self::snapshot`] (or any copy of
+    /// the database file). Returns the number of rows restored.
+    ///
+    /// The snapshot is checked and brought up to this binary's schema in a temporary copy
+    /// first: a corrupted file, or one whose migrations aren't this binary's, is rejected
+    /// without touching the database. Its tables are then copied over in a single
+    /// transaction, except for [`RESTORE_KEPT_TABLES`].
+    pub async fn restore(&self, snapshot: &[u8]) -> Result<u64> {
+        anyhow::ensure!(
+            snapshot.starts_with(SQLITE_HEADER),
+            "not a SQLite database file"
+        );
+
+        let path = std::env::temp_dir().join(format!("amigo-oculto-restore-{}.db", Ulid::new()));
+        tokio::fs::write(&path, snapshot)
+            .await
+            .context("writing snapshot to a temporary file")?;
+        let restored = self.restore_from_file(&path).await;
+        for suffix in ["", "-wal", "-shm"] {
+            let mut file = path.clone().into_os_string();
+            file.push(suffix);
+            match tokio::fs::remove_file(&file).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    tracing::warn!("failed to remove snapshot copy {:?}: {}", file, e);
+                }
+                _ => {}
+            }
+        }
+        restored
+    }
+
+    async fn restore_from_file(&self, path: &std::path::Path) -> Result<u64> {
+        let path_str = path
+            .to_str()
+            .context("temporary directory path isn't valid UTF-8")?;
+
+        let options = SqliteConnectOptions::new().filename(path).read_only(true);
+        let snapshot_pool = SqlitePool::connect_with(options)
+            .await
+            .context("opening snapshot")?;
+        let checked = check_snapshot(&snapshot_pool).await;
+        snapshot_pool.close().await;
+        let schema = match checked? {
+            Some(migration) => format!("migration {migration}"),
+            None => "before migrations".to_string(),
+        };
+
+        let snapshot_pool = init_db(&format!("sqlite://{path_str}"))
+            .await
+            .with_context(|| format!("upgrading snapshot from schema {schema}"))?;
+        snapshot_pool.close().await;
+
+        let mut conn = self.pool.acquire().await?.detach();
+        sqlx::query("ATTACH DATABASE ? AS snapshot")
+            .bind(path_str)
+            .execute(&mut conn)
+            .await
+            .context("attaching snapshot")?;
+        let restored = copy_snapshot_tables(&mut conn).await;
+        // Not returned to the pool, so the snapshot doesn't stay attached if something failed
+        if let Err(e) = conn.close().await {
+            tracing::warn!("failed to close restore connection: {}", e);
+        }
+        let rows = restored?;
+
+        tracing::info!(
+            "restored {} rows from a snapshot with schema {}",
+            rows,
+            schema
+        );
+        Ok(rows)
+    }
+
+    /// The plans of [`HOT_QUERIES`], to check they use the indexes.
+    pub async fn explain_hot_queries(&self) -> Result<Vec<QueryPlan>> {
+        let mut plans = Vec::new();
+        for (query, sql) in HOT_QUERIES {
+            let rows = sqlx::query(&format!("EXPLAIN QUERY PLAN {sql}"))
+                .fetch_all(&self.pool)
+                .await
+                .with_context(|| format!("explaining query {query}"))?;
+
+            // Steps refer to the step they belong to, which comes before them
+            let mut depths = std::collections::HashMap::new();
+            let plan = rows
+                .iter()
+                .map(|r| {
+                    let depth = depths
+                        .get(&r.get::<i64, _>("parent"))
+                        .map_or(0, |depth| depth + 1);
+                    depths.insert(r.get::<i64, _>("id"), depth);
+                    format!("{}{}", "  ".repeat(depth), r.get::<&str, _>("detail"))
+                })
+                .collect();
+            plans.push(QueryPlan {
+                query: query.to_string(),
+                sql: sql.trim().to_string(),
+                plan,
+            });
+        }
+        Ok(plans)
     }
 
+    /// Starts a transaction that takes the write lock right away, so what it reads can't
+    /// change before it commits. Other writers wait for it, up to the busy timeout.
     pub async fn begin(&self) -> Result<Transaction> {
         Ok(Transaction {
-            inner: self.pool.begin().await?,
+            inner: self.pool.begin_with("BEGIN IMMEDIATE").await?,
         })
     }
 
     pub async fn create_game(&self, game: &Game) -> Result<()> {
-        sqlx::query(
-            r#"
-            INSERT INTO games (
-                id,
-                name,
-                event_date,
-                organizer_email,
-                admin_token,
-                created_at,
-                drawn
-            )
-            VALUES (?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(game.id)
-        .bind(&game.name)
-        .bind(game.event_date)
-        .bind(&game.organizer_email)
-        .bind(&game.admin_token)
-        .bind(game.created_at)
-        .bind(game.drawn)
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
+        let mut conn = self.pool.acquire().await?;
+        insert_game(&mut conn, game).await
     }
 
     pub async fn get_game_by_id(&self, game_id: GameId) -> Result<Option<Game>> {
         let row = sqlx::query(
             r#"
-            SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn
+            SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn,
+                allowed_email_domain, frozen_at, frozen_reason, deleted_at,
+                organizer_message, event_location, reveal_at, timezone, locale, locked,
+                reveal_expires_after_event, reveal_single_use, cover_image, drawn_at,
+                reminders_enabled, wrap_up_enabled, wrap_up_sent_at,
+                participant_email_subject, participant_email_body, event_reminder_days,
+                digest_enabled, digest_sent_at, reply_to_organizer, open_tracking_enabled
             FROM games
-            WHERE id = ?
+            WHERE id = ? AND deleted_at IS NULL
             "#,
         )
         .bind(game_id)
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(row.map(|r| Game {
-            id: r.get("id"),
-            name: r.get("name"),
-            event_date: r.get("event_date"),
-            organizer_email: r.get("organizer_email"),
-            admin_token: r.get("admin_token"),
-            created_at: r.get("created_at"),
-            drawn: r.get::<i32, _>("drawn") != 0,
-        }))
+        Ok(row.as_ref().map(game_from_row))
     }
 
-    pub async fn get_game_by_admin_token(&self, admin_token: &AdminToken) -> Result<Option<Game>> {
+    /// Like `get_game_by_id`, but also returns games that were deleted and not yet purged.
+    pub async fn get_game_by_id_including_deleted(&self, game_id: GameId) -> Result<Option<Game>> {
         let row = sqlx::query(
             r#"
-            SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn
+            SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn,
+                allowed_email_domain, frozen_at, frozen_reason, deleted_at,
+                organizer_message, event_location, reveal_at, timezone, locale, locked,
+                reveal_expires_after_event, reveal_single_use, cover_image, drawn_at,
+                reminders_enabled, wrap_up_enabled, wrap_up_sent_at,
+                participant_email_subject, participant_email_body, event_reminder_days,
+                digest_enabled, digest_sent_at, reply_to_organizer, open_tracking_enabled
             FROM games
-            WHERE admin_token = ?
+            WHERE id = ?
             "#,
         )
-        .bind(admin_token)
+        .bind(game_id)
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(row.map(|r| Game {
-            id: r.get("id"),
-            name: r.get("name"),
-            event_date: r.get("event_date"),
-            organizer_email: r.get("organizer_email"),
-            admin_token: r.get("admin_token"),
-            created_at: r.get("created_at"),
-            drawn: r.get::<i32, _>("drawn") != 0,
-        }))
+        Ok(row.as_ref().map(game_from_row))
     }
 
+    /// Routes add participants in a transaction, with the game's capacity check.
+    #[cfg(test)]
     pub async fn add_participant(&self, participant: &Participant) -> Result<()> {
-        sqlx::query(
-            r#"
-            INSERT INTO participants (
-                id,
-                game_id,
-                name,
-                email,
-                matched_with_id,
-                view_token,
-                has_viewed,
-                created_at
-            )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(participant.id)
-        .bind(participant.game_id)
-        .bind(&participant.name)
-        .bind(&participant.email)
-        .bind(participant.matched_with_id)
-        .bind(&participant.view_token)
-        .bind(participant.has_viewed)
-        .bind(participant.created_at)
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
+        let mut conn = self.pool.acquire().await?;
+        insert_participant(&mut conn, participant).await
     }
 
     pub async fn get_participants_by_game(&self, game_id: GameId) -> Result<Vec<Participant>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, game_id, name, email, matched_with_id, view_token, has_viewed, created_at
+            SELECT id, game_id, name, email, matched_with_id, has_viewed, created_at
             FROM participants
             WHERE game_id = ?
             ORDER BY created_at ASC
@@ -239,46 +966,42 @@ impl Database {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(rows
-            .into_iter()
-            .map(|r| Participant {
-                id: r.get("id"),
-                game_id: r.get("game_id"),
-                name: r.get("name"),
-                email: r.get("email"),
-                matched_with_id: r.get("matched_with_id"),
-                view_token: r.get("view_token"),
-                has_viewed: r.get::<i32, _>("has_viewed") != 0,
-                created_at: r.get("created_at"),
-            })
-            .collect())
+        Ok(rows.iter().map(participant_from_row).collect())
     }
 
     pub async fn get_participant_by_view_token(
         &self,
         view_token: &ViewToken,
     ) -> Result<Option<Participant>> {
-        let row = sqlx::query(
+        let rows = sqlx::query(
             r#"
-        SELECT id, game_id, name, email, matched_with_id, view_token, has_viewed, created_at
-        FROM participants
-        WHERE view_token = ?
+        SELECT p.id, p.game_id, p.name, p.email, p.matched_with_id, p.has_viewed, p.created_at,
+            t.token_hash
+        FROM view_tokens t
+        JOIN participants p ON p.id = t.participant_id
+        WHERE t.lookup_key = ?
         "#,
         )
-        .bind(view_token)
-        .fetch_optional(&self.pool)
+        .bind(view_token.lookup_key())
+        .fetch_all(&self.pool)
         .await?;
 
-        Ok(row.map(|r| Participant {
-            id: r.get("id"),
-            game_id: r.get("game_id"),
-            name: r.get("name"),
-            email: r.get("email"),
-            matched_with_id: r.get("matched_with_id"),
-            view_token: r.get("view_token"),
-            has_viewed: r.get::<i32, _>("has_viewed") != 0,
-            created_at: r.get("created_at"),
-        }))
+        Ok(rows
+            .iter()
+            .find(|r| {
+                r.get::<TokenHash, _>("token_hash")
+                    .matches(view_token.as_str())
+            })
+            .map(participant_from_row))
+    }
+
+    /// Issue a new view token for a participant's reveal link. Only its hash is stored, so
+    /// every email gets its own token; earlier ones keep working until revoked.
+    pub async fn create_view_token(&self, participant_id: ParticipantId) -> Result<ViewToken> {
+        let view_token = ViewToken::generate();
+        let mut conn = self.pool.acquire().await?;
+        insert_view_token(&mut conn, participant_id, &view_token, self.clock.now()).await?;
+        Ok(view_token)
     }
 
     pub async fn mark_participant_viewed(&self, participant_id: ParticipantId) -> Result<()> {
@@ -296,13 +1019,51 @@ impl Database {
         Ok(())
     }
 
+    /// Record the first use of a participant's view token.
+    /// Returns false if the token had already been used.
+    pub async fn claim_view_token(&self, participant_id: ParticipantId) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE participants
+            SET view_token_used_at = ?
+            WHERE id = ? AND view_token_used_at IS NULL
+            "#,
+        )
+        .bind(self.clock.now())
+        .bind(participant_id)
+        .execute(&self.pool)
+        .await
+        .context("claiming view token")?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Invalidate all of a participant's view tokens and mark the next one as unused.
+    pub async fn revoke_view_tokens(&self, participant_id: ParticipantId) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM view_tokens WHERE participant_id = ?")
+            .bind(participant_id)
+            .execute(&mut *tx)
+            .await
+            .context("deleting view tokens")?;
+        sqlx::query("UPDATE participants SET view_token_used_at = NULL WHERE id = ?")
+            .bind(participant_id)
+            .execute(&mut *tx)
+            .await
+            .context("resetting view token use")?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
     pub async fn get_participant_by_id(
         &self,
         participant_id: ParticipantId,
     ) -> Result<Option<Participant>> {
         let row = sqlx::query(
             r#"
-            SELECT id, game_id, name, email, matched_with_id, view_token, has_viewed, created_at
+            SELECT id, game_id, name, email, matched_with_id, has_viewed, created_at
             FROM participants
             WHERE id = ?
         "#,
@@ -311,16 +1072,7 @@ impl Database {
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(row.map(|r| Participant {
-            id: r.get("id"),
-            game_id: r.get("game_id"),
-            name: r.get("name"),
-            email: r.get("email"),
-            matched_with_id: r.get("matched_with_id"),
-            view_token: r.get("view_token"),
-            has_viewed: r.get::<i32, _>("has_viewed") != 0,
-            created_at: r.get("created_at"),
-        }))
+        Ok(row.as_ref().map(participant_from_row))
     }
 
     pub async fn update_participant(
@@ -348,7 +1100,8 @@ impl Database {
             sqlx::query(
                 r#"
             UPDATE participants
-            SET email = ?
+            SET email = ?, email_undeliverable_at = NULL, email_undeliverable_reason = NULL,
+                email_opened_at = NULL, email_domain_unreachable_at = NULL
             WHERE id = ?
             "#,
             )
@@ -361,14 +1114,19 @@ impl Database {
         Ok(())
     }
 
-    pub async fn delete_game(&self, game_id: GameId) -> Result<()> {
-        // CASCADE delete will automatically remove participants
+    pub async fn update_game_allowed_email_domain(
+        &self,
+        game_id: GameId,
+        allowed_email_domain: Option<&EmailDomain>,
+    ) -> Result<()> {
         sqlx::query(
             r#"
-            DELETE FROM games
+            UPDATE games
+            SET allowed_email_domain = ?
             WHERE id = ?
-        "#,
+            "#,
         )
+        .bind(allowed_email_domain)
         .bind(game_id)
         .execute(&self.pool)
         .await?;
@@ -376,844 +1134,6235 @@ impl Database {
         Ok(())
     }
 
-    // Email verification functions
-    pub async fn create_email_verification(&self, verification: &EmailVerification) -> Result<()> {
-        sqlx::query(
-            r#"
-            INSERT INTO email_verifications (id, email, code, game_name, event_date, created_at, expires_at, verified, attempts)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+    pub async fn update_game_event_location(
+        &self,
+        game_id: GameId,
+        event_location: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE games
+            SET event_location = ?
+            WHERE id = ?
             "#,
         )
-        .bind(verification.id)
-        .bind(&verification.email)
-        .bind(verification.code)
-        .bind(&verification.game_name)
-        .bind(verification.event_date)
-        .bind(verification.created_at)
-        .bind(verification.expires_at)
-        .bind(verification.verified)
-        .bind(verification.attempts)
+        .bind(event_location)
+        .bind(game_id)
         .execute(&self.pool)
-        .await?;
+        .await
+        .context("updating game event location")?;
 
         Ok(())
     }
 
-    pub async fn get_email_verification_by_id(
+    pub async fn update_game_view_token_policy(
         &self,
-        verification_id: VerificationId,
-    ) -> Result<Option<EmailVerification>> {
-        let row = sqlx::query(
+        game_id: GameId,
+        expires_after_event: bool,
+        single_use: bool,
+    ) -> Result<()> {
+        sqlx::query(
             r#"
-        SELECT id, email, code, game_name, event_date, created_at, expires_at, verified, attempts
-        FROM email_verifications
-        WHERE id = ?
-        "#,
+            UPDATE games
+            SET reveal_expires_after_event = ?, reveal_single_use = ?
+            WHERE id = ?
+            "#,
         )
-        .bind(verification_id)
-        .fetch_optional(&self.pool)
-        .await?;
+        .bind(expires_after_event)
+        .bind(single_use)
+        .bind(game_id)
+        .execute(&self.pool)
+        .await
+        .context("updating game view token policy")?;
 
-        Ok(row.map(|r| EmailVerification {
-            id: r.get("id"),
-            email: r.get("email"),
-            code: r.get("code"),
-            game_name: r.get("game_name"),
-            event_date: r.get("event_date"),
-            created_at: r.get("created_at"),
-            expires_at: r.get("expires_at"),
-            verified: r.get::<i32, _>("verified") != 0,
-            attempts: r.get("attempts"),
-        }))
+        Ok(())
     }
 
-    pub async fn increment_verification_attempts(
+    pub async fn update_game_cover_image(
         &self,
-        verification_id: VerificationId,
+        game_id: GameId,
+        cover_image: Option<&MediaKey>,
     ) -> Result<()> {
         sqlx::query(
             r#"
-            UPDATE email_verifications
-            SET attempts = attempts + 1
+            UPDATE games
+            SET cover_image = ?
             WHERE id = ?
             "#,
         )
-        .bind(verification_id)
+        .bind(cover_image)
+        .bind(game_id)
         .execute(&self.pool)
-        .await?;
+        .await
+        .context("updating game cover image")?;
 
         Ok(())
     }
 
-    pub async fn mark_verification_as_verified(
+    pub async fn update_game_locked(&self, game_id: GameId, locked: bool) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE games
+            SET locked = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(locked)
+        .bind(game_id)
+        .execute(&self.pool)
+        .await
+        .context("updating game participant list lock")?;
+
+        Ok(())
+    }
+
+    pub async fn update_game_reminders_enabled(
         &self,
-        verification_id: VerificationId,
+        game_id: GameId,
+        reminders_enabled: bool,
     ) -> Result<()> {
         sqlx::query(
             r#"
-            UPDATE email_verifications
-            SET verified = 1
+            UPDATE games
+            SET reminders_enabled = ?
             WHERE id = ?
             "#,
         )
-        .bind(verification_id)
+        .bind(reminders_enabled)
+        .bind(game_id)
         .execute(&self.pool)
-        .await?;
+        .await
+        .context("updating game reminders setting")?;
 
         Ok(())
     }
 
-    pub async fn count_recent_verifications_by_email(
+    pub async fn update_game_digest_enabled(
         &self,
-        email: &EmailAddress,
-        since: DateTime<Utc>,
-    ) -> Result<i64> {
-        let row = sqlx::query(
+        game_id: GameId,
+        digest_enabled: bool,
+    ) -> Result<()> {
+        sqlx::query(
             r#"
-        SELECT COUNT(*) as count
-        FROM email_verifications
-        WHERE email = ? AND created_at > ?
-        "#,
+            UPDATE games
+            SET digest_enabled = ?
+            WHERE id = ?
+            "#,
         )
-        .bind(email)
-        .bind(since)
-        .fetch_one(&self.pool)
-        .await?;
+        .bind(digest_enabled)
+        .bind(game_id)
+        .execute(&self.pool)
+        .await
+        .context("updating game digest setting")?;
 
-        Ok(row.get("count"))
+        Ok(())
     }
 
-    pub async fn update_verification_code(
+    pub async fn update_game_reply_to_organizer(
         &self,
-        verification_id: VerificationId,
-        new_code: VerificationCode,
-        new_expires_at: DateTime<Utc>,
+        game_id: GameId,
+        reply_to_organizer: bool,
     ) -> Result<()> {
         sqlx::query(
             r#"
-            UPDATE email_verifications
-            SET code = ?, expires_at = ?, attempts = 0
+            UPDATE games
+            SET reply_to_organizer = ?
             WHERE id = ?
             "#,
         )
-        .bind(new_code)
-        .bind(new_expires_at)
-        .bind(verification_id)
+        .bind(reply_to_organizer)
+        .bind(game_id)
         .execute(&self.pool)
-        .await?;
+        .await
+        .context("updating game reply-to setting")?;
 
         Ok(())
     }
 
-    pub async fn cleanup_expired_verifications(&self) -> Result<u64> {
-        let now = Utc::now();
-        let result = sqlx::query(
+    pub async fn update_game_open_tracking_enabled(
+        &self,
+        game_id: GameId,
+        open_tracking_enabled: bool,
+    ) -> Result<()> {
+        sqlx::query(
             r#"
-            DELETE FROM email_verifications
-            WHERE expires_at < ? AND verified = 0
+            UPDATE games
+            SET open_tracking_enabled = ?
+            WHERE id = ?
             "#,
         )
-        .bind(now)
+        .bind(open_tracking_enabled)
+        .bind(game_id)
         .execute(&self.pool)
-        .await?;
+        .await
+        .context("updating game open tracking setting")?;
 
-        Ok(result.rows_affected())
+        Ok(())
     }
 
-    /// Delete games where event_date is more than GAME_RETENTION_DAYS in the past.
-    /// Returns the number of games deleted.
-    pub async fn cleanup_old_games(&self) -> Result<u64> {
-        let cutoff = Utc::now().date_naive() - Duration::days(GAME_RETENTION_DAYS.into());
-
-        // Delete related records first to handle databases created before CASCADE was added.
-        // Order: email_resends (references participants) → participants → games
+    pub async fn update_game_wrap_up_enabled(
+        &self,
+        game_id: GameId,
+        wrap_up_enabled: bool,
+    ) -> Result<()> {
         sqlx::query(
             r#"
-            DELETE FROM email_resends
-            WHERE game_id IN (SELECT id FROM games WHERE event_date < ?)
+            UPDATE games
+            SET wrap_up_enabled = ?
+            WHERE id = ?
             "#,
         )
-        .bind(cutoff)
+        .bind(wrap_up_enabled)
+        .bind(game_id)
         .execute(&self.pool)
-        .await?;
+        .await
+        .context("updating game wrap-up setting")?;
+
+        Ok(())
+    }
 
+    pub async fn update_game_timezone(
+        &self,
+        game_id: GameId,
+        timezone: GameTimezone,
+    ) -> Result<()> {
         sqlx::query(
             r#"
-            DELETE FROM participants
-            WHERE game_id IN (SELECT id FROM games WHERE event_date < ?)
+            UPDATE games
+            SET timezone = ?
+            WHERE id = ?
             "#,
         )
-        .bind(cutoff)
+        .bind(timezone)
+        .bind(game_id)
         .execute(&self.pool)
-        .await?;
+        .await
+        .context("updating game time zone")?;
 
-        let result = sqlx::query(
+        Ok(())
+    }
+
+    pub async fn update_game_locale(&self, game_id: GameId, locale: Locale) -> Result<()> {
+        sqlx::query(
             r#"
-            DELETE FROM games
-            WHERE event_date < ?
+            UPDATE games
+            SET locale = ?
+            WHERE id = ?
             "#,
         )
-        .bind(cutoff)
+        .bind(locale)
+        .bind(game_id)
         .execute(&self.pool)
-        .await?;
+        .await
+        .context("updating game locale")?;
 
-        Ok(result.rows_affected())
+        Ok(())
     }
 
-    // Email resend tracking functions
-    pub async fn record_email_resend(
+    pub async fn update_game_reveal_at(
         &self,
         game_id: GameId,
-        participant_id: Option<ParticipantId>,
-        resend_type: &str,
+        reveal_at: Option<DateTime<Utc>>,
     ) -> Result<()> {
-        let id = Ulid::new().to_string();
-        let now = Utc::now();
-
         sqlx::query(
             r#"
-            INSERT INTO email_resends (id, game_id, participant_id, resend_type, resent_at)
-            VALUES (?, ?, ?, ?, ?)
+            UPDATE games
+            SET reveal_at = ?
+            WHERE id = ?
             "#,
         )
-        .bind(&id)
+        .bind(reveal_at)
         .bind(game_id)
-        .bind(participant_id)
-        .bind(resend_type)
-        .bind(now)
         .execute(&self.pool)
-        .await?;
+        .await
+        .context("updating game reveal time")?;
 
         Ok(())
     }
 
-    pub async fn count_recent_participant_resends(
+    pub async fn update_game_organizer_message(
         &self,
-        participant_id: ParticipantId,
-        since: DateTime<Utc>,
-    ) -> Result<i64> {
-        let row = sqlx::query(
+        game_id: GameId,
+        organizer_message: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
             r#"
-            SELECT COUNT(*) as count
-            FROM email_resends
-            WHERE participant_id = ? AND resent_at > ? AND resend_type = 'individual'
+            UPDATE games
+            SET organizer_message = ?
+            WHERE id = ?
             "#,
         )
-        .bind(participant_id)
-        .bind(since)
-        .fetch_one(&self.pool)
-        .await?;
+        .bind(organizer_message)
+        .bind(game_id)
+        .execute(&self.pool)
+        .await
+        .context("updating game organizer message")?;
 
-        Ok(row.get("count"))
+        Ok(())
     }
 
-    pub async fn count_total_participant_resends(
+    pub async fn update_game_participant_email_template(
         &self,
-        participant_id: ParticipantId,
-    ) -> Result<i64> {
-        let row = sqlx::query(
+        game_id: GameId,
+        subject: Option<&str>,
+        body: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
             r#"
-            SELECT COUNT(*) as count
-            FROM email_resends
-            WHERE participant_id = ? AND resend_type = 'individual'
+            UPDATE games
+            SET participant_email_subject = ?, participant_email_body = ?
+            WHERE id = ?
             "#,
         )
-        .bind(participant_id)
-        .fetch_one(&self.pool)
-        .await?;
+        .bind(subject)
+        .bind(body)
+        .bind(game_id)
+        .execute(&self.pool)
+        .await
+        .context("updating game participant email template")?;
 
-        Ok(row.get("count"))
+        Ok(())
     }
 
-    pub async fn count_recent_bulk_resends(
+    pub async fn update_game_event_reminder_days(
         &self,
         game_id: GameId,
-        since: DateTime<Utc>,
-    ) -> Result<i64> {
-        let row = sqlx::query(
+        days: &[u32],
+    ) -> Result<()> {
+        sqlx::query(
             r#"
-            SELECT COUNT(*) as count
-            FROM email_resends
-            WHERE game_id = ? AND resent_at > ? AND resend_type = 'bulk'
+            UPDATE games
+            SET event_reminder_days = ?
+            WHERE id = ?
             "#,
         )
+        .bind(format_reminder_days(days))
         .bind(game_id)
-        .bind(since)
-        .fetch_one(&self.pool)
-        .await?;
+        .execute(&self.pool)
+        .await
+        .context("updating game event reminder days")?;
 
-        Ok(row.get("count"))
+        Ok(())
     }
 
-    pub async fn count_total_bulk_resends(&self, game_id: GameId) -> Result<i64> {
-        let row = sqlx::query(
+    // Abuse report functions
+    pub async fn create_abuse_report(&self, game_id: GameId, reason: &str) -> Result<ReportId> {
+        let id = ReportId::new();
+
+        sqlx::query(
             r#"
-            SELECT COUNT(*) as count
-            FROM email_resends
-            WHERE game_id = ? AND resend_type = 'bulk'
+            INSERT INTO abuse_reports (id, game_id, reason, created_at, resolved)
+            VALUES (?, ?, ?, ?, 0)
             "#,
         )
+        .bind(id)
         .bind(game_id)
-        .fetch_one(&self.pool)
-        .await?;
+        .bind(reason)
+        .bind(self.clock.now())
+        .execute(&self.pool)
+        .await
+        .context("inserting abuse report")?;
 
-        Ok(row.get("count"))
+        Ok(id)
     }
 
-    pub async fn count_participants_in_game(&self, game_id: GameId) -> Result<u64> {
-        let row = sqlx::query(
-            r#"
-            SELECT COUNT(*) as count
-            FROM participants
-            WHERE game_id = ?
+    /// Reports about a game filed since `since`.
+    pub async fn recent_abuse_reports(
+        &self,
+        game_id: GameId,
+        since: DateTime<Utc>,
+    ) -> Result<RecentRequests> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS count, MIN(created_at) AS oldest
+            FROM abuse_reports
+            WHERE game_id = ? AND created_at > ?
             "#,
         )
         .bind(game_id)
+        .bind(since)
         .fetch_one(&self.pool)
-        .await?;
+        .await
+        .context("counting recent abuse reports")?;
 
-        let count: i64 = row.get("count");
-        u64::try_from(count).context("converting participant count to u64")
+        Ok(RecentRequests {
+            count: row.get("count"),
+            oldest: row.get("oldest"),
+        })
     }
 
-    // Site admin authentication functions
+    /// Unresolved abuse reports with their game, newest first.
+    pub async fn get_unresolved_abuse_reports(&self) -> Result<Vec<AbuseReportSummary>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT r.id, r.game_id, g.name AS game_name, g.frozen_at, r.reason, r.created_at
+            FROM abuse_reports r
+            JOIN games g ON g.id = r.game_id
+            WHERE r.resolved = 0
+            ORDER BY r.created_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("fetching unresolved abuse reports")?;
 
-    /// Initialize the site admin password from environment variable if not set.
-    /// This should be called on startup.
-    pub async fn init_site_admin_password(&self) -> Result<()> {
-        // Check if password is already set
-        let existing = sqlx::query("SELECT id FROM site_admin_password WHERE id = 1")
-            .fetch_optional(&self.pool)
-            .await?;
+        Ok(rows
+            .iter()
+            .map(|r| AbuseReportSummary {
+                id: r.get("id"),
+                game_id: r.get("game_id"),
+                game_name: r.get("game_name"),
+                game_frozen: r.get::<Option<DateTime<Utc>>, _>("frozen_at").is_some(),
+                reason: r.get("reason"),
+                created_at: r.get("created_at"),
+            })
+            .collect())
+    }
 
-        if existing.is_some() {
-            tracing::info!("site admin password already initialized");
-            return Ok(());
-        }
+    /// Mark a report as handled. Returns false if no such report exists.
+    pub async fn resolve_abuse_report(&self, report_id: ReportId) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE abuse_reports
+            SET resolved = 1
+            WHERE id = ?
+            "#,
+        )
+        .bind(report_id)
+        .execute(&self.pool)
+        .await
+        .context("resolving abuse report")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Replace a game's admin token, so links with the old one stop working.
+    pub async fn update_game_admin_token(
+        &self,
+        game_id: GameId,
+        admin_token_hash: &TokenHash,
+    ) -> Result<()> {
+        sqlx::query("UPDATE games SET admin_token = ? WHERE id = ?")
+            .bind(admin_token_hash)
+            .bind(game_id)
+            .execute(&self.pool)
+            .await
+            .context("updating admin token")?;
+
+        Ok(())
+    }
 
-        // Get password from environment
-        let password = std::env::var("SITE_ADMIN_PASSWORD")
-            .context("SITE_ADMIN_PASSWORD not set and no password in database")?;
+    /// Undo a game's draw: forget the matches and everything that followed from them (reveal
+    /// links, views, reminders, wrap-up and digest), so it can be drawn again. Resends and
+    /// the organizer's match accesses are kept.
+    pub async fn reset_draw(&self, game_id: GameId) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
 
-        if password.is_empty() {
-            anyhow::bail!("SITE_ADMIN_PASSWORD cannot be empty");
+        for statement in [
+            r#"
+            DELETE FROM view_tokens
+            WHERE participant_id IN (SELECT id FROM participants WHERE game_id = ?)
+            "#,
+            r#"
+            DELETE FROM reveal_devices
+            WHERE participant_id IN (SELECT id FROM participants WHERE game_id = ?)
+            "#,
+            "DELETE FROM reveal_events WHERE game_id = ?",
+            "DELETE FROM reminder_sends WHERE game_id = ?",
+            r#"
+            UPDATE participants
+            SET matched_with_id = NULL, has_viewed = 0, view_token_used_at = NULL
+            WHERE game_id = ?
+            "#,
+            r#"
+            UPDATE games
+            SET drawn = 0, drawn_at = NULL, wrap_up_sent_at = NULL, digest_sent_at = NULL
+            WHERE id = ?
+            "#,
+        ] {
+            sqlx::query(statement)
+                .bind(game_id)
+                .execute(&mut *tx)
+                .await
+                .context("resetting draw")?;
         }
 
-        // Hash password
-        let password_hash =
-            bcrypt::hash(&password, bcrypt::DEFAULT_COST).context("hashing site admin password")?;
+        tx.commit().await?;
+        Ok(())
+    }
 
-        // Store in database
+    /// Freeze a game pending moderation, blocking all organizer changes.
+    pub async fn freeze_game(&self, game_id: GameId, reason: &str) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO site_admin_password (id, password_hash, updated_at)
-            VALUES (1, ?, ?)
+            UPDATE games
+            SET frozen_at = ?, frozen_reason = ?
+            WHERE id = ?
             "#,
         )
-        .bind(&password_hash)
-        .bind(Utc::now())
+        .bind(self.clock.now())
+        .bind(reason)
+        .bind(game_id)
         .execute(&self.pool)
         .await
-        .context("storing site admin password")?;
+        .context("freezing game")?;
 
-        tracing::info!("site admin password initialized from environment");
         Ok(())
     }
 
-    /// Verify the site admin password and return true if correct.
-    pub async fn verify_site_admin_password(&self, password: &str) -> Result<bool> {
-        let row = sqlx::query(
+    pub async fn unfreeze_game(&self, game_id: GameId) -> Result<()> {
+        sqlx::query(
             r#"
-            SELECT password_hash
-            FROM site_admin_password
-            WHERE id = 1
+            UPDATE games
+            SET frozen_at = NULL, frozen_reason = NULL
+            WHERE id = ?
             "#,
         )
-        .fetch_optional(&self.pool)
+        .bind(game_id)
+        .execute(&self.pool)
         .await
-        .context("fetching site admin password hash")?;
+        .context("unfreezing game")?;
 
-        let Some(row) = row else {
-            tracing::warn!("site admin password not initialized");
-            return Ok(false);
-        };
+        Ok(())
+    }
 
-        let password_hash: String = row.get("password_hash");
-        bcrypt::verify(password, &password_hash).context("verifying site admin password")
+    /// Record that the organizer did something with the game, postponing idle cleanup.
+    pub async fn touch_game_activity(&self, game_id: GameId) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE games
+            SET last_activity_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(self.clock.now())
+        .bind(game_id)
+        .execute(&self.pool)
+        .await
+        .context("updating game activity timestamp")?;
+
+        Ok(())
     }
 
-    /// Change the site admin password. Requires the current password.
-    pub async fn change_site_admin_password(
-        &self,
-        current_password: &str,
-        new_password: &str,
-    ) -> Result<bool> {
-        // Verify current password
-        if !self.verify_site_admin_password(current_password).await? {
-            return Ok(false);
-        }
+    pub async fn delete_game(&self, game_id: GameId) -> Result<()> {
+        // CASCADE delete will automatically remove participants
+        sqlx::query(
+            r#"
+            DELETE FROM games
+            WHERE id = ?
+        "#,
+        )
+        .bind(game_id)
+        .execute(&self.pool)
+        .await?;
 
-        // Hash new password
-        let new_hash = bcrypt::hash(new_password, bcrypt::DEFAULT_COST)
-            .context("hashing new site admin password")?;
+        Ok(())
+    }
 
-        // Update in database
+    /// Hide a game from organizers and participants. It is purged after
+    /// DELETED_GAME_RETENTION_DAYS unless restored.
+    pub async fn soft_delete_game(&self, game_id: GameId) -> Result<()> {
         sqlx::query(
             r#"
-            UPDATE site_admin_password
-            SET password_hash = ?, updated_at = ?
-            WHERE id = 1
+            UPDATE games
+            SET deleted_at = ?
+            WHERE id = ? AND deleted_at IS NULL
             "#,
         )
-        .bind(&new_hash)
-        .bind(Utc::now())
+        .bind(self.clock.now())
+        .bind(game_id)
         .execute(&self.pool)
         .await
-        .context("updating site admin password")?;
+        .context("soft deleting game")?;
 
-        tracing::info!("site admin password changed");
-        Ok(true)
+        Ok(())
     }
 
-    /// Create a new admin session and return the session token.
-    /// Sessions expire after 24 hours.
-    pub async fn create_admin_session(&self) -> Result<AdminSessionToken> {
-        let session_token = AdminSessionToken::generate();
-        let id = Ulid::new().to_string();
-        let created_at = Utc::now();
-        let expires_at = created_at + Duration::hours(24);
+    /// Undo a soft delete. Returns false if the game was not deleted.
+    pub async fn restore_game(&self, game_id: GameId) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE games
+            SET deleted_at = NULL
+            WHERE id = ? AND deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(game_id)
+        .execute(&self.pool)
+        .await
+        .context("restoring game")?;
+
+        Ok(result.rows_affected() > 0)
+    }
 
+    // Email verification functions
+    pub async fn create_email_verification(&self, verification: &EmailVerification) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO admin_sessions (id, session_token, created_at, expires_at)
-            VALUES (?, ?, ?, ?)
+            INSERT INTO email_verifications (id, email, code, game_name, event_date, created_at, expires_at, verified, attempts, event_location, timezone, locale, link_token)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
-        .bind(&id)
-        .bind(&session_token)
-        .bind(created_at)
-        .bind(expires_at)
+        .bind(verification.id)
+        .bind(&verification.email)
+        .bind(&verification.code_hash)
+        .bind(&verification.game_name)
+        .bind(verification.event_date)
+        .bind(verification.created_at)
+        .bind(verification.expires_at)
+        .bind(verification.verified)
+        .bind(verification.attempts)
+        .bind(&verification.event_location)
+        .bind(verification.timezone)
+        .bind(verification.locale)
+        .bind(&verification.link_token_hash)
         .execute(&self.pool)
-        .await
-        .context("creating admin session")?;
+        .await?;
 
-        Ok(session_token)
+        Ok(())
     }
 
-    /// Validate an admin session token. Returns true if valid and not expired.
-    pub async fn validate_admin_session(&self, session_token: &AdminSessionToken) -> Result<bool> {
+    pub async fn get_email_verification_by_id(
+        &self,
+        verification_id: VerificationId,
+    ) -> Result<Option<EmailVerification>> {
         let row = sqlx::query(
             r#"
-            SELECT expires_at
-            FROM admin_sessions
-            WHERE session_token = ?
-            "#,
+        SELECT id, email, code, game_name, event_date, created_at, expires_at, verified, attempts,
+            event_location, timezone, locale, link_token
+        FROM email_verifications
+        WHERE id = ?
+        "#,
         )
-        .bind(session_token)
+        .bind(verification_id)
         .fetch_optional(&self.pool)
-        .await
-        .context("fetching admin session")?;
-
-        let Some(row) = row else {
-            return Ok(false);
-        };
+        .await?;
 
-        let expires_at: DateTime<Utc> = row.get("expires_at");
-        Ok(Utc::now() < expires_at)
+        Ok(row.as_ref().map(verification_from_row))
     }
 
-    /// Delete an admin session (logout).
-    pub async fn delete_admin_session(&self, session_token: &AdminSessionToken) -> Result<()> {
+    pub async fn increment_verification_attempts(
+        &self,
+        verification_id: VerificationId,
+    ) -> Result<()> {
         sqlx::query(
             r#"
-            DELETE FROM admin_sessions
-            WHERE session_token = ?
+            UPDATE email_verifications
+            SET attempts = attempts + 1
+            WHERE id = ?
             "#,
         )
-        .bind(session_token)
+        .bind(verification_id)
         .execute(&self.pool)
-        .await
-        .context("deleting admin session")?;
+        .await?;
 
         Ok(())
     }
 
-    /// Clean up expired admin sessions. Returns the number of sessions deleted.
-    pub async fn cleanup_expired_admin_sessions(&self) -> Result<u64> {
-        let now = Utc::now();
+    /// Marks a verification as used. Returns `false` if it already was, so that entering the
+    /// code and following the link at the same time creates only one game.
+    pub async fn mark_verification_as_verified(
+        &self,
+        verification_id: VerificationId,
+    ) -> Result<bool> {
         let result = sqlx::query(
             r#"
-            DELETE FROM admin_sessions
-            WHERE expires_at < ?
+            UPDATE email_verifications
+            SET verified = 1
+            WHERE id = ? AND verified = 0
             "#,
         )
-        .bind(now)
+        .bind(verification_id)
         .execute(&self.pool)
-        .await
-        .context("cleaning up expired admin sessions")?;
+        .await?;
 
-        Ok(result.rows_affected())
+        Ok(result.rows_affected() > 0)
     }
 
-    // Site admin game management functions
-
-    /// Search for games by name, organizer email, or game ID.
-    /// Returns paginated results ordered by created_at DESC.
-    pub async fn search_games(
+    pub async fn count_recent_verifications_by_email(
         &self,
-        search: Option<&str>,
-        limit: u32,
-        offset: u64,
-    ) -> Result<Vec<Game>> {
-        // Convert to i64 for SQLite binding
-        let limit_i64 = i64::from(limit);
-        let offset_i64 = i64::try_from(offset).context("offset too large for database")?;
-
-        let query = if let Some(search_term) = search {
-            sqlx::query(
-                r#"
-                SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn
-                FROM games
-                WHERE name LIKE ? OR organizer_email LIKE ? OR id LIKE ?
-                ORDER BY created_at DESC
-                LIMIT ? OFFSET ?
-                "#,
-            )
-            .bind(format!("%{}%", search_term))
-            .bind(format!("%{}%", search_term))
-            .bind(format!("%{}%", search_term))
-            .bind(limit_i64)
-            .bind(offset_i64)
-        } else {
-            sqlx::query(
-                r#"
-                SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn
-                FROM games
-                ORDER BY created_at DESC
-                LIMIT ? OFFSET ?
-                "#,
-            )
-            .bind(limit_i64)
-            .bind(offset_i64)
+        email: &EmailAddress,
+        since: DateTime<Utc>,
+    ) -> Result<i64> {
+        let row = sqlx::query(
+            r#"
+        SELECT COUNT(*) as count
+        FROM email_verifications
+        WHERE email = ? AND created_at > ?
+        "#,
+        )
+        .bind(email)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get("count"))
+    }
+
+    pub async fn update_verification_code(
+        &self,
+        verification_id: VerificationId,
+        new_code_hash: &TokenHash,
+        new_link_token_hash: &TokenHash,
+        new_expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE email_verifications
+            SET code = ?, link_token = ?, expires_at = ?, attempts = 0
+            WHERE id = ?
+            "#,
+        )
+        .bind(new_code_hash)
+        .bind(new_link_token_hash)
+        .bind(new_expires_at)
+        .bind(verification_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn cleanup_expired_verifications(&self) -> Result<u64> {
+        let now = self.clock.now();
+        let result = sqlx::query(
+            r#"
+            DELETE FROM email_verifications
+            WHERE expires_at < ? AND verified = 0
+            "#,
+        )
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Days after event_date before a game is eligible for cleanup.
+    pub async fn get_game_retention_days(&self) -> Result<u32> {
+        let value: Option<String> =
+            sqlx::query_scalar("SELECT value FROM site_settings WHERE key = ?")
+                .bind(GAME_RETENTION_DAYS_SETTING)
+                .fetch_optional(&self.pool)
+                .await
+                .context("getting game retention")?;
+
+        match value {
+            Some(value) => value.parse().context("parsing game retention"),
+            None => Ok(DEFAULT_GAME_RETENTION_DAYS),
+        }
+    }
+
+    pub async fn set_game_retention_days(&self, days: u32) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO site_settings (key, value, updated_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT (key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(GAME_RETENTION_DAYS_SETTING)
+        .bind(days.to_string())
+        .bind(self.clock.now())
+        .execute(&self.pool)
+        .await
+        .context("setting game retention")?;
+
+        Ok(())
+    }
+
+    /// Records the latest run of a background task, which took `duration` until now and
+    /// handled `processed` items or failed with `error`.
+    pub async fn record_job_run(
+        &self,
+        job: &str,
+        duration: std::time::Duration,
+        outcome: Result<u64, String>,
+    ) -> Result<()> {
+        let started_at =
+            self.clock.now() - Duration::from_std(duration).context("converting job duration")?;
+        let duration_ms = i64::try_from(duration.as_millis()).unwrap_or(i64::MAX);
+        let (processed, error) = match outcome {
+            Ok(processed) => (Some(i64::try_from(processed).unwrap_or(i64::MAX)), None),
+            Err(error) => (None, Some(error)),
         };
 
-        let rows = query.fetch_all(&self.pool).await?;
+        sqlx::query(
+            r#"
+            INSERT INTO job_runs (job, started_at, duration_ms, processed, error)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT (job) DO UPDATE SET
+                started_at = excluded.started_at,
+                duration_ms = excluded.duration_ms,
+                processed = excluded.processed,
+                error = excluded.error
+            "#,
+        )
+        .bind(job)
+        .bind(started_at)
+        .bind(duration_ms)
+        .bind(processed)
+        .bind(error)
+        .execute(&self.pool)
+        .await
+        .context("recording job run")?;
 
-        Ok(rows
-            .into_iter()
-            .map(|r| Game {
-                id: r.get("id"),
-                name: r.get("name"),
-                event_date: r.get("event_date"),
-                organizer_email: r.get("organizer_email"),
-                admin_token: r.get("admin_token"),
-                created_at: r.get("created_at"),
-                drawn: r.get::<i32, _>("drawn") != 0,
-            })
-            .collect())
+        Ok(())
     }
 
-    /// Count total games matching search criteria.
-    pub async fn count_games(&self, search: Option<&str>) -> Result<u64> {
-        let row = if let Some(search_term) = search {
-            sqlx::query(
-                r#"
-                SELECT COUNT(*) as count
-                FROM games
-                WHERE name LIKE ? OR organizer_email LIKE ? OR id LIKE ?
-                "#,
-            )
-            .bind(format!("%{}%", search_term))
-            .bind(format!("%{}%", search_term))
-            .bind(format!("%{}%", search_term))
+    /// The latest run of every background task that ran at least once, by name.
+    pub async fn list_job_runs(&self) -> Result<Vec<JobRun>> {
+        let rows = sqlx::query(
+            "SELECT job, started_at, duration_ms, processed, error FROM job_runs ORDER BY job",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("listing job runs")?;
+
+        Ok(rows.iter().map(job_run_from_row).collect())
+    }
+
+    /// Whether a background task started less than `period` ago, whatever the outcome.
+    pub async fn job_ran_within(&self, job: &str, period: Duration) -> Result<bool> {
+        let started_at: Option<DateTime<Utc>> =
+            sqlx::query_scalar("SELECT started_at FROM job_runs WHERE job = ?")
+                .bind(job)
+                .fetch_optional(&self.pool)
+                .await
+                .context("fetching last job run")?;
+        Ok(started_at.is_some_and(|started_at| started_at > self.clock.now() - period))
+    }
+
+    /// Checks the database for corruption, refreshes the statistics the query planner uses,
+    /// and frees unused pages if the database is in incremental vacuum mode. Returns the
+    /// number of pages freed.
+    pub async fn maintain(&self) -> Result<u64> {
+        let problems: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+            .fetch_all(&self.pool)
+            .await
+            .context("checking database integrity")?;
+        if problems != ["ok"] {
+            anyhow::bail!("database is corrupted: {}", problems.join("; "));
+        }
+
+        sqlx::query("PRAGMA optimize")
+            .execute(&self.pool)
+            .await
+            .context("optimizing database")?;
+
+        // 2 is INCREMENTAL; databases created before it was set are NONE
+        let auto_vacuum: i64 = sqlx::query_scalar("PRAGMA auto_vacuum")
             .fetch_one(&self.pool)
-            .await?
-        } else {
-            sqlx::query("SELECT COUNT(*) as count FROM games")
-                .fetch_one(&self.pool)
-                .await?
-        };
+            .await
+            .context("reading auto vacuum mode")?;
+        if auto_vacuum != 2 {
+            tracing::info!("database isn't in incremental vacuum mode, not freeing pages");
+            return Ok(0);
+        }
 
-        let count: i64 = row.get("count");
-        u64::try_from(count).context("converting game count to u64")
+        // Both on the same connection, so no other write frees pages in between
+        let mut conn = self.pool.acquire().await.context("acquiring connection")?;
+        let free_pages: i64 = sqlx::query_scalar("PRAGMA freelist_count")
+            .fetch_one(&mut *conn)
+            .await
+            .context("counting free pages")?;
+        sqlx::query("PRAGMA incremental_vacuum")
+            .execute(&mut *conn)
+            .await
+            .context("freeing unused pages")?;
+        let remaining: i64 = sqlx::query_scalar("PRAGMA freelist_count")
+            .fetch_one(&mut *conn)
+            .await
+            .context("counting free pages")?;
+        u64::try_from(free_pages - remaining).context("converting freed pages to u64")
     }
-}
 
-pub struct Transaction {
-    inner: sqlx::Transaction<'static, Sqlite>,
-}
+    /// Delete games where event_date is more than the game retention in the past.
+    /// Returns the number of games deleted.
+    pub async fn cleanup_old_games(&self) -> Result<u64> {
+        let retention_days = self.get_game_retention_days().await?;
+        let cutoff = self.clock.now().date_naive() - Duration::days(retention_days.into());
+        self.delete_games_where("event_date < ?", cutoff).await
+    }
 
-impl Transaction {
-    pub async fn commit(self) -> Result<()> {
-        self.inner
-            .commit()
+    /// Delete games that were never drawn and have had no organizer activity for
+    /// IDLE_GAME_RETENTION_DAYS. Returns the number of games deleted.
+    pub async fn cleanup_idle_games(&self) -> Result<u64> {
+        let cutoff = self.clock.now() - Duration::days(IDLE_GAME_RETENTION_DAYS.into());
+        self.delete_games_where(
+            "drawn = 0 AND COALESCE(last_activity_at, created_at) < ?",
+            cutoff,
+        )
+        .await
+    }
+
+    /// Permanently delete games that were soft deleted more than DELETED_GAME_RETENTION_DAYS
+    /// ago. Returns the number of games deleted.
+    pub async fn purge_deleted_games(&self) -> Result<u64> {
+        let cutoff = self.clock.now() - Duration::days(DELETED_GAME_RETENTION_DAYS.into());
+        self.delete_games_where("deleted_at < ?", cutoff).await
+    }
+
+    /// Deletes the games matching `condition`, a filter on `games` with one `?` for `cutoff`,
+    /// in one transaction. Returns the number of games deleted.
+    async fn delete_games_where<T>(&self, condition: &str, cutoff: T) -> Result<u64>
+    where
+        T: for<'q> sqlx::Encode<'q, Sqlite> + sqlx::Type<Sqlite> + Send + Copy,
+    {
+        let mut tx = self.pool.begin().await?;
+
+        // Related records go first, for databases created before CASCADE was added.
+        // Order: email_resends (references participants) → participants → games
+        for table in ["email_resends", "participants"] {
+            let statement = format!(
+                "DELETE FROM {table} WHERE game_id IN (SELECT id FROM games WHERE {condition})"
+            );
+            sqlx::query(&statement)
+                .bind(cutoff)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("deleting {table} of expired games"))?;
+        }
+        let statement = format!("DELETE FROM games WHERE {condition}");
+        let result = sqlx::query(&statement)
+            .bind(cutoff)
+            .execute(&mut *tx)
             .await
-            .context("committing transaction")?;
+            .context("deleting expired games")?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    // Reveal recovery functions
+    pub async fn create_reveal_recovery(&self, recovery: &RevealRecovery) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO reveal_recovery (id, email, code, created_at, expires_at, verified, attempts)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(recovery.id)
+        .bind(&recovery.email)
+        .bind(&recovery.code_hash)
+        .bind(recovery.created_at)
+        .bind(recovery.expires_at)
+        .bind(recovery.verified)
+        .bind(recovery.attempts)
+        .execute(&self.pool)
+        .await
+        .context("creating reveal recovery")?;
+
         Ok(())
     }
 
-    pub async fn get_game_by_id(&mut self, game_id: GameId) -> Result<Option<Game>> {
+    pub async fn get_reveal_recovery_by_id(
+        &self,
+        recovery_id: RecoveryId,
+    ) -> Result<Option<RevealRecovery>> {
         let row = sqlx::query(
             r#"
-            SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn
-            FROM games
+            SELECT id, email, code, created_at, expires_at, verified, attempts
+            FROM reveal_recovery
             WHERE id = ?
             "#,
         )
-        .bind(game_id)
-        .fetch_optional(&mut *self.inner)
-        .await?;
+        .bind(recovery_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("fetching reveal recovery")?;
 
-        Ok(row.map(|r| Game {
+        Ok(row.map(|r| RevealRecovery {
             id: r.get("id"),
-            name: r.get("name"),
-            event_date: r.get("event_date"),
-            organizer_email: r.get("organizer_email"),
-            admin_token: r.get("admin_token"),
+            email: r.get("email"),
+            code_hash: r.get("code"),
             created_at: r.get("created_at"),
-            drawn: r.get::<i32, _>("drawn") != 0,
+            expires_at: r.get("expires_at"),
+            verified: r.get::<i32, _>("verified") != 0,
+            attempts: r.get("attempts"),
         }))
     }
 
-    pub async fn get_participants_by_game(&mut self, game_id: GameId) -> Result<Vec<Participant>> {
-        let rows = sqlx::query(
+    /// Counts an attempt at a recovery's PIN, returning how many have been made, or `None` if
+    /// none are left. Claimed before the PIN is compared, so parallel guesses can't all slip
+    /// under the limit.
+    pub async fn claim_reveal_recovery_attempt(
+        &self,
+        recovery_id: RecoveryId,
+    ) -> Result<Option<i32>> {
+        let row = sqlx::query(
             r#"
-            SELECT id, game_id, name, email, matched_with_id, view_token, has_viewed, created_at
-            FROM participants
-            WHERE game_id = ?
-            ORDER BY created_at ASC
-        "#,
+            UPDATE reveal_recovery
+            SET attempts = attempts + 1
+            WHERE id = ? AND attempts < ?
+            RETURNING attempts
+            "#,
         )
-        .bind(game_id)
-        .fetch_all(&mut *self.inner)
-        .await?;
+        .bind(recovery_id)
+        .bind(RevealRecovery::MAX_ATTEMPTS)
+        .fetch_optional(&self.pool)
+        .await
+        .context("claiming a reveal recovery attempt")?;
 
-        Ok(rows
-            .into_iter()
-            .map(|r| Participant {
-                id: r.get("id"),
-                game_id: r.get("game_id"),
-                name: r.get("name"),
-                email: r.get("email"),
-                matched_with_id: r.get("matched_with_id"),
-                view_token: r.get("view_token"),
-                has_viewed: r.get::<i32, _>("has_viewed") != 0,
-                created_at: r.get("created_at"),
-            })
-            .collect())
+        Ok(row.map(|r| r.get("attempts")))
     }
 
-    pub async fn update_participant_matches(
-        &mut self,
-        matches: &[(ParticipantId, ParticipantId)],
-    ) -> Result<()> {
-        for (participant_id, matched_with_id) in matches {
-            sqlx::query(
-                r#"
-                UPDATE participants
-                SET matched_with_id = ?
-                WHERE id = ?
-                "#,
-            )
-            .bind(matched_with_id)
-            .bind(participant_id)
-            .execute(&mut *self.inner)
-            .await?;
+    pub async fn mark_reveal_recovery_as_verified(&self, recovery_id: RecoveryId) -> Result<()> {
+        sqlx::query("UPDATE reveal_recovery SET verified = 1 WHERE id = ?")
+            .bind(recovery_id)
+            .execute(&self.pool)
+            .await
+            .context("marking reveal recovery as verified")?;
+
+        Ok(())
+    }
+
+    /// Reveal recovery PINs requested for an email (case-insensitive) since `since`.
+    pub async fn recent_reveal_recoveries_by_email(
+        &self,
+        email: &EmailAddress,
+        since: DateTime<Utc>,
+    ) -> Result<RecentRequests> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS count, MIN(created_at) AS oldest
+            FROM reveal_recovery
+            WHERE email = ? COLLATE NOCASE AND created_at > ?
+            "#,
+        )
+        .bind(email)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .context("counting recent reveal recoveries")?;
+
+        Ok(RecentRequests {
+            count: row.get("count"),
+            oldest: row.get("oldest"),
+        })
+    }
+
+    /// Reveal links of the participant with the given email (case-insensitive), in every
+    /// drawn game that hasn't been deleted, soonest event first. Issues a new view token for
+    /// each, since only hashes of the earlier ones are stored.
+    pub async fn find_reveals_by_participant_email(
+        &self,
+        email: &EmailAddress,
+    ) -> Result<Vec<RecoveredReveal>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT g.name, g.event_date, p.id
+            FROM participants p
+            JOIN games g ON g.id = p.game_id
+            WHERE p.email = ? COLLATE NOCASE AND g.drawn = 1 AND g.deleted_at IS NULL
+            ORDER BY g.event_date, g.created_at
+            "#,
+        )
+        .bind(email)
+        .fetch_all(&self.pool)
+        .await
+        .context("fetching reveals by participant email")?;
+
+        let mut reveals = Vec::with_capacity(rows.len());
+        for r in &rows {
+            reveals.push(RecoveredReveal {
+                game_name: r.get("name"),
+                event_date: r.get("event_date"),
+                view_token: self.create_view_token(r.get("id")).await?,
+            });
         }
+        Ok(reveals)
+    }
+
+    /// Whether the participant with the given email (case-insensitive) has any reveal links
+    /// to recover, as listed by [`find_reveals_by_participant_email`](Self::find_reveals_by_participant_email).
+    pub async fn has_reveals_by_participant_email(&self, email: &EmailAddress) -> Result<bool> {
+        let row = sqlx::query(
+            r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM participants p
+                JOIN games g ON g.id = p.game_id
+                WHERE p.email = ? COLLATE NOCASE AND g.drawn = 1 AND g.deleted_at IS NULL
+            ) AS found
+            "#,
+        )
+        .bind(email)
+        .fetch_one(&self.pool)
+        .await
+        .context("checking reveals by participant email")?;
+
+        Ok(row.get::<i32, _>("found") != 0)
+    }
+
+    // Email job functions
+
+    pub async fn get_email_job(&self, job_id: EmailJobId) -> Result<Option<EmailJob>> {
+        let row = sqlx::query(
+            r#"
+            SELECT j.id, j.game_id, j.template, j.created_at, j.finished_at,
+                COUNT(r.participant_id) AS total,
+                COALESCE(SUM(r.status = 'sent'), 0) AS sent,
+                COALESCE(SUM(r.status = 'failed'), 0) AS failed
+            FROM email_jobs j
+            LEFT JOIN email_job_recipients r ON r.job_id = j.id
+            WHERE j.id = ?
+            GROUP BY j.id
+            "#,
+        )
+        .bind(job_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("getting email job")?;
+
+        Ok(row.as_ref().map(email_job_from_row))
+    }
+
+    /// Jobs with emails left to send, oldest first.
+    pub async fn find_unfinished_email_jobs(&self) -> Result<Vec<EmailJob>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT j.id, j.game_id, j.template, j.created_at, j.finished_at,
+                COUNT(r.participant_id) AS total,
+                COALESCE(SUM(r.status = 'sent'), 0) AS sent,
+                COALESCE(SUM(r.status = 'failed'), 0) AS failed
+            FROM email_jobs j
+            LEFT JOIN email_job_recipients r ON r.job_id = j.id
+            WHERE j.finished_at IS NULL
+            GROUP BY j.id
+            ORDER BY j.created_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("finding unfinished email jobs")?;
+
+        Ok(rows.iter().map(email_job_from_row).collect())
+    }
+
+    /// Every email job of a game, newest first.
+    pub async fn get_email_jobs_by_game(&self, game_id: GameId) -> Result<Vec<EmailJob>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT j.id, j.game_id, j.template, j.created_at, j.finished_at,
+                COUNT(r.participant_id) AS total,
+                COALESCE(SUM(r.status = 'sent'), 0) AS sent,
+                COALESCE(SUM(r.status = 'failed'), 0) AS failed
+            FROM email_jobs j
+            LEFT JOIN email_job_recipients r ON r.job_id = j.id
+            WHERE j.game_id = ?
+            GROUP BY j.id
+            ORDER BY j.created_at DESC
+            "#,
+        )
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("getting email jobs of game")?;
+
+        Ok(rows.iter().map(email_job_from_row).collect())
+    }
+
+    /// Participants of a job who haven't been emailed yet.
+    /// Participants whose email is due: not tried yet, or waiting for a retry that's due.
+    pub async fn get_pending_email_job_recipients(
+        &self,
+        job_id: EmailJobId,
+    ) -> Result<Vec<Participant>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT p.id, p.game_id, p.name, p.email, p.matched_with_id,
+                p.has_viewed, p.created_at
+            FROM email_job_recipients r
+            JOIN participants p ON p.id = r.participant_id
+            WHERE r.job_id = ? AND r.status = 'pending'
+                AND (r.retry_at IS NULL OR r.retry_at <= ?)
+            ORDER BY p.created_at
+            "#,
+        )
+        .bind(job_id)
+        .bind(self.clock.now())
+        .fetch_all(&self.pool)
+        .await
+        .context("getting pending email job recipients")?;
+
+        Ok(rows.iter().map(participant_from_row).collect())
+    }
+
+    /// Records an attempt at a participant's email. A failed one is retried after
+    /// [`EMAIL_JOB_RETRY_DELAY`] until it was tried [`EMAIL_JOB_ATTEMPTS`] times. Returns
+    /// whether the email is done with: sent, or failed for good.
+    pub async fn record_email_job_result(
+        &self,
+        job_id: EmailJobId,
+        participant_id: ParticipantId,
+        sent: bool,
+    ) -> Result<bool> {
+        let status: String = sqlx::query_scalar(
+            r#"
+            UPDATE email_job_recipients
+            SET attempts = attempts + 1,
+                status = CASE
+                    WHEN ? THEN 'sent'
+                    WHEN attempts + 1 >= ? THEN 'failed'
+                    ELSE 'pending'
+                END,
+                retry_at = ?
+            WHERE job_id = ? AND participant_id = ?
+            RETURNING status
+            "#,
+        )
+        .bind(sent)
+        .bind(EMAIL_JOB_ATTEMPTS)
+        .bind(self.clock.now() + EMAIL_JOB_RETRY_DELAY)
+        .bind(job_id)
+        .bind(participant_id)
+        .fetch_one(&self.pool)
+        .await
+        .context("recording email job result")?;
+
+        Ok(status != "pending")
+    }
+
+    /// When the next failed email of a job is due to be tried again, if any are waiting.
+    /// Without a `job_id`, of any unfinished job.
+    pub async fn next_email_job_retry(
+        &self,
+        job_id: Option<EmailJobId>,
+    ) -> Result<Option<DateTime<Utc>>> {
+        sqlx::query_scalar(
+            r#"
+            SELECT MIN(r.retry_at)
+            FROM email_job_recipients r
+            JOIN email_jobs j ON j.id = r.job_id
+            WHERE r.status = 'pending' AND r.retry_at IS NOT NULL
+                AND j.finished_at IS NULL AND (? IS NULL OR j.id = ?)
+            "#,
+        )
+        .bind(job_id)
+        .bind(job_id)
+        .fetch_one(&self.pool)
+        .await
+        .context("finding the next email job retry")
+    }
+
+    pub async fn finish_email_job(&self, job_id: EmailJobId) -> Result<()> {
+        sqlx::query("UPDATE email_jobs SET finished_at = ? WHERE id = ?")
+            .bind(self.clock.now())
+            .bind(job_id)
+            .execute(&self.pool)
+            .await
+            .context("finishing email job")?;
+
+        Ok(())
+    }
+
+    // Announcement functions
+
+    /// Organizers of games that aren't deleted and whose event hasn't passed, once each and
+    /// in the language of their latest game. Suppressed addresses are left out.
+    pub async fn get_announcement_recipients(&self) -> Result<Vec<AnnouncementRecipient>> {
+        let rows = sqlx::query(
+            r#"
+            -- With MAX, SQLite takes the other columns from the latest game
+            SELECT organizer_email AS email, locale, MAX(created_at)
+            FROM games
+            WHERE deleted_at IS NULL
+                AND event_date >= ?
+                AND organizer_email COLLATE NOCASE NOT IN (SELECT email FROM email_suppressions)
+            GROUP BY organizer_email COLLATE NOCASE
+            ORDER BY organizer_email
+            "#,
+        )
+        .bind(self.clock.now().date_naive())
+        .fetch_all(&self.pool)
+        .await
+        .context("getting announcement recipients")?;
+
+        Ok(rows
+            .iter()
+            .map(|r| AnnouncementRecipient {
+                email: r.get("email"),
+                locale: r.get("locale"),
+            })
+            .collect())
+    }
+
+    /// Queues an announcement to the recipients.
+    pub async fn create_announcement(
+        &self,
+        subject: &str,
+        message: &str,
+        recipients: &[AnnouncementRecipient],
+    ) -> Result<AnnouncementId> {
+        let id = AnnouncementId::new();
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO announcements (id, subject, message, created_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(subject)
+        .bind(message)
+        .bind(self.clock.now())
+        .execute(&mut *tx)
+        .await
+        .context("creating announcement")?;
+        for recipient in recipients {
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO announcement_recipients (announcement_id, email, locale)
+                VALUES (?, ?, ?)
+                "#,
+            )
+            .bind(id)
+            .bind(&recipient.email)
+            .bind(recipient.locale)
+            .execute(&mut *tx)
+            .await
+            .context("queueing announcement recipient")?;
+        }
+
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    /// Every announcement, newest first.
+    pub async fn list_announcements(&self) -> Result<Vec<Announcement>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.id, a.subject, a.message, a.created_at, a.finished_at,
+                COUNT(r.email) AS total,
+                COALESCE(SUM(r.status = 'sent'), 0) AS sent,
+                COALESCE(SUM(r.status = 'failed'), 0) AS failed
+            FROM announcements a
+            LEFT JOIN announcement_recipients r ON r.announcement_id = a.id
+            GROUP BY a.id
+            ORDER BY a.created_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("listing announcements")?;
+
+        Ok(rows.iter().map(announcement_from_row).collect())
+    }
+
+    /// Announcements with emails left to send, oldest first.
+    pub async fn find_unfinished_announcements(&self) -> Result<Vec<Announcement>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.id, a.subject, a.message, a.created_at, a.finished_at,
+                COUNT(r.email) AS total,
+                COALESCE(SUM(r.status = 'sent'), 0) AS sent,
+                COALESCE(SUM(r.status = 'failed'), 0) AS failed
+            FROM announcements a
+            LEFT JOIN announcement_recipients r ON r.announcement_id = a.id
+            WHERE a.finished_at IS NULL
+            GROUP BY a.id
+            ORDER BY a.created_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("finding unfinished announcements")?;
+
+        Ok(rows.iter().map(announcement_from_row).collect())
+    }
+
+    /// Recipients of an announcement who haven't been emailed yet.
+    pub async fn get_pending_announcement_recipients(
+        &self,
+        announcement_id: AnnouncementId,
+    ) -> Result<Vec<AnnouncementRecipient>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT email, locale
+            FROM announcement_recipients
+            WHERE announcement_id = ? AND status = 'pending'
+            ORDER BY email
+            "#,
+        )
+        .bind(announcement_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("getting pending announcement recipients")?;
+
+        Ok(rows
+            .iter()
+            .map(|r| AnnouncementRecipient {
+                email: r.get("email"),
+                locale: r.get("locale"),
+            })
+            .collect())
+    }
+
+    pub async fn record_announcement_result(
+        &self,
+        announcement_id: AnnouncementId,
+        email: &EmailAddress,
+        sent: bool,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE announcement_recipients
+            SET status = ?
+            WHERE announcement_id = ? AND email = ?
+            "#,
+        )
+        .bind(if sent { "sent" } else { "failed" })
+        .bind(announcement_id)
+        .bind(email)
+        .execute(&self.pool)
+        .await
+        .context("recording announcement result")?;
+
+        Ok(())
+    }
+
+    pub async fn finish_announcement(&self, announcement_id: AnnouncementId) -> Result<()> {
+        sqlx::query("UPDATE announcements SET finished_at = ? WHERE id = ?")
+            .bind(self.clock.now())
+            .bind(announcement_id)
+            .execute(&self.pool)
+            .await
+            .context("finishing announcement")?;
+
+        Ok(())
+    }
+
+    // Reminder functions
+
+    /// Participants who haven't seen their match and are due the given reminder.
+    ///
+    /// Only games that are drawn, active, have reminders enabled, have already revealed and
+    /// whose event hasn't passed are considered. Each reminder is sent at most once.
+    pub async fn find_due_reminders(&self, kind: ReminderKind) -> Result<Vec<Participant>> {
+        let now = self.clock.now();
+        let today = now.date_naive();
+        // The last reminder skips games drawn in the past day, whose email is still fresh
+        let (drawn_before, event_until, unknown_draw_time) = match kind {
+            ReminderKind::AfterDraw => {
+                (now - Duration::days(REMINDER_DAYS_AFTER_DRAW), None, false)
+            }
+            ReminderKind::BeforeEvent => (
+                now - Duration::days(1),
+                Some(today + Duration::days(REMINDER_DAYS_BEFORE_EVENT)),
+                true,
+            ),
+        };
+
+        let rows = sqlx::query(
+            r#"
+            SELECT p.id, p.game_id, p.name, p.email, p.matched_with_id,
+                p.has_viewed, p.created_at
+            FROM participants p
+            JOIN games g ON g.id = p.game_id
+            WHERE p.has_viewed = 0
+                AND g.drawn = 1
+                AND g.reminders_enabled = 1
+                AND g.deleted_at IS NULL
+                AND g.frozen_at IS NULL
+                AND (g.drawn_at <= ? OR (g.drawn_at IS NULL AND ?))
+                AND (g.reveal_at IS NULL OR g.reveal_at <= ?)
+                AND g.event_date >= ?
+                AND (? IS NULL OR g.event_date <= ?)
+                AND NOT EXISTS (
+                    SELECT 1 FROM reminder_sends r
+                    WHERE r.participant_id = p.id AND r.reminder_kind = ?
+                )
+            ORDER BY p.game_id, p.created_at
+            "#,
+        )
+        .bind(drawn_before)
+        .bind(unknown_draw_time)
+        .bind(now)
+        .bind(today)
+        .bind(event_until)
+        .bind(event_until)
+        .bind(kind.as_str())
+        .fetch_all(&self.pool)
+        .await
+        .context("finding due reminders")?;
+
+        Ok(rows.iter().map(participant_from_row).collect())
+    }
+
+    /// Drawn games that are due an event reminder, with the days before the event it's for.
+    ///
+    /// Games drawn in the past day are skipped, since their draw email is still fresh.
+    pub async fn find_games_due_event_reminders(&self) -> Result<Vec<(Game, u32)>> {
+        let now = self.clock.now();
+        // Narrowed down by each game's own date below; no time zone is a day ahead of UTC
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn,
+                allowed_email_domain, frozen_at, frozen_reason, deleted_at,
+                organizer_message, event_location, reveal_at, timezone, locale, locked,
+                reveal_expires_after_event, reveal_single_use, cover_image, drawn_at,
+                reminders_enabled, wrap_up_enabled, wrap_up_sent_at,
+                participant_email_subject, participant_email_body, event_reminder_days,
+                digest_enabled, digest_sent_at, reply_to_organizer, open_tracking_enabled
+            FROM games
+            WHERE drawn = 1
+                AND event_reminder_days != ''
+                AND deleted_at IS NULL
+                AND frozen_at IS NULL
+                AND (drawn_at IS NULL OR drawn_at <= ?)
+                AND (reveal_at IS NULL OR reveal_at <= ?)
+                AND event_date >= ?
+            ORDER BY event_date
+            "#,
+        )
+        .bind(now - Duration::days(1))
+        .bind(now)
+        .bind(now.date_naive() - Duration::days(1))
+        .fetch_all(&self.pool)
+        .await
+        .context("finding games due event reminders")?;
+
+        Ok(rows
+            .iter()
+            .map(game_from_row)
+            .filter_map(|game| {
+                let days = game.due_event_reminder(game.timezone.date_at(now))?;
+                Some((game, days))
+            })
+            .collect())
+    }
+
+    /// Participants of a game who weren't sent the event reminder for the given days yet.
+    pub async fn find_due_event_reminder_participants(
+        &self,
+        game_id: GameId,
+        days_before: u32,
+    ) -> Result<Vec<Participant>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT p.id, p.game_id, p.name, p.email, p.matched_with_id,
+                p.has_viewed, p.created_at
+            FROM participants p
+            WHERE p.game_id = ?
+                AND NOT EXISTS (
+                    SELECT 1 FROM reminder_sends r
+                    WHERE r.participant_id = p.id AND r.reminder_kind = ?
+                )
+            ORDER BY p.created_at
+            "#,
+        )
+        .bind(game_id)
+        .bind(event_reminder_kind(days_before))
+        .fetch_all(&self.pool)
+        .await
+        .context("finding participants due an event reminder")?;
+
+        Ok(rows.iter().map(participant_from_row).collect())
+    }
+
+    pub async fn record_event_reminder_sent(
+        &self,
+        participant: &Participant,
+        days_before: u32,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO reminder_sends (id, participant_id, game_id, reminder_kind, sent_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Ulid::new().to_string())
+        .bind(participant.id)
+        .bind(participant.game_id)
+        .bind(event_reminder_kind(days_before))
+        .bind(self.clock.now())
+        .execute(&self.pool)
+        .await
+        .context("recording event reminder send")?;
+
+        Ok(())
+    }
+
+    pub async fn record_reminder_sent(
+        &self,
+        participant: &Participant,
+        kind: ReminderKind,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO reminder_sends (id, participant_id, game_id, reminder_kind, sent_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Ulid::new().to_string())
+        .bind(participant.id)
+        .bind(participant.game_id)
+        .bind(kind.as_str())
+        .bind(self.clock.now())
+        .execute(&self.pool)
+        .await
+        .context("recording reminder send")?;
+
+        Ok(())
+    }
+
+    // Wrap-up functions
+
+    /// Games whose organizer asked for a wrap-up email and is due one: the event has passed
+    /// in the game's time zone, and no wrap-up was sent yet.
+    pub async fn find_games_due_wrap_up(&self) -> Result<Vec<Game>> {
+        let now = self.clock.now();
+        // Narrowed down by each game's own date below; no time zone is a day ahead of UTC
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn,
+                allowed_email_domain, frozen_at, frozen_reason, deleted_at,
+                organizer_message, event_location, reveal_at, timezone, locale, locked,
+                reveal_expires_after_event, reveal_single_use, cover_image, drawn_at,
+                reminders_enabled, wrap_up_enabled, wrap_up_sent_at,
+                participant_email_subject, participant_email_body, event_reminder_days,
+                digest_enabled, digest_sent_at, reply_to_organizer, open_tracking_enabled
+            FROM games
+            WHERE drawn = 1
+                AND wrap_up_enabled = 1
+                AND wrap_up_sent_at IS NULL
+                AND deleted_at IS NULL
+                AND frozen_at IS NULL
+                AND event_date <= ?
+            ORDER BY event_date
+            "#,
+        )
+        .bind(now.date_naive())
+        .fetch_all(&self.pool)
+        .await
+        .context("finding games due a wrap-up")?;
+
+        Ok(rows
+            .iter()
+            .map(game_from_row)
+            .filter(|game| game.event_date < game.timezone.date_at(now))
+            .collect())
+    }
+
+    /// Games whose organizer asked for a weekly digest and is due one: the game was drawn
+    /// (or the last digest sent) a week ago, the event hasn't passed in the game's time zone,
+    /// and someone still hasn't seen their match.
+    pub async fn find_games_due_digest(&self) -> Result<Vec<Game>> {
+        let now = self.clock.now();
+        let last_week = now - Duration::days(DIGEST_INTERVAL_DAYS);
+        // Narrowed down by each game's own date below; no time zone is a day ahead of UTC
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn,
+                allowed_email_domain, frozen_at, frozen_reason, deleted_at,
+                organizer_message, event_location, reveal_at, timezone, locale, locked,
+                reveal_expires_after_event, reveal_single_use, cover_image, drawn_at,
+                reminders_enabled, wrap_up_enabled, wrap_up_sent_at,
+                participant_email_subject, participant_email_body, event_reminder_days,
+                digest_enabled, digest_sent_at, reply_to_organizer, open_tracking_enabled
+            FROM games
+            WHERE drawn = 1
+                AND digest_enabled = 1
+                AND deleted_at IS NULL
+                AND frozen_at IS NULL
+                AND COALESCE(digest_sent_at, drawn_at, created_at) <= ?
+                AND event_date >= ?
+                AND EXISTS (
+                    SELECT 1 FROM participants p
+                    WHERE p.game_id = games.id AND p.has_viewed = 0
+                )
+            ORDER BY event_date
+            "#,
+        )
+        .bind(last_week)
+        .bind(now.date_naive() - Duration::days(1))
+        .fetch_all(&self.pool)
+        .await
+        .context("finding games due a digest")?;
+
+        Ok(rows
+            .iter()
+            .map(game_from_row)
+            .filter(|game| game.event_date >= game.timezone.date_at(now))
+            .collect())
+    }
+
+    pub async fn mark_digest_sent(&self, game_id: GameId) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE games
+            SET digest_sent_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(self.clock.now())
+        .bind(game_id)
+        .execute(&self.pool)
+        .await
+        .context("marking digest as sent")?;
+
+        Ok(())
+    }
+
+    pub async fn mark_wrap_up_sent(&self, game_id: GameId) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE games
+            SET wrap_up_sent_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(self.clock.now())
+        .bind(game_id)
+        .execute(&self.pool)
+        .await
+        .context("marking wrap-up as sent")?;
+
+        Ok(())
+    }
+
+    /// How the game went, for the organizer's wrap-up email.
+    pub async fn get_wrap_up_stats(&self, game_id: GameId) -> Result<WrapUpStats> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM participants WHERE game_id = ?1) as participant_count,
+                (SELECT COUNT(*) FROM participants WHERE game_id = ?1 AND has_viewed = 1)
+                    as viewed_count,
+                (SELECT COUNT(*) FROM reveal_events WHERE game_id = ?1) as reveal_count,
+                (SELECT COUNT(*) FROM email_resends WHERE game_id = ?1) as resend_count,
+                (SELECT COUNT(*) FROM reminder_sends WHERE game_id = ?1) as reminder_count
+            "#,
+        )
+        .bind(game_id)
+        .fetch_one(&self.pool)
+        .await
+        .context("counting wrap-up statistics")?;
+
+        let not_viewed = sqlx::query(
+            r#"
+            SELECT name
+            FROM participants
+            WHERE game_id = ? AND has_viewed = 0
+            ORDER BY name COLLATE NOCASE
+            "#,
+        )
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("listing participants who never saw their match")?;
+
+        let count = |column: &str| u64::try_from(row.get::<i64, _>(column)).unwrap_or(0);
+        Ok(WrapUpStats {
+            participant_count: count("participant_count"),
+            viewed_count: count("viewed_count"),
+            reveal_count: count("reveal_count"),
+            resend_count: count("resend_count"),
+            reminder_count: count("reminder_count"),
+            not_viewed: not_viewed.iter().map(|r| r.get("name")).collect(),
+        })
+    }
+
+    // Reveal audit functions
+
+    /// Record that a participant was shown their match.
+    pub async fn record_reveal_event(
+        &self,
+        participant: &Participant,
+        user_agent: Option<&str>,
+        ip_prefix: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO reveal_events (id, participant_id, game_id, viewed_at, user_agent, ip_prefix)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Ulid::new().to_string())
+        .bind(participant.id)
+        .bind(participant.game_id)
+        .bind(self.clock.now())
+        .bind(user_agent)
+        .bind(ip_prefix)
+        .execute(&self.pool)
+        .await
+        .context("recording reveal event")?;
+
+        Ok(())
+    }
+
+    /// Record that a participant saw their match on a device, identified by a hash.
+    pub async fn record_reveal_device(
+        &self,
+        participant_id: ParticipantId,
+        device_hash: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO reveal_devices (participant_id, device_hash, first_seen_at)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(participant_id)
+        .bind(device_hash)
+        .bind(self.clock.now())
+        .execute(&self.pool)
+        .await
+        .context("recording reveal device")?;
+
+        Ok(())
+    }
+
+    /// Revision of a game, which changes whenever its `game_summary` may have (see
+    /// `0028_game_revisions.sql`). `None` if the game doesn't exist or was deleted.
+    pub async fn game_revision(&self, game_id: GameId) -> Result<Option<i64>> {
+        sqlx::query_scalar("SELECT revision FROM games WHERE id = ? AND deleted_at IS NULL")
+            .bind(game_id)
+            .fetch_optional(&self.pool)
+            .await
+            .context("fetching game revision")
+    }
+
+    /// The game with what its organizer dashboard shows about the participants matching
+    /// `filter`, in a single query. `None` if the game doesn't exist or was deleted.
+    pub async fn game_summary(
+        &self,
+        game_id: GameId,
+        filter: &ParticipantFilter,
+    ) -> Result<Option<GameStatus>> {
+        self.query_game_summary(game_id, filter, false).await
+    }
+
+    /// Like `game_summary`, with every participant, but also for games that were deleted and
+    /// not yet purged.
+    pub async fn game_summary_including_deleted(
+        &self,
+        game_id: GameId,
+    ) -> Result<Option<GameStatus>> {
+        self.query_game_summary(game_id, &ParticipantFilter::default(), true)
+            .await
+    }
+
+    async fn query_game_summary(
+        &self,
+        game_id: GameId,
+        filter: &ParticipantFilter,
+        including_deleted: bool,
+    ) -> Result<Option<GameStatus>> {
+        // A negative limit means no limit in SQLite
+        let limit = filter.limit.map_or(-1, i64::from);
+        let offset = i64::try_from(filter.offset).context("converting offset to i64")?;
+        let rows = sqlx::query(GAME_SUMMARY_QUERY)
+            .bind(game_id)
+            .bind(including_deleted)
+            .bind(filter.viewed)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .context("fetching game summary")?;
+
+        let Some(first) = rows.first() else {
+            return Ok(None);
+        };
+        let game = game_from_row(first);
+        let count = |column: &str| {
+            u64::try_from(first.get::<i64, _>(column))
+                .with_context(|| format!("converting {column} to u64"))
+        };
+        let bulk_resend_count = count("bulk_resend_count")?;
+        let participant_count = count("participant_count")?;
+        let viewed_count = count("viewed_count")?;
+        let matching_count = count("matching_count")?;
+
+        // A game without participants on the page still has a row, with only the game's
+        // columns set
+        let participants = rows
+            .iter()
+            .filter(|r| {
+                r.get::<Option<ParticipantId>, _>("participant_id")
+                    .is_some()
+            })
+            .map(|r| {
+                Ok(ParticipantStatus {
+                    id: r.get("participant_id"),
+                    name: r.get("participant_name"),
+                    email: r.get("participant_email"),
+                    has_viewed: r.get::<i32, _>("participant_has_viewed") != 0,
+                    last_viewed_at: r.get("last_viewed_at"),
+                    device_count: u64::try_from(r.get::<i64, _>("device_count"))
+                        .context("converting device count to u64")?,
+                    email_undeliverable: r.get("email_undeliverable"),
+                    email_opened_at: r.get("email_opened_at"),
+                    email_domain_unreachable: r.get::<i32, _>("email_domain_unreachable") != 0,
+                    resend_count: u64::try_from(r.get::<i64, _>("resend_count"))
+                        .context("converting resend count to u64")?,
+                    created_at: r.get("participant_created_at"),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(GameStatus {
+            game,
+            participants,
+            participant_count,
+            matching_count,
+            viewed_count,
+            bulk_resend_count,
+        }))
+    }
+
+    // Undeliverable email functions
+
+    /// Flags every participant with the given address, in games that weren't deleted, as
+    /// having an email that can't be delivered. Returns the participants that weren't
+    /// flagged before, whose organizers should be told.
+    pub async fn mark_email_undeliverable(
+        &self,
+        email: &EmailAddress,
+        kind: EmailEventKind,
+    ) -> Result<Vec<Participant>> {
+        let rows = sqlx::query(
+            r#"
+            UPDATE participants
+            SET email_undeliverable_at = ?, email_undeliverable_reason = ?
+            WHERE email = ? COLLATE NOCASE
+                AND email_undeliverable_at IS NULL
+                AND game_id IN (SELECT id FROM games WHERE deleted_at IS NULL)
+            RETURNING id, game_id, name, email, matched_with_id, has_viewed,
+                created_at
+            "#,
+        )
+        .bind(self.clock.now())
+        .bind(kind.as_str())
+        .bind(email)
+        .fetch_all(&self.pool)
+        .await
+        .context("marking email as undeliverable")?;
+
+        Ok(rows.iter().map(participant_from_row).collect())
+    }
+
+    // Suppression list functions
+
+    /// Adds an address to the suppression list. A bounce replaces an earlier reason, since
+    /// it suppresses more emails; other reasons don't replace an existing entry.
+    pub async fn suppress_email(
+        &self,
+        email: &EmailAddress,
+        reason: SuppressionReason,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO email_suppressions (email, reason, created_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT (email) DO UPDATE
+            SET reason = excluded.reason, created_at = excluded.created_at
+            WHERE excluded.reason = 'bounce' AND reason != 'bounce'
+            "#,
+        )
+        .bind(email)
+        .bind(reason.as_str())
+        .bind(self.clock.now())
+        .execute(&self.pool)
+        .await
+        .context("suppressing email")?;
+
+        Ok(())
+    }
+
+    /// Why an address is on the suppression list, if it is.
+    pub async fn get_email_suppression(
+        &self,
+        email: &EmailAddress,
+    ) -> Result<Option<SuppressionReason>> {
+        let reason: Option<String> =
+            sqlx::query_scalar("SELECT reason FROM email_suppressions WHERE email = ?")
+                .bind(email)
+                .fetch_optional(&self.pool)
+                .await
+                .context("fetching email suppression")?;
+
+        Ok(reason.map(|reason| match reason.as_str() {
+            "bounce" => SuppressionReason::Bounce,
+            "complaint" => SuppressionReason::Complaint,
+            _ => SuppressionReason::Unsubscribe,
+        }))
+    }
+
+    /// The token for an address's unsubscribe link, created the first time it's needed.
+    pub async fn get_or_create_unsubscribe_token(
+        &self,
+        email: &EmailAddress,
+    ) -> Result<UnsubscribeToken> {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO unsubscribe_tokens (token, email, created_at)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(UnsubscribeToken::generate())
+        .bind(email)
+        .bind(self.clock.now())
+        .execute(&self.pool)
+        .await
+        .context("creating unsubscribe token")?;
+
+        sqlx::query_scalar("SELECT token FROM unsubscribe_tokens WHERE email = ?")
+            .bind(email)
+            .fetch_one(&self.pool)
+            .await
+            .context("fetching unsubscribe token")
+    }
+
+    /// The address an unsubscribe link belongs to.
+    pub async fn find_unsubscribe_email(
+        &self,
+        token: &UnsubscribeToken,
+    ) -> Result<Option<EmailAddress>> {
+        sqlx::query_scalar("SELECT email FROM unsubscribe_tokens WHERE token = ?")
+            .bind(token)
+            .fetch_optional(&self.pool)
+            .await
+            .context("fetching unsubscribe token")
+    }
+
+    // Email open tracking functions
+
+    pub async fn get_or_create_email_open_token(
+        &self,
+        participant_id: ParticipantId,
+    ) -> Result<EmailOpenToken> {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO email_open_tokens (token, participant_id, created_at)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(EmailOpenToken::generate())
+        .bind(participant_id)
+        .bind(self.clock.now())
+        .execute(&self.pool)
+        .await
+        .context("creating email open token")?;
+
+        sqlx::query_scalar("SELECT token FROM email_open_tokens WHERE participant_id = ?")
+            .bind(participant_id)
+            .fetch_one(&self.pool)
+            .await
+            .context("fetching email open token")
+    }
+
+    /// Records the first time a participant opened an email, while their game tracks opens.
+    pub async fn record_email_open(&self, token: &EmailOpenToken) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE participants
+            SET email_opened_at = ?
+            WHERE id = (SELECT participant_id FROM email_open_tokens WHERE token = ?)
+                AND email_opened_at IS NULL
+                AND game_id IN (
+                    SELECT id FROM games
+                    WHERE open_tracking_enabled = 1 AND deleted_at IS NULL
+                )
+            "#,
+        )
+        .bind(self.clock.now())
+        .bind(token)
+        .execute(&self.pool)
+        .await
+        .context("recording email open")?;
+
+        Ok(())
+    }
+
+    /// Flags a participant whose email domain can't receive mail, unless their email has
+    /// changed since it was checked.
+    pub async fn mark_email_domain_unreachable(
+        &self,
+        participant_id: ParticipantId,
+        email: &EmailAddress,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE participants
+            SET email_domain_unreachable_at = ?
+            WHERE id = ? AND email = ?
+            "#,
+        )
+        .bind(self.clock.now())
+        .bind(participant_id)
+        .bind(email)
+        .execute(&self.pool)
+        .await
+        .context("flagging unreachable email domain")?;
+
+        Ok(())
+    }
+
+    // Match access audit functions
+
+    /// Record that the organizer looked at the match assignments of a game.
+    pub async fn record_match_access(&self, game_id: GameId, access_type: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO match_accesses (id, game_id, access_type, accessed_at)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(Ulid::new().to_string())
+        .bind(game_id)
+        .bind(access_type)
+        .bind(self.clock.now())
+        .execute(&self.pool)
+        .await
+        .context("recording match access")?;
+
+        Ok(())
+    }
+
+    pub async fn get_match_accesses(&self, game_id: GameId) -> Result<Vec<MatchAccess>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT access_type, accessed_at
+            FROM match_accesses
+            WHERE game_id = ?
+            ORDER BY accessed_at DESC
+            "#,
+        )
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("fetching match accesses")?;
+
+        Ok(rows
+            .iter()
+            .map(|r| MatchAccess {
+                access_type: r.get("access_type"),
+                accessed_at: r.get("accessed_at"),
+            })
+            .collect())
+    }
+
+    // Email resend tracking functions
+    pub async fn record_email_resend(
+        &self,
+        game_id: GameId,
+        participant_id: Option<ParticipantId>,
+        resend_type: &str,
+    ) -> Result<()> {
+        let id = Ulid::new().to_string();
+        let now = self.clock.now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO email_resends (id, game_id, participant_id, resend_type, resent_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(game_id)
+        .bind(participant_id)
+        .bind(resend_type)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Every resend of a game, newest first.
+    pub async fn get_email_resends(&self, game_id: GameId) -> Result<Vec<EmailResend>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT participant_id, resend_type, resent_at
+            FROM email_resends
+            WHERE game_id = ?
+            ORDER BY resent_at DESC
+            "#,
+        )
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("getting email resends")?;
+
+        Ok(rows
+            .iter()
+            .map(|r| EmailResend {
+                participant_id: r.get("participant_id"),
+                resend_type: r.get("resend_type"),
+                resent_at: r.get("resent_at"),
+            })
+            .collect())
+    }
+
+    pub async fn count_recent_participant_resends(
+        &self,
+        participant_id: ParticipantId,
+        since: DateTime<Utc>,
+    ) -> Result<i64> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) as count
+            FROM email_resends
+            WHERE participant_id = ? AND resent_at > ? AND resend_type = 'individual'
+            "#,
+        )
+        .bind(participant_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get("count"))
+    }
+
+    pub async fn count_total_participant_resends(
+        &self,
+        participant_id: ParticipantId,
+    ) -> Result<i64> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) as count
+            FROM email_resends
+            WHERE participant_id = ? AND resend_type = 'individual'
+            "#,
+        )
+        .bind(participant_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get("count"))
+    }
+
+    pub async fn count_recent_bulk_resends(
+        &self,
+        game_id: GameId,
+        since: DateTime<Utc>,
+    ) -> Result<i64> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) as count
+            FROM email_resends
+            WHERE game_id = ? AND resent_at > ? AND resend_type = 'bulk'
+            "#,
+        )
+        .bind(game_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get("count"))
+    }
+
+    pub async fn count_total_bulk_resends(&self, game_id: GameId) -> Result<i64> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) as count
+            FROM email_resends
+            WHERE game_id = ? AND resend_type = 'bulk'
+            "#,
+        )
+        .bind(game_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get("count"))
+    }
+
+    pub async fn count_participants_in_game(&self, game_id: GameId) -> Result<u64> {
+        let mut conn = self.pool.acquire().await?;
+        count_participants(&mut conn, game_id).await
+    }
+
+    pub async fn count_viewed_participants_in_game(&self, game_id: GameId) -> Result<u64> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) as count
+            FROM participants
+            WHERE game_id = ? AND has_viewed = 1
+            "#,
+        )
+        .bind(game_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let count: i64 = row.get("count");
+        u64::try_from(count).context("converting viewed participant count to u64")
+    }
+
+    // Site statistics functions
+
+    /// Site-wide totals for the site admin dashboard.
+    pub async fn get_site_stats_totals(&self) -> Result<SiteStatsTotals> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM games WHERE deleted_at IS NULL) as games,
+                (SELECT COUNT(*) FROM games WHERE deleted_at IS NULL AND drawn = 1)
+                    as drawn_games,
+                (SELECT COUNT(*) FROM games WHERE deleted_at IS NOT NULL) as deleted_games,
+                (SELECT COUNT(*) FROM participants) as participants,
+                (SELECT COUNT(*) FROM participants WHERE has_viewed = 1) as viewed_participants,
+                (SELECT COUNT(*) FROM reveal_events) as reveals,
+                (SELECT COUNT(*) FROM email_job_recipients WHERE status = 'sent') as emails_sent,
+                (SELECT COUNT(*) FROM email_job_recipients WHERE status = 'failed')
+                    as emails_failed,
+                (SELECT COUNT(*) FROM email_verifications) as verifications,
+                (SELECT COUNT(*) FROM email_verifications WHERE verified = 1)
+                    as completed_verifications
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("counting site statistics")?;
+
+        let count = |column: &str| u64::try_from(row.get::<i64, _>(column)).unwrap_or(0);
+        Ok(SiteStatsTotals {
+            games: count("games"),
+            drawn_games: count("drawn_games"),
+            deleted_games: count("deleted_games"),
+            participants: count("participants"),
+            viewed_participants: count("viewed_participants"),
+            reveals: count("reveals"),
+            emails_sent: count("emails_sent"),
+            emails_failed: count("emails_failed"),
+            verifications: count("verifications"),
+            completed_verifications: count("completed_verifications"),
+        })
+    }
+
+    /// Daily activity over the last `days` days (UTC) up to today, oldest first.
+    pub async fn get_site_stats_trends(&self, days: u32) -> Result<Vec<SiteStatsDay>> {
+        let today = self.clock.now().date_naive();
+        let first_day = today - Duration::days(i64::from(days.max(1)) - 1);
+        let since = first_day.and_time(chrono::NaiveTime::MIN).and_utc();
+
+        // Timestamps are stored as RFC 3339 in UTC, so their first 10 characters are the day
+        let rows = sqlx::query(
+            r#"
+            SELECT substr(created_at, 1, 10) as day, 'games_created' as metric, COUNT(*) as count
+            FROM games WHERE created_at >= ?1 GROUP BY day
+            UNION ALL
+            SELECT substr(drawn_at, 1, 10) as day, 'games_drawn', COUNT(*)
+            FROM games WHERE drawn_at >= ?1 GROUP BY day
+            UNION ALL
+            SELECT substr(viewed_at, 1, 10) as day, 'reveals', COUNT(*)
+            FROM reveal_events WHERE viewed_at >= ?1 GROUP BY day
+            UNION ALL
+            SELECT substr(created_at, 1, 10) as day, 'verifications', COUNT(*)
+            FROM email_verifications WHERE created_at >= ?1 GROUP BY day
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .context("counting daily site statistics")?;
+
+        let mut trends: Vec<SiteStatsDay> = first_day
+            .iter_days()
+            .take_while(|&date| date <= today)
+            .map(|date| SiteStatsDay {
+                date,
+                ..Default::default()
+            })
+            .collect();
+        for row in rows {
+            let Ok(date) = row.get::<String, _>("day").parse::<NaiveDate>() else {
+                continue;
+            };
+            let Some(day) = trends.iter_mut().find(|day| day.date == date) else {
+                continue;
+            };
+            let count = u64::try_from(row.get::<i64, _>("count")).unwrap_or(0);
+            match row.get::<String, _>("metric").as_str() {
+                "games_created" => day.games_created = count,
+                "games_drawn" => day.games_drawn = count,
+                "reveals" => day.reveals = count,
+                _ => day.verifications = count,
+            }
+        }
+        Ok(trends)
+    }
+
+    // Site admin authentication functions
+
+    /// Initialize the site admin password from `SITE_ADMIN_PASSWORD` if not set.
+    /// This should be called on startup.
+    pub async fn init_site_admin_password(&self, password: Option<&str>) -> Result<()> {
+        // Check if password is already set
+        let existing = sqlx::query("SELECT id FROM site_admin_password WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if existing.is_some() {
+            tracing::info!("site admin password already initialized");
+            return Ok(());
+        }
+
+        let password =
+            password.context("SITE_ADMIN_PASSWORD not set and no password in database")?;
+
+        // Hash password
+        let password_hash =
+            bcrypt::hash(password, bcrypt::DEFAULT_COST).context("hashing site admin password")?;
+
+        // Store in database
+        sqlx::query(
+            r#"
+            INSERT INTO site_admin_password (id, password_hash, updated_at)
+            VALUES (1, ?, ?)
+            "#,
+        )
+        .bind(&password_hash)
+        .bind(self.clock.now())
+        .execute(&self.pool)
+        .await
+        .context("storing site admin password")?;
+
+        tracing::info!("site admin password initialized from environment");
+        Ok(())
+    }
+
+    /// Verify the site admin password and return true if correct.
+    pub async fn verify_site_admin_password(&self, password: &str) -> Result<bool> {
+        let row = sqlx::query(
+            r#"
+            SELECT password_hash
+            FROM site_admin_password
+            WHERE id = 1
+            "#,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("fetching site admin password hash")?;
+
+        let Some(row) = row else {
+            tracing::warn!("site admin password not initialized");
+            return Ok(false);
+        };
+
+        let password_hash: String = row.get("password_hash");
+        bcrypt::verify(password, &password_hash).context("verifying site admin password")
+    }
+
+    /// Change the site admin password. Requires the current password.
+    pub async fn change_site_admin_password(
+        &self,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<bool> {
+        // Verify current password
+        if !self.verify_site_admin_password(current_password).await? {
+            return Ok(false);
+        }
+
+        // Hash new password
+        let new_hash = bcrypt::hash(new_password, bcrypt::DEFAULT_COST)
+            .context("hashing new site admin password")?;
+
+        // Update in database
+        sqlx::query(
+            r#"
+            UPDATE site_admin_password
+            SET password_hash = ?, updated_at = ?
+            WHERE id = 1
+            "#,
+        )
+        .bind(&new_hash)
+        .bind(self.clock.now())
+        .execute(&self.pool)
+        .await
+        .context("updating site admin password")?;
+
+        tracing::info!("site admin password changed");
+        Ok(true)
+    }
+
+    /// Create a new admin session and return the session token.
+    /// Sessions expire after [`ADMIN_SESSION_DURATION_HOURS`] without use.
+    pub async fn create_admin_session(&self) -> Result<AdminSessionToken> {
+        let session_token = AdminSessionToken::generate();
+        let id = AdminSessionId::new();
+        let created_at = self.clock.now();
+        let expires_at = created_at + Duration::hours(ADMIN_SESSION_DURATION_HOURS);
+
+        sqlx::query(
+            r#"
+            INSERT INTO admin_sessions (id, session_token, created_at, expires_at)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(&session_token)
+        .bind(created_at)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .context("creating admin session")?;
+
+        Ok(session_token)
+    }
+
+    /// Validate an admin session token. Returns true if valid and not expired.
+    ///
+    /// Using a session extends it, so it only expires after a period of inactivity.
+    pub async fn validate_admin_session(&self, session_token: &AdminSessionToken) -> Result<bool> {
+        let now = self.clock.now();
+        let result = sqlx::query(
+            r#"
+            UPDATE admin_sessions
+            SET last_used_at = ?, expires_at = ?
+            WHERE session_token = ? AND expires_at > ?
+            "#,
+        )
+        .bind(now)
+        .bind(now + Duration::hours(ADMIN_SESSION_DURATION_HOURS))
+        .bind(session_token)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .context("validating admin session")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Active admin sessions, most recently created first, marking the one making the request.
+    pub async fn list_admin_sessions(
+        &self,
+        current: &AdminSessionToken,
+    ) -> Result<Vec<AdminSession>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, session_token, created_at, last_used_at, expires_at
+            FROM admin_sessions
+            WHERE expires_at > ?
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(self.clock.now())
+        .fetch_all(&self.pool)
+        .await
+        .context("fetching admin sessions")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| AdminSession {
+                id: row.get("id"),
+                created_at: row.get("created_at"),
+                last_used_at: row.get("last_used_at"),
+                expires_at: row.get("expires_at"),
+                current: row.get::<AdminSessionToken, _>("session_token") == *current,
+            })
+            .collect())
+    }
+
+    /// Revoke an admin session by ID. Returns false if there's no such session.
+    pub async fn delete_admin_session_by_id(&self, id: AdminSessionId) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM admin_sessions WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("revoking admin session")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete an admin session (logout).
+    pub async fn delete_admin_session(&self, session_token: &AdminSessionToken) -> Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM admin_sessions
+            WHERE session_token = ?
+            "#,
+        )
+        .bind(session_token)
+        .execute(&self.pool)
+        .await
+        .context("deleting admin session")?;
+
+        Ok(())
+    }
+
+    /// Record a site admin password login attempt.
+    pub async fn record_site_admin_login_attempt(
+        &self,
+        outcome: LoginOutcome,
+        user_agent: Option<&str>,
+        ip_prefix: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO site_admin_login_attempts (id, outcome, ip_prefix, user_agent, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Ulid::new().to_string())
+        .bind(outcome.as_str())
+        .bind(ip_prefix)
+        .bind(user_agent)
+        .bind(self.clock.now())
+        .execute(&self.pool)
+        .await
+        .context("recording site admin login attempt")?;
+
+        Ok(())
+    }
+
+    /// Failed site admin logins since the last successful one, within the failure window, and
+    /// when the latest was. With an IP prefix, only attempts from that network count.
+    pub async fn site_admin_login_failures(
+        &self,
+        ip_prefix: Option<&str>,
+    ) -> Result<(u32, Option<DateTime<Utc>>)> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS failures, MAX(created_at) AS last_failure
+            FROM site_admin_login_attempts
+            WHERE outcome IN (?, ?)
+              AND created_at > ?
+              AND (? IS NULL OR ip_prefix = ?)
+              AND created_at > COALESCE((
+                  SELECT MAX(created_at)
+                  FROM site_admin_login_attempts
+                  WHERE outcome = ? AND (? IS NULL OR ip_prefix = ?)
+              ), '')
+            "#,
+        )
+        .bind(LoginOutcome::WrongPassword.as_str())
+        .bind(LoginOutcome::WrongCode.as_str())
+        .bind(self.clock.now() - Duration::hours(LOGIN_FAILURE_WINDOW_HOURS))
+        .bind(ip_prefix)
+        .bind(ip_prefix)
+        .bind(LoginOutcome::Success.as_str())
+        .bind(ip_prefix)
+        .bind(ip_prefix)
+        .fetch_one(&self.pool)
+        .await
+        .context("counting failed site admin logins")?;
+
+        let failures = u32::try_from(row.get::<i64, _>("failures")).unwrap_or(u32::MAX);
+        Ok((failures, row.get("last_failure")))
+    }
+
+    /// The most recent site admin login attempts, newest first.
+    pub async fn list_site_admin_login_attempts(
+        &self,
+        limit: u32,
+    ) -> Result<Vec<SiteAdminLoginAttempt>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT outcome, ip_prefix, user_agent, created_at
+            FROM site_admin_login_attempts
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("fetching site admin login attempts")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| SiteAdminLoginAttempt {
+                outcome: row.get("outcome"),
+                ip_prefix: row.get("ip_prefix"),
+                user_agent: row.get("user_agent"),
+                created_at: row.get("created_at"),
+            })
+            .collect())
+    }
+
+    /// Delete login attempts past their retention. Returns the number of attempts deleted.
+    pub async fn cleanup_old_site_admin_login_attempts(&self) -> Result<u64> {
+        let cutoff = self.clock.now() - Duration::days(LOGIN_ATTEMPT_RETENTION_DAYS);
+        let result = sqlx::query("DELETE FROM site_admin_login_attempts WHERE created_at < ?")
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await
+            .context("cleaning up site admin login attempts")?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Clean up expired admin sessions. Returns the number of sessions deleted.
+    pub async fn cleanup_expired_admin_sessions(&self) -> Result<u64> {
+        let now = self.clock.now();
+        let result = sqlx::query(
+            r#"
+            DELETE FROM admin_sessions
+            WHERE expires_at < ?
+            "#,
+        )
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .context("cleaning up expired admin sessions")?;
+
+        Ok(result.rows_affected())
+    }
+
+    // Site admin two-step verification functions
+
+    /// Whether logging in as site admin requires a one-time password.
+    pub async fn site_admin_totp_enabled(&self) -> Result<bool> {
+        let enabled: Option<bool> =
+            sqlx::query_scalar("SELECT totp_enabled FROM site_admin_password WHERE id = 1")
+                .fetch_optional(&self.pool)
+                .await
+                .context("fetching site admin two-step verification status")?;
+        Ok(enabled.unwrap_or(false))
+    }
+
+    /// Store a new TOTP secret for the site admin, to be enabled once a code from it is
+    /// confirmed. Returns false if two-step verification is already enabled.
+    pub async fn start_site_admin_totp_enrollment(&self, secret: &str) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE site_admin_password
+            SET totp_secret = ?, totp_last_step = NULL
+            WHERE id = 1 AND totp_enabled = 0
+            "#,
+        )
+        .bind(secret)
+        .execute(&self.pool)
+        .await
+        .context("storing site admin TOTP secret")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Check a one-time password against the site admin's TOTP secret, enabled or not.
+    /// A code is only accepted once.
+    pub async fn check_site_admin_totp(&self, code: &str) -> Result<bool> {
+        let row = sqlx::query(
+            r#"
+            SELECT totp_secret, totp_last_step
+            FROM site_admin_password
+            WHERE id = 1
+            "#,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .context("fetching site admin TOTP secret")?;
+
+        let Some(row) = row else {
+            return Ok(false);
+        };
+        let Some(secret) = row.get::<Option<String>, _>("totp_secret") else {
+            return Ok(false);
+        };
+        let last_step: Option<i64> = row.get("totp_last_step");
+        let Some(step) = totp::verify(&secret, code, self.clock.now(), last_step) else {
+            return Ok(false);
+        };
+
+        // Conditional, so two requests can't both use the same code
+        let result = sqlx::query(
+            r#"
+            UPDATE site_admin_password
+            SET totp_last_step = ?
+            WHERE id = 1 AND (totp_last_step IS NULL OR totp_last_step < ?)
+            "#,
+        )
+        .bind(step)
+        .bind(step)
+        .execute(&self.pool)
+        .await
+        .context("recording used site admin TOTP code")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Use up one of the site admin's recovery codes. Returns false if it doesn't match any
+    /// unused code.
+    pub async fn use_site_admin_recovery_code(&self, code: &str) -> Result<bool> {
+        let code = totp::normalize_recovery_code(code);
+        let rows = sqlx::query(
+            r#"
+            SELECT id, code_hash
+            FROM site_admin_recovery_codes
+            WHERE used_at IS NULL
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("fetching site admin recovery codes")?;
+
+        let id = rows.iter().find_map(|row| {
+            let code_hash: TokenHash = row.get("code_hash");
+            code_hash.matches(&code).then(|| row.get::<String, _>("id"))
+        });
+        let Some(id) = id else {
+            return Ok(false);
+        };
+
+        let result = sqlx::query(
+            r#"
+            UPDATE site_admin_recovery_codes
+            SET used_at = ?
+            WHERE id = ? AND used_at IS NULL
+            "#,
+        )
+        .bind(self.clock.now())
+        .bind(&id)
+        .execute(&self.pool)
+        .await
+        .context("using site admin recovery code")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Check the second step of a site admin login: a one-time password or a recovery code.
+    pub async fn verify_site_admin_second_factor(&self, code: &str) -> Result<bool> {
+        if totp::is_totp_code(code) {
+            self.check_site_admin_totp(code).await
+        } else {
+            self.use_site_admin_recovery_code(code).await
+        }
+    }
+
+    /// Require a one-time password at login from now on, replacing any recovery codes with
+    /// new ones, which are returned so they can be shown once.
+    pub async fn enable_site_admin_totp(&self) -> Result<Vec<String>> {
+        let codes = totp::generate_recovery_codes();
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE site_admin_password SET totp_enabled = 1 WHERE id = 1")
+            .execute(&mut *tx)
+            .await
+            .context("enabling site admin two-step verification")?;
+        sqlx::query("DELETE FROM site_admin_recovery_codes")
+            .execute(&mut *tx)
+            .await
+            .context("deleting old site admin recovery codes")?;
+        for code in &codes {
+            sqlx::query(
+                r#"
+                INSERT INTO site_admin_recovery_codes (id, code_hash)
+                VALUES (?, ?)
+                "#,
+            )
+            .bind(Ulid::new().to_string())
+            .bind(TokenHash::new(&totp::normalize_recovery_code(code)))
+            .execute(&mut *tx)
+            .await
+            .context("storing site admin recovery code")?;
+        }
+
+        tx.commit().await?;
+        tracing::info!("site admin two-step verification enabled");
+        Ok(codes)
+    }
+
+    /// Turn off two-step verification, forgetting the secret and recovery codes.
+    pub async fn disable_site_admin_totp(&self) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE site_admin_password
+            SET totp_secret = NULL, totp_enabled = 0, totp_last_step = NULL
+            WHERE id = 1
+            "#,
+        )
+        .execute(&mut *tx)
+        .await
+        .context("disabling site admin two-step verification")?;
+        sqlx::query("DELETE FROM site_admin_recovery_codes")
+            .execute(&mut *tx)
+            .await
+            .context("deleting site admin recovery codes")?;
+
+        tx.commit().await?;
+        tracing::info!("site admin two-step verification disabled");
+        Ok(())
+    }
+
+    /// Number of recovery codes the site admin hasn't used yet.
+    pub async fn count_unused_site_admin_recovery_codes(&self) -> Result<u64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM site_admin_recovery_codes WHERE used_at IS NULL",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("counting site admin recovery codes")?;
+        u64::try_from(count).context("converting recovery code count to u64")
+    }
+
+    // Site admin passkey functions
+
+    /// Store the state of a started passkey ceremony under its challenge, forgetting
+    /// expired ones.
+    pub async fn store_webauthn_challenge(
+        &self,
+        challenge: &str,
+        ceremony: Ceremony,
+        state: &str,
+    ) -> Result<()> {
+        let now = self.clock.now();
+        sqlx::query("DELETE FROM webauthn_challenges WHERE expires_at <= ?")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .context("deleting expired WebAuthn challenges")?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO webauthn_challenges (challenge, ceremony, state, expires_at)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(challenge)
+        .bind(ceremony.as_str())
+        .bind(state)
+        .bind(now + Duration::minutes(webauthn::CHALLENGE_DURATION_MINUTES))
+        .execute(&self.pool)
+        .await
+        .context("storing WebAuthn challenge")?;
+
+        Ok(())
+    }
+
+    /// Use up a challenge, returning the state of its ceremony. Returns None if it wasn't
+    /// issued for `ceremony`, has expired or was already used.
+    pub async fn take_webauthn_challenge(
+        &self,
+        challenge: &str,
+        ceremony: Ceremony,
+    ) -> Result<Option<String>> {
+        sqlx::query_scalar(
+            r#"
+            DELETE FROM webauthn_challenges
+            WHERE challenge = ? AND ceremony = ? AND expires_at > ?
+            RETURNING state
+            "#,
+        )
+        .bind(challenge)
+        .bind(ceremony.as_str())
+        .bind(self.clock.now())
+        .fetch_optional(&self.pool)
+        .await
+        .context("taking WebAuthn challenge")
+    }
+
+    /// Passkeys registered for site admin login, oldest first.
+    pub async fn list_site_admin_passkeys(&self) -> Result<Vec<SiteAdminPasskey>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, created_at, last_used_at
+            FROM site_admin_passkeys
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("fetching site admin passkeys")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| SiteAdminPasskey {
+                id: row.get("id"),
+                name: row.get("name"),
+                created_at: row.get("created_at"),
+                last_used_at: row.get("last_used_at"),
+            })
+            .collect())
+    }
+
+    /// The site admin's passkeys, for starting a ceremony.
+    pub async fn site_admin_passkey_credentials(&self) -> Result<Vec<PasskeyCredential>> {
+        let rows = sqlx::query("SELECT id, passkey FROM site_admin_passkeys ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await
+            .context("fetching site admin passkey credentials")?;
+
+        rows.iter().map(passkey_credential_from_row).collect()
+    }
+
+    /// Store a passkey checked at registration.
+    pub async fn add_site_admin_passkey(
+        &self,
+        credential: &Passkey,
+        name: &str,
+    ) -> Result<SiteAdminPasskey> {
+        let passkey = SiteAdminPasskey {
+            id: PasskeyId::new(),
+            name: name.to_string(),
+            created_at: self.clock.now(),
+            last_used_at: None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO site_admin_passkeys (id, credential_id, passkey, name, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(passkey.id)
+        .bind(BASE64_URL_SAFE_NO_PAD.encode(credential.cred_id()))
+        .bind(serde_json::to_string(credential).context("serializing passkey")?)
+        .bind(&passkey.name)
+        .bind(passkey.created_at)
+        .execute(&self.pool)
+        .await
+        .context("storing site admin passkey")?;
+
+        tracing::info!("site admin passkey registered");
+        Ok(passkey)
+    }
+
+    /// The stored passkey with the given credential ID (base64url-encoded), for checking a
+    /// login.
+    pub async fn get_site_admin_passkey_credential(
+        &self,
+        credential_id: &str,
+    ) -> Result<Option<PasskeyCredential>> {
+        let row =
+            sqlx::query("SELECT id, passkey FROM site_admin_passkeys WHERE credential_id = ?")
+                .bind(credential_id)
+                .fetch_optional(&self.pool)
+                .await
+                .context("fetching site admin passkey")?;
+
+        row.as_ref().map(passkey_credential_from_row).transpose()
+    }
+
+    /// Record a login with a passkey, storing the passkey again if the login updated it
+    /// (e.g. its signature counter).
+    pub async fn record_site_admin_passkey_use(
+        &self,
+        passkey_id: PasskeyId,
+        updated: Option<&Passkey>,
+    ) -> Result<()> {
+        let updated = updated
+            .map(serde_json::to_string)
+            .transpose()
+            .context("serializing passkey")?;
+        sqlx::query(
+            r#"
+            UPDATE site_admin_passkeys
+            SET passkey = COALESCE(?, passkey), last_used_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(updated)
+        .bind(self.clock.now())
+        .bind(passkey_id)
+        .execute(&self.pool)
+        .await
+        .context("recording site admin passkey use")?;
+
+        Ok(())
+    }
+
+    /// Remove a passkey. Returns false if there's no passkey with this ID.
+    pub async fn delete_site_admin_passkey(&self, passkey_id: PasskeyId) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM site_admin_passkeys WHERE id = ?")
+            .bind(passkey_id)
+            .execute(&self.pool)
+            .await
+            .context("deleting site admin passkey")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Email ban functions
+
+    /// Bans an address or domain. Returns `None` if it's banned already.
+    pub async fn create_email_ban(
+        &self,
+        value: &str,
+        reason: Option<&str>,
+    ) -> Result<Option<EmailBan>> {
+        let ban = EmailBan {
+            id: EmailBanId::new(),
+            value: value.to_string(),
+            reason: reason.map(str::to_string),
+            created_at: self.clock.now(),
+        };
+        let result = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO email_bans (id, value, reason, created_at)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(ban.id)
+        .bind(&ban.value)
+        .bind(&ban.reason)
+        .bind(ban.created_at)
+        .execute(&self.pool)
+        .await
+        .context("creating email ban")?;
+
+        Ok((result.rows_affected() > 0).then_some(ban))
+    }
+
+    /// Every ban, newest first.
+    pub async fn list_email_bans(&self) -> Result<Vec<EmailBan>> {
+        let rows = sqlx::query(
+            "SELECT id, value, reason, created_at FROM email_bans ORDER BY created_at DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("listing email bans")?;
+
+        Ok(rows.iter().map(email_ban_from_row).collect())
+    }
+
+    /// Changes the reason of a ban. Returns `None` if there's no such ban.
+    pub async fn update_email_ban(
+        &self,
+        ban_id: EmailBanId,
+        reason: Option<&str>,
+    ) -> Result<Option<EmailBan>> {
+        let row = sqlx::query(
+            r#"
+            UPDATE email_bans
+            SET reason = ?
+            WHERE id = ?
+            RETURNING id, value, reason, created_at
+            "#,
+        )
+        .bind(reason)
+        .bind(ban_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("updating email ban")?;
+
+        Ok(row.as_ref().map(email_ban_from_row))
+    }
+
+    pub async fn delete_email_ban(&self, ban_id: EmailBanId) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM email_bans WHERE id = ?")
+            .bind(ban_id)
+            .execute(&self.pool)
+            .await
+            .context("deleting email ban")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The ban covering an address, if any: of the address itself, its domain or a parent
+    /// domain.
+    pub async fn find_email_ban(&self, email: &EmailAddress) -> Result<Option<EmailBan>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, value, reason, created_at
+            FROM email_bans
+            WHERE value = ?1 OR value = ?2 OR substr(?2, -length(value) - 1) = '.' || value
+            LIMIT 1
+            "#,
+        )
+        .bind(email)
+        .bind(email.domain().to_ascii_lowercase())
+        .fetch_optional(&self.pool)
+        .await
+        .context("finding email ban")?;
+
+        Ok(row.as_ref().map(email_ban_from_row))
+    }
+
+    // Organizer magic-link login functions
+
+    /// All games organized by the given email address (case-insensitive), newest first.
+    pub async fn get_games_by_organizer_email(&self, email: &EmailAddress) -> Result<Vec<Game>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn,
+                allowed_email_domain, frozen_at, frozen_reason, deleted_at,
+                organizer_message, event_location, reveal_at, timezone, locale, locked,
+                reveal_expires_after_event, reveal_single_use, cover_image, drawn_at,
+                reminders_enabled, wrap_up_enabled, wrap_up_sent_at,
+                participant_email_subject, participant_email_body, event_reminder_days,
+                digest_enabled, digest_sent_at, reply_to_organizer, open_tracking_enabled
+            FROM games
+            WHERE organizer_email = ? COLLATE NOCASE AND deleted_at IS NULL
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(email)
+        .fetch_all(&self.pool)
+        .await
+        .context("fetching games by organizer email")?;
+
+        Ok(rows.iter().map(game_from_row).collect())
+    }
+
+    /// Count games organized by the given email address (case-insensitive).
+    pub async fn count_games_by_organizer_email(&self, email: &EmailAddress) -> Result<u64> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) as count
+            FROM games
+            WHERE organizer_email = ? COLLATE NOCASE AND deleted_at IS NULL
+            "#,
+        )
+        .bind(email)
+        .fetch_one(&self.pool)
+        .await
+        .context("counting games by organizer email")?;
+
+        let count: i64 = row.get("count");
+        u64::try_from(count).context("converting game count to u64")
+    }
+
+    /// Create a magic login link for an organizer. The optional game is where the organizer
+    /// is sent after logging in.
+    pub async fn create_organizer_login_link(
+        &self,
+        email: &EmailAddress,
+        game_id: Option<GameId>,
+    ) -> Result<OrganizerLoginToken> {
+        let token = OrganizerLoginToken::generate();
+        let id = Ulid::new().to_string();
+        let created_at = self.clock.now();
+        let expires_at =
+            created_at + Duration::minutes(organizer_auth::LOGIN_LINK_DURATION_MINUTES);
+
+        sqlx::query(
+            r#"
+            INSERT INTO organizer_login_links (id, token, email, game_id, created_at, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&token)
+        .bind(email)
+        .bind(game_id)
+        .bind(created_at)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .context("creating organizer login link")?;
+
+        Ok(token)
+    }
+
+    /// Login links requested for an organizer email (case-insensitive) since `since`.
+    pub async fn recent_organizer_login_links(
+        &self,
+        email: &EmailAddress,
+        since: DateTime<Utc>,
+    ) -> Result<RecentRequests> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS count, MIN(created_at) AS oldest
+            FROM organizer_login_links
+            WHERE email = ? COLLATE NOCASE AND created_at > ?
+            "#,
+        )
+        .bind(email)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .context("counting recent organizer login links")?;
+
+        Ok(RecentRequests {
+            count: row.get("count"),
+            oldest: row.get("oldest"),
+        })
+    }
+
+    /// Whether a login link can still be used, without using it.
+    pub async fn organizer_login_link_is_valid(&self, token: &OrganizerLoginToken) -> Result<bool> {
+        let row = sqlx::query(
+            r#"
+            SELECT 1 FROM organizer_login_links
+            WHERE token = ? AND used = 0 AND expires_at > ?
+            "#,
+        )
+        .bind(token)
+        .bind(self.clock.now())
+        .fetch_optional(&self.pool)
+        .await
+        .context("checking organizer login link")?;
+
+        Ok(row.is_some())
+    }
+
+    /// Mark a login link as used, returning its email and target game if it was still valid.
+    pub async fn consume_organizer_login_link(
+        &self,
+        token: &OrganizerLoginToken,
+    ) -> Result<Option<(EmailAddress, Option<GameId>)>> {
+        // Single statement so the same link can't be used twice concurrently.
+        let row = sqlx::query(
+            r#"
+            UPDATE organizer_login_links
+            SET used = 1
+            WHERE token = ? AND used = 0 AND expires_at > ?
+            RETURNING email, game_id
+            "#,
+        )
+        .bind(token)
+        .bind(self.clock.now())
+        .fetch_optional(&self.pool)
+        .await
+        .context("consuming organizer login link")?;
+
+        Ok(row.map(|r| (r.get("email"), r.get("game_id"))))
+    }
+
+    /// Create a new organizer session for the given email and return the session token.
+    pub async fn create_organizer_session(
+        &self,
+        email: &EmailAddress,
+    ) -> Result<OrganizerSessionToken> {
+        let session_token = OrganizerSessionToken::generate();
+        let id = Ulid::new().to_string();
+        let created_at = self.clock.now();
+        let expires_at = created_at + Duration::days(organizer_auth::SESSION_DURATION_DAYS);
+
+        sqlx::query(
+            r#"
+            INSERT INTO organizer_sessions (id, session_token, email, created_at, expires_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&session_token)
+        .bind(email)
+        .bind(created_at)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await
+        .context("creating organizer session")?;
+
+        Ok(session_token)
+    }
+
+    /// Returns the email of a valid (non-expired) organizer session.
+    pub async fn get_organizer_session_email(
+        &self,
+        session_token: &OrganizerSessionToken,
+    ) -> Result<Option<EmailAddress>> {
+        let row = sqlx::query(
+            r#"
+            SELECT email
+            FROM organizer_sessions
+            WHERE session_token = ? AND expires_at > ?
+            "#,
+        )
+        .bind(session_token)
+        .bind(self.clock.now())
+        .fetch_optional(&self.pool)
+        .await
+        .context("fetching organizer session")?;
+
+        Ok(row.map(|r| r.get("email")))
+    }
+
+    /// Delete an organizer session (logout).
+    pub async fn delete_organizer_session(
+        &self,
+        session_token: &OrganizerSessionToken,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM organizer_sessions
+            WHERE session_token = ?
+            "#,
+        )
+        .bind(session_token)
+        .execute(&self.pool)
+        .await
+        .context("deleting organizer session")?;
+
+        Ok(())
+    }
+
+    /// Clean up expired organizer sessions and login links. Returns the number of rows deleted.
+    pub async fn cleanup_expired_organizer_sessions(&self) -> Result<u64> {
+        let now = self.clock.now();
+        let links = sqlx::query(
+            r#"
+            DELETE FROM organizer_login_links
+            WHERE expires_at < ?
+            "#,
+        )
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .context("cleaning up expired organizer login links")?;
+
+        let sessions = sqlx::query(
+            r#"
+            DELETE FROM organizer_sessions
+            WHERE expires_at < ?
+            "#,
+        )
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .context("cleaning up expired organizer sessions")?;
+
+        Ok(links.rows_affected() + sessions.rows_affected())
+    }
+
+    /// Claims an idempotency key for a request, unless it was already used. A claim left by
+    /// a request that never finished (because the server stopped) can be taken over once
+    /// it's older than `IDEMPOTENCY_CLAIM_TIMEOUT`.
+    pub async fn claim_idempotency_key(
+        &self,
+        key_hash: &str,
+        request_hash: &str,
+    ) -> Result<IdempotencyKeyState> {
+        let now = self.clock.now();
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (key_hash, request_hash, response, created_at)
+            VALUES (?, ?, NULL, ?)
+            ON CONFLICT (key_hash) DO UPDATE SET
+                request_hash = excluded.request_hash,
+                created_at = excluded.created_at
+            WHERE response IS NULL AND created_at < ?
+            "#,
+        )
+        .bind(key_hash)
+        .bind(request_hash)
+        .bind(now)
+        .bind(now - IDEMPOTENCY_CLAIM_TIMEOUT)
+        .execute(&self.pool)
+        .await
+        .context("claiming idempotency key")?
+        .rows_affected()
+            > 0;
+        if claimed {
+            return Ok(IdempotencyKeyState::Claimed);
+        }
+
+        let row =
+            sqlx::query("SELECT request_hash, response FROM idempotency_keys WHERE key_hash = ?")
+                .bind(key_hash)
+                .fetch_one(&self.pool)
+                .await
+                .context("reading idempotency key")?;
+        let response: Option<Vec<u8>> = row.get("response");
+        Ok(IdempotencyKeyState::Used {
+            request_hash: row.get("request_hash"),
+            response,
+        })
+    }
+
+    /// Stores the response to the request that claimed an idempotency key.
+    pub async fn complete_idempotency_key(&self, key_hash: &str, response: &[u8]) -> Result<()> {
+        sqlx::query("UPDATE idempotency_keys SET response = ? WHERE key_hash = ?")
+            .bind(response)
+            .bind(key_hash)
+            .execute(&self.pool)
+            .await
+            .context("storing idempotent response")?;
+        Ok(())
+    }
+
+    /// Frees an idempotency key whose request failed in a way worth retrying.
+    pub async fn release_idempotency_key(&self, key_hash: &str) -> Result<()> {
+        sqlx::query("DELETE FROM idempotency_keys WHERE key_hash = ? AND response IS NULL")
+            .bind(key_hash)
+            .execute(&self.pool)
+            .await
+            .context("releasing idempotency key")?;
+        Ok(())
+    }
+
+    /// Forgets idempotency keys older than `IDEMPOTENCY_KEY_RETENTION`. Returns how many.
+    pub async fn cleanup_idempotency_keys(&self) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE created_at < ?")
+            .bind(self.clock.now() - IDEMPOTENCY_KEY_RETENTION)
+            .execute(&self.pool)
+            .await
+            .context("cleaning up idempotency keys")?;
+        Ok(result.rows_affected())
+    }
+
+    // Site admin game management functions
+
+    /// Search for games by name, organizer email, or game ID.
+    /// Returns paginated results ordered by created_at DESC.
+    pub async fn search_games(
+        &self,
+        search: Option<&str>,
+        limit: u32,
+        offset: u64,
+    ) -> Result<Vec<Game>> {
+        // Convert to i64 for SQLite binding
+        let limit_i64 = i64::from(limit);
+        let offset_i64 = i64::try_from(offset).context("offset too large for database")?;
+
+        let query = if let Some(search_term) = search {
+            sqlx::query(
+                r#"
+                SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn,
+                    allowed_email_domain, frozen_at, frozen_reason, deleted_at,
+                organizer_message, event_location, reveal_at, timezone, locale, locked,
+                reveal_expires_after_event, reveal_single_use, cover_image, drawn_at,
+                reminders_enabled, wrap_up_enabled, wrap_up_sent_at,
+                participant_email_subject, participant_email_body, event_reminder_days,
+                digest_enabled, digest_sent_at, reply_to_organizer, open_tracking_enabled
+                FROM games
+                WHERE name LIKE ? OR organizer_email LIKE ? OR id LIKE ?
+                ORDER BY created_at DESC
+                LIMIT ? OFFSET ?
+                "#,
+            )
+            .bind(format!("%{}%", search_term))
+            .bind(format!("%{}%", search_term))
+            .bind(format!("%{}%", search_term))
+            .bind(limit_i64)
+            .bind(offset_i64)
+        } else {
+            sqlx::query(
+                r#"
+                SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn,
+                    allowed_email_domain, frozen_at, frozen_reason, deleted_at,
+                organizer_message, event_location, reveal_at, timezone, locale, locked,
+                reveal_expires_after_event, reveal_single_use, cover_image, drawn_at,
+                reminders_enabled, wrap_up_enabled, wrap_up_sent_at,
+                participant_email_subject, participant_email_body, event_reminder_days,
+                digest_enabled, digest_sent_at, reply_to_organizer, open_tracking_enabled
+                FROM games
+                ORDER BY created_at DESC
+                LIMIT ? OFFSET ?
+                "#,
+            )
+            .bind(limit_i64)
+            .bind(offset_i64)
+        };
+
+        let rows = query.fetch_all(&self.pool).await?;
+
+        Ok(rows.iter().map(game_from_row).collect())
+    }
+
+    /// Find games matching all of the given site admin cleanup filters, including deleted ones.
+    pub async fn find_games_for_cleanup(
+        &self,
+        undrawn_only: bool,
+        created_before: Option<DateTime<Utc>>,
+        organizer_domain: Option<&EmailDomain>,
+    ) -> Result<Vec<Game>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn,
+                allowed_email_domain, frozen_at, frozen_reason, deleted_at,
+                organizer_message, event_location, reveal_at, timezone, locale, locked,
+                reveal_expires_after_event, reveal_single_use, cover_image, drawn_at,
+                reminders_enabled, wrap_up_enabled, wrap_up_sent_at,
+                participant_email_subject, participant_email_body, event_reminder_days,
+                digest_enabled, digest_sent_at, reply_to_organizer, open_tracking_enabled
+            FROM games
+            WHERE (? = 0 OR drawn = 0)
+                AND (? IS NULL OR created_at < ?)
+                AND (? IS NULL
+                    OR LOWER(SUBSTR(organizer_email, INSTR(organizer_email, '@') + 1)) = ?)
+            ORDER BY created_at
+            "#,
+        )
+        .bind(undrawn_only)
+        .bind(created_before)
+        .bind(created_before)
+        .bind(organizer_domain)
+        .bind(organizer_domain)
+        .fetch_all(&self.pool)
+        .await
+        .context("finding games for cleanup")?;
+
+        Ok(rows.iter().map(game_from_row).collect())
+    }
+
+    /// Count total games matching search criteria.
+    pub async fn count_games(&self, search: Option<&str>) -> Result<u64> {
+        let row = if let Some(search_term) = search {
+            sqlx::query(
+                r#"
+                SELECT COUNT(*) as count
+                FROM games
+                WHERE name LIKE ? OR organizer_email LIKE ? OR id LIKE ?
+                "#,
+            )
+            .bind(format!("%{}%", search_term))
+            .bind(format!("%{}%", search_term))
+            .bind(format!("%{}%", search_term))
+            .fetch_one(&self.pool)
+            .await?
+        } else {
+            sqlx::query("SELECT COUNT(*) as count FROM games")
+                .fetch_one(&self.pool)
+                .await?
+        };
+
+        let count: i64 = row.get("count");
+        u64::try_from(count).context("converting game count to u64")
+    }
+}
+
+pub struct Transaction {
+    inner: sqlx::Transaction<'static, Sqlite>,
+}
+
+impl Transaction {
+    pub async fn commit(self) -> Result<()> {
+        self.inner
+            .commit()
+            .await
+            .context("committing transaction")?;
+        Ok(())
+    }
+
+    pub async fn get_game_by_id(&mut self, game_id: GameId) -> Result<Option<Game>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, event_date, organizer_email, admin_token, created_at, drawn,
+                allowed_email_domain, frozen_at, frozen_reason, deleted_at,
+                organizer_message, event_location, reveal_at, timezone, locale, locked,
+                reveal_expires_after_event, reveal_single_use, cover_image, drawn_at,
+                reminders_enabled, wrap_up_enabled, wrap_up_sent_at,
+                participant_email_subject, participant_email_body, event_reminder_days,
+                digest_enabled, digest_sent_at, reply_to_organizer, open_tracking_enabled
+            FROM games
+            WHERE id = ? AND deleted_at IS NULL
+            "#,
+        )
+        .bind(game_id)
+        .fetch_optional(&mut *self.inner)
+        .await?;
+
+        Ok(row.as_ref().map(game_from_row))
+    }
+
+    pub async fn get_participants_by_game(&mut self, game_id: GameId) -> Result<Vec<Participant>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, game_id, name, email, matched_with_id, has_viewed, created_at
+            FROM participants
+            WHERE game_id = ?
+            ORDER BY created_at ASC
+        "#,
+        )
+        .bind(game_id)
+        .fetch_all(&mut *self.inner)
+        .await?;
+
+        Ok(rows.iter().map(participant_from_row).collect())
+    }
+
+    pub async fn update_participant_matches(
+        &mut self,
+        matches: &[(ParticipantId, ParticipantId)],
+    ) -> Result<()> {
+        for (participant_id, matched_with_id) in matches {
+            sqlx::query(
+                r#"
+                UPDATE participants
+                SET matched_with_id = ?
+                WHERE id = ?
+                "#,
+            )
+            .bind(matched_with_id)
+            .bind(participant_id)
+            .execute(&mut *self.inner)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Queues an email to each of a game's participants, returning the job that tracks them.
+    pub async fn create_email_job(
+        &mut self,
+        game_id: GameId,
+        template: EmailTemplate,
+        participants: &[Participant],
+        created_at: DateTime<Utc>,
+    ) -> Result<EmailJobId> {
+        let job_id = EmailJobId::new();
+        sqlx::query(
+            "INSERT INTO email_jobs (id, game_id, template, created_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(job_id)
+        .bind(game_id)
+        .bind(template.as_str())
+        .bind(created_at)
+        .execute(&mut *self.inner)
+        .await
+        .context("creating email job")?;
+
+        for participant in participants {
+            sqlx::query("INSERT INTO email_job_recipients (job_id, participant_id) VALUES (?, ?)")
+                .bind(job_id)
+                .bind(participant.id)
+                .execute(&mut *self.inner)
+                .await
+                .context("queueing email job recipient")?;
+        }
+
+        Ok(job_id)
+    }
+
+    pub async fn count_participants_in_game(&mut self, game_id: GameId) -> Result<u64> {
+        count_participants(&mut self.inner, game_id).await
+    }
+
+    pub async fn create_game(&mut self, game: &Game) -> Result<()> {
+        insert_game(&mut self.inner, game).await
+    }
+
+    pub async fn add_participant(&mut self, participant: &Participant) -> Result<()> {
+        insert_participant(&mut self.inner, participant).await
+    }
+
+    pub async fn mark_game_as_drawn(
+        &mut self,
+        game_id: GameId,
+        drawn_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE games
+            SET drawn = 1, drawn_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(drawn_at)
+        .bind(game_id)
+        .execute(&mut *self.inner)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{FixedClock, SystemClock};
+    use crate::models::{Game, Participant, ReminderKind};
+    use crate::token::{AdminToken, VerificationCode, VerificationLinkToken};
+    use chrono::{NaiveDate, TimeZone};
+    use std::collections::HashMap;
+
+    /// Create an in-memory database for testing.
+    async fn setup_test_db() -> Database {
+        setup_test_db_with_clock(Arc::new(SystemClock)).await
+    }
+
+    async fn setup_test_db_with_clock(clock: Arc<dyn Clock>) -> Database {
+        let pool = init_db(":memory:").await.unwrap();
+        Database { pool, clock }
+    }
+
+    /// Removes a test database file when dropped.
+    struct TestFile(std::path::PathBuf);
+
+    impl Drop for TestFile {
+        fn drop(&mut self) {
+            for suffix in ["", "-wal", "-shm"] {
+                let _ = std::fs::remove_file(format!("{}{suffix}", self.0.display()));
+            }
+        }
+    }
+
+    /// Create a database in a temporary file, for what doesn't work in memory: in-memory
+    /// databases open the files of `VACUUM INTO` and `ATTACH` in memory too.
+    async fn setup_file_test_db() -> (Database, TestFile) {
+        let path = std::env::temp_dir().join(format!("amigo-oculto-{}.db", Ulid::new()));
+        let pool = init_db(&format!("sqlite://{}", path.display()))
+            .await
+            .unwrap();
+        let db = Database {
+            pool,
+            clock: Arc::new(SystemClock),
+        };
+        (db, TestFile(path))
+    }
+
+    /// What the organizer sees of each participant of a game.
+    async fn participant_statuses(
+        db: &Database,
+        game_id: GameId,
+    ) -> HashMap<ParticipantId, ParticipantStatus> {
+        let status = db
+            .game_summary(game_id, &ParticipantFilter::default())
+            .await
+            .unwrap()
+            .unwrap();
+        status.participants.into_iter().map(|p| (p.id, p)).collect()
+    }
+
+    /// Create a test game with a specific event_date.
+    fn create_test_game(name: &str, event_date: NaiveDate) -> Game {
+        Game {
+            id: GameId::new(),
+            name: name.to_string(),
+            event_date,
+            organizer_email: format!("{}@test.com", name).parse().unwrap(),
+            admin_token_hash: AdminToken::generate().hash(),
+            created_at: Utc::now(),
+            drawn: false,
+            allowed_email_domain: None,
+            frozen_at: None,
+            frozen_reason: None,
+            deleted_at: None,
+            organizer_message: None,
+            event_location: None,
+            reveal_at: None,
+            timezone: GameTimezone::default(),
+            locale: Locale::default(),
+            locked: false,
+            reveal_expires_after_event: false,
+            reveal_single_use: false,
+            cover_image: None,
+            drawn_at: None,
+            reminders_enabled: true,
+            wrap_up_enabled: false,
+            wrap_up_sent_at: None,
+            participant_email_subject: None,
+            participant_email_body: None,
+            event_reminder_days: Vec::new(),
+            digest_enabled: false,
+            digest_sent_at: None,
+            reply_to_organizer: false,
+            open_tracking_enabled: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_old_games_deletes_expired() {
+        let db = setup_test_db().await;
+
+        // Create a game with event_date > 90 days ago
+        let old_date = Utc::now().date_naive() - Duration::days(100);
+        let old_game = create_test_game("old_game", old_date);
+        db.create_game(&old_game).await.unwrap();
+
+        // Verify game exists
+        let found = db.get_game_by_id(old_game.id).await.unwrap();
+        assert!(found.is_some());
+
+        // Run cleanup
+        let deleted = db.cleanup_old_games().await.unwrap();
+        assert_eq!(deleted, 1);
+
+        // Verify game was deleted
+        let found = db.get_game_by_id(old_game.id).await.unwrap();
+        assert!(found.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_old_games_preserves_recent() {
+        let db = setup_test_db().await;
+
+        // Create a game with event_date within retention period
+        let recent_date = Utc::now().date_naive() - Duration::days(30);
+        let recent_game = create_test_game("recent_game", recent_date);
+        db.create_game(&recent_game).await.unwrap();
+
+        // Run cleanup
+        let deleted = db.cleanup_old_games().await.unwrap();
+        assert_eq!(deleted, 0);
+
+        // Verify game still exists
+        let found = db.get_game_by_id(recent_game.id).await.unwrap();
+        assert!(found.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_old_games_preserves_future() {
+        let db = setup_test_db().await;
+
+        // Create a game with event_date in the future
+        let future_date = Utc::now().date_naive() + Duration::days(30);
+        let future_game = create_test_game("future_game", future_date);
+        db.create_game(&future_game).await.unwrap();
+
+        // Run cleanup
+        let deleted = db.cleanup_old_games().await.unwrap();
+        assert_eq!(deleted, 0);
+
+        // Verify game still exists
+        let found = db.get_game_by_id(future_game.id).await.unwrap();
+        assert!(found.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_old_games_returns_correct_count() {
+        let db = setup_test_db().await;
+
+        // Create 3 expired games
+        for i in 0..3 {
+            let old_date = Utc::now().date_naive() - Duration::days(100 + i);
+            let game = create_test_game(&format!("old_{}", i), old_date);
+            db.create_game(&game).await.unwrap();
+        }
+
+        // Create 2 recent games
+        for i in 0..2 {
+            let recent_date = Utc::now().date_naive() - Duration::days(10 + i);
+            let game = create_test_game(&format!("recent_{}", i), recent_date);
+            db.create_game(&game).await.unwrap();
+        }
+
+        // Run cleanup
+        let deleted = db.cleanup_old_games().await.unwrap();
+        assert_eq!(deleted, 3);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_old_games_boundary() {
+        let db = setup_test_db().await;
+
+        // Create a game exactly at the boundary (should NOT be deleted)
+        let boundary_date =
+            Utc::now().date_naive() - Duration::days(DEFAULT_GAME_RETENTION_DAYS.into());
+        let boundary_game = create_test_game("boundary_game", boundary_date);
+        db.create_game(&boundary_game).await.unwrap();
+
+        // Create a game one day past the boundary (should be deleted)
+        let past_boundary =
+            Utc::now().date_naive() - Duration::days(DEFAULT_GAME_RETENTION_DAYS as i64 + 1);
+        let old_game = create_test_game("old_game", past_boundary);
+        db.create_game(&old_game).await.unwrap();
+
+        // Run cleanup
+        let deleted = db.cleanup_old_games().await.unwrap();
+        assert_eq!(deleted, 1);
+
+        // Verify boundary game still exists
+        let found = db.get_game_by_id(boundary_game.id).await.unwrap();
+        assert!(found.is_some());
+
+        // Verify old game was deleted
+        let found = db.get_game_by_id(old_game.id).await.unwrap();
+        assert!(found.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_game_retention_setting() {
+        let db = setup_test_db().await;
+        assert_eq!(
+            db.get_game_retention_days().await.unwrap(),
+            DEFAULT_GAME_RETENTION_DAYS
+        );
+
+        let game = create_test_game(
+            "month_old_game",
+            Utc::now().date_naive() - Duration::days(31),
+        );
+        db.create_game(&game).await.unwrap();
+        assert_eq!(db.cleanup_old_games().await.unwrap(), 0);
+
+        db.set_game_retention_days(60).await.unwrap();
+        db.set_game_retention_days(30).await.unwrap();
+        assert_eq!(db.get_game_retention_days().await.unwrap(), 30);
+        assert_eq!(db.cleanup_old_games().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot() {
+        let (db, _file) = setup_file_test_db().await;
+        let game = create_test_game("snapshot_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+
+        let snapshot = db.snapshot().await.unwrap();
+        assert!(snapshot.starts_with(SQLITE_HEADER));
+    }
+
+    #[tokio::test]
+    async fn test_restore_snapshot() {
+        let (source, _source_file) = setup_file_test_db().await;
+        let game = create_test_game("restored_game", Utc::now().date_naive());
+        source.create_game(&game).await.unwrap();
+        let participant = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        source.add_participant(&participant).await.unwrap();
+        let snapshot = source.snapshot().await.unwrap();
+
+        let (db, _file) = setup_file_test_db().await;
+        let replaced = create_test_game("replaced_game", Utc::now().date_naive());
+        db.create_game(&replaced).await.unwrap();
+        let session = db.create_admin_session().await.unwrap();
+
+        assert_eq!(db.restore(&snapshot).await.unwrap(), 2);
+        assert!(db.get_game_by_id(game.id).await.unwrap().is_some());
+        assert!(db.get_game_by_id(replaced.id).await.unwrap().is_none());
+        assert_eq!(db.get_participants_by_game(game.id).await.unwrap().len(), 1);
+        // The site admin doing the restore stays logged in
+        assert!(db.validate_admin_session(&session).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_restore_rejects_invalid_snapshots() {
+        let (db, _file) = setup_file_test_db().await;
+        let game = create_test_game("kept_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+
+        assert!(db.restore(b"not a database").await.is_err());
+
+        let (source, _source_file) = setup_file_test_db().await;
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) \
+             VALUES (9999, 'unknown', 1, x'00', 0)",
+        )
+        .execute(&source.pool)
+        .await
+        .unwrap();
+        let diverged = source.snapshot().await.unwrap();
+        let error = db.restore(&diverged).await.unwrap_err();
+        assert!(format!("{error:#}").contains("migration 9999"));
+
+        assert!(db.get_game_by_id(game.id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_job_runs_keep_latest() {
+        let db = setup_test_db().await;
+        assert!(db.list_job_runs().await.unwrap().is_empty());
+
+        db.record_job_run("cleanup_games", std::time::Duration::from_millis(12), Ok(3))
+            .await
+            .unwrap();
+        db.record_job_run(
+            "cleanup_games",
+            std::time::Duration::from_millis(5),
+            Err("database is locked".to_string()),
+        )
+        .await
+        .unwrap();
+        db.record_job_run("send_reminders", std::time::Duration::ZERO, Ok(0))
+            .await
+            .unwrap();
+
+        let runs = db.list_job_runs().await.unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].job, "cleanup_games");
+        assert_eq!(runs[0].duration_ms, 5);
+        assert_eq!(runs[0].processed, None);
+        assert_eq!(runs[0].error.as_deref(), Some("database is locked"));
+        assert_eq!(runs[1].job, "send_reminders");
+        assert_eq!(runs[1].processed, Some(0));
+        assert_eq!(runs[1].error, None);
+    }
+
+    #[tokio::test]
+    async fn test_job_ran_within() {
+        let db = setup_test_db().await;
+        let week = Duration::days(7);
+        assert!(!db.job_ran_within("maintain_database", week).await.unwrap());
+
+        db.record_job_run("maintain_database", std::time::Duration::ZERO, Ok(0))
+            .await
+            .unwrap();
+        assert!(db.job_ran_within("maintain_database", week).await.unwrap());
+        assert!(!db.job_ran_within("backup_database", week).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_maintain_frees_pages() {
+        let db = setup_test_db().await;
+        assert_eq!(db.maintain().await.unwrap(), 0);
+
+        let game = create_test_game("big_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+        for i in 0..100 {
+            let participant = Participant::new(
+                game.id,
+                format!("{i} {}", "x".repeat(1000)),
+                format!("p{i}@test.com").parse().unwrap(),
+            );
+            db.add_participant(&participant).await.unwrap();
+        }
+        db.delete_game(game.id).await.unwrap();
+
+        assert!(db.maintain().await.unwrap() > 0);
+        let free_pages: i64 = sqlx::query_scalar("PRAGMA freelist_count")
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(free_pages, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_old_games_with_related_records() {
+        let db = setup_test_db().await;
+
+        // Create an expired game
+        let old_date = Utc::now().date_naive() - Duration::days(100);
+        let game = create_test_game("old_game", old_date);
+        db.create_game(&game).await.unwrap();
+
+        // Add participants
+        let participant1 = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        let participant2 =
+            Participant::new(game.id, "Bob".to_string(), "bob@test.com".parse().unwrap());
+        db.add_participant(&participant1).await.unwrap();
+        db.add_participant(&participant2).await.unwrap();
+
+        // Record email resends (references both game and participants)
+        db.record_email_resend(game.id, None, "admin_link")
+            .await
+            .unwrap();
+        db.record_email_resend(game.id, Some(participant1.id), "participant_reveal")
+            .await
+            .unwrap();
+
+        // Verify records exist
+        assert!(db.get_game_by_id(game.id).await.unwrap().is_some());
+        let participants = db.get_participants_by_game(game.id).await.unwrap();
+        assert_eq!(participants.len(), 2);
+
+        // Run cleanup - this should not fail with foreign key errors
+        let deleted = db.cleanup_old_games().await.unwrap();
+        assert_eq!(deleted, 1);
+
+        // Verify game and all related records are deleted
+        assert!(db.get_game_by_id(game.id).await.unwrap().is_none());
+        let participants = db.get_participants_by_game(game.id).await.unwrap();
+        assert!(participants.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_games_for_cleanup() {
+        let db = setup_test_db().await;
+        let today = Utc::now().date_naive();
+
+        let mut old_undrawn = create_test_game("old_undrawn", today);
+        old_undrawn.created_at = Utc::now() - Duration::days(40);
+        old_undrawn.organizer_email = "someone@Spam.example".parse().unwrap();
+        let mut old_drawn = create_test_game("old_drawn", today);
+        old_drawn.created_at = Utc::now() - Duration::days(40);
+        old_drawn.drawn = true;
+        let recent_undrawn = create_test_game("recent_undrawn", today);
+        for game in [&old_undrawn, &old_drawn, &recent_undrawn] {
+            db.create_game(game).await.unwrap();
+        }
+
+        let ids = |games: Vec<Game>| games.into_iter().map(|g| g.id).collect::<Vec<_>>();
+        let cutoff = Utc::now() - Duration::days(30);
+
+        let games = db.find_games_for_cleanup(true, Some(cutoff), None).await;
+        assert_eq!(ids(games.unwrap()), vec![old_undrawn.id]);
+
+        let games = db.find_games_for_cleanup(false, Some(cutoff), None).await;
+        assert_eq!(ids(games.unwrap()), vec![old_undrawn.id, old_drawn.id]);
+
+        let games = db.find_games_for_cleanup(true, None, None).await;
+        assert_eq!(ids(games.unwrap()), vec![old_undrawn.id, recent_undrawn.id]);
+
+        let domain: EmailDomain = "spam.example".parse().unwrap();
+        let games = db.find_games_for_cleanup(false, None, Some(&domain)).await;
+        assert_eq!(ids(games.unwrap()), vec![old_undrawn.id]);
+    }
+
+    #[tokio::test]
+    async fn test_site_stats() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let db = setup_test_db_with_clock(clock.clone()).await;
+        let today = clock.now().date_naive();
+
+        let old = Game {
+            created_at: clock.now() - Duration::days(1),
+            ..create_test_game("old_game", today)
+        };
+        db.create_game(&old).await.unwrap();
+        let game = Game {
+            created_at: clock.now(),
+            ..create_test_game("stats_game", today)
+        };
+        db.create_game(&game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        db.add_participant(&alice).await.unwrap();
+        db.mark_participant_viewed(alice.id).await.unwrap();
+        db.record_reveal_event(&alice, None, None).await.unwrap();
+        db.soft_delete_game(old.id).await.unwrap();
+
+        assert_eq!(
+            db.get_site_stats_totals().await.unwrap(),
+            SiteStatsTotals {
+                games: 1,
+                deleted_games: 1,
+                participants: 1,
+                viewed_participants: 1,
+                reveals: 1,
+                ..Default::default()
+            }
+        );
+
+        let trends = db.get_site_stats_trends(3).await.unwrap();
+        let dates: Vec<_> = trends.iter().map(|day| day.date).collect();
+        assert_eq!(
+            dates,
+            [today - Duration::days(2), today - Duration::days(1), today]
+        );
+        assert_eq!(
+            trends[0],
+            SiteStatsDay {
+                date: dates[0],
+                ..Default::default()
+            }
+        );
+        assert_eq!(trends[1].games_created, 1);
+        assert_eq!(trends[2].games_created, 1);
+        assert_eq!(trends[2].reveals, 1);
+
+        // Older activity is left out
+        let trends = db.get_site_stats_trends(1).await.unwrap();
+        assert_eq!(trends.len(), 1);
+        assert_eq!(trends[0].games_created, 1);
+    }
+
+    #[tokio::test]
+    async fn test_match_accesses() {
+        let db = setup_test_db().await;
+
+        let game = create_test_game("audited_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+        assert!(db.get_match_accesses(game.id).await.unwrap().is_empty());
+
+        db.record_match_access(game.id, "export").await.unwrap();
+        db.record_match_access(game.id, "reveal").await.unwrap();
+
+        let accesses = db.get_match_accesses(game.id).await.unwrap();
+        assert_eq!(accesses.len(), 2);
+        assert_eq!(accesses[0].access_type, "reveal");
+        assert_eq!(accesses[1].access_type, "export");
+    }
+
+    #[tokio::test]
+    async fn test_claim_and_revoke_view_tokens() {
+        let db = setup_test_db().await;
+
+        let game = create_test_game("token_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        db.add_participant(&alice).await.unwrap();
+
+        // Every email gets its own token, and all of them work
+        let first_token = db.create_view_token(alice.id).await.unwrap();
+        let second_token = db.create_view_token(alice.id).await.unwrap();
+        for token in [&first_token, &second_token] {
+            let found = db.get_participant_by_view_token(token).await.unwrap();
+            assert_eq!(found.unwrap().id, alice.id);
+        }
+        let guess = ViewToken::from(format!("{}{}", first_token.lookup_key(), "x".repeat(24)));
+        assert!(
+            db.get_participant_by_view_token(&guess)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        assert!(db.claim_view_token(alice.id).await.unwrap());
+        assert!(!db.claim_view_token(alice.id).await.unwrap());
+
+        db.revoke_view_tokens(alice.id).await.unwrap();
+        for token in [&first_token, &second_token] {
+            assert!(
+                db.get_participant_by_view_token(token)
+                    .await
+                    .unwrap()
+                    .is_none()
+            );
+        }
+        let new_token = db.create_view_token(alice.id).await.unwrap();
+        let reissued = db.get_participant_by_view_token(&new_token).await.unwrap();
+        assert_eq!(reissued.unwrap().id, alice.id);
+        assert!(db.claim_view_token(alice.id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_hash_plaintext_secrets() {
+        let db = setup_test_db().await;
+
+        // Secrets as stored by versions before hashing
+        let game = create_test_game("legacy_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        db.add_participant(&alice).await.unwrap();
+        let (verification, _, _) = EmailVerification::new(
+            "maria@test.com".parse().unwrap(),
+            "Natal".to_string(),
+            NaiveDate::from_ymd_opt(2025, 12, 24).unwrap(),
+            None,
+            GameTimezone::default(),
+            Locale::default(),
+            Utc::now(),
+        );
+        db.create_email_verification(&verification).await.unwrap();
+
+        let admin_token = AdminToken::generate();
+        let view_token = ViewToken::generate();
+        let code = VerificationCode::generate();
+        let link_token = VerificationLinkToken::generate();
+        sqlx::query("UPDATE games SET admin_token = ?")
+            .bind(&admin_token)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        // As copied from participants by the migration moving them to their own table
+        sqlx::query(
+            "INSERT INTO view_tokens (lookup_key, token_hash, participant_id, created_at) \
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(view_token.lookup_key())
+        .bind(&view_token)
+        .bind(alice.id)
+        .bind(Utc::now())
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        sqlx::query("UPDATE email_verifications SET code = ?, link_token = ?")
+            .bind(code)
+            .bind(&link_token)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        // As listed by the migration, twice to check listed hashes are left alone
+        for _ in 0..2 {
+            sqlx::query(
+                "INSERT OR IGNORE INTO plaintext_secrets (table_name, row_id) \
+                 SELECT 'games', rowid FROM games UNION ALL \
+                 SELECT 'view_tokens', rowid FROM view_tokens UNION ALL \
+                 SELECT 'email_verifications', rowid FROM email_verifications",
+            )
+            .execute(&db.pool)
+            .await
+            .unwrap();
+            hash_plaintext_secrets(&db.pool).await.unwrap();
+        }
+
+        let stored_game = db.get_game_by_id(game.id).await.unwrap().unwrap();
+        assert!(stored_game.admin_token_hash.matches(admin_token.as_str()));
+        let found = db.get_participant_by_view_token(&view_token).await.unwrap();
+        assert_eq!(found.unwrap().id, alice.id);
+        let stored_verification = db
+            .get_email_verification_by_id(verification.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(stored_verification.code_hash.matches(&code.to_string()));
+        assert!(
+            stored_verification
+                .link_token_hash
+                .unwrap()
+                .matches(link_token.as_str())
+        );
+
+        let plaintext: Vec<String> = sqlx::query_scalar(
+            "SELECT admin_token FROM games UNION ALL SELECT token_hash FROM view_tokens",
+        )
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+        assert!(!plaintext.contains(&admin_token.to_string()));
+        assert!(!plaintext.contains(&view_token.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_refuses_newer_database() {
+        let path = std::env::temp_dir().join(format!("amigo-oculto-{}.db", Ulid::new()));
+        let url = format!("sqlite://{}", path.display());
+
+        // A newer binary has migrated the database
+        let pool = init_db(&url).await.unwrap();
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) \
+             VALUES (?, 'newer', 1, x'00', 0)",
+        )
+        .bind(latest_migration() + 1)
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+
+        let error = init_db(&url).await.unwrap_err();
+        assert!(error.to_string().contains("refusing to start"));
+
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+    }
+
+    #[test]
+    fn test_migrations_are_numbered_in_order() {
+        let versions: Vec<i64> = MIGRATOR.iter().map(|m| m.version).collect();
+        assert_eq!(versions, (1..=latest_migration()).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_database_from_before_migrations() {
+        let path = std::env::temp_dir().join(format!("amigo-oculto-{}.db", Ulid::new()));
+        let url = format!("sqlite://{}", path.display());
+
+        // Tables as created on startup before migrations, with plaintext tokens and codes
+        let options = SqliteConnectOptions::from_str(&url)
+            .unwrap()
+            .create_if_missing(true);
+        let pool = SqlitePool::connect_with(options).await.unwrap();
+        let initial = MIGRATOR.iter().next().unwrap();
+        sqlx::raw_sql(&initial.sql).execute(&pool).await.unwrap();
+
+        let game_id = GameId::new();
+        let participant_id = ParticipantId::new();
+        let verification_id = VerificationId::new();
+        let admin_token = AdminToken::generate();
+        let view_token = ViewToken::generate();
+        let code = VerificationCode::generate();
+        sqlx::query("INSERT INTO games (id, name, event_date, organizer_email, admin_token, created_at) VALUES (?, 'Natal', '2025-12-24', 'maria@test.com', ?, ?)")
+            .bind(game_id)
+            .bind(&admin_token)
+            .bind(Utc::now())
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO participants (id, game_id, name, email, view_token, created_at) VALUES (?, ?, 'Alice', 'alice@test.com', ?, ?)")
+            .bind(participant_id)
+            .bind(game_id)
+            .bind(&view_token)
+            .bind(Utc::now())
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO email_resends (id, game_id, participant_id, resend_type, resent_at) VALUES ('resend', ?, ?, 'individual', ?)")
+            .bind(game_id)
+            .bind(participant_id)
+            .bind(Utc::now())
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO email_verifications (id, email, code, game_name, event_date, created_at, expires_at) VALUES (?, 'maria@test.com', ?, 'Natal', '2025-12-24', ?, ?)")
+            .bind(verification_id)
+            .bind(code)
+            .bind(Utc::now())
+            .bind(Utc::now() + Duration::minutes(15))
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+
+        let db = Database {
+            pool: init_db(&url).await.unwrap(),
+            clock: Arc::new(SystemClock),
+        };
+        let game = db.get_game_by_id(game_id).await.unwrap().unwrap();
+        assert_eq!(game.name, "Natal");
+        assert!(game.admin_token_hash.matches(admin_token.as_str()));
+        let found = db.get_participant_by_view_token(&view_token).await.unwrap();
+        assert_eq!(found.unwrap().id, participant_id);
+        let verification = db
+            .get_email_verification_by_id(verification_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(verification.code_hash.matches(&code.to_string()));
+        // Rebuilding the participants table kept the rows referring to it
+        let resends: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM email_resends")
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(resends, 1);
+        let columns: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_info('participants')")
+                .fetch_all(&db.pool)
+                .await
+                .unwrap();
+        assert!(!columns.contains(&"view_token".to_string()));
+        assert!(db.list_job_runs().await.unwrap().is_empty());
+        db.pool.close().await;
+
+        // Starting again finds the migrations applied
+        let pool = init_db(&url).await.unwrap();
+        pool.close().await;
+
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reveal_recovery() {
+        let db = setup_test_db().await;
+
+        let (recovery, code) = RevealRecovery::new("alice@test.com".parse().unwrap(), Utc::now());
+        db.create_reveal_recovery(&recovery).await.unwrap();
+        assert_eq!(
+            db.claim_reveal_recovery_attempt(recovery.id).await.unwrap(),
+            Some(1)
+        );
+        db.mark_reveal_recovery_as_verified(recovery.id)
+            .await
+            .unwrap();
+
+        let stored = db.get_reveal_recovery_by_id(recovery.id).await.unwrap();
+        let stored = stored.unwrap();
+        assert!(stored.code_hash.matches(&code.to_string()));
+        assert_eq!(stored.attempts, 1);
+        assert!(stored.verified);
+
+        for attempt in 2..=RevealRecovery::MAX_ATTEMPTS {
+            assert_eq!(
+                db.claim_reveal_recovery_attempt(recovery.id).await.unwrap(),
+                Some(attempt)
+            );
+        }
+        assert_eq!(
+            db.claim_reveal_recovery_attempt(recovery.id).await.unwrap(),
+            None
+        );
+
+        let email: EmailAddress = "ALICE@test.com".parse().unwrap();
+        let since = Utc::now() - Duration::hours(1);
+        assert_eq!(
+            db.recent_reveal_recoveries_by_email(&email, since)
+                .await
+                .unwrap()
+                .count,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_reveals_by_participant_email() {
+        let db = setup_test_db().await;
+
+        let undrawn = create_test_game("undrawn_game", Utc::now().date_naive());
+        let mut drawn = create_test_game("drawn_game", Utc::now().date_naive());
+        drawn.drawn = true;
+        db.create_game(&undrawn).await.unwrap();
+        db.create_game(&drawn).await.unwrap();
+
+        for game in [&undrawn, &drawn] {
+            let alice = Participant::new(
+                game.id,
+                "Alice".to_string(),
+                "alice@test.com".parse().unwrap(),
+            );
+            db.add_participant(&alice).await.unwrap();
+        }
+
+        let email: EmailAddress = "Alice@Test.com".parse().unwrap();
+        let reveals = db.find_reveals_by_participant_email(&email).await.unwrap();
+        assert_eq!(reveals.len(), 1);
+        assert_eq!(reveals[0].game_name, "drawn_game");
+
+        db.soft_delete_game(drawn.id).await.unwrap();
+        let reveals = db.find_reveals_by_participant_email(&email).await.unwrap();
+        assert!(reveals.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_transactions_take_the_write_lock() {
+        // In-memory databases lock tables instead of waiting for each other
+        let (db, _file) = setup_file_test_db().await;
+        let game = create_test_game("locked_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+
+        let mut tx = db.begin().await.unwrap();
+        let other = tokio::spawn({
+            let db = db.clone();
+            async move {
+                let mut tx = db.begin().await.unwrap();
+                let count = tx.count_participants_in_game(game.id).await.unwrap();
+                tx.commit().await.unwrap();
+                count
+            }
+        });
+
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        assert_eq!(tx.count_participants_in_game(game.id).await.unwrap(), 0);
+        tx.add_participant(&alice).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(!other.is_finished());
+        tx.commit().await.unwrap();
+
+        // The other transaction only started after the participant was added
+        assert_eq!(other.await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_game_in_transaction() {
+        let db = setup_test_db().await;
+        let game = create_test_game("imported_game", Utc::now().date_naive());
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+
+        // Dropping the transaction leaves neither the game nor its participants
+        let mut tx = db.begin().await.unwrap();
+        tx.create_game(&game).await.unwrap();
+        tx.add_participant(&alice).await.unwrap();
+        drop(tx);
+        assert!(db.get_game_by_id(game.id).await.unwrap().is_none());
+        assert!(db.get_participant_by_id(alice.id).await.unwrap().is_none());
+
+        let mut tx = db.begin().await.unwrap();
+        tx.create_game(&game).await.unwrap();
+        tx.add_participant(&alice).await.unwrap();
+        tx.commit().await.unwrap();
+        assert!(db.get_game_by_id(game.id).await.unwrap().is_some());
+        assert_eq!(db.get_participants_by_game(game.id).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_hot_queries_use_indexes() {
+        let db = setup_test_db().await;
+        let plans = db.explain_hot_queries().await.unwrap();
+        assert_eq!(plans.len(), HOT_QUERIES.len());
+        for plan in &plans {
+            // A SCAN without an index reads the whole table, unless it's the already filtered
+            // result of a subquery
+            let materialized: Vec<_> = plan
+                .plan
+                .iter()
+                .filter_map(|step| step.trim_start().strip_prefix("MATERIALIZE "))
+                .collect();
+            let scans: Vec<_> = plan
+                .plan
+                .iter()
+                .filter_map(|step| step.trim_start().strip_prefix("SCAN "))
+                .filter(|scan| !scan.contains("INDEX") && !materialized.contains(scan))
+                .collect();
+            assert!(scans.is_empty(), "{}: {:?}", plan.query, plan.plan);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_game_summary() {
+        let db = setup_test_db().await;
+        assert!(
+            db.game_summary(GameId::new(), &ParticipantFilter::default())
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let game = create_test_game("summary_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+        let status = db
+            .game_summary(game.id, &ParticipantFilter::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(status.game.name, "summary_game");
+        assert!(status.participants.is_empty());
+        assert_eq!((status.viewed_count, status.bulk_resend_count), (0, 0));
+
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        let bob = Participant::new(game.id, "Bob".to_string(), "bob@test.com".parse().unwrap());
+        db.add_participant(&alice).await.unwrap();
+        db.add_participant(&bob).await.unwrap();
+        db.mark_participant_viewed(bob.id).await.unwrap();
+        db.record_email_resend(game.id, None, "bulk").await.unwrap();
+        db.record_email_resend(game.id, Some(alice.id), "individual")
+            .await
+            .unwrap();
+        db.record_email_resend(game.id, Some(alice.id), "reissue")
+            .await
+            .unwrap();
+
+        let status = db
+            .game_summary(game.id, &ParticipantFilter::default())
+            .await
+            .unwrap()
+            .unwrap();
+        let names: Vec<_> = status
+            .participants
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, ["Alice", "Bob"]);
+        assert_eq!(status.participants[0].resend_count, 2);
+        assert_eq!(status.participants[1].resend_count, 0);
+        assert!(status.participants[1].has_viewed);
+        assert_eq!((status.viewed_count, status.bulk_resend_count), (1, 1));
+
+        db.soft_delete_game(game.id).await.unwrap();
+        assert!(
+            db.game_summary(game.id, &ParticipantFilter::default())
+                .await
+                .unwrap()
+                .is_none()
+        );
+        let status = db
+            .game_summary_including_deleted(game.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(status.participants.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_game_revision() {
+        let db = setup_test_db().await;
+        assert_eq!(db.game_revision(GameId::new()).await.unwrap(), None);
+
+        let game = create_test_game("revision_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+        let mut last = db.game_revision(game.id).await.unwrap().unwrap();
+        let mut assert_changed = async |db: &Database| {
+            let revision = db.game_revision(game.id).await.unwrap().unwrap();
+            assert!(revision > last);
+            last = revision;
+        };
+
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        db.add_participant(&alice).await.unwrap();
+        assert_changed(&db).await;
+        db.update_game_locked(game.id, true).await.unwrap();
+        assert_changed(&db).await;
+        db.mark_participant_viewed(alice.id).await.unwrap();
+        assert_changed(&db).await;
+        db.record_reveal_event(&alice, None, None).await.unwrap();
+        assert_changed(&db).await;
+        db.record_reveal_device(alice.id, "device").await.unwrap();
+        assert_changed(&db).await;
+        db.record_email_resend(game.id, Some(alice.id), "individual")
+            .await
+            .unwrap();
+        assert_changed(&db).await;
+
+        db.soft_delete_game(game.id).await.unwrap();
+        assert_eq!(db.game_revision(game.id).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_game_summary_filters_participants() {
+        let db = setup_test_db().await;
+        let game = create_test_game("paged_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+        let mut participants = Vec::new();
+        for name in ["Alice", "Bob", "Carol", "Dave", "Eve"] {
+            let participant = Participant::new(
+                game.id,
+                name.to_string(),
+                format!("{}@test.com", name.to_lowercase()).parse().unwrap(),
+            );
+            db.add_participant(&participant).await.unwrap();
+            participants.push(participant);
+        }
+        db.mark_participant_viewed(participants[1].id)
+            .await
+            .unwrap();
+        db.mark_participant_viewed(participants[3].id)
+            .await
+            .unwrap();
+
+        let summary = |filter: ParticipantFilter| {
+            let db = db.clone();
+            async move {
+                let status = db.game_summary(game.id, &filter).await.unwrap().unwrap();
+                let names: Vec<_> = status.participants.into_iter().map(|p| p.name).collect();
+                (names, status.matching_count)
+            }
+        };
+
+        let page = |limit, offset| ParticipantFilter {
+            viewed: None,
+            limit: Some(limit),
+            offset,
+        };
+        assert_eq!(
+            summary(page(2, 0)).await,
+            (vec!["Alice".into(), "Bob".into()], 5)
+        );
+        assert_eq!(summary(page(2, 4)).await, (vec!["Eve".into()], 5));
+        assert_eq!(summary(page(2, 6)).await, (vec![], 5));
+
+        let viewed = |viewed| ParticipantFilter {
+            viewed: Some(viewed),
+            ..Default::default()
+        };
+        assert_eq!(
+            summary(viewed(true)).await,
+            (vec!["Bob".into(), "Dave".into()], 2)
+        );
+        assert_eq!(
+            summary(viewed(false)).await,
+            (vec!["Alice".into(), "Carol".into(), "Eve".into()], 3)
+        );
+
+        // Counts cover the whole game, even when the page is empty
+        let status = db
+            .game_summary(
+                game.id,
+                &ParticipantFilter {
+                    viewed: Some(true),
+                    limit: Some(10),
+                    offset: 10,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(status.participants.is_empty());
+        assert_eq!(
+            (
+                status.participant_count,
+                status.matching_count,
+                status.viewed_count
+            ),
+            (5, 2, 2)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_count_viewed_participants_in_game() {
+        let db = setup_test_db().await;
+
+        let game = create_test_game("viewed_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        let bob = Participant::new(game.id, "Bob".to_string(), "bob@test.com".parse().unwrap());
+        db.add_participant(&alice).await.unwrap();
+        db.add_participant(&bob).await.unwrap();
+        assert_eq!(
+            db.count_viewed_participants_in_game(game.id).await.unwrap(),
+            0
+        );
+
+        db.mark_participant_viewed(alice.id).await.unwrap();
+        assert_eq!(
+            db.count_viewed_participants_in_game(game.id).await.unwrap(),
+            1
+        );
+        assert_eq!(db.count_participants_in_game(game.id).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_update_game_allowed_email_domain() {
+        let db = setup_test_db().await;
+
+        let game = create_test_game("corp_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+
+        let domain: EmailDomain = "@Empresa.com.br".parse().unwrap();
+        db.update_game_allowed_email_domain(game.id, Some(&domain))
+            .await
+            .unwrap();
+        let found = db.get_game_by_id(game.id).await.unwrap().unwrap();
+        assert_eq!(found.allowed_email_domain.as_ref(), Some(&domain));
+        assert_eq!(domain.as_str(), "empresa.com.br");
+
+        db.update_game_allowed_email_domain(game.id, None)
+            .await
+            .unwrap();
+        let found = db.get_game_by_id(game.id).await.unwrap().unwrap();
+        assert!(found.allowed_email_domain.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_game_cover_image() {
+        let db = setup_test_db().await;
+
+        let game = create_test_game("cover_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+
+        let key = MediaKey::generate(crate::storage::ImageType::Png);
+        db.update_game_cover_image(game.id, Some(&key))
+            .await
+            .unwrap();
+        let updated = db.get_game_by_id(game.id).await.unwrap().unwrap();
+        assert_eq!(updated.cover_image, Some(key));
+
+        db.update_game_cover_image(game.id, None).await.unwrap();
+        let updated = db.get_game_by_id(game.id).await.unwrap().unwrap();
+        assert_eq!(updated.cover_image, None);
+    }
+
+    #[tokio::test]
+    async fn test_update_game_locked() {
+        let db = setup_test_db().await;
+
+        let game = create_test_game("locked_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+        assert!(!db.get_game_by_id(game.id).await.unwrap().unwrap().locked);
+
+        db.update_game_locked(game.id, true).await.unwrap();
+        assert!(db.get_game_by_id(game.id).await.unwrap().unwrap().locked);
+
+        db.update_game_locked(game.id, false).await.unwrap();
+        assert!(!db.get_game_by_id(game.id).await.unwrap().unwrap().locked);
+    }
+
+    #[tokio::test]
+    async fn test_update_game_reveal_at() {
+        let db = setup_test_db().await;
+
+        let game = create_test_game("scheduled_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+
+        let reveal_at = "2030-12-24T20:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        db.update_game_reveal_at(game.id, Some(reveal_at))
+            .await
+            .unwrap();
+        let found = db.get_game_by_id(game.id).await.unwrap().unwrap();
+        assert_eq!(found.reveal_at, Some(reveal_at));
+
+        db.update_game_reveal_at(game.id, None).await.unwrap();
+        let found = db.get_game_by_id(game.id).await.unwrap().unwrap();
+        assert!(found.reveal_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_game_organizer_message() {
+        let db = setup_test_db().await;
+
+        let game = create_test_game("message_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+
+        db.update_game_organizer_message(game.id, Some("Valor máximo: R$ 50"))
+            .await
+            .unwrap();
+        let found = db.get_game_by_id(game.id).await.unwrap().unwrap();
+        assert_eq!(
+            found.organizer_message.as_deref(),
+            Some("Valor máximo: R$ 50")
+        );
+
+        db.update_game_organizer_message(game.id, None)
+            .await
+            .unwrap();
+        let found = db.get_game_by_id(game.id).await.unwrap().unwrap();
+        assert!(found.organizer_message.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_games_by_organizer_email() {
+        let db = setup_test_db().await;
+        let event_date = Utc::now().date_naive();
+
+        let mut first = create_test_game("first", event_date);
+        first.organizer_email = "Ana@example.com".parse().unwrap();
+        first.created_at = Utc::now() - Duration::days(1);
+        db.create_game(&first).await.unwrap();
+
+        let mut second = create_test_game("second", event_date);
+        second.organizer_email = "ana@example.com".parse().unwrap();
+        db.create_game(&second).await.unwrap();
+
+        let mut other = create_test_game("other", event_date);
+        other.organizer_email = "bia@example.com".parse().unwrap();
+        db.create_game(&other).await.unwrap();
+
+        let email: EmailAddress = "ANA@example.com".parse().unwrap();
+        let games = db.get_games_by_organizer_email(&email).await.unwrap();
+        let ids: Vec<_> = games.iter().map(|g| g.id).collect();
+        assert_eq!(ids, vec![second.id, first.id]);
+    }
+
+    #[tokio::test]
+    async fn test_abuse_reports() {
+        let db = setup_test_db().await;
+
+        let game = create_test_game("reported_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+
+        let one_hour_ago = Utc::now() - Duration::hours(1);
+        let report_id = db.create_abuse_report(game.id, "spam").await.unwrap();
+        db.create_abuse_report(game.id, "more spam").await.unwrap();
+        let recent = db
+            .recent_abuse_reports(game.id, one_hour_ago)
+            .await
+            .unwrap();
+        assert_eq!(recent.count, 2);
+        let retry_after = recent
+            .retry_after(2, Duration::hours(1), Utc::now())
+            .unwrap();
+        assert!(retry_after > Duration::minutes(59) && retry_after <= Duration::hours(1));
+        assert_eq!(recent.retry_after(3, Duration::hours(1), Utc::now()), None);
+
+        let reports = db.get_unresolved_abuse_reports().await.unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].game_name, "reported_game");
+        assert!(!reports[0].game_frozen);
+
+        assert!(db.resolve_abuse_report(report_id).await.unwrap());
+        let reports = db.get_unresolved_abuse_reports().await.unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].reason, "more spam");
+
+        // Reports are removed along with their game
+        db.delete_game(game.id).await.unwrap();
+        assert!(db.get_unresolved_abuse_reports().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete_and_restore_game() {
+        let db = setup_test_db().await;
+
+        let game = create_test_game("deleted_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+
+        db.soft_delete_game(game.id).await.unwrap();
+        assert!(db.get_game_by_id(game.id).await.unwrap().is_none());
+        let found = db
+            .get_game_by_id_including_deleted(game.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(found.deleted_at.is_some());
+
+        assert!(db.restore_game(game.id).await.unwrap());
+        assert!(db.get_game_by_id(game.id).await.unwrap().is_some());
+        assert!(!db.restore_game(game.id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_purge_deleted_games() {
+        let db = setup_test_db().await;
+        let event_date = Utc::now().date_naive() + Duration::days(30);
+
+        let old = create_test_game("old_deleted", event_date);
+        db.create_game(&old).await.unwrap();
+        let recent = create_test_game("recent_deleted", event_date);
+        db.create_game(&recent).await.unwrap();
+        let kept = create_test_game("kept", event_date);
+        db.create_game(&kept).await.unwrap();
+
+        db.soft_delete_game(recent.id).await.unwrap();
+        sqlx::query("UPDATE games SET deleted_at = ? WHERE id = ?")
+            .bind(Utc::now() - Duration::days(DELETED_GAME_RETENTION_DAYS as i64 + 1))
+            .bind(old.id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        assert_eq!(db.purge_deleted_games().await.unwrap(), 1);
+        assert!(
+            db.get_game_by_id_including_deleted(old.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            db.get_game_by_id_including_deleted(recent.id)
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(db.get_game_by_id(kept.id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_freeze_and_unfreeze_game() {
+        let db = setup_test_db().await;
+
+        let game = create_test_game("frozen_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+
+        db.freeze_game(game.id, "spam").await.unwrap();
+        let found = db.get_game_by_id(game.id).await.unwrap().unwrap();
+        assert!(found.frozen_at.is_some());
+        assert_eq!(found.frozen_reason.as_deref(), Some("spam"));
+
+        db.unfreeze_game(game.id).await.unwrap();
+        let found = db.get_game_by_id(game.id).await.unwrap().unwrap();
+        assert!(found.frozen_at.is_none());
+        assert!(found.frozen_reason.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_idle_games() {
+        let db = setup_test_db().await;
+        let event_date = Utc::now().date_naive() + Duration::days(60);
+        let long_ago = Utc::now() - Duration::days(IDLE_GAME_RETENTION_DAYS as i64 + 1);
+
+        // Idle and never drawn: should be deleted
+        let mut idle_game = create_test_game("idle_game", event_date);
+        idle_game.created_at = long_ago;
+        db.create_game(&idle_game).await.unwrap();
+
+        // Idle but drawn: left to the event-based retention
+        let mut drawn_game = create_test_game("drawn_game", event_date);
+        drawn_game.created_at = long_ago;
+        drawn_game.drawn = true;
+        db.create_game(&drawn_game).await.unwrap();
+
+        // Old but recently touched: should be kept
+        let mut active_game = create_test_game("active_game", event_date);
+        active_game.created_at = long_ago;
+        db.create_game(&active_game).await.unwrap();
+        db.touch_game_activity(active_game.id).await.unwrap();
+
+        let deleted = db.cleanup_idle_games().await.unwrap();
+        assert_eq!(deleted, 1);
+
+        assert!(db.get_game_by_id(idle_game.id).await.unwrap().is_none());
+        assert!(db.get_game_by_id(drawn_game.id).await.unwrap().is_some());
+        assert!(db.get_game_by_id(active_game.id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_last_viewed_times() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let db = setup_test_db_with_clock(clock.clone()).await;
+
+        let game = create_test_game("viewed_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        let bob = Participant::new(game.id, "Bob".to_string(), "bob@test.com".parse().unwrap());
+        db.add_participant(&alice).await.unwrap();
+        db.add_participant(&bob).await.unwrap();
+        let statuses = participant_statuses(&db, game.id).await;
+        assert!(statuses.values().all(|p| p.last_viewed_at.is_none()));
+
+        db.record_reveal_event(&alice, Some("Firefox"), Some("203.0.113.0/24"))
+            .await
+            .unwrap();
+        clock.advance(Duration::hours(1));
+        db.record_reveal_event(&alice, None, None).await.unwrap();
+
+        let statuses = participant_statuses(&db, game.id).await;
+        assert_eq!(statuses[&alice.id].last_viewed_at, Some(clock.now()));
+        assert_eq!(statuses[&bob.id].last_viewed_at, None);
+    }
+
+    #[tokio::test]
+    async fn test_reveal_device_counts() {
+        let db = setup_test_db().await;
+
+        let game = create_test_game("devices_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        let bob = Participant::new(game.id, "Bob".to_string(), "bob@test.com".parse().unwrap());
+        db.add_participant(&alice).await.unwrap();
+        db.add_participant(&bob).await.unwrap();
+
+        db.record_reveal_device(alice.id, "phone").await.unwrap();
+        db.record_reveal_device(alice.id, "phone").await.unwrap();
+        db.record_reveal_device(alice.id, "laptop").await.unwrap();
+        db.record_reveal_device(bob.id, "phone").await.unwrap();
+
+        let statuses = participant_statuses(&db, game.id).await;
+        assert_eq!(statuses[&alice.id].device_count, 2);
+        assert_eq!(statuses[&bob.id].device_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_find_due_reminders() {
+        let start = Utc::now();
+        let clock = Arc::new(FixedClock::new(start));
+        let db = setup_test_db_with_clock(clock.clone()).await;
+
+        let mut game = create_test_game("reminder_game", start.date_naive() + Duration::days(10));
+        game.drawn = true;
+        game.drawn_at = Some(start);
+        db.create_game(&game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        let bob = Participant::new(game.id, "Bob".to_string(), "bob@test.com".parse().unwrap());
+        db.add_participant(&alice).await.unwrap();
+        db.add_participant(&bob).await.unwrap();
+        db.mark_participant_viewed(bob.id).await.unwrap();
+
+        let due_ids = async |kind| {
+            let due = db.find_due_reminders(kind).await.unwrap();
+            due.into_iter().map(|p| p.id).collect::<Vec<_>>()
+        };
+
+        assert!(due_ids(ReminderKind::AfterDraw).await.is_empty());
+        clock.advance(Duration::days(REMINDER_DAYS_AFTER_DRAW) + Duration::hours(1));
+        assert_eq!(due_ids(ReminderKind::AfterDraw).await, vec![alice.id]);
+        assert!(due_ids(ReminderKind::BeforeEvent).await.is_empty());
+
+        // Each reminder is only sent once
+        db.record_reminder_sent(&alice, ReminderKind::AfterDraw)
+            .await
+            .unwrap();
+        assert!(due_ids(ReminderKind::AfterDraw).await.is_empty());
+
+        clock.advance(Duration::days(6));
+        assert_eq!(due_ids(ReminderKind::BeforeEvent).await, vec![alice.id]);
+
+        db.update_game_reminders_enabled(game.id, false)
+            .await
+            .unwrap();
+        assert!(due_ids(ReminderKind::BeforeEvent).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_due_event_reminders() {
+        let start = Utc.with_ymd_and_hms(2025, 12, 1, 15, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock::new(start));
+        let db = setup_test_db_with_clock(clock.clone()).await;
+
+        let mut game = create_test_game(
+            "event_reminder_game",
+            start.date_naive() + Duration::days(10),
+        );
+        game.drawn = true;
+        game.drawn_at = Some(start);
+        game.event_reminder_days = vec![7, 1];
+        db.create_game(&game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        db.add_participant(&alice).await.unwrap();
+        db.mark_participant_viewed(alice.id).await.unwrap();
+
+        let due = async || {
+            let games = db.find_games_due_event_reminders().await.unwrap();
+            games
+                .into_iter()
+                .map(|(game, days)| (game.id, days))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(due().await.is_empty());
+        clock.advance(Duration::days(3));
+        assert_eq!(due().await, vec![(game.id, 7)]);
+
+        // Participants who saw their match are reminded too, once
+        let participants = db
+            .find_due_event_reminder_participants(game.id, 7)
+            .await
+            .unwrap();
+        assert_eq!(participants.len(), 1);
+        db.record_event_reminder_sent(&alice, 7).await.unwrap();
+        assert!(
+            db.find_due_event_reminder_participants(game.id, 7)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        clock.advance(Duration::days(6));
+        assert_eq!(due().await, vec![(game.id, 1)]);
+        db.update_game_event_reminder_days(game.id, &[])
+            .await
+            .unwrap();
+        assert!(due().await.is_empty());
+
+        // Nothing is due once the event has passed
+        db.update_game_event_reminder_days(game.id, &[7, 1])
+            .await
+            .unwrap();
+        clock.advance(Duration::days(2));
+        assert!(due().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_email_jobs() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let db = setup_test_db_with_clock(clock.clone()).await;
+        let game = create_test_game("email_job_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        let bob = Participant::new(game.id, "Bob".to_string(), "bob@test.com".parse().unwrap());
+        db.add_participant(&alice).await.unwrap();
+        db.add_participant(&bob).await.unwrap();
+
+        let mut tx = db.begin().await.unwrap();
+        let job_id = tx
+            .create_email_job(
+                game.id,
+                EmailTemplate::ParticipantNotification,
+                &[alice.clone(), bob.clone()],
+                Utc::now(),
+            )
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        let unfinished = db.find_unfinished_email_jobs().await.unwrap();
+        assert_eq!(unfinished.len(), 1);
+        assert_eq!((unfinished[0].id, unfinished[0].total), (job_id, 2));
+        assert_eq!(
+            unfinished[0].template,
+            Some(EmailTemplate::ParticipantNotification)
+        );
+
+        assert!(
+            db.record_email_job_result(job_id, alice.id, true)
+                .await
+                .unwrap()
+        );
+        let pending = db.get_pending_email_job_recipients(job_id).await.unwrap();
+        assert_eq!(pending.iter().map(|p| p.id).collect::<Vec<_>>(), [bob.id]);
+
+        // A failed email waits for its retry, and fails for good after the last attempt
+        for attempt in 1..=EMAIL_JOB_ATTEMPTS {
+            let done = db
+                .record_email_job_result(job_id, bob.id, false)
+                .await
+                .unwrap();
+            assert_eq!(done, attempt == EMAIL_JOB_ATTEMPTS);
+            if done {
+                break;
+            }
+            assert!(
+                db.get_pending_email_job_recipients(job_id)
+                    .await
+                    .unwrap()
+                    .is_empty()
+            );
+            let retry_at = db.next_email_job_retry(Some(job_id)).await.unwrap();
+            assert_eq!(retry_at, Some(clock.now() + EMAIL_JOB_RETRY_DELAY));
+            assert_eq!(db.next_email_job_retry(None).await.unwrap(), retry_at);
+
+            clock.advance(EMAIL_JOB_RETRY_DELAY);
+            let pending = db.get_pending_email_job_recipients(job_id).await.unwrap();
+            assert_eq!(pending.iter().map(|p| p.id).collect::<Vec<_>>(), [bob.id]);
+        }
+        assert_eq!(db.next_email_job_retry(Some(job_id)).await.unwrap(), None);
+        db.finish_email_job(job_id).await.unwrap();
+        let job = db.get_email_job(job_id).await.unwrap().unwrap();
+        assert_eq!((job.total, job.sent, job.failed), (2, 1, 1));
+        assert!(job.finished_at.is_some());
+        assert!(db.find_unfinished_email_jobs().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_email_bans() {
+        let db = setup_test_db().await;
+        let banned = |email: &str| {
+            let email: EmailAddress = email.parse().unwrap();
+            let db = db.clone();
+            async move { db.find_email_ban(&email).await.unwrap().is_some() }
+        };
+
+        let spammer = db
+            .create_email_ban("spammer@gmail.com", Some("spam"))
+            .await
+            .unwrap()
+            .unwrap();
+        db.create_email_ban("abuse.example", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(
+            db.create_email_ban("SPAMMER@gmail.com", None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        assert!(banned("spammer@gmail.com").await);
+        assert!(banned("Spammer@Gmail.com").await);
+        assert!(!banned("someone@gmail.com").await);
+        assert!(banned("someone@abuse.example").await);
+        assert!(banned("someone@mail.ABUSE.example").await);
+        assert!(!banned("someone@notabuse.example").await);
+
+        let updated = db
+            .update_email_ban(spammer.id, Some("phishing"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.reason.as_deref(), Some("phishing"));
+        assert_eq!(db.list_email_bans().await.unwrap().len(), 2);
+
+        assert!(db.delete_email_ban(spammer.id).await.unwrap());
+        assert!(!db.delete_email_ban(spammer.id).await.unwrap());
+        assert!(!banned("spammer@gmail.com").await);
+    }
+
+    #[tokio::test]
+    async fn test_announcements() {
+        let start = Utc.with_ymd_and_hms(2025, 12, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock::new(start));
+        let db = setup_test_db_with_clock(clock.clone()).await;
+        let next_week = start.date_naive() + Duration::days(7);
+
+        let older = Game {
+            organizer_email: "alice@test.com".parse().unwrap(),
+            created_at: start - Duration::days(1),
+            ..create_test_game("older", next_week)
+        };
+        let newer = Game {
+            organizer_email: "Alice@test.com".parse().unwrap(),
+            created_at: start,
+            locale: Locale::En,
+            ..create_test_game("newer", next_week)
+        };
+        let deleted = create_test_game("deleted", next_week);
+        let past = create_test_game("past", start.date_naive() - Duration::days(1));
+        let suppressed = create_test_game("suppressed", next_week);
+        for game in [&older, &newer, &deleted, &past, &suppressed] {
+            db.create_game(game).await.unwrap();
+        }
+        db.soft_delete_game(deleted.id).await.unwrap();
+        db.suppress_email(&suppressed.organizer_email, SuppressionReason::Unsubscribe)
+            .await
+            .unwrap();
+
+        // Alice once, in the language of her latest game
+        let recipients = db.get_announcement_recipients().await.unwrap();
+        assert_eq!(recipients.len(), 1);
+        assert_eq!(recipients[0].email, newer.organizer_email);
+        assert_eq!(recipients[0].locale, Locale::En);
+
+        let id = db
+            .create_announcement("Manutenção", "Fora do ar no sábado.", &recipients)
+            .await
+            .unwrap();
+        let unfinished = db.find_unfinished_announcements().await.unwrap();
+        assert_eq!(unfinished.len(), 1);
+        assert_eq!((unfinished[0].id, unfinished[0].total), (id, 1));
+
+        db.record_announcement_result(id, &recipients[0].email, true)
+            .await
+            .unwrap();
+        assert!(
+            db.get_pending_announcement_recipients(id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        db.finish_announcement(id).await.unwrap();
+        assert!(db.find_unfinished_announcements().await.unwrap().is_empty());
+        let announcements = db.list_announcements().await.unwrap();
+        assert_eq!((announcements[0].sent, announcements[0].failed), (1, 0));
+        assert!(announcements[0].finished_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_reset_draw() {
+        let db = setup_test_db().await;
+        let game = create_test_game("reset_draw_game", Utc::now().date_naive());
+        db.create_game(&game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        let bob = Participant::new(game.id, "Bob".to_string(), "bob@test.com".parse().unwrap());
+        db.add_participant(&alice).await.unwrap();
+        db.add_participant(&bob).await.unwrap();
+
+        let mut tx = db.begin().await.unwrap();
+        tx.update_participant_matches(&[(alice.id, bob.id), (bob.id, alice.id)])
+            .await
+            .unwrap();
+        tx.mark_game_as_drawn(game.id, Utc::now()).await.unwrap();
+        tx.commit().await.unwrap();
+        db.create_view_token(alice.id).await.unwrap();
+        db.mark_participant_viewed(alice.id).await.unwrap();
+        db.record_reveal_event(&alice, None, None).await.unwrap();
+        db.record_reminder_sent(&bob, ReminderKind::AfterDraw)
+            .await
+            .unwrap();
+
+        db.reset_draw(game.id).await.unwrap();
+
+        let game = db.get_game_by_id(game.id).await.unwrap().unwrap();
+        assert!(!game.drawn);
+        assert!(game.drawn_at.is_none());
+        for participant in db.get_participants_by_game(game.id).await.unwrap() {
+            assert!(participant.matched_with_id.is_none());
+            assert!(!participant.has_viewed);
+        }
+        let statuses = participant_statuses(&db, game.id).await;
+        assert!(statuses.values().all(|p| p.last_viewed_at.is_none()));
+        let view_tokens: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM view_tokens")
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(view_tokens, 0);
+    }
+
+    #[tokio::test]
+    async fn test_email_history() {
+        let start = Utc.with_ymd_and_hms(2025, 12, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock::new(start));
+        let db = setup_test_db_with_clock(clock.clone()).await;
+        let game = create_test_game("email_history_game", start.date_naive());
+        let other_game = create_test_game("other_game", start.date_naive());
+        db.create_game(&game).await.unwrap();
+        db.create_game(&other_game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        db.add_participant(&alice).await.unwrap();
+
+        let mut tx = db.begin().await.unwrap();
+        let first_job = tx
+            .create_email_job(
+                game.id,
+                EmailTemplate::ParticipantNotification,
+                std::slice::from_ref(&alice),
+                start,
+            )
+            .await
+            .unwrap();
+        let second_job = tx
+            .create_email_job(
+                game.id,
+                EmailTemplate::ParticipantNotification,
+                &[],
+                start + Duration::hours(1),
+            )
+            .await
+            .unwrap();
+        tx.create_email_job(
+            other_game.id,
+            EmailTemplate::ParticipantNotification,
+            &[],
+            start,
+        )
+        .await
+        .unwrap();
+        tx.commit().await.unwrap();
+        db.record_email_job_result(first_job, alice.id, true)
+            .await
+            .unwrap();
+
+        let jobs = db.get_email_jobs_by_game(game.id).await.unwrap();
+        assert_eq!(
+            jobs.iter().map(|j| j.id).collect::<Vec<_>>(),
+            [second_job, first_job]
+        );
+        assert_eq!((jobs[1].total, jobs[1].sent), (1, 1));
+
+        db.record_email_resend(game.id, Some(alice.id), "individual")
+            .await
+            .unwrap();
+        clock.advance(Duration::minutes(5));
+        db.record_email_resend(game.id, None, "bulk").await.unwrap();
+        db.record_email_resend(other_game.id, None, "bulk")
+            .await
+            .unwrap();
+
+        let resends = db.get_email_resends(game.id).await.unwrap();
+        assert_eq!(
+            resends
+                .iter()
+                .map(|r| (r.participant_id, r.resend_type.as_str()))
+                .collect::<Vec<_>>(),
+            [(None, "bulk"), (Some(alice.id), "individual")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wrap_up() {
+        // 09:00 on the day of the event, in São Paulo
+        let start = Utc.with_ymd_and_hms(2025, 12, 24, 12, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock::new(start));
+        let db = setup_test_db_with_clock(clock.clone()).await;
+
+        let mut game = create_test_game("wrap_up_game", start.date_naive());
+        game.drawn = true;
+        db.create_game(&game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        let bob = Participant::new(game.id, "Bob".to_string(), "bob@test.com".parse().unwrap());
+        db.add_participant(&alice).await.unwrap();
+        db.add_participant(&bob).await.unwrap();
+        db.mark_participant_viewed(bob.id).await.unwrap();
+        for _ in 0..2 {
+            db.record_reveal_event(&bob, None, None).await.unwrap();
+        }
+        db.record_reminder_sent(&alice, ReminderKind::AfterDraw)
+            .await
+            .unwrap();
+
+        let due_ids = async || {
+            let due = db.find_games_due_wrap_up().await.unwrap();
+            due.into_iter().map(|g| g.id).collect::<Vec<_>>()
+        };
+
+        // Only sent when the organizer asked for it
+        clock.advance(Duration::days(1));
+        assert!(due_ids().await.is_empty());
+        db.update_game_wrap_up_enabled(game.id, true).await.unwrap();
+        assert_eq!(due_ids().await, vec![game.id]);
+
+        // Not before the event is over in the game's time zone: 22:00 on the 24th
+        clock.advance(Duration::hours(-11));
+        assert!(due_ids().await.is_empty());
+        clock.advance(Duration::hours(3));
+        assert_eq!(due_ids().await, vec![game.id]);
+
+        assert_eq!(
+            db.get_wrap_up_stats(game.id).await.unwrap(),
+            WrapUpStats {
+                participant_count: 2,
+                viewed_count: 1,
+                reveal_count: 2,
+                resend_count: 0,
+                reminder_count: 1,
+                not_viewed: vec!["Alice".to_string()],
+            }
+        );
+
+        db.mark_wrap_up_sent(game.id).await.unwrap();
+        assert!(due_ids().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_digest() {
+        let start = Utc.with_ymd_and_hms(2025, 12, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock::new(start));
+        let db = setup_test_db_with_clock(clock.clone()).await;
+
+        let mut game = create_test_game("digest_game", start.date_naive() + Duration::days(23));
+        game.drawn = true;
+        game.drawn_at = Some(start);
+        db.create_game(&game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        let bob = Participant::new(game.id, "Bob".to_string(), "bob@test.com".parse().unwrap());
+        db.add_participant(&alice).await.unwrap();
+        db.add_participant(&bob).await.unwrap();
+        db.mark_participant_viewed(bob.id).await.unwrap();
+
+        let due_ids = async || {
+            let due = db.find_games_due_digest().await.unwrap();
+            due.into_iter().map(|g| g.id).collect::<Vec<_>>()
+        };
+
+        // Only sent when the organizer asked for it, a week after the draw
+        clock.advance(Duration::days(DIGEST_INTERVAL_DAYS));
+        assert!(due_ids().await.is_empty());
+        db.update_game_digest_enabled(game.id, true).await.unwrap();
+        assert_eq!(due_ids().await, vec![game.id]);
+        clock.advance(Duration::hours(-1));
+        assert!(due_ids().await.is_empty());
+        clock.advance(Duration::hours(1));
+
+        // Then weekly
+        db.mark_digest_sent(game.id).await.unwrap();
+        assert!(due_ids().await.is_empty());
+        clock.advance(Duration::days(DIGEST_INTERVAL_DAYS));
+        assert_eq!(due_ids().await, vec![game.id]);
+
+        // Not once everyone has seen their match
+        db.mark_participant_viewed(alice.id).await.unwrap();
+        assert!(due_ids().await.is_empty());
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn test_email_suppressions() {
+        let db = setup_test_db().await;
+        let email: EmailAddress = "maria@test.com".parse().unwrap();
+        let same_email: EmailAddress = "Maria@Test.com".parse().unwrap();
+
+        assert_eq!(db.get_email_suppression(&email).await.unwrap(), None);
+
+        // A bounce replaces an unsubscribe, but not the other way around
+        db.suppress_email(&email, SuppressionReason::Unsubscribe)
+            .await
+            .unwrap();
+        db.suppress_email(&same_email, SuppressionReason::Complaint)
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_email_suppression(&email).await.unwrap(),
+            Some(SuppressionReason::Unsubscribe)
+        );
+        db.suppress_email(&email, SuppressionReason::Bounce)
+            .await
+            .unwrap();
+        db.suppress_email(&email, SuppressionReason::Unsubscribe)
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_email_suppression(&same_email).await.unwrap(),
+            Some(SuppressionReason::Bounce)
+        );
     }
 
-    pub async fn mark_game_as_drawn(&mut self, game_id: GameId) -> Result<()> {
-        sqlx::query(
-            r#"
-            UPDATE games
-            SET drawn = 1
-            WHERE id = ?
-            "#,
+    #[tokio::test]
+    async fn test_verification_link() {
+        let db = setup_test_db().await;
+
+        let (verification, code, token) = EmailVerification::new(
+            "maria@test.com".parse().unwrap(),
+            "Natal".to_string(),
+            NaiveDate::from_ymd_opt(2025, 12, 24).unwrap(),
+            None,
+            GameTimezone::default(),
+            Locale::default(),
+            Utc::now(),
+        );
+        db.create_email_verification(&verification).await.unwrap();
+
+        let found = db
+            .get_email_verification_by_id(verification.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(found.code_hash.matches(&code.to_string()));
+        let link_token_hash = found.link_token_hash.unwrap();
+        assert!(link_token_hash.matches(token.as_str()));
+        assert!(!link_token_hash.matches(VerificationLinkToken::generate().as_str()));
+
+        // Resending replaces the code and the link
+        let new_code = VerificationCode::generate();
+        let new_token = VerificationLinkToken::generate();
+        db.update_verification_code(
+            verification.id,
+            &new_code.hash(),
+            &new_token.hash(),
+            verification.expires_at,
         )
-        .bind(game_id)
-        .execute(&mut *self.inner)
-        .await?;
+        .await
+        .unwrap();
+        let found = db
+            .get_email_verification_by_id(verification.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(found.code_hash.matches(&new_code.to_string()));
+        assert!(!found.link_token_hash.unwrap().matches(token.as_str()));
+
+        // Only one of the code and the link can create the game
+        assert!(
+            db.mark_verification_as_verified(verification.id)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !db.mark_verification_as_verified(verification.id)
+                .await
+                .unwrap()
+        );
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn test_unsubscribe_tokens() {
+        let db = setup_test_db().await;
+        let email: EmailAddress = "maria@test.com".parse().unwrap();
+
+        let token = db.get_or_create_unsubscribe_token(&email).await.unwrap();
+        assert_eq!(
+            db.get_or_create_unsubscribe_token(&"MARIA@test.com".parse().unwrap())
+                .await
+                .unwrap(),
+            token
+        );
+        assert_eq!(
+            db.find_unsubscribe_email(&token).await.unwrap(),
+            Some(email)
+        );
+        assert_eq!(
+            db.find_unsubscribe_email(&UnsubscribeToken::generate())
+                .await
+                .unwrap(),
+            None
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::models::{Game, Participant};
-    use chrono::NaiveDate;
+    #[tokio::test]
+    async fn test_email_open_tracking() {
+        let start = Utc.with_ymd_and_hms(2025, 12, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(FixedClock::new(start));
+        let db = setup_test_db_with_clock(clock.clone()).await;
 
-    /// Create an in-memory database for testing.
-    async fn setup_test_db() -> Database {
-        let pool = init_db(":memory:").await.unwrap();
-        Database { pool }
-    }
+        let game = create_test_game("open_game", start.date_naive() + Duration::days(23));
+        db.create_game(&game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        db.add_participant(&alice).await.unwrap();
 
-    /// Create a test game with a specific event_date.
-    fn create_test_game(name: &str, event_date: NaiveDate) -> Game {
-        Game {
-            id: GameId::new(),
-            name: name.to_string(),
-            event_date,
-            organizer_email: format!("{}@test.com", name).parse().unwrap(),
-            admin_token: crate::token::AdminToken::generate(),
-            created_at: Utc::now(),
-            drawn: false,
-        }
+        let token = db.get_or_create_email_open_token(alice.id).await.unwrap();
+        assert_eq!(
+            db.get_or_create_email_open_token(alice.id).await.unwrap(),
+            token
+        );
+
+        // Ignored unless the organizer turned tracking on
+        db.record_email_open(&token).await.unwrap();
+        let statuses = participant_statuses(&db, game.id).await;
+        assert_eq!(statuses[&alice.id].email_opened_at, None);
+
+        // Only the first open counts
+        db.update_game_open_tracking_enabled(game.id, true)
+            .await
+            .unwrap();
+        db.record_email_open(&token).await.unwrap();
+        clock.advance(Duration::hours(1));
+        db.record_email_open(&token).await.unwrap();
+        db.record_email_open(&EmailOpenToken::generate())
+            .await
+            .unwrap();
+        let statuses = participant_statuses(&db, game.id).await;
+        assert_eq!(statuses[&alice.id].email_opened_at, Some(start));
     }
 
     #[tokio::test]
-    async fn test_cleanup_old_games_deletes_expired() {
+    async fn test_unreachable_email_domain() {
         let db = setup_test_db().await;
 
-        // Create a game with event_date > 90 days ago
-        let old_date = Utc::now().date_naive() - Duration::days(100);
-        let old_game = create_test_game("old_game", old_date);
-        db.create_game(&old_game).await.unwrap();
+        let game = create_test_game("mx_game", NaiveDate::from_ymd_opt(2025, 12, 24).unwrap());
+        db.create_game(&game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@gmial.com".parse().unwrap(),
+        );
+        db.add_participant(&alice).await.unwrap();
 
-        // Verify game exists
-        let found = db.get_game_by_id(old_game.id).await.unwrap();
-        assert!(found.is_some());
+        // A check of an address the participant no longer has is ignored
+        let fixed: EmailAddress = "alice@gmail.com".parse().unwrap();
+        db.update_participant(alice.id, None, Some(fixed.clone()))
+            .await
+            .unwrap();
+        db.mark_email_domain_unreachable(alice.id, &alice.email)
+            .await
+            .unwrap();
+        assert!(!participant_statuses(&db, game.id).await[&alice.id].email_domain_unreachable);
 
-        // Run cleanup
-        let deleted = db.cleanup_old_games().await.unwrap();
-        assert_eq!(deleted, 1);
+        db.mark_email_domain_unreachable(alice.id, &fixed)
+            .await
+            .unwrap();
+        assert!(participant_statuses(&db, game.id).await[&alice.id].email_domain_unreachable);
 
-        // Verify game was deleted
-        let found = db.get_game_by_id(old_game.id).await.unwrap();
-        assert!(found.is_none());
+        // Cleared when the email is corrected
+        db.update_participant(alice.id, None, Some(alice.email.clone()))
+            .await
+            .unwrap();
+        assert!(!participant_statuses(&db, game.id).await[&alice.id].email_domain_unreachable);
     }
 
     #[tokio::test]
-    async fn test_cleanup_old_games_preserves_recent() {
+    async fn test_mark_email_undeliverable() {
         let db = setup_test_db().await;
 
-        // Create a game with event_date within retention period
-        let recent_date = Utc::now().date_naive() - Duration::days(30);
-        let recent_game = create_test_game("recent_game", recent_date);
-        db.create_game(&recent_game).await.unwrap();
+        let game = create_test_game(
+            "bounce_game",
+            NaiveDate::from_ymd_opt(2025, 12, 24).unwrap(),
+        );
+        db.create_game(&game).await.unwrap();
+        let alice = Participant::new(
+            game.id,
+            "Alice".to_string(),
+            "alice@test.com".parse().unwrap(),
+        );
+        let bob = Participant::new(game.id, "Bob".to_string(), "bob@test.com".parse().unwrap());
+        db.add_participant(&alice).await.unwrap();
+        db.add_participant(&bob).await.unwrap();
+
+        // Addresses are matched regardless of case, and only flagged once
+        let email = "ALICE@test.com".parse().unwrap();
+        let flagged = db
+            .mark_email_undeliverable(&email, EmailEventKind::Bounce)
+            .await
+            .unwrap();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].id, alice.id);
+        assert!(
+            db.mark_email_undeliverable(&email, EmailEventKind::Complaint)
+                .await
+                .unwrap()
+                .is_empty()
+        );
 
-        // Run cleanup
-        let deleted = db.cleanup_old_games().await.unwrap();
-        assert_eq!(deleted, 0);
+        let statuses = participant_statuses(&db, game.id).await;
+        assert_eq!(
+            statuses[&alice.id].email_undeliverable.as_deref(),
+            Some("bounce")
+        );
+        assert_eq!(statuses[&bob.id].email_undeliverable, None);
 
-        // Verify game still exists
-        let found = db.get_game_by_id(recent_game.id).await.unwrap();
-        assert!(found.is_some());
+        // Fixing the address clears the flag
+        db.update_participant(alice.id, None, Some("alice@example.com".parse().unwrap()))
+            .await
+            .unwrap();
+        let statuses = participant_statuses(&db, game.id).await;
+        assert!(statuses.values().all(|p| p.email_undeliverable.is_none()));
     }
 
     #[tokio::test]
-    async fn test_cleanup_old_games_preserves_future() {
-        let db = setup_test_db().await;
+    async fn test_admin_session_expiry() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let db = setup_test_db_with_clock(clock.clone()).await;
+
+        let token = db.create_admin_session().await.unwrap();
+        clock.advance(Duration::hours(23));
+        assert!(db.validate_admin_session(&token).await.unwrap());
+        assert_eq!(db.cleanup_expired_admin_sessions().await.unwrap(), 0);
+
+        // Using the session extended it
+        clock.advance(Duration::hours(23));
+        assert!(db.validate_admin_session(&token).await.unwrap());
+
+        clock.advance(Duration::hours(25));
+        assert!(!db.validate_admin_session(&token).await.unwrap());
+        assert_eq!(db.cleanup_expired_admin_sessions().await.unwrap(), 1);
+    }
 
-        // Create a game with event_date in the future
-        let future_date = Utc::now().date_naive() + Duration::days(30);
-        let future_game = create_test_game("future_game", future_date);
-        db.create_game(&future_game).await.unwrap();
+    #[tokio::test]
+    async fn test_idempotency_keys() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let db = setup_test_db_with_clock(clock.clone()).await;
+        let used = |response: Option<&[u8]>| IdempotencyKeyState::Used {
+            request_hash: "request".to_string(),
+            response: response.map(<[u8]>::to_vec),
+        };
 
-        // Run cleanup
-        let deleted = db.cleanup_old_games().await.unwrap();
-        assert_eq!(deleted, 0);
+        assert_eq!(
+            db.claim_idempotency_key("key", "request").await.unwrap(),
+            IdempotencyKeyState::Claimed
+        );
+        // Still in progress
+        assert_eq!(
+            db.claim_idempotency_key("key", "request").await.unwrap(),
+            used(None)
+        );
+        db.complete_idempotency_key("key", b"response")
+            .await
+            .unwrap();
+        assert_eq!(
+            db.claim_idempotency_key("key", "other").await.unwrap(),
+            used(Some(b"response"))
+        );
+        // Completed keys aren't released
+        db.release_idempotency_key("key").await.unwrap();
+        assert_eq!(
+            db.claim_idempotency_key("key", "request").await.unwrap(),
+            used(Some(b"response"))
+        );
 
-        // Verify game still exists
-        let found = db.get_game_by_id(future_game.id).await.unwrap();
-        assert!(found.is_some());
+        // Released and abandoned claims can be taken again
+        db.claim_idempotency_key("released", "request")
+            .await
+            .unwrap();
+        db.release_idempotency_key("released").await.unwrap();
+        assert_eq!(
+            db.claim_idempotency_key("released", "request")
+                .await
+                .unwrap(),
+            IdempotencyKeyState::Claimed
+        );
+        clock.advance(IDEMPOTENCY_CLAIM_TIMEOUT + Duration::seconds(1));
+        assert_eq!(
+            db.claim_idempotency_key("released", "other").await.unwrap(),
+            IdempotencyKeyState::Claimed
+        );
+
+        assert_eq!(db.cleanup_idempotency_keys().await.unwrap(), 0);
+        clock.advance(IDEMPOTENCY_KEY_RETENTION + Duration::seconds(1));
+        assert_eq!(db.cleanup_idempotency_keys().await.unwrap(), 2);
     }
 
     #[tokio::test]
-    async fn test_cleanup_old_games_returns_correct_count() {
+    async fn test_list_and_revoke_admin_sessions() {
         let db = setup_test_db().await;
+        let first = db.create_admin_session().await.unwrap();
+        let second = db.create_admin_session().await.unwrap();
+        assert!(db.validate_admin_session(&second).await.unwrap());
+
+        let sessions = db.list_admin_sessions(&second).await.unwrap();
+        assert_eq!(sessions.len(), 2);
+        let current = sessions.iter().find(|s| s.current).unwrap();
+        assert!(current.last_used_at.is_some());
+        let other = sessions.iter().find(|s| !s.current).unwrap();
+        assert!(other.last_used_at.is_none());
+
+        assert!(db.delete_admin_session_by_id(other.id).await.unwrap());
+        assert!(!db.delete_admin_session_by_id(other.id).await.unwrap());
+        assert!(!db.validate_admin_session(&first).await.unwrap());
+        assert!(db.validate_admin_session(&second).await.unwrap());
+    }
 
-        // Create 3 expired games
-        for i in 0..3 {
-            let old_date = Utc::now().date_naive() - Duration::days(100 + i);
-            let game = create_test_game(&format!("old_{}", i), old_date);
-            db.create_game(&game).await.unwrap();
+    #[tokio::test]
+    async fn test_site_admin_login_failures() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let db = setup_test_db_with_clock(clock.clone()).await;
+        let attacker = Some("203.0.113.0/24");
+        let admin = Some("198.51.100.0/24");
+
+        for outcome in [LoginOutcome::WrongPassword, LoginOutcome::WrongCode] {
+            clock.advance(Duration::seconds(1));
+            db.record_site_admin_login_attempt(outcome, None, attacker)
+                .await
+                .unwrap();
         }
+        clock.advance(Duration::seconds(1));
+        db.record_site_admin_login_attempt(LoginOutcome::LockedOut, None, attacker)
+            .await
+            .unwrap();
+        let (failures, last_failure) = db.site_admin_login_failures(attacker).await.unwrap();
+        assert_eq!(failures, 2);
+        assert_eq!(last_failure, Some(clock.now() - Duration::seconds(1)));
+        assert_eq!(
+            db.site_admin_login_failures(admin).await.unwrap(),
+            (0, None)
+        );
+        assert_eq!(db.site_admin_login_failures(None).await.unwrap().0, 2);
 
-        // Create 2 recent games
-        for i in 0..2 {
-            let recent_date = Utc::now().date_naive() - Duration::days(10 + i);
-            let game = create_test_game(&format!("recent_{}", i), recent_date);
-            db.create_game(&game).await.unwrap();
-        }
+        // A success only resets the failures before it
+        clock.advance(Duration::seconds(1));
+        db.record_site_admin_login_attempt(LoginOutcome::Success, Some("Firefox"), admin)
+            .await
+            .unwrap();
+        clock.advance(Duration::seconds(1));
+        db.record_site_admin_login_attempt(LoginOutcome::WrongPassword, None, attacker)
+            .await
+            .unwrap();
+        assert_eq!(db.site_admin_login_failures(attacker).await.unwrap().0, 3);
+        assert_eq!(db.site_admin_login_failures(None).await.unwrap().0, 1);
+
+        // Old failures are forgotten
+        clock.advance(Duration::hours(LOGIN_FAILURE_WINDOW_HOURS));
+        assert_eq!(
+            db.site_admin_login_failures(attacker).await.unwrap(),
+            (0, None)
+        );
 
-        // Run cleanup
-        let deleted = db.cleanup_old_games().await.unwrap();
-        assert_eq!(deleted, 3);
+        let attempts = db.list_site_admin_login_attempts(10).await.unwrap();
+        assert_eq!(attempts.len(), 5);
+        assert_eq!(attempts[0].outcome, "wrong_password");
+        assert_eq!(attempts[1].user_agent.as_deref(), Some("Firefox"));
+
+        clock.advance(Duration::days(LOGIN_ATTEMPT_RETENTION_DAYS));
+        assert_eq!(db.cleanup_old_site_admin_login_attempts().await.unwrap(), 5);
     }
 
     #[tokio::test]
-    async fn test_cleanup_old_games_boundary() {
+    async fn test_site_admin_totp_enrollment() {
         let db = setup_test_db().await;
+        db.init_site_admin_password(Some("password123"))
+            .await
+            .unwrap();
 
-        // Create a game exactly at the boundary (should NOT be deleted)
-        let boundary_date = Utc::now().date_naive() - Duration::days(GAME_RETENTION_DAYS.into());
-        let boundary_game = create_test_game("boundary_game", boundary_date);
-        db.create_game(&boundary_game).await.unwrap();
+        // An unconfirmed secret isn't required at login
+        assert!(
+            db.start_site_admin_totp_enrollment(&totp::generate_secret())
+                .await
+                .unwrap()
+        );
+        assert!(!db.site_admin_totp_enabled().await.unwrap());
+        assert!(!db.check_site_admin_totp("000000").await.unwrap());
+
+        let codes = db.enable_site_admin_totp().await.unwrap();
+        assert!(db.site_admin_totp_enabled().await.unwrap());
+        assert!(
+            !db.start_site_admin_totp_enrollment(&totp::generate_secret())
+                .await
+                .unwrap()
+        );
 
-        // Create a game one day past the boundary (should be deleted)
-        let past_boundary =
-            Utc::now().date_naive() - Duration::days(GAME_RETENTION_DAYS as i64 + 1);
-        let old_game = create_test_game("old_game", past_boundary);
-        db.create_game(&old_game).await.unwrap();
+        // Recovery codes work once each, ignoring case and dashes
+        let code = codes[0].to_uppercase().replace('-', "");
+        assert!(db.verify_site_admin_second_factor(&code).await.unwrap());
+        assert!(!db.verify_site_admin_second_factor(&codes[0]).await.unwrap());
+        assert!(
+            !db.verify_site_admin_second_factor("wrong-code")
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            db.count_unused_site_admin_recovery_codes().await.unwrap(),
+            u64::try_from(totp::RECOVERY_CODE_COUNT).unwrap() - 1
+        );
 
-        // Run cleanup
-        let deleted = db.cleanup_old_games().await.unwrap();
-        assert_eq!(deleted, 1);
+        db.disable_site_admin_totp().await.unwrap();
+        assert!(!db.site_admin_totp_enabled().await.unwrap());
+        assert!(!db.verify_site_admin_second_factor(&codes[1]).await.unwrap());
+    }
 
-        // Verify boundary game still exists
-        let found = db.get_game_by_id(boundary_game.id).await.unwrap();
-        assert!(found.is_some());
+    #[tokio::test]
+    async fn test_webauthn_challenges_single_use() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let db = setup_test_db_with_clock(clock.clone()).await;
 
-        // Verify old game was deleted
-        let found = db.get_game_by_id(old_game.id).await.unwrap();
-        assert!(found.is_none());
+        db.store_webauthn_challenge("AQID", Ceremony::Authentication, "{\"state\":1}")
+            .await
+            .unwrap();
+        assert_eq!(
+            db.take_webauthn_challenge("AQID", Ceremony::Registration)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            db.take_webauthn_challenge("AQID", Ceremony::Authentication)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("{\"state\":1}")
+        );
+        assert_eq!(
+            db.take_webauthn_challenge("AQID", Ceremony::Authentication)
+                .await
+                .unwrap(),
+            None
+        );
+
+        db.store_webauthn_challenge("BAUG", Ceremony::Authentication, "{}")
+            .await
+            .unwrap();
+        clock.advance(Duration::minutes(webauthn::CHALLENGE_DURATION_MINUTES));
+        assert_eq!(
+            db.take_webauthn_challenge("BAUG", Ceremony::Authentication)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    fn test_passkey(counter: u32) -> Passkey {
+        serde_json::from_value(serde_json::json!({
+            "cred": {
+                "cred_id": "AQIDBA",
+                "cred": {
+                    "type_": "ES256",
+                    "key": { "EC_EC2": { "curve": "SECP256R1", "x": vec![1; 32], "y": vec![2; 32] } },
+                },
+                "counter": counter,
+                "transports": null,
+                "user_verified": true,
+                "backup_eligible": false,
+                "backup_state": false,
+                "registration_policy": "required",
+                "extensions": {},
+                "attestation": { "data": "None", "metadata": "None" },
+                "attestation_format": "none",
+            }
+        }))
+        .unwrap()
     }
 
     #[tokio::test]
-    async fn test_cleanup_old_games_with_related_records() {
+    async fn test_site_admin_passkeys() {
         let db = setup_test_db().await;
 
-        // Create an expired game
-        let old_date = Utc::now().date_naive() - Duration::days(100);
-        let game = create_test_game("old_game", old_date);
-        db.create_game(&game).await.unwrap();
+        let passkey = db
+            .add_site_admin_passkey(&test_passkey(3), "Laptop")
+            .await
+            .unwrap();
+        let credentials = db.site_admin_passkey_credentials().await.unwrap();
+        assert_eq!(credentials.len(), 1);
+        assert_eq!(credentials[0].passkey.cred_id().as_slice(), [1, 2, 3, 4]);
 
-        // Add participants
-        let participant1 = Participant::new(
-            game.id,
-            "Alice".to_string(),
-            "alice@test.com".parse().unwrap(),
+        let stored = db
+            .get_site_admin_passkey_credential("AQIDBA")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.id, passkey.id);
+        assert_eq!(
+            serde_json::to_value(&stored.passkey).unwrap(),
+            serde_json::to_value(test_passkey(3)).unwrap()
         );
-        let participant2 =
-            Participant::new(game.id, "Bob".to_string(), "bob@test.com".parse().unwrap());
-        db.add_participant(&participant1).await.unwrap();
-        db.add_participant(&participant2).await.unwrap();
 
-        // Record email resends (references both game and participants)
-        db.record_email_resend(game.id, None, "admin_link")
+        db.record_site_admin_passkey_use(passkey.id, Some(&test_passkey(4)))
             .await
             .unwrap();
-        db.record_email_resend(game.id, Some(participant1.id), "participant_reveal")
+        let stored = db
+            .get_site_admin_passkey_credential("AQIDBA")
             .await
+            .unwrap()
             .unwrap();
+        assert_eq!(
+            serde_json::to_value(&stored.passkey).unwrap(),
+            serde_json::to_value(test_passkey(4)).unwrap()
+        );
+        db.record_site_admin_passkey_use(passkey.id, None)
+            .await
+            .unwrap();
+        let listed = db.list_site_admin_passkeys().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].last_used_at.is_some());
+
+        assert!(db.delete_site_admin_passkey(passkey.id).await.unwrap());
+        assert!(!db.delete_site_admin_passkey(passkey.id).await.unwrap());
+        assert!(
+            db.get_site_admin_passkey_credential("AQIDBA")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
 
-        // Verify records exist
-        assert!(db.get_game_by_id(game.id).await.unwrap().is_some());
-        let participants = db.get_participants_by_game(game.id).await.unwrap();
-        assert_eq!(participants.len(), 2);
+    #[tokio::test]
+    async fn test_organizer_login_link_single_use() {
+        let db = setup_test_db().await;
+        let email: EmailAddress = "organizer@test.com".parse().unwrap();
+        let game_id = GameId::new();
 
-        // Run cleanup - this should not fail with foreign key errors
-        let deleted = db.cleanup_old_games().await.unwrap();
-        assert_eq!(deleted, 1);
+        let token = db
+            .create_organizer_login_link(&email, Some(game_id))
+            .await
+            .unwrap();
 
-        // Verify game and all related records are deleted
-        assert!(db.get_game_by_id(game.id).await.unwrap().is_none());
-        let participants = db.get_participants_by_game(game.id).await.unwrap();
-        assert!(participants.is_empty());
+        // Checking a link doesn't use it
+        assert!(db.organizer_login_link_is_valid(&token).await.unwrap());
+        assert!(db.organizer_login_link_is_valid(&token).await.unwrap());
+
+        let (found_email, found_game_id) = db
+            .consume_organizer_login_link(&token)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found_email, email);
+        assert_eq!(found_game_id, Some(game_id));
+
+        // A link can only be used once
+        assert!(!db.organizer_login_link_is_valid(&token).await.unwrap());
+        assert!(
+            db.consume_organizer_login_link(&token)
+                .await
+                .unwrap()
+                .is_none()
+        );
     }
 }
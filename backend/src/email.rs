@@ -1,131 +1,360 @@
-use crate::email_templates::{html, plain};
-use crate::token::{AdminToken, EmailAddress, GameId, VerificationCode, ViewToken};
-use anyhow::{Context, Result};
-use chrono::{Locale, NaiveDate};
-use lettre::{
-    AsyncSmtpTransport, AsyncTransport, Tokio1Executor,
-    message::{Mailbox, Message, header::ContentType},
-    transport::smtp::authentication::Credentials,
+use crate::clock::Clock;
+use crate::datetime;
+use crate::db::Database;
+use crate::email_events::EmailEventKind;
+use crate::email_templates::{custom, html, plain};
+use crate::email_transport::{Attachment, EmailProvider, EmailTransport, OutgoingEmail};
+use crate::i18n::Locale;
+use crate::ics;
+use crate::models::{Game, Participant, SmtpTestReport, WrapUpStats};
+use crate::storage::MediaKey;
+use crate::token::{
+    AdminToken, EmailAddress, GameId, OrganizerLoginToken, ParticipantId, VerificationCode,
+    VerificationId, VerificationLinkToken, ViewToken,
 };
+use anyhow::{Result, bail};
+use base64::prelude::*;
+use chrono::NaiveDate;
+use image::{ImageFormat, Luma};
+use lettre::message::Mailbox;
+use qrcode::{EcLevel, QrCode};
+use std::io::Cursor;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use url::Url;
 
-type SmtpTransport = AsyncSmtpTransport<Tokio1Executor>;
+/// Longest game name used in a subject line, in characters.
+const MAX_SUBJECT_GAME_NAME_LENGTH: usize = 60;
 
+/// How long the connection test waits for the server or provider.
+const CONNECTION_TEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+const CONNECTION_FAILED_HINT: &str = "Check the host, port, credentials, and TLS settings.";
+const CONNECTION_TIMED_OUT_HINT: &str = "The server may be unreachable or, for SMTP, the port may \
+    require different TLS settings (try port 465 for TLS or 587 for STARTTLS).";
+const SEND_FAILED_HINT: &str = "The connection works, so check that the sender address \
+    (SMTP_FROM) is allowed by the server or provider and that the recipient exists.";
+
+#[derive(Clone)]
 pub struct EmailConfig {
-    pub smtp_host: String,
-    pub smtp_port: u16,
-    pub smtp_username: String,
-    pub smtp_password: String,
-    pub from_address: String,
+    pub provider: EmailProvider,
+    pub from_address: EmailAddress,
     pub base_url: Url,
+    /// Whether admin links in emails include the admin token (see `LEGACY_ADMIN_TOKENS`).
+    pub legacy_admin_tokens: bool,
+    /// Where test emails are sent; defaults to the sender address.
+    pub site_admin_address: Option<EmailAddress>,
 }
 
-impl EmailConfig {
-    /// Returns a display string for logging (without credentials)
-    fn display(&self) -> String {
-        format!(
-            "{}:{} (from: {})",
-            self.smtp_host, self.smtp_port, self.from_address
-        )
-    }
+/// Subject and bodies of an email, before it's addressed and sent.
+pub struct RenderedEmail {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+    /// Images the HTML body shows by content ID, such as the QR code.
+    pub inline_images: Vec<Attachment>,
 }
 
-impl EmailConfig {
-    pub fn from_env() -> Result<Self> {
-        Ok(Self {
-            smtp_host: std::env::var("SMTP_HOST")?,
-            smtp_port: std::env::var("SMTP_PORT")?.parse()?,
-            smtp_username: std::env::var("SMTP_USERNAME")?,
-            smtp_password: std::env::var("SMTP_PASSWORD")?,
-            from_address: std::env::var("SMTP_FROM")?,
-            base_url: std::env::var("BASE_URL")?.parse()?,
-        })
+impl RenderedEmail {
+    /// The HTML body with its images embedded as data URLs, for showing it in a browser.
+    pub fn html_with_embedded_images(&self) -> String {
+        self.inline_images
+            .iter()
+            .fold(self.html.clone(), |html, image| match &image.content_id {
+                Some(content_id) => html.replace(
+                    &format!("cid:{content_id}"),
+                    &format!(
+                        "data:{};base64,{}",
+                        image.content_type,
+                        BASE64_STANDARD.encode(&image.data)
+                    ),
+                ),
+                None => html,
+            })
     }
 }
 
+/// Where the recipient of an optional email can unsubscribe.
+struct UnsubscribeLinks {
+    /// Page that asks for confirmation, linked from the email body.
+    page: Url,
+    /// Unsubscribes on POST, for the `List-Unsubscribe` header.
+    one_click: Url,
+}
+
 #[derive(Clone)]
 pub struct EmailService {
     inner: Arc<EmailServiceInner>,
 }
 
 struct EmailServiceInner {
-    mailer: SmtpTransport,
+    transport: Arc<dyn EmailTransport>,
+    /// For the suppression list and unsubscribe tokens.
+    db: Database,
+    clock: Arc<dyn Clock>,
     from_address: Mailbox,
     base_url: Url,
-    smtp_display: String,
+    legacy_admin_tokens: bool,
+    site_admin_address: Mailbox,
+    transport_display: String,
 }
 
 impl EmailService {
-    pub fn from_env() -> Result<Self> {
-        let config = EmailConfig::from_env()?;
-        Self::new(config)
-    }
-
-    pub fn new(config: EmailConfig) -> Result<Self> {
-        let smtp_display = config.display();
-        let creds = Credentials::new(config.smtp_username, config.smtp_password);
-
-        // Use direct TLS for port 465, STARTTLS for other ports (typically 587)
-        let mailer = if config.smtp_port == 465 {
-            SmtpTransport::relay(&config.smtp_host)
-                .context("failed to create TLS SMTP transport")?
-                .port(config.smtp_port)
-                .credentials(creds)
-                .timeout(Some(Duration::from_secs(30)))
-                .build()
-        } else {
-            SmtpTransport::starttls_relay(&config.smtp_host)
-                .context("failed to create STARTTLS SMTP transport")?
-                .port(config.smtp_port)
-                .credentials(creds)
-                .timeout(Some(Duration::from_secs(30)))
-                .build()
-        };
-
-        let email_address = config.from_address.parse()?;
-        let from_address = Mailbox::new(Some("Amigo Oculto".to_string()), email_address);
+    pub fn new(config: EmailConfig, db: Database, clock: Arc<dyn Clock>) -> Result<Self> {
+        let transport = config.provider.transport(clock.clone())?;
+        let transport_display = format!(
+            "{} (from: {})",
+            transport.description(),
+            config.from_address
+        );
+
+        let from_address = Mailbox::new(
+            Some("Amigo Oculto".to_string()),
+            config.from_address.to_mailbox().email,
+        );
+        let site_admin_address = config
+            .site_admin_address
+            .map(|email| email.to_mailbox())
+            .unwrap_or_else(|| from_address.clone());
 
         Ok(Self {
             inner: EmailServiceInner {
-                mailer,
+                transport,
+                db,
+                clock,
                 from_address,
                 base_url: config.base_url,
-                smtp_display,
+                legacy_admin_tokens: config.legacy_admin_tokens,
+                site_admin_address,
+                transport_display,
             }
             .into(),
         })
     }
 
+    /// Where emails are sent, without the sender address or credentials.
+    pub fn transport_description(&self) -> String {
+        self.inner.transport.description()
+    }
+
     pub async fn test(&self) -> Result<()> {
-        tracing::info!(smtp = %self.inner.smtp_display, "testing SMTP connection...");
+        let transport = &self.inner.transport_display;
+        tracing::info!(transport = %transport, "testing email connection...");
 
-        match tokio::time::timeout(Duration::from_secs(30), self.inner.mailer.test_connection())
-            .await
+        match tokio::time::timeout(
+            CONNECTION_TEST_TIMEOUT,
+            self.inner.transport.test_connection(),
+        )
+        .await
         {
-            Ok(Ok(_)) => {
-                tracing::info!(smtp = %self.inner.smtp_display, "SMTP connection test successful");
+            Ok(Ok(())) => {
+                tracing::info!(transport = %transport, "email connection test successful");
                 Ok(())
             }
             Ok(Err(e)) => {
-                tracing::error!(smtp = %self.inner.smtp_display, error = %e, "SMTP connection test failed");
+                tracing::error!(transport = %transport, error = %e, "email connection test failed");
                 Err(anyhow::anyhow!(
-                    "SMTP connection to {} failed: {}. Check host, port, credentials, and TLS settings.",
-                    self.inner.smtp_display,
-                    e
+                    "connection to {} failed: {:#}. {}",
+                    transport,
+                    e,
+                    CONNECTION_FAILED_HINT
                 ))
             }
             Err(_) => {
-                tracing::error!(smtp = %self.inner.smtp_display, "SMTP connection test timed out after 30s");
+                tracing::error!(transport = %transport, "email connection test timed out after 30s");
                 Err(anyhow::anyhow!(
-                    "SMTP connection to {} timed out after 30s. The server may be unreachable or the port may require different TLS settings (try port 465 for TLS or 587 for STARTTLS).",
-                    self.inner.smtp_display
+                    "connection to {} timed out after 30s. {}",
+                    transport,
+                    CONNECTION_TIMED_OUT_HINT
                 ))
             }
         }
     }
 
+    /// Connects to the email server or provider and, if asked, sends a test email to the
+    /// given address or the site admin, timing each step.
+    ///
+    /// Failures are reported in the result rather than returned as errors, since diagnosing
+    /// them is the point. The test email skips the suppression list.
+    pub async fn run_test(&self, send: bool, to: Option<&EmailAddress>) -> SmtpTestReport {
+        let to = match to {
+            Some(to) => to.to_mailbox(),
+            None => self.inner.site_admin_address.clone(),
+        };
+        let mut report = SmtpTestReport {
+            smtp_server: self.inner.transport_display.clone(),
+            recipient: None,
+            connection_ms: None,
+            send_ms: None,
+            error: None,
+            failed_step: None,
+            hint: None,
+        };
+
+        let started = Instant::now();
+        match tokio::time::timeout(
+            CONNECTION_TEST_TIMEOUT,
+            self.inner.transport.test_connection(),
+        )
+        .await
+        {
+            Ok(Ok(())) => report.connection_ms = Some(elapsed_ms(started)),
+            Ok(Err(e)) => {
+                report.error = Some(format!("connection failed: {:#}", e));
+                report.failed_step = Some("connection");
+                report.hint = Some(CONNECTION_FAILED_HINT);
+                return report;
+            }
+            Err(_) => {
+                report.error = Some("connection timed out after 30s".to_string());
+                report.failed_step = Some("connection");
+                report.hint = Some(CONNECTION_TIMED_OUT_HINT);
+                return report;
+            }
+        }
+        if !send {
+            return report;
+        }
+
+        report.recipient = Some(to.email.to_string());
+        let email = OutgoingEmail {
+            from: self.inner.from_address.clone(),
+            to,
+            reply_to: None,
+            subject: "Amigo Oculto - Teste de Email".to_string(),
+            text: format!(
+                "Este é um email de teste enviado pelo painel de administração.\n\nServidor: {}",
+                self.inner.transport_display
+            ),
+            html: None,
+            list_unsubscribe: None,
+            attachments: Vec::new(),
+        };
+
+        let started = Instant::now();
+        match self.inner.transport.send(&email).await {
+            Ok(()) => report.send_ms = Some(elapsed_ms(started)),
+            Err(e) => {
+                report.error = Some(format!("sending failed: {:#}", e));
+                report.failed_step = Some("send");
+                report.hint = Some(SEND_FAILED_HINT);
+            }
+        }
+
+        report
+    }
+
+    /// Sends an essential email with plain-text and HTML versions. Fails if the address
+    /// bounced before.
+    async fn send(&self, to: Mailbox, subject: String, text: String, html: String) -> Result<()> {
+        self.send_with_attachments(to, None, subject, text, html, Vec::new())
+            .await
+    }
+
+    /// Like [`EmailService::send`], with files attached and replies optionally going
+    /// elsewhere than the sender.
+    async fn send_with_attachments(
+        &self,
+        to: Mailbox,
+        reply_to: Option<Mailbox>,
+        subject: String,
+        text: String,
+        html: String,
+        attachments: Vec<Attachment>,
+    ) -> Result<()> {
+        let address = EmailAddress::from(to.email.clone());
+        if let Some(reason) = self.inner.db.get_email_suppression(&address).await?
+            && reason.blocks_essential_emails()
+        {
+            bail!(
+                "{} is on the suppression list ({})",
+                address,
+                reason.as_str()
+            );
+        }
+
+        let email = OutgoingEmail {
+            from: self.inner.from_address.clone(),
+            to,
+            reply_to,
+            subject,
+            text,
+            html: Some(html),
+            list_unsubscribe: None,
+            attachments,
+        };
+        self.inner.transport.send(&email).await
+    }
+
+    /// Sends an optional email, such as a reminder, with a `List-Unsubscribe` header.
+    async fn send_optional(
+        &self,
+        to: &EmailAddress,
+        reply_to: Option<Mailbox>,
+        links: UnsubscribeLinks,
+        subject: String,
+        text: String,
+        html: String,
+    ) -> Result<()> {
+        let email = OutgoingEmail {
+            from: self.inner.from_address.clone(),
+            to: to.to_mailbox(),
+            reply_to,
+            subject,
+            text,
+            html: Some(html),
+            list_unsubscribe: Some(links.one_click),
+            attachments: Vec::new(),
+        };
+        self.inner.transport.send(&email).await
+    }
+
+    /// Unsubscribe links for an optional email to the address, or `None` if the address is
+    /// on the suppression list and the email shouldn't be sent.
+    async fn unsubscribe_links(&self, email: &EmailAddress) -> Result<Option<UnsubscribeLinks>> {
+        if let Some(reason) = self.inner.db.get_email_suppression(email).await? {
+            tracing::info!(
+                "not sending optional email to suppressed address ({})",
+                reason.as_str()
+            );
+            return Ok(None);
+        }
+
+        let token = self.inner.db.get_or_create_unsubscribe_token(email).await?;
+        Ok(Some(UnsubscribeLinks {
+            page: self
+                .inner
+                .base_url
+                .join(&format!("descadastrar/{}", token))
+                .unwrap(),
+            one_click: self
+                .inner
+                .base_url
+                .join(&format!("api/email/unsubscribe/{}", token))
+                .unwrap(),
+        }))
+    }
+
+    /// Address of the tracking pixel recording when a participant opens their emails.
+    async fn open_tracking_url(&self, participant_id: ParticipantId) -> Result<Url> {
+        let token = self
+            .inner
+            .db
+            .get_or_create_email_open_token(participant_id)
+            .await?;
+        Ok(self
+            .inner
+            .base_url
+            .join(&format!("api/email/open/{}", token))
+            .unwrap())
+    }
+
+    /// Where participants' replies go: the organizer, if they opted in.
+    fn reply_to(game: &Game) -> Option<Mailbox> {
+        game.reply_to_organizer
+            .then(|| game.organizer_email.to_mailbox())
+    }
+
     fn reveal_url(&self, view_token: &ViewToken) -> Url {
         self.inner
             .base_url
@@ -133,134 +362,532 @@ impl EmailService {
             .unwrap()
     }
 
-    pub async fn send_participant_notification(
+    fn media_url(&self, key: &MediaKey) -> Url {
+        self.inner.base_url.join(&format!("media/{}", key)).unwrap()
+    }
+
+    /// Calendar event for the game, so recipients can add it to their calendars.
+    fn calendar_event(&self, game: &Game) -> Attachment {
+        let host = self.inner.base_url.host_str().unwrap_or("amigo-oculto");
+        let calendar = ics::render_event(
+            &format!("{}@{}", game.id, host),
+            &format!("Amigo Oculto: {}", game.name),
+            game.event_date,
+            game.event_location.as_deref(),
+            self.inner.clock.now(),
+        );
+        Attachment {
+            filename: "amigo-oculto.ics".to_string(),
+            content_type: "text/calendar; charset=utf-8; method=PUBLISH".to_string(),
+            data: calendar.into_bytes(),
+            content_id: None,
+        }
+    }
+
+    /// QR code of the link, for the HTML body to show as [`html::QR_CODE_CID`].
+    fn qr_code(url: &Url) -> Attachment {
+        Attachment {
+            filename: html::QR_CODE_CID.to_string(),
+            content_type: "image/png".to_string(),
+            data: qr_png(url).expect("links fit in a QR code"),
+            content_id: Some(html::QR_CODE_CID.to_string()),
+        }
+    }
+
+    /// Google Maps search link for a free-text event location.
+    fn maps_url(event_location: &str) -> Url {
+        Url::parse_with_params(
+            "https://www.google.com/maps/search/?api=1",
+            &[("query", event_location)],
+        )
+        .expect("static maps URL is valid")
+    }
+
+    /// The email telling a participant about the draw, as sent by
+    /// [`send_participant_notification`](Self::send_participant_notification).
+    pub fn participant_notification(
         &self,
+        game: &Game,
         participant_name: &str,
-        participant_email: &EmailAddress,
-        game_name: &str,
-        event_date: NaiveDate,
         view_token: &ViewToken,
-    ) -> Result<()> {
+        open_tracking_url: Option<&Url>,
+    ) -> RenderedEmail {
         let reveal_url = self.reveal_url(view_token);
-        let formatted_date = format_brazilian_date(event_date);
+        let formatted_date = datetime::format_date(game.event_date, game.locale);
+        let maps_url = game.event_location.as_deref().map(Self::maps_url);
+        let event_location = game.event_location.as_deref().zip(maps_url.as_ref());
+
+        let cover_url = game.cover_image.as_ref().map(|key| self.media_url(key));
+
+        let values = custom::TemplateValues {
+            participant_name,
+            game_name: &game.name,
+            event_date: &formatted_date,
+            event_location: game.event_location.as_deref(),
+            organizer_message: game.organizer_message.as_deref(),
+            reveal_url: reveal_url.as_str(),
+        };
+        let custom_body = game
+            .participant_email_body
+            .as_deref()
+            .and_then(|body| render_custom(game, body, &values));
+        let subject = game
+            .participant_email_subject
+            .as_deref()
+            .and_then(|subject| render_custom(game, subject, &values))
+            .unwrap_or_else(|| game.name.clone());
+
+        let (html_body, plain_body) = match custom_body {
+            Some(body) => (
+                html::custom_participant_email(
+                    game.locale,
+                    &game.name,
+                    &body,
+                    cover_url.as_ref(),
+                    &reveal_url,
+                    open_tracking_url,
+                )
+                .into_string(),
+                plain::custom_participant_email(game.locale, &body, &reveal_url),
+            ),
+            None => (
+                // Generate HTML using Maud template (XSS-safe)
+                html::participant_email(
+                    game.locale,
+                    participant_name,
+                    &game.name,
+                    &formatted_date,
+                    event_location,
+                    game.organizer_message.as_deref(),
+                    cover_url.as_ref(),
+                    &reveal_url,
+                    open_tracking_url,
+                )
+                .into_string(),
+                plain::participant_email(
+                    game.locale,
+                    participant_name,
+                    &game.name,
+                    &formatted_date,
+                    event_location,
+                    game.organizer_message.as_deref(),
+                    &reveal_url,
+                ),
+            ),
+        };
 
-        // Generate HTML using Maud template (XSS-safe)
-        let html_body =
-            html::participant_email(participant_name, game_name, &formatted_date, &reveal_url)
-                .into_string();
+        RenderedEmail {
+            subject: game_subject("🎁", &subject),
+            text: plain_body,
+            html: html_body,
+            inline_images: vec![Self::qr_code(&reveal_url)],
+        }
+    }
 
-        // Generate plain-text
-        let plain_body =
-            plain::participant_email(participant_name, game_name, &formatted_date, &reveal_url);
-
-        let email = Message::builder()
-            .from(self.inner.from_address.clone())
-            .to(participant_email.to_mailbox())
-            .subject(format!("🎁 {}", game_name))
-            .multipart(
-                lettre::message::MultiPart::alternative()
-                    .singlepart(
-                        lettre::message::SinglePart::builder()
-                            .header(ContentType::TEXT_PLAIN)
-                            .body(plain_body),
-                    )
-                    .singlepart(
-                        lettre::message::SinglePart::builder()
-                            .header(ContentType::TEXT_HTML)
-                            .body(html_body),
-                    ),
-            )?;
+    pub async fn send_participant_notification(
+        &self,
+        game: &Game,
+        participant: &Participant,
+    ) -> Result<()> {
+        let open_tracking_url = if game.open_tracking_enabled {
+            Some(self.open_tracking_url(participant.id).await?)
+        } else {
+            None
+        };
+        let view_token = self.inner.db.create_view_token(participant.id).await?;
+        let email = self.participant_notification(
+            game,
+            &participant.name,
+            &view_token,
+            open_tracking_url.as_ref(),
+        );
+        let mut attachments = vec![self.calendar_event(game)];
+        attachments.extend(email.inline_images);
+        self.send_with_attachments(
+            participant.email.to_mailbox(),
+            Self::reply_to(game),
+            email.subject,
+            email.text,
+            email.html,
+            attachments,
+        )
+        .await
+    }
+
+    /// Reminds a participant who hasn't seen their match yet, unless they unsubscribed.
+    pub async fn send_participant_reminder(
+        &self,
+        game: &Game,
+        participant: &Participant,
+    ) -> Result<()> {
+        let Some(links) = self.unsubscribe_links(&participant.email).await? else {
+            return Ok(());
+        };
+        let view_token = self.inner.db.create_view_token(participant.id).await?;
+        let reveal_url = self.reveal_url(&view_token);
+        let formatted_date = datetime::format_date(game.event_date, game.locale);
+
+        let html_body = html::participant_reminder_email(
+            game.locale,
+            &participant.name,
+            &game.name,
+            &formatted_date,
+            &reveal_url,
+            &links.page,
+        )
+        .into_string();
+        let plain_body = plain::participant_reminder_email(
+            game.locale,
+            &participant.name,
+            &game.name,
+            &formatted_date,
+            &reveal_url,
+            &links.page,
+        );
+
+        self.send_optional(
+            &participant.email,
+            Self::reply_to(game),
+            links,
+            game_subject(game.locale.tr("⏰ Lembrete:"), &game.name),
+            plain_body,
+            html_body,
+        )
+        .await
+    }
 
-        self.inner.mailer.send(email).await?;
-        Ok(())
+    /// Reminds a participant of the upcoming event, unless they unsubscribed.
+    pub async fn send_participant_event_reminder(
+        &self,
+        game: &Game,
+        participant: &Participant,
+    ) -> Result<()> {
+        let Some(links) = self.unsubscribe_links(&participant.email).await? else {
+            return Ok(());
+        };
+        let view_token = self.inner.db.create_view_token(participant.id).await?;
+        let reveal_url = self.reveal_url(&view_token);
+        let formatted_date = datetime::format_date(game.event_date, game.locale);
+        let maps_url = game.event_location.as_deref().map(Self::maps_url);
+        let event_location = game.event_location.as_deref().zip(maps_url.as_ref());
+
+        let html_body = html::participant_event_reminder_email(
+            game.locale,
+            &participant.name,
+            &game.name,
+            &formatted_date,
+            event_location,
+            game.organizer_message.as_deref(),
+            &reveal_url,
+            &links.page,
+        )
+        .into_string();
+        let plain_body = plain::participant_event_reminder_email(
+            game.locale,
+            &participant.name,
+            &game.name,
+            &formatted_date,
+            event_location,
+            game.organizer_message.as_deref(),
+            &reveal_url,
+            &links.page,
+        );
+
+        self.send_optional(
+            &participant.email,
+            Self::reply_to(game),
+            links,
+            game_subject(game.locale.tr("📅 Está chegando:"), &game.name),
+            plain_body,
+            html_body,
+        )
+        .await
     }
 
-    fn admin_url(&self, game_id: GameId, admin_token: &AdminToken) -> Url {
+    /// The game's page, where organizers manage it.
+    pub fn game_url(&self, game_id: GameId) -> Url {
         let mut url = self.inner.base_url.clone();
         url.path_segments_mut()
             .unwrap()
             .push("jogo")
             .push(&game_id.to_string());
-        url.query_pairs_mut()
-            .append_pair("admin_token", admin_token.as_str());
         url
     }
 
+    /// Link to the game for its organizer. Only the welcome email can include the legacy
+    /// admin token, since just its hash is stored; later emails rely on magic link logins.
+    pub fn admin_url(&self, game_id: GameId, admin_token: &AdminToken) -> Url {
+        let mut url = self.game_url(game_id);
+        // Without legacy tokens, organizers log in via magic link when opening the page.
+        if self.inner.legacy_admin_tokens {
+            url.query_pairs_mut()
+                .append_pair("admin_token", admin_token.as_str());
+        }
+        url
+    }
+
+    /// The email confirming the draw to the organizer, as sent by
+    /// [`send_organizer_confirmation`](Self::send_organizer_confirmation).
+    pub fn organizer_confirmation(&self, game: &Game, participant_count: usize) -> RenderedEmail {
+        let admin_url = self.game_url(game.id);
+        let formatted_date = datetime::format_date(game.event_date, game.locale);
+        let maps_url = game.event_location.as_deref().map(Self::maps_url);
+        let event_location = game.event_location.as_deref().zip(maps_url.as_ref());
+
+        // Generate HTML using Maud template (XSS-safe)
+        let html_body = html::organizer_email(
+            game.locale,
+            &game.name,
+            &formatted_date,
+            event_location,
+            participant_count,
+            &admin_url,
+        )
+        .into_string();
+
+        // Generate plain-text
+        let plain_body = plain::organizer_email(
+            game.locale,
+            &game.name,
+            &formatted_date,
+            event_location,
+            participant_count,
+            &admin_url,
+        );
+
+        RenderedEmail {
+            subject: game_subject(game.locale.tr("✅ Sorteio Realizado:"), &game.name),
+            text: plain_body,
+            html: html_body,
+            inline_images: vec![Self::qr_code(&admin_url)],
+        }
+    }
+
     pub async fn send_organizer_confirmation(
         &self,
-        organizer_email: &EmailAddress,
-        game_name: &str,
-        event_date: NaiveDate,
-        game_id: GameId,
-        admin_token: &AdminToken,
+        game: &Game,
         participant_count: usize,
     ) -> Result<()> {
-        let admin_url = self.admin_url(game_id, admin_token);
-        let formatted_date = format_brazilian_date(event_date);
+        let email = self.organizer_confirmation(game, participant_count);
+        let mut attachments = vec![self.calendar_event(game)];
+        attachments.extend(email.inline_images);
+        self.send_with_attachments(
+            game.organizer_email.to_mailbox(),
+            None,
+            email.subject,
+            email.text,
+            email.html,
+            attachments,
+        )
+        .await
+    }
+
+    /// Tells the organizer how the game went, after the event, unless they unsubscribed.
+    pub async fn send_wrap_up(&self, game: &Game, stats: &WrapUpStats) -> Result<()> {
+        let Some(links) = self.unsubscribe_links(&game.organizer_email).await? else {
+            return Ok(());
+        };
+        let formatted_date = datetime::format_date(game.event_date, game.locale);
 
-        // Generate HTML using Maud template (XSS-safe)
         let html_body =
-            html::organizer_email(game_name, &formatted_date, participant_count, &admin_url)
+            html::wrap_up_email(game.locale, &game.name, &formatted_date, stats, &links.page)
                 .into_string();
-
-        // Generate plain-text
         let plain_body =
-            plain::organizer_email(game_name, &formatted_date, participant_count, &admin_url);
-
-        let email = Message::builder()
-            .from(self.inner.from_address.clone())
-            .to(organizer_email.to_mailbox())
-            .subject(format!("✅ Sorteio Realizado: {}", game_name))
-            .multipart(
-                lettre::message::MultiPart::alternative()
-                    .singlepart(
-                        lettre::message::SinglePart::builder()
-                            .header(ContentType::TEXT_PLAIN)
-                            .body(plain_body),
-                    )
-                    .singlepart(
-                        lettre::message::SinglePart::builder()
-                            .header(ContentType::TEXT_HTML)
-                            .body(html_body),
-                    ),
-            )?;
+            plain::wrap_up_email(game.locale, &game.name, &formatted_date, stats, &links.page);
+
+        self.send_optional(
+            &game.organizer_email,
+            None,
+            links,
+            game_subject(game.locale.tr("🎉 Resumo:"), &game.name),
+            plain_body,
+            html_body,
+        )
+        .await
+    }
 
-        self.inner.mailer.send(email).await?;
-        Ok(())
+    /// Tells the organizer who has seen their match so far, unless they unsubscribed.
+    pub async fn send_digest(&self, game: &Game, stats: &WrapUpStats) -> Result<()> {
+        let Some(links) = self.unsubscribe_links(&game.organizer_email).await? else {
+            return Ok(());
+        };
+        let admin_url = self.game_url(game.id);
+        let formatted_date = datetime::format_date(game.event_date, game.locale);
+
+        let html_body = html::digest_email(
+            game.locale,
+            &game.name,
+            &formatted_date,
+            stats,
+            &admin_url,
+            &links.page,
+        )
+        .into_string();
+        let plain_body = plain::digest_email(
+            game.locale,
+            &game.name,
+            &formatted_date,
+            stats,
+            &admin_url,
+            &links.page,
+        );
+
+        self.send_optional(
+            &game.organizer_email,
+            None,
+            links,
+            game_subject(game.locale.tr("📊 Resumo semanal:"), &game.name),
+            plain_body,
+            html_body,
+        )
+        .await
+    }
+
+    /// Sends an announcement from the site admin to an organizer, unless they unsubscribed.
+    pub async fn send_announcement(
+        &self,
+        to: &EmailAddress,
+        locale: Locale,
+        subject: &str,
+        message: &str,
+    ) -> Result<()> {
+        let Some(links) = self.unsubscribe_links(to).await? else {
+            return Ok(());
+        };
+
+        let html_body =
+            html::announcement_email(locale, subject, message, &links.page).into_string();
+        let plain_body = plain::announcement_email(locale, message, &links.page);
+
+        self.send_optional(
+            to,
+            None,
+            links,
+            game_subject(locale.tr("📢 Aviso:"), subject),
+            plain_body,
+            html_body,
+        )
+        .await
+    }
+
+    /// Tells the organizer that a participant's email bounced or was marked as spam.
+    pub async fn send_undeliverable_notice(
+        &self,
+        game: &Game,
+        participant: &Participant,
+        kind: EmailEventKind,
+    ) -> Result<()> {
+        let admin_url = self.game_url(game.id);
+        let complaint = kind == EmailEventKind::Complaint;
+
+        let html_body = html::undeliverable_email(
+            game.locale,
+            &game.name,
+            &participant.name,
+            participant.email.as_ref(),
+            complaint,
+            &admin_url,
+        )
+        .into_string();
+        let plain_body = plain::undeliverable_email(
+            game.locale,
+            &game.name,
+            &participant.name,
+            participant.email.as_ref(),
+            complaint,
+            &admin_url,
+        );
+
+        self.send(
+            game.organizer_email.to_mailbox(),
+            game_subject(game.locale.tr("⚠️ Email não entregue:"), &game.name),
+            plain_body,
+            html_body,
+        )
+        .await
     }
 
     pub async fn send_verification_code(
         &self,
         recipient_email: &EmailAddress,
         game_name: &str,
+        verification_id: VerificationId,
         verification_code: VerificationCode,
+        link_token: &VerificationLinkToken,
+        locale: Locale,
     ) -> Result<()> {
+        let confirm_url = self
+            .inner
+            .base_url
+            .join(&format!("verificar/{}/{}", verification_id, link_token))
+            .unwrap();
+
         // Generate HTML using Maud template (XSS-safe)
-        let html_body = html::verification_email(game_name, verification_code).into_string();
+        let html_body =
+            html::verification_email(locale, game_name, verification_code, &confirm_url)
+                .into_string();
 
         // Generate plain-text
-        let plain_body = plain::verification_email(game_name, verification_code);
-
-        let email = Message::builder()
-            .from(self.inner.from_address.clone())
-            .to(recipient_email.to_mailbox())
-            .subject("🔐 Código de Verificação")
-            .multipart(
-                lettre::message::MultiPart::alternative()
-                    .singlepart(
-                        lettre::message::SinglePart::builder()
-                            .header(ContentType::TEXT_PLAIN)
-                            .body(plain_body),
-                    )
-                    .singlepart(
-                        lettre::message::SinglePart::builder()
-                            .header(ContentType::TEXT_HTML)
-                            .body(html_body),
-                    ),
-            )?;
+        let plain_body =
+            plain::verification_email(locale, game_name, verification_code, &confirm_url);
 
-        self.inner.mailer.send(email).await?;
-        Ok(())
+        self.send(
+            recipient_email.to_mailbox(),
+            locale.tr("🔐 Código de Verificação").to_string(),
+            plain_body,
+            html_body,
+        )
+        .await
+    }
+
+    pub async fn send_reveal_recovery_code(
+        &self,
+        recipient_email: &EmailAddress,
+        recovery_code: VerificationCode,
+        locale: Locale,
+    ) -> Result<()> {
+        let html_body = html::reveal_recovery_email(locale, recovery_code).into_string();
+        let plain_body = plain::reveal_recovery_email(locale, recovery_code);
+
+        self.send(
+            recipient_email.to_mailbox(),
+            locale
+                .tr("🔐 Código para recuperar seu Amigo Oculto")
+                .to_string(),
+            plain_body,
+            html_body,
+        )
+        .await
+    }
+
+    fn login_url(&self, token: &OrganizerLoginToken) -> Url {
+        self.inner
+            .base_url
+            .join(&format!("entrar/{}", token))
+            .unwrap()
+    }
+
+    pub async fn send_organizer_login_link(
+        &self,
+        organizer_email: &EmailAddress,
+        token: &OrganizerLoginToken,
+        locale: Locale,
+    ) -> Result<()> {
+        let login_url = self.login_url(token);
+
+        // Generate HTML using Maud template (XSS-safe)
+        let html_body = html::organizer_login_email(locale, &login_url).into_string();
+
+        // Generate plain-text
+        let plain_body = plain::organizer_login_email(locale, &login_url);
+
+        self.send(
+            organizer_email.to_mailbox(),
+            locale.tr("🔑 Acesso do Organizador").to_string(),
+            plain_body,
+            html_body,
+        )
+        .await
     }
 
     pub async fn send_admin_welcome(
@@ -270,42 +897,126 @@ impl EmailService {
         event_date: NaiveDate,
         game_id: GameId,
         admin_token: &AdminToken,
+        locale: Locale,
     ) -> Result<()> {
         let admin_url = self.admin_url(game_id, admin_token);
-        let formatted_date = format_brazilian_date(event_date);
+        let formatted_date = datetime::format_date(event_date, locale);
 
         // Generate HTML using Maud template (XSS-safe)
         let html_body =
-            html::admin_welcome_email(game_name, &formatted_date, &admin_url).into_string();
+            html::admin_welcome_email(locale, game_name, &formatted_date, &admin_url).into_string();
 
         // Generate plain-text
-        let plain_body = plain::admin_welcome_email(game_name, &formatted_date, &admin_url);
-
-        let email = Message::builder()
-            .from(self.inner.from_address.clone())
-            .to(organizer_email.to_mailbox())
-            .subject(format!("🎉 Jogo Criado: {}", game_name))
-            .multipart(
-                lettre::message::MultiPart::alternative()
-                    .singlepart(
-                        lettre::message::SinglePart::builder()
-                            .header(ContentType::TEXT_PLAIN)
-                            .body(plain_body),
-                    )
-                    .singlepart(
-                        lettre::message::SinglePart::builder()
-                            .header(ContentType::TEXT_HTML)
-                            .body(html_body),
-                    ),
-            )?;
+        let plain_body = plain::admin_welcome_email(locale, game_name, &formatted_date, &admin_url);
+
+        self.send_with_attachments(
+            organizer_email.to_mailbox(),
+            None,
+            game_subject(locale.tr("🎉 Jogo Criado:"), game_name),
+            plain_body,
+            html_body,
+            vec![Self::qr_code(&admin_url)],
+        )
+        .await
+    }
+}
+
+/// Renders an organizer-written template, or `None` to fall back to the built-in one if it
+/// became invalid (e.g. it was imported from an export).
+fn render_custom(game: &Game, template: &str, values: &custom::TemplateValues) -> Option<String> {
+    custom::render(template, values)
+        .inspect_err(|e| tracing::warn!("invalid email template in game {}: {}", game.id, e))
+        .ok()
+}
+
+/// PNG of a QR code of the link, at error correction level M with 6 pixels per module.
+fn qr_png(url: &Url) -> Result<Vec<u8>> {
+    let image = QrCode::with_error_correction_level(url.as_str(), EcLevel::M)?
+        .render::<Luma<u8>>()
+        .module_dimensions(6, 6)
+        .build();
+    let mut png = Vec::new();
+    image.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+    Ok(png)
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
 
-        self.inner.mailer.send(email).await?;
-        Ok(())
+/// Subject line mentioning a game, e.g. "🎁 Natal da Família".
+///
+/// Line breaks and other control characters are replaced and runs of whitespace collapsed,
+/// and long names are cut short with an ellipsis. Lettre then encodes non-ASCII subjects
+/// as RFC 2047 encoded words.
+fn game_subject(prefix: &str, game_name: &str) -> String {
+    let words: Vec<&str> = game_name
+        .split(|c: char| c.is_whitespace() || c.is_control())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let name = words.join(" ");
+
+    if name.chars().count() <= MAX_SUBJECT_GAME_NAME_LENGTH {
+        return format!("{prefix} {name}");
     }
+    let truncated: String = name
+        .chars()
+        .take(MAX_SUBJECT_GAME_NAME_LENGTH - 1)
+        .collect();
+    format!("{prefix} {}…", truncated.trim_end())
 }
 
-/// Formats a date in Brazilian Portuguese format (e.g., "25 de dezembro de 2024")
-fn format_brazilian_date(date: NaiveDate) -> String {
-    date.format_localized("%e de %B de %Y", Locale::pt_BR)
-        .to_string()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lettre::Message;
+
+    #[test]
+    fn test_game_subject_sanitizes_name() {
+        assert_eq!(
+            game_subject("🎁", "  Natal\r\nBcc: x@example.com\t da  Família "),
+            "🎁 Natal Bcc: x@example.com da Família"
+        );
+    }
+
+    #[test]
+    fn test_game_subject_truncates_long_names() {
+        let subject = game_subject("🎁", &"Amigo Oculto ".repeat(25));
+        assert!(subject.starts_with("🎁 Amigo Oculto Amigo"));
+        assert!(subject.ends_with("Amigo O…"));
+        assert_eq!(
+            subject.chars().count(),
+            "🎁 ".chars().count() + MAX_SUBJECT_GAME_NAME_LENGTH
+        );
+    }
+
+    #[test]
+    fn test_long_subject_header_lines_are_short() {
+        let message = Message::builder()
+            .from("a@example.com".parse().unwrap())
+            .to("b@example.com".parse().unwrap())
+            .subject(game_subject("🎁", &"Confraternização ".repeat(20)))
+            .body(String::new())
+            .unwrap();
+        let formatted = String::from_utf8(message.formatted()).unwrap();
+        assert!(formatted.lines().all(|line| line.len() <= 78));
+    }
+
+    #[test]
+    fn test_qr_png() {
+        let url: Url = "https://amigooculto.app/revelar/01JG3Z5Y8K4N2M7Q9R0S1T2V3W"
+            .parse()
+            .unwrap();
+        let png = qr_png(&url).unwrap();
+        let image = image::load_from_memory_with_format(&png, ImageFormat::Png)
+            .unwrap()
+            .into_luma8();
+        let modules = QrCode::with_error_correction_level(url.as_str(), EcLevel::M)
+            .unwrap()
+            .width() as u32;
+        // Quiet zone of 4 modules on each side, then the top left finder
+        assert_eq!(image.width(), (modules + 8) * 6);
+        assert_eq!(image.get_pixel(3 * 6, 3 * 6).0, [255]);
+        assert_eq!(image.get_pixel(4 * 6, 4 * 6).0, [0]);
+    }
 }
@@ -0,0 +1,129 @@
+//! Disposable ("throwaway") email domains, which can't be used to verify a game.
+//!
+//! A game created with a throwaway address can't be managed once the inbox expires, and such
+//! addresses are mostly used to abuse the site. The bundled list covers popular services;
+//! `BLOCKED_EMAIL_DOMAINS` adds more.
+
+use crate::token::{EmailAddress, EmailDomain};
+use std::collections::HashSet;
+
+/// Popular disposable email services.
+const DISPOSABLE_DOMAINS: &[&str] = &[
+    "10minutemail.com",
+    "10minutemail.net",
+    "20minutemail.com",
+    "33mail.com",
+    "anonbox.net",
+    "burnermail.io",
+    "discard.email",
+    "dispostable.com",
+    "dropmail.me",
+    "emailondeck.com",
+    "fakeinbox.com",
+    "getairmail.com",
+    "getnada.com",
+    "guerrillamail.biz",
+    "guerrillamail.com",
+    "guerrillamail.de",
+    "guerrillamail.info",
+    "guerrillamail.net",
+    "guerrillamail.org",
+    "guerrillamailblock.com",
+    "harakirimail.com",
+    "inboxkitten.com",
+    "incognitomail.org",
+    "jetable.org",
+    "mail-temp.com",
+    "mailcatch.com",
+    "maildrop.cc",
+    "mailinator.com",
+    "mailinator.net",
+    "mailnesia.com",
+    "mailsac.com",
+    "mintemail.com",
+    "mohmal.com",
+    "moakt.com",
+    "mytemp.email",
+    "nada.email",
+    "sharklasers.com",
+    "spam4.me",
+    "spamgourmet.com",
+    "temp-mail.io",
+    "temp-mail.org",
+    "tempail.com",
+    "tempmail.dev",
+    "tempmail.net",
+    "tempmailo.com",
+    "tempr.email",
+    "throwawaymail.com",
+    "tmail.ws",
+    "trashmail.com",
+    "trashmail.de",
+    "trashmail.net",
+    "yopmail.com",
+    "yopmail.fr",
+    "yopmail.net",
+];
+
+#[derive(Debug, Clone)]
+pub struct EmailBlocklist {
+    domains: HashSet<String>,
+}
+
+impl EmailBlocklist {
+    /// The bundled domains plus `extra_domains`.
+    pub fn new(extra_domains: &[EmailDomain]) -> Self {
+        let domains = DISPOSABLE_DOMAINS
+            .iter()
+            .map(|domain| domain.to_string())
+            .chain(
+                extra_domains
+                    .iter()
+                    .map(|domain| domain.as_str().to_string()),
+            )
+            .collect();
+        Self { domains }
+    }
+
+    /// Whether the address belongs to a blocked domain or one of its subdomains.
+    pub fn is_blocked(&self, email: &EmailAddress) -> bool {
+        let domain = email.domain().to_ascii_lowercase();
+        let mut candidate = domain.as_str();
+        loop {
+            if self.domains.contains(candidate) {
+                return true;
+            }
+            match candidate.split_once('.') {
+                Some((_, parent)) if parent.contains('.') => candidate = parent,
+                _ => return false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocked(blocklist: &EmailBlocklist, email: &str) -> bool {
+        blocklist.is_blocked(&email.parse().unwrap())
+    }
+
+    #[test]
+    fn test_bundled_domains() {
+        let blocklist = EmailBlocklist::new(&[]);
+        assert!(blocked(&blocklist, "someone@mailinator.com"));
+        assert!(blocked(&blocklist, "someone@YOPMAIL.com"));
+        assert!(blocked(&blocklist, "someone@inbox.guerrillamail.com"));
+        assert!(!blocked(&blocklist, "someone@gmail.com"));
+        assert!(!blocked(&blocklist, "someone@notmailinator.com"));
+    }
+
+    #[test]
+    fn test_extra_domains() {
+        let blocklist = EmailBlocklist::new(&["descartavel.com.br".parse().unwrap()]);
+        assert!(blocked(&blocklist, "someone@descartavel.com.br"));
+        assert!(!blocked(&blocklist, "someone@com.br"));
+        assert!(blocked(&blocklist, "someone@mailinator.com"));
+    }
+}
@@ -0,0 +1,267 @@
+//! Sends the participant emails of a draw, and the site admin's announcements, in the
+//! background.
+//!
+//! Emailing a large game one participant at a time on the request path kept the organizer
+//! waiting, so the draw only queues an [`EmailJob`] and returns its id for the frontend to
+//! poll. A single worker sends queued jobs a few emails at a time and at a limited rate, so
+//! that email providers don't throttle us. Jobs are stored in the database: emails that
+//! weren't sent before a restart are sent once the server is back. [`Announcement`]s to
+//! every organizer are queued and sent the same way, after the draws.
+//!
+//! Jobs store which [`EmailTemplate`] to send and to whom, not rendered emails: each one is
+//! rendered from the game and participant as they are when it's sent. Failed sends are
+//! retried a few times after a delay (see `Database::record_email_job_result`), so a
+//! participant whose name or address the organizer corrected meanwhile gets the fixed email.
+
+use crate::db::Database;
+use crate::email::EmailService;
+use crate::game_events::{GameEvent, GameEvents};
+use crate::models::{Announcement, EmailJob, EmailTemplate};
+use crate::server::record_run;
+use anyhow::Result;
+use std::num::{NonZeroU32, NonZeroUsize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::JoinSet;
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+
+/// How fast emails are sent (`EMAIL_CONCURRENCY` and `EMAIL_RATE_LIMIT`).
+#[derive(Debug, Clone, Copy)]
+pub struct DispatchConfig {
+    /// Emails being sent at the same time.
+    pub concurrency: NonZeroUsize,
+    /// Emails started per second.
+    pub per_second: NonZeroU32,
+}
+
+impl Default for DispatchConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: NonZeroUsize::new(5).expect("5 is not zero"),
+            per_second: NonZeroU32::new(10).expect("10 is not zero"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct EmailDispatcher {
+    db: Database,
+    email_service: EmailService,
+    events: GameEvents,
+    config: DispatchConfig,
+    queued: Arc<Notify>,
+}
+
+impl EmailDispatcher {
+    pub fn new(
+        db: Database,
+        email_service: EmailService,
+        events: GameEvents,
+        config: DispatchConfig,
+    ) -> Self {
+        Self {
+            db,
+            email_service,
+            events,
+            config,
+            queued: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Tells the worker that a job was queued.
+    pub fn wake(&self) {
+        self.queued.notify_one();
+    }
+
+    /// Sends queued jobs until shutdown, starting with those left over from a previous run,
+    /// and again whenever a failed email is due for a retry.
+    ///
+    /// On shutdown the emails already being sent are finished and the rest stay queued.
+    pub async fn run(self, cancel: CancellationToken) {
+        loop {
+            if let Err(e) = record_run(&self.db, "dispatch_emails", self.send_queued(&cancel)).await
+            {
+                tracing::error!("failed to send queued emails: {}", e);
+            }
+            let next_retry = match self.db.next_email_job_retry(None).await {
+                Ok(next_retry) => next_retry,
+                Err(e) => {
+                    tracing::error!("failed to find email retries: {}", e);
+                    None
+                }
+            };
+            let until_retry = next_retry
+                .map(|retry_at| (retry_at - chrono::Utc::now()).to_std().unwrap_or_default());
+
+            tokio::select! {
+                _ = self.queued.notified() => {}
+                _ = tokio::time::sleep(until_retry.unwrap_or_default()), if until_retry.is_some() => {}
+                _ = cancel.cancelled() => {
+                    tracing::trace!("email dispatch task received shutdown signal");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Sends every queued email. Returns the number of emails attempted.
+    async fn send_queued(&self, cancel: &CancellationToken) -> Result<u64> {
+        let mut attempted = 0;
+        for job in self.db.find_unfinished_email_jobs().await? {
+            if cancel.is_cancelled() {
+                break;
+            }
+            attempted += self.send_job(&job, cancel).await?;
+        }
+        for announcement in self.db.find_unfinished_announcements().await? {
+            if cancel.is_cancelled() {
+                break;
+            }
+            attempted += self.send_announcement(&announcement, cancel).await?;
+        }
+        Ok(attempted)
+    }
+
+    async fn send_job(&self, job: &EmailJob, cancel: &CancellationToken) -> Result<u64> {
+        let Some(template) = job.template else {
+            // Left for the newer version that queued it
+            tracing::warn!("skipping email job {} with an unknown template", job.id);
+            return Ok(0);
+        };
+        let Some(game) = self.db.get_game_by_id(job.game_id).await? else {
+            // Deleted since the draw
+            self.db.finish_email_job(job.id).await?;
+            return Ok(0);
+        };
+
+        let mut pending = self
+            .db
+            .get_pending_email_job_recipients(job.id)
+            .await?
+            .into_iter();
+        let mut pace = tokio::time::interval(Duration::from_secs(1) / self.config.per_second.get());
+        pace.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut in_flight = JoinSet::new();
+        let mut attempted = 0;
+
+        loop {
+            if in_flight.len() < self.config.concurrency.get()
+                && !cancel.is_cancelled()
+                && let Some(participant) = pending.next()
+            {
+                pace.tick().await;
+                let email_service = self.email_service.clone();
+                let game = game.clone();
+                in_flight.spawn(async move {
+                    let result = match template {
+                        EmailTemplate::ParticipantNotification => {
+                            email_service
+                                .send_participant_notification(&game, &participant)
+                                .await
+                        }
+                    };
+                    (participant, result)
+                });
+                continue;
+            }
+
+            let Some(finished) = in_flight.join_next().await else {
+                break;
+            };
+            let (participant, result) = finished?;
+            if let Err(e) = &result {
+                tracing::error!("failed to send email to {}: {}", participant.email, e);
+            }
+            let done = self
+                .db
+                .record_email_job_result(job.id, participant.id, result.is_ok())
+                .await?;
+            let participant_id = participant.id;
+            if done {
+                let event = match result {
+                    Ok(()) => GameEvent::EmailSent { participant_id },
+                    Err(_) => GameEvent::EmailFailed { participant_id },
+                };
+                self.events.publish(game.id, event);
+            }
+            attempted += 1;
+        }
+
+        if !pending.as_slice().is_empty() {
+            // Interrupted by shutdown
+            return Ok(attempted);
+        }
+        if self.db.next_email_job_retry(Some(job.id)).await?.is_some() {
+            // Finished once the retries are
+            return Ok(attempted);
+        }
+
+        if let Err(e) = self
+            .email_service
+            .send_organizer_confirmation(&game, job.total as usize)
+            .await
+        {
+            tracing::error!("failed to send confirmation email to organizer: {}", e);
+        }
+        self.db.finish_email_job(job.id).await?;
+        Ok(attempted)
+    }
+
+    async fn send_announcement(
+        &self,
+        announcement: &Announcement,
+        cancel: &CancellationToken,
+    ) -> Result<u64> {
+        let mut pending = self
+            .db
+            .get_pending_announcement_recipients(announcement.id)
+            .await?
+            .into_iter();
+        let mut pace = tokio::time::interval(Duration::from_secs(1) / self.config.per_second.get());
+        pace.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut in_flight = JoinSet::new();
+        let mut attempted = 0;
+
+        loop {
+            if in_flight.len() < self.config.concurrency.get()
+                && !cancel.is_cancelled()
+                && let Some(recipient) = pending.next()
+            {
+                pace.tick().await;
+                let email_service = self.email_service.clone();
+                let (subject, message) =
+                    (announcement.subject.clone(), announcement.message.clone());
+                in_flight.spawn(async move {
+                    let result = email_service
+                        .send_announcement(&recipient.email, recipient.locale, &subject, &message)
+                        .await;
+                    (recipient, result)
+                });
+                continue;
+            }
+
+            let Some(finished) = in_flight.join_next().await else {
+                break;
+            };
+            let (recipient, result) = finished?;
+            if let Err(e) = &result {
+                tracing::error!("failed to send announcement to {}: {}", recipient.email, e);
+            }
+            self.db
+                .record_announcement_result(announcement.id, &recipient.email, result.is_ok())
+                .await?;
+            attempted += 1;
+        }
+
+        if !pending.as_slice().is_empty() {
+            // Interrupted by shutdown
+            return Ok(attempted);
+        }
+
+        tracing::info!("finished sending announcement {}", announcement.id);
+        self.db.finish_announcement(announcement.id).await?;
+        Ok(attempted)
+    }
+}
@@ -0,0 +1,291 @@
+//! Bounce and complaint notifications from email providers.
+//!
+//! Amazon SES posts them through SNS, and SendGrid through its event webhook. Both are
+//! reduced to [`EmailEvent`]s; everything else they report (deliveries, opens, temporary
+//! bounces) is ignored.
+//!
+//! Providers authenticate with the `EMAIL_WEBHOOK_SECRET` as the password of HTTP Basic
+//! credentials in the webhook URL (`https://webhook:<secret>@host/api/email/events`), which
+//! both SendGrid and SNS support, or as `Authorization: Bearer <secret>`. Never in the query
+//! string, which ends up in access logs.
+
+use crate::token::EmailAddress;
+use axum::http::{HeaderMap, header};
+use base64::prelude::*;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailEventKind {
+    /// The address doesn't exist or permanently rejects email.
+    Bounce,
+    /// The recipient marked the email as spam.
+    Complaint,
+}
+
+impl EmailEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Bounce => "bounce",
+            Self::Complaint => "complaint",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailEvent {
+    pub email: EmailAddress,
+    pub kind: EmailEventKind,
+}
+
+/// What a provider posted to the webhook.
+#[derive(Debug, PartialEq, Eq)]
+pub enum WebhookPayload {
+    Events(Vec<EmailEvent>),
+    /// SNS asks for the subscription to be confirmed by visiting a URL.
+    SnsSubscription {
+        subscribe_url: String,
+    },
+}
+
+/// The webhook secret sent with a request: the Basic password, whatever the user name, or the
+/// Bearer token.
+pub fn secret_from_headers(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    if let Some(token) = value.strip_prefix("Bearer ") {
+        return Some(token.trim().to_string());
+    }
+    let credentials = BASE64_STANDARD
+        .decode(value.strip_prefix("Basic ")?.trim())
+        .ok()?;
+    let credentials = String::from_utf8(credentials).ok()?;
+    let (_, password) = credentials.split_once(':')?;
+    Some(password.to_string())
+}
+
+/// Parses a webhook body in either SendGrid (a JSON array) or SNS/SES (a JSON object) format.
+pub fn parse(body: &[u8]) -> serde_json::Result<WebhookPayload> {
+    let value: serde_json::Value = serde_json::from_slice(body)?;
+    if value.is_array() {
+        let events: Vec<SendGridEvent> = serde_json::from_value(value)?;
+        return Ok(WebhookPayload::Events(
+            events.iter().filter_map(SendGridEvent::to_event).collect(),
+        ));
+    }
+
+    let notification: SnsNotification = serde_json::from_value(value.clone())?;
+    match notification.kind.as_deref() {
+        Some("SubscriptionConfirmation") => Ok(WebhookPayload::SnsSubscription {
+            subscribe_url: notification.subscribe_url.unwrap_or_default(),
+        }),
+        Some("Notification") => {
+            let message: SesMessage =
+                serde_json::from_str(notification.message.as_deref().unwrap_or("{}"))?;
+            Ok(WebhookPayload::Events(message.events()))
+        }
+        // SNS raw message delivery posts the SES message as is
+        _ => Ok(WebhookPayload::Events(
+            serde_json::from_value::<SesMessage>(value)?.events(),
+        )),
+    }
+}
+
+#[derive(Deserialize)]
+struct SendGridEvent {
+    email: String,
+    event: String,
+    /// For bounces: `bounce` for permanent ones, `blocked` for temporary ones.
+    #[serde(rename = "type")]
+    bounce_type: Option<String>,
+}
+
+impl SendGridEvent {
+    fn to_event(&self) -> Option<EmailEvent> {
+        let kind = match (self.event.as_str(), self.bounce_type.as_deref()) {
+            ("bounce", Some("blocked")) => return None,
+            ("bounce", _) => EmailEventKind::Bounce,
+            ("spamreport", _) => EmailEventKind::Complaint,
+            _ => return None,
+        };
+        Some(EmailEvent {
+            email: self.email.trim().parse().ok()?,
+            kind,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct SnsNotification {
+    #[serde(rename = "Type")]
+    kind: Option<String>,
+    #[serde(rename = "Message")]
+    message: Option<String>,
+    #[serde(rename = "SubscribeURL")]
+    subscribe_url: Option<String>,
+}
+
+/// An SES notification (`notificationType`) or event (`eventType`).
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SesMessage {
+    notification_type: Option<String>,
+    event_type: Option<String>,
+    bounce: Option<SesBounce>,
+    complaint: Option<SesComplaint>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SesBounce {
+    bounce_type: String,
+    bounced_recipients: Vec<SesRecipient>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SesComplaint {
+    complained_recipients: Vec<SesRecipient>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SesRecipient {
+    email_address: String,
+}
+
+impl SesMessage {
+    fn events(&self) -> Vec<EmailEvent> {
+        let (kind, recipients) = match (
+            self.notification_type
+                .as_deref()
+                .or(self.event_type.as_deref()),
+            &self.bounce,
+            &self.complaint,
+        ) {
+            // Transient bounces (e.g. a full mailbox) may succeed later
+            (Some("Bounce"), Some(bounce), _) if bounce.bounce_type == "Permanent" => {
+                (EmailEventKind::Bounce, &bounce.bounced_recipients)
+            }
+            (Some("Complaint"), _, Some(complaint)) => {
+                (EmailEventKind::Complaint, &complaint.complained_recipients)
+            }
+            _ => return Vec::new(),
+        };
+
+        recipients
+            .iter()
+            .filter_map(|recipient| recipient.email_address.trim().parse().ok())
+            .map(|email| EmailEvent { email, kind })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn event(email: &str, kind: EmailEventKind) -> EmailEvent {
+        EmailEvent {
+            email: email.parse().unwrap(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_secret_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert!(secret_from_headers(&headers).is_none());
+
+        // webhook:s3cr:et
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Basic d2ViaG9vazpzM2NyOmV0"),
+        );
+        assert_eq!(secret_from_headers(&headers).unwrap(), "s3cr:et");
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer s3cret"),
+        );
+        assert_eq!(secret_from_headers(&headers).unwrap(), "s3cret");
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Basic not base64!"),
+        );
+        assert!(secret_from_headers(&headers).is_none());
+    }
+
+    #[test]
+    fn test_parse_sendgrid() {
+        let body = br#"[
+            {"email": "maria@example.com", "event": "bounce", "type": "bounce"},
+            {"email": "joao@example.com", "event": "bounce", "type": "blocked"},
+            {"email": "ana@example.com", "event": "spamreport"},
+            {"email": "pedro@example.com", "event": "delivered"}
+        ]"#;
+        assert_eq!(
+            parse(body).unwrap(),
+            WebhookPayload::Events(vec![
+                event("maria@example.com", EmailEventKind::Bounce),
+                event("ana@example.com", EmailEventKind::Complaint),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_ses() {
+        let message = serde_json::json!({
+            "notificationType": "Bounce",
+            "bounce": {
+                "bounceType": "Permanent",
+                "bouncedRecipients": [{ "emailAddress": "maria@example.com" }],
+            },
+        });
+        let body = serde_json::json!({
+            "Type": "Notification",
+            "Message": message.to_string(),
+        });
+        assert_eq!(
+            parse(body.to_string().as_bytes()).unwrap(),
+            WebhookPayload::Events(vec![event("maria@example.com", EmailEventKind::Bounce)])
+        );
+
+        // Raw message delivery, and configuration set events
+        let body = serde_json::json!({
+            "eventType": "Complaint",
+            "complaint": { "complainedRecipients": [{ "emailAddress": "ana@example.com" }] },
+        });
+        assert_eq!(
+            parse(body.to_string().as_bytes()).unwrap(),
+            WebhookPayload::Events(vec![event("ana@example.com", EmailEventKind::Complaint)])
+        );
+
+        let body = serde_json::json!({
+            "eventType": "Bounce",
+            "bounce": {
+                "bounceType": "Transient",
+                "bouncedRecipients": [{ "emailAddress": "maria@example.com" }],
+            },
+        });
+        assert_eq!(
+            parse(body.to_string().as_bytes()).unwrap(),
+            WebhookPayload::Events(Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_parse_sns_subscription() {
+        let body = br#"{
+            "Type": "SubscriptionConfirmation",
+            "SubscribeURL": "https://sns.us-east-1.amazonaws.com/?Action=ConfirmSubscription"
+        }"#;
+        assert_eq!(
+            parse(body).unwrap(),
+            WebhookPayload::SnsSubscription {
+                subscribe_url: "https://sns.us-east-1.amazonaws.com/?Action=ConfirmSubscription"
+                    .to_string()
+            }
+        );
+    }
+}
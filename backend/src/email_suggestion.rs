@@ -0,0 +1,128 @@
+//! Detection of likely typos in email addresses.
+//!
+//! Organizers frequently mistype popular domains (e.g. `gmial.com`), which means the
+//! verification code never arrives. We compare the domain against a list of common
+//! providers and suggest the closest one when it is within a small edit distance.
+
+use crate::token::EmailAddress;
+
+/// Domains commonly used by our (mostly Brazilian) audience.
+const COMMON_DOMAINS: &[&str] = &[
+    "gmail.com",
+    "googlemail.com",
+    "hotmail.com",
+    "hotmail.com.br",
+    "outlook.com",
+    "outlook.com.br",
+    "live.com",
+    "msn.com",
+    "yahoo.com",
+    "yahoo.com.br",
+    "icloud.com",
+    "me.com",
+    "uol.com.br",
+    "bol.com.br",
+    "terra.com.br",
+    "ig.com.br",
+    "globo.com",
+    "protonmail.com",
+    "proton.me",
+];
+
+/// Maximum edit distance for a domain to be considered a typo of a common one.
+const MAX_DISTANCE: usize = 2;
+
+/// Common domains shorter than this only allow a distance of 1, as many legitimate
+/// domains are two edits away from `me.com` or `msn.com`.
+const SHORT_DOMAIN_LENGTH: usize = 8;
+
+/// How many edits away from `candidate` a domain can be to be considered a typo of it.
+fn max_distance(candidate: &str) -> usize {
+    if candidate.len() < SHORT_DOMAIN_LENGTH {
+        1
+    } else {
+        MAX_DISTANCE
+    }
+}
+
+/// Returns a corrected address if the domain looks like a misspelling of a common provider.
+///
+/// Returns `None` when the domain is already a known provider or no provider is close enough
+/// (see `max_distance`).
+pub fn suggest_correction(email: &EmailAddress) -> Option<EmailAddress> {
+    let domain = email.domain().to_ascii_lowercase();
+    if COMMON_DOMAINS.contains(&domain.as_str()) {
+        return None;
+    }
+
+    let (best, _) = COMMON_DOMAINS
+        .iter()
+        .map(|candidate| (candidate, levenshtein(&domain, candidate)))
+        .filter(|(candidate, distance)| *distance <= max_distance(candidate))
+        .min_by_key(|(_, distance)| *distance)?;
+
+    format!("{}@{}", email.user(), best).parse().ok()
+}
+
+/// Classic Levenshtein distance over chars, using a single rolling row.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous_diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous_diagonal + usize::from(ca != *cb);
+            previous_diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggest(email: &str) -> Option<String> {
+        suggest_correction(&email.parse().unwrap()).map(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("gmail.com", "gmail.com"), 0);
+        assert_eq!(levenshtein("gmial.com", "gmail.com"), 2);
+        assert_eq!(levenshtein("gmal.com", "gmail.com"), 1);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_suggests_common_typos() {
+        assert_eq!(suggest("ana@gmial.com").as_deref(), Some("ana@gmail.com"));
+        assert_eq!(
+            suggest("ana@hotmial.com").as_deref(),
+            Some("ana@hotmail.com")
+        );
+        assert_eq!(
+            suggest("ana@yahoo.com.bt").as_deref(),
+            Some("ana@yahoo.com.br")
+        );
+    }
+
+    #[test]
+    fn test_no_suggestion_for_known_or_unrelated_domains() {
+        assert_eq!(suggest("ana@gmail.com"), None);
+        assert_eq!(suggest("ana@GMAIL.com"), None);
+        assert_eq!(suggest("ana@empresa.com.br"), None);
+    }
+
+    #[test]
+    fn test_short_domains_allow_a_single_edit() {
+        // Two edits away from `me.com`, but a real domain
+        assert_eq!(suggest("ana@mac.com"), None);
+        assert_eq!(suggest("ana@ig.com"), None);
+        assert_eq!(suggest("ana@msm.com").as_deref(), Some("ana@msn.com"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::i18n::Locale;
 use maud::{Markup, html};
 use url::Url;
 
@@ -22,6 +23,49 @@ pub fn primary_button(href: &Url, text: &str) -> Markup {
     }
 }
 
+/// Event location with a link to open it in a map
+pub fn location_line(locale: Locale, location: &str, maps_url: &Url) -> Markup {
+    html! {
+        p {
+            "📍 " strong { (locale.tr("Local:")) } " " (location)
+            " (" a href=(maps_url.as_str()) { (locale.tr("ver no mapa")) } ")"
+        }
+    }
+}
+
+/// Content ID of the QR code image attached to emails that show one.
+pub const QR_CODE_CID: &str = "qr-code.png";
+
+/// QR code of the email's link, to open it on a phone
+pub fn qr_code(locale: Locale) -> Markup {
+    html! {
+        div class="qr-code" {
+            p class="text-muted" { (locale.tr("Ou aponte a câmera do celular para o código:")) }
+            img src=(format!("cid:{QR_CODE_CID}")) width="180" height="180" alt="QR code";
+        }
+    }
+}
+
+/// Invisible image loaded from the server when the email is opened
+pub fn tracking_pixel(url: Option<&Url>) -> Markup {
+    html! {
+        @if let Some(url) = url {
+            img src=(url.as_str()) width="1" height="1" alt="" style="display:block;border:0;";
+        }
+    }
+}
+
+/// Link to copy when the button doesn't work
+pub fn fallback_link(locale: Locale, url: &Url) -> Markup {
+    html! {
+        p class="text-muted" {
+            (locale.tr("Se o botão não funcionar, copie e cole este link no seu navegador:"))
+            br;
+            a href=(url.as_str()) { (url.as_str()) }
+        }
+    }
+}
+
 /// Info box with content
 pub fn info_box(content: Markup) -> Markup {
     html! {
@@ -50,10 +94,23 @@ pub fn warning_box(content: Markup) -> Markup {
 }
 
 /// Footer component
-pub fn app_footer() -> Markup {
+pub fn app_footer(locale: Locale) -> Markup {
     html! {
         div class="footer" {
-            p { "Amigo Oculto - Sistema de Sorteio" }
+            p { (locale.tr("Amigo Oculto - Sistema de Sorteio")) }
+        }
+    }
+}
+
+/// Footer for optional emails, with a link to stop receiving them
+pub fn unsubscribe_footer(locale: Locale, unsubscribe_url: &Url) -> Markup {
+    html! {
+        div class="footer" {
+            p { (locale.tr("Amigo Oculto - Sistema de Sorteio")) }
+            p {
+                (locale.tr("Não quer mais receber lembretes e resumos? "))
+                a href=(unsubscribe_url.as_str()) { (locale.tr("Descadastre-se")) }
+            }
         }
     }
 }
@@ -0,0 +1,195 @@
+//! Organizer-written subject and body for the participant email.
+//!
+//! Templates are rendered by MiniJinja in an empty environment: no filters, tests,
+//! functions or other templates, and only the variables below in the context, so a
+//! template can't reach anything but what the email already shows. Undefined variables are
+//! errors rather than blanks, and rendering runs on a fuel budget so loops can't run away.
+//! Values are inserted as text; the HTML email escapes them like any other content.
+
+use minijinja::{Environment, ErrorKind, UndefinedBehavior, context};
+use std::fmt;
+
+/// Variables available to templates.
+pub const VARIABLES: &[&str] = &[
+    "participant_name",
+    "game_name",
+    "event_date",
+    "event_location",
+    "organizer_message",
+    "reveal_url",
+];
+
+/// Instructions a template may run while rendering, far more than filling in variables
+/// and a few conditionals need.
+const FUEL: u64 = 10_000;
+
+/// Values of the [`VARIABLES`] for one email.
+pub struct TemplateValues<'a> {
+    pub participant_name: &'a str,
+    pub game_name: &'a str,
+    pub event_date: &'a str,
+    pub event_location: Option<&'a str>,
+    pub organizer_message: Option<&'a str>,
+    pub reveal_url: &'a str,
+}
+
+impl TemplateValues<'_> {
+    fn context(&self) -> minijinja::Value {
+        context! {
+            participant_name => self.participant_name,
+            game_name => self.game_name,
+            event_date => self.event_date,
+            event_location => self.event_location.unwrap_or_default(),
+            organizer_message => self.organizer_message.unwrap_or_default(),
+            reveal_url => self.reveal_url,
+        }
+    }
+}
+
+/// Why a template can't be used. The message is shown to the organizer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    UnknownVariable(String),
+    /// Invalid syntax, on the given line.
+    Syntax(usize),
+    /// A filter, test or function, none of which are available.
+    Unsupported,
+    Invalid,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownVariable(name) => write!(f, "Variável desconhecida no modelo: {}", name),
+            Self::Syntax(line) => write!(f, "Erro de sintaxe no modelo, na linha {}", line),
+            Self::Unsupported => write!(f, "Modelos não aceitam filtros nem funções"),
+            Self::Invalid => write!(f, "Modelo inválido"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl From<minijinja::Error> for TemplateError {
+    fn from(error: minijinja::Error) -> Self {
+        match error.kind() {
+            ErrorKind::SyntaxError => Self::Syntax(error.line().unwrap_or(1)),
+            ErrorKind::UnknownFilter
+            | ErrorKind::UnknownTest
+            | ErrorKind::UnknownFunction
+            | ErrorKind::UnknownMethod => Self::Unsupported,
+            _ => Self::Invalid,
+        }
+    }
+}
+
+fn environment() -> Environment<'static> {
+    let mut env = Environment::empty();
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    env.set_fuel(Some(FUEL));
+    env
+}
+
+/// Checks that a template compiles, only uses known variables and renders.
+pub fn validate(template: &str) -> Result<(), TemplateError> {
+    let env = environment();
+    let compiled = env.template_from_str(template)?;
+    let mut unknown: Vec<String> = compiled
+        .undeclared_variables(false)
+        .into_iter()
+        .filter(|variable| !VARIABLES.contains(&variable.as_str()))
+        .collect();
+    unknown.sort();
+    if let Some(variable) = unknown.into_iter().next() {
+        return Err(TemplateError::UnknownVariable(variable));
+    }
+    // Filters and functions are only looked up when used, so a render finds them
+    let values = TemplateValues {
+        participant_name: "",
+        game_name: "",
+        event_date: "",
+        event_location: None,
+        organizer_message: None,
+        reveal_url: "",
+    };
+    compiled.render(values.context())?;
+    Ok(())
+}
+
+/// Fills in a template's variables.
+pub fn render(template: &str, values: &TemplateValues) -> Result<String, TemplateError> {
+    let env = environment();
+    Ok(env.template_from_str(template)?.render(values.context())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> TemplateValues<'static> {
+        TemplateValues {
+            participant_name: "<Ana>",
+            game_name: "Natal",
+            event_date: "25 de dezembro de 2025",
+            event_location: None,
+            organizer_message: Some("Até R$ 50"),
+            reveal_url: "https://amigooculto.app/revelar/abc",
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render(
+                "Oi {{participant_name}}! {{ game_name }} em {{ event_date }}{{ event_location }}. {{ organizer_message }} {x}",
+                &values()
+            )
+            .unwrap(),
+            "Oi <Ana>! Natal em 25 de dezembro de 2025. Até R$ 50 {x}"
+        );
+        assert_eq!(
+            render(
+                "{% if event_location %}Em {{ event_location }}{% else %}Local a definir{% endif %}",
+                &values()
+            )
+            .unwrap(),
+            "Local a definir"
+        );
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        assert_eq!(
+            validate("Oi {{ admin_token }}"),
+            Err(TemplateError::UnknownVariable("admin_token".to_string()))
+        );
+        assert_eq!(
+            validate("{% for p in participants %}{% endfor %}"),
+            Err(TemplateError::UnknownVariable("participants".to_string()))
+        );
+        assert_eq!(validate("Oi\n{{ game_name"), Err(TemplateError::Syntax(2)));
+        assert_eq!(
+            validate("{{ game_name | upper }}"),
+            Err(TemplateError::Unsupported)
+        );
+        assert_eq!(
+            validate("{{ range(10) }}"),
+            Err(TemplateError::UnknownVariable("range".to_string()))
+        );
+        assert_eq!(
+            validate("{% include 'admin.html' %}"),
+            Err(TemplateError::Syntax(1))
+        );
+        assert_eq!(
+            validate("{{ game_name.secret }}"),
+            Err(TemplateError::Invalid)
+        );
+        assert_eq!(validate("{# comentário #}{{ game_name }}"), Ok(()));
+    }
+
+    #[test]
+    fn test_runaway_templates_run_out_of_fuel() {
+        let template = "{% for a in reveal_url %}{% for b in reveal_url %}{% for c in reveal_url %}x{% endfor %}{% endfor %}{% endfor %}";
+        assert_eq!(render(template, &values()), Err(TemplateError::Invalid));
+    }
+}
@@ -1,11 +1,12 @@
 use crate::email_templates::styles::EMAIL_STYLES;
+use crate::i18n::Locale;
 use maud::{DOCTYPE, Markup, PreEscaped, html};
 
 /// Base HTML email layout
-pub fn email_layout(title: &str, content: Markup) -> Markup {
+pub fn email_layout(locale: Locale, title: &str, content: Markup) -> Markup {
     html! {
         (DOCTYPE)
-        html lang="pt-BR" {
+        html lang=(locale.as_str()) {
             head {
                 meta charset="UTF-8";
                 meta name="viewport" content="width=device-width, initial-scale=1.0";
@@ -0,0 +1,41 @@
+use super::base::email_layout;
+use crate::email_templates::components::{
+    app_footer, fallback_link, gradient_header, primary_button, warning_box,
+};
+use crate::i18n::Locale;
+use maud::{Markup, html};
+use url::Url;
+
+/// Organizer magic login link email
+pub fn organizer_login_email(locale: Locale, login_url: &Url) -> Markup {
+    let content = html! {
+        (gradient_header(locale.tr("🔑 Acesso do Organizador"), locale.tr("Amigo Oculto")))
+
+        div class="content" {
+            p { (locale.tr("Recebemos um pedido de acesso aos jogos organizados por este email.")) }
+
+            (primary_button(login_url, locale.tr("Entrar")))
+
+            (warning_box(html! {
+                p {
+                    strong { (locale.tr("⏱️ Atenção:")) }
+                    (locale.tr(" Este link expira em 15 minutos e só pode ser usado uma vez."))
+                }
+            }))
+
+            (fallback_link(locale, login_url))
+
+            p class="text-muted" {
+                (locale.tr("Se você não solicitou este acesso, ignore este email."))
+            }
+        }
+
+        (app_footer(locale))
+    };
+
+    email_layout(
+        locale,
+        locale.tr("Acesso do Organizador - Amigo Oculto"),
+        content,
+    )
+}
@@ -1,8 +1,16 @@
 mod base;
+mod login;
 mod organizer;
 mod participant;
 mod verification;
 
-pub use organizer::organizer_email;
-pub use participant::participant_email;
-pub use verification::{admin_welcome_email, verification_email};
+pub use super::components::QR_CODE_CID;
+pub use login::organizer_login_email;
+pub use organizer::{
+    announcement_email, digest_email, organizer_email, undeliverable_email, wrap_up_email,
+};
+pub use participant::{
+    custom_participant_email, participant_email, participant_event_reminder_email,
+    participant_reminder_email,
+};
+pub use verification::{admin_welcome_email, reveal_recovery_email, verification_email};
@@ -1,48 +1,235 @@
 use super::base::email_layout;
 use crate::email_templates::components::{
-    app_footer, gradient_header, primary_button, success_box, warning_box,
+    app_footer, fallback_link, gradient_header, info_box, location_line, primary_button, qr_code,
+    success_box, unsubscribe_footer, warning_box,
 };
+use crate::i18n::{Locale, fill};
+use crate::models::WrapUpStats;
 use maud::{Markup, html};
 use url::Url;
 
 /// Organizer confirmation email template
 pub fn organizer_email(
+    locale: Locale,
     game_name: &str,
     event_date: &str,
+    event_location: Option<(&str, &Url)>,
     participant_count: usize,
     admin_url: &Url,
 ) -> Markup {
     let content = html! {
-        (gradient_header("✅ Sorteio Realizado!", game_name))
+        (gradient_header(locale.tr("✅ Sorteio Realizado!"), game_name))
 
         div class="content" {
-            p { "Parabéns! O sorteio foi realizado com sucesso! 🎉" }
+            p { (locale.tr("Parabéns! O sorteio foi realizado com sucesso! 🎉")) }
 
             (success_box(html! {
-                p { "📅 " strong { "Data do evento:" } " " (event_date) }
-                p { "👥 " strong { "Participantes:" } " " (participant_count) }
+                p { "📅 " strong { (locale.tr("Data do evento:")) } " " (event_date) }
+                @if let Some((location, maps_url)) = event_location {
+                    (location_line(locale, location, maps_url))
+                }
+                p { "👥 " strong { (locale.tr("Participantes:")) } " " (participant_count) }
             }))
 
-            p { "Todos os participantes receberam um email com o link para descobrir quem tiraram." }
+            p { (locale.tr("Todos os participantes receberam um email com o link para descobrir quem tiraram.")) }
 
             (warning_box(html! {
                 p {
-                    strong { "⚠️ Importante:" }
-                    " Guarde este email! Use o link abaixo para acompanhar quem já visualizou seu amigo oculto."
+                    strong { (locale.tr("⚠️ Importante:")) }
+                    (locale.tr(" Guarde este email! Use o link abaixo para acompanhar quem já visualizou seu amigo oculto."))
+                }
+            }))
+
+            (primary_button(admin_url, locale.tr("Acompanhar Status")))
+            (qr_code(locale))
+
+            (fallback_link(locale, admin_url))
+        }
+
+        (app_footer(locale))
+    };
+
+    email_layout(
+        locale,
+        &fill(locale.tr("Sorteio Realizado - {}"), &[game_name]),
+        content,
+    )
+}
+
+/// Wrap-up email sent to the organizer after the event
+pub fn wrap_up_email(
+    locale: Locale,
+    game_name: &str,
+    event_date: &str,
+    stats: &WrapUpStats,
+    unsubscribe_url: &Url,
+) -> Markup {
+    let content = html! {
+        (gradient_header(locale.tr("🎉 Resumo do Amigo Oculto"), game_name))
+
+        div class="content" {
+            p { (fill(locale.tr("O Amigo Oculto de {} já aconteceu! Veja como foi:"), &[event_date])) }
+
+            (success_box(html! {
+                p { "👥 " strong { (locale.tr("Participantes:")) } " " (stats.participant_count) }
+                p {
+                    "👀 " strong { (locale.tr("Viram quem tiraram:")) } " "
+                    (stats.viewed_count) " " (locale.tr("de")) " " (stats.participant_count)
                 }
+                p { "🔁 " strong { (locale.tr("Visualizações no total:")) } " " (stats.reveal_count) }
+                p { "📨 " strong { (locale.tr("Emails reenviados:")) } " " (stats.resend_count) }
+                p { "⏰ " strong { (locale.tr("Lembretes enviados:")) } " " (stats.reminder_count) }
             }))
 
-            (primary_button(admin_url, "Acompanhar Status"))
+            @if !stats.not_viewed.is_empty() {
+                (info_box(html! {
+                    p { strong { (locale.tr("Nunca abriram o link de revelação:")) } }
+                    p {
+                        @for (i, name) in stats.not_viewed.iter().enumerate() {
+                            @if i > 0 { br; }
+                            (name)
+                        }
+                    }
+                }))
+            }
+
+            p { (locale.tr("Obrigado por usar o Amigo Oculto! Até a próxima! 🎁")) }
+        }
+
+        (unsubscribe_footer(locale, unsubscribe_url))
+    };
+
+    email_layout(
+        locale,
+        &fill(locale.tr("Resumo - {}"), &[game_name]),
+        content,
+    )
+}
 
-            p class="text-muted" {
-                "Se o botão não funcionar, copie e cole este link no seu navegador:"
-                br;
-                a href=(admin_url.as_str()) { (admin_url.as_str()) }
+/// Weekly digest for the organizer, until every participant has seen their match
+pub fn digest_email(
+    locale: Locale,
+    game_name: &str,
+    event_date: &str,
+    stats: &WrapUpStats,
+    admin_url: &Url,
+    unsubscribe_url: &Url,
+) -> Markup {
+    let content = html! {
+        (gradient_header(locale.tr("📊 Resumo Semanal"), game_name))
+
+        div class="content" {
+            p { (fill(locale.tr("Veja como está o Amigo Oculto de {}:"), &[event_date])) }
+
+            (success_box(html! {
+                p { "👥 " strong { (locale.tr("Participantes:")) } " " (stats.participant_count) }
+                p {
+                    "👀 " strong { (locale.tr("Viram quem tiraram:")) } " "
+                    (stats.viewed_count) " " (locale.tr("de")) " " (stats.participant_count)
+                }
+            }))
+
+            @if !stats.not_viewed.is_empty() {
+                (info_box(html! {
+                    p { strong { (locale.tr("Ainda não viram quem tiraram:")) } }
+                    p {
+                        @for (i, name) in stats.not_viewed.iter().enumerate() {
+                            @if i > 0 { br; }
+                            (name)
+                        }
+                    }
+                }))
+            }
+
+            p { (locale.tr("Você pode reenviar os emails pela página do jogo.")) }
+
+            (primary_button(admin_url, locale.tr("Acompanhar Status")))
+
+            (fallback_link(locale, admin_url))
+        }
+
+        (unsubscribe_footer(locale, unsubscribe_url))
+    };
+
+    email_layout(
+        locale,
+        &fill(locale.tr("Resumo Semanal - {}"), &[game_name]),
+        content,
+    )
+}
+
+/// Tells the organizer a participant's email can't be delivered
+pub fn undeliverable_email(
+    locale: Locale,
+    game_name: &str,
+    participant_name: &str,
+    participant_email: &str,
+    complaint: bool,
+    admin_url: &Url,
+) -> Markup {
+    let participant = format!("{participant_name} ({participant_email})");
+    let content = html! {
+        (gradient_header(locale.tr("⚠️ Email Não Entregue"), game_name))
+
+        div class="content" {
+            (warning_box(html! {
+                @if complaint {
+                    p { (fill(locale.tr("{} marcou o email do Amigo Oculto como spam."), &[&participant])) }
+                } @else {
+                    p {
+                        (fill(
+                            locale.tr("O email para {} não pôde ser entregue. O endereço pode estar errado ou não existir mais."),
+                            &[&participant],
+                        ))
+                    }
+                }
+            }))
+
+            p {
+                (fill(
+                    locale.tr("Confira o endereço com {} e corrija-o na página do jogo para reenviar o link de revelação."),
+                    &[participant_name],
+                ))
+            }
+
+            (primary_button(admin_url, locale.tr("Corrigir Email")))
+
+            (fallback_link(locale, admin_url))
+        }
+
+        (app_footer(locale))
+    };
+
+    email_layout(
+        locale,
+        &fill(locale.tr("Email Não Entregue - {}"), &[game_name]),
+        content,
+    )
+}
+
+/// Announcement from the site admin to the organizers of active games
+pub fn announcement_email(
+    locale: Locale,
+    subject: &str,
+    message: &str,
+    unsubscribe_url: &Url,
+) -> Markup {
+    let content = html! {
+        (gradient_header(locale.tr("📢 Aviso do Amigo Oculto"), subject))
+
+        div class="content" {
+            @for paragraph in message.split("\n\n").filter(|p| !p.trim().is_empty()) {
+                p {
+                    @for (i, line) in paragraph.trim().lines().enumerate() {
+                        @if i > 0 { br; }
+                        (line)
+                    }
+                }
             }
         }
 
-        (app_footer())
+        (unsubscribe_footer(locale, unsubscribe_url))
     };
 
-    email_layout(&format!("Sorteio Realizado - {}", game_name), content)
+    email_layout(locale, subject, content)
 }
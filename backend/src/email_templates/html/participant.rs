@@ -1,47 +1,222 @@
 use super::base::email_layout;
-use crate::email_templates::components::{app_footer, gradient_header, info_box, primary_button};
+use crate::email_templates::components::{
+    app_footer, fallback_link, gradient_header, info_box, location_line, primary_button, qr_code,
+    tracking_pixel, unsubscribe_footer,
+};
+use crate::i18n::{Locale, fill};
 use maud::{Markup, html};
 use url::Url;
 
 /// Participant notification email template
+#[allow(clippy::too_many_arguments)]
 pub fn participant_email(
+    locale: Locale,
     participant_name: &str,
     game_name: &str,
     event_date: &str,
+    event_location: Option<(&str, &Url)>,
+    organizer_message: Option<&str>,
+    cover_url: Option<&Url>,
     reveal_url: &Url,
+    open_tracking_url: Option<&Url>,
 ) -> Markup {
     let content = html! {
-        (gradient_header("Amigo Oculto", game_name))
+        (gradient_header(locale.tr("Amigo Oculto"), game_name))
 
         div class="content" {
-            p { "Olá " strong { (participant_name) } "!" }
+            @if let Some(cover_url) = cover_url {
+                img class="cover-image" src=(cover_url.as_str()) alt=(game_name);
+            }
+
+            p { (locale.tr("Olá ")) strong { (participant_name) } "!" }
 
             p {
-                "Você foi convidado para participar do Amigo Oculto "
+                (locale.tr("Você foi convidado para participar do Amigo Oculto "))
                 strong { (game_name) } "!"
             }
 
-            p { "📅 " strong { "Data do evento:" } " " (event_date) }
+            p { "📅 " strong { (locale.tr("Data do evento:")) } " " (event_date) }
+
+            @if let Some((location, maps_url)) = event_location {
+                (location_line(locale, location, maps_url))
+            }
+
+            @if let Some(message) = organizer_message {
+                p { strong { (locale.tr("Mensagem do organizador:")) } }
+                p {
+                    @for (i, line) in message.lines().enumerate() {
+                        @if i > 0 { br; }
+                        (line)
+                    }
+                }
+            }
 
             (info_box(html! {
-                p { "Clique no botão abaixo para descobrir quem você tirou:" }
-                (primary_button(reveal_url, "Ver Meu Amigo Oculto"))
+                p { (locale.tr("Clique no botão abaixo para descobrir quem você tirou:")) }
+                (primary_button(reveal_url, locale.tr("Ver Meu Amigo Oculto")))
+                (qr_code(locale))
             }))
 
             p class="text-muted" {
-                strong { "Dica:" }
-                " Guarde este email! Você pode precisar dele para consultar quem você tirou mais tarde."
+                strong { (locale.tr("Dica:")) }
+                (locale.tr(" Guarde este email! Você pode precisar dele para consultar quem você tirou mais tarde."))
             }
 
-            p class="text-muted" {
-                "Se o botão não funcionar, copie e cole este link no seu navegador:"
-                br;
-                a href=(reveal_url.as_str()) { (reveal_url.as_str()) }
+            (fallback_link(locale, reveal_url))
+        }
+
+        (app_footer(locale))
+        (tracking_pixel(open_tracking_url))
+    };
+
+    email_layout(
+        locale,
+        &fill(locale.tr("Amigo Oculto - {}"), &[game_name]),
+        content,
+    )
+}
+
+/// Reminder for a participant who hasn't seen their match yet
+pub fn participant_reminder_email(
+    locale: Locale,
+    participant_name: &str,
+    game_name: &str,
+    event_date: &str,
+    reveal_url: &Url,
+    unsubscribe_url: &Url,
+) -> Markup {
+    let content = html! {
+        (gradient_header(locale.tr("⏰ Lembrete"), game_name))
+
+        div class="content" {
+            p { (locale.tr("Olá ")) strong { (participant_name) } "!" }
+
+            p {
+                (locale.tr("O sorteio do Amigo Oculto "))
+                strong { (game_name) }
+                (locale.tr(" já foi feito, mas você ainda não viu quem tirou."))
             }
+
+            p { "📅 " strong { (locale.tr("Data do evento:")) } " " (event_date) }
+
+            (info_box(html! {
+                p { (locale.tr("Clique no botão abaixo para descobrir quem você tirou:")) }
+                (primary_button(reveal_url, locale.tr("Ver Meu Amigo Oculto")))
+            }))
+
+            (fallback_link(locale, reveal_url))
+        }
+
+        (unsubscribe_footer(locale, unsubscribe_url))
+    };
+
+    email_layout(
+        locale,
+        &fill(locale.tr("Lembrete - {}"), &[game_name]),
+        content,
+    )
+}
+
+/// Reminder of the upcoming event, sent to every participant
+#[allow(clippy::too_many_arguments)]
+pub fn participant_event_reminder_email(
+    locale: Locale,
+    participant_name: &str,
+    game_name: &str,
+    event_date: &str,
+    event_location: Option<(&str, &Url)>,
+    organizer_message: Option<&str>,
+    reveal_url: &Url,
+    unsubscribe_url: &Url,
+) -> Markup {
+    let content = html! {
+        (gradient_header(locale.tr("📅 O evento está chegando"), game_name))
+
+        div class="content" {
+            p { (locale.tr("Olá ")) strong { (participant_name) } "!" }
+
+            p {
+                (locale.tr("O Amigo Oculto "))
+                strong { (game_name) }
+                (locale.tr(" está chegando!"))
+            }
+
+            p { "📅 " strong { (locale.tr("Data do evento:")) } " " (event_date) }
+
+            @if let Some((location, maps_url)) = event_location {
+                (location_line(locale, location, maps_url))
+            }
+
+            @if let Some(message) = organizer_message {
+                p { strong { (locale.tr("Mensagem do organizador:")) } }
+                p {
+                    @for (i, line) in message.lines().enumerate() {
+                        @if i > 0 { br; }
+                        (line)
+                    }
+                }
+            }
+
+            (info_box(html! {
+                p { (locale.tr("Esqueceu quem você tirou? Clique no botão abaixo para ver de novo:")) }
+                (primary_button(reveal_url, locale.tr("Ver Meu Amigo Oculto")))
+            }))
+
+            (fallback_link(locale, reveal_url))
+        }
+
+        (unsubscribe_footer(locale, unsubscribe_url))
+    };
+
+    email_layout(
+        locale,
+        &fill(locale.tr("Lembrete - {}"), &[game_name]),
+        content,
+    )
+}
+
+/// Participant notification with a body written by the organizer
+pub fn custom_participant_email(
+    locale: Locale,
+    game_name: &str,
+    body: &str,
+    cover_url: Option<&Url>,
+    reveal_url: &Url,
+    open_tracking_url: Option<&Url>,
+) -> Markup {
+    let content = html! {
+        (gradient_header(locale.tr("Amigo Oculto"), game_name))
+
+        div class="content" {
+            @if let Some(cover_url) = cover_url {
+                img class="cover-image" src=(cover_url.as_str()) alt=(game_name);
+            }
+
+            @for paragraph in body.split("\n\n").filter(|p| !p.trim().is_empty()) {
+                p {
+                    @for (i, line) in paragraph.lines().enumerate() {
+                        @if i > 0 { br; }
+                        (line)
+                    }
+                }
+            }
+
+            (info_box(html! {
+                p { (locale.tr("Clique no botão abaixo para descobrir quem você tirou:")) }
+                (primary_button(reveal_url, locale.tr("Ver Meu Amigo Oculto")))
+                (qr_code(locale))
+            }))
+
+            (fallback_link(locale, reveal_url))
         }
 
-        (app_footer())
+        (app_footer(locale))
+        (tracking_pixel(open_tracking_url))
     };
 
-    email_layout(&format!("Amigo Oculto - {}", game_name), content)
+    email_layout(
+        locale,
+        &fill(locale.tr("Amigo Oculto - {}"), &[game_name]),
+        content,
+    )
 }
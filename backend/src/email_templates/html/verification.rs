@@ -1,20 +1,26 @@
 use super::base::email_layout;
 use crate::email_templates::components::{
-    app_footer, gradient_header, info_box, primary_button, warning_box,
+    app_footer, fallback_link, gradient_header, info_box, primary_button, qr_code, warning_box,
 };
+use crate::i18n::{Locale, fill};
 use crate::token::VerificationCode;
 use maud::{Markup, html};
 use url::Url;
 
 /// Email verification code template
-pub fn verification_email(game_name: &str, verification_code: VerificationCode) -> Markup {
+pub fn verification_email(
+    locale: Locale,
+    game_name: &str,
+    verification_code: VerificationCode,
+    confirm_url: &Url,
+) -> Markup {
     let content = html! {
-        (gradient_header("🔐 Código de Verificação", "Amigo Oculto"))
+        (gradient_header(locale.tr("🔐 Código de Verificação"), locale.tr("Amigo Oculto")))
 
         div class="content" {
-            p { "Você está criando o jogo: " strong { (game_name) } }
+            p { (locale.tr("Você está criando o jogo: ")) strong { (game_name) } }
 
-            p { "Digite o código abaixo na página de criação para continuar:" }
+            p { (locale.tr("Digite o código abaixo na página de criação para continuar:")) }
 
             div style="text-align: center; margin: 30px 0;" {
                 div style="display: inline-block; background: #4A5759; padding: 20px 40px; border-radius: 12px; font-size: 36px; font-weight: bold; color: white; letter-spacing: 8px; font-family: monospace;" {
@@ -22,62 +28,113 @@ pub fn verification_email(game_name: &str, verification_code: VerificationCode)
                 }
             }
 
+            (info_box(html! {
+                p { (locale.tr("Ou clique no botão abaixo para confirmar seu email e criar o jogo:")) }
+                (primary_button(confirm_url, locale.tr("Criar Meu Jogo")))
+            }))
+
             (warning_box(html! {
                 p {
-                    strong { "⏱️ Atenção:" }
-                    " Este código expira em 15 minutos."
+                    strong { (locale.tr("⏱️ Atenção:")) }
+                    (locale.tr(" Este código expira em 15 minutos."))
                 }
             }))
 
+            (fallback_link(locale, confirm_url))
+
             p class="text-muted" {
-                "Se você não solicitou este código, ignore este email."
+                (locale.tr("Se você não solicitou este código, ignore este email."))
             }
         }
 
-        (app_footer())
+        (app_footer(locale))
     };
 
-    email_layout("Código de Verificação - Amigo Oculto", content)
+    email_layout(
+        locale,
+        locale.tr("Código de Verificação - Amigo Oculto"),
+        content,
+    )
+}
+
+/// PIN for a participant recovering their reveal links
+pub fn reveal_recovery_email(locale: Locale, recovery_code: VerificationCode) -> Markup {
+    let content = html! {
+        (gradient_header(locale.tr("🔐 Recuperar Amigo Oculto"), locale.tr("Amigo Oculto")))
+
+        div class="content" {
+            p { (locale.tr("Alguém pediu para recuperar os links de amigo oculto deste email.")) }
+
+            p { (locale.tr("Digite o código abaixo na página de revelação para ver seus sorteios:")) }
+
+            div style="text-align: center; margin: 30px 0;" {
+                div style="display: inline-block; background: #4A5759; padding: 20px 40px; border-radius: 12px; font-size: 36px; font-weight: bold; color: white; letter-spacing: 8px; font-family: monospace;" {
+                    (recovery_code)
+                }
+            }
+
+            (warning_box(html! {
+                p {
+                    strong { (locale.tr("⏱️ Atenção:")) }
+                    (locale.tr(" Este código expira em 15 minutos."))
+                }
+            }))
+
+            p class="text-muted" {
+                (locale.tr("Se você não solicitou este código, ignore este email."))
+            }
+        }
+
+        (app_footer(locale))
+    };
+
+    email_layout(locale, locale.tr("Recuperar Amigo Oculto"), content)
 }
 
 /// Admin welcome email (sent immediately after game creation)
-pub fn admin_welcome_email(game_name: &str, event_date: &str, admin_url: &Url) -> Markup {
+pub fn admin_welcome_email(
+    locale: Locale,
+    game_name: &str,
+    event_date: &str,
+    admin_url: &Url,
+) -> Markup {
     let content = html! {
-        (gradient_header("🎉 Jogo Criado!", game_name))
+        (gradient_header(locale.tr("🎉 Jogo Criado!"), game_name))
 
         div class="content" {
-            p { "Parabéns! Seu jogo foi criado com sucesso!" }
+            p { (locale.tr("Parabéns! Seu jogo foi criado com sucesso!")) }
 
             (info_box(html! {
-                p { "📅 " strong { "Data do evento:" } " " (event_date) }
+                p { "📅 " strong { (locale.tr("Data do evento:")) } " " (event_date) }
             }))
 
-            p { "Agora você pode:" }
+            p { (locale.tr("Agora você pode:")) }
             ul {
-                li { "Adicionar participantes" }
-                li { "Realizar o sorteio" }
-                li { "Acompanhar quem já visualizou" }
-                li { "Reenviar emails se necessário" }
+                li { (locale.tr("Adicionar participantes")) }
+                li { (locale.tr("Realizar o sorteio")) }
+                li { (locale.tr("Acompanhar quem já visualizou")) }
+                li { (locale.tr("Reenviar emails se necessário")) }
             }
 
             (warning_box(html! {
                 p {
-                    strong { "⚠️ Importante:" }
-                    " Guarde este link! Você precisará dele para gerenciar seu jogo."
+                    strong { (locale.tr("⚠️ Importante:")) }
+                    (locale.tr(" Guarde este link! Você precisará dele para gerenciar seu jogo."))
                 }
             }))
 
-            (primary_button(admin_url, "Acessar Painel de Administração"))
+            (primary_button(admin_url, locale.tr("Acessar Painel de Administração")))
+            (qr_code(locale))
 
-            p class="text-muted" {
-                "Se o botão não funcionar, copie e cole este link no seu navegador:"
-                br;
-                a href=(admin_url.as_str()) { (admin_url.as_str()) }
-            }
+            (fallback_link(locale, admin_url))
         }
 
-        (app_footer())
+        (app_footer(locale))
     };
 
-    email_layout(&format!("Jogo Criado - {}", game_name), content)
+    email_layout(
+        locale,
+        &fill(locale.tr("Jogo Criado - {}"), &[game_name]),
+        content,
+    )
 }
@@ -1,5 +1,6 @@
 mod components;
 mod styles;
 
+pub mod custom;
 pub mod html;
 pub mod plain;
@@ -0,0 +1,22 @@
+use super::footer;
+use crate::i18n::{Locale, fill};
+use url::Url;
+
+/// Organizer magic login link plain-text email
+pub fn organizer_login_email(locale: Locale, login_url: &Url) -> String {
+    fill(
+        locale.tr("Acesso do Organizador - Amigo Oculto 🔑
+
+Recebemos um pedido de acesso aos jogos organizados por este email.
+
+Para entrar, acesse o link abaixo:
+{}
+
+⏱️ Este link expira em 15 minutos e só pode ser usado uma vez.
+
+Se você não solicitou este acesso, ignore este email.
+
+{}"),
+        &[login_url.as_str(), &footer(locale)],
+    )
+}
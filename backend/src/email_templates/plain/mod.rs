@@ -1,7 +1,29 @@
+mod login;
 mod organizer;
 mod participant;
 mod verification;
 
-pub use organizer::organizer_email;
-pub use participant::participant_email;
-pub use verification::{admin_welcome_email, verification_email};
+pub use login::organizer_login_email;
+pub use organizer::{
+    announcement_email, digest_email, organizer_email, undeliverable_email, wrap_up_email,
+};
+pub use participant::{
+    custom_participant_email, participant_email, participant_event_reminder_email,
+    participant_reminder_email,
+};
+pub use verification::{admin_welcome_email, reveal_recovery_email, verification_email};
+
+use crate::i18n::{Locale, fill};
+
+/// Footer at the end of every email
+fn footer(locale: Locale) -> String {
+    format!("---\n{}", locale.tr("Amigo Oculto - Sistema de Sorteio"))
+}
+
+/// Line after the footer of optional emails, with a link to stop receiving them
+fn unsubscribe_line(locale: Locale, unsubscribe_url: &url::Url) -> String {
+    fill(
+        locale.tr("Não quer mais receber lembretes e resumos? Descadastre-se: {}"),
+        &[unsubscribe_url],
+    )
+}
@@ -1,20 +1,29 @@
+use super::{footer, unsubscribe_line};
+use crate::i18n::{Locale, fill};
+use crate::models::WrapUpStats;
 use url::Url;
 
-const FOOTER: &str = "---\nAmigo Oculto - Sistema de Sorteio";
-
 /// Organizer confirmation plain-text email
 pub fn organizer_email(
+    locale: Locale,
     game_name: &str,
     event_date: &str,
+    event_location: Option<(&str, &Url)>,
     participant_count: usize,
     admin_url: &Url,
 ) -> String {
-    format!(
-        "Parabéns! O sorteio foi realizado com sucesso! 🎉
+    let event_location = event_location
+        .map(|(location, maps_url)| {
+            format!("📍 {} {} ({})\n", locale.tr("Local:"), location, maps_url)
+        })
+        .unwrap_or_default();
+
+    fill(
+        locale.tr("Parabéns! O sorteio foi realizado com sucesso! 🎉
 
 Amigo Oculto: {}
 📅 Data do evento: {}
-👥 Participantes: {}
+{}👥 Participantes: {}
 
 Todos os participantes receberam um email com o link para descobrir quem tiraram.
 
@@ -23,7 +32,168 @@ Para acompanhar quem já visualizou seu amigo oculto, acesse:
 
 ⚠️ Importante: Guarde este email para consultar o status do sorteio mais tarde.
 
-{}",
-        game_name, event_date, participant_count, admin_url, FOOTER
+{}"),
+        &[
+            game_name,
+            event_date,
+            &event_location,
+            &participant_count.to_string(),
+            admin_url.as_str(),
+            &footer(locale),
+        ],
+    )
+}
+
+/// Wrap-up plain-text email sent to the organizer after the event
+pub fn wrap_up_email(
+    locale: Locale,
+    game_name: &str,
+    event_date: &str,
+    stats: &WrapUpStats,
+    unsubscribe_url: &Url,
+) -> String {
+    let not_viewed = if stats.not_viewed.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "{}\n{}\n\n",
+            locale.tr("Nunca abriram o link de revelação:"),
+            stats
+                .not_viewed
+                .iter()
+                .map(|name| format!("- {}", name))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    };
+
+    fill(
+        locale.tr("O Amigo Oculto \"{}\" de {} já aconteceu! Veja como foi:
+
+👥 Participantes: {}
+👀 Viram quem tiraram: {} de {}
+🔁 Visualizações no total: {}
+📨 Emails reenviados: {}
+⏰ Lembretes enviados: {}
+
+{}Obrigado por usar o Amigo Oculto! Até a próxima! 🎁
+
+{}
+{}"),
+        &[
+            game_name,
+            event_date,
+            &stats.participant_count.to_string(),
+            &stats.viewed_count.to_string(),
+            &stats.participant_count.to_string(),
+            &stats.reveal_count.to_string(),
+            &stats.resend_count.to_string(),
+            &stats.reminder_count.to_string(),
+            &not_viewed,
+            &footer(locale),
+            &unsubscribe_line(locale, unsubscribe_url),
+        ],
+    )
+}
+
+/// Weekly digest plain-text email for the organizer
+pub fn digest_email(
+    locale: Locale,
+    game_name: &str,
+    event_date: &str,
+    stats: &WrapUpStats,
+    admin_url: &Url,
+    unsubscribe_url: &Url,
+) -> String {
+    let not_viewed = if stats.not_viewed.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "{}\n{}\n\n",
+            locale.tr("Ainda não viram quem tiraram:"),
+            stats
+                .not_viewed
+                .iter()
+                .map(|name| format!("- {}", name))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    };
+
+    fill(
+        locale.tr("Veja como está o Amigo Oculto \"{}\" de {}:
+
+👥 Participantes: {}
+👀 Viram quem tiraram: {} de {}
+
+{}Você pode reenviar os emails pela página do jogo:
+{}
+
+{}
+{}"),
+        &[
+            game_name,
+            event_date,
+            &stats.participant_count.to_string(),
+            &stats.viewed_count.to_string(),
+            &stats.participant_count.to_string(),
+            &not_viewed,
+            admin_url.as_str(),
+            &footer(locale),
+            &unsubscribe_line(locale, unsubscribe_url),
+        ],
+    )
+}
+
+/// Plain-text notice that a participant's email can't be delivered
+pub fn undeliverable_email(
+    locale: Locale,
+    game_name: &str,
+    participant_name: &str,
+    participant_email: &str,
+    complaint: bool,
+    admin_url: &Url,
+) -> String {
+    let participant = format!("{} ({})", participant_name, participant_email);
+    let problem = if complaint {
+        fill(
+            locale.tr("{} marcou o email do Amigo Oculto como spam."),
+            &[&participant],
+        )
+    } else {
+        fill(
+            locale.tr(
+                "O email para {} não pôde ser entregue. O endereço pode estar errado ou não existir mais.",
+            ),
+            &[&participant],
+        )
+    };
+
+    fill(
+        locale.tr("⚠️ Amigo Oculto: {}
+
+{}
+
+Confira o endereço com {} e corrija-o na página do jogo para reenviar o link de revelação:
+{}
+
+{}"),
+        &[
+            game_name,
+            &problem,
+            participant_name,
+            admin_url.as_str(),
+            &footer(locale),
+        ],
+    )
+}
+
+/// Announcement plain-text email from the site admin to the organizers of active games
+pub fn announcement_email(locale: Locale, message: &str, unsubscribe_url: &Url) -> String {
+    format!(
+        "{}\n\n{}\n{}",
+        message.trim(),
+        footer(locale),
+        unsubscribe_line(locale, unsubscribe_url)
     )
 }
@@ -1,27 +1,136 @@
+use super::{footer, unsubscribe_line};
+use crate::i18n::{Locale, fill};
 use url::Url;
 
-const FOOTER: &str = "---\nAmigo Oculto - Sistema de Sorteio";
-
 /// Participant notification plain-text email
 pub fn participant_email(
+    locale: Locale,
     participant_name: &str,
     game_name: &str,
     event_date: &str,
+    event_location: Option<(&str, &Url)>,
+    organizer_message: Option<&str>,
     reveal_url: &Url,
 ) -> String {
-    format!(
-        "Olá {}!
+    let event_location = event_location
+        .map(|(location, maps_url)| {
+            format!("\n📍 {} {}\n{}\n", locale.tr("Local:"), location, maps_url)
+        })
+        .unwrap_or_default();
+    let organizer_message = organizer_message
+        .map(|message| format!("{}\n{}\n\n", locale.tr("Mensagem do organizador:"), message))
+        .unwrap_or_default();
+
+    fill(
+        locale.tr("Olá {}!
 
 Você foi convidado para participar do Amigo Oculto \"{}\"!
 
+📅 Data do evento: {}
+{}
+{}Para descobrir quem você tirou, acesse o link abaixo:
+{}
+
+Guarde este email para consultar seu amigo oculto mais tarde se necessário.
+
+{}"),
+        &[
+            participant_name,
+            game_name,
+            event_date,
+            &event_location,
+            &organizer_message,
+            reveal_url.as_str(),
+            &footer(locale),
+        ],
+    )
+}
+
+/// Reminder plain-text email for a participant who hasn't seen their match yet
+pub fn participant_reminder_email(
+    locale: Locale,
+    participant_name: &str,
+    game_name: &str,
+    event_date: &str,
+    reveal_url: &Url,
+    unsubscribe_url: &Url,
+) -> String {
+    fill(
+        locale.tr("Olá {}!
+
+O sorteio do Amigo Oculto \"{}\" já foi feito, mas você ainda não viu quem tirou.
+
 📅 Data do evento: {}
 
 Para descobrir quem você tirou, acesse o link abaixo:
 {}
 
-Guarde este email para consultar seu amigo oculto mais tarde se necessário.
+{}
+{}"),
+        &[
+            participant_name,
+            game_name,
+            event_date,
+            reveal_url.as_str(),
+            &footer(locale),
+            &unsubscribe_line(locale, unsubscribe_url),
+        ],
+    )
+}
+
+/// Plain-text reminder of the upcoming event, sent to every participant
+#[allow(clippy::too_many_arguments)]
+pub fn participant_event_reminder_email(
+    locale: Locale,
+    participant_name: &str,
+    game_name: &str,
+    event_date: &str,
+    event_location: Option<(&str, &Url)>,
+    organizer_message: Option<&str>,
+    reveal_url: &Url,
+    unsubscribe_url: &Url,
+) -> String {
+    let event_location = event_location
+        .map(|(location, maps_url)| {
+            format!("\n📍 {} {}\n{}\n", locale.tr("Local:"), location, maps_url)
+        })
+        .unwrap_or_default();
+    let organizer_message = organizer_message
+        .map(|message| format!("{}\n{}\n\n", locale.tr("Mensagem do organizador:"), message))
+        .unwrap_or_default();
+
+    fill(
+        locale.tr("Olá {}!
 
-{}",
-        participant_name, game_name, event_date, reveal_url, FOOTER
+O Amigo Oculto \"{}\" está chegando!
+
+📅 Data do evento: {}
+{}
+{}Esqueceu quem você tirou? Acesse o link abaixo para ver de novo:
+{}
+
+{}
+{}"),
+        &[
+            participant_name,
+            game_name,
+            event_date,
+            &event_location,
+            &organizer_message,
+            reveal_url.as_str(),
+            &footer(locale),
+            &unsubscribe_line(locale, unsubscribe_url),
+        ],
+    )
+}
+
+/// Participant notification plain-text email with a body written by the organizer
+pub fn custom_participant_email(locale: Locale, body: &str, reveal_url: &Url) -> String {
+    format!(
+        "{}\n\n{}\n{}\n\n{}",
+        body.trim_end(),
+        locale.tr("Para descobrir quem você tirou, acesse o link abaixo:"),
+        reveal_url,
+        footer(locale)
     )
 }
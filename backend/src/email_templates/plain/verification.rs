@@ -1,11 +1,17 @@
+use super::footer;
+use crate::i18n::{Locale, fill};
 use crate::token::VerificationCode;
-
-const FOOTER: &str = "---\nAmigo Oculto - Sistema de Sorteio";
+use url::Url;
 
 /// Email verification code plain-text email
-pub fn verification_email(game_name: &str, verification_code: VerificationCode) -> String {
-    format!(
-        "Código de Verificação - Amigo Oculto 🎁
+pub fn verification_email(
+    locale: Locale,
+    game_name: &str,
+    verification_code: VerificationCode,
+    confirm_url: &Url,
+) -> String {
+    fill(
+        locale.tr("Código de Verificação - Amigo Oculto 🎁
 
 Você está criando o jogo: {}
 
@@ -15,19 +21,53 @@ Seu código de verificação é:
 
 ⏱️ Este código expira em 15 minutos.
 
-Digite este código na página de criação do jogo para continuar.
+Digite este código na página de criação do jogo para continuar, ou confirme seu email e crie o jogo direto por este link:
+
+{}
+
+Se você não solicitou este código, ignore este email.
+
+{}"),
+        &[
+            game_name,
+            &verification_code.to_string(),
+            confirm_url.as_str(),
+            &footer(locale),
+        ],
+    )
+}
+
+/// PIN for a participant recovering their reveal links
+pub fn reveal_recovery_email(locale: Locale, recovery_code: VerificationCode) -> String {
+    fill(
+        locale.tr("Recuperar Amigo Oculto 🎁
+
+Alguém pediu para recuperar os links de amigo oculto deste email.
+
+Seu código é:
+
+{}
+
+⏱️ Este código expira em 15 minutos.
+
+Digite este código na página de revelação para ver seus sorteios.
 
 Se você não solicitou este código, ignore este email.
 
-{}",
-        game_name, verification_code, FOOTER
+{}"),
+        &[recovery_code.to_string(), footer(locale)],
     )
 }
 
 /// Admin welcome email (sent immediately after game creation)
-pub fn admin_welcome_email(game_name: &str, event_date: &str, admin_url: &url::Url) -> String {
-    format!(
-        "Seu jogo foi criado com sucesso! 🎉
+pub fn admin_welcome_email(
+    locale: Locale,
+    game_name: &str,
+    event_date: &str,
+    admin_url: &url::Url,
+) -> String {
+    fill(
+        locale.tr("Seu jogo foi criado com sucesso! 🎉
 
 Amigo Oculto: {}
 📅 Data do evento: {}
@@ -43,7 +83,7 @@ Acesse o painel de administração:
   • Acompanhar quem já visualizou
   • Reenviar emails
 
-{}",
-        game_name, event_date, admin_url, FOOTER
+{}"),
+        &[game_name, event_date, admin_url.as_str(), &footer(locale)],
     )
 }
@@ -40,6 +40,20 @@ body {
     font-size: 16px;
 }
 
+.cover-image {
+    display: block;
+    width: 100%;
+    max-height: 240px;
+    object-fit: cover;
+    border-radius: 8px;
+    margin-bottom: 20px;
+}
+
+.qr-code {
+    text-align: center;
+    margin: 20px 0;
+}
+
 .info-box {
     background: white;
     padding: 20px;
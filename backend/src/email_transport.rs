@@ -0,0 +1,1410 @@
+//! Ways of delivering email: over SMTP, or through the HTTP APIs of email providers.
+//!
+//! Many hosts block outbound SMTP ports, so an HTTP API may be the only way to send email
+//! from them. The transport is chosen by [`EmailProvider`], read from `EMAIL_PROVIDER`.
+//!
+//! For local development and staging, `EMAIL_MODE` can instead log emails or write them to
+//! a maildir, so nothing is ever delivered.
+
+use crate::clock::Clock;
+use crate::prometheus;
+use crate::sigv4;
+use anyhow::{Context, Result, bail, ensure};
+use axum::http::{Method, StatusCode, header};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use chrono::{DateTime, Utc};
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Tokio1Executor,
+    message::{
+        Attachment as MimeAttachment, Mailbox, Message, MultiPart, SinglePart,
+        header::{ContentType, HeaderName, HeaderValue},
+    },
+    transport::smtp::{
+        authentication::Credentials,
+        client::{Tls, TlsParameters},
+    },
+};
+use rand::distr::Alphanumeric;
+use rand::{Rng, rng};
+use std::fmt;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use url::{Url, form_urlencoded};
+
+/// How long connecting to the provider and sending one email (or API request) may take.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest part of an error response kept in error messages, in characters.
+const MAX_ERROR_BODY_LENGTH: usize = 500;
+
+/// Consecutive failed sends after which the secondary SMTP server takes over.
+const FAILOVER_AFTER_FAILURES: u32 = 3;
+
+/// How often the primary SMTP server is tested again while sending through the secondary.
+const PRIMARY_RETEST_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+pub type TransportFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Delivers emails to a mail server or provider.
+pub trait EmailTransport: Send + Sync {
+    /// Where emails are sent, for logs and the site admin dashboard. Never includes credentials.
+    fn description(&self) -> String;
+
+    /// Checks that the server or provider can be reached and accepts the credentials.
+    fn test_connection(&self) -> TransportFuture<'_, ()>;
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> TransportFuture<'a, ()>;
+}
+
+/// An email ready to be delivered.
+#[derive(Debug, Clone)]
+pub struct OutgoingEmail {
+    pub from: Mailbox,
+    pub to: Mailbox,
+    /// Where replies go instead of `from`, such as the game's organizer.
+    pub reply_to: Option<Mailbox>,
+    pub subject: String,
+    pub text: String,
+    /// HTML alternative to the plain-text body.
+    pub html: Option<String>,
+    /// Where the recipient can unsubscribe with one click, for optional emails.
+    pub list_unsubscribe: Option<Url>,
+    pub attachments: Vec<Attachment>,
+}
+
+/// A file attached to an email, such as a calendar event.
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub filename: String,
+    /// MIME type, e.g. `text/calendar; charset=utf-8`.
+    pub content_type: String,
+    pub data: Vec<u8>,
+    /// Shown in the HTML body as `cid:<content_id>` instead of attached, when set. Mailgun
+    /// only knows inline files by name, so it should be the filename.
+    pub content_id: Option<String>,
+}
+
+impl OutgoingEmail {
+    /// Headers besides the addresses and subject: `List-Unsubscribe`, with one-click
+    /// unsubscribing (RFC 8058), when the email has an unsubscribe link.
+    fn extra_headers(&self) -> Vec<(&'static str, String)> {
+        match &self.list_unsubscribe {
+            Some(url) => vec![
+                ("List-Unsubscribe", format!("<{url}>")),
+                (
+                    "List-Unsubscribe-Post",
+                    "List-Unsubscribe=One-Click".to_string(),
+                ),
+            ],
+            None => Vec::new(),
+        }
+    }
+
+    /// Builds the MIME message sent over SMTP.
+    fn to_message(&self) -> Result<Message> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .to(self.to.clone())
+            .subject(&self.subject);
+        if let Some(reply_to) = &self.reply_to {
+            builder = builder.reply_to(reply_to.clone());
+        }
+        let builder = self
+            .extra_headers()
+            .into_iter()
+            .fold(builder, |builder, (name, value)| {
+                builder.raw_header(HeaderValue::new(
+                    HeaderName::new_from_ascii_str(name),
+                    value,
+                ))
+            });
+        let text = SinglePart::builder()
+            .header(ContentType::TEXT_PLAIN)
+            .body(self.text.clone());
+
+        let (inline, attachments): (Vec<_>, Vec<_>) = self
+            .attachments
+            .iter()
+            .partition(|attachment| attachment.content_id.is_some());
+        let body = match &self.html {
+            Some(html) => {
+                let html = SinglePart::builder()
+                    .header(ContentType::TEXT_HTML)
+                    .body(html.clone());
+                let alternative = MultiPart::alternative().singlepart(text.clone());
+                if inline.is_empty() {
+                    Some(alternative.singlepart(html))
+                } else {
+                    let mut related = MultiPart::related().singlepart(html);
+                    for attachment in inline {
+                        let content_type = ContentType::parse(&attachment.content_type)
+                            .context("parsing attachment content type")?;
+                        let content_id = attachment.content_id.clone().unwrap_or_default();
+                        related = related.singlepart(
+                            MimeAttachment::new_inline_with_name(
+                                content_id,
+                                attachment.filename.clone(),
+                            )
+                            .body(attachment.data.clone(), content_type),
+                        );
+                    }
+                    Some(alternative.multipart(related))
+                }
+            }
+            None => None,
+        };
+        if attachments.is_empty() {
+            let message = match body {
+                Some(body) => builder.multipart(body),
+                None => builder.singlepart(text),
+            };
+            return message.context("building email message");
+        }
+
+        let mut mixed = match body {
+            Some(body) => MultiPart::mixed().multipart(body),
+            None => MultiPart::mixed().singlepart(text),
+        };
+        for attachment in attachments {
+            let content_type = ContentType::parse(&attachment.content_type)
+                .context("parsing attachment content type")?;
+            mixed = mixed.singlepart(
+                MimeAttachment::new(attachment.filename.clone())
+                    .body(attachment.data.clone(), content_type),
+            );
+        }
+        builder.multipart(mixed).context("building email message")
+    }
+}
+
+/// Which transport delivers emails, with its settings.
+#[derive(Clone)]
+pub enum EmailProvider {
+    Smtp {
+        primary: SmtpConfig,
+        /// Takes over when the primary server keeps failing (`SMTP_SECONDARY_HOST`).
+        secondary: Option<SmtpConfig>,
+    },
+    SendGrid {
+        api_key: String,
+    },
+    Mailgun(MailgunConfig),
+    Ses(SesConfig),
+    /// Logs emails instead of sending them (`EMAIL_MODE=log`).
+    Log,
+    /// Writes emails to a maildir instead of sending them (`EMAIL_MODE=file`).
+    Maildir(PathBuf),
+}
+
+#[derive(Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub tls: SmtpTls,
+    /// Only optional without TLS, for local servers such as MailHog.
+    pub credentials: Option<Credentials>,
+}
+
+/// How the SMTP connection is encrypted (`SMTP_TLS`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTls {
+    /// TLS from the start of the connection, usually on port 465 (`tls`).
+    Implicit,
+    /// Upgrade with STARTTLS, failing if the server doesn't offer it (`starttls`).
+    Starttls,
+    /// Upgrade with STARTTLS when the server offers it (`opportunistic`).
+    Opportunistic,
+    /// Plaintext, only for local servers such as MailHog (`none`).
+    None,
+}
+
+impl SmtpTls {
+    /// The usual strategy for a port: implicit TLS on 465, STARTTLS elsewhere.
+    pub fn default_for_port(port: u16) -> Self {
+        if port == 465 {
+            Self::Implicit
+        } else {
+            Self::Starttls
+        }
+    }
+}
+
+impl fmt::Display for SmtpTls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Implicit => "tls",
+            Self::Starttls => "starttls",
+            Self::Opportunistic => "opportunistic",
+            Self::None => "none",
+        })
+    }
+}
+
+/// Error type for unknown `SMTP_TLS` values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidSmtpTls;
+
+impl fmt::Display for InvalidSmtpTls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected tls, starttls, opportunistic or none")
+    }
+}
+
+impl std::error::Error for InvalidSmtpTls {}
+
+impl FromStr for SmtpTls {
+    type Err = InvalidSmtpTls;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tls" => Ok(Self::Implicit),
+            "starttls" => Ok(Self::Starttls),
+            "opportunistic" => Ok(Self::Opportunistic),
+            "none" => Ok(Self::None),
+            _ => Err(InvalidSmtpTls),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MailgunConfig {
+    /// A private API key; sending keys can't be used to test the connection.
+    pub api_key: String,
+    pub domain: String,
+    /// `https://api.mailgun.net` by default, or `https://api.eu.mailgun.net` for EU domains.
+    pub api_base: Url,
+}
+
+#[derive(Clone)]
+pub struct SesConfig {
+    pub region: String,
+    pub credentials: sigv4::Credentials,
+    /// Configuration set to send with, whose event destinations can receive bounces and
+    /// complaints.
+    pub configuration_set: Option<String>,
+}
+
+impl EmailProvider {
+    /// Short name of the provider, or the mode for emails that aren't sent.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Smtp { .. } => "smtp",
+            Self::SendGrid { .. } => "sendgrid",
+            Self::Mailgun(_) => "mailgun",
+            Self::Ses(_) => "ses",
+            Self::Log => "log",
+            Self::Maildir(_) => "file",
+        }
+    }
+
+    /// Creates the transport. The clock signs requests to AWS, names maildir files and times
+    /// the failover's retests of the primary.
+    pub fn transport(&self, clock: Arc<dyn Clock>) -> Result<Arc<dyn EmailTransport>> {
+        Ok(match self {
+            Self::Smtp { primary, secondary } => {
+                let primary = Arc::new(SmtpTransport::new(primary)?);
+                match secondary {
+                    Some(secondary) => Arc::new(FailoverTransport::new(
+                        primary,
+                        Arc::new(SmtpTransport::new(secondary)?),
+                        clock,
+                    )),
+                    None => primary,
+                }
+            }
+            Self::SendGrid { api_key } => Arc::new(SendGridTransport {
+                client: HttpsClient::new()?,
+                api_key: api_key.clone(),
+            }),
+            Self::Mailgun(config) => Arc::new(MailgunTransport {
+                client: HttpsClient::new()?,
+                config: config.clone(),
+            }),
+            Self::Ses(config) => {
+                ensure!(
+                    !config.region.is_empty()
+                        && config
+                            .region
+                            .chars()
+                            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'),
+                    "invalid AWS region {:?}",
+                    config.region
+                );
+                Arc::new(SesTransport {
+                    client: HttpsClient::new()?,
+                    config: config.clone(),
+                    clock,
+                })
+            }
+            Self::Log => Arc::new(LogTransport),
+            Self::Maildir(dir) => Arc::new(MaildirTransport::new(dir.clone(), clock)?),
+        })
+    }
+}
+
+/// Sends through a primary transport, and through a secondary one once the primary fails
+/// its connection test or [`FAILOVER_AFTER_FAILURES`] sends in a row.
+///
+/// Failing over is logged as an error and counted in [`prometheus::EMAIL_FAILOVERS`], for
+/// alerting. While failed over, a send tests the primary's connection again once every
+/// [`PRIMARY_RETEST_INTERVAL`], and so does any connection test such as the site admin's SMTP
+/// test; the first one that passes switches back to it.
+pub struct FailoverTransport {
+    primary: Arc<dyn EmailTransport>,
+    secondary: Arc<dyn EmailTransport>,
+    clock: Arc<dyn Clock>,
+    /// Failed sends through the primary since the last successful one.
+    failures: AtomicU32,
+    failed_over: AtomicBool,
+    /// When a send may test the primary again, while failed over.
+    retest_at: Mutex<DateTime<Utc>>,
+}
+
+impl FailoverTransport {
+    pub fn new(
+        primary: Arc<dyn EmailTransport>,
+        secondary: Arc<dyn EmailTransport>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        let now = clock.now();
+        Self {
+            primary,
+            secondary,
+            clock,
+            failures: AtomicU32::new(0),
+            failed_over: AtomicBool::new(false),
+            retest_at: Mutex::new(now),
+        }
+    }
+
+    fn fail_over(&self, reason: &str) {
+        if !self.failed_over.swap(true, Ordering::Relaxed) {
+            self.schedule_retest();
+            metrics::counter!(prometheus::EMAIL_FAILOVERS).increment(1);
+            tracing::error!(
+                primary = %self.primary.description(),
+                secondary = %self.secondary.description(),
+                "email failover: {}, sending through the secondary server",
+                reason
+            );
+        }
+    }
+
+    fn switch_back(&self) {
+        self.failures.store(0, Ordering::Relaxed);
+        if self.failed_over.swap(false, Ordering::Relaxed) {
+            tracing::warn!(
+                primary = %self.primary.description(),
+                "email failover: primary server is back, switching to it"
+            );
+        }
+    }
+
+    fn schedule_retest(&self) {
+        *self.retest_at.lock().unwrap() = self.clock.now() + PRIMARY_RETEST_INTERVAL;
+    }
+
+    /// Whether the primary is due for a retest, claiming it so concurrent sends don't all
+    /// test it.
+    fn claim_retest(&self) -> bool {
+        let mut retest_at = self.retest_at.lock().unwrap();
+        let now = self.clock.now();
+        if now < *retest_at {
+            return false;
+        }
+        *retest_at = now + PRIMARY_RETEST_INTERVAL;
+        true
+    }
+}
+
+impl EmailTransport for FailoverTransport {
+    fn description(&self) -> String {
+        format!(
+            "{}, failing over to {}",
+            self.primary.description(),
+            self.secondary.description()
+        )
+    }
+
+    fn test_connection(&self) -> TransportFuture<'_, ()> {
+        Box::pin(async move {
+            match self.primary.test_connection().await {
+                Ok(()) => {
+                    self.switch_back();
+                    Ok(())
+                }
+                Err(e) => {
+                    self.fail_over(&format!("primary failed its connection test ({e:#})"));
+                    self.secondary.test_connection().await
+                }
+            }
+        })
+    }
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> TransportFuture<'a, ()> {
+        Box::pin(async move {
+            if self.failed_over.load(Ordering::Relaxed) && self.claim_retest() {
+                match self.primary.test_connection().await {
+                    Ok(()) => self.switch_back(),
+                    Err(e) => tracing::warn!(
+                        primary = %self.primary.description(),
+                        "email failover: primary server still fails its connection test: {e:#}"
+                    ),
+                }
+            }
+            if self.failed_over.load(Ordering::Relaxed) {
+                return self.secondary.send(email).await;
+            }
+
+            let Err(e) = self.primary.send(email).await else {
+                self.failures.store(0, Ordering::Relaxed);
+                return Ok(());
+            };
+            let failures = self.failures.fetch_add(1, Ordering::Relaxed) + 1;
+            if failures < FAILOVER_AFTER_FAILURES {
+                return Err(e);
+            }
+            self.fail_over(&format!("{failures} sends in a row failed ({e:#})"));
+            self.secondary.send(email).await
+        })
+    }
+}
+
+/// Logs emails instead of sending them.
+pub struct LogTransport;
+
+impl EmailTransport for LogTransport {
+    fn description(&self) -> String {
+        "log (emails are not sent)".to_string()
+    }
+
+    fn test_connection(&self) -> TransportFuture<'_, ()> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> TransportFuture<'a, ()> {
+        Box::pin(async move {
+            tracing::info!(
+                to = %email.to,
+                subject = %email.subject,
+                "email not sent (EMAIL_MODE=log):\n{}",
+                email.text
+            );
+            Ok(())
+        })
+    }
+}
+
+/// Writes emails to a maildir, where mail clients such as mutt can read them.
+pub struct MaildirTransport {
+    dir: PathBuf,
+    clock: Arc<dyn Clock>,
+}
+
+impl MaildirTransport {
+    fn new(dir: PathBuf, clock: Arc<dyn Clock>) -> Result<Self> {
+        for subdir in ["tmp", "new", "cur"] {
+            std::fs::create_dir_all(dir.join(subdir))
+                .with_context(|| format!("creating maildir {}", dir.display()))?;
+        }
+        Ok(Self { dir, clock })
+    }
+}
+
+impl EmailTransport for MaildirTransport {
+    fn description(&self) -> String {
+        format!("maildir {} (emails are not sent)", self.dir.display())
+    }
+
+    fn test_connection(&self) -> TransportFuture<'_, ()> {
+        Box::pin(async move {
+            ensure!(
+                tokio::fs::metadata(self.dir.join("new")).await?.is_dir(),
+                "maildir {} is missing its new directory",
+                self.dir.display()
+            );
+            Ok(())
+        })
+    }
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> TransportFuture<'a, ()> {
+        Box::pin(async move {
+            let message = email.to_message()?.formatted();
+            // Unique file name, as the maildir format asks: time, then something random
+            let suffix: String = rng()
+                .sample_iter(Alphanumeric)
+                .take(16)
+                .map(char::from)
+                .collect();
+            let name = format!(
+                "{}.{}.amigo-oculto.eml",
+                self.clock.now().timestamp_millis(),
+                suffix
+            );
+
+            // Written in tmp first, so readers never see a partial email in new
+            let tmp_path = self.dir.join("tmp").join(&name);
+            tokio::fs::write(&tmp_path, message)
+                .await
+                .with_context(|| format!("writing {}", tmp_path.display()))?;
+            tokio::fs::rename(&tmp_path, self.dir.join("new").join(&name))
+                .await
+                .context("moving email into maildir")?;
+
+            tracing::info!(to = %email.to, subject = %email.subject, "email written to maildir: {}", name);
+            Ok(())
+        })
+    }
+}
+
+pub struct SmtpTransport {
+    mailer: AsyncSmtpTransport<Tokio1Executor>,
+    description: String,
+}
+
+impl SmtpTransport {
+    pub fn new(config: &SmtpConfig) -> Result<Self> {
+        let tls = match config.tls {
+            SmtpTls::None => Tls::None,
+            strategy => {
+                let parameters =
+                    TlsParameters::new(config.host.clone()).context("failed to set up SMTP TLS")?;
+                match strategy {
+                    SmtpTls::Implicit => Tls::Wrapper(parameters),
+                    SmtpTls::Opportunistic => Tls::Opportunistic(parameters),
+                    _ => Tls::Required(parameters),
+                }
+            }
+        };
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+            .port(config.port)
+            .tls(tls)
+            .timeout(Some(TIMEOUT));
+        if let Some(credentials) = &config.credentials {
+            builder = builder.credentials(credentials.clone());
+        }
+
+        Ok(Self {
+            mailer: builder.build(),
+            description: format!("SMTP {}:{} ({})", config.host, config.port, config.tls),
+        })
+    }
+}
+
+impl EmailTransport for SmtpTransport {
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn test_connection(&self) -> TransportFuture<'_, ()> {
+        Box::pin(async move {
+            if !self.mailer.test_connection().await? {
+                bail!("SMTP server rejected the connection");
+            }
+            Ok(())
+        })
+    }
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> TransportFuture<'a, ()> {
+        Box::pin(async move {
+            self.mailer.send(email.to_message()?).await?;
+            Ok(())
+        })
+    }
+}
+
+/// Sends email through the SendGrid v3 API.
+pub struct SendGridTransport {
+    client: HttpsClient,
+    api_key: String,
+}
+
+impl SendGridTransport {
+    const API_BASE: &str = "https://api.sendgrid.com/v3/";
+
+    fn url(path: &str) -> Url {
+        Url::parse(Self::API_BASE)
+            .and_then(|base| base.join(path))
+            .expect("SendGrid API URL is valid")
+    }
+
+    fn authorization(&self) -> String {
+        format!("Bearer {}", self.api_key)
+    }
+}
+
+impl EmailTransport for SendGridTransport {
+    fn description(&self) -> String {
+        "SendGrid API".to_string()
+    }
+
+    fn test_connection(&self) -> TransportFuture<'_, ()> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .request(
+                    Method::GET,
+                    &Self::url("scopes"),
+                    &[("Authorization", self.authorization())],
+                    None,
+                )
+                .await?
+                .ensure_success("SendGrid")?;
+
+            #[derive(serde::Deserialize)]
+            struct Scopes {
+                scopes: Vec<String>,
+            }
+            let scopes: Scopes =
+                serde_json::from_str(&response.body).context("parsing SendGrid API key scopes")?;
+            ensure!(
+                scopes.scopes.iter().any(|scope| scope == "mail.send"),
+                "SendGrid API key is not allowed to send email (missing the mail.send scope)"
+            );
+            Ok(())
+        })
+    }
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> TransportFuture<'a, ()> {
+        Box::pin(async move {
+            let body = serde_json::to_vec(&sendgrid_body(email))?;
+            self.client
+                .request(
+                    Method::POST,
+                    &Self::url("mail/send"),
+                    &[("Authorization", self.authorization())],
+                    Some(("application/json", body)),
+                )
+                .await?
+                .ensure_success("SendGrid")?;
+            Ok(())
+        })
+    }
+}
+
+/// Request body for SendGrid's `mail/send` endpoint.
+fn sendgrid_body(email: &OutgoingEmail) -> serde_json::Value {
+    let address = |mailbox: &Mailbox| {
+        let mut address = serde_json::json!({ "email": mailbox.email.to_string() });
+        if let Some(name) = &mailbox.name {
+            address["name"] = name.clone().into();
+        }
+        address
+    };
+
+    // SendGrid requires the plain-text part to come first
+    let mut content = vec![serde_json::json!({ "type": "text/plain", "value": email.text })];
+    if let Some(html) = &email.html {
+        content.push(serde_json::json!({ "type": "text/html", "value": html }));
+    }
+
+    let mut body = serde_json::json!({
+        "personalizations": [{ "to": [address(&email.to)] }],
+        "from": address(&email.from),
+        "subject": email.subject,
+        "content": content,
+    });
+    if let Some(reply_to) = &email.reply_to {
+        body["reply_to"] = address(reply_to);
+    }
+    let headers = email.extra_headers();
+    if !headers.is_empty() {
+        body["headers"] = headers
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.into()))
+            .collect::<serde_json::Map<_, _>>()
+            .into();
+    }
+    if !email.attachments.is_empty() {
+        body["attachments"] = email
+            .attachments
+            .iter()
+            .map(|attachment| {
+                let mut json = serde_json::json!({
+                    "content": BASE64_STANDARD.encode(&attachment.data),
+                    "type": attachment.content_type,
+                    "filename": attachment.filename,
+                    "disposition": "attachment",
+                });
+                if let Some(content_id) = &attachment.content_id {
+                    json["disposition"] = "inline".into();
+                    json["content_id"] = content_id.clone().into();
+                }
+                json
+            })
+            .collect();
+    }
+    body
+}
+
+/// Sends email through the Mailgun v3 API.
+pub struct MailgunTransport {
+    client: HttpsClient,
+    config: MailgunConfig,
+}
+
+impl MailgunTransport {
+    fn url(&self, path: &str) -> Result<Url> {
+        let mut url = self.config.api_base.clone();
+        url.path_segments_mut()
+            .map_err(|()| anyhow::anyhow!("invalid Mailgun API URL"))?
+            .pop_if_empty()
+            .extend(["v3"])
+            .extend(path.split('/'));
+        Ok(url)
+    }
+
+    fn authorization(&self) -> String {
+        let credentials = BASE64_STANDARD.encode(format!("api:{}", self.config.api_key));
+        format!("Basic {credentials}")
+    }
+}
+
+impl EmailTransport for MailgunTransport {
+    fn description(&self) -> String {
+        format!(
+            "Mailgun API {} ({})",
+            self.config.api_base, self.config.domain
+        )
+    }
+
+    fn test_connection(&self) -> TransportFuture<'_, ()> {
+        Box::pin(async move {
+            let url = self.url(&format!("domains/{}", self.config.domain))?;
+            self.client
+                .request(
+                    Method::GET,
+                    &url,
+                    &[("Authorization", self.authorization())],
+                    None,
+                )
+                .await?
+                .ensure_success("Mailgun")?;
+            Ok(())
+        })
+    }
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> TransportFuture<'a, ()> {
+        Box::pin(async move {
+            let url = self.url(&format!("{}/messages", self.config.domain))?;
+            let (content_type, form) = mailgun_form(email);
+            self.client
+                .request(
+                    Method::POST,
+                    &url,
+                    &[("Authorization", self.authorization())],
+                    Some((&content_type, form)),
+                )
+                .await?
+                .ensure_success("Mailgun")?;
+            Ok(())
+        })
+    }
+}
+
+/// Form body for Mailgun's `messages` endpoint, with its content type. Emails with
+/// attachments need a multipart form; others are sent URL-encoded.
+fn mailgun_form(email: &OutgoingEmail) -> (String, Vec<u8>) {
+    let mut fields = vec![
+        ("from".to_string(), email.from.to_string()),
+        ("to".to_string(), email.to.to_string()),
+        ("subject".to_string(), email.subject.clone()),
+        ("text".to_string(), email.text.clone()),
+    ];
+    if let Some(reply_to) = &email.reply_to {
+        fields.push(("h:Reply-To".to_string(), reply_to.to_string()));
+    }
+    if let Some(html) = &email.html {
+        fields.push(("html".to_string(), html.clone()));
+    }
+    for (name, value) in email.extra_headers() {
+        fields.push((format!("h:{name}"), value));
+    }
+
+    if email.attachments.is_empty() {
+        let form = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&fields)
+            .finish();
+        return (
+            "application/x-www-form-urlencoded".to_string(),
+            form.into_bytes(),
+        );
+    }
+
+    let boundary: String = rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect();
+    let mut form = Vec::new();
+    for (name, value) in &fields {
+        form.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+            )
+            .as_bytes(),
+        );
+    }
+    for attachment in &email.attachments {
+        form.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                if attachment.content_id.is_some() { "inline" } else { "attachment" },
+                attachment.filename.replace(['"', '\r', '\n'], "_"),
+                attachment.content_type
+            )
+            .as_bytes(),
+        );
+        form.extend_from_slice(&attachment.data);
+        form.extend_from_slice(b"\r\n");
+    }
+    form.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+    (format!("multipart/form-data; boundary={boundary}"), form)
+}
+
+/// Sends email through the Amazon SES v2 API.
+pub struct SesTransport {
+    client: HttpsClient,
+    config: SesConfig,
+    clock: Arc<dyn Clock>,
+}
+
+impl SesTransport {
+    fn url(&self, path: &str) -> Result<Url> {
+        let mut url = Url::parse(&format!(
+            "https://email.{}.amazonaws.com/v2/email",
+            self.config.region
+        ))?;
+        url.path_segments_mut()
+            .map_err(|()| anyhow::anyhow!("invalid SES API URL"))?
+            .extend(path.split('/'));
+        Ok(url)
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Vec<u8>>,
+    ) -> Result<HttpResponse> {
+        let url = self.url(path)?;
+        let content_type = body.as_ref().map(|_| "application/json");
+        let body = body.unwrap_or_default();
+        let headers = sigv4::sign(
+            &self.config.credentials,
+            &self.config.region,
+            "ses",
+            &sigv4::SignableRequest {
+                method: method.as_str(),
+                url: &url,
+                content_type,
+                body: sigv4::Payload::Bytes(&body),
+            },
+            self.clock.now(),
+        );
+
+        self.client
+            .request(method, &url, &headers, content_type.zip(Some(body)))
+            .await?
+            .ensure_success("SES")
+    }
+}
+
+impl EmailTransport for SesTransport {
+    fn description(&self) -> String {
+        match &self.config.configuration_set {
+            Some(configuration_set) => format!(
+                "Amazon SES {} (configuration set: {})",
+                self.config.region, configuration_set
+            ),
+            None => format!("Amazon SES {}", self.config.region),
+        }
+    }
+
+    fn test_connection(&self) -> TransportFuture<'_, ()> {
+        Box::pin(async move {
+            #[derive(serde::Deserialize)]
+            #[serde(rename_all = "PascalCase")]
+            struct Account {
+                sending_enabled: bool,
+            }
+            let response = self.request(Method::GET, "account", None).await?;
+            let account: Account =
+                serde_json::from_str(&response.body).context("parsing SES account details")?;
+            ensure!(
+                account.sending_enabled,
+                "sending is disabled for this SES account"
+            );
+
+            if let Some(configuration_set) = &self.config.configuration_set {
+                self.request(
+                    Method::GET,
+                    &format!("configuration-sets/{configuration_set}"),
+                    None,
+                )
+                .await
+                .context("checking SES configuration set")?;
+            }
+            Ok(())
+        })
+    }
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> TransportFuture<'a, ()> {
+        Box::pin(async move {
+            let body = ses_body(email, self.config.configuration_set.as_deref())?;
+            self.request(
+                Method::POST,
+                "outbound-emails",
+                Some(serde_json::to_vec(&body)?),
+            )
+            .await?;
+            Ok(())
+        })
+    }
+}
+
+/// Request body for SES's `SendEmail` operation. Emails with attachments are sent as raw
+/// MIME messages.
+fn ses_body(email: &OutgoingEmail, configuration_set: Option<&str>) -> Result<serde_json::Value> {
+    let content = |data: &str| serde_json::json!({ "Data": data, "Charset": "UTF-8" });
+
+    let mut request = serde_json::json!({
+        "FromEmailAddress": email.from.to_string(),
+        "Destination": { "ToAddresses": [email.to.to_string()] },
+    });
+    if let Some(reply_to) = &email.reply_to {
+        request["ReplyToAddresses"] = serde_json::json!([reply_to.to_string()]);
+    }
+    if let Some(configuration_set) = configuration_set {
+        request["ConfigurationSetName"] = configuration_set.into();
+    }
+    if !email.attachments.is_empty() {
+        let message = email.to_message()?.formatted();
+        request["Content"] =
+            serde_json::json!({ "Raw": { "Data": BASE64_STANDARD.encode(message) } });
+        return Ok(request);
+    }
+
+    let mut body = serde_json::json!({ "Text": content(&email.text) });
+    if let Some(html) = &email.html {
+        body["Html"] = content(html);
+    }
+
+    request["Content"] = serde_json::json!({
+        "Simple": {
+            "Subject": content(&email.subject),
+            "Body": body,
+        },
+    });
+    let headers = email.extra_headers();
+    if !headers.is_empty() {
+        request["Content"]["Simple"]["Headers"] = headers
+            .into_iter()
+            .map(|(name, value)| serde_json::json!({ "Name": name, "Value": value }))
+            .collect();
+    }
+    Ok(request)
+}
+
+/// HTTPS client for email provider and CAPTCHA APIs, pooling connections to each of them.
+#[derive(Clone)]
+pub(crate) struct HttpsClient {
+    client: reqwest::Client,
+}
+
+#[derive(Debug)]
+pub(crate) struct HttpResponse {
+    pub(crate) status: StatusCode,
+    pub(crate) body: String,
+}
+
+impl HttpResponse {
+    pub(crate) fn ensure_success(self, provider: &str) -> Result<Self> {
+        if !self.status.is_success() {
+            let body: String = self.body.chars().take(MAX_ERROR_BODY_LENGTH).collect();
+            bail!("{provider} responded with {}: {}", self.status, body.trim());
+        }
+        Ok(self)
+    }
+}
+
+impl HttpsClient {
+    pub(crate) fn new() -> Result<Self> {
+        // API credentials are never sent without TLS, even after a redirect
+        let client = reqwest::Client::builder()
+            .user_agent("amigo-oculto")
+            .https_only(true)
+            .timeout(TIMEOUT)
+            .build()
+            .context("creating HTTPS client")?;
+        Ok(Self { client })
+    }
+
+    pub(crate) async fn request(
+        &self,
+        method: Method,
+        url: &Url,
+        headers: &[(&str, String)],
+        body: Option<(&str, Vec<u8>)>,
+    ) -> Result<HttpResponse> {
+        let host = url.host_str().context("API URL has no host")?;
+        let mut request = self
+            .client
+            .request(method, url.clone())
+            .header(header::ACCEPT, "application/json");
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+        if let Some((content_type, body)) = body {
+            request = request
+                .header(header::CONTENT_TYPE, content_type)
+                .body(body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("sending request to {host}"))?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .with_context(|| format!("reading response from {host}"))?;
+        Ok(HttpResponse { status, body })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+
+    fn test_email() -> OutgoingEmail {
+        OutgoingEmail {
+            from: "Amigo Oculto <noreply@example.com>".parse().unwrap(),
+            to: "maria@example.com".parse().unwrap(),
+            reply_to: None,
+            subject: "🎁 Natal & Ano Novo".to_string(),
+            text: "Olá!".to_string(),
+            html: Some("<p>Olá!</p>".to_string()),
+            list_unsubscribe: None,
+            attachments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_list_unsubscribe_headers() {
+        let mut email = test_email();
+        let message = String::from_utf8(email.to_message().unwrap().formatted()).unwrap();
+        assert!(!message.contains("List-Unsubscribe"));
+
+        email.list_unsubscribe = Some(
+            "https://amigooculto.app/api/email/unsubscribe/abc"
+                .parse()
+                .unwrap(),
+        );
+        let message = String::from_utf8(email.to_message().unwrap().formatted()).unwrap();
+        assert!(
+            message.contains(
+                "List-Unsubscribe: <https://amigooculto.app/api/email/unsubscribe/abc>\r\n"
+            )
+        );
+        assert!(message.contains("List-Unsubscribe-Post: List-Unsubscribe=One-Click\r\n"));
+
+        assert_eq!(
+            sendgrid_body(&email)["headers"],
+            serde_json::json!({
+                "List-Unsubscribe": "<https://amigooculto.app/api/email/unsubscribe/abc>",
+                "List-Unsubscribe-Post": "List-Unsubscribe=One-Click",
+            })
+        );
+        assert_eq!(
+            ses_body(&email, None).unwrap()["Content"]["Simple"]["Headers"][1],
+            serde_json::json!({
+                "Name": "List-Unsubscribe-Post",
+                "Value": "List-Unsubscribe=One-Click",
+            })
+        );
+    }
+
+    #[test]
+    fn test_reply_to() {
+        let mut email = test_email();
+        email.reply_to = Some("Ana <ana@example.com>".parse().unwrap());
+
+        let message = String::from_utf8(email.to_message().unwrap().formatted()).unwrap();
+        assert!(message.contains("Reply-To: Ana <ana@example.com>\r\n"));
+        assert_eq!(
+            sendgrid_body(&email)["reply_to"],
+            serde_json::json!({ "email": "ana@example.com", "name": "Ana" })
+        );
+        let (_, form) = mailgun_form(&email);
+        assert!(
+            form_urlencoded::parse(&form)
+                .any(|(name, value)| name == "h:Reply-To" && value == "Ana <ana@example.com>")
+        );
+        assert_eq!(
+            ses_body(&email, None).unwrap()["ReplyToAddresses"],
+            serde_json::json!(["Ana <ana@example.com>"])
+        );
+    }
+
+    #[test]
+    fn test_attachments() {
+        let mut email = test_email();
+        email.attachments.push(Attachment {
+            filename: "amigo-oculto.ics".to_string(),
+            content_type: "text/calendar; charset=utf-8".to_string(),
+            data: b"BEGIN:VCALENDAR".to_vec(),
+            content_id: None,
+        });
+
+        let message = String::from_utf8(email.to_message().unwrap().formatted()).unwrap();
+        assert!(message.contains("Content-Type: multipart/mixed"));
+        assert!(message.contains("Content-Type: multipart/alternative"));
+        assert!(message.contains("Content-Disposition: attachment; filename=\"amigo-oculto.ics\""));
+
+        assert_eq!(
+            sendgrid_body(&email)["attachments"],
+            serde_json::json!([{
+                "content": "QkVHSU46VkNBTEVOREFS",
+                "type": "text/calendar; charset=utf-8",
+                "filename": "amigo-oculto.ics",
+                "disposition": "attachment",
+            }])
+        );
+
+        let (content_type, form) = mailgun_form(&email);
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        let form = String::from_utf8(form).unwrap();
+        assert!(form.contains(&format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"subject\"\r\n\r\n🎁 Natal & Ano Novo\r\n"
+        )));
+        assert!(form.contains(
+            "name=\"attachment\"; filename=\"amigo-oculto.ics\"\r\n\
+             Content-Type: text/calendar; charset=utf-8\r\n\r\nBEGIN:VCALENDAR\r\n"
+        ));
+        assert!(form.ends_with(&format!("--{boundary}--\r\n")));
+
+        let body = ses_body(&email, None).unwrap();
+        let raw = BASE64_STANDARD
+            .decode(body["Content"]["Raw"]["Data"].as_str().unwrap())
+            .unwrap();
+        assert!(String::from_utf8(raw).unwrap().contains("multipart/mixed"));
+    }
+
+    #[test]
+    fn test_inline_attachments() {
+        let mut email = test_email();
+        email.attachments.push(Attachment {
+            filename: "qr-code.png".to_string(),
+            content_type: "image/png".to_string(),
+            data: b"PNG".to_vec(),
+            content_id: Some("qr-code.png".to_string()),
+        });
+
+        let message = String::from_utf8(email.to_message().unwrap().formatted()).unwrap();
+        assert!(message.contains("Content-Type: multipart/related"));
+        assert!(message.contains("Content-ID: <qr-code.png>\r\n"));
+        assert!(!message.contains("multipart/mixed"));
+
+        let attachment = &sendgrid_body(&email)["attachments"][0];
+        assert_eq!(attachment["disposition"], "inline");
+        assert_eq!(attachment["content_id"], "qr-code.png");
+
+        let form = String::from_utf8(mailgun_form(&email).1).unwrap();
+        assert!(form.contains("name=\"inline\"; filename=\"qr-code.png\""));
+    }
+
+    /// Counts sent emails, or fails while `down`.
+    #[derive(Default)]
+    struct FakeTransport {
+        down: AtomicBool,
+        sent: AtomicU32,
+    }
+
+    impl EmailTransport for FakeTransport {
+        fn description(&self) -> String {
+            "fake".to_string()
+        }
+
+        fn test_connection(&self) -> TransportFuture<'_, ()> {
+            Box::pin(async move {
+                ensure!(!self.down.load(Ordering::Relaxed), "down");
+                Ok(())
+            })
+        }
+
+        fn send<'a>(&'a self, _email: &'a OutgoingEmail) -> TransportFuture<'a, ()> {
+            Box::pin(async move {
+                self.test_connection().await?;
+                self.sent.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failover() {
+        let primary = Arc::new(FakeTransport::default());
+        let secondary = Arc::new(FakeTransport::default());
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let transport = FailoverTransport::new(primary.clone(), secondary.clone(), clock.clone());
+        let email = test_email();
+        let sent = |fake: &FakeTransport| fake.sent.load(Ordering::Relaxed);
+
+        transport.send(&email).await.unwrap();
+        assert_eq!((sent(&primary), sent(&secondary)), (1, 0));
+
+        // Fails over on the third failure in a row, sending that email through the secondary
+        primary.down.store(true, Ordering::Relaxed);
+        for _ in 1..FAILOVER_AFTER_FAILURES {
+            assert!(transport.send(&email).await.is_err());
+        }
+        transport.send(&email).await.unwrap();
+        assert_eq!((sent(&primary), sent(&secondary)), (1, 1));
+
+        // Stays on the secondary until a connection test finds the primary back
+        primary.down.store(false, Ordering::Relaxed);
+        transport.send(&email).await.unwrap();
+        assert_eq!((sent(&primary), sent(&secondary)), (1, 2));
+        transport.test_connection().await.unwrap();
+        transport.send(&email).await.unwrap();
+        assert_eq!((sent(&primary), sent(&secondary)), (2, 2));
+
+        // Sends retest the primary once the interval has passed, switching back once it passes
+        primary.down.store(true, Ordering::Relaxed);
+        for _ in 0..FAILOVER_AFTER_FAILURES {
+            let _ = transport.send(&email).await;
+        }
+        assert_eq!((sent(&primary), sent(&secondary)), (2, 3));
+        clock.advance(chrono::Duration::from_std(PRIMARY_RETEST_INTERVAL).unwrap());
+        transport.send(&email).await.unwrap();
+        assert_eq!((sent(&primary), sent(&secondary)), (2, 4));
+        primary.down.store(false, Ordering::Relaxed);
+        transport.send(&email).await.unwrap();
+        assert_eq!((sent(&primary), sent(&secondary)), (2, 5));
+        clock.advance(chrono::Duration::from_std(PRIMARY_RETEST_INTERVAL).unwrap());
+        transport.send(&email).await.unwrap();
+        assert_eq!((sent(&primary), sent(&secondary)), (3, 5));
+
+        // A failed connection test fails over right away
+        primary.down.store(true, Ordering::Relaxed);
+        transport.test_connection().await.unwrap();
+        transport.send(&email).await.unwrap();
+        assert_eq!((sent(&primary), sent(&secondary)), (3, 6));
+    }
+
+    #[test]
+    fn test_sendgrid_body() {
+        assert_eq!(
+            sendgrid_body(&test_email()),
+            serde_json::json!({
+                "personalizations": [{ "to": [{ "email": "maria@example.com" }] }],
+                "from": { "email": "noreply@example.com", "name": "Amigo Oculto" },
+                "subject": "🎁 Natal & Ano Novo",
+                "content": [
+                    { "type": "text/plain", "value": "Olá!" },
+                    { "type": "text/html", "value": "<p>Olá!</p>" },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_mailgun_form() {
+        let mut email = test_email();
+        email.list_unsubscribe = Some(
+            "https://amigooculto.app/api/email/unsubscribe/abc"
+                .parse()
+                .unwrap(),
+        );
+        let form: Vec<(String, String)> = form_urlencoded::parse(&mailgun_form(&email).1)
+            .into_owned()
+            .collect();
+        assert_eq!(
+            form,
+            [
+                ("from", "Amigo Oculto <noreply@example.com>"),
+                ("to", "maria@example.com"),
+                ("subject", "🎁 Natal & Ano Novo"),
+                ("text", "Olá!"),
+                ("html", "<p>Olá!</p>"),
+                (
+                    "h:List-Unsubscribe",
+                    "<https://amigooculto.app/api/email/unsubscribe/abc>"
+                ),
+                ("h:List-Unsubscribe-Post", "List-Unsubscribe=One-Click"),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_ses_body() {
+        let mut email = test_email();
+        email.html = None;
+        assert_eq!(
+            ses_body(&email, Some("amigo-oculto")).unwrap(),
+            serde_json::json!({
+                "FromEmailAddress": "Amigo Oculto <noreply@example.com>",
+                "Destination": { "ToAddresses": ["maria@example.com"] },
+                "Content": {
+                    "Simple": {
+                        "Subject": { "Data": "🎁 Natal & Ano Novo", "Charset": "UTF-8" },
+                        "Body": { "Text": { "Data": "Olá!", "Charset": "UTF-8" } },
+                    },
+                },
+                "ConfigurationSetName": "amigo-oculto",
+            })
+        );
+    }
+
+    #[test]
+    fn test_mailgun_url() {
+        let transport = MailgunTransport {
+            client: HttpsClient::new().unwrap(),
+            config: MailgunConfig {
+                api_key: "key".to_string(),
+                domain: "mg.example.com".to_string(),
+                api_base: "https://api.eu.mailgun.net".parse().unwrap(),
+            },
+        };
+        assert_eq!(
+            transport.url("mg.example.com/messages").unwrap().as_str(),
+            "https://api.eu.mailgun.net/v3/mg.example.com/messages"
+        );
+        assert_eq!(transport.authorization(), "Basic YXBpOmtleQ==");
+    }
+
+    #[test]
+    fn test_ensure_success() {
+        let response = HttpResponse {
+            status: StatusCode::UNAUTHORIZED,
+            body: "Forbidden\n".to_string(),
+        };
+        assert_eq!(
+            response.ensure_success("Mailgun").unwrap_err().to_string(),
+            "Mailgun responded with 401 Unauthorized: Forbidden"
+        );
+        let response = HttpResponse {
+            status: StatusCode::ACCEPTED,
+            body: String::new(),
+        };
+        assert!(response.ensure_success("Mailgun").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_refuses_plain_http() {
+        let client = HttpsClient::new().unwrap();
+        let url = Url::parse("http://localhost:9/v3/messages").unwrap();
+        assert!(client.request(Method::GET, &url, &[], None).await.is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! Reports unexpected errors to Sentry, or any service accepting Sentry events (such as
+//! GlitchTip), so they outlive the ephemeral logs.
+//!
+//! Only failures worth a developer's attention are sent: requests answered with a server
+//! error because of the database or an internal error, failed background task runs, and
+//! panics. Events are tagged with the route or task and the release, and sent in the
+//! background by the `sentry` crate. Reporting is disabled unless `SENTRY_DSN` is set.
+
+use crate::request_id::RequestId;
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use sentry::protocol::{Event, Level};
+use sentry::types::Dsn;
+use std::collections::BTreeMap;
+
+/// Version of the server, as events are tagged with it.
+const RELEASE: &str = concat!("amigo-oculto-backend@", env!("CARGO_PKG_VERSION"));
+
+#[derive(Clone)]
+pub struct ErrorReportingConfig {
+    pub dsn: Dsn,
+    /// e.g. `production` or `staging` (`SENTRY_ENVIRONMENT`).
+    pub environment: String,
+}
+
+/// Where an error happened.
+#[derive(Debug, Clone, Copy)]
+pub enum Source<'a> {
+    /// A request, with its route rather than its path, which may contain tokens.
+    Request {
+        method: &'a str,
+        route: &'a str,
+        request_id: Option<&'a RequestId>,
+    },
+    /// A run of a background task.
+    Job(&'a str),
+}
+
+/// Starts reporting errors. Events still queued are sent when the returned guard is
+/// dropped, so it must be kept until the server exits.
+pub fn init(config: ErrorReportingConfig) -> sentry::ClientInitGuard {
+    sentry::init(sentry::ClientOptions {
+        dsn: Some(config.dsn),
+        release: Some(RELEASE.into()),
+        environment: Some(config.environment.into()),
+        ..Default::default()
+    })
+}
+
+/// Sends an error in the background, if reporting is set up.
+pub fn capture(message: String, source: Source<'_>) {
+    sentry::capture_event(event(message, source));
+}
+
+/// The event describing an error.
+fn event(message: String, source: Source<'_>) -> Event<'static> {
+    let mut tags = BTreeMap::new();
+    let (transaction, request) = match source {
+        Source::Request {
+            method,
+            route,
+            request_id,
+        } => {
+            tags.insert("route".to_string(), route.to_string());
+            if let Some(request_id) = request_id {
+                tags.insert("request_id".to_string(), request_id.as_str().to_string());
+            }
+            // Neither the URL nor the headers are sent, as they may hold tokens
+            let request = sentry::protocol::Request {
+                method: Some(method.to_string()),
+                ..Default::default()
+            };
+            (format!("{method} {route}"), Some(request))
+        }
+        Source::Job(job) => {
+            tags.insert("job".to_string(), job.to_string());
+            (job.to_string(), None)
+        }
+    };
+    Event {
+        level: Level::Error,
+        logger: Some("amigo-oculto".to_string()),
+        message: Some(message),
+        transaction: Some(transaction),
+        request,
+        tags,
+        ..Default::default()
+    }
+}
+
+/// Marks a response whose error should be reported, with its details, which the response
+/// itself may not include.
+#[derive(Debug, Clone)]
+pub struct ReportableError(pub String);
+
+/// Middleware reporting the errors of responses marked with [`ReportableError`]. Runs inside
+/// `sentry_tower::NewSentryLayer`, so each request reports through its own hub.
+pub async fn report_errors(request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string());
+    let request_id = request.extensions().get::<RequestId>().cloned();
+    let response = next.run(request).await;
+    if let Some(ReportableError(message)) = response.extensions().get::<ReportableError>() {
+        let route = route.as_deref().unwrap_or("unknown");
+        capture(
+            message.clone(),
+            Source::Request {
+                method: method.as_str(),
+                route,
+                request_id: request_id.as_ref(),
+            },
+        );
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event() {
+        let request_id =
+            RequestId::from_header(&axum::http::HeaderValue::from_static("01J9Z3")).unwrap();
+        let request = event(
+            "database error: disk I/O error".to_string(),
+            Source::Request {
+                method: "POST",
+                route: "/api/games/{game_id}/draw",
+                request_id: Some(&request_id),
+            },
+        );
+        assert_eq!(request.level, Level::Error);
+        assert_eq!(
+            request.transaction.as_deref(),
+            Some("POST /api/games/{game_id}/draw")
+        );
+        let details = request.request.as_ref().unwrap();
+        assert_eq!(details.method.as_deref(), Some("POST"));
+        assert!(details.url.is_none());
+        assert_eq!(request.tags["request_id"], "01J9Z3");
+        assert_eq!(
+            request.message.as_deref(),
+            Some("database error: disk I/O error")
+        );
+
+        let job = event(
+            "database is locked".to_string(),
+            Source::Job("send_reminders"),
+        );
+        assert_eq!(job.tags["job"], "send_reminders");
+        assert!(job.request.is_none());
+    }
+}
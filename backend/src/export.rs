@@ -0,0 +1,437 @@
+use crate::datetime::GameTimezone;
+use crate::i18n::Locale;
+use crate::models::{Game, Participant, ParticipantStatus};
+use crate::token::{EmailAddress, EmailDomain};
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use utoipa::ToSchema;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    /// The format preferred by an `Accept` header, if it names one.
+    pub fn from_accept(header: &str) -> Option<Self> {
+        let mut ranges: Vec<(&str, f32)> = header
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let media_type = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse().ok())?;
+                Some((media_type, quality))
+            })
+            .filter(|&(_, quality)| quality > 0.0)
+            .collect();
+        // Stable, so equally preferred types keep their order
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges.into_iter().find_map(|(media_type, _)| {
+            match media_type.to_ascii_lowercase().as_str() {
+                "application/json" | "application/*" | "*/*" => Some(Self::Json),
+                "text/csv" => Some(Self::Csv),
+                _ => None,
+            }
+        })
+    }
+}
+
+/// Offline record of a game, as downloaded by its organizer.
+///
+/// The JSON form can be imported again to recreate the game, e.g. on another instance.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GameExport {
+    pub name: String,
+    pub event_date: NaiveDate,
+    #[serde(default)]
+    pub event_location: Option<String>,
+    pub organizer_email: EmailAddress,
+    pub created_at: DateTime<Utc>,
+    pub drawn: bool,
+    #[serde(default)]
+    pub timezone: GameTimezone,
+    #[serde(default)]
+    pub locale: Locale,
+    #[serde(default)]
+    pub allowed_email_domain: Option<EmailDomain>,
+    #[serde(default)]
+    pub organizer_message: Option<String>,
+    #[serde(default)]
+    pub reveal_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub reveal_expires_after_event: bool,
+    #[serde(default)]
+    pub reveal_single_use: bool,
+    #[serde(default = "default_true")]
+    pub reminders_enabled: bool,
+    #[serde(default)]
+    pub wrap_up_enabled: bool,
+    #[serde(default)]
+    pub digest_enabled: bool,
+    #[serde(default)]
+    pub reply_to_organizer: bool,
+    #[serde(default)]
+    pub open_tracking_enabled: bool,
+    #[serde(default)]
+    pub participant_email_subject: Option<String>,
+    #[serde(default)]
+    pub participant_email_body: Option<String>,
+    #[serde(default)]
+    pub event_reminder_days: Vec<u32>,
+    pub participants: Vec<ParticipantExport>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ParticipantExport {
+    pub name: String,
+    pub email: EmailAddress,
+    #[serde(default)]
+    pub has_viewed: bool,
+    /// Only present when the organizer explicitly asked for the match assignments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_email: Option<EmailAddress>,
+}
+
+impl GameExport {
+    pub fn new(game: Game, participants: Vec<Participant>, include_matches: bool) -> Self {
+        let by_id: HashMap<_, _> = participants
+            .iter()
+            .map(|p| (p.id, (p.name.clone(), p.email.clone())))
+            .collect();
+
+        let participants = participants
+            .into_iter()
+            .map(|p| {
+                let matched = p
+                    .matched_with_id
+                    .filter(|_| include_matches)
+                    .and_then(|id| by_id.get(&id).cloned());
+                ParticipantExport {
+                    name: p.name,
+                    email: p.email,
+                    has_viewed: p.has_viewed,
+                    matched_name: matched.as_ref().map(|(name, _)| name.clone()),
+                    matched_email: matched.map(|(_, email)| email),
+                }
+            })
+            .collect();
+
+        Self {
+            name: game.name,
+            event_date: game.event_date,
+            event_location: game.event_location,
+            organizer_email: game.organizer_email,
+            created_at: game.created_at,
+            drawn: game.drawn,
+            timezone: game.timezone,
+            locale: game.locale,
+            allowed_email_domain: game.allowed_email_domain,
+            organizer_message: game.organizer_message,
+            reveal_at: game.reveal_at,
+            locked: game.locked,
+            reveal_expires_after_event: game.reveal_expires_after_event,
+            reveal_single_use: game.reveal_single_use,
+            reminders_enabled: game.reminders_enabled,
+            wrap_up_enabled: game.wrap_up_enabled,
+            digest_enabled: game.digest_enabled,
+            reply_to_organizer: game.reply_to_organizer,
+            open_tracking_enabled: game.open_tracking_enabled,
+            participant_email_subject: game.participant_email_subject,
+            participant_email_body: game.participant_email_body,
+            event_reminder_days: game.event_reminder_days,
+            participants,
+        }
+    }
+
+    /// Recreates the exported game and its participants, with fresh IDs and tokens.
+    ///
+    /// Match assignments are restored only if the export holds a complete and consistent set
+    /// of them. Otherwise the game is imported as not drawn, so the organizer can draw again.
+    pub fn into_game(self) -> (Game, Vec<Participant>) {
+        let mut game = Game {
+            event_location: self.event_location,
+            timezone: self.timezone,
+            locale: self.locale,
+            allowed_email_domain: self.allowed_email_domain,
+            organizer_message: self.organizer_message,
+            reveal_at: self.reveal_at,
+            locked: self.locked,
+            reveal_expires_after_event: self.reveal_expires_after_event,
+            reveal_single_use: self.reveal_single_use,
+            reminders_enabled: self.reminders_enabled,
+            wrap_up_enabled: self.wrap_up_enabled,
+            digest_enabled: self.digest_enabled,
+            reply_to_organizer: self.reply_to_organizer,
+            open_tracking_enabled: self.open_tracking_enabled,
+            participant_email_subject: self.participant_email_subject,
+            participant_email_body: self.participant_email_body,
+            event_reminder_days: self.event_reminder_days,
+            ..Game::new(self.name, self.event_date, self.organizer_email)
+        };
+
+        let mut participants: Vec<Participant> = self
+            .participants
+            .iter()
+            .map(|p| Participant::new(game.id, p.name.clone(), p.email.clone()))
+            .collect();
+
+        // Matches are stored by email, so they can only be restored when emails are unique
+        let ids_by_email: HashMap<_, _> = participants.iter().map(|p| (&p.email, p.id)).collect();
+        let matched_ids: Option<Vec<_>> = self
+            .participants
+            .iter()
+            .map(|p| {
+                p.matched_email
+                    .as_ref()
+                    .and_then(|email| ids_by_email.get(email).copied())
+            })
+            .collect();
+        let matches = matched_ids.filter(|ids| {
+            self.drawn
+                && ids_by_email.len() == participants.len()
+                && ids.iter().collect::<HashSet<_>>().len() == ids.len()
+                && ids.iter().zip(&participants).all(|(id, p)| *id != p.id)
+        });
+
+        if let Some(matched_ids) = matches {
+            game.drawn = true;
+            for ((participant, matched_id), exported) in participants
+                .iter_mut()
+                .zip(matched_ids)
+                .zip(&self.participants)
+            {
+                participant.matched_with_id = Some(matched_id);
+                participant.has_viewed = exported.has_viewed;
+            }
+        }
+
+        (game, participants)
+    }
+
+    /// Renders the participant list as CSV, one row per participant.
+    pub fn to_csv(&self) -> String {
+        let include_matches = self.participants.iter().any(|p| p.matched_name.is_some());
+
+        let mut csv = String::from("nome,email,visualizou");
+        if include_matches {
+            csv.push_str(",amigo_oculto");
+        }
+        csv.push_str("\r\n");
+
+        for p in &self.participants {
+            csv.push_str(&csv_field(&p.name));
+            csv.push(',');
+            csv.push_str(&csv_field(p.email.as_ref()));
+            csv.push(',');
+            csv.push_str(if p.has_viewed { "sim" } else { "não" });
+            if include_matches {
+                csv.push(',');
+                csv.push_str(&csv_field(p.matched_name.as_deref().unwrap_or_default()));
+            }
+            csv.push_str("\r\n");
+        }
+
+        csv
+    }
+}
+
+/// Renders participant statuses as CSV, for organizers following the game in a spreadsheet.
+pub fn status_csv(participants: &[ParticipantStatus]) -> String {
+    let mut csv = String::from(
+        "nome,email,visualizou,ultima_visualizacao,dispositivos,email_aberto_em,entrega,reenvios\r\n",
+    );
+    let time = |time: Option<DateTime<Utc>>| {
+        time.map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+            .unwrap_or_default()
+    };
+
+    for p in participants {
+        let delivery = match p.email_undeliverable.as_deref() {
+            Some("complaint") => "marcado como spam",
+            Some(_) => "devolvido",
+            None if p.email_domain_unreachable => "domínio inexistente",
+            None => "",
+        };
+        let fields = [
+            csv_field(&p.name),
+            csv_field(p.email.as_ref()),
+            (if p.has_viewed { "sim" } else { "não" }).to_string(),
+            time(p.last_viewed_at),
+            p.device_count.to_string(),
+            time(p.email_opened_at),
+            delivery.to_string(),
+            p.resend_count.to_string(),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Quotes a field when needed, and neutralizes values that spreadsheets would run as formulas.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::GameId;
+
+    fn drawn_game() -> (Game, Vec<Participant>) {
+        let mut game = Game::new(
+            "Natal".to_string(),
+            NaiveDate::from_ymd_opt(2025, 12, 24).unwrap(),
+            "organizer@example.com".parse().unwrap(),
+        );
+        let mut alice = Participant::new(
+            GameId::new(),
+            "Alice, a \"Tia\"".to_string(),
+            "alice@example.com".parse().unwrap(),
+        );
+        let mut bob = Participant::new(
+            GameId::new(),
+            "=Bob".to_string(),
+            "bob@example.com".parse().unwrap(),
+        );
+        alice.matched_with_id = Some(bob.id);
+        bob.matched_with_id = Some(alice.id);
+        bob.has_viewed = true;
+        game.drawn = true;
+        (game, vec![alice, bob])
+    }
+
+    #[test]
+    fn test_format_from_accept() {
+        assert_eq!(
+            ExportFormat::from_accept("text/csv"),
+            Some(ExportFormat::Csv)
+        );
+        assert_eq!(
+            ExportFormat::from_accept("application/json;q=0.5, text/csv"),
+            Some(ExportFormat::Csv)
+        );
+        assert_eq!(
+            ExportFormat::from_accept("text/csv;q=0.5, */*"),
+            Some(ExportFormat::Json)
+        );
+        assert_eq!(ExportFormat::from_accept("text/html"), None);
+    }
+
+    #[test]
+    fn test_status_csv() {
+        let (_, participants) = drawn_game();
+        let statuses: Vec<_> = participants
+            .into_iter()
+            .map(|p| ParticipantStatus {
+                id: p.id,
+                name: p.name,
+                email: p.email,
+                has_viewed: p.has_viewed,
+                last_viewed_at: p
+                    .has_viewed
+                    .then(|| "2025-12-20T18:30:00Z".parse().unwrap()),
+                device_count: u64::from(p.has_viewed),
+                email_undeliverable: (!p.has_viewed).then(|| "bounce".to_string()),
+                email_opened_at: None,
+                email_domain_unreachable: false,
+                resend_count: 1,
+                created_at: "2025-12-01T12:00:00Z".parse().unwrap(),
+            })
+            .collect();
+        assert_eq!(
+            status_csv(&statuses),
+            "nome,email,visualizou,ultima_visualizacao,dispositivos,email_aberto_em,entrega,reenvios\r\n\
+             \"Alice, a \"\"Tia\"\"\",alice@example.com,não,,0,,devolvido,1\r\n\
+             '=Bob,bob@example.com,sim,2025-12-20T18:30:00Z,1,,,1\r\n"
+        );
+    }
+
+    #[test]
+    fn test_export_hides_matches_by_default() {
+        let (game, participants) = drawn_game();
+        let export = GameExport::new(game, participants, false);
+        assert!(export.participants.iter().all(|p| p.matched_name.is_none()));
+        assert_eq!(
+            export.to_csv(),
+            "nome,email,visualizou\r\n\
+             \"Alice, a \"\"Tia\"\"\",alice@example.com,não\r\n\
+             '=Bob,bob@example.com,sim\r\n"
+        );
+    }
+
+    #[test]
+    fn test_export_with_matches() {
+        let (game, participants) = drawn_game();
+        let export = GameExport::new(game, participants, true);
+        assert_eq!(export.participants[0].matched_name.as_deref(), Some("=Bob"));
+        assert_eq!(
+            export.participants[0]
+                .matched_email
+                .as_ref()
+                .map(|e| e.as_ref()),
+            Some("bob@example.com")
+        );
+        assert_eq!(
+            export.to_csv(),
+            "nome,email,visualizou,amigo_oculto\r\n\
+             \"Alice, a \"\"Tia\"\"\",alice@example.com,não,'=Bob\r\n\
+             '=Bob,bob@example.com,sim,\"Alice, a \"\"Tia\"\"\"\r\n"
+        );
+    }
+
+    #[test]
+    fn test_import_restores_matches_with_fresh_ids() {
+        let (game, participants) = drawn_game();
+        let old_ids: Vec<_> = participants.iter().map(|p| p.id).collect();
+        let json = serde_json::to_string(&GameExport::new(game, participants, true)).unwrap();
+
+        let export: GameExport = serde_json::from_str(&json).unwrap();
+        let (game, participants) = export.into_game();
+        assert!(game.drawn);
+        assert_eq!(participants[0].matched_with_id, Some(participants[1].id));
+        assert_eq!(participants[1].matched_with_id, Some(participants[0].id));
+        assert!(!participants[0].has_viewed);
+        assert!(participants[1].has_viewed);
+        assert!(participants.iter().all(|p| p.game_id == game.id));
+        assert!(participants.iter().all(|p| !old_ids.contains(&p.id)));
+    }
+
+    #[test]
+    fn test_import_without_matches_is_not_drawn() {
+        let (game, participants) = drawn_game();
+        let json = serde_json::to_string(&GameExport::new(game, participants, false)).unwrap();
+
+        let export: GameExport = serde_json::from_str(&json).unwrap();
+        let (game, participants) = export.into_game();
+        assert!(!game.drawn);
+        assert!(participants.iter().all(|p| p.matched_with_id.is_none()));
+        assert!(participants.iter().all(|p| !p.has_viewed));
+    }
+}
@@ -0,0 +1,156 @@
+//! Live updates of games, streamed to the organizer dashboard as Server-Sent Events so it
+//! doesn't have to poll the game status.
+//!
+//! Events are only sent to clients connected to the instance where they happened. They
+//! carry just enough for the dashboard to know what changed; it then reloads the game, as
+//! it also does after reconnecting, so missed events don't leave it out of date.
+
+use crate::token::{GameId, ParticipantId};
+use axum::response::sse::Event;
+use futures_util::{Stream, StreamExt, stream};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_util::sync::CancellationToken;
+
+/// Events kept for each client that's slow to receive them, before it's told it missed some.
+const CHANNEL_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameEvent {
+    ParticipantAdded {
+        participant_id: ParticipantId,
+    },
+    /// A participant was emailed their reveal link, after the draw or when resending.
+    EmailSent {
+        participant_id: ParticipantId,
+    },
+    EmailFailed {
+        participant_id: ParticipantId,
+    },
+    /// A participant saw their match.
+    MatchViewed {
+        participant_id: ParticipantId,
+    },
+    /// The client fell behind and missed events.
+    Lagged,
+}
+
+impl GameEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::ParticipantAdded { .. } => "participant_added",
+            Self::EmailSent { .. } => "email_sent",
+            Self::EmailFailed { .. } => "email_failed",
+            Self::MatchViewed { .. } => "match_viewed",
+            Self::Lagged => "lagged",
+        }
+    }
+
+    pub fn to_sse(&self) -> Event {
+        Event::default()
+            .event(self.name())
+            .json_data(self)
+            .expect("game events serialize to JSON")
+    }
+}
+
+/// Where events are published, and followed by the organizers' dashboards.
+#[derive(Clone)]
+pub struct GameEvents {
+    /// Channels of the games someone follows.
+    channels: Arc<Mutex<HashMap<GameId, broadcast::Sender<GameEvent>>>>,
+    /// Ends the streams on shutdown, which would otherwise wait for them.
+    shutdown: CancellationToken,
+}
+
+impl GameEvents {
+    pub fn new(shutdown: CancellationToken) -> Self {
+        Self {
+            channels: Arc::default(),
+            shutdown,
+        }
+    }
+
+    pub fn publish(&self, game_id: GameId, event: GameEvent) {
+        let mut channels = self.channels.lock().expect("game events lock poisoned");
+        if let Some(sender) = channels.get(&game_id)
+            && sender.send(event).is_err()
+        {
+            // Everyone following the game is gone
+            channels.remove(&game_id);
+        }
+    }
+
+    /// Events of a game from now on, until shutdown.
+    pub fn follow(&self, game_id: GameId) -> impl Stream<Item = GameEvent> + use<> {
+        let receiver = {
+            let mut channels = self.channels.lock().expect("game events lock poisoned");
+            channels.retain(|_, sender| sender.receiver_count() > 0);
+            channels
+                .entry(game_id)
+                .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+                .subscribe()
+        };
+        stream::unfold(receiver, |mut receiver| async move {
+            let event = match receiver.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => GameEvent::Lagged,
+                Err(RecvError::Closed) => return None,
+            };
+            Some((event, receiver))
+        })
+        .take_until(self.shutdown.clone().cancelled_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_follow() {
+        let shutdown = CancellationToken::new();
+        let events = GameEvents::new(shutdown.clone());
+        let game_id = GameId::new();
+        let participant_id = ParticipantId::new();
+
+        // Nobody is following yet
+        events.publish(game_id, GameEvent::EmailSent { participant_id });
+
+        let mut followed = Box::pin(events.follow(game_id));
+        let mut other = Box::pin(events.follow(GameId::new()));
+        events.publish(game_id, GameEvent::MatchViewed { participant_id });
+        assert_eq!(
+            followed.next().await,
+            Some(GameEvent::MatchViewed { participant_id })
+        );
+
+        shutdown.cancel();
+        assert_eq!(followed.next().await, None);
+        assert_eq!(other.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_lagged() {
+        let events = GameEvents::new(CancellationToken::new());
+        let game_id = GameId::new();
+        let mut followed = Box::pin(events.follow(game_id));
+        for _ in 0..=CHANNEL_CAPACITY {
+            let participant_id = ParticipantId::new();
+            events.publish(game_id, GameEvent::ParticipantAdded { participant_id });
+        }
+        assert_eq!(followed.next().await, Some(GameEvent::Lagged));
+    }
+
+    #[tokio::test]
+    async fn test_channels_are_dropped_when_unfollowed() {
+        let events = GameEvents::new(CancellationToken::new());
+        let game_id = GameId::new();
+        drop(events.follow(game_id));
+        events.publish(game_id, GameEvent::Lagged);
+        assert!(events.channels.lock().unwrap().is_empty());
+    }
+}
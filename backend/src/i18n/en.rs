@@ -0,0 +1,867 @@
+//! English translations, keyed by the Portuguese text.
+
+pub(super) const CATALOG: &[(&str, &str)] = &[
+    // Email subjects
+    ("⏰ Lembrete:", "⏰ Reminder:"),
+    ("📅 Está chegando:", "📅 Coming up:"),
+    ("✅ Sorteio Realizado:", "✅ Draw Complete:"),
+    ("🎉 Resumo:", "🎉 Summary:"),
+    ("📊 Resumo semanal:", "📊 Weekly summary:"),
+    ("⚠️ Email não entregue:", "⚠️ Email not delivered:"),
+    ("🔐 Código de Verificação", "🔐 Verification Code"),
+    (
+        "🔐 Código para recuperar seu Amigo Oculto",
+        "🔐 Code to recover your Secret Santa",
+    ),
+    ("🔑 Acesso do Organizador", "🔑 Organizer Access"),
+    ("🎉 Jogo Criado:", "🎉 Game Created:"),
+    ("📢 Aviso:", "📢 Notice:"),
+    // Shared email components
+    ("Local:", "Location:"),
+    ("ver no mapa", "view on map"),
+    (
+        "Se o botão não funcionar, copie e cole este link no seu navegador:",
+        "If the button doesn't work, copy and paste this link into your browser:",
+    ),
+    (
+        "Ou aponte a câmera do celular para o código:",
+        "Or point your phone's camera at the code:",
+    ),
+    (
+        "Amigo Oculto - Sistema de Sorteio",
+        "Secret Santa - Gift Exchange Draw",
+    ),
+    (
+        "Não quer mais receber lembretes e resumos? ",
+        "Don't want reminders and summaries anymore? ",
+    ),
+    ("Descadastre-se", "Unsubscribe"),
+    ("Amigo Oculto", "Secret Santa"),
+    // Printable card
+    ("Para:", "To:"),
+    ("De: seu amigo oculto", "From: your Secret Santa"),
+    // HTML emails
+    (
+        "Recebemos um pedido de acesso aos jogos organizados por este email.",
+        "We received a request to access the games organized by this email.",
+    ),
+    ("Entrar", "Sign in"),
+    ("⏱️ Atenção:", "⏱️ Note:"),
+    (
+        " Este link expira em 15 minutos e só pode ser usado uma vez.",
+        " This link expires in 15 minutes and can only be used once.",
+    ),
+    (
+        "Se você não solicitou este acesso, ignore este email.",
+        "If you didn't request this access, ignore this email.",
+    ),
+    (
+        "Acesso do Organizador - Amigo Oculto",
+        "Organizer Access - Secret Santa",
+    ),
+    ("✅ Sorteio Realizado!", "✅ Draw Complete!"),
+    (
+        "Parabéns! O sorteio foi realizado com sucesso! 🎉",
+        "Congratulations! The draw was completed successfully! 🎉",
+    ),
+    ("Data do evento:", "Event date:"),
+    ("Participantes:", "Participants:"),
+    ("Participante", "Participant"),
+    (
+        "Todos os participantes receberam um email com o link para descobrir quem tiraram.",
+        "Every participant got an email with a link to find out who they drew.",
+    ),
+    ("⚠️ Importante:", "⚠️ Important:"),
+    (
+        " Guarde este email! Use o link abaixo para acompanhar quem já visualizou seu amigo oculto.",
+        " Keep this email! Use the link below to see who has already viewed their Secret Santa.",
+    ),
+    ("Acompanhar Status", "Track Status"),
+    ("Sorteio Realizado - {}", "Draw Complete - {}"),
+    ("🎉 Resumo do Amigo Oculto", "🎉 Secret Santa Summary"),
+    (
+        "O Amigo Oculto de {} já aconteceu! Veja como foi:",
+        "The Secret Santa on {} is over! Here's how it went:",
+    ),
+    ("Viram quem tiraram:", "Saw who they drew:"),
+    ("de", "of"),
+    ("Visualizações no total:", "Total views:"),
+    ("Emails reenviados:", "Emails resent:"),
+    ("Lembretes enviados:", "Reminders sent:"),
+    (
+        "Nunca abriram o link de revelação:",
+        "Never opened their reveal link:",
+    ),
+    (
+        "Obrigado por usar o Amigo Oculto! Até a próxima! 🎁",
+        "Thanks for using Secret Santa! See you next time! 🎁",
+    ),
+    ("Resumo - {}", "Summary - {}"),
+    ("📊 Resumo Semanal", "📊 Weekly Summary"),
+    (
+        "Veja como está o Amigo Oculto de {}:",
+        "Here's how the Secret Santa on {} is going:",
+    ),
+    (
+        "Ainda não viram quem tiraram:",
+        "Haven't seen who they drew yet:",
+    ),
+    (
+        "Você pode reenviar os emails pela página do jogo.",
+        "You can resend their emails from the game page.",
+    ),
+    ("Resumo Semanal - {}", "Weekly Summary - {}"),
+    ("📢 Aviso do Amigo Oculto", "📢 Secret Santa Notice"),
+    ("⚠️ Email Não Entregue", "⚠️ Email Not Delivered"),
+    (
+        "{} marcou o email do Amigo Oculto como spam.",
+        "{} marked the Secret Santa email as spam.",
+    ),
+    (
+        "O email para {} não pôde ser entregue. O endereço pode estar errado ou não existir mais.",
+        "The email to {} couldn't be delivered. The address may be wrong or no longer exist.",
+    ),
+    (
+        "Confira o endereço com {} e corrija-o na página do jogo para reenviar o link de revelação.",
+        "Check the address with {} and fix it on the game page to resend the reveal link.",
+    ),
+    ("Corrigir Email", "Fix Email"),
+    ("Email Não Entregue - {}", "Email Not Delivered - {}"),
+    ("Olá ", "Hi "),
+    (
+        "Você foi convidado para participar do Amigo Oculto ",
+        "You've been invited to the Secret Santa ",
+    ),
+    ("Mensagem do organizador:", "Message from the organizer:"),
+    (
+        "Clique no botão abaixo para descobrir quem você tirou:",
+        "Click the button below to find out who you drew:",
+    ),
+    ("Ver Meu Amigo Oculto", "See My Secret Santa"),
+    ("Dica:", "Tip:"),
+    (
+        " Guarde este email! Você pode precisar dele para consultar quem você tirou mais tarde.",
+        " Keep this email! You may need it to check who you drew later.",
+    ),
+    ("Amigo Oculto - {}", "Secret Santa - {}"),
+    ("⏰ Lembrete", "⏰ Reminder"),
+    (
+        "O sorteio do Amigo Oculto ",
+        "The draw for the Secret Santa ",
+    ),
+    (
+        " já foi feito, mas você ainda não viu quem tirou.",
+        " is done, but you haven't seen who you drew yet.",
+    ),
+    ("Lembrete - {}", "Reminder - {}"),
+    ("📅 O evento está chegando", "📅 The event is coming up"),
+    ("O Amigo Oculto ", "The Secret Santa "),
+    (" está chegando!", " is coming up!"),
+    (
+        "Esqueceu quem você tirou? Clique no botão abaixo para ver de novo:",
+        "Forgot who you drew? Click the button below to see again:",
+    ),
+    ("Você está criando o jogo: ", "You're creating the game: "),
+    (
+        "Digite o código abaixo na página de criação para continuar:",
+        "Enter the code below on the creation page to continue:",
+    ),
+    (
+        "Ou clique no botão abaixo para confirmar seu email e criar o jogo:",
+        "Or click the button below to confirm your email and create the game:",
+    ),
+    ("Criar Meu Jogo", "Create My Game"),
+    (
+        " Este código expira em 15 minutos.",
+        " This code expires in 15 minutes.",
+    ),
+    (
+        "Se você não solicitou este código, ignore este email.",
+        "If you didn't request this code, ignore this email.",
+    ),
+    (
+        "Código de Verificação - Amigo Oculto",
+        "Verification Code - Secret Santa",
+    ),
+    ("🔐 Recuperar Amigo Oculto", "🔐 Recover Secret Santa"),
+    (
+        "Alguém pediu para recuperar os links de amigo oculto deste email.",
+        "Someone asked to recover the Secret Santa links for this email.",
+    ),
+    (
+        "Digite o código abaixo na página de revelação para ver seus sorteios:",
+        "Enter the code below on the reveal page to see your draws:",
+    ),
+    ("Recuperar Amigo Oculto", "Recover Secret Santa"),
+    ("🎉 Jogo Criado!", "🎉 Game Created!"),
+    (
+        "Parabéns! Seu jogo foi criado com sucesso!",
+        "Congratulations! Your game was created successfully!",
+    ),
+    ("Agora você pode:", "Now you can:"),
+    ("Adicionar participantes", "Add participants"),
+    ("Realizar o sorteio", "Run the draw"),
+    (
+        "Acompanhar quem já visualizou",
+        "Track who has already viewed",
+    ),
+    ("Reenviar emails se necessário", "Resend emails if needed"),
+    (
+        " Guarde este link! Você precisará dele para gerenciar seu jogo.",
+        " Keep this link! You'll need it to manage your game.",
+    ),
+    ("Acessar Painel de Administração", "Open Admin Dashboard"),
+    ("Jogo Criado - {}", "Game Created - {}"),
+    // Plain-text emails
+    (
+        "Acesso do Organizador - Amigo Oculto 🔑
+
+Recebemos um pedido de acesso aos jogos organizados por este email.
+
+Para entrar, acesse o link abaixo:
+{}
+
+⏱️ Este link expira em 15 minutos e só pode ser usado uma vez.
+
+Se você não solicitou este acesso, ignore este email.
+
+{}",
+        "Organizer Access - Secret Santa 🔑
+
+We received a request to access the games organized by this email.
+
+To sign in, open the link below:
+{}
+
+⏱️ This link expires in 15 minutes and can only be used once.
+
+If you didn't request this access, ignore this email.
+
+{}",
+    ),
+    (
+        "Para descobrir quem você tirou, acesse o link abaixo:",
+        "To find out who you drew, open the link below:",
+    ),
+    (
+        "Não quer mais receber lembretes e resumos? Descadastre-se: {}",
+        "Don't want reminders and summaries anymore? Unsubscribe: {}",
+    ),
+    (
+        "Parabéns! O sorteio foi realizado com sucesso! 🎉
+
+Amigo Oculto: {}
+📅 Data do evento: {}
+{}👥 Participantes: {}
+
+Todos os participantes receberam um email com o link para descobrir quem tiraram.
+
+Para acompanhar quem já visualizou seu amigo oculto, acesse:
+{}
+
+⚠️ Importante: Guarde este email para consultar o status do sorteio mais tarde.
+
+{}",
+        "Congratulations! The draw was completed successfully! 🎉
+
+Secret Santa: {}
+📅 Event date: {}
+{}👥 Participants: {}
+
+Every participant got an email with a link to find out who they drew.
+
+To see who has already viewed their Secret Santa, open:
+{}
+
+⚠️ Important: Keep this email to check the status of the draw later.
+
+{}",
+    ),
+    (
+        "O Amigo Oculto \"{}\" de {} já aconteceu! Veja como foi:
+
+👥 Participantes: {}
+👀 Viram quem tiraram: {} de {}
+🔁 Visualizações no total: {}
+📨 Emails reenviados: {}
+⏰ Lembretes enviados: {}
+
+{}Obrigado por usar o Amigo Oculto! Até a próxima! 🎁
+
+{}
+{}",
+        "The Secret Santa \"{}\" on {} is over! Here's how it went:
+
+👥 Participants: {}
+👀 Saw who they drew: {} of {}
+🔁 Total views: {}
+📨 Emails resent: {}
+⏰ Reminders sent: {}
+
+{}Thanks for using Secret Santa! See you next time! 🎁
+
+{}
+{}",
+    ),
+    (
+        "Veja como está o Amigo Oculto \"{}\" de {}:
+
+👥 Participantes: {}
+👀 Viram quem tiraram: {} de {}
+
+{}Você pode reenviar os emails pela página do jogo:
+{}
+
+{}
+{}",
+        "Here's how the Secret Santa \"{}\" on {} is going:
+
+👥 Participants: {}
+👀 Saw who they drew: {} of {}
+
+{}You can resend their emails from the game page:
+{}
+
+{}
+{}",
+    ),
+    (
+        "⚠️ Amigo Oculto: {}
+
+{}
+
+Confira o endereço com {} e corrija-o na página do jogo para reenviar o link de revelação:
+{}
+
+{}",
+        "⚠️ Secret Santa: {}
+
+{}
+
+Check the address with {} and fix it on the game page to resend the reveal link:
+{}
+
+{}",
+    ),
+    (
+        "Olá {}!
+
+Você foi convidado para participar do Amigo Oculto \"{}\"!
+
+📅 Data do evento: {}
+{}
+{}Para descobrir quem você tirou, acesse o link abaixo:
+{}
+
+Guarde este email para consultar seu amigo oculto mais tarde se necessário.
+
+{}",
+        "Hi {}!
+
+You've been invited to the Secret Santa \"{}\"!
+
+📅 Event date: {}
+{}
+{}To find out who you drew, open the link below:
+{}
+
+Keep this email to check your Secret Santa later if needed.
+
+{}",
+    ),
+    (
+        "Olá {}!
+
+O sorteio do Amigo Oculto \"{}\" já foi feito, mas você ainda não viu quem tirou.
+
+📅 Data do evento: {}
+
+Para descobrir quem você tirou, acesse o link abaixo:
+{}
+
+{}
+{}",
+        "Hi {}!
+
+The draw for the Secret Santa \"{}\" is done, but you haven't seen who you drew yet.
+
+📅 Event date: {}
+
+To find out who you drew, open the link below:
+{}
+
+{}
+{}",
+    ),
+    (
+        "Olá {}!
+
+O Amigo Oculto \"{}\" está chegando!
+
+📅 Data do evento: {}
+{}
+{}Esqueceu quem você tirou? Acesse o link abaixo para ver de novo:
+{}
+
+{}
+{}",
+        "Hi {}!
+
+The Secret Santa \"{}\" is coming up!
+
+📅 Event date: {}
+{}
+{}Forgot who you drew? Open the link below to see again:
+{}
+
+{}
+{}",
+    ),
+    (
+        "Código de Verificação - Amigo Oculto 🎁
+
+Você está criando o jogo: {}
+
+Seu código de verificação é:
+
+{}
+
+⏱️ Este código expira em 15 minutos.
+
+Digite este código na página de criação do jogo para continuar, ou confirme seu email e crie o jogo direto por este link:
+
+{}
+
+Se você não solicitou este código, ignore este email.
+
+{}",
+        "Verification Code - Secret Santa 🎁
+
+You're creating the game: {}
+
+Your verification code is:
+
+{}
+
+⏱️ This code expires in 15 minutes.
+
+Enter this code on the game creation page to continue, or confirm your email and create the game right away with this link:
+
+{}
+
+If you didn't request this code, ignore this email.
+
+{}",
+    ),
+    (
+        "Recuperar Amigo Oculto 🎁
+
+Alguém pediu para recuperar os links de amigo oculto deste email.
+
+Seu código é:
+
+{}
+
+⏱️ Este código expira em 15 minutos.
+
+Digite este código na página de revelação para ver seus sorteios.
+
+Se você não solicitou este código, ignore este email.
+
+{}",
+        "Recover Secret Santa 🎁
+
+Someone asked to recover the Secret Santa links for this email.
+
+Your code is:
+
+{}
+
+⏱️ This code expires in 15 minutes.
+
+Enter this code on the reveal page to see your draws.
+
+If you didn't request this code, ignore this email.
+
+{}",
+    ),
+    (
+        "Seu jogo foi criado com sucesso! 🎉
+
+Amigo Oculto: {}
+📅 Data do evento: {}
+
+Agora você pode adicionar participantes e realizar o sorteio.
+
+Acesse o painel de administração:
+{}
+
+⚠️ Importante: Guarde este link para gerenciar seu jogo. Você precisará dele para:
+  • Adicionar participantes
+  • Realizar o sorteio
+  • Acompanhar quem já visualizou
+  • Reenviar emails
+
+{}",
+        "Your game was created successfully! 🎉
+
+Secret Santa: {}
+📅 Event date: {}
+
+Now you can add participants and run the draw.
+
+Open the admin dashboard:
+{}
+
+⚠️ Important: Keep this link to manage your game. You'll need it to:
+  • Add participants
+  • Run the draw
+  • Track who has already viewed
+  • Resend emails
+
+{}",
+    ),
+    // API messages
+    ("A data do evento deve ser nos próximos 5 anos", "The event date must be within the next 5 years"),
+    (
+        "A data do evento não pode estar no passado",
+        "The event date can't be in the past",
+    ),
+    (
+        "A lista de participantes está trancada. Destranque-a para adicionar participantes.",
+        "The participant list is locked. Unlock it to add participants.",
+    ),
+    (
+        "A mensagem deve ter no máximo {} caracteres",
+        "The message must be at most {} characters",
+    ),
+    ("A retenção dos jogos deve ser de {} a {} dias", "Games must be kept for {} to {} days"),
+    (
+        "A verificação em duas etapas já está ativada",
+        "Two-step verification is already enabled",
+    ),
+    (
+        "A verificação em duas etapas não está ativada",
+        "Two-step verification isn't enabled",
+    ),
+    (
+        "Ainda não chegou a hora da revelação",
+        "It's not time for the reveal yet",
+    ),
+    ("Arquivo não encontrado", "File not found"),
+    ("Backups não estão configurados", "Backups aren't configured"),
+    ("Banimento não encontrado", "Ban not found"),
+    ("Banimento removido", "Ban lifted"),
+    ("Campo obrigatório", "Required field"),
+    (
+        "Código de verificação incorreto",
+        "Incorrect verification code",
+    ),
+    (
+        "Código expirado. Solicite um novo código.",
+        "Code expired. Request a new code.",
+    ),
+    (
+        "Código incorreto. {} tentativas restantes.",
+        "Incorrect code. {} attempts left.",
+    ),
+    ("Denúncia não encontrada", "Report not found"),
+    (
+        "Denúncia recebida. Obrigado por nos avisar.",
+        "Report received. Thanks for letting us know.",
+    ),
+    ("Denúncia resolvida", "Report resolved"),
+    (
+        "Descreva o motivo da denúncia",
+        "Describe the reason for the report",
+    ),
+    ("Digite \"{}\" para confirmar", "Type \"{}\" to confirm"),
+    ("Email de boas-vindas reenviado com sucesso", "Welcome email resent successfully"),
+    ("Email inválido", "Invalid email"),
+    ("Email reenviado para {}", "Email resent to {}"),
+    (
+        "Emails reenviados: {} enviados, {} falharam",
+        "Emails resent: {} sent, {} failed",
+    ),
+    ("Envio de emails não encontrado", "Email sending not found"),
+    (
+        "Escolha no máximo {} lembretes antes do evento",
+        "Choose at most {} reminders before the event",
+    ),
+    (
+        "Erro ao enviar email de acesso",
+        "Error sending the access email",
+    ),
+    (
+        "Erro ao enviar email de recuperação",
+        "Error sending the recovery email",
+    ),
+    (
+        "Erro ao enviar email de verificação",
+        "Error sending the verification email",
+    ),
+    (
+        "Erro de sintaxe no modelo, na linha {}",
+        "Syntax error in template, on line {}",
+    ),
+    ("Erro interno do servidor", "Internal server error"),
+    (
+        "Esta verificação já foi usada",
+        "This verification was already used",
+    ),
+    ("Este código já foi usado", "This code was already used"),
+    ("Este email ou domínio já está banido", "This email or domain is already banned"),
+    (
+        "Este jogo aceita apenas emails do domínio @{}",
+        "This game only accepts emails from the @{} domain",
+    ),
+    (
+        "Este jogo foi bloqueado pela administração: {}",
+        "This game was blocked by the administrators: {}",
+    ),
+    ("Este link de acesso é inválido, expirou ou já foi usado", "This login link is invalid, expired or was already used"),
+    ("Este link de confirmação é inválido, expirou ou já foi usado", "This confirmation link is invalid, expired or was already used"),
+    (
+        "Este link expirou, pois a data do evento já passou.",
+        "This link has expired because the event date has passed.",
+    ),
+    (
+        "Este link já foi usado. Peça um novo link ao organizador.",
+        "This link was already used. Ask the organizer for a new link.",
+    ),
+    ("Evento de email inválido: {}", "Invalid email event: {}"),
+    (
+        "Faça login para ver seus jogos",
+        "Sign in to see your games",
+    ),
+    ("Formato de autorização inválido", "Invalid authorization format"),
+    (
+        "Formato de imagem não suportado. Use PNG, JPEG ou WebP.",
+        "Unsupported image format. Use PNG, JPEG or WebP.",
+    ),
+    (
+        "Idempotency-Key inválida: use de 16 a 255 caracteres visíveis",
+        "Invalid Idempotency-Key: use 16 to 255 visible characters",
+    ),
+    (
+        "Idempotency-Key já usada com outra requisição",
+        "Idempotency-Key already used with a different request",
+    ),
+    (
+        "Informe o link recebido ou o código do jogo",
+        "Enter the link you received or the game code",
+    ),
+    ("Informe o nome do jogo", "Enter the game's name"),
+    ("Informe o nome do participante", "Enter the participant's name"),
+    ("Informe pelo menos um filtro", "Enter at least one filter"),
+    ("Informe um email ou domínio válido", "Enter a valid email or domain"),
+    ("JSON aninhado demais", "JSON nested too deeply"),
+    ("Jogo atualizado com sucesso", "Game updated successfully"),
+    ("Jogo bloqueado com sucesso", "Game blocked successfully"),
+    ("Jogo desbloqueado com sucesso", "Game unblocked successfully"),
+    ("Jogo excluído com sucesso", "Game deleted successfully"),
+    ("Jogo excluído não encontrado", "Deleted game not found"),
+    ("Jogo não encontrado", "Game not found"),
+    ("Jogo restaurado com sucesso", "Game restored successfully"),
+    (
+        "Limite de 3 reenvios em massa atingido.",
+        "Limit of 3 bulk resends reached.",
+    ),
+    (
+        "Limite de 3 reenvios para este participante atingido.",
+        "Limit of 3 resends for this participant reached.",
+    ),
+    (
+        "Limite máximo de {} participantes atingido",
+        "Maximum of {} participants reached",
+    ),
+    ("Link de descadastro inválido", "Invalid unsubscribe link"),
+    (
+        "Link inválido ou acesso não autorizado",
+        "Invalid link or unauthorized access",
+    ),
+    ("Modelo inválido", "Invalid template"),
+    (
+        "Modelos não aceitam filtros nem funções",
+        "Templates don't support filters or functions",
+    ),
+    (
+        "Muitas requisições. Tente novamente em alguns minutos.",
+        "Too many requests. Try again in a few minutes.",
+    ),
+    (
+        "Muitas tentativas de verificação. Tente novamente em 1 hora.",
+        "Too many verification attempts. Try again in 1 hour.",
+    ),
+    ("Nenhuma passkey cadastrada", "No passkeys registered"),
+    ("Nova senha deve ter pelo menos 8 caracteres", "The new password must have at least 8 characters"),
+    ("Nova senha não pode ser vazia", "The new password can't be empty"),
+    ("Não encontrado", "Not found"),
+    ("Não foi possível restaurar o backup: {}", "Couldn't restore the backup: {}"),
+    (
+        "Não foi possível verificar o CAPTCHA. Tente novamente.",
+        "Couldn't verify the CAPTCHA. Try again.",
+    ),
+    (
+        "Confirme que você não é um robô e tente novamente.",
+        "Confirm you're not a robot and try again.",
+    ),
+    (
+        "Emails temporários não são aceitos. Use um email que você continuará acessando.",
+        "Temporary emails aren't accepted. Use an email you'll keep having access to.",
+    ),
+    (
+        "Este email não pode ser usado para criar jogos.",
+        "This email can't be used to create games.",
+    ),
+    ("Novo link enviado para {}", "New link sent to {}"),
+    (
+        "Não é possível adicionar participantes após o sorteio já ter sido realizado",
+        "Participants can't be added after the draw",
+    ),
+    (
+        "Não é possível editar participante após ter visualizado o sorteio.",
+        "A participant can't be edited after they viewed the draw.",
+    ),
+    (
+        "Número máximo de tentativas excedido. Solicite um novo código.",
+        "Maximum number of attempts exceeded. Request a new code.",
+    ),
+    ("O assunto deve ter no máximo {} caracteres", "The subject must have at most {} characters"),
+    (
+        "O cartão não está disponível para links de uso único.",
+        "The card isn't available for single-use links.",
+    ),
+    (
+        "O modelo de email deve ter no máximo {} caracteres",
+        "The email template must be at most {} characters",
+    ),
+    (
+        "O local do evento deve ter no máximo {} caracteres",
+        "The event location must be at most {} characters",
+    ),
+    (
+        "O motivo da denúncia deve ter no máximo {} caracteres",
+        "The report reason must be at most {} characters",
+    ),
+    ("O motivo deve ter no máximo {} caracteres", "The reason must have at most {} characters"),
+    ("O motivo do bloqueio é obrigatório", "The reason for blocking is required"),
+    (
+        "O nome da passkey deve ter entre 1 e {} caracteres",
+        "The passkey name must be between 1 and {} characters",
+    ),
+    ("O nome deve ter no máximo {} caracteres", "The name must be at most {} characters long"),
+    (
+        "Os emails do sorteio ainda estão sendo enviados",
+        "The draw emails are still being sent",
+    ),
+    (
+        "Os lembretes devem ser enviados de 1 a {} dias antes do evento",
+        "Reminders must be sent 1 to {} days before the event",
+    ),
+    (
+        "O sorteio ainda não foi realizado",
+        "The draw hasn't happened yet",
+    ),
+    (
+        "O sorteio ainda não foi realizado. Aguarde o organizador finalizar o sorteio.",
+        "The draw hasn't happened yet. Wait for the organizer to run the draw.",
+    ),
+    (
+        "O sorteio ainda não foi realizado. Realize o sorteio antes de reenviar emails.",
+        "The draw hasn't happened yet. Run the draw before resending emails.",
+    ),
+    (
+        "O sorteio ainda não foi realizado.",
+        "The draw hasn't happened yet.",
+    ),
+    (
+        "O sorteio já foi realizado para este jogo",
+        "The draw has already been done for this game",
+    ),
+    (
+        "Participante atualizado com sucesso",
+        "Participant updated successfully",
+    ),
+    ("Participante não encontrado", "Participant not found"),
+    (
+        "Participante não pertence a este jogo",
+        "Participant doesn't belong to this game",
+    ),
+    (
+        "Participante sorteado não encontrado",
+        "Drawn participant not found",
+    ),
+    (
+        "Passkey inválida. Tente novamente.",
+        "Invalid passkey. Please try again.",
+    ),
+    ("Passkey não encontrada", "Passkey not found"),
+    ("Passkey removida", "Passkey removed"),
+    (
+        "Passkeys não estão disponíveis neste endereço",
+        "Passkeys aren't available at this address",
+    ),
+    (
+        "Precisa de pelo menos 2 participantes para fazer o sorteio",
+        "The draw needs at least 2 participants",
+    ),
+    (
+        "Pronto! Você não receberá mais lembretes e resumos do Amigo Oculto.",
+        "Done! You won't get Secret Santa reminders and summaries anymore.",
+    ),
+    ("Recuperação não encontrada", "Recovery not found"),
+    ("Requisição grande demais", "Request too large"),
+    ("Requisição inválida", "Invalid request"),
+    (
+        "Resposta armazenada ilegível",
+        "Unreadable stored response",
+    ),
+    (
+        "Revele seu amigo oculto antes de baixar o cartão.",
+        "Reveal your Secret Santa before downloading the card.",
+    ),
+    (
+        "Se houver jogos organizados por este email, você receberá um link de acesso.",
+        "If there are games organized by this email, you'll get an access link.",
+    ),
+    ("Senha alterada com sucesso", "Password changed successfully"),
+    ("Senha atual incorreta", "Incorrect current password"),
+    ("Senha incorreta", "Incorrect password"),
+    ("Sessão encerrada com sucesso", "Signed out successfully"),
+    ("Sessão inválida ou expirada", "Invalid or expired session"),
+    ("Sessão não encontrada", "Session not found"),
+    (
+        "Sorteio ainda não foi realizado",
+        "The draw hasn't happened yet",
+    ),
+    ("Sorteio desfeito com sucesso", "Draw undone successfully"),
+    (
+        "Sorteio realizado com sucesso! Os emails estão sendo enviados.",
+        "Draw completed! The emails are being sent.",
+    ),
+    (
+        "Só é possível reenviar email para este participante uma vez por hora.",
+        "The email to this participant can only be resent once an hour.",
+    ),
+    (
+        "Só é possível reenviar emails em massa uma vez por hora.",
+        "Emails can only be resent in bulk once an hour.",
+    ),
+    ("Token de acesso ausente", "Missing access token"),
+    ("Token inválido", "Invalid token"),
+    (
+        "Uma requisição com esta Idempotency-Key ainda está em andamento",
+        "A request with this Idempotency-Key is still in progress",
+    ),
+    ("Valor inválido", "Invalid value"),
+    (
+        "Variável desconhecida no modelo: {}",
+        "Unknown variable in template: {}",
+    ),
+    (
+        "Verificação em duas etapas desativada",
+        "Two-step verification disabled",
+    ),
+    ("Verificação não encontrada", "Verification not found"),
+];
@@ -0,0 +1,1066 @@
+//! Spanish translations, keyed by the Portuguese text.
+
+pub(super) const CATALOG: &[(&str, &str)] = &[
+    // Email subjects
+    (
+        "⏰ Lembrete:",
+        "⏰ Recordatorio:",
+    ),
+    ("📅 Está chegando:", "📅 Se acerca:"),
+    (
+        "✅ Sorteio Realizado:",
+        "✅ Sorteo Realizado:",
+    ),
+    (
+        "🎉 Resumo:",
+        "🎉 Resumen:",
+    ),
+    ("📊 Resumo semanal:", "📊 Resumen semanal:"),
+    (
+        "⚠️ Email não entregue:",
+        "⚠️ Email no entregado:",
+    ),
+    (
+        "🔐 Código de Verificação",
+        "🔐 Código de Verificación",
+    ),
+    (
+        "🔐 Código para recuperar seu Amigo Oculto",
+        "🔐 Código para recuperar tu Amigo Secreto",
+    ),
+    (
+        "🔑 Acesso do Organizador",
+        "🔑 Acceso del Organizador",
+    ),
+    (
+        "🎉 Jogo Criado:",
+        "🎉 Juego Creado:",
+    ),
+    ("📢 Aviso:", "📢 Aviso:"),
+
+    // Shared email components
+    (
+        "Local:",
+        "Lugar:",
+    ),
+    (
+        "ver no mapa",
+        "ver en el mapa",
+    ),
+    (
+        "Se o botão não funcionar, copie e cole este link no seu navegador:",
+        "Si el botón no funciona, copia y pega este enlace en tu navegador:",
+    ),
+    (
+        "Ou aponte a câmera do celular para o código:",
+        "O apunta la cámara del celular al código:",
+    ),
+    (
+        "Amigo Oculto - Sistema de Sorteio",
+        "Amigo Secreto - Sistema de Sorteo",
+    ),
+    (
+        "Não quer mais receber lembretes e resumos? ",
+        "¿No quieres recibir más recordatorios y resúmenes? ",
+    ),
+    (
+        "Descadastre-se",
+        "Darse de baja",
+    ),
+    (
+        "Amigo Oculto",
+        "Amigo Secreto",
+    ),
+
+    // Printable card
+    ("Para:", "Para:"),
+    ("De: seu amigo oculto", "De: tu amigo secreto"),
+    // HTML emails
+    (
+        "Recebemos um pedido de acesso aos jogos organizados por este email.",
+        "Recibimos una solicitud de acceso a los juegos organizados por este email.",
+    ),
+    (
+        "Entrar",
+        "Entrar",
+    ),
+    (
+        "⏱️ Atenção:",
+        "⏱️ Atención:",
+    ),
+    (
+        " Este link expira em 15 minutos e só pode ser usado uma vez.",
+        " Este enlace caduca en 15 minutos y solo se puede usar una vez.",
+    ),
+    (
+        "Se você não solicitou este acesso, ignore este email.",
+        "Si no solicitaste este acceso, ignora este email.",
+    ),
+    (
+        "Acesso do Organizador - Amigo Oculto",
+        "Acceso del Organizador - Amigo Secreto",
+    ),
+    (
+        "✅ Sorteio Realizado!",
+        "✅ ¡Sorteo Realizado!",
+    ),
+    (
+        "Parabéns! O sorteio foi realizado com sucesso! 🎉",
+        "¡Felicidades! ¡El sorteo se realizó con éxito! 🎉",
+    ),
+    (
+        "Data do evento:",
+        "Fecha del evento:",
+    ),
+    (
+        "Participantes:",
+        "Participantes:",
+    ),
+    ("Participante", "Participante"),
+    (
+        "Todos os participantes receberam um email com o link para descobrir quem tiraram.",
+        "Todos los participantes recibieron un email con el enlace para descubrir a quién les tocó.",
+    ),
+    (
+        "⚠️ Importante:",
+        "⚠️ Importante:",
+    ),
+    (
+        " Guarde este email! Use o link abaixo para acompanhar quem já visualizou seu amigo oculto.",
+        " ¡Guarda este email! Usa el enlace de abajo para ver quién ya vio a su amigo secreto.",
+    ),
+    (
+        "Acompanhar Status",
+        "Ver Estado",
+    ),
+    (
+        "Sorteio Realizado - {}",
+        "Sorteo Realizado - {}",
+    ),
+    (
+        "🎉 Resumo do Amigo Oculto",
+        "🎉 Resumen del Amigo Secreto",
+    ),
+    (
+        "O Amigo Oculto de {} já aconteceu! Veja como foi:",
+        "¡El Amigo Secreto del {} ya pasó! Así fue:",
+    ),
+    (
+        "Viram quem tiraram:",
+        "Vieron a quién les tocó:",
+    ),
+    (
+        "de",
+        "de",
+    ),
+    (
+        "Visualizações no total:",
+        "Visualizaciones en total:",
+    ),
+    (
+        "Emails reenviados:",
+        "Emails reenviados:",
+    ),
+    (
+        "Lembretes enviados:",
+        "Recordatorios enviados:",
+    ),
+    (
+        "Nunca abriram o link de revelação:",
+        "Nunca abrieron el enlace de revelación:",
+    ),
+    (
+        "Obrigado por usar o Amigo Oculto! Até a próxima! 🎁",
+        "¡Gracias por usar Amigo Secreto! ¡Hasta la próxima! 🎁",
+    ),
+    (
+        "Resumo - {}",
+        "Resumen - {}",
+    ),
+    ("📊 Resumo Semanal", "📊 Resumen Semanal"),
+    (
+        "Veja como está o Amigo Oculto de {}:",
+        "Así va el Amigo Secreto del {}:",
+    ),
+    (
+        "Ainda não viram quem tiraram:",
+        "Todavía no vieron a quién les tocó:",
+    ),
+    (
+        "Você pode reenviar os emails pela página do jogo.",
+        "Puedes reenviar sus emails desde la página del juego.",
+    ),
+    ("Resumo Semanal - {}", "Resumen Semanal - {}"),
+    (
+        "📢 Aviso do Amigo Oculto",
+        "📢 Aviso del Amigo Secreto",
+    ),
+    (
+        "⚠️ Email Não Entregue",
+        "⚠️ Email No Entregado",
+    ),
+    (
+        "{} marcou o email do Amigo Oculto como spam.",
+        "{} marcó el email del Amigo Secreto como spam.",
+    ),
+    (
+        "O email para {} não pôde ser entregue. O endereço pode estar errado ou não existir mais.",
+        "El email para {} no se pudo entregar. La dirección puede estar mal o ya no existir.",
+    ),
+    (
+        "Confira o endereço com {} e corrija-o na página do jogo para reenviar o link de revelação.",
+        "Confirma la dirección con {} y corrígela en la página del juego para reenviar el enlace de revelación.",
+    ),
+    (
+        "Corrigir Email",
+        "Corregir Email",
+    ),
+    (
+        "Email Não Entregue - {}",
+        "Email No Entregado - {}",
+    ),
+    (
+        "Olá ",
+        "¡Hola ",
+    ),
+    (
+        "Você foi convidado para participar do Amigo Oculto ",
+        "¡Te invitaron a participar del Amigo Secreto ",
+    ),
+    (
+        "Mensagem do organizador:",
+        "Mensaje del organizador:",
+    ),
+    (
+        "Clique no botão abaixo para descobrir quem você tirou:",
+        "Haz clic en el botón de abajo para descubrir a quién te tocó:",
+    ),
+    (
+        "Ver Meu Amigo Oculto",
+        "Ver Mi Amigo Secreto",
+    ),
+    (
+        "Dica:",
+        "Consejo:",
+    ),
+    (
+        " Guarde este email! Você pode precisar dele para consultar quem você tirou mais tarde.",
+        " ¡Guarda este email! Puedes necesitarlo para consultar a quién te tocó más tarde.",
+    ),
+    (
+        "Amigo Oculto - {}",
+        "Amigo Secreto - {}",
+    ),
+    (
+        "⏰ Lembrete",
+        "⏰ Recordatorio",
+    ),
+    (
+        "O sorteio do Amigo Oculto ",
+        "El sorteo del Amigo Secreto ",
+    ),
+    (
+        " já foi feito, mas você ainda não viu quem tirou.",
+        " ya se hizo, pero todavía no viste a quién te tocó.",
+    ),
+    (
+        "Lembrete - {}",
+        "Recordatorio - {}",
+    ),
+    ("📅 O evento está chegando", "📅 El evento se acerca"),
+    ("O Amigo Oculto ", "¡El Amigo Secreto "),
+    (" está chegando!", " se acerca!"),
+    (
+        "Esqueceu quem você tirou? Clique no botão abaixo para ver de novo:",
+        "¿Olvidaste a quién te tocó? Haz clic en el botón de abajo para verlo de nuevo:",
+    ),
+    (
+        "Você está criando o jogo: ",
+        "Estás creando el juego: ",
+    ),
+    (
+        "Digite o código abaixo na página de criação para continuar:",
+        "Ingresa el código de abajo en la página de creación para continuar:",
+    ),
+    (
+        "Ou clique no botão abaixo para confirmar seu email e criar o jogo:",
+        "O haz clic en el botón de abajo para confirmar tu email y crear el juego:",
+    ),
+    (
+        "Criar Meu Jogo",
+        "Crear Mi Juego",
+    ),
+    (
+        " Este código expira em 15 minutos.",
+        " Este código caduca en 15 minutos.",
+    ),
+    (
+        "Se você não solicitou este código, ignore este email.",
+        "Si no solicitaste este código, ignora este email.",
+    ),
+    (
+        "Código de Verificação - Amigo Oculto",
+        "Código de Verificación - Amigo Secreto",
+    ),
+    (
+        "🔐 Recuperar Amigo Oculto",
+        "🔐 Recuperar Amigo Secreto",
+    ),
+    (
+        "Alguém pediu para recuperar os links de amigo oculto deste email.",
+        "Alguien pidió recuperar los enlaces de amigo secreto de este email.",
+    ),
+    (
+        "Digite o código abaixo na página de revelação para ver seus sorteios:",
+        "Ingresa el código de abajo en la página de revelación para ver tus sorteos:",
+    ),
+    (
+        "Recuperar Amigo Oculto",
+        "Recuperar Amigo Secreto",
+    ),
+    (
+        "🎉 Jogo Criado!",
+        "🎉 ¡Juego Creado!",
+    ),
+    (
+        "Parabéns! Seu jogo foi criado com sucesso!",
+        "¡Felicidades! ¡Tu juego se creó con éxito!",
+    ),
+    (
+        "Agora você pode:",
+        "Ahora puedes:",
+    ),
+    (
+        "Adicionar participantes",
+        "Agregar participantes",
+    ),
+    (
+        "Realizar o sorteio",
+        "Realizar el sorteo",
+    ),
+    (
+        "Acompanhar quem já visualizou",
+        "Ver quién ya lo vio",
+    ),
+    (
+        "Reenviar emails se necessário",
+        "Reenviar emails si es necesario",
+    ),
+    (
+        " Guarde este link! Você precisará dele para gerenciar seu jogo.",
+        " ¡Guarda este enlace! Lo necesitarás para administrar tu juego.",
+    ),
+    (
+        "Acessar Painel de Administração",
+        "Abrir Panel de Administración",
+    ),
+    (
+        "Jogo Criado - {}",
+        "Juego Creado - {}",
+    ),
+
+    // Plain-text emails
+    (
+        "Acesso do Organizador - Amigo Oculto 🔑
+
+Recebemos um pedido de acesso aos jogos organizados por este email.
+
+Para entrar, acesse o link abaixo:
+{}
+
+⏱️ Este link expira em 15 minutos e só pode ser usado uma vez.
+
+Se você não solicitou este acesso, ignore este email.
+
+{}",
+        "Acceso del Organizador - Amigo Secreto 🔑
+
+Recibimos una solicitud de acceso a los juegos organizados por este email.
+
+Para entrar, abre el enlace de abajo:
+{}
+
+⏱️ Este enlace caduca en 15 minutos y solo se puede usar una vez.
+
+Si no solicitaste este acceso, ignora este email.
+
+{}",
+    ),
+    (
+        "Para descobrir quem você tirou, acesse o link abaixo:",
+        "Para descubrir a quién te tocó, abre el enlace de abajo:",
+    ),
+    (
+        "Não quer mais receber lembretes e resumos? Descadastre-se: {}",
+        "¿No quieres recibir más recordatorios y resúmenes? Darse de baja: {}",
+    ),
+    (
+        "Parabéns! O sorteio foi realizado com sucesso! 🎉
+
+Amigo Oculto: {}
+📅 Data do evento: {}
+{}👥 Participantes: {}
+
+Todos os participantes receberam um email com o link para descobrir quem tiraram.
+
+Para acompanhar quem já visualizou seu amigo oculto, acesse:
+{}
+
+⚠️ Importante: Guarde este email para consultar o status do sorteio mais tarde.
+
+{}",
+        "¡Felicidades! ¡El sorteo se realizó con éxito! 🎉
+
+Amigo Secreto: {}
+📅 Fecha del evento: {}
+{}👥 Participantes: {}
+
+Todos los participantes recibieron un email con el enlace para descubrir a quién les tocó.
+
+Para ver quién ya vio a su amigo secreto, abre:
+{}
+
+⚠️ Importante: Guarda este email para consultar el estado del sorteo más tarde.
+
+{}",
+    ),
+    (
+        "O Amigo Oculto \"{}\" de {} já aconteceu! Veja como foi:
+
+👥 Participantes: {}
+👀 Viram quem tiraram: {} de {}
+🔁 Visualizações no total: {}
+📨 Emails reenviados: {}
+⏰ Lembretes enviados: {}
+
+{}Obrigado por usar o Amigo Oculto! Até a próxima! 🎁
+
+{}
+{}",
+        "¡El Amigo Secreto \"{}\" del {} ya pasó! Así fue:
+
+👥 Participantes: {}
+👀 Vieron a quién les tocó: {} de {}
+🔁 Visualizaciones en total: {}
+📨 Emails reenviados: {}
+⏰ Recordatorios enviados: {}
+
+{}¡Gracias por usar Amigo Secreto! ¡Hasta la próxima! 🎁
+
+{}
+{}",
+    ),
+    (
+        "Veja como está o Amigo Oculto \"{}\" de {}:
+
+👥 Participantes: {}
+👀 Viram quem tiraram: {} de {}
+
+{}Você pode reenviar os emails pela página do jogo:
+{}
+
+{}
+{}",
+        "Así va el Amigo Secreto \"{}\" del {}:
+
+👥 Participantes: {}
+👀 Vieron a quién les tocó: {} de {}
+
+{}Puedes reenviar sus emails desde la página del juego:
+{}
+
+{}
+{}",
+    ),
+    (
+        "⚠️ Amigo Oculto: {}
+
+{}
+
+Confira o endereço com {} e corrija-o na página do jogo para reenviar o link de revelação:
+{}
+
+{}",
+        "⚠️ Amigo Secreto: {}
+
+{}
+
+Confirma la dirección con {} y corrígela en la página del juego para reenviar el enlace de revelación:
+{}
+
+{}",
+    ),
+    (
+        "Olá {}!
+
+Você foi convidado para participar do Amigo Oculto \"{}\"!
+
+📅 Data do evento: {}
+{}
+{}Para descobrir quem você tirou, acesse o link abaixo:
+{}
+
+Guarde este email para consultar seu amigo oculto mais tarde se necessário.
+
+{}",
+        "¡Hola {}!
+
+¡Te invitaron a participar del Amigo Secreto \"{}\"!
+
+📅 Fecha del evento: {}
+{}
+{}Para descubrir a quién te tocó, abre el enlace de abajo:
+{}
+
+Guarda este email para consultar a tu amigo secreto más tarde si es necesario.
+
+{}",
+    ),
+    (
+        "Olá {}!
+
+O sorteio do Amigo Oculto \"{}\" já foi feito, mas você ainda não viu quem tirou.
+
+📅 Data do evento: {}
+
+Para descobrir quem você tirou, acesse o link abaixo:
+{}
+
+{}
+{}",
+        "¡Hola {}!
+
+El sorteo del Amigo Secreto \"{}\" ya se hizo, pero todavía no viste a quién te tocó.
+
+📅 Fecha del evento: {}
+
+Para descubrir a quién te tocó, abre el enlace de abajo:
+{}
+
+{}
+{}",
+    ),
+    (
+        "Olá {}!
+
+O Amigo Oculto \"{}\" está chegando!
+
+📅 Data do evento: {}
+{}
+{}Esqueceu quem você tirou? Acesse o link abaixo para ver de novo:
+{}
+
+{}
+{}",
+        "¡Hola {}!
+
+¡El Amigo Secreto \"{}\" se acerca!
+
+📅 Fecha del evento: {}
+{}
+{}¿Olvidaste a quién te tocó? Abre el enlace de abajo para verlo de nuevo:
+{}
+
+{}
+{}",
+    ),
+    (
+        "Código de Verificação - Amigo Oculto 🎁
+
+Você está criando o jogo: {}
+
+Seu código de verificação é:
+
+{}
+
+⏱️ Este código expira em 15 minutos.
+
+Digite este código na página de criação do jogo para continuar, ou confirme seu email e crie o jogo direto por este link:
+
+{}
+
+Se você não solicitou este código, ignore este email.
+
+{}",
+        "Código de Verificación - Amigo Secreto 🎁
+
+Estás creando el juego: {}
+
+Tu código de verificación es:
+
+{}
+
+⏱️ Este código caduca en 15 minutos.
+
+Ingresa este código en la página de creación del juego para continuar, o confirma tu email y crea el juego directamente con este enlace:
+
+{}
+
+Si no solicitaste este código, ignora este email.
+
+{}",
+    ),
+    (
+        "Recuperar Amigo Oculto 🎁
+
+Alguém pediu para recuperar os links de amigo oculto deste email.
+
+Seu código é:
+
+{}
+
+⏱️ Este código expira em 15 minutos.
+
+Digite este código na página de revelação para ver seus sorteios.
+
+Se você não solicitou este código, ignore este email.
+
+{}",
+        "Recuperar Amigo Secreto 🎁
+
+Alguien pidió recuperar los enlaces de amigo secreto de este email.
+
+Tu código es:
+
+{}
+
+⏱️ Este código caduca en 15 minutos.
+
+Ingresa este código en la página de revelación para ver tus sorteos.
+
+Si no solicitaste este código, ignora este email.
+
+{}",
+    ),
+    (
+        "Seu jogo foi criado com sucesso! 🎉
+
+Amigo Oculto: {}
+📅 Data do evento: {}
+
+Agora você pode adicionar participantes e realizar o sorteio.
+
+Acesse o painel de administração:
+{}
+
+⚠️ Importante: Guarde este link para gerenciar seu jogo. Você precisará dele para:
+  • Adicionar participantes
+  • Realizar o sorteio
+  • Acompanhar quem já visualizou
+  • Reenviar emails
+
+{}",
+        "¡Tu juego se creó con éxito! 🎉
+
+Amigo Secreto: {}
+📅 Fecha del evento: {}
+
+Ahora puedes agregar participantes y realizar el sorteo.
+
+Abre el panel de administración:
+{}
+
+⚠️ Importante: Guarda este enlace para administrar tu juego. Lo necesitarás para:
+  • Agregar participantes
+  • Realizar el sorteo
+  • Ver quién ya lo vio
+  • Reenviar emails
+
+{}",
+    ),
+
+    // API messages
+    ("A data do evento deve ser nos próximos 5 anos", "La fecha del evento debe estar dentro de los próximos 5 años"),
+    (
+        "A data do evento não pode estar no passado",
+        "La fecha del evento no puede estar en el pasado",
+    ),
+    (
+        "A lista de participantes está trancada. Destranque-a para adicionar participantes.",
+        "La lista de participantes está cerrada. Ábrela para agregar participantes.",
+    ),
+    (
+        "A mensagem deve ter no máximo {} caracteres",
+        "El mensaje debe tener como máximo {} caracteres",
+    ),
+    ("A retenção dos jogos deve ser de {} a {} dias", "Los juegos deben conservarse de {} a {} días"),
+    (
+        "A verificação em duas etapas já está ativada",
+        "La verificación en dos pasos ya está activada",
+    ),
+    (
+        "A verificação em duas etapas não está ativada",
+        "La verificación en dos pasos no está activada",
+    ),
+    (
+        "Ainda não chegou a hora da revelação",
+        "Todavía no es hora de la revelación",
+    ),
+    (
+        "Arquivo não encontrado",
+        "Archivo no encontrado",
+    ),
+    ("Backups não estão configurados", "Las copias de seguridad no están configuradas"),
+    ("Banimento não encontrado", "Bloqueo de email no encontrado"),
+    ("Banimento removido", "Bloqueo de email retirado"),
+    ("Campo obrigatório", "Campo obligatorio"),
+    (
+        "Código de verificação incorreto",
+        "Código de verificación incorrecto",
+    ),
+    (
+        "Código expirado. Solicite um novo código.",
+        "Código caducado. Solicita un código nuevo.",
+    ),
+    (
+        "Código incorreto. {} tentativas restantes.",
+        "Código incorrecto. Quedan {} intentos.",
+    ),
+    (
+        "Denúncia não encontrada",
+        "Denuncia no encontrada",
+    ),
+    (
+        "Denúncia recebida. Obrigado por nos avisar.",
+        "Denuncia recibida. Gracias por avisarnos.",
+    ),
+    ("Denúncia resolvida", "Denuncia resuelta"),
+    (
+        "Descreva o motivo da denúncia",
+        "Describe el motivo de la denuncia",
+    ),
+    (
+        "Digite \"{}\" para confirmar",
+        "Escribe \"{}\" para confirmar",
+    ),
+    ("Email de boas-vindas reenviado com sucesso", "Email de bienvenida reenviado con éxito"),
+    ("Email inválido", "Email no válido"),
+    (
+        "Email reenviado para {}",
+        "Email reenviado a {}",
+    ),
+    (
+        "Emails reenviados: {} enviados, {} falharam",
+        "Emails reenviados: {} enviados, {} fallaron",
+    ),
+    ("Envio de emails não encontrado", "Envío de emails no encontrado"),
+    (
+        "Escolha no máximo {} lembretes antes do evento",
+        "Elige como máximo {} recordatorios antes del evento",
+    ),
+    (
+        "Erro ao enviar email de acesso",
+        "Error al enviar el email de acceso",
+    ),
+    (
+        "Erro ao enviar email de recuperação",
+        "Error al enviar el email de recuperación",
+    ),
+    (
+        "Erro ao enviar email de verificação",
+        "Error al enviar el email de verificación",
+    ),
+    (
+        "Erro de sintaxe no modelo, na linha {}",
+        "Error de sintaxis en la plantilla, en la línea {}",
+    ),
+    (
+        "Erro interno do servidor",
+        "Error interno del servidor",
+    ),
+    (
+        "Esta verificação já foi usada",
+        "Esta verificación ya se usó",
+    ),
+    (
+        "Este código já foi usado",
+        "Este código ya se usó",
+    ),
+    ("Este email ou domínio já está banido", "Este email o dominio ya está bloqueado"),
+    (
+        "Este jogo aceita apenas emails do domínio @{}",
+        "Este juego solo acepta emails del dominio @{}",
+    ),
+    (
+        "Este jogo foi bloqueado pela administração: {}",
+        "Este juego fue bloqueado por la administración: {}",
+    ),
+    ("Este link de acesso é inválido, expirou ou já foi usado", "Este enlace de acceso no es válido, expiró o ya fue usado"),
+    ("Este link de confirmação é inválido, expirou ou já foi usado", "Este enlace de confirmación no es válido, expiró o ya fue usado"),
+    (
+        "Este link expirou, pois a data do evento já passou.",
+        "Este enlace caducó porque la fecha del evento ya pasó.",
+    ),
+    (
+        "Este link já foi usado. Peça um novo link ao organizador.",
+        "Este enlace ya se usó. Pide un enlace nuevo al organizador.",
+    ),
+    ("Evento de email inválido: {}", "Evento de email no válido: {}"),
+    (
+        "Faça login para ver seus jogos",
+        "Inicia sesión para ver tus juegos",
+    ),
+    ("Formato de autorização inválido", "Formato de autorización no válido"),
+    (
+        "Formato de imagem não suportado. Use PNG, JPEG ou WebP.",
+        "Formato de imagen no compatible. Usa PNG, JPEG o WebP.",
+    ),
+    (
+        "Idempotency-Key inválida: use de 16 a 255 caracteres visíveis",
+        "Idempotency-Key no válida: usa de 16 a 255 caracteres visibles",
+    ),
+    (
+        "Idempotency-Key já usada com outra requisição",
+        "Idempotency-Key ya usada con otra solicitud",
+    ),
+    (
+        "Informe o link recebido ou o código do jogo",
+        "Ingresa el enlace que recibiste o el código del juego",
+    ),
+    ("Informe o nome do jogo", "Indica el nombre del juego"),
+    ("Informe o nome do participante", "Indica el nombre del participante"),
+    ("Informe pelo menos um filtro", "Indica al menos un filtro"),
+    ("Informe um email ou domínio válido", "Ingresa un email o dominio válido"),
+    ("JSON aninhado demais", "JSON anidado en exceso"),
+    (
+        "Jogo atualizado com sucesso",
+        "Juego actualizado con éxito",
+    ),
+    ("Jogo bloqueado com sucesso", "Juego bloqueado con éxito"),
+    ("Jogo desbloqueado com sucesso", "Juego desbloqueado con éxito"),
+    (
+        "Jogo excluído com sucesso",
+        "Juego eliminado con éxito",
+    ),
+    ("Jogo excluído não encontrado", "Juego eliminado no encontrado"),
+    (
+        "Jogo não encontrado",
+        "Juego no encontrado",
+    ),
+    ("Jogo restaurado com sucesso", "Juego restaurado con éxito"),
+    (
+        "Limite de 3 reenvios em massa atingido.",
+        "Se alcanzó el límite de 3 reenvíos masivos.",
+    ),
+    (
+        "Limite de 3 reenvios para este participante atingido.",
+        "Se alcanzó el límite de 3 reenvíos para este participante.",
+    ),
+    (
+        "Limite máximo de {} participantes atingido",
+        "Se alcanzó el máximo de {} participantes",
+    ),
+    (
+        "Link de descadastro inválido",
+        "Enlace para darse de baja inválido",
+    ),
+    (
+        "Link inválido ou acesso não autorizado",
+        "Enlace inválido o acceso no autorizado",
+    ),
+    ("Modelo inválido", "Plantilla no válida"),
+    (
+        "Modelos não aceitam filtros nem funções",
+        "Las plantillas no admiten filtros ni funciones",
+    ),
+    (
+        "Muitas requisições. Tente novamente em alguns minutos.",
+        "Demasiadas solicitudes. Inténtalo de nuevo en unos minutos.",
+    ),
+    (
+        "Muitas tentativas de verificação. Tente novamente em 1 hora.",
+        "Demasiados intentos de verificación. Inténtalo de nuevo en 1 hora.",
+    ),
+    (
+        "Nenhuma passkey cadastrada",
+        "No hay llaves de acceso registradas",
+    ),
+    ("Nova senha deve ter pelo menos 8 caracteres", "La nueva contraseña debe tener al menos 8 caracteres"),
+    ("Nova senha não pode ser vazia", "La nueva contraseña no puede estar vacía"),
+    ("Não encontrado", "No encontrado"),
+    ("Não foi possível restaurar o backup: {}", "No se pudo restaurar la copia de seguridad: {}"),
+    (
+        "Não foi possível verificar o CAPTCHA. Tente novamente.",
+        "No se pudo verificar el CAPTCHA. Inténtalo de nuevo.",
+    ),
+    (
+        "Confirme que você não é um robô e tente novamente.",
+        "Confirma que no eres un robot e inténtalo de nuevo.",
+    ),
+    (
+        "Emails temporários não são aceitos. Use um email que você continuará acessando.",
+        "No se aceptan emails temporales. Usa un email al que seguirás teniendo acceso.",
+    ),
+    (
+        "Este email não pode ser usado para criar jogos.",
+        "Este email no se puede usar para crear juegos.",
+    ),
+    (
+        "Novo link enviado para {}",
+        "Enlace nuevo enviado a {}",
+    ),
+    (
+        "Não é possível adicionar participantes após o sorteio já ter sido realizado",
+        "No se pueden agregar participantes después del sorteo",
+    ),
+    (
+        "Não é possível editar participante após ter visualizado o sorteio.",
+        "No se puede editar un participante después de que vio el sorteo.",
+    ),
+    (
+        "Número máximo de tentativas excedido. Solicite um novo código.",
+        "Se superó el número máximo de intentos. Solicita un código nuevo.",
+    ),
+    ("O assunto deve ter no máximo {} caracteres", "El asunto debe tener como máximo {} caracteres"),
+    (
+        "O cartão não está disponível para links de uso único.",
+        "La tarjeta no está disponible para enlaces de un solo uso.",
+    ),
+    (
+        "O modelo de email deve ter no máximo {} caracteres",
+        "La plantilla de email debe tener como máximo {} caracteres",
+    ),
+    (
+        "O local do evento deve ter no máximo {} caracteres",
+        "El lugar del evento debe tener como máximo {} caracteres",
+    ),
+    (
+        "O motivo da denúncia deve ter no máximo {} caracteres",
+        "El motivo de la denuncia debe tener como máximo {} caracteres",
+    ),
+    ("O motivo deve ter no máximo {} caracteres", "El motivo debe tener como máximo {} caracteres"),
+    ("O motivo do bloqueio é obrigatório", "El motivo del bloqueo es obligatorio"),
+    (
+        "O nome da passkey deve ter entre 1 e {} caracteres",
+        "El nombre de la llave de acceso debe tener entre 1 y {} caracteres",
+    ),
+    ("O nome deve ter no máximo {} caracteres", "El nombre debe tener como máximo {} caracteres"),
+    (
+        "Os emails do sorteio ainda estão sendo enviados",
+        "Los emails del sorteo todavía se están enviando",
+    ),
+    (
+        "Os lembretes devem ser enviados de 1 a {} dias antes do evento",
+        "Los recordatorios deben enviarse de 1 a {} días antes del evento",
+    ),
+    (
+        "O sorteio ainda não foi realizado",
+        "El sorteo todavía no se realizó",
+    ),
+    (
+        "O sorteio ainda não foi realizado. Aguarde o organizador finalizar o sorteio.",
+        "El sorteo todavía no se realizó. Espera a que el organizador haga el sorteo.",
+    ),
+    (
+        "O sorteio ainda não foi realizado. Realize o sorteio antes de reenviar emails.",
+        "El sorteo todavía no se realizó. Realiza el sorteo antes de reenviar emails.",
+    ),
+    (
+        "O sorteio ainda não foi realizado.",
+        "El sorteo todavía no se realizó.",
+    ),
+    (
+        "O sorteio já foi realizado para este jogo",
+        "El sorteo ya se realizó para este juego",
+    ),
+    (
+        "Participante atualizado com sucesso",
+        "Participante actualizado con éxito",
+    ),
+    (
+        "Participante não encontrado",
+        "Participante no encontrado",
+    ),
+    (
+        "Participante não pertence a este jogo",
+        "El participante no pertenece a este juego",
+    ),
+    (
+        "Participante sorteado não encontrado",
+        "Participante sorteado no encontrado",
+    ),
+    (
+        "Passkey inválida. Tente novamente.",
+        "Llave de acceso no válida. Inténtalo de nuevo.",
+    ),
+    ("Passkey não encontrada", "Llave de acceso no encontrada"),
+    ("Passkey removida", "Llave de acceso eliminada"),
+    (
+        "Passkeys não estão disponíveis neste endereço",
+        "Las llaves de acceso no están disponibles en esta dirección",
+    ),
+    (
+        "Precisa de pelo menos 2 participantes para fazer o sorteio",
+        "Se necesitan al menos 2 participantes para hacer el sorteo",
+    ),
+    (
+        "Pronto! Você não receberá mais lembretes e resumos do Amigo Oculto.",
+        "¡Listo! Ya no recibirás recordatorios ni resúmenes del Amigo Secreto.",
+    ),
+    (
+        "Recuperação não encontrada",
+        "Recuperación no encontrada",
+    ),
+    ("Requisição grande demais", "Solicitud demasiado grande"),
+    ("Requisição inválida", "Solicitud no válida"),
+    (
+        "Resposta armazenada ilegível",
+        "Respuesta almacenada ilegible",
+    ),
+    (
+        "Revele seu amigo oculto antes de baixar o cartão.",
+        "Revela a tu amigo secreto antes de descargar la tarjeta.",
+    ),
+    (
+        "Se houver jogos organizados por este email, você receberá um link de acesso.",
+        "Si hay juegos organizados por este email, recibirás un enlace de acceso.",
+    ),
+    ("Senha alterada com sucesso", "Contraseña cambiada con éxito"),
+    ("Senha atual incorreta", "Contraseña actual incorrecta"),
+    ("Senha incorreta", "Contraseña incorrecta"),
+    (
+        "Sessão encerrada com sucesso",
+        "Sesión cerrada con éxito",
+    ),
+    ("Sessão inválida ou expirada", "Sesión no válida o caducada"),
+    ("Sessão não encontrada", "Sesión no encontrada"),
+    (
+        "Sorteio ainda não foi realizado",
+        "El sorteo todavía no se realizó",
+    ),
+    ("Sorteio desfeito com sucesso", "Sorteo deshecho con éxito"),
+    (
+        "Sorteio realizado com sucesso! Os emails estão sendo enviados.",
+        "¡Sorteo realizado con éxito! Los emails se están enviando.",
+    ),
+    (
+        "Só é possível reenviar email para este participante uma vez por hora.",
+        "Solo se puede reenviar el email a este participante una vez por hora.",
+    ),
+    (
+        "Só é possível reenviar emails em massa uma vez por hora.",
+        "Solo se pueden reenviar emails masivos una vez por hora.",
+    ),
+    ("Token de acesso ausente", "Falta el token de acceso"),
+    (
+        "Token inválido",
+        "Token inválido",
+    ),
+    (
+        "Uma requisição com esta Idempotency-Key ainda está em andamento",
+        "Una solicitud con esta Idempotency-Key todavía está en curso",
+    ),
+    ("Valor inválido", "Valor no válido"),
+    (
+        "Variável desconhecida no modelo: {}",
+        "Variable desconocida en la plantilla: {}",
+    ),
+    (
+        "Verificação em duas etapas desativada",
+        "Verificación en dos pasos desactivada",
+    ),
+    (
+        "Verificação não encontrada",
+        "Verificación no encontrada",
+    ),
+];
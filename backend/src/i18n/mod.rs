@@ -0,0 +1,551 @@
+//! Languages for emails and API error messages.
+//!
+//! Text is written in Brazilian Portuguese throughout the code and translated by looking it
+//! up in a catalog per language, the way gettext does. Text with `{}` placeholders is filled
+//! in with [`fill`] after translating. Anything missing from a catalog stays in Portuguese.
+
+mod en;
+mod es;
+
+use axum::{
+    body::{self, Body, HttpBody},
+    extract::{FromRequestParts, Request},
+    http::{HeaderMap, header, request::Parts},
+    middleware::Next,
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+use utoipa::openapi::RefOr;
+use utoipa::openapi::schema::{ObjectBuilder, Schema, Type};
+use utoipa::{PartialSchema, ToSchema};
+
+/// Largest JSON response body whose messages are translated, in bytes.
+const MAX_TRANSLATED_BODY: usize = 64 * 1024;
+
+tokio::task_local! {
+    /// Language of the game a request is about, once it's been loaded.
+    static GAME_LOCALE: Cell<Option<Locale>>;
+}
+
+/// Answers the current request in a game's language rather than the requester's, as
+/// responses about a game (including errors) are read by its participants.
+pub fn use_game_locale(locale: Locale) {
+    // Outside `localize_messages`, e.g. in tests, there's nothing to translate
+    let _ = GAME_LOCALE.try_with(|game_locale| game_locale.set(Some(locale)));
+}
+
+/// Language of a game's emails, chosen by the organizer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Locale {
+    #[default]
+    PtBr,
+    En,
+    Es,
+}
+
+/// Error type for unsupported languages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedLocale;
+
+impl fmt::Display for UnsupportedLocale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unsupported locale (expected pt-BR, en or es)")
+    }
+}
+
+impl std::error::Error for UnsupportedLocale {}
+
+impl Locale {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PtBr => "pt-BR",
+            Self::En => "en",
+            Self::Es => "es",
+        }
+    }
+
+    /// The preferred supported language in an `Accept-Language` header, if any.
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut ranges: Vec<(&str, f32)> = header
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse().ok())?;
+                Some((tag, quality))
+            })
+            .filter(|&(_, quality)| quality > 0.0)
+            .collect();
+        // Stable, so equally preferred languages keep their order
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges.into_iter().find_map(|(tag, _)| {
+            let language = tag.split('-').next()?.to_ascii_lowercase();
+            match language.as_str() {
+                "pt" => Some(Self::PtBr),
+                "en" => Some(Self::En),
+                "es" => Some(Self::Es),
+                _ => None,
+            }
+        })
+    }
+
+    /// Translates Portuguese text, as written in the code.
+    pub fn tr(self, text: &'static str) -> &'static str {
+        self.catalog()
+            .iter()
+            .find(|(source, _)| *source == text)
+            .map_or(text, |(_, translation)| translation)
+    }
+
+    /// Translates a message that may have been filled in from a catalog entry, such as an
+    /// error message. Returns `None` if no entry matches.
+    pub fn translate_message(self, message: &str) -> Option<String> {
+        self.catalog().iter().find_map(|(source, translation)| {
+            let args = match_template(source, message)?;
+            Some(fill(translation, &args))
+        })
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::PtBr => &[],
+            Self::En => en::CATALOG,
+            Self::Es => es::CATALOG,
+        }
+    }
+}
+
+/// The language of a request: the preferred supported language in its `Accept-Language`
+/// header, or Portuguese.
+impl<S: Send + Sync> FromRequestParts<S> for Locale {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+impl Locale {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Self::from_accept_language)
+            .unwrap_or_default()
+    }
+}
+
+/// Middleware that translates the `error` and `message` fields of JSON responses to the
+/// language of the game the request is about (see [`use_game_locale`]), or else the
+/// request's language.
+pub async fn localize_messages(request: Request, next: Next) -> Response {
+    let requested = Locale::from_headers(request.headers());
+    let (locale, response) = GAME_LOCALE
+        .scope(Cell::new(None), async {
+            let response = next.run(request).await;
+            (GAME_LOCALE.with(Cell::get).unwrap_or(requested), response)
+        })
+        .await;
+    let small = response
+        .body()
+        .size_hint()
+        .exact()
+        .is_some_and(|size| size <= MAX_TRANSLATED_BODY as u64);
+    if locale == Locale::PtBr || !small || !is_json(response.headers()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = body::to_bytes(body, MAX_TRANSLATED_BODY).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    let Ok(serde_json::Value::Object(mut fields)) = serde_json::from_slice(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    let mut translated = false;
+    let mut translate = |message: &mut String| {
+        if let Some(translation) = locale.translate_message(message) {
+            *message = translation;
+            translated = true;
+        }
+    };
+    for key in ["error", "message"] {
+        if let Some(serde_json::Value::String(message)) = fields.get_mut(key) {
+            translate(message);
+        }
+    }
+    // Messages by field, of validation errors
+    if let Some(serde_json::Value::Object(invalid)) = fields.get_mut("fields") {
+        for messages in invalid
+            .values_mut()
+            .filter_map(serde_json::Value::as_array_mut)
+        {
+            for message in messages.iter_mut() {
+                if let serde_json::Value::String(message) = message {
+                    translate(message);
+                }
+            }
+        }
+    }
+    if !translated {
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+
+    let body = serde_json::to_vec(&fields).expect("JSON values always serialize");
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+/// Replaces each `{}` in a template with the next argument.
+pub fn fill<T: fmt::Display>(template: &str, args: &[T]) -> String {
+    let mut parts = template.split("{}");
+    let mut filled = parts.next().unwrap_or_default().to_string();
+    for (i, part) in parts.enumerate() {
+        if let Some(arg) = args.get(i) {
+            filled.push_str(&arg.to_string());
+        }
+        filled.push_str(part);
+    }
+    filled
+}
+
+/// The values a message filled in for each `{}` of a template, if it matches the template.
+fn match_template<'a>(template: &str, message: &'a str) -> Option<Vec<&'a str>> {
+    let mut parts = template.split("{}");
+    let mut rest = message.strip_prefix(parts.next()?)?;
+    let mut args = Vec::new();
+    let mut parts = parts.peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            // The last literal part must end the message
+            args.push(rest.strip_suffix(part)?);
+            rest = "";
+        } else {
+            let end = rest.find(part)?;
+            args.push(&rest[..end]);
+            rest = &rest[end + part.len()..];
+        }
+    }
+    rest.is_empty().then_some(args)
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = UnsupportedLocale;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pt-BR" => Ok(Self::PtBr),
+            "en" => Ok(Self::En),
+            "es" => Ok(Self::Es),
+            _ => Err(UnsupportedLocale),
+        }
+    }
+}
+
+impl Serialize for Locale {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl PartialSchema for Locale {
+    fn schema() -> RefOr<Schema> {
+        let tags = [Self::PtBr, Self::En, Self::Es].map(|locale| locale.as_str());
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .enum_values(Some(tags))
+            .into()
+    }
+}
+
+impl ToSchema for Locale {}
+
+impl<'de> Deserialize<'de> for Locale {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl sqlx::Type<sqlx::Sqlite> for Locale {
+    fn type_info() -> sqlx::sqlite::SqliteTypeInfo {
+        <String as sqlx::Type<sqlx::Sqlite>>::type_info()
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Sqlite> for Locale {
+    fn decode(value: sqlx::sqlite::SqliteValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let s = <String as sqlx::Decode<sqlx::Sqlite>>::decode(value)?;
+        Ok(Self::from_str(&s)?)
+    }
+}
+
+impl<'q> sqlx::Encode<'q, sqlx::Sqlite> for Locale {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<sqlx::sqlite::SqliteArgumentValue<'q>>,
+    ) -> Result<sqlx::encode::IsNull, Box<dyn std::error::Error + Send + Sync>> {
+        <&str as sqlx::Encode<sqlx::Sqlite>>::encode_by_ref(&self.as_str(), args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_from_accept_language() {
+        let locale = Locale::from_accept_language;
+        assert_eq!(locale("en-US,en;q=0.9"), Some(Locale::En));
+        assert_eq!(locale("fr-FR, es;q=0.8, pt-BR;q=0.9"), Some(Locale::PtBr));
+        assert_eq!(locale("ES-419"), Some(Locale::Es));
+        assert_eq!(locale("en;q=0, de"), None);
+        assert_eq!(locale(""), None);
+    }
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(Locale::En.tr("Jogo não encontrado"), "Game not found");
+        assert_eq!(
+            Locale::PtBr.tr("Jogo não encontrado"),
+            "Jogo não encontrado"
+        );
+        assert_eq!(Locale::Es.tr("Texto sem tradução"), "Texto sem tradução");
+
+        assert_eq!(
+            Locale::En
+                .translate_message("Limite máximo de 100 participantes atingido")
+                .unwrap(),
+            "Maximum of 100 participants reached"
+        );
+        assert_eq!(
+            Locale::Es
+                .translate_message("Este jogo aceita apenas emails do domínio @empresa.com")
+                .unwrap(),
+            "Este juego solo acepta emails del dominio @empresa.com"
+        );
+        assert_eq!(Locale::En.translate_message("Outra mensagem"), None);
+    }
+
+    #[tokio::test]
+    async fn test_localize_messages_in_game_locale() {
+        use axum::{Json, Router, middleware, routing::get};
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route(
+                "/requested",
+                get(|| async { Json(serde_json::json!({ "error": "Jogo não encontrado" })) }),
+            )
+            .route(
+                "/game",
+                get(|| async {
+                    use_game_locale(Locale::Es);
+                    Json(serde_json::json!({ "error": "Jogo não encontrado" }))
+                }),
+            )
+            .layer(middleware::from_fn(localize_messages));
+        let error = |path: &'static str| {
+            let app = app.clone();
+            async move {
+                let request = Request::get(path)
+                    .header(header::ACCEPT_LANGUAGE, "en")
+                    .body(Body::empty())
+                    .unwrap();
+                let response = app.oneshot(request).await.unwrap();
+                let body = body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()["error"].clone()
+            }
+        };
+        assert_eq!(error("/requested").await, "Game not found");
+        assert_eq!(error("/game").await, "Juego no encontrado");
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(fill("{} de {}", &["1", "3"]), "1 de 3");
+        assert_eq!(fill("sem argumentos", &[""; 0]), "sem argumentos");
+    }
+
+    #[test]
+    fn test_catalogs_are_consistent() {
+        let placeholders = |text: &str| text.matches("{}").count();
+        let sources = |catalog: &[(&'static str, &str)]| {
+            catalog
+                .iter()
+                .map(|(source, _)| *source)
+                .collect::<Vec<_>>()
+        };
+
+        for catalog in [en::CATALOG, es::CATALOG] {
+            let unique: HashSet<_> = sources(catalog).into_iter().collect();
+            assert_eq!(unique.len(), catalog.len(), "duplicate catalog entry");
+            for (source, translation) in catalog {
+                assert_eq!(
+                    placeholders(source),
+                    placeholders(translation),
+                    "placeholders differ in translation of {source:?}"
+                );
+            }
+        }
+        assert_eq!(sources(en::CATALOG), sources(es::CATALOG));
+    }
+
+    /// Portuguese string literals passed where API messages are built, with `{name}`
+    /// placeholders written as `{}` like in the catalogs.
+    fn api_messages(code: &str) -> Vec<String> {
+        // Each marker is followed by the arguments before the message
+        type SkipArguments = fn(&str) -> Option<&str>;
+        let markers: [(&str, SkipArguments); 7] = [
+            ("AppError::", |rest| {
+                rest.trim_start_matches(char::is_alphanumeric)
+                    .strip_prefix('(')
+            }),
+            ("StatusCode::", |rest| {
+                rest.trim_start_matches(|c: char| c.is_ascii_uppercase() || c == '_')
+                    .strip_prefix(',')
+            }),
+            (".add(", |rest| {
+                // Skip the field argument, which may be a call
+                let mut depth = 0;
+                for (i, c) in rest.char_indices() {
+                    match c {
+                        '(' => depth += 1,
+                        ')' if depth == 0 => return None,
+                        ')' => depth -= 1,
+                        ',' if depth == 0 => return Some(&rest[i + 1..]),
+                        '"' | ';' => return None,
+                        _ => {}
+                    }
+                }
+                None
+            }),
+            ("ErrorBody::new(", |rest| Some(rest)),
+            ("unauthorized_response(", |rest| Some(rest)),
+            ("\"error\":", |rest| Some(rest)),
+            ("\"message\":", |rest| Some(rest)),
+        ];
+
+        let mut messages = Vec::new();
+        for (marker, skip_arguments) in markers {
+            for (start, _) in code.match_indices(marker) {
+                let Some(rest) = skip_arguments(&code[start + marker.len()..]) else {
+                    continue;
+                };
+                let rest = rest.trim_start();
+                let rest = rest.strip_prefix("format!(").unwrap_or(rest).trim_start();
+                let Some(literal) = rest.strip_prefix('"') else {
+                    continue;
+                };
+
+                let mut message = String::new();
+                let mut chars = literal.chars();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => message.push('\n'),
+                            Some('\n') => {
+                                chars = chars.as_str().trim_start().chars();
+                            }
+                            Some(escaped) => message.push(escaped),
+                            None => break,
+                        },
+                        c => message.push(c),
+                    }
+                }
+
+                let mut normalized = String::new();
+                let mut in_placeholder = false;
+                for c in message.chars() {
+                    match c {
+                        '{' => {
+                            in_placeholder = true;
+                            normalized.push('{');
+                        }
+                        '}' => {
+                            in_placeholder = false;
+                            normalized.push('}');
+                        }
+                        _ if in_placeholder => {}
+                        c => normalized.push(c),
+                    }
+                }
+                messages.push(normalized);
+            }
+        }
+        messages
+    }
+
+    #[test]
+    fn test_api_messages_are_translated() {
+        fn source_files(dir: &std::path::Path, files: &mut Vec<std::path::PathBuf>) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    source_files(&path, files);
+                } else if path.extension().is_some_and(|ext| ext == "rs") {
+                    files.push(path);
+                }
+            }
+        }
+
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut files = Vec::new();
+        source_files(&src, &mut files);
+        let sources: HashSet<_> = en::CATALOG.iter().map(|(source, _)| *source).collect();
+
+        let mut missing = Vec::new();
+        let mut checked = 0;
+        for path in files {
+            // The catalogs themselves, and static files whose errors are plain text
+            if path.starts_with(src.join("i18n")) || path.ends_with("static_files.rs") {
+                continue;
+            }
+            let code = std::fs::read_to_string(&path).unwrap();
+            let code = code.split("#[cfg(test)]\nmod tests").next().unwrap();
+            for message in api_messages(code) {
+                checked += 1;
+                if !sources.contains(message.as_str()) {
+                    missing.push(format!("{}: {message:?}", path.display()));
+                }
+            }
+        }
+        assert!(checked > 100, "only found {checked} messages");
+        assert!(
+            missing.is_empty(),
+            "untranslated messages:\n{}",
+            missing.join("\n")
+        );
+    }
+}
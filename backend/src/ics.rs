@@ -0,0 +1,117 @@
+//! Calendar events (iCalendar, RFC 5545) attached to emails, so recipients can add the gift
+//! exchange to their calendars.
+//!
+//! Events never include reveal or admin links: calendars are often shared or synced to
+//! other services, and the links would give away the match or the game.
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use std::fmt::Write as _;
+
+/// Longest content line, in bytes, before it's folded onto the next one.
+const MAX_LINE_LENGTH: usize = 75;
+
+/// Renders an all-day event on the event date.
+///
+/// The `uid` identifies the event, so that a resent email updates the event added before
+/// instead of adding a second one.
+pub fn render_event(
+    uid: &str,
+    summary: &str,
+    date: NaiveDate,
+    location: Option<&str>,
+    now: DateTime<Utc>,
+) -> String {
+    let next_day = date.checked_add_days(Days::new(1)).unwrap_or(date);
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Amigo Oculto//Amigo Oculto//PT".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", escape(uid)),
+        format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")),
+        format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+        format!("DTEND;VALUE=DATE:{}", next_day.format("%Y%m%d")),
+        format!("SUMMARY:{}", escape(summary)),
+    ];
+    if let Some(location) = location {
+        lines.push(format!("LOCATION:{}", escape(location)));
+    }
+    lines.extend([
+        "DESCRIPTION:Troca de presentes do Amigo Oculto 🎁".to_string(),
+        "TRANSP:TRANSPARENT".to_string(),
+        "END:VEVENT".to_string(),
+        "END:VCALENDAR".to_string(),
+    ]);
+
+    let mut calendar = String::new();
+    for line in lines {
+        write!(calendar, "{}\r\n", fold(&line)).unwrap();
+    }
+    calendar
+}
+
+/// Escapes text for a property value.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\r', '\n'], "\\n")
+}
+
+/// Splits a line longer than the limit, continuing it on lines that start with a space.
+/// Never splits a character.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            // The space counts towards the continuation line
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_render_event() {
+        let calendar = render_event(
+            "01JDQ7Z5@amigooculto.app",
+            "Amigo Oculto: Natal, Família; 2025",
+            NaiveDate::from_ymd_opt(2025, 12, 31).unwrap(),
+            Some("Rua das Flores, 123"),
+            Utc.with_ymd_and_hms(2025, 12, 1, 12, 30, 0).unwrap(),
+        );
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(calendar.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert!(calendar.contains("\r\nDTSTAMP:20251201T123000Z\r\n"));
+        assert!(calendar.contains("\r\nDTSTART;VALUE=DATE:20251231\r\n"));
+        assert!(calendar.contains("\r\nDTEND;VALUE=DATE:20260101\r\n"));
+        assert!(calendar.contains("\r\nSUMMARY:Amigo Oculto: Natal\\, Família\\; 2025\r\n"));
+        assert!(calendar.contains("\r\nLOCATION:Rua das Flores\\, 123\r\n"));
+    }
+
+    #[test]
+    fn test_fold() {
+        assert_eq!(fold("SUMMARY:curto"), "SUMMARY:curto");
+
+        let line = format!("SUMMARY:{}", "ã".repeat(50));
+        let folded = fold(&line);
+        assert_eq!(folded.replace("\r\n ", ""), line);
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= MAX_LINE_LENGTH);
+        }
+    }
+}
@@ -0,0 +1,329 @@
+//! `Idempotency-Key` support, so that clients retrying over a flaky network don't create a
+//! game twice, add a participant twice or send emails again.
+//!
+//! The first request with a key is handled as usual, and its response stored. Later
+//! requests with the same key get that response again (with `Idempotent-Replayed: true`)
+//! instead of being handled, as long as they're the same request: same method, path,
+//! credentials and body. Server errors and rate limits aren't stored, so retrying those
+//! does handle the request again.
+//!
+//! Responses can hold tokens (creating a game returns its admin token and sets a session
+//! cookie), so they're encrypted with a key derived from the `Idempotency-Key`, which is only
+//! stored hashed: the database alone can't be used to read them.
+
+use crate::db::Database;
+use crate::models::IdempotencyKeyState;
+use crate::organizer_auth;
+use crate::routes::AppError;
+use axum::{
+    body::{self, Body, Bytes},
+    extract::{FromRequest, Request, State},
+    http::{HeaderName, HeaderValue, StatusCode, header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use ring::aead::{Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::ops::RangeInclusive;
+
+pub static IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+
+static IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// Accepted key lengths: enough for a UUID or ULID to be required in practice, as keys
+/// must be hard to guess.
+const KEY_LENGTH: RangeInclusive<usize> = 16..=255;
+
+/// What the header is, for the OpenAPI document.
+pub const KEY_DESCRIPTION: &str = "A unique key, of 16 to 255 visible characters, to retry the \
+    request with. Retries get the first response again, with an `Idempotent-Replayed: true` \
+    header, instead of being handled again.";
+
+/// Largest response stored; those of the routes accepting keys are much smaller.
+const MAX_RESPONSE_BYTES: usize = 1024 * 1024;
+
+/// Response headers replayed. Others, such as `X-Request-Id`, belong to each request.
+const REPLAYED_HEADERS: [HeaderName; 2] = [header::CONTENT_TYPE, header::SET_COOKIE];
+
+/// A response as stored, before encryption.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct StoredResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    /// Base64
+    body: String,
+}
+
+/// Middleware handling requests with an `Idempotency-Key` at most once.
+pub async fn idempotent(State(db): State<Database>, request: Request, next: Next) -> Response {
+    let Some(key) = request.headers().get(&IDEMPOTENCY_KEY) else {
+        return next.run(request).await;
+    };
+    let Some(key) = key
+        .to_str()
+        .ok()
+        .filter(|key| {
+            KEY_LENGTH.contains(&key.len()) && key.bytes().all(|byte| byte.is_ascii_graphic())
+        })
+        .map(str::to_string)
+    else {
+        return AppError::BadRequest(
+            "Idempotency-Key inválida: use de 16 a 255 caracteres visíveis".to_string(),
+        )
+        .into_response();
+    };
+
+    // The body is read here to tell retries from different requests with the same key
+    let (parts, body) = request.into_parts();
+    let body = match Bytes::from_request(Request::from_parts(parts.clone(), body), &()).await {
+        Ok(body) => body,
+        Err(rejection) => return rejection.into_response(),
+    };
+    let key_hash = digest(&["idempotency key", &key]);
+    let request_hash = request_hash(&parts, &body);
+
+    match handle(&db, &key, &key_hash, &request_hash).await {
+        Ok(None) => {}
+        Ok(Some(replayed)) => return replayed,
+        Err(e) => return e.into_response(),
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let status = response.status();
+    if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+        if let Err(e) = db.release_idempotency_key(&key_hash).await {
+            tracing::error!("failed to release idempotency key: {:#}", e);
+        }
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body = match body::to_bytes(body, MAX_RESPONSE_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!("failed to read idempotent response: {}", e);
+            if let Err(e) = db.release_idempotency_key(&key_hash).await {
+                tracing::error!("failed to release idempotency key: {:#}", e);
+            }
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let stored = StoredResponse {
+        status: status.as_u16(),
+        headers: REPLAYED_HEADERS
+            .iter()
+            .flat_map(|name| {
+                parts.headers.get_all(name).iter().filter_map(move |value| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+            })
+            .collect(),
+        body: BASE64_STANDARD.encode(&body),
+    };
+    // The request was handled either way, so failing to store its response is only logged;
+    // retries are refused until the claim times out
+    let sealed = serde_json::to_vec(&stored).map(|plaintext| seal(&key, plaintext));
+    match sealed {
+        Ok(sealed) => {
+            if let Err(e) = db.complete_idempotency_key(&key_hash, &sealed).await {
+                tracing::error!("failed to store idempotent response: {:#}", e);
+            }
+        }
+        Err(e) => tracing::error!("failed to serialize idempotent response: {}", e),
+    }
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Claims the key, or returns the response to replay.
+async fn handle(
+    db: &Database,
+    key: &str,
+    key_hash: &str,
+    request_hash: &str,
+) -> Result<Option<Response>, AppError> {
+    match db.claim_idempotency_key(key_hash, request_hash).await? {
+        IdempotencyKeyState::Claimed => Ok(None),
+        IdempotencyKeyState::Used {
+            request_hash: used, ..
+        } if used != request_hash => Err(AppError::BadRequest(
+            "Idempotency-Key já usada com outra requisição".to_string(),
+        )),
+        IdempotencyKeyState::Used { response: None, .. } => Err(AppError::Conflict(
+            "Uma requisição com esta Idempotency-Key ainda está em andamento".to_string(),
+        )),
+        IdempotencyKeyState::Used {
+            response: Some(sealed),
+            ..
+        } => {
+            let stored = open(key, &sealed)
+                .and_then(|plaintext| serde_json::from_slice(&plaintext).ok())
+                .ok_or_else(|| {
+                    AppError::InternalError("Resposta armazenada ilegível".to_string())
+                })?;
+            Ok(Some(replay(stored)?))
+        }
+    }
+}
+
+fn replay(stored: StoredResponse) -> Result<Response, AppError> {
+    let unreadable = || AppError::InternalError("Resposta armazenada ilegível".to_string());
+    let body = BASE64_STANDARD
+        .decode(&stored.body)
+        .map_err(|_| unreadable())?;
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = StatusCode::from_u16(stored.status).map_err(|_| unreadable())?;
+    for (name, value) in stored.headers {
+        let name = HeaderName::try_from(name).map_err(|_| unreadable())?;
+        let value = HeaderValue::try_from(value).map_err(|_| unreadable())?;
+        response.headers_mut().append(name, value);
+    }
+    response.headers_mut().insert(
+        IDEMPOTENT_REPLAYED.clone(),
+        HeaderValue::from_static("true"),
+    );
+    Ok(response)
+}
+
+/// Identifies a request: what it does, and on whose behalf.
+fn request_hash(parts: &Parts, body: &[u8]) -> String {
+    let header = |name| {
+        parts
+            .headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+    };
+    let session = organizer_auth::session_token_from_headers(&parts.headers)
+        .map(|token| token.to_string())
+        .unwrap_or_default();
+    let mut hasher = Sha256::new();
+    for part in [
+        parts.method.as_str(),
+        parts.uri.path(),
+        parts.uri.query().unwrap_or_default(),
+        header(header::AUTHORIZATION),
+        &session,
+    ] {
+        hasher.update(part);
+        hasher.update([0]);
+    }
+    hasher.update(body);
+    hex(&hasher.finalize())
+}
+
+fn digest(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+        hasher.update([0]);
+    }
+    hex(&hasher.finalize())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// The cipher for the response to a key. Each key encrypts a single response.
+fn cipher(key: &str) -> LessSafeKey {
+    let secret = Sha256::new()
+        .chain_update("idempotent response\0")
+        .chain_update(key)
+        .finalize();
+    LessSafeKey::new(
+        UnboundKey::new(&CHACHA20_POLY1305, &secret).expect("SHA-256 digests are valid keys"),
+    )
+}
+
+/// The nonce, then the encrypted plaintext.
+fn seal(key: &str, mut plaintext: Vec<u8>) -> Vec<u8> {
+    let nonce: [u8; NONCE_LEN] = rand::random();
+    cipher(key)
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut plaintext,
+        )
+        .expect("responses are far smaller than the cipher's limit");
+    [&nonce[..], &plaintext].concat()
+}
+
+fn open(key: &str, sealed: &[u8]) -> Option<Vec<u8>> {
+    let (nonce, ciphertext) = sealed.split_at_checked(NONCE_LEN)?;
+    let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+    let mut in_out = ciphertext.to_vec();
+    let plaintext = cipher(key)
+        .open_in_place(nonce, Aad::empty(), &mut in_out)
+        .ok()?;
+    Some(plaintext.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal() {
+        let key = "01J9Z3K4M5N6P7Q8R9S0T1V2W3";
+        let sealed = seal(key, b"{\"participant_id\":\"x\"}".to_vec());
+        assert!(!sealed.windows(14).any(|w| w == b"participant_id"));
+        assert_eq!(
+            open(key, &sealed).as_deref(),
+            Some(&b"{\"participant_id\":\"x\"}"[..])
+        );
+        assert_eq!(open("another key of some length", &sealed), None);
+        assert_eq!(open(key, &sealed[..8]), None);
+    }
+
+    #[test]
+    fn test_request_hash() {
+        let request = |method: &str, uri: &str, authorization: Option<&str>| {
+            let mut builder = Request::builder().method(method).uri(uri);
+            if let Some(authorization) = authorization {
+                builder = builder.header(header::AUTHORIZATION, authorization);
+            }
+            builder.body(()).unwrap().into_parts().0
+        };
+        let path = "/api/games/01J9Z3/participants";
+        let hash = request_hash(&request("POST", path, None), b"{}");
+        assert_eq!(hash, request_hash(&request("POST", path, None), b"{}"));
+        assert_ne!(hash, request_hash(&request("POST", path, None), b"{ }"));
+        assert_ne!(hash, request_hash(&request("PUT", path, None), b"{}"));
+        assert_ne!(
+            hash,
+            request_hash(
+                &request("POST", "/api/games/01J9Z4/participants", None),
+                b"{}"
+            )
+        );
+        assert_ne!(
+            hash,
+            request_hash(&request("POST", path, Some("Bearer token")), b"{}")
+        );
+    }
+
+    #[test]
+    fn test_replay() {
+        let stored = StoredResponse {
+            status: 200,
+            headers: vec![
+                ("content-type".to_string(), "application/json".to_string()),
+                (
+                    "set-cookie".to_string(),
+                    "organizer_session=abc".to_string(),
+                ),
+            ],
+            body: BASE64_STANDARD.encode("{\"success\":true}"),
+        };
+        let response = replay(stored).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::SET_COOKIE],
+            "organizer_session=abc"
+        );
+        assert_eq!(response.headers()[&IDEMPOTENT_REPLAYED], "true");
+    }
+}
@@ -1,19 +1,80 @@
+mod admin_sessions;
+mod backup;
+mod body_limit;
+mod captcha;
+mod card;
+mod client;
+mod clock;
+mod config;
+mod config_file;
+mod datetime;
 mod db;
 mod email;
+mod email_blocklist;
+mod email_dispatch;
+mod email_events;
+mod email_suggestion;
 mod email_templates;
+mod email_transport;
+mod error_reporting;
+mod export;
+mod game_events;
+mod i18n;
+mod ics;
+mod idempotency;
 mod matching;
 mod models;
+mod mx_check;
+mod openapi;
+mod organizer_auth;
+mod prometheus;
+mod rate_limiter;
+mod redis;
+mod request_id;
 mod routes;
 mod server;
+mod sigv4;
 mod site_admin_auth;
+mod static_files;
+mod storage;
 mod token;
+mod totp;
+mod validation;
+mod webauthn;
 
-use crate::{db::Database, server::Server};
+use crate::{
+    admin_sessions::{AdminSessionStore, RedisAdminSessions},
+    backup::Backups,
+    captcha::Captcha,
+    clock::SystemClock,
+    config::Config,
+    db::Database,
+    mx_check::MailDomainChecker,
+    redis::Redis,
+    server::Server,
+};
 use anyhow::Context;
 use email::EmailService;
+use email_dispatch::EmailDispatcher;
+use game_events::GameEvents;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+/// What to do instead of serving, if anything.
+enum Command {
+    Serve,
+    /// Replace the data with a backup (`restore <snapshot>`).
+    Restore(PathBuf),
+    /// Print the query plans of the hot queries (`explain`).
+    Explain,
+}
+
+/// Exit code for invalid command line arguments (`EX_USAGE` from sysexits.h).
+const EXIT_USAGE: i32 = 64;
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initialize tracing
@@ -25,27 +86,131 @@ async fn main() -> anyhow::Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = match args.as_slice() {
+        [] => Command::Serve,
+        [command, snapshot] if command == "restore" => Command::Restore(PathBuf::from(snapshot)),
+        [command] if command == "explain" => Command::Explain,
+        _ => {
+            eprintln!("usage: amigo-oculto-backend [restore <snapshot> | explain]");
+            std::process::exit(EXIT_USAGE);
+        }
+    };
+
     // Load environment variables
     dotenvy::dotenv().ok();
 
-    let db = Database::from_env().await?;
+    // Validate the whole configuration up front, reporting every problem at once
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::error!("{e}");
+            std::process::exit(config::EXIT_CONFIG);
+        }
+    };
+
+    if config.metrics_token.is_some() {
+        prometheus::install()?;
+    }
+
+    let clock = Arc::new(SystemClock);
+    let _error_reporting = config.error_reporting.clone().map(error_reporting::init);
+    let db = Database::connect(&config.database_url, clock.clone()).await?;
+
+    match command {
+        Command::Serve => {}
+        Command::Restore(path) => {
+            let snapshot = tokio::fs::read(&path)
+                .await
+                .with_context(|| format!("reading {}", path.display()))?;
+            let summary = backup::restore(&db, snapshot)
+                .await
+                .with_context(|| format!("restoring {}", path.display()))?;
+            tracing::info!(
+                "restored {} rows ({} bytes) from {}",
+                summary.rows,
+                summary.size,
+                path.display()
+            );
+            return Ok(());
+        }
+        Command::Explain => {
+            for plan in db.explain_hot_queries().await? {
+                println!("{}:", plan.query);
+                for step in &plan.plan {
+                    println!("  {step}");
+                }
+            }
+            return Ok(());
+        }
+    }
 
     // Initialize site admin password if not already set
-    db.init_site_admin_password()
+    db.init_site_admin_password(config.site_admin_password.as_deref())
         .await
         .context("initializing site admin password")?;
 
-    let cancel = CancellationToken::new();
-    let server = Server::new(&db, cancel.clone())?;
-    let email_service = EmailService::from_env()?;
+    let email_service = EmailService::new(config.email.clone(), db.clone(), clock.clone())?;
 
     email_service.test().await.context("testing connection")?;
 
-    let app = routes::make(db, email_service);
+    let cancel = CancellationToken::new();
+    let game_events = GameEvents::new(cancel.clone());
+    let email_dispatcher = EmailDispatcher::new(
+        db.clone(),
+        email_service.clone(),
+        game_events.clone(),
+        config.email_dispatch,
+    );
+
+    // Shares rate limits and site admin sessions between instances
+    let redis = match &config.redis {
+        Some(redis) => Some(Redis::connect(redis).await?),
+        None => None,
+    };
+    let admin_sessions: Arc<dyn AdminSessionStore> = match &redis {
+        Some(redis) => Arc::new(RedisAdminSessions::new(redis.clone(), clock.clone())),
+        None => Arc::new(db.clone()),
+    };
+
+    let backups = config
+        .backup
+        .clone()
+        .map(|backup| Backups::new(backup, db.clone(), clock.clone()))
+        .transpose()?;
+
+    let server = Server::new(
+        &db,
+        &admin_sessions,
+        &email_service,
+        &email_dispatcher,
+        backups.as_ref(),
+        cancel.clone(),
+    )?;
+
+    let storage = config.media.open(clock.clone())?;
+    let captcha = config.captcha.clone().map(Captcha::new).transpose()?;
+    let mail_domains = config
+        .check_email_domains
+        .then(MailDomainChecker::from_system_conf)
+        .transpose()?;
+
+    let app = routes::make(
+        db,
+        admin_sessions,
+        redis,
+        email_service,
+        email_dispatcher,
+        game_events,
+        captcha,
+        backups,
+        storage,
+        mail_domains,
+        clock,
+        &config,
+    );
 
-    let port = std::env::var("PORT")
-        .unwrap_or_else(|_| "3000".to_string())
-        .parse::<u16>()?;
+    let port = config.port;
 
     let addr = format!("0.0.0.0:{}", port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
@@ -53,9 +218,12 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("📝 App available at http://localhost:{}/", port);
 
     // Run the HTTP server with graceful shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal(cancel))
-        .await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal(cancel))
+    .await?;
 
     // Shutdown background tasks
     server.shutdown().await;
@@ -29,7 +29,7 @@ pub fn generate_matches(
 mod tests {
     use super::*;
     use crate::models::Participant;
-    use crate::token::{GameId, ViewToken};
+    use crate::token::GameId;
 
     fn create_test_participant(name: &str) -> Participant {
         Participant {
@@ -38,7 +38,6 @@ mod tests {
             name: name.to_string(),
             email: format!("{}@test.com", name).parse().unwrap(),
             matched_with_id: None,
-            view_token: ViewToken::generate(),
             has_viewed: false,
             created_at: chrono::Utc::now(),
         }
@@ -1,42 +1,86 @@
+use crate::captcha::CaptchaProvider;
+use crate::datetime::GameTimezone;
+use crate::email_events::EmailEventKind;
+use crate::export::ExportFormat;
+use crate::i18n::Locale;
+use crate::storage::MediaKey;
 use crate::token::{
-    AdminToken, EmailAddress, GameId, ParticipantId, VerificationCode, VerificationId, ViewToken,
+    AdminSessionId, AdminToken, AnnouncementId, EmailAddress, EmailBanId, EmailDomain, EmailJobId,
+    GameId, ParticipantId, PasskeyId, RecoveryId, ReportId, TokenHash, VerificationCode,
+    VerificationId, VerificationLinkToken, ViewToken,
 };
-use chrono::{DateTime, NaiveDate, Utc};
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use webauthn_rs::prelude::{Passkey, PublicKeyCredential, RegisterPublicKeyCredential};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Deserializes a field that was present in the input, so that `Option<Option<T>>` can tell
+/// an omitted field (`None`) apart from an explicit `null` (`Some(None)`).
+///
+/// Must be combined with `#[serde(default)]`.
+fn deserialize_present<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Clone)]
 pub struct EmailVerification {
     pub id: VerificationId,
     pub email: EmailAddress,
-    pub code: VerificationCode,
+    pub code_hash: TokenHash,
     pub game_name: String,
     pub event_date: NaiveDate,
     pub created_at: DateTime<Utc>,
     pub expires_at: DateTime<Utc>,
     pub verified: bool,
     pub attempts: i32,
+    pub event_location: Option<String>,
+    pub timezone: GameTimezone,
+    pub locale: Locale,
+    /// Hash of the token that confirms the verification without typing the code. `None` for
+    /// verifications requested before confirmation links were added.
+    pub link_token_hash: Option<TokenHash>,
 }
 
 impl EmailVerification {
-    pub fn new(email: EmailAddress, game_name: String, event_date: NaiveDate) -> Self {
-        let created_at = Utc::now();
+    /// Creates a verification, along with the code and link token to email. Only their
+    /// hashes are kept.
+    pub fn new(
+        email: EmailAddress,
+        game_name: String,
+        event_date: NaiveDate,
+        event_location: Option<String>,
+        timezone: GameTimezone,
+        locale: Locale,
+        created_at: DateTime<Utc>,
+    ) -> (Self, VerificationCode, VerificationLinkToken) {
         let expires_at = created_at + chrono::Duration::minutes(15);
+        let code = VerificationCode::generate();
+        let link_token = VerificationLinkToken::generate();
 
-        Self {
+        let verification = Self {
             id: VerificationId::new(),
             email,
-            code: VerificationCode::generate(),
+            code_hash: code.hash(),
             game_name,
             event_date,
             created_at,
             expires_at,
             verified: false,
             attempts: 0,
-        }
+            event_location,
+            timezone,
+            locale,
+            link_token_hash: Some(link_token.hash()),
+        };
+        (verification, code, link_token)
     }
 
-    pub fn is_expired(&self) -> bool {
-        Utc::now() > self.expires_at
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now > self.expires_at
     }
 
     pub fn can_attempt(&self) -> bool {
@@ -44,15 +88,253 @@ impl EmailVerification {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A PIN sent to a participant who lost their email, to find their reveal links again.
+#[derive(Debug, Clone)]
+pub struct RevealRecovery {
+    pub id: RecoveryId,
+    pub email: EmailAddress,
+    pub code_hash: TokenHash,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub verified: bool,
+    pub attempts: i32,
+}
+
+impl RevealRecovery {
+    /// Guesses allowed per PIN before a new one must be requested.
+    pub const MAX_ATTEMPTS: i32 = 5;
+
+    /// Creates a recovery, along with the PIN to email. Only its hash is kept.
+    pub fn new(email: EmailAddress, created_at: DateTime<Utc>) -> (Self, VerificationCode) {
+        let expires_at = created_at + chrono::Duration::minutes(15);
+        let code = VerificationCode::generate();
+
+        let recovery = Self {
+            id: RecoveryId::new(),
+            email,
+            code_hash: code.hash(),
+            created_at,
+            expires_at,
+            verified: false,
+            attempts: 0,
+        };
+        (recovery, code)
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now > self.expires_at
+    }
+}
+
+/// Requests counted against a limit over a time window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecentRequests {
+    pub count: i64,
+    pub oldest: Option<DateTime<Utc>>,
+}
+
+impl RecentRequests {
+    /// How long to wait until the oldest request leaves the `window`, if `limit` of them
+    /// were already made in it.
+    pub fn retry_after(
+        &self,
+        limit: i64,
+        window: Duration,
+        now: DateTime<Utc>,
+    ) -> Option<Duration> {
+        match self.oldest {
+            Some(oldest) if self.count >= limit => Some(oldest + window - now),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct Game {
     pub id: GameId,
     pub name: String,
     pub event_date: NaiveDate,
     pub organizer_email: EmailAddress,
-    pub admin_token: AdminToken,
+    /// Hash of the token in legacy `?admin_token=` links.
+    #[serde(skip)]
+    pub admin_token_hash: TokenHash,
     pub created_at: DateTime<Utc>,
     pub drawn: bool,
+    /// When set, only participants with emails in this domain can be added.
+    pub allowed_email_domain: Option<EmailDomain>,
+    /// Set by a site admin to block organizer changes pending abuse review.
+    pub frozen_at: Option<DateTime<Utc>>,
+    pub frozen_reason: Option<String>,
+    /// Set when the organizer deletes the game; it is purged after a grace period.
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Free-text message from the organizer included in participant emails.
+    pub organizer_message: Option<String>,
+    /// Where the event takes place, linked to a map in emails.
+    pub event_location: Option<String>,
+    /// Reveal links only show the match after this time, when set.
+    pub reveal_at: Option<DateTime<Utc>>,
+    /// Where the event happens; used to interpret dates and times for the organizer.
+    pub timezone: GameTimezone,
+    /// Language of the game's emails.
+    pub locale: Locale,
+    /// When set, the organizer has closed the participant list to new additions.
+    pub locked: bool,
+    /// Reveal links stop working once the event date has passed.
+    pub reveal_expires_after_event: bool,
+    /// Reveal links work only once; the organizer can issue new ones.
+    pub reveal_single_use: bool,
+    #[schema(value_type = Option<String>)]
+    pub cover_image: Option<MediaKey>,
+    /// When the draw was done; unknown for games drawn before it was recorded.
+    pub drawn_at: Option<DateTime<Utc>>,
+    /// Whether participants who haven't seen their match get reminder emails.
+    pub reminders_enabled: bool,
+    /// Whether the organizer gets a wrap-up email with statistics after the event.
+    pub wrap_up_enabled: bool,
+    pub wrap_up_sent_at: Option<DateTime<Utc>>,
+    /// Organizer-written subject and body of the participant email (see
+    /// `email_templates::custom`), replacing the built-in ones.
+    pub participant_email_subject: Option<String>,
+    pub participant_email_body: Option<String>,
+    /// Days before the event when every participant is reminded of it, largest first.
+    /// Event reminders are off when empty.
+    pub event_reminder_days: Vec<u32>,
+    /// Whether the organizer gets a weekly digest of who has seen their match.
+    pub digest_enabled: bool,
+    pub digest_sent_at: Option<DateTime<Utc>>,
+    /// Whether replies to participant emails go to the organizer instead of `SMTP_FROM`.
+    pub reply_to_organizer: bool,
+    /// Whether participant emails have a tracking pixel, to tell unopened emails apart.
+    pub open_tracking_enabled: bool,
+}
+
+/// How a game went, for the organizer's wrap-up email.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrapUpStats {
+    pub participant_count: u64,
+    pub viewed_count: u64,
+    /// Times participants were shown their match, counting repeat views.
+    pub reveal_count: u64,
+    pub resend_count: u64,
+    pub reminder_count: u64,
+    /// Names of participants who never saw their match.
+    pub not_viewed: Vec<String>,
+}
+
+/// The participant emails of a draw, sent in the background (see `email_dispatch`).
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EmailJob {
+    pub id: EmailJobId,
+    #[serde(skip)]
+    pub game_id: GameId,
+    /// `None` for a template this version doesn't know, queued by a newer one.
+    #[serde(skip)]
+    pub template: Option<EmailTemplate>,
+    pub total: u64,
+    pub sent: u64,
+    pub failed: u64,
+    pub created_at: DateTime<Utc>,
+    /// When every email was attempted and the organizer was sent the confirmation.
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Which email an [`EmailJob`] sends. Jobs store its name and the recipients, and each email
+/// is rendered when it's sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailTemplate {
+    /// A participant's reveal link, after the draw.
+    ParticipantNotification,
+}
+
+impl EmailTemplate {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ParticipantNotification => "participant_notification",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "participant_notification" => Some(Self::ParticipantNotification),
+            _ => None,
+        }
+    }
+}
+
+/// An email from the site admin to the organizers of every active game, such as a notice of
+/// scheduled maintenance. Sent in the background like an [`EmailJob`].
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Announcement {
+    pub id: AnnouncementId,
+    pub subject: String,
+    pub message: String,
+    pub total: u64,
+    pub sent: u64,
+    pub failed: u64,
+    pub created_at: DateTime<Utc>,
+    /// When every email was attempted.
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// An organizer an announcement goes to, in the language of their latest game.
+#[derive(Debug, Clone)]
+pub struct AnnouncementRecipient {
+    pub email: EmailAddress,
+    pub locale: Locale,
+}
+
+/// Reminder emails sent to participants who haven't seen their match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderKind {
+    /// A few days after the draw.
+    AfterDraw,
+    /// A few days before the event.
+    BeforeEvent,
+}
+
+impl ReminderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AfterDraw => "after_draw",
+            Self::BeforeEvent => "before_event",
+        }
+    }
+}
+
+/// Why an address is on the suppression list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionReason {
+    /// The address permanently rejected an email. Nothing is sent to it anymore.
+    Bounce,
+    /// The recipient marked an email as spam. Only essential emails are sent.
+    Complaint,
+    /// The recipient unsubscribed. Only essential emails are sent.
+    Unsubscribe,
+}
+
+impl SuppressionReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Bounce => "bounce",
+            Self::Complaint => "complaint",
+            Self::Unsubscribe => "unsubscribe",
+        }
+    }
+
+    /// Whether essential emails, such as reveal links and verification codes, are
+    /// suppressed too, rather than only reminders and wrap-ups.
+    pub fn blocks_essential_emails(&self) -> bool {
+        *self == Self::Bounce
+    }
+}
+
+impl From<EmailEventKind> for SuppressionReason {
+    fn from(kind: EmailEventKind) -> Self {
+        match kind {
+            EmailEventKind::Bounce => Self::Bounce,
+            EmailEventKind::Complaint => Self::Complaint,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,69 +344,221 @@ pub struct Participant {
     pub name: String,
     pub email: EmailAddress,
     pub matched_with_id: Option<ParticipantId>,
-    pub view_token: ViewToken,
     pub has_viewed: bool,
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct AddParticipantRequest {
     pub name: String,
     pub email: EmailAddress,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AddParticipantResponse {
     pub participant_id: ParticipantId,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateParticipantRequest {
     pub name: Option<String>,
     pub email: Option<EmailAddress>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateGameRequest {
+    /// Omit to leave unchanged, `null` to remove the restriction.
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub allowed_email_domain: Option<Option<EmailDomain>>,
+    /// Omit to leave unchanged, `null` or blank to remove the message.
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub organizer_message: Option<Option<String>>,
+    /// Omit to leave unchanged, `null` or blank to remove the location.
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub event_location: Option<Option<String>>,
+    /// Omit to leave unchanged, `null` to make reveal links active right away.
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub reveal_at: Option<Option<DateTime<Utc>>>,
+    #[serde(default)]
+    pub timezone: Option<GameTimezone>,
+    #[serde(default)]
+    pub locale: Option<Locale>,
+    /// Lock or unlock the participant list.
+    #[serde(default)]
+    pub locked: Option<bool>,
+    #[serde(default)]
+    pub reveal_expires_after_event: Option<bool>,
+    #[serde(default)]
+    pub reveal_single_use: Option<bool>,
+    #[serde(default)]
+    pub reminders_enabled: Option<bool>,
+    #[serde(default)]
+    pub wrap_up_enabled: Option<bool>,
+    #[serde(default)]
+    pub digest_enabled: Option<bool>,
+    #[serde(default)]
+    pub reply_to_organizer: Option<bool>,
+    #[serde(default)]
+    pub open_tracking_enabled: Option<bool>,
+    /// Omit to leave unchanged, `null` or blank to use the built-in subject.
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub participant_email_subject: Option<Option<String>>,
+    /// Omit to leave unchanged, `null` or blank to use the built-in body.
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub participant_email_body: Option<Option<String>>,
+    /// Days before the event to remind participants of it; empty turns event reminders off.
+    #[serde(default)]
+    pub event_reminder_days: Option<Vec<u32>>,
+}
+
+/// A game with what its organizer dashboard shows, from `Database::game_summary`.
+#[derive(Debug)]
+pub struct GameStatus {
+    pub game: Game,
+    pub participants: Vec<ParticipantStatus>,
+    /// Participants in the game, whether or not they're listed.
+    pub participant_count: u64,
+    /// Participants matching the filter, across every page.
+    pub matching_count: u64,
+    /// Participants who saw their match.
+    pub viewed_count: u64,
+    /// Times the organizer resent every email at once, which is limited per game.
+    pub bulk_resend_count: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct GameStatusResponse {
     pub game: Game,
+    /// Event date formatted for display, e.g. "14 de dezembro de 2025".
+    pub event_date_formatted: String,
     pub participants: Vec<ParticipantStatus>,
+    pub participant_count: u64,
+    pub matching_count: u64,
+    pub viewed_count: u64,
+    pub bulk_resend_count: u64,
+    /// Only present when a page was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<u32>,
+}
+
+/// Which participants the game status lists. Every one of them when neither `page` nor
+/// `per_page` is given.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GameStatusQuery {
+    /// Page of participants, starting at 1.
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+    /// Only participants who saw their match (`true`) or who haven't (`false`).
+    pub viewed: Option<bool>,
+    /// `csv` for the participants as CSV. Otherwise chosen by the `Accept` header.
+    #[param(inline)]
+    pub format: Option<ExportFormat>,
 }
 
-#[derive(Debug, Serialize)]
+/// Participants to include in a game summary, in the order they joined.
+#[derive(Debug, Default)]
+pub struct ParticipantFilter {
+    pub viewed: Option<bool>,
+    /// At most this many, or all of them if `None`.
+    pub limit: Option<u32>,
+    pub offset: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ParticipantStatus {
     pub id: ParticipantId,
     pub name: String,
     pub email: EmailAddress,
     pub has_viewed: bool,
+    /// When the participant last saw their match, if it was recorded.
+    pub last_viewed_at: Option<DateTime<Utc>>,
+    /// On how many distinct devices the participant saw their match. More than one may mean
+    /// the link was shared or forwarded.
+    pub device_count: u64,
+    /// Why emails to the participant can't be delivered (`bounce` or `complaint`), as
+    /// reported by the email provider.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_undeliverable: Option<String>,
+    /// When the participant first opened an email, if the game tracks opens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_opened_at: Option<DateTime<Utc>>,
+    /// Whether the participant's email domain can't receive mail, likely a typo.
+    pub email_domain_unreachable: bool,
+    /// How many times the organizer resent the participant's email or reissued their link.
+    pub resend_count: u64,
+    /// When the participant joined the game.
+    pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct RevealResponse {
     pub game_name: String,
     pub event_date: NaiveDate,
+    pub event_date_formatted: String,
     pub your_name: String,
-    pub matched_name: String,
+    /// Only present once the participant confirms the reveal.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reveal_at: Option<DateTime<Utc>>,
+    /// Reveal time in the game's time zone, e.g. "24 de dezembro de 2025 às 20:00".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reveal_at_formatted: Option<String>,
+    /// Countdown for the client, so it does not depend on the device clock.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seconds_until_reveal: Option<u64>,
+    /// Path of the game's cover image, e.g. `/media/01JF...XYZ.png`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover_image_url: Option<String>,
+    /// Path of the printable card, once the participant confirms the reveal.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_url: Option<String>,
+    pub participant_count: u64,
+    /// How many participants have already seen their match (including this one).
+    pub viewed_count: u64,
+    /// Days from today until the event, in the game's time zone; negative once it has passed.
+    pub days_until_event: i64,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct RequestVerificationRequest {
     pub name: String,
     pub event_date: NaiveDate,
     pub organizer_email: EmailAddress,
+    #[serde(default)]
+    pub event_location: Option<String>,
+    #[serde(default)]
+    pub timezone: GameTimezone,
+    /// Language of the game's emails; defaults to the request's `Accept-Language`.
+    #[serde(default)]
+    pub locale: Option<Locale>,
+    /// Set once the organizer has confirmed their address after seeing a typo suggestion.
+    #[serde(default)]
+    pub skip_typo_check: bool,
+    /// Token from the CAPTCHA widget, required when CAPTCHA checks are enabled.
+    #[serde(default)]
+    pub captcha_token: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct RequestVerificationResponse {
-    pub verification_id: VerificationId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification_id: Option<VerificationId>,
+    /// Likely intended address, returned instead of sending a code when the domain looks mistyped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_email: Option<EmailAddress>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct VerifyCodeRequest {
     pub verification_id: VerificationId,
     pub code: VerificationCode,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct VerifyCodeResponse {
     pub success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -137,12 +571,53 @@ pub struct VerifyCodeResponse {
     pub attempts_remaining: Option<i32>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Whether an emailed link can still be used. Checking doesn't use it up.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LinkStatusResponse {
+    pub valid: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RequestRevealRecoveryRequest {
+    pub email: EmailAddress,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RequestRevealRecoveryResponse {
+    pub recovery_id: RecoveryId,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VerifyRevealRecoveryRequest {
+    pub recovery_id: RecoveryId,
+    pub code: VerificationCode,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VerifyRevealRecoveryResponse {
+    pub success: bool,
+    /// The participant's reveal links, one per drawn game they take part in.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reveals: Vec<RecoveredReveal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attempts_remaining: Option<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecoveredReveal {
+    pub game_name: String,
+    pub event_date: NaiveDate,
+    pub view_token: ViewToken,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct ResendVerificationRequest {
     pub verification_id: VerificationId,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ResendVerificationResponse {
     pub success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -150,16 +625,57 @@ pub struct ResendVerificationResponse {
 }
 
 impl Game {
+    /// Creates a game whose admin token nobody knows. Set `admin_token_hash` to the hash of
+    /// a generated [`AdminToken`] to hand out a legacy admin link.
     pub fn new(name: String, event_date: NaiveDate, organizer_email: EmailAddress) -> Self {
         Self {
             id: GameId::new(),
             name,
             event_date,
             organizer_email,
-            admin_token: AdminToken::generate(),
+            admin_token_hash: AdminToken::generate().hash(),
             created_at: Utc::now(),
             drawn: false,
+            allowed_email_domain: None,
+            frozen_at: None,
+            frozen_reason: None,
+            deleted_at: None,
+            organizer_message: None,
+            event_location: None,
+            reveal_at: None,
+            timezone: GameTimezone::default(),
+            locale: Locale::default(),
+            locked: false,
+            reveal_expires_after_event: false,
+            reveal_single_use: false,
+            cover_image: None,
+            drawn_at: None,
+            reminders_enabled: true,
+            wrap_up_enabled: false,
+            wrap_up_sent_at: None,
+            participant_email_subject: None,
+            participant_email_body: None,
+            event_reminder_days: Vec::new(),
+            digest_enabled: false,
+            digest_sent_at: None,
+            reply_to_organizer: false,
+            open_tracking_enabled: false,
+        }
+    }
+
+    /// The event reminder due on a day in the game's time zone: the one for the fewest
+    /// days before the event that have been reached. Reminders passed by the time of a
+    /// late draw are skipped, except for the closest one.
+    pub fn due_event_reminder(&self, today: NaiveDate) -> Option<u32> {
+        let days_left = (self.event_date - today).num_days();
+        if days_left < 0 {
+            return None;
         }
+        self.event_reminder_days
+            .iter()
+            .copied()
+            .filter(|&days| i64::from(days) >= days_left)
+            .min()
     }
 }
 
@@ -171,33 +687,409 @@ impl Participant {
             name,
             email,
             matched_with_id: None,
-            view_token: ViewToken::generate(),
             has_viewed: false,
             created_at: Utc::now(),
         }
     }
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct OrganizerLoginRequest {
+    pub email: EmailAddress,
+    /// Game to open after logging in, when the login started from a game page.
+    #[serde(default)]
+    pub game_id: Option<GameId>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrganizerLoginResponse {
+    /// Game to open, when the login started from a game page. Otherwise, the organizer's
+    /// list of games.
+    pub game_id: Option<GameId>,
+}
+
 // Site admin request/response models
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct SiteAdminLoginRequest {
     pub password: String,
+    /// One-time password or recovery code, once two-step verification is enabled
+    #[serde(default)]
+    pub totp_code: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum SiteAdminLoginResponse {
+    Session {
+        session_token: String,
+        expires_at: DateTime<Utc>,
+    },
+    /// The password is correct, but a one-time password is needed as well
+    TotpRequired { totp_required: bool },
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TotpStatusResponse {
+    pub enabled: bool,
+    pub recovery_codes_remaining: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TotpEnrollmentResponse {
+    /// Base32 secret, for entering in the authenticator app by hand
+    pub secret: String,
+    pub otpauth_url: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConfirmTotpRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConfirmTotpResponse {
+    /// Shown only once; only their hashes are stored
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DisableTotpRequest {
+    pub password: String,
+    /// One-time password or recovery code
+    pub code: String,
+}
+
+/// A passkey registered for site admin login.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SiteAdminPasskey {
+    pub id: PasskeyId,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// An address, or a domain with its subdomains, that the site admin banned from creating
+/// games.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EmailBan {
+    pub id: EmailBanId,
+    /// An address, or a domain if it has no `@`.
+    pub value: String,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A stored passkey, for starting and finishing ceremonies.
+#[derive(Debug)]
+pub struct PasskeyCredential {
+    pub id: PasskeyId,
+    pub passkey: Passkey,
 }
 
-#[derive(Debug, Serialize)]
-pub struct SiteAdminLoginResponse {
-    pub session_token: String,
+/// The credential is the browser's `PublicKeyCredential`, as JSON.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterPasskeyRequest {
+    pub name: String,
+    #[schema(value_type = Object)]
+    pub credential: RegisterPublicKeyCredential,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PasskeyLoginRequest {
+    #[schema(value_type = Object)]
+    pub credential: PublicKeyCredential,
+}
+
+/// A site admin session that hasn't expired.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdminSession {
+    pub id: AdminSessionId,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
     pub expires_at: DateTime<Utc>,
+    /// Whether this is the session making the request
+    pub current: bool,
 }
 
-#[derive(Debug, Deserialize)]
+/// How a site admin password login attempt ended, recorded for audit and lockouts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginOutcome {
+    Success,
+    WrongPassword,
+    /// The password was right, but the one-time password or recovery code wasn't.
+    WrongCode,
+    /// Rejected without checking the password, because of too many failures.
+    LockedOut,
+}
+
+impl LoginOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::WrongPassword => "wrong_password",
+            Self::WrongCode => "wrong_code",
+            Self::LockedOut => "locked_out",
+        }
+    }
+}
+
+/// A recorded site admin password login attempt.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SiteAdminLoginAttempt {
+    pub outcome: String,
+    pub ip_prefix: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct ChangePasswordRequest {
     pub current_password: String,
     pub new_password: String,
 }
 
-#[derive(Debug, Deserialize)]
+/// Report about a game, filed by someone who received unwanted emails.
+///
+/// Either the view token from the received email or the game ID identifies the game.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateReportRequest {
+    #[serde(default)]
+    pub view_token: Option<ViewToken>,
+    #[serde(default)]
+    pub game_id: Option<GameId>,
+    pub reason: String,
+}
+
+/// An unresolved abuse report, as shown in the site admin dashboard.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AbuseReportSummary {
+    pub id: ReportId,
+    pub game_id: GameId,
+    pub game_name: String,
+    pub game_frozen: bool,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MyGamesResponse {
+    pub email: EmailAddress,
+    pub games: Vec<GameSummary>,
+}
+
+/// What to test from the site admin dashboard. Without a body, the connection is tested and
+/// a test email sent to the site admin.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct EmailTestRequest {
+    /// Whether to send a test email after testing the connection.
+    #[serde(default = "default_send_test_email")]
+    pub send: bool,
+    /// Where to send the test email, instead of the site admin.
+    #[serde(default)]
+    pub to: Option<EmailAddress>,
+}
+
+impl Default for EmailTestRequest {
+    fn default() -> Self {
+        Self {
+            send: default_send_test_email(),
+            to: None,
+        }
+    }
+}
+
+fn default_send_test_email() -> bool {
+    true
+}
+
+/// Outcome of an SMTP test run from the site admin dashboard.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SmtpTestReport {
+    pub smtp_server: String,
+    /// Where the test email went, if one was sent.
+    pub recipient: Option<String>,
+    /// Time to connect, negotiate TLS and authenticate.
+    pub connection_ms: Option<u64>,
+    /// Time to send the test message over a fresh connection.
+    pub send_ms: Option<u64>,
+    /// The transport's error, with its causes.
+    pub error: Option<String>,
+    /// Which step failed: `connection` or `send`.
+    pub failed_step: Option<&'static str>,
+    /// What to check to fix the failure.
+    pub hint: Option<&'static str>,
+}
+
+/// Site-wide numbers for the site admin dashboard.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SiteStats {
+    pub totals: SiteStatsTotals,
+    /// One entry per day (UTC), oldest first, including days without activity.
+    pub trends: Vec<SiteStatsDay>,
+}
+
+/// Counts over everything in the database. Expired games and verifications are cleaned up,
+/// so these don't cover the site's whole history.
+#[derive(Debug, Default, Serialize, PartialEq, Eq, ToSchema)]
+pub struct SiteStatsTotals {
+    /// Games not deleted, drawn or not.
+    pub games: u64,
+    pub drawn_games: u64,
+    /// Games deleted but not purged yet.
+    pub deleted_games: u64,
+    pub participants: u64,
+    /// Participants who saw their match.
+    pub viewed_participants: u64,
+    /// Times participants were shown their match, counting repeat views.
+    pub reveals: u64,
+    /// Draw emails sent and failed.
+    pub emails_sent: u64,
+    pub emails_failed: u64,
+    pub verifications: u64,
+    pub completed_verifications: u64,
+}
+
+#[derive(Debug, Default, Serialize, PartialEq, Eq, ToSchema)]
+pub struct SiteStatsDay {
+    pub date: NaiveDate,
+    pub games_created: u64,
+    pub games_drawn: u64,
+    pub reveals: u64,
+    pub verifications: u64,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RestoreBackupQuery {
+    /// Must repeat the confirmation phrase shown to the site admin.
+    pub confirmation: String,
+}
+
+/// Whether a request can go ahead with its `Idempotency-Key`, from
+/// `Database::claim_idempotency_key`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum IdempotencyKeyState {
+    /// The key is new: the request is handled, and its response stored.
+    Claimed,
+    /// The key was used by a request with this hash, whose (encrypted) response is stored
+    /// once it's done.
+    Used {
+        request_hash: String,
+        response: Option<Vec<u8>>,
+    },
+}
+
+/// What's deployed, to check a deploy went through or show it in the frontend.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VersionInfo {
+    pub version: &'static str,
+    /// Commit the binary was built from, if it was known at build time.
+    pub commit: Option<&'static str>,
+    pub built_at: Option<DateTime<Utc>>,
+    pub features: EnabledFeatures,
+}
+
+/// Optional features turned on by the configuration.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EnabledFeatures {
+    /// `smtp`, `sendgrid`, `mailgun` or `ses`, or `log` or `file` when emails aren't sent.
+    pub email_mode: &'static str,
+    pub captcha: Option<CaptchaProvider>,
+    pub backups: bool,
+    /// Where uploaded media is stored: `disk` or `s3`.
+    pub media_storage: &'static str,
+    pub redis: bool,
+    pub error_reporting: bool,
+    pub legacy_admin_tokens: bool,
+    pub check_email_domains: bool,
+}
+
+/// Whether the server can handle requests, for the orchestrator's readiness checks.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Readiness {
+    /// Whether the database answered a query.
+    pub database: bool,
+    /// Where emails are sent. The server doesn't start without a valid email configuration,
+    /// so there always is one.
+    pub email_transport: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SiteStatsQuery {
+    /// Days of trends, up to today.
+    #[serde(default = "default_stats_days")]
+    pub days: u32,
+}
+
+fn default_stats_days() -> u32 {
+    30
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FreezeGameRequest {
+    pub reason: String,
+}
+
+/// The latest run of a background task, such as a cleanup or the reminders.
+#[derive(Debug, Serialize, PartialEq, Eq, ToSchema)]
+pub struct JobRun {
+    /// Name of the task, e.g. `cleanup_games`.
+    pub job: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// Items the run handled (e.g. games deleted or emails sent), unless it failed.
+    pub processed: Option<u64>,
+    /// Why the run failed.
+    pub error: Option<String>,
+}
+
+/// How SQLite runs one of the hot queries, from `EXPLAIN QUERY PLAN`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QueryPlan {
+    pub query: String,
+    pub sql: String,
+    /// Steps of the plan, indented under the step they belong to.
+    pub plan: Vec<String>,
+}
+
+/// Settings the site admin can change without a restart.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SiteSettings {
+    /// Days after the event before a game and its participants are deleted.
+    pub game_retention_days: u32,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateSiteSettingsRequest {
+    pub game_retention_days: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateEmailBanRequest {
+    /// An address, or a domain to ban it and its subdomains.
+    pub value: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateEmailBanRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateAnnouncementRequest {
+    pub subject: String,
+    pub message: String,
+    /// Only count the recipients, without sending anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchGamesQuery {
     #[serde(default)]
     pub search: Option<String>,
@@ -207,11 +1099,55 @@ pub struct SearchGamesQuery {
     pub offset: u64,
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportGameQuery {
+    #[serde(default)]
+    #[param(inline)]
+    pub format: ExportFormat,
+    /// Include who drew whom. Off unless the organizer explicitly confirms it.
+    #[serde(default)]
+    pub include_matches: bool,
+}
+
+/// Which email to preview.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailPreviewType {
+    Participant,
+    Organizer,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailPreviewFormat {
+    #[default]
+    Html,
+    Text,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EmailPreviewQuery {
+    #[serde(rename = "type")]
+    #[param(inline)]
+    pub email_type: EmailPreviewType,
+    #[serde(default)]
+    #[param(inline)]
+    pub format: EmailPreviewFormat,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmailPreviewResponse {
+    pub subject: String,
+    pub body: String,
+}
+
 fn default_limit() -> u32 {
     20
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SearchGamesResponse {
     pub games: Vec<GameSummary>,
     pub total: u64,
@@ -219,7 +1155,7 @@ pub struct SearchGamesResponse {
     pub offset: u64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct GameSummary {
     pub id: GameId,
     pub name: String,
@@ -227,12 +1163,96 @@ pub struct GameSummary {
     pub organizer_email: EmailAddress,
     pub created_at: DateTime<Utc>,
     pub drawn: bool,
+    pub frozen: bool,
+    pub deleted: bool,
     pub participant_count: u64,
 }
 
-#[derive(Debug, Serialize)]
+impl GameSummary {
+    pub fn new(game: Game, participant_count: u64) -> Self {
+        Self {
+            id: game.id,
+            name: game.name,
+            event_date: game.event_date,
+            organizer_email: game.organizer_email,
+            created_at: game.created_at,
+            drawn: game.drawn,
+            frozen: game.frozen_at.is_some(),
+            deleted: game.deleted_at.is_some(),
+            participant_count,
+        }
+    }
+}
+
+/// Games matching all of the given filters are deleted. At least one filter is required.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkDeleteGamesRequest {
+    #[serde(default)]
+    pub undrawn_only: bool,
+    #[serde(default)]
+    pub older_than_days: Option<u32>,
+    #[serde(default)]
+    pub organizer_domain: Option<EmailDomain>,
+    /// Only list the games that would be deleted. On unless explicitly disabled.
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkDeleteGamesResponse {
+    pub dry_run: bool,
+    pub games: Vec<GameSummary>,
+}
+
+/// Everything about a game a site admin can see, short of who drew whom.
+#[derive(Debug, Serialize, ToSchema)]
 pub struct GameDetailResponse {
     pub game: Game,
-    pub participants: Vec<Participant>,
+    pub participants: Vec<ParticipantStatus>,
     pub participant_count: u64,
+    pub match_accesses: Vec<MatchAccess>,
+    /// Each time the draw emails were sent, newest first.
+    pub email_jobs: Vec<EmailJob>,
+    /// Emails the organizer sent again, newest first.
+    pub email_resends: Vec<EmailResend>,
+    /// How many times the organizer resent the emails of every participant.
+    pub bulk_resends: u64,
+}
+
+/// An email the organizer sent again, to one participant or to all of them.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmailResend {
+    /// Who it was sent to, for individual resends.
+    pub participant_id: Option<ParticipantId>,
+    /// `individual`, `reissue` (with a new link) or `bulk`.
+    pub resend_type: String,
+    pub resent_at: DateTime<Utc>,
+}
+
+/// A time the organizer looked at who drew whom, either on screen or in an export.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MatchAccess {
+    pub access_type: String,
+    pub accessed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RevealMatchesRequest {
+    /// Must repeat the confirmation phrase shown to the organizer.
+    pub confirmation: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevealMatchesResponse {
+    pub matches: Vec<MatchEntry>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MatchEntry {
+    pub name: String,
+    pub matched_name: String,
 }
@@ -0,0 +1,84 @@
+//! Checks whether an email domain can receive mail, by looking up its MX records.
+//!
+//! Catches typos like `gmial.com` when participants are added, long before the draw emails
+//! would bounce. Lookups use the nameservers in `/etc/resolv.conf`, through hickory's
+//! resolver, which caches answers and retries other nameservers.
+
+use anyhow::{Context, Result};
+use hickory_resolver::TokioResolver;
+use std::time::Duration;
+
+/// How long each nameserver gets to answer.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Looks up whether email domains can receive mail.
+#[derive(Clone)]
+pub struct MailDomainChecker {
+    resolver: TokioResolver,
+}
+
+impl MailDomainChecker {
+    /// A checker using the system's resolver configuration.
+    pub fn from_system_conf() -> Result<Self> {
+        let mut builder =
+            TokioResolver::builder_tokio().context("reading the system resolver configuration")?;
+        builder.options_mut().timeout = TIMEOUT;
+        Ok(Self {
+            resolver: builder.build(),
+        })
+    }
+
+    /// Whether mail can be delivered to `domain`.
+    ///
+    /// Fails when the answer isn't known, e.g. when no nameserver can be reached, so that
+    /// addresses are only flagged when the domain definitely can't receive mail.
+    pub async fn domain_accepts_mail(&self, domain: &str) -> Result<bool> {
+        // Address literals, like `user@[192.0.2.1]`, don't need a lookup
+        if domain.starts_with('[') {
+            return Ok(true);
+        }
+        // Fully qualified, so the resolver doesn't try its search domains
+        let name = format!("{}.", domain.trim_end_matches('.'));
+
+        match self.resolver.mx_lookup(name.as_str()).await {
+            // A "null MX" record declares that the domain accepts no mail (RFC 7505)
+            Ok(records) if records.iter().next().is_some() => {
+                return Ok(!records.iter().all(|mx| mx.exchange().is_root()));
+            }
+            Ok(_) => {}
+            Err(e) if e.is_nx_domain() => return Ok(false),
+            Err(e) if !e.is_no_records_found() => {
+                return Err(e).with_context(|| format!("looking up the MX records of {domain}"));
+            }
+            Err(_) => {}
+        }
+
+        // Without MX records, mail goes to the domain's own address (RFC 5321, section 5.1)
+        match self.resolver.lookup_ip(name.as_str()).await {
+            Ok(addresses) => Ok(addresses.iter().next().is_some()),
+            Err(e) if e.is_no_records_found() => Ok(false),
+            Err(e) => Err(e).with_context(|| format!("looking up the addresses of {domain}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hickory_resolver::config::ResolverConfig;
+    use hickory_resolver::name_server::TokioConnectionProvider;
+
+    #[tokio::test]
+    async fn test_address_literals_skip_the_lookup() {
+        // No nameservers, so any lookup would fail
+        let checker = MailDomainChecker {
+            resolver: TokioResolver::builder_with_config(
+                ResolverConfig::new(),
+                TokioConnectionProvider::default(),
+            )
+            .build(),
+        };
+        assert!(checker.domain_accepts_mail("[192.0.2.1]").await.unwrap());
+        assert!(checker.domain_accepts_mail("example.com").await.is_err());
+    }
+}
@@ -0,0 +1,464 @@
+//! OpenAPI description of the HTTP API, served at `/api/openapi.json` (and browsable with
+//! Swagger UI at `/api/docs` when running locally).
+//!
+//! The document is derived by `utoipa` from the `#[utoipa::path]` attribute of each handler
+//! in `routes` and the models deriving `ToSchema`, so it follows the code. Every operation
+//! can fail with an [`ErrorBody`], which is added here rather than on each handler. Tests
+//! check that every route of the router is documented.
+
+use crate::routes::{self, ErrorBody};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::{ContentBuilder, OpenApi as Document, Ref, ResponseBuilder};
+use utoipa::{Modify, OpenApi};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Amigo Oculto API",
+        description = "Errors are JSON objects with a message in `error`, a stable `code` \
+            (such as `NOT_FOUND`, `RATE_LIMITED`, `GAME_ALREADY_DRAWN` or \
+            `PARTICIPANT_LIMIT`; see `AppError::code`) to branch on, and the `request_id` \
+            to quote when reporting them."
+    ),
+    paths(
+        routes::health_check,
+        routes::api_health_check,
+        routes::readiness_check,
+        routes::get_metrics,
+        routes::get_version,
+        routes::get_captcha,
+        routes::get_openapi,
+        routes::get_api_docs,
+        routes::request_verification,
+        routes::verify_code,
+        routes::resend_verification,
+        routes::verification_link_status,
+        routes::confirm_verification_link,
+        routes::get_game_status,
+        routes::update_game,
+        routes::delete_game,
+        routes::draw_game,
+        routes::get_email_job,
+        routes::follow_game_events,
+        routes::resend_all_emails,
+        routes::export_game,
+        routes::preview_email,
+        routes::upload_cover_image,
+        routes::delete_cover_image,
+        routes::reveal_matches,
+        routes::add_participant,
+        routes::update_participant,
+        routes::resend_participant_email,
+        routes::reissue_view_token,
+        routes::get_reveal,
+        routes::confirm_reveal,
+        routes::get_reveal_card,
+        routes::request_reveal_recovery,
+        routes::verify_reveal_recovery,
+        routes::request_organizer_login,
+        routes::organizer_login_link_status,
+        routes::organizer_login,
+        routes::organizer_logout,
+        routes::get_my_games,
+        routes::create_report,
+        routes::email_events_webhook,
+        routes::unsubscribe_email,
+        routes::track_email_open,
+        routes::get_media,
+        routes::site_admin_login,
+        routes::site_admin_passkey_login_options,
+        routes::site_admin_passkey_login,
+        routes::site_admin_logout,
+        routes::site_admin_change_password,
+        routes::site_admin_totp_status,
+        routes::site_admin_enroll_totp,
+        routes::site_admin_confirm_totp,
+        routes::site_admin_disable_totp,
+        routes::site_admin_list_sessions,
+        routes::site_admin_revoke_session,
+        routes::site_admin_list_login_attempts,
+        routes::site_admin_list_passkeys,
+        routes::site_admin_passkey_registration_options,
+        routes::site_admin_register_passkey,
+        routes::site_admin_delete_passkey,
+        routes::site_admin_search_games,
+        routes::site_admin_bulk_delete_games,
+        routes::site_admin_import_game,
+        routes::site_admin_get_game,
+        routes::site_admin_delete_game,
+        routes::site_admin_restore_game,
+        routes::site_admin_freeze_game,
+        routes::site_admin_unfreeze_game,
+        routes::site_admin_reset_draw,
+        routes::site_admin_regenerate_admin_link,
+        routes::site_admin_resend_welcome,
+        routes::site_admin_email_test,
+        routes::site_admin_list_announcements,
+        routes::site_admin_create_announcement,
+        routes::site_admin_list_email_bans,
+        routes::site_admin_create_email_ban,
+        routes::site_admin_update_email_ban,
+        routes::site_admin_delete_email_ban,
+        routes::site_admin_list_reports,
+        routes::site_admin_resolve_report,
+        routes::site_admin_stats,
+        routes::site_admin_list_job_runs,
+        routes::site_admin_query_plans,
+        routes::site_admin_create_backup,
+        routes::site_admin_restore_backup,
+        routes::site_admin_get_settings,
+        routes::site_admin_update_settings,
+    ),
+    components(schemas(ErrorBody)),
+    modifiers(&SecuritySchemes, &ErrorResponses)
+)]
+struct ApiDoc;
+
+/// The OpenAPI document.
+pub fn document() -> Document {
+    ApiDoc::openapi()
+}
+
+/// How callers authenticate, as named in the `security` of the handlers.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut Document) {
+        let bearer = |description: &str| {
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some(description))
+                    .build(),
+            )
+        };
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "adminToken",
+            bearer("The game's admin token, from the organizer's admin link"),
+        );
+        components.add_security_scheme(
+            "organizerSession",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new("organizer_session"))),
+        );
+        components.add_security_scheme(
+            "siteAdminSession",
+            bearer("Session token from `POST /api/site-admin/login`"),
+        );
+        components.add_security_scheme("metricsToken", bearer("`METRICS_TOKEN`"));
+        components.add_security_scheme(
+            "emailWebhookSecret",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Basic)
+                    .description(Some(
+                        "`EMAIL_WEBHOOK_SECRET` as the password, with any user name. Also \
+                         accepted as a Bearer token",
+                    ))
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// Documents the error body of every operation, as its default response.
+struct ErrorResponses;
+
+impl Modify for ErrorResponses {
+    fn modify(&self, openapi: &mut Document) {
+        let error = ResponseBuilder::new()
+            .description("The request failed")
+            .content(
+                "application/json",
+                ContentBuilder::new()
+                    .schema(Some(Ref::from_schema_name("ErrorBody")))
+                    .build(),
+            )
+            .build();
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [
+                &mut item.get,
+                &mut item.put,
+                &mut item.post,
+                &mut item.delete,
+                &mut item.patch,
+            ];
+            for operation in operations.into_iter().flatten() {
+                operation
+                    .responses
+                    .responses
+                    .insert("default".to_string(), error.clone().into());
+            }
+        }
+    }
+}
+
+/// Swagger UI for the document, loaded from a CDN.
+pub const SWAGGER_UI: &str = r##"<!doctype html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>Amigo Oculto API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: "/api/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>
+"##;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::captcha::{CaptchaProvider, CaptchaWidget};
+    use crate::models::*;
+    use crate::token::{GameId, ParticipantId, VerificationId};
+    use chrono::{NaiveDate, Utc};
+    use std::collections::BTreeSet;
+
+    /// Routes registered in `routes::make`, as (method, full path), read from its source.
+    fn router_routes() -> BTreeSet<(String, String)> {
+        let source = include_str!("routes.rs");
+        let start = source.find("pub fn make(").unwrap();
+        let end = source[start..].find("pub struct AppState").unwrap() + start;
+        let source = &source[start..end];
+
+        let mut routes = BTreeSet::new();
+        let mut router = "";
+        let mut rest = source;
+        while let Some(i) = rest.find(".route(") {
+            // The router being built is the last one assigned before the route
+            if let Some(let_at) = rest[..i].rfind("let ") {
+                let name = rest[let_at + 4..].split([' ', '=', ':']).next().unwrap();
+                router = name;
+            }
+            rest = &rest[i + ".route(".len()..];
+
+            // Arguments of `.route(...)`: the path, then the method router
+            let mut depth = 1;
+            let len = rest
+                .char_indices()
+                .find(|&(_, c)| {
+                    match c {
+                        '(' => depth += 1,
+                        ')' => depth -= 1,
+                        _ => {}
+                    }
+                    depth == 0
+                })
+                .unwrap()
+                .0;
+            let arguments = &rest[..len];
+            let path = arguments.split('"').nth(1).unwrap();
+            let prefix = match router {
+                "site_admin_protected" => "/api/site-admin",
+                "media_routes" => "/media",
+                "probe_routes" => "",
+                _ => "/api",
+            };
+            for method in ["get", "post", "put", "patch", "delete"] {
+                let call = format!("{method}(");
+                let called = arguments.match_indices(&call).any(|(at, _)| {
+                    let before = arguments[..at].chars().next_back().unwrap();
+                    !(before.is_alphanumeric() || before == '_')
+                });
+                if called {
+                    routes.insert((method.to_uppercase(), format!("{prefix}{path}")));
+                }
+            }
+        }
+        routes
+    }
+
+    /// The document, as JSON.
+    fn json() -> serde_json::Value {
+        serde_json::to_value(document()).unwrap()
+    }
+
+    #[test]
+    fn test_every_route_is_documented() {
+        let document = json();
+        let mut documented = BTreeSet::new();
+        for (path, item) in document["paths"].as_object().unwrap() {
+            for method in item.as_object().unwrap().keys() {
+                documented.insert((method.to_uppercase(), path.clone()));
+            }
+        }
+
+        let routes = router_routes();
+        assert!(routes.contains(&("GET".to_string(), "/api/site-admin/stats".to_string())));
+        assert!(routes.contains(&("GET".to_string(), "/readyz".to_string())));
+        let undocumented: Vec<_> = routes.difference(&documented).collect();
+        assert!(undocumented.is_empty(), "undocumented: {undocumented:?}");
+        let missing: Vec<_> = documented.difference(&routes).collect();
+        assert!(missing.is_empty(), "not routed: {missing:?}");
+    }
+
+    #[test]
+    fn test_document_references() {
+        let document = json();
+        let schemas = document["components"]["schemas"].as_object().unwrap();
+        let text = document.to_string();
+        for reference in text.split("\"#/components/schemas/").skip(1) {
+            let name = reference.split('"').next().unwrap();
+            assert!(schemas.contains_key(name), "missing schema {name}");
+        }
+
+        let status = &document["paths"]["/api/games/{game_id}"]["get"];
+        assert_eq!(status["parameters"][0]["name"], "game_id");
+        assert_eq!(status["parameters"][0]["in"], "path");
+        assert!(status["security"].is_array());
+        assert!(
+            document["paths"]["/api/reveal/{view_token}"]["get"]
+                .get("security")
+                .is_none()
+        );
+    }
+
+    /// Checks the schema lists exactly the fields of a serialized value with every optional
+    /// field set.
+    fn assert_documented(name: &str, value: impl serde::Serialize) {
+        let document = json();
+        let schema = &document["components"]["schemas"][name];
+        let documented: BTreeSet<_> = schema["properties"]
+            .as_object()
+            .unwrap_or_else(|| panic!("no schema for {name}"))
+            .keys()
+            .cloned()
+            .collect();
+        let serialized: BTreeSet<_> = serde_json::to_value(value)
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        assert_eq!(documented, serialized, "fields of {name}");
+    }
+
+    #[test]
+    fn test_schemas_match_models() {
+        let event_date = NaiveDate::from_ymd_opt(2026, 12, 24).unwrap();
+        let game = Game::new(
+            "Família".to_string(),
+            event_date,
+            "organizer@example.com".parse().unwrap(),
+        );
+        assert_documented("Game", &game);
+
+        let participant = ParticipantStatus {
+            id: ParticipantId::new(),
+            name: "Ana".to_string(),
+            email: "ana@example.com".parse().unwrap(),
+            has_viewed: true,
+            last_viewed_at: Some(Utc::now()),
+            device_count: 1,
+            email_undeliverable: Some("bounce".to_string()),
+            email_opened_at: Some(Utc::now()),
+            email_domain_unreachable: false,
+            resend_count: 0,
+            created_at: Utc::now(),
+        };
+        assert_documented("ParticipantStatus", &participant);
+        assert_documented(
+            "GameStatusResponse",
+            GameStatusResponse {
+                game,
+                event_date_formatted: "24 de dezembro de 2026".to_string(),
+                participants: vec![participant],
+                participant_count: 1,
+                matching_count: 1,
+                viewed_count: 1,
+                bulk_resend_count: 0,
+                page: Some(1),
+                per_page: Some(20),
+            },
+        );
+        assert_documented(
+            "RevealResponse",
+            RevealResponse {
+                game_name: "Família".to_string(),
+                event_date,
+                event_date_formatted: "24 de dezembro de 2026".to_string(),
+                your_name: "Ana".to_string(),
+                matched_name: Some("Bruno".to_string()),
+                reveal_at: Some(Utc::now()),
+                reveal_at_formatted: Some("24 de dezembro de 2026 às 20:00".to_string()),
+                seconds_until_reveal: Some(60),
+                cover_image_url: Some("/media/cover.png".to_string()),
+                card_url: Some("/api/reveal/token/card".to_string()),
+                participant_count: 2,
+                viewed_count: 1,
+                days_until_event: 3,
+            },
+        );
+        assert_documented(
+            "AddParticipantResponse",
+            AddParticipantResponse {
+                participant_id: ParticipantId::new(),
+            },
+        );
+        assert_documented(
+            "RequestVerificationResponse",
+            RequestVerificationResponse {
+                verification_id: Some(VerificationId::new()),
+                suggested_email: Some("ana@gmail.com".parse().unwrap()),
+            },
+        );
+        assert_documented(
+            "VerifyCodeResponse",
+            VerifyCodeResponse {
+                success: true,
+                game_id: Some(GameId::new()),
+                admin_token: Some("token".to_string()),
+                error: Some("Código inválido".to_string()),
+                attempts_remaining: Some(4),
+            },
+        );
+        assert_documented(
+            "ResendVerificationResponse",
+            ResendVerificationResponse {
+                success: false,
+                error: Some("Aguarde".to_string()),
+            },
+        );
+        let features = EnabledFeatures {
+            email_mode: "smtp",
+            captcha: Some(CaptchaProvider::Turnstile),
+            backups: true,
+            media_storage: "s3",
+            redis: false,
+            error_reporting: false,
+            legacy_admin_tokens: true,
+            check_email_domains: true,
+        };
+        assert_documented("EnabledFeatures", &features);
+        assert_documented(
+            "VersionInfo",
+            VersionInfo {
+                version: "1.0.0",
+                commit: Some("abc123"),
+                built_at: Some(Utc::now()),
+                features,
+            },
+        );
+        assert_documented(
+            "Readiness",
+            Readiness {
+                database: true,
+                email_transport: "smtp.example.com:587".to_string(),
+            },
+        );
+        assert_documented(
+            "CaptchaWidget",
+            CaptchaWidget {
+                provider: CaptchaProvider::HCaptcha,
+                site_key: "key".to_string(),
+            },
+        );
+    }
+}
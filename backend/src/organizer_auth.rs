@@ -0,0 +1,249 @@
+//! Organizer authentication.
+//!
+//! Organizers can access a game either with its admin token (the `?admin_token=` link in
+//! the welcome email) or with a session cookie obtained through a magic login link sent to the
+//! organizer's email address. Admin tokens are being phased out and can be disabled with
+//! `LEGACY_ADMIN_TOKENS=false`.
+//!
+//! API requests should send the admin token as `Authorization: Bearer <token>`, since query
+//! strings end up in access logs and `Referer` headers. The `?admin_token=` query parameter
+//! is still accepted for older clients.
+
+use crate::{
+    db::Database,
+    i18n,
+    models::Game,
+    routes::{AppError, AppState},
+    token::{AdminToken, EmailAddress, GameId, OrganizerSessionToken, TokenHash},
+};
+use anyhow::Result;
+use axum::{
+    extract::{FromRequestParts, Query, RawPathParams},
+    http::{HeaderMap, HeaderValue, header, request::Parts},
+};
+use serde::Deserialize;
+use std::{
+    convert::Infallible,
+    sync::{Arc, LazyLock},
+};
+
+/// Name of the cookie holding the organizer session token.
+const SESSION_COOKIE: &str = "organizer_session";
+
+/// How long an organizer session lasts after logging in.
+pub const SESSION_DURATION_DAYS: i64 = 30;
+
+/// How long a magic login link remains valid.
+pub const LOGIN_LINK_DURATION_MINUTES: i64 = 15;
+
+/// The game of a `/games/{game_id}/...` route, checked to be accessible by the organizer.
+///
+/// Rejects the request with [`AppError::AccessDenied`] unless the request's credentials
+/// grant organizer access to the game in the `game_id` path parameter.
+#[derive(Debug, Clone)]
+pub struct OrganizerAuth(pub Game);
+
+impl FromRequestParts<Arc<AppState>> for OrganizerAuth {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let params = RawPathParams::from_request_parts(parts, state)
+            .await
+            .map_err(|_| AppError::AccessDenied)?;
+        let game_id: GameId = params
+            .iter()
+            .find_map(|(name, value)| (name == "game_id").then(|| value.parse()))
+            .ok_or(AppError::AccessDenied)?
+            .map_err(|_| AppError::AccessDenied)?;
+
+        let Ok(credentials) = OrganizerCredentials::from_request_parts(parts, state).await;
+        let game = credentials.require(&state.db, game_id).await?;
+        i18n::use_game_locale(game.locale);
+        Ok(Self(game))
+    }
+}
+
+/// Whatever the request carries that may grant organizer access to a game.
+///
+/// Extraction never fails; use [`OrganizerCredentials::authorize`] to check access.
+#[derive(Debug, Clone)]
+pub struct OrganizerCredentials {
+    admin_token: Option<AdminToken>,
+    session_token: Option<OrganizerSessionToken>,
+}
+
+#[derive(Deserialize)]
+struct AdminTokenQuery {
+    admin_token: Option<AdminToken>,
+}
+
+impl FromRequestParts<Arc<AppState>> for OrganizerCredentials {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let admin_token = if state.legacy_admin_tokens {
+            admin_token_from_headers(&parts.headers)
+                .or_else(|| {
+                    Query::<AdminTokenQuery>::try_from_uri(&parts.uri)
+                        .ok()
+                        .and_then(|Query(query)| query.admin_token)
+                })
+                .filter(|token| !token.as_str().is_empty())
+        } else {
+            None
+        };
+
+        Ok(Self {
+            admin_token,
+            session_token: session_token_from_headers(&parts.headers),
+        })
+    }
+}
+
+/// Hash of a random admin token, compared against when the game doesn't exist.
+static MISSING_GAME_TOKEN_HASH: LazyLock<TokenHash> =
+    LazyLock::new(|| AdminToken::generate().hash());
+
+impl OrganizerCredentials {
+    /// Returns the game if these credentials grant organizer access to it.
+    ///
+    /// The game is always looked up by ID and the admin token compared to its hash in constant
+    /// time, so the work done doesn't depend on whether the game exists or which credential
+    /// is wrong.
+    pub async fn authorize(&self, db: &Database, game_id: GameId) -> Result<Option<Game>> {
+        let game = db.get_game_by_id(game_id).await?;
+        let session_email = match &self.session_token {
+            Some(session_token) => db.get_organizer_session_email(session_token).await?,
+            None => None,
+        };
+
+        // Missing games are compared against a hash no token matches, so they take as long
+        let admin_token_hash = game
+            .as_ref()
+            .map_or(&*MISSING_GAME_TOKEN_HASH, |game| &game.admin_token_hash);
+        let admin_token_matches = self
+            .admin_token
+            .as_ref()
+            .is_some_and(|admin_token| admin_token_hash.matches(admin_token.as_str()));
+
+        let Some(game) = game else {
+            return Ok(None);
+        };
+        let session_matches = session_email.is_some_and(|email| {
+            game.organizer_email
+                .as_ref()
+                .eq_ignore_ascii_case(email.as_ref())
+        });
+
+        Ok((admin_token_matches || session_matches).then_some(game))
+    }
+
+    /// Like [`OrganizerCredentials::authorize`], but fails with [`AppError::AccessDenied`].
+    pub async fn require(&self, db: &Database, game_id: GameId) -> Result<Game, AppError> {
+        self.authorize(db, game_id)
+            .await?
+            .ok_or(AppError::AccessDenied)
+    }
+
+    /// Returns the email of the logged-in organizer, if the request has a valid session.
+    pub async fn session_email(&self, db: &Database) -> Result<Option<EmailAddress>> {
+        match &self.session_token {
+            Some(session_token) => db.get_organizer_session_email(session_token).await,
+            None => Ok(None),
+        }
+    }
+
+    pub fn session_token(&self) -> Option<&OrganizerSessionToken> {
+        self.session_token.as_ref()
+    }
+}
+
+fn admin_token_from_headers(headers: &HeaderMap) -> Option<AdminToken> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let token = value.strip_prefix("Bearer ")?.trim();
+    Some(token.to_string().into())
+}
+
+pub fn session_token_from_headers(headers: &HeaderMap) -> Option<OrganizerSessionToken> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| {
+            let (name, value) = pair.trim().split_once('=')?;
+            (name == SESSION_COOKIE && !value.is_empty()).then(|| value.to_string().into())
+        })
+}
+
+/// `Set-Cookie` value that stores the organizer session in the browser.
+pub fn session_cookie(token: &OrganizerSessionToken, secure: bool) -> HeaderValue {
+    let max_age = SESSION_DURATION_DAYS * 24 * 60 * 60;
+    let secure = if secure { "; Secure" } else { "" };
+    HeaderValue::from_str(&format!(
+        "{SESSION_COOKIE}={token}; Path=/; HttpOnly; SameSite=Lax; Max-Age={max_age}{secure}"
+    ))
+    .expect("session tokens are alphanumeric")
+}
+
+/// `Set-Cookie` value that removes the organizer session from the browser.
+pub fn clear_session_cookie(secure: bool) -> HeaderValue {
+    let secure = if secure { "; Secure" } else { "" };
+    HeaderValue::from_str(&format!(
+        "{SESSION_COOKIE}=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0{secure}"
+    ))
+    .expect("cookie attributes are valid header characters")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_token_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("theme=dark; organizer_session=abc123; other=1"),
+        );
+        let token = session_token_from_headers(&headers).unwrap();
+        assert_eq!(token.as_str(), "abc123");
+    }
+
+    #[test]
+    fn test_admin_token_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert!(admin_token_from_headers(&headers).is_none());
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Basic abc123"),
+        );
+        assert!(admin_token_from_headers(&headers).is_none());
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer abc123"),
+        );
+        let token = admin_token_from_headers(&headers).unwrap();
+        assert_eq!(token.as_str(), "abc123");
+    }
+
+    #[test]
+    fn test_session_token_from_headers_missing() {
+        let mut headers = HeaderMap::new();
+        assert!(session_token_from_headers(&headers).is_none());
+
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("organizer_session="),
+        );
+        assert!(session_token_from_headers(&headers).is_none());
+    }
+}
@@ -0,0 +1,91 @@
+//! Prometheus metrics.
+//!
+//! Counters are recorded through the `metrics` facade (`metrics::counter!`) where something
+//! worth alerting on happens, and served in the Prometheus text format at `GET /metrics` when
+//! `METRICS_TOKEN` is set. Without it no recorder is installed, and recording does nothing.
+
+use crate::token;
+use anyhow::{Context, Result};
+use axum::http::{HeaderMap, header};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use std::sync::OnceLock;
+
+/// Requests let through because the rate limit store failed, by budget.
+pub const RATE_LIMIT_STORE_ERRORS: &str = "rate_limit_store_errors_total";
+/// Times email sending switched from the primary transport to the secondary.
+pub const EMAIL_FAILOVERS: &str = "email_failovers_total";
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Installs the global recorder. Called once at startup.
+pub fn install() -> Result<()> {
+    let handle = PrometheusBuilder::new()
+        .install_recorder()
+        .context("installing the Prometheus recorder")?;
+    metrics::describe_counter!(
+        RATE_LIMIT_STORE_ERRORS,
+        "Requests let through because the rate limit store failed"
+    );
+    metrics::describe_counter!(
+        EMAIL_FAILOVERS,
+        "Times email sending switched to the secondary transport"
+    );
+    let _ = HANDLE.set(handle);
+    Ok(())
+}
+
+/// The metrics recorded so far, in the Prometheus text format. Empty if no recorder is
+/// installed.
+pub fn render() -> String {
+    HANDLE
+        .get()
+        .map(|handle| {
+            handle.run_upkeep();
+            handle.render()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether a request sends `token` as `Authorization: Bearer <token>`.
+pub fn authorized(headers: &HeaderMap, token: &str) -> bool {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|sent| token::constant_time_eq(sent.trim().as_bytes(), token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_authorized() {
+        let mut headers = HeaderMap::new();
+        assert!(!authorized(&headers, "s3cret"));
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer s3cret"),
+        );
+        assert!(authorized(&headers, "s3cret"));
+        assert!(!authorized(&headers, "other"));
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Basic czNjcmV0"),
+        );
+        assert!(!authorized(&headers, "s3cret"));
+    }
+
+    #[test]
+    fn test_render() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        metrics::with_local_recorder(&recorder, || {
+            metrics::counter!(EMAIL_FAILOVERS).increment(2);
+        });
+        assert!(handle.render().contains("email_failovers_total 2"));
+    }
+}
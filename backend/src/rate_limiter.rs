@@ -0,0 +1,346 @@
+//! Per-client rate limiting for routes that send emails or notify the site admin.
+//!
+//! The per-address limits stored in the database don't stop a single client from cycling
+//! through many addresses, so these routes also get a budget per IP address, kept in memory,
+//! or in Redis when `REDIS_URL` is set, so all instances share it.
+//! IPv6 clients are counted per /64, since they usually control a whole one.
+
+use crate::client;
+use crate::clock::Clock;
+use crate::prometheus;
+use crate::redis::Redis;
+use crate::routes::AppError;
+use anyhow::Result;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+use redis::Script;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use ulid::Ulid;
+
+/// Number of clients tracked before those without recent requests are forgotten.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// How many requests a client can make within a window.
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    /// Tells budgets apart in shared storage.
+    pub name: &'static str,
+    pub requests: usize,
+    pub window: Duration,
+}
+
+/// Requesting, entering, resending and confirming verification codes.
+pub const VERIFICATION_BUDGET: Budget = Budget {
+    name: "verification",
+    requests: 10,
+    window: Duration::minutes(15),
+};
+
+/// Logging in as site admin, with a password or a passkey.
+pub const LOGIN_BUDGET: Budget = Budget {
+    name: "login",
+    requests: 10,
+    window: Duration::minutes(15),
+};
+
+/// Resending and reissuing participants' emails.
+pub const RESEND_BUDGET: Budget = Budget {
+    name: "resend",
+    requests: 30,
+    window: Duration::hours(1),
+};
+
+/// Requesting a reveal recovery PIN or an organizer login link.
+pub const CODE_REQUEST_BUDGET: Budget = Budget {
+    name: "code_request",
+    requests: 10,
+    window: Duration::minutes(15),
+};
+
+/// Reporting a game to the site admin.
+pub const REPORT_BUDGET: Budget = Budget {
+    name: "report",
+    requests: 10,
+    window: Duration::hours(1),
+};
+
+pub type LimitFuture<'a> = Pin<Box<dyn Future<Output = Result<Result<(), Duration>>> + Send + 'a>>;
+
+/// Where the requests counted against a budget are kept.
+///
+/// Limits fail open: when a store returns an error, [`RateLimiter::check`] lets the request
+/// through, logging a warning and counting it in the `rate_limit_store_errors_total` metric.
+/// An outage of the store (i.e. Redis) would otherwise take down every route behind a
+/// limit, and the per-address limits kept in the database still apply meanwhile.
+pub trait RateLimitStore: Send + Sync {
+    /// Records a request from `client` at `now`. Returns how long to wait instead if the
+    /// client has used up its budget; rejected requests don't count towards it.
+    fn hit<'a>(&'a self, client: IpAddr, budget: Budget, now: DateTime<Utc>) -> LimitFuture<'a>;
+}
+
+/// Keeps requests in memory, for a single instance. Each budget needs its own.
+#[derive(Default)]
+pub struct MemoryRateLimits {
+    requests: Mutex<HashMap<IpAddr, VecDeque<DateTime<Utc>>>>,
+}
+
+impl MemoryRateLimits {
+    fn check(&self, client: IpAddr, budget: Budget, now: DateTime<Utc>) -> Result<(), Duration> {
+        let window_start = now - budget.window;
+        let mut requests = self.requests.lock().expect("rate limiter lock poisoned");
+
+        if requests.len() >= MAX_TRACKED_CLIENTS {
+            requests.retain(|_, times| times.back().is_some_and(|&time| time > window_start));
+        }
+
+        let times = requests.entry(client).or_default();
+        while times.front().is_some_and(|&time| time <= window_start) {
+            times.pop_front();
+        }
+        if times.len() >= budget.requests {
+            let oldest = *times.front().expect("budget allows at least one request");
+            return Err(oldest + budget.window - now);
+        }
+        times.push_back(now);
+        Ok(())
+    }
+}
+
+impl RateLimitStore for MemoryRateLimits {
+    fn hit<'a>(&'a self, client: IpAddr, budget: Budget, now: DateTime<Utc>) -> LimitFuture<'a> {
+        Box::pin(async move { Ok(self.check(client, budget, now)) })
+    }
+}
+
+/// Drops requests that left the window, then records the new one if the budget allows.
+/// Returns the milliseconds to wait, or -1 if the request was recorded. Runs atomically,
+/// so concurrent requests to different instances can't both take the last slot.
+const REDIS_HIT_SCRIPT: &str = r#"
+local now = tonumber(ARGV[1])
+local window = tonumber(ARGV[2])
+redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now - window)
+if redis.call('ZCARD', KEYS[1]) >= tonumber(ARGV[3]) then
+    local oldest = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+    return tonumber(oldest[2]) + window - now
+end
+redis.call('ZADD', KEYS[1], now, ARGV[4])
+redis.call('PEXPIRE', KEYS[1], window)
+return -1
+"#;
+
+/// Keeps requests in Redis, as a sorted set of request times per budget and client.
+pub struct RedisRateLimits {
+    redis: Redis,
+    script: Script,
+}
+
+impl RedisRateLimits {
+    pub fn new(redis: Redis) -> Self {
+        Self {
+            redis,
+            script: Script::new(REDIS_HIT_SCRIPT),
+        }
+    }
+}
+
+impl RateLimitStore for RedisRateLimits {
+    fn hit<'a>(&'a self, client: IpAddr, budget: Budget, now: DateTime<Utc>) -> LimitFuture<'a> {
+        Box::pin(async move {
+            let key = format!("amigo-oculto:rate-limit:{}:{client}", budget.name);
+            let reply: i64 = self
+                .script
+                .key(key)
+                .arg(now.timestamp_millis())
+                .arg(budget.window.num_milliseconds())
+                .arg(budget.requests)
+                .arg(Ulid::new().to_string())
+                .invoke_async(&mut self.redis.connection())
+                .await?;
+            Ok(if reply < 0 {
+                Ok(())
+            } else {
+                Err(Duration::milliseconds(reply))
+            })
+        })
+    }
+}
+
+pub struct RateLimiter {
+    budget: Budget,
+    /// Whether the client IP is taken from `X-Forwarded-For`, set by a reverse proxy.
+    trust_proxy: bool,
+    clock: Arc<dyn Clock>,
+    store: Arc<dyn RateLimitStore>,
+}
+
+impl RateLimiter {
+    pub fn new(
+        budget: Budget,
+        trust_proxy: bool,
+        clock: Arc<dyn Clock>,
+        store: Arc<dyn RateLimitStore>,
+    ) -> Self {
+        Self {
+            budget,
+            trust_proxy,
+            clock,
+            store,
+        }
+    }
+
+    /// Records a request from `ip`. Returns how long to wait instead if the client has
+    /// used up its budget.
+    ///
+    /// Requests are let through if the store fails (see [`RateLimitStore`]).
+    pub async fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        let now = self.clock.now();
+        match self.store.hit(client_key(ip), self.budget, now).await {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::warn!(
+                    "failed to check {} rate limit, letting the request through: {e:#}",
+                    self.budget.name
+                );
+                metrics::counter!(prometheus::RATE_LIMIT_STORE_ERRORS, "budget" => self.budget.name)
+                    .increment(1);
+                Ok(())
+            }
+        }
+    }
+
+    /// The IP address requests are counted against (see [`client::client_ip`]).
+    fn client_ip(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> Option<IpAddr> {
+        client::client_ip(headers, peer, self.trust_proxy)
+    }
+}
+
+/// Counts requests from the same network as one client.
+fn client_key(ip: IpAddr) -> IpAddr {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => IpAddr::V4(ip),
+        IpAddr::V6(ip) => {
+            let [a, b, c, d, ..] = ip.segments();
+            IpAddr::V6(Ipv6Addr::new(a, b, c, d, 0, 0, 0, 0))
+        }
+    }
+}
+
+/// Middleware rejecting requests over the limiter's budget with `429 Too Many Requests`.
+/// Requests whose client IP is unknown are let through.
+pub async fn limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    if let Some(ip) = limiter.client_ip(request.headers(), peer)
+        && let Err(retry_after) = limiter.check(ip).await
+    {
+        return AppError::TooManyRequests(retry_after).into_response();
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use axum::http::HeaderValue;
+
+    const BUDGET: Budget = Budget {
+        name: "test",
+        requests: 2,
+        window: Duration::minutes(10),
+    };
+
+    fn limiter(trust_proxy: bool) -> (RateLimiter, Arc<FixedClock>) {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let store = Arc::new(MemoryRateLimits::default());
+        (
+            RateLimiter::new(BUDGET, trust_proxy, clock.clone(), store),
+            clock,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_check() {
+        let (limiter, clock) = limiter(false);
+        let alice: IpAddr = "203.0.113.1".parse().unwrap();
+        let bob: IpAddr = "203.0.113.2".parse().unwrap();
+
+        assert!(limiter.check(alice).await.is_ok());
+        clock.advance(Duration::minutes(4));
+        assert!(limiter.check(alice).await.is_ok());
+        assert_eq!(limiter.check(alice).await, Err(Duration::minutes(6)));
+        assert!(limiter.check(bob).await.is_ok());
+
+        // The first request leaves the window
+        clock.advance(Duration::minutes(6));
+        assert!(limiter.check(alice).await.is_ok());
+        assert!(limiter.check(alice).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ipv6_networks_share_a_budget() {
+        let (limiter, _) = limiter(false);
+        assert!(limiter.check("2001:db8::1".parse().unwrap()).await.is_ok());
+        assert!(limiter.check("2001:db8::2".parse().unwrap()).await.is_ok());
+        assert!(limiter.check("2001:db8::3".parse().unwrap()).await.is_err());
+        assert!(
+            limiter
+                .check("2001:db8:0:1::1".parse().unwrap())
+                .await
+                .is_ok()
+        );
+    }
+
+    struct FailingStore;
+
+    impl RateLimitStore for FailingStore {
+        fn hit<'a>(&'a self, _: IpAddr, _: Budget, _: DateTime<Utc>) -> LimitFuture<'a> {
+            Box::pin(async { anyhow::bail!("store is down") })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_fails_open() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let limiter = RateLimiter::new(BUDGET, false, clock, Arc::new(FailingStore));
+        let alice: IpAddr = "203.0.113.1".parse().unwrap();
+        for _ in 0..=BUDGET.requests {
+            assert!(limiter.check(alice).await.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let peer = Some("10.0.0.1".parse().unwrap());
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("198.51.100.9, 203.0.113.7"),
+        );
+
+        let (direct, _) = limiter(false);
+        assert_eq!(direct.client_ip(&headers, peer), peer);
+
+        let (proxied, _) = limiter(true);
+        assert_eq!(
+            proxied.client_ip(&headers, peer),
+            Some("203.0.113.7".parse().unwrap())
+        );
+        assert_eq!(proxied.client_ip(&HeaderMap::new(), peer), peer);
+    }
+}
@@ -0,0 +1,99 @@
+//! Request IDs, so that a user reporting an error can be matched with the server's logs.
+//!
+//! Each request gets the `X-Request-Id` set by a reverse proxy, or a new one, which is sent
+//! back in the same header, recorded in the request's tracing span and included in JSON
+//! error bodies and error reports.
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use std::fmt;
+use ulid::Ulid;
+
+pub static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest request ID accepted from a proxy.
+const MAX_LENGTH: usize = 64;
+
+tokio::task_local! {
+    static CURRENT: RequestId;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(String);
+
+impl RequestId {
+    fn new() -> Self {
+        Self(Ulid::new().to_string())
+    }
+
+    /// An ID set by a proxy, if it's short and safe to log as is.
+    pub fn from_header(value: &HeaderValue) -> Option<Self> {
+        let value = value.to_str().ok()?;
+        let valid = !value.is_empty()
+            && value.len() <= MAX_LENGTH
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        valid.then(|| Self(value.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The ID of the request being handled, outside of requests `None`.
+pub fn current() -> Option<RequestId> {
+    CURRENT.try_with(Clone::clone).ok()
+}
+
+/// Middleware giving each request an ID, available to the handlers through [`current`] and
+/// to other middleware as a request extension.
+pub async fn assign(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&X_REQUEST_ID)
+        .and_then(RequestId::from_header)
+        .unwrap_or_else(RequestId::new);
+    request.extensions_mut().insert(id.clone());
+
+    let mut response = CURRENT.scope(id.clone(), next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(id.as_str()) {
+        response.headers_mut().insert(X_REQUEST_ID.clone(), value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_header() {
+        let id = RequestId::from_header(&HeaderValue::from_static("fly-01HX.abc_1")).unwrap();
+        assert_eq!(id.as_str(), "fly-01HX.abc_1");
+
+        assert!(RequestId::from_header(&HeaderValue::from_static("")).is_none());
+        assert!(RequestId::from_header(&HeaderValue::from_static("id with spaces")).is_none());
+        let long = "a".repeat(MAX_LENGTH + 1);
+        assert!(RequestId::from_header(&HeaderValue::from_str(&long).unwrap()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_current() {
+        assert_eq!(current(), None);
+        let id = RequestId::new();
+        let seen = CURRENT.scope(id.clone(), async { current() }).await;
+        assert_eq!(seen, Some(id));
+    }
+}
@@ -1,81 +1,360 @@
 use crate::{
-    db::Database,
+    admin_sessions::AdminSessionStore,
+    backup::{self, BackupSummary, Backups, RestoreSummary},
+    body_limit,
+    captcha::{Captcha, CaptchaWidget},
+    card,
+    client::ClientInfo,
+    clock::Clock,
+    config::Config,
+    datetime,
+    db::{self, Database},
     email::EmailService,
-    matching,
+    email_blocklist::EmailBlocklist,
+    email_dispatch::EmailDispatcher,
+    email_events::{self, WebhookPayload},
+    email_suggestion,
+    error_reporting::{self, ReportableError},
+    export::{self, ExportFormat, GameExport},
+    game_events::{GameEvent, GameEvents},
+    i18n::{self, Locale},
+    idempotency, matching,
     models::*,
+    mx_check::MailDomainChecker,
+    openapi,
+    organizer_auth::{self, OrganizerAuth, OrganizerCredentials},
+    prometheus,
+    rate_limiter::{self, Budget, MemoryRateLimits, RateLimitStore, RateLimiter, RedisRateLimits},
+    redis::Redis,
+    request_id::{self, RequestId},
     site_admin_auth::{self, AuthenticatedAdmin},
-    token::{AdminToken, GameId, ParticipantId, VerificationCode, ViewToken},
+    storage::{self, ImageType, MediaKey, Storage},
+    token::{
+        self, AdminSessionId, AdminToken, EmailAddress, EmailBanId, EmailDomain, EmailJobId,
+        EmailOpenToken, GameId, OrganizerLoginToken, OrganizerSessionToken, ParticipantId,
+        PasskeyId, ReportId, UnsubscribeToken, VerificationCode, VerificationId,
+        VerificationLinkToken, ViewToken,
+    },
+    totp,
+    validation::{FieldErrors, Valid},
+    webauthn::{self, Ceremony, RelyingParty},
 };
+use amigo_oculto_validation::{MAX_BAN_REASON_LENGTH, MAX_PASSKEY_NAME_LENGTH};
 use anyhow::Context;
 use axum::{
     Json, Router,
-    extract::{Path, Query, State},
-    http::{HeaderValue, StatusCode},
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
     middleware,
-    response::IntoResponse,
-    routing::{get, get_service, patch, post},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::{delete, get, patch, post, put},
 };
-use chrono::{Duration, Utc};
-use serde::Deserialize;
+use base64::prelude::*;
+use chrono::{DateTime, Duration};
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::convert::Infallible;
 use std::sync::Arc;
+use tower::ServiceBuilder;
 use tower_http::{
     cors::{self, AllowOrigin, CorsLayer},
-    services::{ServeDir, ServeFile},
     trace::TraceLayer,
 };
-use url::Url;
+use utoipa::ToSchema;
 
 /// Maximum number of participants allowed per game to prevent abuse
 const MAX_PARTICIPANTS_PER_GAME: u64 = 100;
+/// Participants listed per page of the game status, when paging
+const DEFAULT_PARTICIPANTS_PER_PAGE: u32 = 20;
+const MAX_PARTICIPANTS_PER_PAGE: u32 = 100;
+/// A transparent 1x1 GIF, served as the open tracking pixel
+const TRACKING_PIXEL_GIF: &[u8] = &[
+    0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 0x01, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0xff, 0xff, 0xff, 0x21, 0xf9, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x2c, 0x00, 0x00, 0x00, 0x00,
+    0x01, 0x00, 0x01, 0x00, 0x00, 0x02, 0x02, 0x44, 0x01, 0x00, 0x3b,
+];
+/// Phrase the organizer must type to see who drew whom
+const MATCH_REVEAL_CONFIRMATION: &str = "quero ver o sorteio";
+/// Phrase the site admin must type to replace the database with a backup
+const RESTORE_CONFIRMATION: &str = "substituir todos os dados";
+/// Largest backup the site admin can upload to restore
+const MAX_RESTORE_UPLOAD_BYTES: usize = 512 * 1024 * 1024;
+/// Site admin login attempts shown for audit
+const LOGIN_ATTEMPTS_SHOWN: u32 = 100;
+/// Longest period of daily statistics shown to the site admin
+const MAX_STATS_DAYS: u32 = 365;
+/// How long the readiness check waits for the database
+const READINESS_DB_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+#[allow(clippy::too_many_arguments)]
+pub fn make(
+    db: Database,
+    admin_sessions: Arc<dyn AdminSessionStore>,
+    redis: Option<Redis>,
+    email_service: EmailService,
+    email_dispatcher: EmailDispatcher,
+    game_events: GameEvents,
+    captcha: Option<Captcha>,
+    backups: Option<Backups>,
+    storage: Arc<dyn Storage>,
+    mail_domains: Option<MailDomainChecker>,
+    clock: Arc<dyn Clock>,
+    config: &Config,
+) -> Router {
+    let base_url = &config.base_url;
+    let is_localhost = matches!(base_url.host_str(), Some("localhost" | "127.0.0.1"));
+
+    let state = Arc::new(AppState {
+        db,
+        admin_sessions,
+        email_service,
+        email_dispatcher,
+        game_events,
+        legacy_admin_tokens: config.legacy_admin_tokens,
+        secure_cookies: base_url.scheme() == "https",
+        trust_proxy: config.trust_proxy,
+        storage,
+        clock,
+        email_webhook_secret: config.email_webhook_secret.clone(),
+        metrics_token: config.metrics_token.clone(),
+        captcha,
+        backups,
+        email_blocklist: EmailBlocklist::new(&config.blocked_email_domains),
+        mail_domains,
+        relying_party: RelyingParty::new(base_url)
+            .inspect_err(|e| tracing::warn!("passkeys are disabled: {e:#}"))
+            .ok(),
+        version: version_info(config),
+    });
+
+    // Budgets are shared by all instances through Redis, if there's one
+    let rate_limiter = |budget: Budget| {
+        let store: Arc<dyn RateLimitStore> = match &redis {
+            Some(redis) => Arc::new(RedisRateLimits::new(redis.clone())),
+            None => Arc::new(MemoryRateLimits::default()),
+        };
+        Arc::new(RateLimiter::new(
+            budget,
+            config.trust_proxy,
+            state.clock.clone(),
+            store,
+        ))
+    };
+
+    // Retries of these requests with the same `Idempotency-Key` get the first response
+    let idempotent = || middleware::from_fn_with_state(state.db.clone(), idempotency::idempotent);
 
-pub fn make(db: Database, email_service: EmailService) -> Router {
-    let state = Arc::new(AppState { db, email_service });
+    // Larger bodies are refused with `413`, before handlers read them
+    let body_limit = |max_bytes: usize| {
+        ServiceBuilder::new()
+            .layer(DefaultBodyLimit::max(max_bytes))
+            .layer(middleware::from_fn_with_state(max_bytes, body_limit::limit))
+    };
 
     // Site admin protected routes (require authentication)
     let site_admin_protected = Router::new()
         .route("/logout", post(site_admin_logout))
         .route("/change-password", post(site_admin_change_password))
+        .route("/totp", get(site_admin_totp_status))
+        .route("/totp/enroll", post(site_admin_enroll_totp))
+        .route("/totp/confirm", post(site_admin_confirm_totp))
+        .route("/totp/disable", post(site_admin_disable_totp))
+        .route("/sessions", get(site_admin_list_sessions))
+        .route("/sessions/{session_id}", delete(site_admin_revoke_session))
+        .route("/login-attempts", get(site_admin_list_login_attempts))
+        .route("/passkeys", get(site_admin_list_passkeys))
+        .route(
+            "/passkeys/register/options",
+            post(site_admin_passkey_registration_options),
+        )
+        .route("/passkeys/register", post(site_admin_register_passkey))
+        .route("/passkeys/{passkey_id}", delete(site_admin_delete_passkey))
         .route("/games", get(site_admin_search_games))
+        .route("/games/bulk-delete", post(site_admin_bulk_delete_games))
         .route(
             "/games/{game_id}",
             get(site_admin_get_game).delete(site_admin_delete_game),
         )
+        .route("/games/{game_id}/restore", post(site_admin_restore_game))
+        .route("/games/{game_id}/freeze", post(site_admin_freeze_game))
+        .route("/games/{game_id}/unfreeze", post(site_admin_unfreeze_game))
+        .route("/games/{game_id}/reset-draw", post(site_admin_reset_draw))
+        .route(
+            "/games/{game_id}/regenerate-admin-link",
+            post(site_admin_regenerate_admin_link),
+        )
+        .route(
+            "/games/{game_id}/resend-welcome",
+            post(site_admin_resend_welcome),
+        )
+        .route("/email/test", post(site_admin_email_test))
+        .route(
+            "/announcements",
+            get(site_admin_list_announcements).post(site_admin_create_announcement),
+        )
+        .route(
+            "/bans",
+            get(site_admin_list_email_bans).post(site_admin_create_email_ban),
+        )
+        .route(
+            "/bans/{ban_id}",
+            patch(site_admin_update_email_ban).delete(site_admin_delete_email_ban),
+        )
+        .route("/reports", get(site_admin_list_reports))
+        .route("/stats", get(site_admin_stats))
+        .route("/jobs", get(site_admin_list_job_runs))
+        .route("/query-plans", get(site_admin_query_plans))
+        .route("/backups", post(site_admin_create_backup))
+        .route(
+            "/settings",
+            get(site_admin_get_settings).patch(site_admin_update_settings),
+        )
+        .route(
+            "/reports/{report_id}/resolve",
+            post(site_admin_resolve_report),
+        )
+        .route_layer(body_limit(config.body_limits.default))
+        .route(
+            "/games/import",
+            post(site_admin_import_game).layer(body_limit(config.body_limits.import)),
+        )
+        .route(
+            "/backups/restore",
+            post(site_admin_restore_backup).layer(body_limit(MAX_RESTORE_UPLOAD_BYTES)),
+        )
         .layer(middleware::from_fn_with_state(
-            state.db.clone(),
+            state.admin_sessions.clone(),
             site_admin_auth::require_site_admin,
         ))
         .with_state(state.clone());
 
-    let api_routes = Router::new()
-        .route("/health", get(health_check))
+    // Routes that send emails get a budget per client, on top of the per-address limits
+    let verification_routes = Router::new()
         .route("/verifications/request", post(request_verification))
-        .route("/verifications/verify", post(verify_code))
+        .route(
+            "/verifications/verify",
+            post(verify_code).layer(idempotent()),
+        )
         .route("/verifications/resend", post(resend_verification))
-        .route("/games/{game_id}/participants", post(add_participant))
-        .route("/games/{game_id}/draw", post(draw_game))
+        .route(
+            "/verifications/{verification_id}/confirm/{token}",
+            get(verification_link_status).post(confirm_verification_link),
+        )
+        .route_layer(body_limit(config.body_limits.verification))
+        .route_layer(middleware::from_fn_with_state(
+            rate_limiter(rate_limiter::VERIFICATION_BUDGET),
+            rate_limiter::limit,
+        ));
+    let site_admin_login_routes = Router::new()
+        .route("/site-admin/login", post(site_admin_login))
+        .route(
+            "/site-admin/passkeys/login/options",
+            post(site_admin_passkey_login_options),
+        )
+        .route("/site-admin/passkeys/login", post(site_admin_passkey_login))
+        .route_layer(middleware::from_fn_with_state(
+            rate_limiter(rate_limiter::LOGIN_BUDGET),
+            rate_limiter::limit,
+        ));
+    let code_request_routes = Router::new()
+        .route("/reveal/recover", post(request_reveal_recovery))
+        .route("/organizer/login", post(request_organizer_login))
+        .route_layer(middleware::from_fn_with_state(
+            rate_limiter(rate_limiter::CODE_REQUEST_BUDGET),
+            rate_limiter::limit,
+        ));
+    let report_routes = Router::new()
+        .route("/reports", post(create_report))
+        .route_layer(middleware::from_fn_with_state(
+            rate_limiter(rate_limiter::REPORT_BUDGET),
+            rate_limiter::limit,
+        ));
+    let resend_routes = Router::new()
         .route("/games/{game_id}/resend-all", post(resend_all_emails))
         .route(
             "/games/{game_id}/participants/{participant_id}/resend",
             post(resend_participant_email),
         )
+        .route(
+            "/games/{game_id}/participants/{participant_id}/reissue",
+            post(reissue_view_token),
+        )
+        .route_layer(idempotent())
+        .route_layer(middleware::from_fn_with_state(
+            rate_limiter(rate_limiter::RESEND_BUDGET),
+            rate_limiter::limit,
+        ));
+
+    let api_routes = Router::new()
+        .route("/health", get(api_health_check))
+        .route("/version", get(get_version))
+        .route("/captcha", get(get_captcha))
+        .route("/openapi.json", get(get_openapi))
+        .merge(resend_routes)
+        .route(
+            "/games/{game_id}/participants",
+            post(add_participant).layer(idempotent()),
+        )
+        .route("/games/{game_id}/draw", post(draw_game).layer(idempotent()))
+        .route("/games/{game_id}/email-jobs/{job_id}", get(get_email_job))
+        .route("/games/{game_id}/events", get(follow_game_events))
+        .route("/games/{game_id}/export", get(export_game))
+        .route("/games/{game_id}/emails/preview", get(preview_email))
+        .route("/games/{game_id}/matches/reveal", post(reveal_matches))
         .route(
             "/games/{game_id}/participants/{participant_id}",
             patch(update_participant),
         )
-        .route("/games/{game_id}", get(get_game_status).delete(delete_game))
-        .route("/reveal/{view_token}", get(reveal_match))
+        .route(
+            "/games/{game_id}",
+            get(get_game_status).patch(update_game).delete(delete_game),
+        )
+        .route("/reveal/{view_token}", get(get_reveal))
+        .route("/reveal/{view_token}/confirm", post(confirm_reveal))
+        .route("/reveal/{view_token}/card", get(get_reveal_card))
+        .merge(code_request_routes)
+        .route("/reveal/recover/verify", post(verify_reveal_recovery))
+        .route(
+            "/organizer/login/{token}",
+            get(organizer_login_link_status).post(organizer_login),
+        )
+        .route("/organizer/logout", post(organizer_logout))
+        .route("/my/games", get(get_my_games))
+        .merge(report_routes)
+        .route("/email/events", post(email_events_webhook))
+        .route("/email/unsubscribe/{token}", post(unsubscribe_email))
+        .route("/email/open/{token}", get(track_email_open))
         // Site admin public routes (no authentication required)
-        .route("/site-admin/login", post(site_admin_login))
+        .merge(site_admin_login_routes)
+        // Routes with other limits, or their own, are added after this
+        .route_layer(body_limit(config.body_limits.default))
+        .merge(verification_routes)
+        .route(
+            "/games/{game_id}/cover",
+            put(upload_cover_image)
+                .delete(delete_cover_image)
+                .layer(body_limit(storage::MAX_COVER_IMAGE_BYTES)),
+        )
         // Site admin protected routes
         .nest("/site-admin", site_admin_protected)
-        .with_state(state);
+        .layer(middleware::from_fn(i18n::localize_messages))
+        .with_state(state.clone());
+
+    // The API documentation page loads Swagger UI from a CDN, so it's only served locally
+    let api_routes = if is_localhost {
+        api_routes.route("/docs", get(get_api_docs))
+    } else {
+        api_routes
+    };
 
-    let base_url = std::env::var("BASE_URL").unwrap_or_else(|_| "http://localhost:3000".into());
-    let base_url = Url::parse(&base_url).expect("BASE_URL must be a valid URL");
     let base_origin = base_url.origin().ascii_serialization();
     let base_origin_header =
         HeaderValue::from_str(&base_origin).expect("BASE_URL must be a valid origin URL");
-    let is_localhost = matches!(base_url.host_str(), Some("localhost" | "127.0.0.1"));
     let dev_origin = if is_localhost {
         Some(HeaderValue::from_static("http://localhost:5173"))
     } else {
@@ -90,114 +369,409 @@ pub fn make(db: Database, email_service: EmailService) -> Router {
         .allow_methods(cors::Any)
         .allow_headers(cors::Any);
 
-    let static_base_dir = std::path::PathBuf::from(
-        std::env::var("STATIC_DIR").unwrap_or_else(|_| "/app/public".into()),
-    );
+    let media_routes = Router::new()
+        .route("/{key}", get(get_media))
+        .with_state(state.clone());
 
-    let static_dir = ServeDir::new(&static_base_dir)
-        .not_found_service(ServeFile::new(static_base_dir.join("index.html")));
+    // Outside `/api`, where orchestrators such as Fly and Kubernetes look for them
+    let probe_routes = Router::new()
+        .route("/healthz", get(health_check))
+        .route("/readyz", get(readiness_check))
+        .route("/metrics", get(get_metrics))
+        .with_state(state);
 
     Router::new()
         .nest("/api", api_routes)
-        .fallback_service(get_service(static_dir).handle_error(|error| async move {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("static file error: {error}"),
-            )
-        }))
+        .nest("/media", media_routes)
+        .merge(probe_routes)
+        .fallback_service(config.static_files.service())
+        .layer(middleware::from_fn(error_reporting::report_errors))
+        .layer(sentry_tower::NewSentryLayer::<axum::extract::Request>::new_from_top())
         .layer(cors)
-        .layer(TraceLayer::new_for_http())
+        .layer(
+            TraceLayer::new_for_http().make_span_with(|request: &axum::extract::Request| {
+                let request_id = request.extensions().get::<RequestId>();
+                tracing::info_span!(
+                    "request",
+                    method = %request.method(),
+                    uri = %request.uri(),
+                    request_id = request_id.map(RequestId::as_str),
+                )
+            }),
+        )
+        .layer(middleware::from_fn(request_id::assign))
 }
 
 pub struct AppState {
     pub db: Database,
+    /// Site admin sessions, in the database or shared through Redis.
+    pub admin_sessions: Arc<dyn AdminSessionStore>,
     pub email_service: EmailService,
+    /// Sends the emails of a draw in the background.
+    pub email_dispatcher: EmailDispatcher,
+    /// Live updates for the organizer dashboard.
+    pub game_events: GameEvents,
+    /// Whether `?admin_token=` is accepted for organizer access (see `organizer_auth`).
+    pub legacy_admin_tokens: bool,
+    /// Whether cookies are marked `Secure` (when served over HTTPS).
+    pub secure_cookies: bool,
+    /// Whether client IPs are taken from `X-Forwarded-For` (see `client::client_ip`).
+    pub trust_proxy: bool,
+    pub storage: Arc<dyn Storage>,
+    pub clock: Arc<dyn Clock>,
+    /// Secret for the bounce and complaint webhook. It's disabled when unset.
+    pub email_webhook_secret: Option<String>,
+    /// Token for reading the Prometheus metrics. They're not served when unset.
+    pub metrics_token: Option<String>,
+    /// Checks verification requests for bots. Disabled when not configured.
+    pub captcha: Option<Captcha>,
+    /// Uploads database backups. Disabled when not configured.
+    pub backups: Option<Backups>,
+    /// Disposable email domains organizers can't use.
+    pub email_blocklist: EmailBlocklist,
+    /// Checks participants' email domains for MX records. Disabled when not configured.
+    pub mail_domains: Option<MailDomainChecker>,
+    /// Checks site admin passkeys. Disabled when the base URL has no domain name.
+    pub relying_party: Option<RelyingParty>,
+    pub version: VersionInfo,
+}
+
+/// What this binary is and which optional features the configuration enables.
+fn version_info(config: &Config) -> VersionInfo {
+    VersionInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        commit: option_env!("GIT_COMMIT"),
+        built_at: env!("BUILD_TIMESTAMP")
+            .parse()
+            .ok()
+            .and_then(|seconds| DateTime::from_timestamp(seconds, 0)),
+        features: EnabledFeatures {
+            email_mode: config.email.provider.name(),
+            captcha: config.captcha.as_ref().map(|captcha| captcha.provider),
+            backups: config.backup.is_some(),
+            media_storage: config.media.name(),
+            redis: config.redis.is_some(),
+            error_reporting: config.error_reporting.is_some(),
+            legacy_admin_tokens: config.legacy_admin_tokens,
+            check_email_domains: config.check_email_domains,
+        },
+    }
 }
 
-/// GET /api/health - Health check endpoint for Railway
+/// GET /healthz - Liveness check: the server is up, whatever the state of the database
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "Server",
+    summary = "Liveness check",
+    responses(
+        (status = 200, description = "Success")
+    )
+)]
 async fn health_check() -> StatusCode {
     StatusCode::OK
 }
 
-#[derive(Deserialize)]
-pub struct AdminQuery {
-    pub admin_token: AdminToken,
+/// GET /api/health - Liveness check, like `/healthz`
+#[utoipa::path(
+    get,
+    path = "/api/health",
+    tag = "Server",
+    summary = "Liveness check",
+    responses(
+        (status = 200, description = "Success")
+    )
+)]
+async fn api_health_check() -> StatusCode {
+    health_check().await
+}
+
+/// GET /metrics - Prometheus metrics, for the `METRICS_TOKEN` bearer; `404` unless it's set
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "Server",
+    summary = "Prometheus metrics",
+    responses(
+        (status = 200, description = "Metrics in the Prometheus text format", body = String, content_type = "text/plain")
+    ),
+    security(
+        ("metricsToken" = [])
+    )
+)]
+async fn get_metrics(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let Some(token) = &state.metrics_token else {
+        return Err(AppError::NotFound("Não encontrado".to_string()));
+    };
+    if !prometheus::authorized(&headers, token) {
+        return Err(AppError::Unauthorized("Token inválido".to_string()));
+    }
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        prometheus::render(),
+    )
+        .into_response())
+}
+
+/// GET /readyz - Readiness check: `503` while the database can't be reached
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "Server",
+    summary = "Readiness check: `503` while the database can't be reached",
+    responses(
+        (status = 200, description = "Success", body = Readiness),
+        (status = 503, description = "The database can't be reached", body = Readiness)
+    )
+)]
+async fn readiness_check(State(state): State<Arc<AppState>>) -> (StatusCode, Json<Readiness>) {
+    let database = match tokio::time::timeout(READINESS_DB_TIMEOUT, state.db.ping()).await {
+        Ok(Ok(())) => true,
+        Ok(Err(e)) => {
+            tracing::warn!("readiness check failed: {:#}", e);
+            false
+        }
+        Err(_) => {
+            tracing::warn!("readiness check failed: database didn't answer in time");
+            false
+        }
+    };
+    let status = if database {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(Readiness {
+            database,
+            email_transport: state.email_service.transport_description(),
+        }),
+    )
+}
+
+/// GET /api/version - Version, commit and build time of the server, and its enabled features
+#[utoipa::path(
+    get,
+    path = "/api/version",
+    tag = "Server",
+    summary = "Version, commit and build time of the server, and its enabled features",
+    responses(
+        (status = 200, description = "Success", body = VersionInfo)
+    )
+)]
+async fn get_version(State(state): State<Arc<AppState>>) -> Json<VersionInfo> {
+    Json(state.version.clone())
+}
+
+/// GET /api/openapi.json - OpenAPI description of the API
+#[utoipa::path(
+    get,
+    path = "/api/openapi.json",
+    tag = "Server",
+    summary = "This document",
+    responses(
+        (status = 200, description = "Success", body = Object)
+    )
+)]
+async fn get_openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(openapi::document())
+}
+
+/// GET /api/docs - Swagger UI for the OpenAPI description, when running locally
+#[utoipa::path(
+    get,
+    path = "/api/docs",
+    tag = "Server",
+    summary = "Swagger UI for this document, only when running locally",
+    responses(
+        (status = 200, description = "Success", content(("text/html")))
+    )
+)]
+async fn get_api_docs() -> axum::response::Html<&'static str> {
+    axum::response::Html(openapi::SWAGGER_UI)
+}
+
+/// GET /api/captcha - The CAPTCHA widget to show before requesting a verification code,
+/// or `null` if CAPTCHA checks are disabled
+#[utoipa::path(
+    get,
+    path = "/api/captcha",
+    tag = "Server",
+    summary = "CAPTCHA widget to show before requesting a verification code, `null` when disabled",
+    responses(
+        (status = 200, description = "Success", body = Option<CaptchaWidget>)
+    )
+)]
+async fn get_captcha(State(state): State<Arc<AppState>>) -> Json<Option<CaptchaWidget>> {
+    Json(state.captcha.as_ref().map(Captcha::widget))
 }
 
 // POST /api/games/:game_id/participants - Add a participant to a game
+#[utoipa::path(
+    post,
+    path = "/api/games/{game_id}/participants",
+    tag = "Participants",
+    summary = "Add a participant",
+    params(
+        ("game_id" = inline(GameId), Path),
+        ("Idempotency-Key" = Option<String>, Header, description = idempotency::KEY_DESCRIPTION)
+    ),
+    request_body = AddParticipantRequest,
+    responses(
+        (status = 200, description = "Success", body = AddParticipantResponse)
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
 pub async fn add_participant(
     State(state): State<Arc<AppState>>,
-    Path(game_id): Path<GameId>,
-    Query(query): Query<AdminQuery>,
-    Json(req): Json<AddParticipantRequest>,
+    OrganizerAuth(game): OrganizerAuth,
+    Valid(req): Valid<AddParticipantRequest>,
 ) -> Result<Json<AddParticipantResponse>, AppError> {
-    // Verify admin token
-    let game = state
-        .db
-        .get_game_by_admin_token(&query.admin_token)
-        .await?
-        .ok_or(AppError::Unauthorized(
-            "Token de administrador inválido".to_string(),
-        ))?;
+    let game_id = game.id;
+    check_not_frozen(&game)?;
 
-    // Verify game_id matches
-    if game.id != game_id {
-        return Err(AppError::Unauthorized(
-            "Token de administrador inválido para este jogo".to_string(),
-        ));
-    }
+    // Checked with the write lock held, so that concurrent requests can't go over the limit
+    // or add participants while the game is being drawn
+    let mut tx = state.db.begin().await?;
+    let game = tx
+        .get_game_by_id(game_id)
+        .await?
+        .ok_or(AppError::NotFound("Jogo não encontrado".to_string()))?;
 
     // Check if game has already been drawn
     if game.drawn {
-        return Err(AppError::BadRequest(
+        return Err(AppError::GameAlreadyDrawn(
             "Não é possível adicionar participantes após o sorteio já ter sido realizado"
                 .to_string(),
         ));
     }
 
+    check_participants_unlocked(&game)?;
+
     // Check participant limit to prevent abuse
-    let participant_count = state.db.count_participants_in_game(game_id).await?;
+    let participant_count = tx.count_participants_in_game(game_id).await?;
     if participant_count >= MAX_PARTICIPANTS_PER_GAME {
-        return Err(AppError::BadRequest(format!(
-            "Limite máximo de {} participantes atingido",
-            MAX_PARTICIPANTS_PER_GAME
-        )));
+        return Err(AppError::ParticipantLimit);
     }
 
-    let participant = Participant::new(game_id, req.name, req.email);
-    state.db.add_participant(&participant).await?;
+    check_email_domain_policy(&game, &req.email)?;
+
+    let participant = Participant::new(game_id, req.name.trim().to_string(), req.email);
+    tx.add_participant(&participant).await?;
+    tx.commit().await?;
+    state.db.touch_game_activity(game_id).await?;
+    spawn_email_domain_check(&state, participant.id, participant.email.clone());
+    state.game_events.publish(
+        game_id,
+        GameEvent::ParticipantAdded {
+            participant_id: participant.id,
+        },
+    );
 
     Ok(Json(AddParticipantResponse {
         participant_id: participant.id,
     }))
 }
 
+/// Checks in the background whether a participant's email domain can receive mail, and
+/// flags the participant for the organizer if it can't.
+fn spawn_email_domain_check(
+    state: &Arc<AppState>,
+    participant_id: ParticipantId,
+    email: EmailAddress,
+) {
+    let Some(mail_domains) = state.mail_domains.clone() else {
+        return;
+    };
+    let state = state.clone();
+    tokio::spawn(async move {
+        match mail_domains.domain_accepts_mail(email.domain()).await {
+            Ok(true) => {}
+            Ok(false) => {
+                tracing::info!("email domain {} can't receive mail", email.domain());
+                if let Err(e) = state
+                    .db
+                    .mark_email_domain_unreachable(participant_id, &email)
+                    .await
+                {
+                    tracing::error!("failed to flag unreachable email domain: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("failed to check email domain {}: {}", email.domain(), e),
+        }
+    });
+}
+
+/// Rejects participant emails outside the game's allowed domain, if one is configured.
+fn check_email_domain_policy(game: &Game, email: &EmailAddress) -> Result<(), AppError> {
+    match &game.allowed_email_domain {
+        Some(domain) if !email.is_in_domain(domain) => {
+            Err(AppError::EmailDomainNotAllowed(domain.clone()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Trims optional text, such as the event location or a custom email template, treating
+/// blank as absent. Lengths and syntax are checked by `validation`.
+fn normalize_optional_text(text: Option<&str>) -> Option<String> {
+    text.map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+/// Rejects new participants when the organizer has locked the participant list.
+fn check_participants_unlocked(game: &Game) -> Result<(), AppError> {
+    if game.locked {
+        return Err(AppError::ParticipantsLocked);
+    }
+    Ok(())
+}
+
+/// Rejects organizer changes to a game that a site admin has frozen.
+fn check_not_frozen(game: &Game) -> Result<(), AppError> {
+    match &game.frozen_reason {
+        Some(reason) if game.frozen_at.is_some() => Err(AppError::GameFrozen(reason.clone())),
+        _ => Ok(()),
+    }
+}
+
 // POST /api/games/:game_id/draw - Generate matches and send emails
+#[utoipa::path(
+    post,
+    path = "/api/games/{game_id}/draw",
+    tag = "Games",
+    summary = "Draw the matches and queue the participant emails",
+    params(
+        ("game_id" = inline(GameId), Path),
+        ("Idempotency-Key" = Option<String>, Header, description = idempotency::KEY_DESCRIPTION)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
 pub async fn draw_game(
     State(state): State<Arc<AppState>>,
-    Path(game_id): Path<GameId>,
-    Query(query): Query<AdminQuery>,
+    OrganizerAuth(game): OrganizerAuth,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    // Verify admin token
-    let admin_game = state
-        .db
-        .get_game_by_admin_token(&query.admin_token)
-        .await?
-        .ok_or(AppError::Unauthorized(
-            "Token de administrador inválido".to_string(),
-        ))?;
-
-    // Verify game_id matches
-    if admin_game.id != game_id {
-        return Err(AppError::Unauthorized(
-            "Token de administrador inválido para este jogo".to_string(),
-        ));
-    }
+    let game_id = game.id;
+    check_not_frozen(&game)?;
 
     // Start a transaction to prevent race conditions
     let mut tx = state.db.begin().await?;
 
-    // Get game with lock (IMMEDIATE transaction prevents concurrent draws)
+    // Get game with the write lock held, so concurrent draws wait for this one
     let game = tx
         .get_game_by_id(game_id)
         .await?
@@ -205,7 +779,7 @@ pub async fn draw_game(
 
     // Check if already drawn
     if game.drawn {
-        return Err(AppError::BadRequest(
+        return Err(AppError::GameAlreadyDrawn(
             "O sorteio já foi realizado para este jogo".to_string(),
         ));
     }
@@ -213,9 +787,7 @@ pub async fn draw_game(
     // Get participants
     let participants = tx.get_participants_by_game(game_id).await?;
     if participants.len() < 2 {
-        return Err(AppError::BadRequest(
-            "Precisa de pelo menos 2 participantes para fazer o sorteio".to_string(),
-        ));
+        return Err(AppError::NotEnoughParticipants);
     }
 
     // Generate matches
@@ -223,166 +795,453 @@ pub async fn draw_game(
 
     // Save matches and mark as drawn (all within transaction)
     tx.update_participant_matches(&matches).await?;
-    tx.mark_game_as_drawn(game_id).await?;
-
-    // Commit transaction before sending emails
-    tx.commit().await?;
-
-    // Send emails to all participants
-    for participant in &participants {
-        if let Err(e) = state
-            .email_service
-            .send_participant_notification(
-                &participant.name,
-                &participant.email,
-                &game.name,
-                game.event_date,
-                &participant.view_token,
-            )
-            .await
-        {
-            tracing::error!("failed to send email to {}: {}", participant.email, e);
-        }
-    }
-
-    // Send confirmation email to organizer
-    if let Err(e) = state
-        .email_service
-        .send_organizer_confirmation(
-            &game.organizer_email,
-            &game.name,
-            game.event_date,
-            game.id,
-            &game.admin_token,
-            participants.len(),
+    tx.mark_game_as_drawn(game_id, state.clock.now()).await?;
+
+    // Queue the emails with the draw, so they're sent even if we restart right after
+    let email_job_id = tx
+        .create_email_job(
+            game_id,
+            EmailTemplate::ParticipantNotification,
+            &participants,
+            state.clock.now(),
         )
-        .await
-    {
-        tracing::error!("failed to send confirmation email to organizer: {}", e);
-    }
+        .await?;
+    tx.commit().await?;
+    state.email_dispatcher.wake();
 
     Ok(Json(serde_json::json!({
         "success": true,
-        "message": "Sorteio realizado com sucesso! Emails enviados para todos os participantes."
+        "message": "Sorteio realizado com sucesso! Os emails estão sendo enviados.",
+        "email_job_id": email_job_id
     })))
 }
 
-// GET /api/games/:game_id?admin_token=xxx - Get game status (organizer view)
-pub async fn get_game_status(
+// GET /api/games/:game_id/email-jobs/:job_id - Progress of the emails sent after the draw
+#[utoipa::path(
+    get,
+    path = "/api/games/{game_id}/email-jobs/{job_id}",
+    tag = "Games",
+    summary = "Progress of the emails queued by the draw",
+    params(
+        ("game_id" = inline(GameId), Path),
+        ("job_id" = inline(EmailJobId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = EmailJob)
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
+pub async fn get_email_job(
     State(state): State<Arc<AppState>>,
-    Path(game_id): Path<GameId>,
-    Query(query): Query<AdminQuery>,
-) -> Result<Json<GameStatusResponse>, AppError> {
-    // Get game by admin token
-    let game = state
+    Path((_, job_id)): Path<(GameId, EmailJobId)>,
+    OrganizerAuth(game): OrganizerAuth,
+) -> Result<Json<EmailJob>, AppError> {
+    let game_id = game.id;
+
+    let job = state
         .db
-        .get_game_by_admin_token(&query.admin_token)
+        .get_email_job(job_id)
         .await?
-        .ok_or(AppError::Unauthorized(
-            "Token de administrador inválido".to_string(),
+        .filter(|job| job.game_id == game_id)
+        .ok_or(AppError::NotFound(
+            "Envio de emails não encontrado".to_string(),
         ))?;
 
-    // Verify game_id matches
-    if game.id != game_id {
-        return Err(AppError::Unauthorized(
-            "Token de administrador inválido para este jogo".to_string(),
-        ));
+    Ok(Json(job))
+}
+
+// GET /api/games/:game_id/events - Live updates for the organizer dashboard
+//
+// A Server-Sent Events stream of what happens to the game: participants added, emails sent
+// and matches viewed.
+#[utoipa::path(
+    get,
+    path = "/api/games/{game_id}/events",
+    tag = "Games",
+    summary = "Server-Sent Events when participants are added, emails are sent or matches are viewed",
+    params(
+        ("game_id" = inline(GameId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", content(("text/event-stream")))
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
+pub async fn follow_game_events(
+    State(state): State<Arc<AppState>>,
+    OrganizerAuth(game): OrganizerAuth,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events = state
+        .game_events
+        .follow(game.id)
+        .map(|event| Ok(event.to_sse()));
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+// GET /api/games/:game_id - Get game status (organizer view)
+//
+// Answers `304 Not Modified` when the game's revision shows nothing changed since the
+// `If-None-Match` ETag, without reading the status again. With `?format=csv` or
+// `Accept: text/csv`, returns just the participants as CSV.
+#[utoipa::path(
+    get,
+    path = "/api/games/{game_id}",
+    tag = "Games",
+    summary = "Game details and participant statuses",
+    params(
+        ("game_id" = inline(GameId), Path),
+        GameStatusQuery
+    ),
+    responses(
+        (status = 200, description = "Success", content((GameStatusResponse = "application/json"), (String = "text/csv"))),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag")
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
+pub async fn get_game_status(
+    State(state): State<Arc<AppState>>,
+    OrganizerAuth(game): OrganizerAuth,
+    Query(query): Query<GameStatusQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let page = (query.page.is_some() || query.per_page.is_some()).then(|| {
+        let per_page = query
+            .per_page
+            .unwrap_or(DEFAULT_PARTICIPANTS_PER_PAGE)
+            .clamp(1, MAX_PARTICIPANTS_PER_PAGE);
+        (query.page.unwrap_or(1).max(1), per_page)
+    });
+
+    let revision = state
+        .db
+        .game_revision(game.id)
+        .await?
+        .ok_or(AppError::NotFound("Jogo não encontrado".to_string()))?;
+    let format = query.format.unwrap_or_else(|| {
+        headers
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .and_then(ExportFormat::from_accept)
+            .unwrap_or_default()
+    });
+    let etag = game_status_etag(revision, page, query.viewed, format);
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        // Cached by the browser, but always checked with the ETag
+        (header::CACHE_CONTROL, "private, no-cache".to_string()),
+        (header::VARY, header::ACCEPT.to_string()),
+    ];
+    if if_none_match(&headers, &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
     }
+    let filter = ParticipantFilter {
+        viewed: query.viewed,
+        limit: page.map(|(_, per_page)| per_page),
+        offset: page.map_or(0, |(page, per_page)| {
+            u64::from(page - 1) * u64::from(per_page)
+        }),
+    };
+    let status = state
+        .db
+        .game_summary(game.id, &filter)
+        .await?
+        .ok_or(AppError::NotFound("Jogo não encontrado".to_string()))?;
 
-    // Get participants
-    let participants = state.db.get_participants_by_game(game_id).await?;
+    if format == ExportFormat::Csv {
+        let headers = [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"amigo-oculto-{}-status.csv\"",
+                    game.id
+                ),
+            ),
+        ];
+        let csv = export::status_csv(&status.participants);
+        return Ok((cache_headers, headers, csv).into_response());
+    }
 
-    let participant_statuses: Vec<ParticipantStatus> = participants
-        .into_iter()
-        .map(|p| ParticipantStatus {
-            id: p.id,
-            name: p.name,
-            email: p.email,
-            has_viewed: p.has_viewed,
-        })
-        .collect();
+    let response = GameStatusResponse {
+        event_date_formatted: datetime::format_date(status.game.event_date, status.game.locale),
+        game: status.game,
+        participants: status.participants,
+        participant_count: status.participant_count,
+        matching_count: status.matching_count,
+        viewed_count: status.viewed_count,
+        bulk_resend_count: status.bulk_resend_count,
+        page: page.map(|(page, _)| page),
+        per_page: page.map(|(_, per_page)| per_page),
+    };
+    Ok((cache_headers, Json(response)).into_response())
+}
 
-    Ok(Json(GameStatusResponse {
-        game,
-        participants: participant_statuses,
-    }))
+/// ETag of a game status: its revision, the participants shown and the format. The crate
+/// version is included in case the response format changed.
+fn game_status_etag(
+    revision: i64,
+    page: Option<(u32, u32)>,
+    viewed: Option<bool>,
+    format: ExportFormat,
+) -> String {
+    let page = page.map_or("all".to_string(), |(page, per_page)| {
+        format!("{page}x{per_page}")
+    });
+    let viewed = viewed.map_or("any", |viewed| if viewed { "viewed" } else { "unviewed" });
+    let format = match format {
+        ExportFormat::Json => "json",
+        ExportFormat::Csv => "csv",
+    };
+    format!(
+        "W/\"{}-{revision}-{page}-{viewed}-{format}\"",
+        env!("CARGO_PKG_VERSION")
+    )
 }
 
-// POST /api/games/:game_id/resend-all - Resend emails to all participants
-pub async fn resend_all_emails(
+/// Whether the request's `If-None-Match` lists the ETag, compared weakly.
+pub(crate) fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    let strip_weak = |tag: &str| tag.strip_prefix("W/").unwrap_or(tag).to_string();
+    let etag = strip_weak(etag);
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|tag| tag == "*" || strip_weak(tag) == etag)
+}
+
+// PATCH /api/games/:game_id - Update game settings
+#[utoipa::path(
+    patch,
+    path = "/api/games/{game_id}",
+    tag = "Games",
+    summary = "Update game settings",
+    params(
+        ("game_id" = inline(GameId), Path)
+    ),
+    request_body = UpdateGameRequest,
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
+pub async fn update_game(
     State(state): State<Arc<AppState>>,
-    Path(game_id): Path<GameId>,
-    Query(query): Query<AdminQuery>,
+    OrganizerAuth(game): OrganizerAuth,
+    Valid(req): Valid<UpdateGameRequest>,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    // Verify admin token
-    let game = state
-        .db
-        .get_game_by_admin_token(&query.admin_token)
-        .await?
-        .ok_or(AppError::Unauthorized(
-            "Token de administrador inválido".to_string(),
-        ))?;
+    let game_id = game.id;
+    check_not_frozen(&game)?;
 
-    // Verify game_id matches
-    if game.id != game_id {
-        return Err(AppError::Unauthorized(
-            "Token de administrador inválido para este jogo".to_string(),
-        ));
+    if let Some(allowed_email_domain) = req.allowed_email_domain {
+        state
+            .db
+            .update_game_allowed_email_domain(game_id, allowed_email_domain.as_ref())
+            .await?;
     }
 
-    // Check if game has been drawn
-    if !game.drawn {
-        return Err(AppError::BadRequest(
-            "O sorteio ainda não foi realizado. Realize o sorteio antes de reenviar emails."
-                .to_string(),
-        ));
+    if req.reveal_expires_after_event.is_some() || req.reveal_single_use.is_some() {
+        state
+            .db
+            .update_game_view_token_policy(
+                game_id,
+                req.reveal_expires_after_event
+                    .unwrap_or(game.reveal_expires_after_event),
+                req.reveal_single_use.unwrap_or(game.reveal_single_use),
+            )
+            .await?;
     }
 
-    // Rate limiting: Check recent bulk resends (within last hour)
-    let one_hour_ago = Utc::now() - Duration::hours(1);
-    let recent_resends = state
-        .db
-        .count_recent_bulk_resends(game_id, one_hour_ago)
-        .await?;
-    if recent_resends > 0 {
-        return Err(AppError::BadRequest(
-            "Só é possível reenviar emails em massa uma vez por hora.".to_string(),
-        ));
+    if let Some(locked) = req.locked {
+        state.db.update_game_locked(game_id, locked).await?;
     }
 
-    // Check total bulk resends (lifetime limit)
-    let total_resends = state.db.count_total_bulk_resends(game_id).await?;
-    if total_resends >= 3 {
-        return Err(AppError::BadRequest(
-            "Limite de 3 reenvios em massa atingido.".to_string(),
-        ));
+    if let Some(reminders_enabled) = req.reminders_enabled {
+        state
+            .db
+            .update_game_reminders_enabled(game_id, reminders_enabled)
+            .await?;
     }
 
-    // Get all participants
-    let participants = state.db.get_participants_by_game(game_id).await?;
+    if let Some(wrap_up_enabled) = req.wrap_up_enabled {
+        state
+            .db
+            .update_game_wrap_up_enabled(game_id, wrap_up_enabled)
+            .await?;
+    }
 
-    // Resend emails to all participants
-    let mut sent_count = 0;
-    let mut failed_count = 0;
+    if let Some(digest_enabled) = req.digest_enabled {
+        state
+            .db
+            .update_game_digest_enabled(game_id, digest_enabled)
+            .await?;
+    }
 
-    for participant in &participants {
-        match state
-            .email_service
-            .send_participant_notification(
-                &participant.name,
-                &participant.email,
-                &game.name,
-                game.event_date,
-                &participant.view_token,
-            )
+    if let Some(reply_to_organizer) = req.reply_to_organizer {
+        state
+            .db
+            .update_game_reply_to_organizer(game_id, reply_to_organizer)
+            .await?;
+    }
+
+    if let Some(open_tracking_enabled) = req.open_tracking_enabled {
+        state
+            .db
+            .update_game_open_tracking_enabled(game_id, open_tracking_enabled)
+            .await?;
+    }
+
+    if let Some(timezone) = req.timezone {
+        state.db.update_game_timezone(game_id, timezone).await?;
+    }
+
+    if let Some(locale) = req.locale {
+        state.db.update_game_locale(game_id, locale).await?;
+    }
+
+    if let Some(reveal_at) = req.reveal_at {
+        state.db.update_game_reveal_at(game_id, reveal_at).await?;
+    }
+
+    if let Some(event_location) = req.event_location {
+        let event_location = normalize_optional_text(event_location.as_deref());
+        state
+            .db
+            .update_game_event_location(game_id, event_location.as_deref())
+            .await?;
+    }
+
+    if let Some(organizer_message) = req.organizer_message {
+        let organizer_message = normalize_optional_text(organizer_message.as_deref());
+        state
+            .db
+            .update_game_organizer_message(game_id, organizer_message.as_deref())
+            .await?;
+    }
+
+    if req.participant_email_subject.is_some() || req.participant_email_body.is_some() {
+        let subject = match req.participant_email_subject {
+            Some(subject) => normalize_optional_text(subject.as_deref()),
+            None => game.participant_email_subject,
+        };
+        let body = match req.participant_email_body {
+            Some(body) => normalize_optional_text(body.as_deref()),
+            None => game.participant_email_body,
+        };
+        state
+            .db
+            .update_game_participant_email_template(game_id, subject.as_deref(), body.as_deref())
+            .await?;
+    }
+
+    if let Some(mut days) = req.event_reminder_days {
+        days.sort_unstable_by(|a, b| b.cmp(a));
+        days.dedup();
+        state
+            .db
+            .update_game_event_reminder_days(game_id, &days)
+            .await?;
+    }
+
+    state.db.touch_game_activity(game_id).await?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Jogo atualizado com sucesso"
+    })))
+}
+
+// POST /api/games/:game_id/resend-all - Resend emails to all participants
+#[utoipa::path(
+    post,
+    path = "/api/games/{game_id}/resend-all",
+    tag = "Games",
+    summary = "Resend the emails of every participant",
+    params(
+        ("game_id" = inline(GameId), Path),
+        ("Idempotency-Key" = Option<String>, Header, description = idempotency::KEY_DESCRIPTION)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
+pub async fn resend_all_emails(
+    State(state): State<Arc<AppState>>,
+    OrganizerAuth(game): OrganizerAuth,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let game_id = game.id;
+    check_not_frozen(&game)?;
+
+    // Check if game has been drawn
+    if !game.drawn {
+        return Err(AppError::GameNotDrawn(
+            "O sorteio ainda não foi realizado. Realize o sorteio antes de reenviar emails."
+                .to_string(),
+        ));
+    }
+
+    // Rate limiting: Check recent bulk resends (within last hour)
+    let one_hour_ago = state.clock.now() - Duration::hours(1);
+    let recent_resends = state
+        .db
+        .count_recent_bulk_resends(game_id, one_hour_ago)
+        .await?;
+    if recent_resends > 0 {
+        return Err(AppError::ResendLimit(
+            "Só é possível reenviar emails em massa uma vez por hora.".to_string(),
+        ));
+    }
+
+    // Check total bulk resends (lifetime limit)
+    let total_resends = state.db.count_total_bulk_resends(game_id).await?;
+    if total_resends >= 3 {
+        return Err(AppError::ResendLimit(
+            "Limite de 3 reenvios em massa atingido.".to_string(),
+        ));
+    }
+
+    // Get all participants
+    let participants = state.db.get_participants_by_game(game_id).await?;
+
+    // Resend emails to all participants
+    let mut sent_count = 0;
+    let mut failed_count = 0;
+
+    for participant in &participants {
+        let participant_id = participant.id;
+        let event = match state
+            .email_service
+            .send_participant_notification(&game, participant)
             .await
         {
-            Ok(_) => sent_count += 1,
+            Ok(_) => {
+                sent_count += 1;
+                GameEvent::EmailSent { participant_id }
+            }
             Err(e) => {
                 tracing::error!("failed to resend email to {}: {}", participant.email, e);
                 failed_count += 1;
+                GameEvent::EmailFailed { participant_id }
             }
-        }
+        };
+        state.game_events.publish(game_id, event);
     }
 
     // Record the bulk resend
@@ -397,27 +1256,32 @@ pub async fn resend_all_emails(
 }
 
 // PATCH /api/games/:game_id/participants/:participant_id - Update participant details
+#[utoipa::path(
+    patch,
+    path = "/api/games/{game_id}/participants/{participant_id}",
+    tag = "Participants",
+    summary = "Edit a participant",
+    params(
+        ("game_id" = inline(GameId), Path),
+        ("participant_id" = inline(ParticipantId), Path)
+    ),
+    request_body = UpdateParticipantRequest,
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
 pub async fn update_participant(
     State(state): State<Arc<AppState>>,
-    Path((game_id, participant_id)): Path<(GameId, ParticipantId)>,
-    Query(query): Query<AdminQuery>,
-    Json(req): Json<UpdateParticipantRequest>,
+    Path((_, participant_id)): Path<(GameId, ParticipantId)>,
+    OrganizerAuth(game): OrganizerAuth,
+    Valid(req): Valid<UpdateParticipantRequest>,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    // Verify admin token
-    let game = state
-        .db
-        .get_game_by_admin_token(&query.admin_token)
-        .await?
-        .ok_or(AppError::Unauthorized(
-            "Token de administrador inválido".to_string(),
-        ))?;
-
-    // Verify game_id matches
-    if game.id != game_id {
-        return Err(AppError::Unauthorized(
-            "Token de administrador inválido para este jogo".to_string(),
-        ));
-    }
+    let game_id = game.id;
+    check_not_frozen(&game)?;
 
     // Get participant to verify it exists and belongs to this game
     let participant = state
@@ -441,11 +1305,23 @@ pub async fn update_participant(
         ));
     }
 
+    if let Some(email) = &req.email {
+        check_email_domain_policy(&game, email)?;
+    }
+
     // Update participant
     state
         .db
-        .update_participant(participant_id, req.name, req.email)
+        .update_participant(
+            participant_id,
+            req.name.map(|name| name.trim().to_string()),
+            req.email.clone(),
+        )
         .await?;
+    state.db.touch_game_activity(game_id).await?;
+    if let Some(email) = req.email {
+        spawn_email_domain_check(&state, participant_id, email);
+    }
 
     Ok(Json(serde_json::json!({
         "success": true,
@@ -454,30 +1330,35 @@ pub async fn update_participant(
 }
 
 // POST /api/games/:game_id/participants/:participant_id/resend - Resend email to one participant
+#[utoipa::path(
+    post,
+    path = "/api/games/{game_id}/participants/{participant_id}/resend",
+    tag = "Participants",
+    summary = "Resend a participant's email",
+    params(
+        ("game_id" = inline(GameId), Path),
+        ("participant_id" = inline(ParticipantId), Path),
+        ("Idempotency-Key" = Option<String>, Header, description = idempotency::KEY_DESCRIPTION)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
 pub async fn resend_participant_email(
     State(state): State<Arc<AppState>>,
-    Path((game_id, participant_id)): Path<(GameId, ParticipantId)>,
-    Query(query): Query<AdminQuery>,
+    Path((_, participant_id)): Path<(GameId, ParticipantId)>,
+    OrganizerAuth(game): OrganizerAuth,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    // Verify admin token
-    let game = state
-        .db
-        .get_game_by_admin_token(&query.admin_token)
-        .await?
-        .ok_or(AppError::Unauthorized(
-            "Token de administrador inválido".to_string(),
-        ))?;
-
-    // Verify game_id matches
-    if game.id != game_id {
-        return Err(AppError::Unauthorized(
-            "Token de administrador inválido para este jogo".to_string(),
-        ));
-    }
+    let game_id = game.id;
+    check_not_frozen(&game)?;
 
     // Check if game has been drawn
     if !game.drawn {
-        return Err(AppError::BadRequest(
+        return Err(AppError::GameNotDrawn(
             "O sorteio ainda não foi realizado.".to_string(),
         ));
     }
@@ -498,14 +1379,110 @@ pub async fn resend_participant_email(
         ));
     }
 
+    check_participant_resend_limits(&state, participant_id).await?;
+
+    // Resend email
+    state
+        .email_service
+        .send_participant_notification(&game, &participant)
+        .await?;
+
+    // Record the individual resend
+    state
+        .db
+        .record_email_resend(game_id, Some(participant_id), "individual")
+        .await?;
+    state
+        .game_events
+        .publish(game_id, GameEvent::EmailSent { participant_id });
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": format!("Email reenviado para {}", participant.email)
+    })))
+}
+
+// POST /api/games/:game_id/participants/:participant_id/reissue - Replace a reveal link
+//
+// Invalidates the participant's current links and emails them a new one. Counts towards the
+// same limits as resending.
+#[utoipa::path(
+    post,
+    path = "/api/games/{game_id}/participants/{participant_id}/reissue",
+    tag = "Participants",
+    summary = "Revoke a participant's reveal links and email a new one",
+    params(
+        ("game_id" = inline(GameId), Path),
+        ("participant_id" = inline(ParticipantId), Path),
+        ("Idempotency-Key" = Option<String>, Header, description = idempotency::KEY_DESCRIPTION)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
+pub async fn reissue_view_token(
+    State(state): State<Arc<AppState>>,
+    Path((_, participant_id)): Path<(GameId, ParticipantId)>,
+    OrganizerAuth(game): OrganizerAuth,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let game_id = game.id;
+    check_not_frozen(&game)?;
+
+    if !game.drawn {
+        return Err(AppError::GameNotDrawn(
+            "O sorteio ainda não foi realizado.".to_string(),
+        ));
+    }
+
+    let participant = state
+        .db
+        .get_participant_by_id(participant_id)
+        .await?
+        .filter(|p| p.game_id == game_id)
+        .ok_or(AppError::NotFound(
+            "Participante não encontrado".to_string(),
+        ))?;
+
+    check_participant_resend_limits(&state, participant_id).await?;
+
+    state.db.revoke_view_tokens(participant_id).await?;
+
+    state
+        .email_service
+        .send_participant_notification(&game, &participant)
+        .await?;
+
+    state
+        .db
+        .record_email_resend(game_id, Some(participant_id), "reissue")
+        .await?;
+    state
+        .game_events
+        .publish(game_id, GameEvent::EmailSent { participant_id });
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": format!("Novo link enviado para {}", participant.email)
+    })))
+}
+
+/// Limits emails to a single participant: once per hour, and 3 times in total.
+async fn check_participant_resend_limits(
+    state: &AppState,
+    participant_id: ParticipantId,
+) -> Result<(), AppError> {
     // Rate limiting: Check recent individual resends (within last hour)
-    let one_hour_ago = Utc::now() - Duration::hours(1);
+    let one_hour_ago = state.clock.now() - Duration::hours(1);
     let recent_resends = state
         .db
         .count_recent_participant_resends(participant_id, one_hour_ago)
         .await?;
     if recent_resends > 0 {
-        return Err(AppError::BadRequest(
+        return Err(AppError::ResendLimit(
             "Só é possível reenviar email para este participante uma vez por hora.".to_string(),
         ));
     }
@@ -516,59 +1493,340 @@ pub async fn resend_participant_email(
         .count_total_participant_resends(participant_id)
         .await?;
     if total_resends >= 3 {
-        return Err(AppError::BadRequest(
+        return Err(AppError::ResendLimit(
             "Limite de 3 reenvios para este participante atingido.".to_string(),
         ));
     }
 
-    // Resend email
-    state
-        .email_service
-        .send_participant_notification(
-            &participant.name,
-            &participant.email,
-            &game.name,
-            game.event_date,
-            &participant.view_token,
-        )
-        .await?;
+    Ok(())
+}
 
-    // Record the individual resend
+// PUT /api/games/:game_id/cover - Upload a cover image (raw PNG, JPEG or WebP body)
+#[utoipa::path(
+    put,
+    path = "/api/games/{game_id}/cover",
+    tag = "Games",
+    summary = "Upload a cover image (PNG, JPEG or WebP, at most 1 MB)",
+    params(
+        ("game_id" = inline(GameId), Path)
+    ),
+    request_body(content(("image/png"), ("image/jpeg"), ("image/webp"))),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
+pub async fn upload_cover_image(
+    State(state): State<Arc<AppState>>,
+    OrganizerAuth(game): OrganizerAuth,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let game_id = game.id;
+    check_not_frozen(&game)?;
+
+    let image_type = ImageType::detect(&body).ok_or(AppError::BadRequest(
+        "Formato de imagem não suportado. Use PNG, JPEG ou WebP.".to_string(),
+    ))?;
+
+    let key = MediaKey::generate(image_type);
+    state.storage.put(&key, body.to_vec()).await?;
     state
         .db
-        .record_email_resend(game_id, Some(participant_id), "individual")
+        .update_game_cover_image(game_id, Some(&key))
         .await?;
 
+    if let Some(old_key) = game.cover_image
+        && let Err(e) = state.storage.delete(&old_key).await
+    {
+        tracing::error!("failed to delete old cover image {}: {}", old_key, e);
+    }
+
     Ok(Json(serde_json::json!({
         "success": true,
-        "message": format!("Email reenviado para {}", participant.email)
+        "cover_image_url": media_path(&key)
     })))
 }
 
-// DELETE /api/games/:game_id - Delete a game (requires admin_token)
-pub async fn delete_game(
+// DELETE /api/games/:game_id/cover - Remove the cover image
+#[utoipa::path(
+    delete,
+    path = "/api/games/{game_id}/cover",
+    tag = "Games",
+    summary = "Remove the cover image",
+    params(
+        ("game_id" = inline(GameId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
+pub async fn delete_cover_image(
     State(state): State<Arc<AppState>>,
-    Path(game_id): Path<GameId>,
-    Query(query): Query<AdminQuery>,
+    OrganizerAuth(game): OrganizerAuth,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    // Verify admin token
-    let game = state
-        .db
-        .get_game_by_admin_token(&query.admin_token)
-        .await?
-        .ok_or(AppError::Unauthorized(
-            "Token de administrador inválido".to_string(),
-        ))?;
+    let game_id = game.id;
+    check_not_frozen(&game)?;
 
-    // Verify game_id matches
-    if game.id != game_id {
-        return Err(AppError::Unauthorized(
-            "Token de administrador inválido para este jogo".to_string(),
+    if let Some(key) = game.cover_image {
+        state.db.update_game_cover_image(game_id, None).await?;
+        state.storage.delete(&key).await?;
+    }
+
+    Ok(Json(serde_json::json!({ "success": true })))
+}
+
+/// GET /media/:key - Serve uploaded media
+#[utoipa::path(
+    get,
+    path = "/media/{key}",
+    tag = "Server",
+    summary = "Uploaded media, such as cover images",
+    params(
+        ("key" = String, Path)
+    ),
+    responses(
+        (status = 200, description = "Success", content(("image/png"), ("image/jpeg"), ("image/webp")))
+    )
+)]
+pub async fn get_media(
+    State(state): State<Arc<AppState>>,
+    Path(key): Path<String>,
+) -> Result<Response, AppError> {
+    let not_found = || AppError::NotFound("Arquivo não encontrado".to_string());
+    let key: MediaKey = key.parse().map_err(|_| not_found())?;
+    let data = state.storage.get(&key).await?.ok_or_else(not_found)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, key.image_type().content_type()),
+            // Keys are never reused, so the content never changes
+            (header::CACHE_CONTROL, "public, max-age=31536000, immutable"),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+fn media_path(key: &MediaKey) -> String {
+    format!("/media/{key}")
+}
+
+// GET /api/games/:game_id/emails/preview?type=participant|organizer&format=html|text
+//
+// Renders an email as it will be sent after the draw. The participant email is addressed to
+// the first participant, with a reveal link that doesn't work, so the preview never gives
+// away a match.
+#[utoipa::path(
+    get,
+    path = "/api/games/{game_id}/emails/preview",
+    tag = "Games",
+    summary = "Preview the participant or organizer email",
+    params(
+        ("game_id" = inline(GameId), Path),
+        EmailPreviewQuery
+    ),
+    responses(
+        (status = 200, description = "Success", body = EmailPreviewResponse)
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
+pub async fn preview_email(
+    State(state): State<Arc<AppState>>,
+    OrganizerAuth(game): OrganizerAuth,
+    Query(query): Query<EmailPreviewQuery>,
+) -> Result<Json<EmailPreviewResponse>, AppError> {
+    let game_id = game.id;
+
+    let participants = state.db.get_participants_by_game(game_id).await?;
+    let email = match query.email_type {
+        EmailPreviewType::Participant => {
+            let participant_name = participants
+                .first()
+                .map_or(game.locale.tr("Participante"), |p| p.name.as_str());
+            state.email_service.participant_notification(
+                &game,
+                participant_name,
+                &ViewToken::generate(),
+                None,
+            )
+        }
+        EmailPreviewType::Organizer => state
+            .email_service
+            .organizer_confirmation(&game, participants.len()),
+    };
+
+    let body = match query.format {
+        EmailPreviewFormat::Html => email.html_with_embedded_images(),
+        EmailPreviewFormat::Text => email.text,
+    };
+    Ok(Json(EmailPreviewResponse {
+        subject: email.subject,
+        body,
+    }))
+}
+
+// GET /api/games/:game_id/export?format=json|csv&include_matches=true - Download game data
+#[utoipa::path(
+    get,
+    path = "/api/games/{game_id}/export",
+    tag = "Games",
+    summary = "Download participants and statuses",
+    params(
+        ("game_id" = inline(GameId), Path),
+        ExportGameQuery
+    ),
+    responses(
+        (status = 200, description = "Success", content((GameExport = "application/json"), (String = "text/csv")))
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
+pub async fn export_game(
+    State(state): State<Arc<AppState>>,
+    OrganizerAuth(game): OrganizerAuth,
+    Query(query): Query<ExportGameQuery>,
+) -> Result<Response, AppError> {
+    let game_id = game.id;
+
+    let participants = state.db.get_participants_by_game(game_id).await?;
+    if query.include_matches && game.drawn {
+        state.db.record_match_access(game_id, "export").await?;
+        tracing::warn!("organizer exported the matches of game {}", game_id);
+    }
+    let export = GameExport::new(game, participants, query.include_matches);
+
+    let (content_type, extension, body) = match query.format {
+        ExportFormat::Json => (
+            "application/json",
+            "json",
+            serde_json::to_string_pretty(&export).context("serializing game export")?,
+        ),
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "csv", export.to_csv()),
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"amigo-oculto-{game_id}.{extension}\""),
+            ),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+// POST /api/games/:game_id/matches/reveal - Show the organizer who drew whom
+//
+// Deliberately gated behind a typed confirmation phrase, and every access is recorded.
+#[utoipa::path(
+    post,
+    path = "/api/games/{game_id}/matches/reveal",
+    tag = "Games",
+    summary = "Show the organizer who drew whom, after a typed confirmation",
+    params(
+        ("game_id" = inline(GameId), Path)
+    ),
+    request_body = RevealMatchesRequest,
+    responses(
+        (status = 200, description = "Success", body = RevealMatchesResponse)
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
+pub async fn reveal_matches(
+    State(state): State<Arc<AppState>>,
+    OrganizerAuth(game): OrganizerAuth,
+    Json(req): Json<RevealMatchesRequest>,
+) -> Result<Json<RevealMatchesResponse>, AppError> {
+    let game_id = game.id;
+    check_not_frozen(&game)?;
+
+    if !game.drawn {
+        return Err(AppError::GameNotDrawn(
+            "O sorteio ainda não foi realizado".to_string(),
         ));
     }
 
-    // Delete game (participants will be cascade deleted)
-    state.db.delete_game(game_id).await?;
+    if !req
+        .confirmation
+        .trim()
+        .eq_ignore_ascii_case(MATCH_REVEAL_CONFIRMATION)
+    {
+        return Err(AppError::BadRequest(format!(
+            "Digite \"{}\" para confirmar",
+            MATCH_REVEAL_CONFIRMATION
+        )));
+    }
+
+    let participants = state.db.get_participants_by_game(game_id).await?;
+    let names: HashMap<_, _> = participants
+        .iter()
+        .map(|p| (p.id, p.name.as_str()))
+        .collect();
+    let matches =
+        participants
+            .iter()
+            .map(|p| {
+                let matched_name = p.matched_with_id.and_then(|id| names.get(&id)).ok_or(
+                    AppError::InternalError("Participante sorteado não encontrado".to_string()),
+                )?;
+                Ok(MatchEntry {
+                    name: p.name.clone(),
+                    matched_name: matched_name.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+
+    state.db.record_match_access(game_id, "reveal").await?;
+    tracing::warn!("organizer revealed the matches of game {}", game_id);
+
+    Ok(Json(RevealMatchesResponse { matches }))
+}
+
+// DELETE /api/games/:game_id - Delete a game (requires admin_token)
+#[utoipa::path(
+    delete,
+    path = "/api/games/{game_id}",
+    tag = "Games",
+    summary = "Delete the game",
+    params(
+        ("game_id" = inline(GameId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("organizerSession" = []),
+        ("adminToken" = [])
+    )
+)]
+pub async fn delete_game(
+    State(state): State<Arc<AppState>>,
+    OrganizerAuth(game): OrganizerAuth,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let game_id = game.id;
+    check_not_frozen(&game)?;
+
+    // Soft delete; the game is purged by the cleanup task after a grace period
+    state.db.soft_delete_game(game_id).await?;
 
     Ok(Json(serde_json::json!({
         "success": true,
@@ -576,55 +1834,378 @@ pub async fn delete_game(
     })))
 }
 
-// GET /api/reveal/:view_token - View your match
-pub async fn reveal_match(
-    State(state): State<Arc<AppState>>,
-    Path(view_token): Path<ViewToken>,
-) -> Result<Json<RevealResponse>, AppError> {
+/// Finds the participant and game behind a view token, if the link can still be used.
+async fn find_reveal(
+    state: &AppState,
+    view_token: &ViewToken,
+) -> Result<(Participant, Game), AppError> {
     // Get participant by view token
     let participant = state
         .db
-        .get_participant_by_view_token(&view_token)
+        .get_participant_by_view_token(view_token)
         .await?
-        .ok_or(AppError::NotFound("Link inválido ou expirado".to_string()))?;
+        .ok_or(AppError::AccessDenied)?;
 
     // Check if game has been drawn
     let game = state
         .db
         .get_game_by_id(participant.game_id)
         .await?
-        .ok_or(AppError::NotFound("Jogo não encontrado".to_string()))?;
+        .ok_or(AppError::AccessDenied)?;
+    i18n::use_game_locale(game.locale);
 
     if !game.drawn {
-        return Err(AppError::BadRequest(
+        return Err(AppError::GameNotDrawn(
             "O sorteio ainda não foi realizado. Aguarde o organizador finalizar o sorteio."
                 .to_string(),
         ));
     }
 
-    // Get matched participant
+    if game.reveal_expires_after_event && game.event_date < game.timezone.date_at(state.clock.now())
+    {
+        return Err(AppError::Gone(
+            "Este link expirou, pois a data do evento já passou.".to_string(),
+        ));
+    }
+
+    Ok((participant, game))
+}
+
+/// Everything the reveal page shows, except the match itself.
+async fn reveal_info(
+    state: &AppState,
+    participant: Participant,
+    game: Game,
+) -> Result<RevealResponse, AppError> {
+    // Before the scheduled reveal, say how long is left
+    let now = state.clock.now();
+    let seconds_until_reveal = game
+        .reveal_at
+        .filter(|reveal_at| *reveal_at > now)
+        .map(|reveal_at| u64::try_from((reveal_at - now).num_seconds()))
+        .transpose()
+        .context("converting time until reveal to u64")?;
+
+    Ok(RevealResponse {
+        event_date_formatted: datetime::format_date(game.event_date, game.locale),
+        your_name: participant.name,
+        matched_name: None,
+        reveal_at: game.reveal_at,
+        reveal_at_formatted: game
+            .reveal_at
+            .map(|reveal_at| game.timezone.format_datetime(reveal_at, game.locale)),
+        seconds_until_reveal,
+        cover_image_url: game.cover_image.as_ref().map(media_path),
+        card_url: None,
+        participant_count: state.db.count_participants_in_game(game.id).await?,
+        viewed_count: state.db.count_viewed_participants_in_game(game.id).await?,
+        days_until_event: (game.event_date - game.timezone.date_at(now)).num_days(),
+        game_name: game.name,
+        event_date: game.event_date,
+    })
+}
+
+/// The participant drawn for the given one.
+async fn find_match(state: &AppState, participant: &Participant) -> Result<Participant, AppError> {
     let matched_with_id = participant.matched_with_id.ok_or(AppError::InternalError(
         "Sorteio ainda não foi realizado".to_string(),
     ))?;
 
-    let matched_participant = state
+    state
         .db
         .get_participant_by_id(matched_with_id)
         .await?
         .ok_or(AppError::InternalError(
             "Participante sorteado não encontrado".to_string(),
-        ))?;
+        ))
+}
+
+/// GET /api/reveal/{view_token} - Game info for a participant, without their match
+///
+/// Safe to fetch without user interaction: email scanners and link prefetchers open this
+/// link, so it neither discloses the match nor marks the participant as having viewed it.
+#[utoipa::path(
+    get,
+    path = "/api/reveal/{view_token}",
+    tag = "Reveal",
+    summary = "Game info for a participant, without their match",
+    params(
+        ("view_token" = inline(ViewToken), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = RevealResponse)
+    )
+)]
+pub async fn get_reveal(
+    State(state): State<Arc<AppState>>,
+    Path(view_token): Path<ViewToken>,
+) -> Result<Json<RevealResponse>, AppError> {
+    let (participant, game) = find_reveal(&state, &view_token).await?;
+    Ok(Json(reveal_info(&state, participant, game).await?))
+}
+
+/// POST /api/reveal/{view_token}/confirm - Disclose the participant's match
+///
+/// Marks the participant as having viewed their match, and uses up single-use links.
+#[utoipa::path(
+    post,
+    path = "/api/reveal/{view_token}/confirm",
+    tag = "Reveal",
+    summary = "Disclose the participant's match and mark it as viewed",
+    params(
+        ("view_token" = inline(ViewToken), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = RevealResponse)
+    )
+)]
+pub async fn confirm_reveal(
+    State(state): State<Arc<AppState>>,
+    Path(view_token): Path<ViewToken>,
+    client: ClientInfo,
+) -> Result<Json<RevealResponse>, AppError> {
+    let (participant, game) = find_reveal(&state, &view_token).await?;
+
+    if game
+        .reveal_at
+        .is_some_and(|reveal_at| reveal_at > state.clock.now())
+    {
+        return Err(AppError::BadRequest(
+            "Ainda não chegou a hora da revelação".to_string(),
+        ));
+    }
+
+    let matched_participant = find_match(&state, &participant).await?;
+
+    // Single-use links only show the match the first time
+    let first_use = state.db.claim_view_token(participant.id).await?;
+    if game.reveal_single_use && !first_use {
+        return Err(AppError::Gone(
+            "Este link já foi usado. Peça um novo link ao organizador.".to_string(),
+        ));
+    }
 
     // Mark as viewed
     if !participant.has_viewed {
         state.db.mark_participant_viewed(participant.id).await?;
     }
+    state.game_events.publish(
+        game.id,
+        GameEvent::MatchViewed {
+            participant_id: participant.id,
+        },
+    );
+    state
+        .db
+        .record_reveal_event(
+            &participant,
+            client.user_agent.as_deref(),
+            client.ip_prefix().as_deref(),
+        )
+        .await?;
+    state
+        .db
+        .record_reveal_device(
+            participant.id,
+            &client.device_hash(&participant.id.to_string()),
+        )
+        .await?;
+
+    let single_use = game.reveal_single_use;
+    let mut response = reveal_info(&state, participant, game).await?;
+    response.matched_name = Some(matched_participant.name);
+    if !single_use {
+        response.card_url = Some(format!("/api/reveal/{view_token}/card"));
+    }
+    Ok(Json(response))
+}
 
-    Ok(Json(RevealResponse {
-        game_name: game.name,
-        event_date: game.event_date,
-        your_name: participant.name,
-        matched_name: matched_participant.name,
+/// GET /api/reveal/{view_token}/card - Download a printable card with the participant's match
+///
+/// Only available after the participant has confirmed the reveal, so fetching the link doesn't
+/// disclose the match by itself. Single-use links have no card, as it would show the match again.
+#[utoipa::path(
+    get,
+    path = "/api/reveal/{view_token}/card",
+    tag = "Reveal",
+    summary = "Printable card with the participant's match",
+    params(
+        ("view_token" = inline(ViewToken), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", content(("application/pdf")))
+    )
+)]
+pub async fn get_reveal_card(
+    State(state): State<Arc<AppState>>,
+    Path(view_token): Path<ViewToken>,
+) -> Result<Response, AppError> {
+    let (participant, game) = find_reveal(&state, &view_token).await?;
+
+    if game.reveal_single_use {
+        return Err(AppError::Gone(
+            "O cartão não está disponível para links de uso único.".to_string(),
+        ));
+    }
+    if !participant.has_viewed
+        || game
+            .reveal_at
+            .is_some_and(|reveal_at| reveal_at > state.clock.now())
+    {
+        return Err(AppError::BadRequest(
+            "Revele seu amigo oculto antes de baixar o cartão.".to_string(),
+        ));
+    }
+
+    let matched_participant = find_match(&state, &participant).await?;
+    let body = card::render_pdf(
+        &game.name,
+        &datetime::format_date(game.event_date, game.locale),
+        &matched_participant.name,
+        game.locale,
+    )?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"amigo-oculto.pdf\"",
+            ),
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// POST /api/reveal/recover - Request a PIN to recover lost reveal links
+///
+/// Sends a 6-digit PIN to the given email if it belongs to a participant of a drawn game.
+/// The response is the same either way, so it can't be used to find out who takes part
+/// in a game. The PIN expires in 15 minutes.
+///
+/// Rate limiting: Maximum 3 recovery requests per email per hour, within a per-IP budget.
+#[utoipa::path(
+    post,
+    path = "/api/reveal/recover",
+    tag = "Reveal",
+    summary = "Email a PIN to a participant who lost their reveal link",
+    request_body = RequestRevealRecoveryRequest,
+    responses(
+        (status = 200, description = "Success", body = RequestRevealRecoveryResponse)
+    )
+)]
+pub async fn request_reveal_recovery(
+    State(state): State<Arc<AppState>>,
+    locale: Locale,
+    Valid(req): Valid<RequestRevealRecoveryRequest>,
+) -> Result<Json<RequestRevealRecoveryResponse>, AppError> {
+    let now = state.clock.now();
+    let recent = state
+        .db
+        .recent_reveal_recoveries_by_email(&req.email, now - Duration::hours(1))
+        .await?;
+    if let Some(retry_after) = recent.retry_after(3, Duration::hours(1), now) {
+        return Err(AppError::TooManyRequests(retry_after));
+    }
+
+    let (recovery, code) = RevealRecovery::new(req.email, state.clock.now());
+    state.db.create_reveal_recovery(&recovery).await?;
+
+    if state
+        .db
+        .has_reveals_by_participant_email(&recovery.email)
+        .await?
+        && let Err(e) = state
+            .email_service
+            .send_reveal_recovery_code(&recovery.email, code, locale)
+            .await
+    {
+        tracing::error!("failed to send reveal recovery email: {}", e);
+        return Err(AppError::InternalError(
+            "Erro ao enviar email de recuperação".to_string(),
+        ));
+    }
+
+    Ok(Json(RequestRevealRecoveryResponse {
+        recovery_id: recovery.id,
+    }))
+}
+
+/// POST /api/reveal/recover/verify - Verify the PIN and list the participant's reveal links
+///
+/// Maximum 5 attempts per PIN before a new one must be requested.
+#[utoipa::path(
+    post,
+    path = "/api/reveal/recover/verify",
+    tag = "Reveal",
+    summary = "Verify the PIN and list the participant's reveal links",
+    request_body = VerifyRevealRecoveryRequest,
+    responses(
+        (status = 200, description = "Success", body = VerifyRevealRecoveryResponse)
+    )
+)]
+pub async fn verify_reveal_recovery(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<VerifyRevealRecoveryRequest>,
+) -> Result<Json<VerifyRevealRecoveryResponse>, AppError> {
+    let recovery = state
+        .db
+        .get_reveal_recovery_by_id(req.recovery_id)
+        .await?
+        .ok_or(AppError::NotFound("Recuperação não encontrada".to_string()))?;
+
+    let failure = |error: &str, attempts_remaining| {
+        Json(VerifyRevealRecoveryResponse {
+            success: false,
+            reveals: Vec::new(),
+            error: Some(error.to_string()),
+            attempts_remaining,
+        })
+    };
+
+    if recovery.verified {
+        return Err(AppError::BadRequest("Este código já foi usado".to_string()));
+    }
+
+    if recovery.is_expired(state.clock.now()) {
+        return Ok(failure("Código expirado. Solicite um novo código.", None));
+    }
+
+    let Some(attempts) = state.db.claim_reveal_recovery_attempt(recovery.id).await? else {
+        return Ok(failure(
+            "Número máximo de tentativas excedido. Solicite um novo código.",
+            Some(0),
+        ));
+    };
+
+    if !recovery.code_hash.matches(&req.code.to_string()) {
+        let attempts_remaining = RevealRecovery::MAX_ATTEMPTS - attempts;
+        return Ok(failure(
+            &format!(
+                "Código incorreto. {} tentativas restantes.",
+                attempts_remaining
+            ),
+            Some(attempts_remaining),
+        ));
+    }
+
+    state
+        .db
+        .mark_reveal_recovery_as_verified(recovery.id)
+        .await?;
+
+    let reveals = state
+        .db
+        .find_reveals_by_participant_email(&recovery.email)
+        .await?;
+
+    Ok(Json(VerifyRevealRecoveryResponse {
+        success: true,
+        reveals,
+        error: None,
+        attempts_remaining: None,
     }))
 }
 
@@ -633,13 +2214,57 @@ pub async fn reveal_match(
 /// Initiates the email verification process by generating a 6-digit code
 /// and sending it to the organizer's email. The code expires in 15 minutes.
 ///
+/// If the email domain looks like a typo of a common provider, no code is sent and a
+/// suggested address is returned instead. The client should confirm with the organizer and
+/// retry with `skip_typo_check` set.
+///
 /// Rate limiting: Maximum 3 verification requests per email per hour.
+#[utoipa::path(
+    post,
+    path = "/api/verifications/request",
+    tag = "Verification",
+    summary = "Request an email verification code",
+    request_body = RequestVerificationRequest,
+    responses(
+        (status = 200, description = "Success", body = RequestVerificationResponse)
+    )
+)]
 pub async fn request_verification(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<RequestVerificationRequest>,
+    locale: Locale,
+    client: ClientInfo,
+    Valid(req): Valid<RequestVerificationRequest>,
 ) -> Result<Json<RequestVerificationResponse>, AppError> {
+    validate_organizer_email(&state, &req.organizer_email).await?;
+
+    if !req.skip_typo_check
+        && let Some(suggested_email) = email_suggestion::suggest_correction(&req.organizer_email)
+    {
+        return Ok(Json(RequestVerificationResponse {
+            verification_id: None,
+            suggested_email: Some(suggested_email),
+        }));
+    }
+
+    // Checked only now, since the token can't be reused after a typo suggestion
+    if let Some(captcha) = &state.captcha {
+        let token = req.captcha_token.as_deref().unwrap_or_default();
+        let solved = !token.is_empty()
+            && captcha.verify(token, client.ip).await.map_err(|e| {
+                tracing::error!("failed to verify CAPTCHA: {}", e);
+                AppError::InternalError(
+                    "Não foi possível verificar o CAPTCHA. Tente novamente.".to_string(),
+                )
+            })?;
+        if !solved {
+            return Err(AppError::BadRequest(
+                "Confirme que você não é um robô e tente novamente.".to_string(),
+            ));
+        }
+    }
+
     // Rate limiting: Check if email has requested too many verifications recently
-    let one_hour_ago = Utc::now() - Duration::hours(1);
+    let one_hour_ago = state.clock.now() - Duration::hours(1);
     let recent_count = state
         .db
         .count_recent_verifications_by_email(&req.organizer_email, one_hour_ago)
@@ -652,10 +2277,14 @@ pub async fn request_verification(
     }
 
     // Create verification
-    let verification = EmailVerification::new(
+    let (verification, code, link_token) = EmailVerification::new(
         req.organizer_email.clone(),
-        req.name.clone(),
+        req.name.trim().to_string(),
         req.event_date,
+        normalize_optional_text(req.event_location.as_deref()),
+        req.timezone,
+        req.locale.unwrap_or(locale),
+        state.clock.now(),
     );
 
     // Store in database
@@ -667,7 +2296,10 @@ pub async fn request_verification(
         .send_verification_code(
             &verification.email,
             &verification.game_name,
-            verification.code,
+            verification.id,
+            code,
+            &link_token,
+            verification.locale,
         )
         .await
     {
@@ -678,20 +2310,56 @@ pub async fn request_verification(
     }
 
     Ok(Json(RequestVerificationResponse {
-        verification_id: verification.id,
+        verification_id: Some(verification.id),
+        suggested_email: None,
     }))
 }
 
+/// Rejects addresses that can't be used to organize a game.
+async fn validate_organizer_email(state: &AppState, email: &EmailAddress) -> Result<(), AppError> {
+    if state.email_blocklist.is_blocked(email) {
+        return Err(AppError::BadRequest(
+            "Emails temporários não são aceitos. Use um email que você continuará acessando."
+                .to_string(),
+        ));
+    }
+    check_not_banned(state, email).await
+}
+
+/// Rejects addresses the site admin banned.
+async fn check_not_banned(state: &AppState, email: &EmailAddress) -> Result<(), AppError> {
+    if let Some(ban) = state.db.find_email_ban(email).await? {
+        tracing::info!("rejected banned organizer email (ban {})", ban.id);
+        return Err(AppError::BadRequest(
+            "Este email não pode ser usado para criar jogos.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 /// POST /api/verifications/verify - Verify code and create game
 ///
 /// Validates the 6-digit verification code and creates the game if successful.
 /// On success, sends an admin welcome email with the admin panel link.
 ///
 /// Maximum 5 attempts per verification before it must be requested again.
+#[utoipa::path(
+    post,
+    path = "/api/verifications/verify",
+    tag = "Verification",
+    summary = "Verify the code and create the game",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = idempotency::KEY_DESCRIPTION)
+    ),
+    request_body = VerifyCodeRequest,
+    responses(
+        (status = 200, description = "Success", body = VerifyCodeResponse)
+    )
+)]
 pub async fn verify_code(
     State(state): State<Arc<AppState>>,
     Json(req): Json<VerifyCodeRequest>,
-) -> Result<Json<VerifyCodeResponse>, AppError> {
+) -> Result<Response, AppError> {
     // Get verification
     let verification = state
         .db
@@ -707,14 +2375,15 @@ pub async fn verify_code(
     }
 
     // Check if expired
-    if verification.is_expired() {
+    if verification.is_expired(state.clock.now()) {
         return Ok(Json(VerifyCodeResponse {
             success: false,
             game_id: None,
             admin_token: None,
             error: Some("Código expirado. Solicite um novo código.".to_string()),
             attempts_remaining: None,
-        }));
+        })
+        .into_response());
     }
 
     // Check attempts
@@ -727,11 +2396,12 @@ pub async fn verify_code(
                 "Número máximo de tentativas excedido. Solicite um novo código.".to_string(),
             ),
             attempts_remaining: Some(0),
-        }));
+        })
+        .into_response());
     }
 
     // Verify code
-    if verification.code != req.code {
+    if !verification.code_hash.matches(&req.code.to_string()) {
         // Increment attempts
         state
             .db
@@ -748,23 +2418,162 @@ pub async fn verify_code(
                 attempts_remaining
             )),
             attempts_remaining: Some(attempts_remaining),
-        }));
+        })
+        .into_response());
     }
 
     // Code is correct! Create the game
-    let game = Game::new(
-        verification.game_name.clone(),
-        verification.event_date,
-        verification.email.clone(),
-    );
+    let Some((game, admin_token, session_token)) =
+        create_verified_game(&state, &verification).await?
+    else {
+        return Err(AppError::BadRequest(
+            "Esta verificação já foi usada".to_string(),
+        ));
+    };
 
-    state.db.create_game(&game).await?;
+    let admin_token = state.legacy_admin_tokens.then(|| admin_token.to_string());
+
+    Ok((
+        [(
+            header::SET_COOKIE,
+            organizer_auth::session_cookie(&session_token, state.secure_cookies),
+        )],
+        Json(VerifyCodeResponse {
+            success: true,
+            game_id: Some(game.id),
+            admin_token,
+            error: None,
+            attempts_remaining: None,
+        }),
+    )
+        .into_response())
+}
 
-    // Mark verification as verified
-    state
+/// GET /api/verifications/:verification_id/confirm/:token - Check a verification email link
+///
+/// Only tells whether the link can still be used, so mail scanners and link prefetchers
+/// following it don't create the game. The confirmation page then `POST`s to the same path.
+#[utoipa::path(
+    get,
+    path = "/api/verifications/{verification_id}/confirm/{token}",
+    tag = "Verification",
+    summary = "Check the link in the verification email, without using it",
+    params(
+        ("verification_id" = inline(VerificationId), Path),
+        ("token" = inline(VerificationLinkToken), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = LinkStatusResponse)
+    )
+)]
+pub async fn verification_link_status(
+    State(state): State<Arc<AppState>>,
+    Path((verification_id, token)): Path<(VerificationId, VerificationLinkToken)>,
+) -> Result<Json<LinkStatusResponse>, AppError> {
+    let verification = usable_verification_link(&state, verification_id, &token).await?;
+    Ok(Json(LinkStatusResponse {
+        valid: verification.is_some(),
+    }))
+}
+
+/// POST /api/verifications/:verification_id/confirm/:token - Confirm a verification email link
+///
+/// Creates the game like entering the code would, and starts an organizer session (cookie).
+/// Fails with `410 Gone` if the link is invalid, expired, or already used.
+#[utoipa::path(
+    post,
+    path = "/api/verifications/{verification_id}/confirm/{token}",
+    tag = "Verification",
+    summary = "Confirm the link in the verification email and create the game",
+    params(
+        ("verification_id" = inline(VerificationId), Path),
+        ("token" = inline(VerificationLinkToken), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = VerifyCodeResponse)
+    )
+)]
+pub async fn confirm_verification_link(
+    State(state): State<Arc<AppState>>,
+    Path((verification_id, token)): Path<(VerificationId, VerificationLinkToken)>,
+) -> Result<Response, AppError> {
+    let created = match usable_verification_link(&state, verification_id, &token).await? {
+        Some(verification) => create_verified_game(&state, &verification).await?,
+        None => None,
+    };
+    let Some((game, admin_token, session_token)) = created else {
+        return Err(AppError::Gone(
+            "Este link de confirmação é inválido, expirou ou já foi usado".to_string(),
+        ));
+    };
+
+    let admin_token = state.legacy_admin_tokens.then(|| admin_token.to_string());
+
+    Ok((
+        [(
+            header::SET_COOKIE,
+            organizer_auth::session_cookie(&session_token, state.secure_cookies),
+        )],
+        Json(VerifyCodeResponse {
+            success: true,
+            game_id: Some(game.id),
+            admin_token,
+            error: None,
+            attempts_remaining: None,
+        }),
+    )
+        .into_response())
+}
+
+/// The verification a link confirms, unless it's wrong, expired or already used.
+async fn usable_verification_link(
+    state: &AppState,
+    verification_id: VerificationId,
+    token: &VerificationLinkToken,
+) -> Result<Option<EmailVerification>, AppError> {
+    Ok(state
         .db
-        .mark_verification_as_verified(req.verification_id)
-        .await?;
+        .get_email_verification_by_id(verification_id)
+        .await?
+        .filter(|v| {
+            v.link_token_hash
+                .as_ref()
+                .is_some_and(|hash| hash.matches(token.as_str()))
+        })
+        .filter(|v| !v.verified && !v.is_expired(state.clock.now())))
+}
+
+/// Creates the game of a verification whose email was just confirmed, and logs the organizer
+/// in. Returns `None` if the verification was used in the meantime.
+async fn create_verified_game(
+    state: &AppState,
+    verification: &EmailVerification,
+) -> Result<Option<(Game, AdminToken, OrganizerSessionToken)>, AppError> {
+    // Banned after requesting the verification
+    check_not_banned(state, &verification.email).await?;
+
+    if !state
+        .db
+        .mark_verification_as_verified(verification.id)
+        .await?
+    {
+        return Ok(None);
+    }
+
+    let admin_token = AdminToken::generate();
+    let game = Game {
+        admin_token_hash: admin_token.hash(),
+        event_location: verification.event_location.clone(),
+        timezone: verification.timezone,
+        locale: verification.locale,
+        ..Game::new(
+            verification.game_name.clone(),
+            verification.event_date,
+            verification.email.clone(),
+        )
+    };
+
+    state.db.create_game(&game).await?;
 
     // Send admin welcome email
     if let Err(e) = state
@@ -774,7 +2583,8 @@ pub async fn verify_code(
             &game.name,
             game.event_date,
             game.id,
-            &game.admin_token,
+            &admin_token,
+            game.locale,
         )
         .await
     {
@@ -782,19 +2592,29 @@ pub async fn verify_code(
         // Don't fail the request if email fails
     }
 
-    Ok(Json(VerifyCodeResponse {
-        success: true,
-        game_id: Some(game.id),
-        admin_token: Some(game.admin_token.to_string()),
-        error: None,
-        attempts_remaining: None,
-    }))
+    // The organizer just proved ownership of the email, so log them in as well
+    let session_token = state
+        .db
+        .create_organizer_session(&game.organizer_email)
+        .await?;
+
+    Ok(Some((game, admin_token, session_token)))
 }
 
 /// POST /api/verifications/resend - Resend verification code
 ///
 /// Generates and sends a new 6-digit verification code, resetting the attempt counter.
 /// The new code expires in 15 minutes. Rate limiting applies (max 3 per hour).
+#[utoipa::path(
+    post,
+    path = "/api/verifications/resend",
+    tag = "Verification",
+    summary = "Resend the verification code",
+    request_body = ResendVerificationRequest,
+    responses(
+        (status = 200, description = "Success", body = ResendVerificationResponse)
+    )
+)]
 pub async fn resend_verification(
     State(state): State<Arc<AppState>>,
     Json(req): Json<ResendVerificationRequest>,
@@ -815,7 +2635,7 @@ pub async fn resend_verification(
     }
 
     // Rate limiting: Check recent verifications for this email
-    let one_hour_ago = Utc::now() - Duration::hours(1);
+    let one_hour_ago = state.clock.now() - Duration::hours(1);
     let recent_count = state
         .db
         .count_recent_verifications_by_email(&verification.email, one_hour_ago)
@@ -828,20 +2648,33 @@ pub async fn resend_verification(
         }));
     }
 
-    // Generate new code
+    // Generate new code and link
     let new_code = VerificationCode::generate();
-    let new_expires_at = Utc::now() + Duration::minutes(15);
+    let new_link_token = VerificationLinkToken::generate();
+    let new_expires_at = state.clock.now() + Duration::minutes(15);
 
     // Update verification with new code
     state
         .db
-        .update_verification_code(req.verification_id, new_code, new_expires_at)
+        .update_verification_code(
+            req.verification_id,
+            &new_code.hash(),
+            &new_link_token.hash(),
+            new_expires_at,
+        )
         .await?;
 
     // Send new verification email
     if let Err(e) = state
         .email_service
-        .send_verification_code(&verification.email, &verification.game_name, new_code)
+        .send_verification_code(
+            &verification.email,
+            &verification.game_name,
+            verification.id,
+            new_code,
+            &new_link_token,
+            verification.locale,
+        )
         .await
     {
         tracing::error!("failed to resend verification email: {}", e);
@@ -857,84 +2690,866 @@ pub async fn resend_verification(
     }))
 }
 
-// Site admin endpoints
+// Organizer login endpoints
 
-/// POST /api/site-admin/login - Authenticate with password and get session token
-pub async fn site_admin_login(
+/// POST /api/organizer/login - Request a magic login link by email
+///
+/// Always reports success so the response doesn't reveal whether the email organizes any
+/// games. Rate limiting: Maximum 3 links per email per hour, within a per-IP budget.
+#[utoipa::path(
+    post,
+    path = "/api/organizer/login",
+    tag = "Organizer",
+    summary = "Email a magic login link to an organizer",
+    request_body = OrganizerLoginRequest,
+    responses(
+        (status = 200, description = "Success", body = Object)
+    )
+)]
+pub async fn request_organizer_login(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<SiteAdminLoginRequest>,
-) -> Result<Json<SiteAdminLoginResponse>, AppError> {
-    // Verify password
-    let valid = state.db.verify_site_admin_password(&req.password).await?;
-
-    if !valid {
-        tracing::warn!("failed site admin login attempt");
-        return Err(AppError::Unauthorized("Senha incorreta".to_string()));
+    locale: Locale,
+    Valid(req): Valid<OrganizerLoginRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let now = state.clock.now();
+    let recent = state
+        .db
+        .recent_organizer_login_links(&req.email, now - Duration::hours(1))
+        .await?;
+    if let Some(retry_after) = recent.retry_after(3, Duration::hours(1), now) {
+        return Err(AppError::TooManyRequests(retry_after));
     }
 
-    // Create session
-    let session_token = state.db.create_admin_session().await?;
-    let expires_at = Utc::now() + Duration::hours(24);
-
-    tracing::info!("site admin logged in");
-
-    Ok(Json(SiteAdminLoginResponse {
-        session_token: session_token.to_string(),
-        expires_at,
-    }))
-}
-
-/// POST /api/site-admin/logout - Invalidate the current session
-pub async fn site_admin_logout(
-    State(state): State<Arc<AppState>>,
-    AuthenticatedAdmin(session_token): AuthenticatedAdmin,
-) -> Result<Json<serde_json::Value>, AppError> {
-    state.db.delete_admin_session(&session_token).await?;
+    if state.db.count_games_by_organizer_email(&req.email).await? > 0 {
+        let token = state
+            .db
+            .create_organizer_login_link(&req.email, req.game_id)
+            .await?;
 
-    tracing::info!("site admin logged out");
+        if let Err(e) = state
+            .email_service
+            .send_organizer_login_link(&req.email, &token, locale)
+            .await
+        {
+            tracing::error!("failed to send organizer login email: {}", e);
+            return Err(AppError::InternalError(
+                "Erro ao enviar email de acesso".to_string(),
+            ));
+        }
+    }
 
     Ok(Json(serde_json::json!({
         "success": true,
-        "message": "Sessão encerrada com sucesso"
+        "message": "Se houver jogos organizados por este email, você receberá um link de acesso."
     })))
 }
 
-/// POST /api/site-admin/change-password - Change the site admin password
-pub async fn site_admin_change_password(
+/// GET /api/organizer/login/:token - Check a magic login link
+///
+/// Only tells whether the link can still be used, so mail scanners and link prefetchers
+/// following it don't use it up. The login page then `POST`s to the same path.
+#[utoipa::path(
+    get,
+    path = "/api/organizer/login/{token}",
+    tag = "Organizer",
+    summary = "Check a magic login link, without using it",
+    params(
+        ("token" = inline(OrganizerLoginToken), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = LinkStatusResponse)
+    )
+)]
+pub async fn organizer_login_link_status(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<ChangePasswordRequest>,
-) -> Result<Json<serde_json::Value>, AppError> {
-    // Validate new password
-    if req.new_password.is_empty() {
-        return Err(AppError::BadRequest(
-            "Nova senha não pode ser vazia".to_string(),
-        ));
-    }
+    Path(token): Path<OrganizerLoginToken>,
+) -> Result<Json<LinkStatusResponse>, AppError> {
+    Ok(Json(LinkStatusResponse {
+        valid: state.db.organizer_login_link_is_valid(&token).await?,
+    }))
+}
 
-    if req.new_password.len() < 8 {
-        return Err(AppError::BadRequest(
-            "Nova senha deve ter pelo menos 8 caracteres".to_string(),
+/// POST /api/organizer/login/:token - Log in with a magic login link
+///
+/// Starts an organizer session (cookie) and answers with the game the link was requested
+/// from, if any. Fails with `410 Gone` if the link is invalid, expired, or already used.
+#[utoipa::path(
+    post,
+    path = "/api/organizer/login/{token}",
+    tag = "Organizer",
+    summary = "Log in with a magic login link: sets the session cookie",
+    params(
+        ("token" = inline(OrganizerLoginToken), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = OrganizerLoginResponse)
+    )
+)]
+pub async fn organizer_login(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<OrganizerLoginToken>,
+) -> Result<Response, AppError> {
+    let Some((email, game_id)) = state.db.consume_organizer_login_link(&token).await? else {
+        return Err(AppError::Gone(
+            "Este link de acesso é inválido, expirou ou já foi usado".to_string(),
         ));
-    }
+    };
 
-    // Change password
-    let success = state
-        .db
-        .change_site_admin_password(&req.current_password, &req.new_password)
-        .await?;
+    let session_token = state.db.create_organizer_session(&email).await?;
 
-    if !success {
-        return Err(AppError::Unauthorized("Senha atual incorreta".to_string()));
-    }
+    tracing::info!("organizer logged in via magic link");
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "Senha alterada com sucesso"
-    })))
+    Ok((
+        [(
+            header::SET_COOKIE,
+            organizer_auth::session_cookie(&session_token, state.secure_cookies),
+        )],
+        Json(OrganizerLoginResponse { game_id }),
+    )
+        .into_response())
 }
 
-/// GET /api/site-admin/games - Search and list games with pagination
-pub async fn site_admin_search_games(
+/// GET /api/my/games - List every game organized by the logged-in organizer
+#[utoipa::path(
+    get,
+    path = "/api/my/games",
+    tag = "Organizer",
+    summary = "Games of the logged-in organizer",
+    responses(
+        (status = 200, description = "Success", body = MyGamesResponse)
+    ),
+    security(
+        ("organizerSession" = [])
+    )
+)]
+pub async fn get_my_games(
+    State(state): State<Arc<AppState>>,
+    credentials: OrganizerCredentials,
+) -> Result<Json<MyGamesResponse>, AppError> {
+    let email = credentials
+        .session_email(&state.db)
+        .await?
+        .ok_or(AppError::Unauthorized(
+            "Faça login para ver seus jogos".to_string(),
+        ))?;
+
+    let games = state.db.get_games_by_organizer_email(&email).await?;
+
+    let mut game_summaries = Vec::new();
+    for game in games {
+        let participant_count = state.db.count_participants_in_game(game.id).await?;
+        game_summaries.push(GameSummary::new(game, participant_count));
+    }
+
+    Ok(Json(MyGamesResponse {
+        email,
+        games: game_summaries,
+    }))
+}
+
+/// POST /api/reports - Report a game for unwanted emails
+///
+/// Rate limiting: Maximum 5 reports per game per hour, within a per-IP budget.
+#[utoipa::path(
+    post,
+    path = "/api/reports",
+    tag = "Organizer",
+    summary = "Report a game for unwanted emails",
+    request_body = CreateReportRequest,
+    responses(
+        (status = 200, description = "Success", body = Object)
+    )
+)]
+pub async fn create_report(
+    State(state): State<Arc<AppState>>,
+    Valid(req): Valid<CreateReportRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let reason = req.reason.trim();
+
+    let game_id = match (&req.view_token, req.game_id) {
+        (Some(view_token), _) => state
+            .db
+            .get_participant_by_view_token(view_token)
+            .await?
+            .map(|participant| participant.game_id),
+        (None, Some(game_id)) => state.db.get_game_by_id(game_id).await?.map(|game| game.id),
+        (None, None) => {
+            return Err(AppError::BadRequest(
+                "Informe o link recebido ou o código do jogo".to_string(),
+            ));
+        }
+    }
+    .ok_or(AppError::AccessDenied)?;
+
+    // Rate limiting: Check recent reports for this game
+    let now = state.clock.now();
+    let recent = state
+        .db
+        .recent_abuse_reports(game_id, now - Duration::hours(1))
+        .await?;
+    if let Some(retry_after) = recent.retry_after(5, Duration::hours(1), now) {
+        return Err(AppError::TooManyRequests(retry_after));
+    }
+
+    let report_id = state.db.create_abuse_report(game_id, reason).await?;
+
+    tracing::warn!("abuse report {} filed for game {}", report_id, game_id);
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Denúncia recebida. Obrigado por nos avisar."
+    })))
+}
+
+/// POST /api/organizer/logout - End the organizer session
+#[utoipa::path(
+    post,
+    path = "/api/organizer/logout",
+    tag = "Organizer",
+    summary = "End the organizer session",
+    responses(
+        (status = 200, description = "Success", body = Object)
+    )
+)]
+pub async fn organizer_logout(
+    State(state): State<Arc<AppState>>,
+    credentials: OrganizerCredentials,
+) -> Result<Response, AppError> {
+    if let Some(session_token) = credentials.session_token() {
+        state.db.delete_organizer_session(session_token).await?;
+    }
+
+    Ok((
+        [(
+            header::SET_COOKIE,
+            organizer_auth::clear_session_cookie(state.secure_cookies),
+        )],
+        Json(serde_json::json!({
+            "success": true,
+            "message": "Sessão encerrada com sucesso"
+        })),
+    )
+        .into_response())
+}
+
+// Site admin endpoints
+
+/// POST /api/site-admin/login - Authenticate with password and get session token
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/login",
+    tag = "Site admin",
+    summary = "Log in with the password and get a session token",
+    request_body = SiteAdminLoginRequest,
+    responses(
+        (status = 200, description = "Success", body = SiteAdminLoginResponse)
+    )
+)]
+pub async fn site_admin_login(
+    State(state): State<Arc<AppState>>,
+    client: ClientInfo,
+    Json(req): Json<SiteAdminLoginRequest>,
+) -> Result<Json<SiteAdminLoginResponse>, AppError> {
+    let ip_prefix = client.ip_prefix();
+    let record = async |outcome| {
+        state
+            .db
+            .record_site_admin_login_attempt(
+                outcome,
+                client.user_agent.as_deref(),
+                ip_prefix.as_deref(),
+            )
+            .await
+    };
+
+    // Checked before the password, so a locked out client can't keep guessing
+    if let Some(retry_after) = site_admin_login_lockout(&state, ip_prefix.as_deref()).await? {
+        tracing::warn!("site admin login rejected by lockout");
+        record(LoginOutcome::LockedOut).await?;
+        return Err(AppError::TooManyRequests(retry_after));
+    }
+
+    // Verify password
+    let valid = state.db.verify_site_admin_password(&req.password).await?;
+
+    if !valid {
+        tracing::warn!("failed site admin login attempt");
+        record(LoginOutcome::WrongPassword).await?;
+        return Err(AppError::Unauthorized("Senha incorreta".to_string()));
+    }
+
+    if state.db.site_admin_totp_enabled().await? {
+        let Some(code) = req.totp_code.filter(|code| !code.trim().is_empty()) else {
+            return Ok(Json(SiteAdminLoginResponse::TotpRequired {
+                totp_required: true,
+            }));
+        };
+        if !state.db.verify_site_admin_second_factor(&code).await? {
+            tracing::warn!("site admin login with wrong one-time password");
+            record(LoginOutcome::WrongCode).await?;
+            return Err(AppError::Unauthorized(
+                "Código de verificação incorreto".to_string(),
+            ));
+        }
+    }
+
+    // Create session
+    let session_token = state.admin_sessions.create().await?;
+    let expires_at = state.clock.now() + Duration::hours(db::ADMIN_SESSION_DURATION_HOURS);
+    record(LoginOutcome::Success).await?;
+
+    tracing::info!("site admin logged in");
+
+    Ok(Json(SiteAdminLoginResponse::Session {
+        session_token: session_token.to_string(),
+        expires_at,
+    }))
+}
+
+/// How long password logins must wait after recent failures, from the client's network or
+/// from anywhere, whichever is longer.
+async fn site_admin_login_lockout(
+    state: &AppState,
+    ip_prefix: Option<&str>,
+) -> Result<Option<Duration>, AppError> {
+    let mut allowed_at = None;
+    let mut limits = vec![(None, site_admin_auth::ACCOUNT_LOCKOUT_FAILURES)];
+    if let Some(ip_prefix) = ip_prefix {
+        limits.push((Some(ip_prefix), site_admin_auth::IP_LOCKOUT_FAILURES));
+    }
+    for (ip_prefix, lockout_failures) in limits {
+        let (failures, last_failure) = state.db.site_admin_login_failures(ip_prefix).await?;
+        let limit = last_failure.and_then(|last_failure| {
+            site_admin_auth::login_allowed_at(failures, last_failure, lockout_failures)
+        });
+        allowed_at = allowed_at.max(limit);
+    }
+
+    let now = state.clock.now();
+    Ok(allowed_at
+        .filter(|&allowed_at| allowed_at > now)
+        .map(|allowed_at| allowed_at - now))
+}
+
+/// POST /api/site-admin/logout - Invalidate the current session
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/logout",
+    tag = "Site admin",
+    summary = "End the current session",
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_logout(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(session_token): AuthenticatedAdmin,
+) -> Result<Json<serde_json::Value>, AppError> {
+    state.admin_sessions.delete(&session_token).await?;
+
+    tracing::info!("site admin logged out");
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Sessão encerrada com sucesso"
+    })))
+}
+
+/// GET /api/site-admin/sessions - List active sessions
+#[utoipa::path(
+    get,
+    path = "/api/site-admin/sessions",
+    tag = "Site admin",
+    summary = "Active sessions",
+    responses(
+        (status = 200, description = "Success", body = Vec<AdminSession>)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_list_sessions(
+    State(state): State<Arc<AppState>>,
+    AuthenticatedAdmin(session_token): AuthenticatedAdmin,
+) -> Result<Json<Vec<AdminSession>>, AppError> {
+    Ok(Json(state.admin_sessions.list(&session_token).await?))
+}
+
+/// GET /api/site-admin/login-attempts - Recent password login attempts, for audit
+#[utoipa::path(
+    get,
+    path = "/api/site-admin/login-attempts",
+    tag = "Site admin",
+    summary = "Recent password login attempts",
+    responses(
+        (status = 200, description = "Success", body = Vec<SiteAdminLoginAttempt>)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_list_login_attempts(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<SiteAdminLoginAttempt>>, AppError> {
+    Ok(Json(
+        state
+            .db
+            .list_site_admin_login_attempts(LOGIN_ATTEMPTS_SHOWN)
+            .await?,
+    ))
+}
+
+/// DELETE /api/site-admin/sessions/:session_id - Revoke a session, e.g. on a lost device
+#[utoipa::path(
+    delete,
+    path = "/api/site-admin/sessions/{session_id}",
+    tag = "Site admin",
+    summary = "Revoke a session",
+    params(
+        ("session_id" = inline(AdminSessionId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_revoke_session(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<AdminSessionId>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    if !state.admin_sessions.delete_by_id(session_id).await? {
+        return Err(AppError::NotFound("Sessão não encontrada".to_string()));
+    }
+
+    tracing::info!("site admin session revoked");
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Sessão encerrada com sucesso"
+    })))
+}
+
+/// POST /api/site-admin/change-password - Change the site admin password
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/change-password",
+    tag = "Site admin",
+    summary = "Change the site admin password",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_change_password(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ChangePasswordRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    // Validate new password
+    if req.new_password.is_empty() {
+        return Err(AppError::BadRequest(
+            "Nova senha não pode ser vazia".to_string(),
+        ));
+    }
+
+    if req.new_password.len() < 8 {
+        return Err(AppError::BadRequest(
+            "Nova senha deve ter pelo menos 8 caracteres".to_string(),
+        ));
+    }
+
+    // Change password
+    let success = state
+        .db
+        .change_site_admin_password(&req.current_password, &req.new_password)
+        .await?;
+
+    if !success {
+        return Err(AppError::Unauthorized("Senha atual incorreta".to_string()));
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Senha alterada com sucesso"
+    })))
+}
+
+/// GET /api/site-admin/totp - Whether two-step verification is enabled
+#[utoipa::path(
+    get,
+    path = "/api/site-admin/totp",
+    tag = "Site admin",
+    summary = "Whether two-step verification is enabled",
+    responses(
+        (status = 200, description = "Success", body = TotpStatusResponse)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_totp_status(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<TotpStatusResponse>, AppError> {
+    Ok(Json(TotpStatusResponse {
+        enabled: state.db.site_admin_totp_enabled().await?,
+        recovery_codes_remaining: state.db.count_unused_site_admin_recovery_codes().await?,
+    }))
+}
+
+/// POST /api/site-admin/totp/enroll - Start setting up two-step verification
+///
+/// Returns a new secret for the authenticator app. It's only required at login once a code
+/// from it is confirmed, so an abandoned setup can't lock the admin out.
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/totp/enroll",
+    tag = "Site admin",
+    summary = "Start setting up two-step verification",
+    responses(
+        (status = 200, description = "Success", body = TotpEnrollmentResponse)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_enroll_totp(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<TotpEnrollmentResponse>, AppError> {
+    let secret = totp::generate_secret();
+    if !state.db.start_site_admin_totp_enrollment(&secret).await? {
+        return Err(AppError::BadRequest(
+            "A verificação em duas etapas já está ativada".to_string(),
+        ));
+    }
+
+    Ok(Json(TotpEnrollmentResponse {
+        otpauth_url: totp::otpauth_url(&secret).context("generated TOTP secret is invalid")?,
+        secret,
+    }))
+}
+
+/// POST /api/site-admin/totp/confirm - Enable two-step verification with a code from the app
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/totp/confirm",
+    tag = "Site admin",
+    summary = "Enable two-step verification with a code from the app",
+    request_body = ConfirmTotpRequest,
+    responses(
+        (status = 200, description = "Success", body = ConfirmTotpResponse)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_confirm_totp(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ConfirmTotpRequest>,
+) -> Result<Json<ConfirmTotpResponse>, AppError> {
+    if state.db.site_admin_totp_enabled().await? {
+        return Err(AppError::BadRequest(
+            "A verificação em duas etapas já está ativada".to_string(),
+        ));
+    }
+    if !state.db.check_site_admin_totp(&req.code).await? {
+        return Err(AppError::Unauthorized(
+            "Código de verificação incorreto".to_string(),
+        ));
+    }
+
+    let recovery_codes = state.db.enable_site_admin_totp().await?;
+    Ok(Json(ConfirmTotpResponse { recovery_codes }))
+}
+
+/// POST /api/site-admin/totp/disable - Turn off two-step verification
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/totp/disable",
+    tag = "Site admin",
+    summary = "Turn off two-step verification",
+    request_body = DisableTotpRequest,
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_disable_totp(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<DisableTotpRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    if !state.db.verify_site_admin_password(&req.password).await? {
+        return Err(AppError::Unauthorized("Senha incorreta".to_string()));
+    }
+    if !state.db.site_admin_totp_enabled().await? {
+        return Err(AppError::BadRequest(
+            "A verificação em duas etapas não está ativada".to_string(),
+        ));
+    }
+    if !state.db.verify_site_admin_second_factor(&req.code).await? {
+        return Err(AppError::Unauthorized(
+            "Código de verificação incorreto".to_string(),
+        ));
+    }
+
+    state.db.disable_site_admin_totp().await?;
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Verificação em duas etapas desativada"
+    })))
+}
+
+/// The site as a WebAuthn relying party, if passkeys can be used at its base URL.
+fn relying_party(state: &AppState) -> Result<&RelyingParty, AppError> {
+    state.relying_party.as_ref().ok_or_else(|| {
+        AppError::BadRequest("Passkeys não estão disponíveis neste endereço".to_string())
+    })
+}
+
+/// GET /api/site-admin/passkeys - List the passkeys registered for login
+#[utoipa::path(
+    get,
+    path = "/api/site-admin/passkeys",
+    tag = "Site admin",
+    summary = "Passkeys registered for login",
+    responses(
+        (status = 200, description = "Success", body = Vec<SiteAdminPasskey>)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_list_passkeys(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<SiteAdminPasskey>>, AppError> {
+    Ok(Json(state.db.list_site_admin_passkeys().await?))
+}
+
+/// POST /api/site-admin/passkeys/register/options - Options for creating a passkey
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/passkeys/register/options",
+    tag = "Site admin",
+    summary = "Options for creating a passkey",
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_passkey_registration_options(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let existing: Vec<_> = state
+        .db
+        .site_admin_passkey_credentials()
+        .await?
+        .into_iter()
+        .map(|credential| credential.passkey)
+        .collect();
+    let started = relying_party(&state)?.start_registration(&existing)?;
+    state
+        .db
+        .store_webauthn_challenge(&started.challenge, Ceremony::Registration, &started.state)
+        .await?;
+    Ok(Json(started.options))
+}
+
+/// POST /api/site-admin/passkeys/register - Store a passkey created by the browser
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/passkeys/register",
+    tag = "Site admin",
+    summary = "Store a passkey created by the browser",
+    request_body = RegisterPasskeyRequest,
+    responses(
+        (status = 200, description = "Success", body = SiteAdminPasskey)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_register_passkey(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<RegisterPasskeyRequest>,
+) -> Result<Json<SiteAdminPasskey>, AppError> {
+    let name = req.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_PASSKEY_NAME_LENGTH {
+        return Err(AppError::BadRequest(format!(
+            "O nome da passkey deve ter entre 1 e {} caracteres",
+            MAX_PASSKEY_NAME_LENGTH
+        )));
+    }
+    let relying_party = relying_party(&state)?;
+
+    let passkey = async {
+        let challenge = webauthn::answered_challenge(&req.credential.response.client_data_json)?;
+        let ceremony = state
+            .db
+            .take_webauthn_challenge(&challenge, Ceremony::Registration)
+            .await?
+            .context("unknown or expired challenge")?;
+        relying_party.finish_registration(&req.credential, &ceremony)
+    }
+    .await
+    .map_err(|e| {
+        tracing::warn!("rejected site admin passkey registration: {e:#}");
+        AppError::BadRequest("Passkey inválida. Tente novamente.".to_string())
+    })?;
+
+    Ok(Json(state.db.add_site_admin_passkey(&passkey, name).await?))
+}
+
+/// DELETE /api/site-admin/passkeys/:passkey_id - Remove a passkey
+#[utoipa::path(
+    delete,
+    path = "/api/site-admin/passkeys/{passkey_id}",
+    tag = "Site admin",
+    summary = "Remove a passkey",
+    params(
+        ("passkey_id" = inline(PasskeyId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_delete_passkey(
+    State(state): State<Arc<AppState>>,
+    Path(passkey_id): Path<PasskeyId>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    if !state.db.delete_site_admin_passkey(passkey_id).await? {
+        return Err(AppError::NotFound("Passkey não encontrada".to_string()));
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Passkey removida"
+    })))
+}
+
+/// POST /api/site-admin/passkeys/login/options - Options for logging in with a passkey
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/passkeys/login/options",
+    tag = "Site admin",
+    summary = "Options for logging in with a passkey",
+    responses(
+        (status = 200, description = "Success", body = Object)
+    )
+)]
+pub async fn site_admin_passkey_login_options(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let passkeys: Vec<_> = state
+        .db
+        .site_admin_passkey_credentials()
+        .await?
+        .into_iter()
+        .map(|credential| credential.passkey)
+        .collect();
+    if passkeys.is_empty() {
+        return Err(AppError::BadRequest(
+            "Nenhuma passkey cadastrada".to_string(),
+        ));
+    }
+
+    let started = relying_party(&state)?.start_authentication(&passkeys)?;
+    state
+        .db
+        .store_webauthn_challenge(&started.challenge, Ceremony::Authentication, &started.state)
+        .await?;
+    Ok(Json(started.options))
+}
+
+/// POST /api/site-admin/passkeys/login - Log in with a passkey instead of the password
+///
+/// A passkey already proves possession of a device, so two-step verification isn't asked.
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/passkeys/login",
+    tag = "Site admin",
+    summary = "Log in with a passkey instead of the password",
+    request_body = PasskeyLoginRequest,
+    responses(
+        (status = 200, description = "Success", body = SiteAdminLoginResponse)
+    )
+)]
+pub async fn site_admin_passkey_login(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<PasskeyLoginRequest>,
+) -> Result<Json<SiteAdminLoginResponse>, AppError> {
+    let relying_party = relying_party(&state)?;
+    let (passkey_id, updated) = async {
+        let challenge = webauthn::answered_challenge(&req.credential.response.client_data_json)?;
+        let ceremony = state
+            .db
+            .take_webauthn_challenge(&challenge, Ceremony::Authentication)
+            .await?
+            .context("unknown or expired challenge")?;
+        let result = relying_party.finish_authentication(&req.credential, &ceremony)?;
+
+        let mut credential = state
+            .db
+            .get_site_admin_passkey_credential(&BASE64_URL_SAFE_NO_PAD.encode(result.cred_id()))
+            .await?
+            .context("unknown credential")?;
+        let updated = credential
+            .passkey
+            .update_credential(&result)
+            .unwrap_or(false)
+            .then_some(credential.passkey);
+        Ok((credential.id, updated))
+    }
+    .await
+    .map_err(|e: anyhow::Error| {
+        tracing::warn!("failed site admin passkey login: {e:#}");
+        AppError::Unauthorized("Passkey inválida. Tente novamente.".to_string())
+    })?;
+
+    state
+        .db
+        .record_site_admin_passkey_use(passkey_id, updated.as_ref())
+        .await?;
+    let session_token = state.admin_sessions.create().await?;
+    let expires_at = state.clock.now() + Duration::hours(db::ADMIN_SESSION_DURATION_HOURS);
+
+    tracing::info!("site admin logged in with a passkey");
+
+    Ok(Json(SiteAdminLoginResponse::Session {
+        session_token: session_token.to_string(),
+        expires_at,
+    }))
+}
+
+/// GET /api/site-admin/games - Search and list games with pagination
+#[utoipa::path(
+    get,
+    path = "/api/site-admin/games",
+    tag = "Site admin",
+    summary = "Search and list games",
+    params(
+        SearchGamesQuery
+    ),
+    responses(
+        (status = 200, description = "Success", body = SearchGamesResponse)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_search_games(
     State(state): State<Arc<AppState>>,
     Query(query): Query<SearchGamesQuery>,
 ) -> Result<Json<SearchGamesResponse>, AppError> {
@@ -948,91 +3563,1242 @@ pub async fn site_admin_search_games(
         .search_games(query.search.as_deref(), limit, offset)
         .await?;
 
-    // Get total count
-    let total = state.db.count_games(query.search.as_deref()).await?;
+    // Get total count
+    let total = state.db.count_games(query.search.as_deref()).await?;
+
+    // Build response with participant counts
+    let mut game_summaries = Vec::new();
+    for game in games {
+        let participant_count = state.db.count_participants_in_game(game.id).await?;
+        game_summaries.push(GameSummary::new(game, participant_count));
+    }
+
+    Ok(Json(SearchGamesResponse {
+        games: game_summaries,
+        total,
+        limit,
+        offset,
+    }))
+}
+
+/// POST /api/site-admin/games/bulk-delete - Delete every game matching a filter
+///
+/// Defaults to a dry run that only lists the matching games; pass `dry_run: false` to
+/// actually delete them.
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/games/bulk-delete",
+    tag = "Site admin",
+    summary = "Delete every game matching a filter",
+    request_body = BulkDeleteGamesRequest,
+    responses(
+        (status = 200, description = "Success", body = BulkDeleteGamesResponse)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_bulk_delete_games(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<BulkDeleteGamesRequest>,
+) -> Result<Json<BulkDeleteGamesResponse>, AppError> {
+    if !req.undrawn_only && req.older_than_days.is_none() && req.organizer_domain.is_none() {
+        return Err(AppError::BadRequest(
+            "Informe pelo menos um filtro".to_string(),
+        ));
+    }
+
+    let created_before = req
+        .older_than_days
+        .map(|days| state.clock.now() - Duration::days(days.into()));
+    let games = state
+        .db
+        .find_games_for_cleanup(
+            req.undrawn_only,
+            created_before,
+            req.organizer_domain.as_ref(),
+        )
+        .await?;
+
+    let mut game_summaries = Vec::new();
+    for game in games {
+        let participant_count = state.db.count_participants_in_game(game.id).await?;
+        game_summaries.push(GameSummary::new(game, participant_count));
+    }
+
+    if !req.dry_run {
+        for game in &game_summaries {
+            state.db.delete_game(game.id).await?;
+        }
+        tracing::info!("site admin bulk deleted {} games", game_summaries.len());
+    }
+
+    Ok(Json(BulkDeleteGamesResponse {
+        dry_run: req.dry_run,
+        games: game_summaries,
+    }))
+}
+
+/// POST /api/site-admin/games/import - Recreate a game from an organizer's JSON export
+///
+/// The imported game gets a new ID and fresh tokens, so the organizer is emailed a new
+/// admin link. Participants are not emailed; the organizer can resend their links.
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/games/import",
+    tag = "Site admin",
+    summary = "Recreate a game from an organizer's JSON export",
+    request_body = GameExport,
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_import_game(
+    State(state): State<Arc<AppState>>,
+    Json(export): Json<GameExport>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let participant_count = u64::try_from(export.participants.len())
+        .context("converting imported participant count to u64")?;
+    if participant_count > MAX_PARTICIPANTS_PER_GAME {
+        return Err(AppError::ParticipantLimit);
+    }
+
+    let (game, participants) = export.into_game();
+    let admin_token = AdminToken::generate();
+    let game = Game {
+        admin_token_hash: admin_token.hash(),
+        ..game
+    };
+
+    // All or nothing, so a failed import can be retried without leaving a partial copy
+    let mut tx = state.db.begin().await?;
+    tx.create_game(&game).await?;
+    for participant in &participants {
+        tx.add_participant(participant).await?;
+    }
+    tx.commit().await?;
+
+    tracing::info!(
+        "site admin imported game {} ({}) with {} participants",
+        game.id,
+        game.name,
+        participants.len()
+    );
+
+    if let Err(e) = state
+        .email_service
+        .send_admin_welcome(
+            &game.organizer_email,
+            &game.name,
+            game.event_date,
+            game.id,
+            &admin_token,
+            game.locale,
+        )
+        .await
+    {
+        tracing::error!("failed to send admin welcome email: {}", e);
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "game_id": game.id,
+        "drawn": game.drawn
+    })))
+}
+
+/// GET /api/site-admin/games/:game_id - Get full game details including admin token, participants
+/// (but not their matches) and email history
+#[utoipa::path(
+    get,
+    path = "/api/site-admin/games/{game_id}",
+    tag = "Site admin",
+    summary = "Full details of a game",
+    params(
+        ("game_id" = inline(GameId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = GameDetailResponse)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_get_game(
+    State(state): State<Arc<AppState>>,
+    Path(game_id): Path<GameId>,
+) -> Result<Json<GameDetailResponse>, AppError> {
+    let status = state
+        .db
+        .game_summary_including_deleted(game_id)
+        .await?
+        .ok_or(AppError::NotFound("Jogo não encontrado".to_string()))?;
+
+    let participant_count =
+        u64::try_from(status.participants.len()).context("converting participant count to u64")?;
+    let match_accesses = state.db.get_match_accesses(game_id).await?;
+    let email_jobs = state.db.get_email_jobs_by_game(game_id).await?;
+    let email_resends = state.db.get_email_resends(game_id).await?;
+
+    // Statuses leave out who drew whom, which not even the site admin gets to see here
+    Ok(Json(GameDetailResponse {
+        game: status.game,
+        participants: status.participants,
+        participant_count,
+        match_accesses,
+        email_jobs,
+        email_resends,
+        bulk_resends: status.bulk_resend_count,
+    }))
+}
+
+/// DELETE /api/site-admin/games/:game_id - Permanently delete a game
+#[utoipa::path(
+    delete,
+    path = "/api/site-admin/games/{game_id}",
+    tag = "Site admin",
+    summary = "Permanently delete a game",
+    params(
+        ("game_id" = inline(GameId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_delete_game(
+    State(state): State<Arc<AppState>>,
+    Path(game_id): Path<GameId>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    // Verify game exists
+    let game = state
+        .db
+        .get_game_by_id_including_deleted(game_id)
+        .await?
+        .ok_or(AppError::NotFound("Jogo não encontrado".to_string()))?;
+
+    // Delete game (participants will be cascade deleted)
+    state.db.delete_game(game_id).await?;
+
+    tracing::info!(
+        "site admin deleted game {} ({}) organized by {}",
+        game_id,
+        game.name,
+        game.organizer_email
+    );
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Jogo excluído com sucesso"
+    })))
+}
+
+/// POST /api/site-admin/games/:game_id/restore - Undo an organizer's deletion
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/games/{game_id}/restore",
+    tag = "Site admin",
+    summary = "Undo an organizer's deletion",
+    params(
+        ("game_id" = inline(GameId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_restore_game(
+    State(state): State<Arc<AppState>>,
+    Path(game_id): Path<GameId>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    if !state.db.restore_game(game_id).await? {
+        return Err(AppError::NotFound(
+            "Jogo excluído não encontrado".to_string(),
+        ));
+    }
+
+    tracing::info!("site admin restored game {}", game_id);
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Jogo restaurado com sucesso"
+    })))
+}
+
+/// POST /api/site-admin/games/:game_id/freeze - Block organizer changes pending review
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/games/{game_id}/freeze",
+    tag = "Site admin",
+    summary = "Block organizer changes pending review",
+    params(
+        ("game_id" = inline(GameId), Path)
+    ),
+    request_body = FreezeGameRequest,
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_freeze_game(
+    State(state): State<Arc<AppState>>,
+    Path(game_id): Path<GameId>,
+    Json(req): Json<FreezeGameRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let reason = req.reason.trim();
+    if reason.is_empty() {
+        return Err(AppError::BadRequest(
+            "O motivo do bloqueio é obrigatório".to_string(),
+        ));
+    }
+
+    // Verify game exists
+    state
+        .db
+        .get_game_by_id_including_deleted(game_id)
+        .await?
+        .ok_or(AppError::NotFound("Jogo não encontrado".to_string()))?;
+
+    state.db.freeze_game(game_id, reason).await?;
+
+    tracing::info!("site admin froze game {}: {}", game_id, reason);
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Jogo bloqueado com sucesso"
+    })))
+}
+
+/// POST /api/site-admin/games/:game_id/unfreeze - Allow organizer changes again
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/games/{game_id}/unfreeze",
+    tag = "Site admin",
+    summary = "Allow organizer changes again",
+    params(
+        ("game_id" = inline(GameId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_unfreeze_game(
+    State(state): State<Arc<AppState>>,
+    Path(game_id): Path<GameId>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    // Verify game exists
+    state
+        .db
+        .get_game_by_id_including_deleted(game_id)
+        .await?
+        .ok_or(AppError::NotFound("Jogo não encontrado".to_string()))?;
+
+    state.db.unfreeze_game(game_id).await?;
+
+    tracing::info!("site admin unfroze game {}", game_id);
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Jogo desbloqueado com sucesso"
+    })))
+}
+
+/// POST /api/site-admin/games/:game_id/reset-draw - Undo a draw so the game can be drawn again
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/games/{game_id}/reset-draw",
+    tag = "Site admin",
+    summary = "Undo a draw so the game can be drawn again",
+    params(
+        ("game_id" = inline(GameId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_reset_draw(
+    State(state): State<Arc<AppState>>,
+    Path(game_id): Path<GameId>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let game = state
+        .db
+        .get_game_by_id_including_deleted(game_id)
+        .await?
+        .ok_or(AppError::NotFound("Jogo não encontrado".to_string()))?;
+
+    if !game.drawn {
+        return Err(AppError::GameNotDrawn(
+            "O sorteio ainda não foi realizado.".to_string(),
+        ));
+    }
 
-    // Build response with participant counts
-    let mut game_summaries = Vec::new();
-    for game in games {
-        let participant_count = state.db.count_participants_in_game(game.id).await?;
-        game_summaries.push(GameSummary {
-            id: game.id,
-            name: game.name,
-            event_date: game.event_date,
-            organizer_email: game.organizer_email,
-            created_at: game.created_at,
-            drawn: game.drawn,
-            participant_count,
-        });
+    // The dispatcher would otherwise keep sending the old matches
+    let unfinished_jobs = state.db.find_unfinished_email_jobs().await?;
+    if unfinished_jobs.iter().any(|job| job.game_id == game_id) {
+        return Err(AppError::BadRequest(
+            "Os emails do sorteio ainda estão sendo enviados".to_string(),
+        ));
     }
 
-    Ok(Json(SearchGamesResponse {
-        games: game_summaries,
-        total,
-        limit,
-        offset,
-    }))
+    state.db.reset_draw(game_id).await?;
+
+    tracing::info!("site admin reset the draw of game {}", game_id);
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Sorteio desfeito com sucesso"
+    })))
 }
 
-/// GET /api/site-admin/games/:game_id - Get full game details including admin token
-pub async fn site_admin_get_game(
+/// POST /api/site-admin/games/:game_id/regenerate-admin-link - Replace the organizer's admin
+/// token, returning the new link so it can be handed to the organizer
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/games/{game_id}/regenerate-admin-link",
+    tag = "Site admin",
+    summary = "Replace the organizer's admin link",
+    params(
+        ("game_id" = inline(GameId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_regenerate_admin_link(
     State(state): State<Arc<AppState>>,
     Path(game_id): Path<GameId>,
-) -> Result<Json<GameDetailResponse>, AppError> {
+) -> Result<Json<serde_json::Value>, AppError> {
     let game = state
         .db
-        .get_game_by_id(game_id)
+        .get_game_by_id_including_deleted(game_id)
         .await?
         .ok_or(AppError::NotFound("Jogo não encontrado".to_string()))?;
 
-    let participants = state.db.get_participants_by_game(game_id).await?;
-    let participant_count =
-        u64::try_from(participants.len()).context("converting participant count to u64")?;
+    let admin_token = AdminToken::generate();
+    state
+        .db
+        .update_game_admin_token(game.id, &admin_token.hash())
+        .await?;
 
-    Ok(Json(GameDetailResponse {
-        game,
-        participants,
-        participant_count,
-    }))
+    tracing::info!("site admin regenerated the admin link of game {}", game_id);
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "admin_link": state.email_service.admin_url(game.id, &admin_token).to_string()
+    })))
 }
 
-/// DELETE /api/site-admin/games/:game_id - Permanently delete a game
-pub async fn site_admin_delete_game(
+/// POST /api/site-admin/games/:game_id/resend-welcome - Email the organizer a new admin link
+///
+/// Only hashes of admin tokens are stored, so the old link stops working.
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/games/{game_id}/resend-welcome",
+    tag = "Site admin",
+    summary = "Email the organizer a new admin link",
+    params(
+        ("game_id" = inline(GameId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_resend_welcome(
     State(state): State<Arc<AppState>>,
     Path(game_id): Path<GameId>,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    // Verify game exists
     let game = state
         .db
-        .get_game_by_id(game_id)
+        .get_game_by_id_including_deleted(game_id)
         .await?
         .ok_or(AppError::NotFound("Jogo não encontrado".to_string()))?;
 
-    // Delete game (participants will be cascade deleted)
-    state.db.delete_game(game_id).await?;
+    let admin_token = AdminToken::generate();
+    state
+        .db
+        .update_game_admin_token(game.id, &admin_token.hash())
+        .await?;
+    state
+        .email_service
+        .send_admin_welcome(
+            &game.organizer_email,
+            &game.name,
+            game.event_date,
+            game.id,
+            &admin_token,
+            game.locale,
+        )
+        .await?;
+
+    tracing::info!("site admin resent the welcome email of game {}", game_id);
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Email de boas-vindas reenviado com sucesso"
+    })))
+}
+
+/// POST /api/site-admin/email/test - Test the connection to the email server or provider and
+/// optionally send a test email, reporting timings and errors
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/email/test",
+    tag = "Site admin",
+    summary = "Test the connection to the email server or provider",
+    request_body = Option<EmailTestRequest>,
+    responses(
+        (status = 200, description = "Success", body = SmtpTestReport)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_email_test(
+    State(state): State<Arc<AppState>>,
+    req: Option<Json<EmailTestRequest>>,
+) -> Json<SmtpTestReport> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    let report = state
+        .email_service
+        .run_test(req.send, req.to.as_ref())
+        .await;
+
+    match &report.error {
+        Some(error) => tracing::warn!("site admin email test failed: {}", error),
+        None => tracing::info!(
+            "site admin email test succeeded (connection: {:?}ms, send: {:?}ms)",
+            report.connection_ms,
+            report.send_ms
+        ),
+    }
+
+    Json(report)
+}
+
+/// POST /api/site-admin/announcements - Email every organizer of an active game
+///
+/// With `dry_run`, only counts who would get the email.
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/announcements",
+    tag = "Site admin",
+    summary = "Email every organizer of an active game",
+    request_body = CreateAnnouncementRequest,
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_create_announcement(
+    State(state): State<Arc<AppState>>,
+    Valid(req): Valid<CreateAnnouncementRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let (subject, message) = (req.subject.trim(), req.message.trim());
+
+    let recipients = state.db.get_announcement_recipients().await?;
+    if req.dry_run {
+        return Ok(Json(serde_json::json!({ "recipients": recipients.len() })));
+    }
+
+    let announcement_id = state
+        .db
+        .create_announcement(subject, message, &recipients)
+        .await?;
+    state.email_dispatcher.wake();
 
     tracing::info!(
-        "site admin deleted game {} ({}) organized by {}",
-        game_id,
-        game.name,
-        game.organizer_email
+        "site admin queued announcement {} to {} organizers",
+        announcement_id,
+        recipients.len()
+    );
+
+    Ok(Json(serde_json::json!({
+        "announcement_id": announcement_id,
+        "recipients": recipients.len()
+    })))
+}
+
+/// GET /api/site-admin/announcements - Announcements and how far along sending them is
+#[utoipa::path(
+    get,
+    path = "/api/site-admin/announcements",
+    tag = "Site admin",
+    summary = "Announcements and how far along sending them is",
+    responses(
+        (status = 200, description = "Success", body = Vec<Announcement>)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_list_announcements(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<Announcement>>, AppError> {
+    Ok(Json(state.db.list_announcements().await?))
+}
+
+/// GET /api/site-admin/jobs - When each background task last ran, and how it went
+#[utoipa::path(
+    get,
+    path = "/api/site-admin/jobs",
+    tag = "Site admin",
+    summary = "When each background task last ran, and how it went",
+    responses(
+        (status = 200, description = "Success", body = Vec<JobRun>)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_list_job_runs(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<JobRun>>, AppError> {
+    Ok(Json(state.db.list_job_runs().await?))
+}
+
+/// GET /api/site-admin/query-plans - How SQLite runs the hot queries, to spot missing indexes
+#[utoipa::path(
+    get,
+    path = "/api/site-admin/query-plans",
+    tag = "Site admin",
+    summary = "How SQLite runs the hot queries",
+    responses(
+        (status = 200, description = "Success", body = Vec<QueryPlan>)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_query_plans(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<QueryPlan>>, AppError> {
+    Ok(Json(state.db.explain_hot_queries().await?))
+}
+
+/// POST /api/site-admin/backups - Back up the database now, without waiting for the schedule
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/backups",
+    tag = "Site admin",
+    summary = "Back up the database now",
+    responses(
+        (status = 200, description = "Success", body = BackupSummary)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_create_backup(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<BackupSummary>, AppError> {
+    let Some(backups) = &state.backups else {
+        return Err(AppError::NotFound(
+            "Backups não estão configurados".to_string(),
+        ));
+    };
+    let backup = backups.run().await?;
+    tracing::info!("site admin backed up the database to {}", backup.key);
+    Ok(Json(backup))
+}
+
+/// POST /api/site-admin/backups/restore - Replace all data with an uploaded backup
+///
+/// Gated behind a typed confirmation phrase. The backup is checked before anything is
+/// replaced, and the site admin's sessions are kept.
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/backups/restore",
+    tag = "Site admin",
+    summary = "Replace all data with an uploaded backup",
+    params(
+        RestoreBackupQuery
+    ),
+    request_body(content(("application/octet-stream"))),
+    responses(
+        (status = 200, description = "Success", body = RestoreSummary)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_restore_backup(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RestoreBackupQuery>,
+    body: Bytes,
+) -> Result<Json<RestoreSummary>, AppError> {
+    if !query
+        .confirmation
+        .trim()
+        .eq_ignore_ascii_case(RESTORE_CONFIRMATION)
+    {
+        return Err(AppError::BadRequest(format!(
+            "Digite \"{}\" para confirmar",
+            RESTORE_CONFIRMATION
+        )));
+    }
+
+    let summary = backup::restore(&state.db, body.to_vec())
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Não foi possível restaurar o backup: {e:#}")))?;
+    tracing::warn!(
+        "site admin restored {} rows ({} bytes) from a backup",
+        summary.rows,
+        summary.size
     );
+    Ok(Json(summary))
+}
+
+/// GET /api/site-admin/settings - Settings that can be changed at runtime
+#[utoipa::path(
+    get,
+    path = "/api/site-admin/settings",
+    tag = "Site admin",
+    summary = "Settings that can be changed at runtime",
+    responses(
+        (status = 200, description = "Success", body = SiteSettings)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_get_settings(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<SiteSettings>, AppError> {
+    Ok(Json(SiteSettings {
+        game_retention_days: state.db.get_game_retention_days().await?,
+    }))
+}
+
+/// PATCH /api/site-admin/settings - Change settings, taking effect on the next cleanup
+#[utoipa::path(
+    patch,
+    path = "/api/site-admin/settings",
+    tag = "Site admin",
+    summary = "Change settings",
+    request_body = UpdateSiteSettingsRequest,
+    responses(
+        (status = 200, description = "Success", body = SiteSettings)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_update_settings(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<UpdateSiteSettingsRequest>,
+) -> Result<Json<SiteSettings>, AppError> {
+    if let Some(days) = req.game_retention_days {
+        if !db::GAME_RETENTION_DAYS_RANGE.contains(&days) {
+            return Err(AppError::BadRequest(format!(
+                "A retenção dos jogos deve ser de {} a {} dias",
+                db::GAME_RETENTION_DAYS_RANGE.start(),
+                db::GAME_RETENTION_DAYS_RANGE.end()
+            )));
+        }
+        state.db.set_game_retention_days(days).await?;
+        tracing::info!("site admin set the game retention to {} days", days);
+    }
+
+    site_admin_get_settings(State(state)).await
+}
+
+/// GET /api/site-admin/bans - Addresses and domains banned from creating games
+#[utoipa::path(
+    get,
+    path = "/api/site-admin/bans",
+    tag = "Site admin",
+    summary = "Addresses and domains banned from creating games",
+    responses(
+        (status = 200, description = "Success", body = Vec<EmailBan>)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_list_email_bans(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<EmailBan>>, AppError> {
+    Ok(Json(state.db.list_email_bans().await?))
+}
+
+/// Trims a ban reason, treating blank as unset, and checks its length.
+fn normalize_ban_reason(reason: Option<&str>) -> Result<Option<&str>, AppError> {
+    let reason = reason.map(str::trim).filter(|r| !r.is_empty());
+    if reason.is_some_and(|r| r.chars().count() > MAX_BAN_REASON_LENGTH) {
+        return Err(AppError::BadRequest(format!(
+            "O motivo deve ter no máximo {} caracteres",
+            MAX_BAN_REASON_LENGTH
+        )));
+    }
+    Ok(reason)
+}
+
+/// POST /api/site-admin/bans - Ban an address, or a domain and its subdomains
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/bans",
+    tag = "Site admin",
+    summary = "Ban an address, or a domain and its subdomains",
+    request_body = CreateEmailBanRequest,
+    responses(
+        (status = 200, description = "Success", body = EmailBan)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_create_email_ban(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CreateEmailBanRequest>,
+) -> Result<Json<EmailBan>, AppError> {
+    let value = req.value.trim();
+    let value = if value.contains('@') {
+        value
+            .parse::<EmailAddress>()
+            .ok()
+            .map(|email| email.to_string().to_lowercase())
+    } else {
+        value
+            .parse::<EmailDomain>()
+            .ok()
+            .map(|domain| domain.as_str().to_string())
+    }
+    .ok_or(AppError::BadRequest(
+        "Informe um email ou domínio válido".to_string(),
+    ))?;
+    let reason = normalize_ban_reason(req.reason.as_deref())?;
+
+    let ban = state
+        .db
+        .create_email_ban(&value, reason)
+        .await?
+        .ok_or(AppError::BadRequest(
+            "Este email ou domínio já está banido".to_string(),
+        ))?;
+
+    tracing::info!("site admin banned {}", ban.value);
+
+    Ok(Json(ban))
+}
+
+/// PATCH /api/site-admin/bans/:ban_id - Change why an address or domain is banned
+#[utoipa::path(
+    patch,
+    path = "/api/site-admin/bans/{ban_id}",
+    tag = "Site admin",
+    summary = "Change why an address or domain is banned",
+    params(
+        ("ban_id" = inline(EmailBanId), Path)
+    ),
+    request_body = UpdateEmailBanRequest,
+    responses(
+        (status = 200, description = "Success", body = EmailBan)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_update_email_ban(
+    State(state): State<Arc<AppState>>,
+    Path(ban_id): Path<EmailBanId>,
+    Json(req): Json<UpdateEmailBanRequest>,
+) -> Result<Json<EmailBan>, AppError> {
+    let reason = normalize_ban_reason(req.reason.as_deref())?;
+
+    state
+        .db
+        .update_email_ban(ban_id, reason)
+        .await?
+        .map(Json)
+        .ok_or(AppError::NotFound("Banimento não encontrado".to_string()))
+}
+
+/// DELETE /api/site-admin/bans/:ban_id - Lift a ban
+#[utoipa::path(
+    delete,
+    path = "/api/site-admin/bans/{ban_id}",
+    tag = "Site admin",
+    summary = "Lift a ban",
+    params(
+        ("ban_id" = inline(EmailBanId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_delete_email_ban(
+    State(state): State<Arc<AppState>>,
+    Path(ban_id): Path<EmailBanId>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    if !state.db.delete_email_ban(ban_id).await? {
+        return Err(AppError::NotFound("Banimento não encontrado".to_string()));
+    }
+
+    tracing::info!("site admin lifted ban {}", ban_id);
 
     Ok(Json(serde_json::json!({
         "success": true,
-        "message": "Jogo excluído com sucesso"
+        "message": "Banimento removido"
+    })))
+}
+
+/// POST /api/email/events - Bounce and complaint notifications from the email provider
+///
+/// Accepts SendGrid's event webhook and SES notifications through SNS. Participants whose
+/// email bounced or who marked it as spam are flagged, and their organizers told, so the
+/// address can be fixed before the event.
+#[utoipa::path(
+    post,
+    path = "/api/email/events",
+    tag = "Email",
+    summary = "Bounce and complaint webhook for the email provider",
+    request_body = Object,
+    responses(
+        (status = 200, description = "Success")
+    ),
+    security(
+        ("emailWebhookSecret" = [])
+    )
+)]
+pub async fn email_events_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, AppError> {
+    let Some(secret) = &state.email_webhook_secret else {
+        return Err(AppError::NotFound("Não encontrado".to_string()));
+    };
+    let authorized = email_events::secret_from_headers(&headers)
+        .is_some_and(|sent| token::constant_time_eq(sent.as_bytes(), secret.as_bytes()));
+    if !authorized {
+        // SNS only sends the credentials in its URL after being asked for them
+        let mut response = AppError::Unauthorized("Token inválido".to_string()).into_response();
+        response.headers_mut().insert(
+            header::WWW_AUTHENTICATE,
+            HeaderValue::from_static("Basic realm=\"email events\""),
+        );
+        return Ok(response);
+    }
+
+    let events = match email_events::parse(&body) {
+        Ok(WebhookPayload::Events(events)) => events,
+        Ok(WebhookPayload::SnsSubscription { subscribe_url }) => {
+            tracing::warn!(
+                "SNS subscription to the email events webhook must be confirmed: {}",
+                subscribe_url
+            );
+            return Ok(StatusCode::OK.into_response());
+        }
+        Err(e) => {
+            return Err(AppError::BadRequest(format!(
+                "Evento de email inválido: {}",
+                e
+            )));
+        }
+    };
+
+    for event in events {
+        state
+            .db
+            .suppress_email(&event.email, event.kind.into())
+            .await?;
+        let participants = state
+            .db
+            .mark_email_undeliverable(&event.email, event.kind)
+            .await?;
+        for participant in participants {
+            tracing::warn!(
+                "email to participant {} is undeliverable ({})",
+                participant.id,
+                event.kind.as_str()
+            );
+            let Some(game) = state.db.get_game_by_id(participant.game_id).await? else {
+                continue;
+            };
+            // Too late to fix the address
+            if game.event_date < game.timezone.date_at(state.clock.now()) {
+                continue;
+            }
+            if let Err(e) = state
+                .email_service
+                .send_undeliverable_notice(&game, &participant, event.kind)
+                .await
+            {
+                tracing::error!("failed to send undeliverable notice to organizer: {}", e);
+            }
+        }
+    }
+
+    Ok(StatusCode::OK.into_response())
+}
+
+/// POST /api/email/unsubscribe/:token - Stop reminders and wrap-ups to an address
+///
+/// Linked from optional emails, and used by mail clients for one-click unsubscribing
+/// (`List-Unsubscribe-Post`), which send a form body that is ignored here.
+#[utoipa::path(
+    post,
+    path = "/api/email/unsubscribe/{token}",
+    tag = "Email",
+    summary = "Stop reminders and wrap-ups to an address",
+    params(
+        ("token" = inline(UnsubscribeToken), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    )
+)]
+pub async fn unsubscribe_email(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<UnsubscribeToken>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let email = state
+        .db
+        .find_unsubscribe_email(&token)
+        .await?
+        .ok_or(AppError::NotFound(
+            "Link de descadastro inválido".to_string(),
+        ))?;
+
+    state
+        .db
+        .suppress_email(&email, SuppressionReason::Unsubscribe)
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Pronto! Você não receberá mais lembretes e resumos do Amigo Oculto."
+    })))
+}
+
+/// GET /api/email/open/:token - Tracking pixel recording that a participant opened an email
+///
+/// Always answers with the image, so a broken or stale token never shows up in the email.
+#[utoipa::path(
+    get,
+    path = "/api/email/open/{token}",
+    tag = "Email",
+    summary = "Tracking pixel recording that a participant opened an email",
+    params(
+        ("token" = inline(EmailOpenToken), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", content(("image/gif")))
+    )
+)]
+pub async fn track_email_open(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<EmailOpenToken>,
+) -> Response {
+    if let Err(e) = state.db.record_email_open(&token).await {
+        tracing::warn!("failed to record email open: {}", e);
+    }
+
+    (
+        [
+            (header::CONTENT_TYPE, "image/gif"),
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        TRACKING_PIXEL_GIF,
+    )
+        .into_response()
+}
+
+/// GET /api/site-admin/stats - Site-wide totals and daily trends
+#[utoipa::path(
+    get,
+    path = "/api/site-admin/stats",
+    tag = "Site admin",
+    summary = "Site-wide totals and daily trends",
+    params(
+        SiteStatsQuery
+    ),
+    responses(
+        (status = 200, description = "Success", body = SiteStats)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_stats(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SiteStatsQuery>,
+) -> Result<Json<SiteStats>, AppError> {
+    let days = query.days.clamp(1, MAX_STATS_DAYS);
+    Ok(Json(SiteStats {
+        totals: state.db.get_site_stats_totals().await?,
+        trends: state.db.get_site_stats_trends(days).await?,
+    }))
+}
+
+/// GET /api/site-admin/reports - List unresolved abuse reports
+#[utoipa::path(
+    get,
+    path = "/api/site-admin/reports",
+    tag = "Site admin",
+    summary = "Unresolved abuse reports",
+    responses(
+        (status = 200, description = "Success", body = Vec<AbuseReportSummary>)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_list_reports(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<AbuseReportSummary>>, AppError> {
+    Ok(Json(state.db.get_unresolved_abuse_reports().await?))
+}
+
+/// POST /api/site-admin/reports/:report_id/resolve - Dismiss an abuse report
+#[utoipa::path(
+    post,
+    path = "/api/site-admin/reports/{report_id}/resolve",
+    tag = "Site admin",
+    summary = "Dismiss an abuse report",
+    params(
+        ("report_id" = inline(ReportId), Path)
+    ),
+    responses(
+        (status = 200, description = "Success", body = Object)
+    ),
+    security(
+        ("siteAdminSession" = [])
+    )
+)]
+pub async fn site_admin_resolve_report(
+    State(state): State<Arc<AppState>>,
+    Path(report_id): Path<ReportId>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    if !state.db.resolve_abuse_report(report_id).await? {
+        return Err(AppError::NotFound("Denúncia não encontrada".to_string()));
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Denúncia resolvida"
     })))
 }
 
 // Error handling
+
+/// Body of error responses.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: String,
+    /// Stable code to branch on, such as `NOT_FOUND` (see [`AppError::code`]).
+    pub code: &'static str,
+    /// Messages by field, when `code` is `VALIDATION_FAILED`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<HashMap<String, Vec<String>>>)]
+    pub fields: Option<serde_json::Value>,
+    /// Users can quote it to support, who can find the request in the logs.
+    pub request_id: Option<String>,
+}
+
+impl ErrorBody {
+    fn new(error: impl Into<String>, code: &'static str) -> Self {
+        Self {
+            error: error.into(),
+            code,
+            fields: None,
+            request_id: request_id::current().map(|id| id.to_string()),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum AppError {
     Database(sqlx::Error),
     NotFound(String),
     BadRequest(String),
     Unauthorized(String),
+    /// A token or ID did not grant access. Deliberately indistinguishable from a missing
+    /// game, so responses don't reveal which games exist.
+    AccessDenied,
+    /// A link that used to work has expired or was already used.
+    Gone(String),
+    /// The request conflicts with another one being handled.
+    Conflict(String),
+    /// The client exceeded a rate limit and should retry after the given time.
+    TooManyRequests(Duration),
     InternalError(String),
     Anyhow(anyhow::Error),
+    /// The game was already drawn, so it can't be changed or drawn again.
+    GameAlreadyDrawn(String),
+    /// The game wasn't drawn yet, so there are no matches or emails to send.
+    GameNotDrawn(String),
+    /// The game has `MAX_PARTICIPANTS_PER_GAME` participants.
+    ParticipantLimit,
+    /// Drawing needs at least 2 participants.
+    NotEnoughParticipants,
+    /// The organizer locked the participant list.
+    ParticipantsLocked,
+    /// The email is outside the game's allowed domain, given here.
+    EmailDomainNotAllowed(EmailDomain),
+    /// A site admin froze the game, for the reason given here.
+    GameFrozen(String),
+    /// The emails were resent too recently or too often.
+    ResendLimit(String),
+    /// The request body has invalid fields.
+    Validation(FieldErrors),
+    /// The request body is larger than the route accepts.
+    PayloadTooLarge,
+}
+
+impl AppError {
+    /// Stable code of the error, in the `code` field of error bodies, for clients to tell
+    /// errors apart without relying on the (translated) message. Never change existing codes.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Database(_) | AppError::InternalError(_) | AppError::Anyhow(_) => {
+                "INTERNAL_ERROR"
+            }
+            // Same code as a missing game, like the status
+            AppError::NotFound(_) | AppError::AccessDenied => "NOT_FOUND",
+            AppError::BadRequest(_) => "BAD_REQUEST",
+            AppError::Unauthorized(_) => "UNAUTHORIZED",
+            AppError::Gone(_) => "GONE",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::TooManyRequests(_) => "RATE_LIMITED",
+            AppError::GameAlreadyDrawn(_) => "GAME_ALREADY_DRAWN",
+            AppError::GameNotDrawn(_) => "GAME_NOT_DRAWN",
+            AppError::ParticipantLimit => "PARTICIPANT_LIMIT",
+            AppError::NotEnoughParticipants => "NOT_ENOUGH_PARTICIPANTS",
+            AppError::ParticipantsLocked => "PARTICIPANTS_LOCKED",
+            AppError::EmailDomainNotAllowed(_) => "EMAIL_DOMAIN_NOT_ALLOWED",
+            AppError::GameFrozen(_) => "GAME_FROZEN",
+            AppError::ResendLimit(_) => "RESEND_LIMIT",
+            AppError::Validation(_) => "VALIDATION_FAILED",
+            AppError::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+        }
+    }
 }
 
 impl From<sqlx::Error> for AppError {
@@ -1049,9 +4815,13 @@ impl From<anyhow::Error> for AppError {
 
 impl IntoResponse for AppError {
     fn into_response(self) -> axum::response::Response {
+        // Details of unexpected errors, for error reporting
+        let mut reportable = None;
+        let code = self.code();
         let (status, message) = match self {
             AppError::Database(e) => {
                 tracing::error!("database error: {}", e);
+                reportable = Some(format!("database error: {e}"));
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     "Erro interno do servidor".to_string(),
@@ -1060,17 +4830,153 @@ impl IntoResponse for AppError {
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
             AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
-            AppError::InternalError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            AppError::AccessDenied => (
+                StatusCode::NOT_FOUND,
+                "Link inválido ou acesso não autorizado".to_string(),
+            ),
+            AppError::Gone(msg) => (StatusCode::GONE, msg),
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+            AppError::TooManyRequests(retry_after) => {
+                // Rounded up, so clients don't retry a moment too early
+                let seconds = (retry_after.num_milliseconds() + 999) / 1000;
+                let body = Json(ErrorBody::new(
+                    "Muitas requisições. Tente novamente em alguns minutos.",
+                    code,
+                ));
+                return (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [(header::RETRY_AFTER, seconds.to_string())],
+                    body,
+                )
+                    .into_response();
+            }
+            AppError::InternalError(msg) => {
+                reportable = Some(msg.clone());
+                (StatusCode::INTERNAL_SERVER_ERROR, msg)
+            }
             AppError::Anyhow(e) => {
                 tracing::error!("error: {}", e);
+                reportable = Some(format!("{e:#}"));
                 (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
             }
+            AppError::GameAlreadyDrawn(msg)
+            | AppError::GameNotDrawn(msg)
+            | AppError::ResendLimit(msg) => (StatusCode::BAD_REQUEST, msg),
+            AppError::ParticipantLimit => (
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Limite máximo de {} participantes atingido",
+                    MAX_PARTICIPANTS_PER_GAME
+                ),
+            ),
+            AppError::NotEnoughParticipants => (
+                StatusCode::BAD_REQUEST,
+                "Precisa de pelo menos 2 participantes para fazer o sorteio".to_string(),
+            ),
+            AppError::ParticipantsLocked => (
+                StatusCode::BAD_REQUEST,
+                "A lista de participantes está trancada. Destranque-a para adicionar participantes."
+                    .to_string(),
+            ),
+            AppError::EmailDomainNotAllowed(domain) => (
+                StatusCode::BAD_REQUEST,
+                format!("Este jogo aceita apenas emails do domínio @{}", domain),
+            ),
+            AppError::GameFrozen(reason) => (
+                StatusCode::LOCKED,
+                format!("Este jogo foi bloqueado pela administração: {}", reason),
+            ),
+            AppError::PayloadTooLarge => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "Requisição grande demais".to_string(),
+            ),
+            AppError::Validation(errors) => {
+                let body = Json(ErrorBody {
+                    fields: Some(errors.to_json()),
+                    ..ErrorBody::new(errors.first_message().unwrap_or("Requisição inválida"), code)
+                });
+                return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+            }
         };
 
-        let body = Json(serde_json::json!({
-            "error": message
-        }));
+        let body = Json(ErrorBody::new(message, code));
+
+        let mut response = (status, body).into_response();
+        if let Some(error) = reportable {
+            response.extensions_mut().insert(ReportableError(error));
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        (status, body).into_response()
+    async fn error_body(error: AppError) -> (StatusCode, serde_json::Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_error_codes() {
+        let (status, body) = error_body(AppError::ParticipantLimit).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "PARTICIPANT_LIMIT");
+        assert_eq!(body["error"], "Limite máximo de 100 participantes atingido");
+
+        let (status, body) = error_body(AppError::GameFrozen("spam".to_string())).await;
+        assert_eq!(status, StatusCode::LOCKED);
+        assert_eq!(body["code"], "GAME_FROZEN");
+
+        let (status, body) = error_body(AppError::TooManyRequests(Duration::seconds(5))).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["code"], "RATE_LIMITED");
+
+        // Indistinguishable from a missing game
+        let (_, denied) = error_body(AppError::AccessDenied).await;
+        let (_, missing) = error_body(AppError::NotFound("Jogo não encontrado".to_string())).await;
+        assert_eq!(denied["code"], missing["code"]);
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = game_status_etag(3, Some((2, 20)), None, ExportFormat::Json);
+        assert_ne!(
+            etag,
+            game_status_etag(4, Some((2, 20)), None, ExportFormat::Json)
+        );
+        assert_ne!(etag, game_status_etag(3, None, None, ExportFormat::Json));
+        assert_ne!(
+            etag,
+            game_status_etag(3, Some((2, 20)), Some(false), ExportFormat::Json)
+        );
+        assert_ne!(
+            etag,
+            game_status_etag(3, Some((2, 20)), None, ExportFormat::Csv)
+        );
+
+        let headers = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::IF_NONE_MATCH, value.parse().unwrap());
+            headers
+        };
+        assert!(!if_none_match(&HeaderMap::new(), &etag));
+        assert!(if_none_match(&headers(&etag), &etag));
+        assert!(if_none_match(
+            &headers(etag.trim_start_matches("W/")),
+            &etag
+        ));
+        assert!(if_none_match(
+            &headers(&format!("\"other\", {etag}")),
+            &etag
+        ));
+        assert!(if_none_match(&headers("*"), &etag));
+        let stale = game_status_etag(2, Some((2, 20)), None, ExportFormat::Json);
+        assert!(!if_none_match(&headers(&stale), &etag));
     }
 }
@@ -4,8 +4,17 @@
 //! "database is locked" errors. Each task runs once at startup (to handle frequently
 //! restarting servers), then continues on a regular interval.
 
+use crate::admin_sessions::AdminSessionStore;
+use crate::backup::Backups;
 use crate::db::Database;
+use crate::email::EmailService;
+use crate::email_dispatch::EmailDispatcher;
+use crate::error_reporting;
+use crate::models::ReminderKind;
 use anyhow::Result;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
@@ -13,9 +22,35 @@ use tokio_util::task::JoinMap;
 
 const CLEANUP_INTERVAL: Duration = Duration::from_secs(3600); // 1 hour
 
+/// Time between runs of the database maintenance, counted across restarts.
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(7 * 24 * 3600);
+
 /// Stagger between cleanup tasks to avoid concurrent SQLite writes.
 const CLEANUP_STAGGER: Duration = Duration::from_secs(5);
 
+/// Times one run of a background task and records it in `job_runs`, for the site admin's
+/// job status, reporting it if it failed. Failing to record it is only logged.
+pub async fn record_run(
+    db: &Database,
+    job: &str,
+    run: impl Future<Output = Result<u64>>,
+) -> Result<u64> {
+    let started = Instant::now();
+    let result = run.await;
+    let outcome = match &result {
+        Ok(processed) => Ok(*processed),
+        Err(e) => {
+            let error = format!("{e:#}");
+            error_reporting::capture(error.clone(), error_reporting::Source::Job(job));
+            Err(error)
+        }
+    };
+    if let Err(e) = db.record_job_run(job, started.elapsed(), outcome).await {
+        tracing::error!("failed to record run of {}: {}", job, e);
+    }
+    result
+}
+
 pub struct Server {
     /// JoinMap allows us to associate each task with a name, which is returned
     /// when the task completes so we can log which task stopped.
@@ -23,9 +58,21 @@ pub struct Server {
 }
 
 impl Server {
-    pub fn new(db: &Database, cancel: CancellationToken) -> Result<Self> {
+    pub fn new(
+        db: &Database,
+        admin_sessions: &Arc<dyn AdminSessionStore>,
+        email_service: &EmailService,
+        email_dispatcher: &EmailDispatcher,
+        backups: Option<&Backups>,
+        cancel: CancellationToken,
+    ) -> Result<Self> {
         let mut tasks = JoinMap::new();
         let now = Instant::now();
+        // Resumes emails interrupted by a restart right away, unlike the cleanup tasks
+        tasks.spawn(
+            "dispatch_emails",
+            email_dispatcher.clone().run(cancel.clone()),
+        );
         tasks.spawn(
             "cleanup_verifications",
             Self::cleanup_verifications_task(db.clone(), cancel.clone(), now),
@@ -36,8 +83,74 @@ impl Server {
         );
         tasks.spawn(
             "cleanup_admin_sessions",
-            Self::cleanup_admin_sessions_task(db.clone(), cancel, now + CLEANUP_STAGGER * 2),
+            Self::cleanup_admin_sessions_task(
+                db.clone(),
+                admin_sessions.clone(),
+                cancel.clone(),
+                now + CLEANUP_STAGGER * 2,
+            ),
+        );
+        tasks.spawn(
+            "cleanup_idle_games",
+            Self::cleanup_idle_games_task(db.clone(), cancel.clone(), now + CLEANUP_STAGGER * 3),
+        );
+        tasks.spawn(
+            "cleanup_organizer_sessions",
+            Self::cleanup_organizer_sessions_task(
+                db.clone(),
+                cancel.clone(),
+                now + CLEANUP_STAGGER * 4,
+            ),
+        );
+        tasks.spawn(
+            "purge_deleted_games",
+            Self::purge_deleted_games_task(db.clone(), cancel.clone(), now + CLEANUP_STAGGER * 5),
+        );
+        tasks.spawn(
+            "send_reminders",
+            Self::send_reminders_task(
+                db.clone(),
+                email_service.clone(),
+                cancel.clone(),
+                now + CLEANUP_STAGGER * 6,
+            ),
+        );
+        tasks.spawn(
+            "send_wrap_ups",
+            Self::send_wrap_ups_task(
+                db.clone(),
+                email_service.clone(),
+                cancel.clone(),
+                now + CLEANUP_STAGGER * 7,
+            ),
+        );
+        tasks.spawn(
+            "send_digests",
+            Self::send_digests_task(
+                db.clone(),
+                email_service.clone(),
+                cancel.clone(),
+                now + CLEANUP_STAGGER * 8,
+            ),
         );
+        tasks.spawn(
+            "maintain_database",
+            Self::maintain_database_task(db.clone(), cancel.clone(), now + CLEANUP_STAGGER * 9),
+        );
+        tasks.spawn(
+            "cleanup_idempotency_keys",
+            Self::cleanup_idempotency_keys_task(
+                db.clone(),
+                cancel.clone(),
+                now + CLEANUP_STAGGER * 10,
+            ),
+        );
+        if let Some(backups) = backups {
+            tasks.spawn(
+                "backup_database",
+                Self::backup_database_task(db.clone(), backups.clone(), cancel, now),
+            );
+        }
         Ok(Self { tasks })
     }
 
@@ -70,7 +183,13 @@ impl Server {
         interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
         loop {
-            match db.cleanup_expired_verifications().await {
+            match record_run(
+                &db,
+                "cleanup_verifications",
+                db.cleanup_expired_verifications(),
+            )
+            .await
+            {
                 Ok(count) if count > 0 => {
                     tracing::info!("cleaned up {} expired verification(s)", count);
                 }
@@ -107,7 +226,7 @@ impl Server {
         interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
         loop {
-            match db.cleanup_old_games().await {
+            match record_run(&db, "cleanup_games", db.cleanup_old_games()).await {
                 Ok(count) if count > 0 => {
                     tracing::info!("cleaned up {} old game(s)", count);
                 }
@@ -129,7 +248,12 @@ impl Server {
         }
     }
 
-    async fn cleanup_admin_sessions_task(db: Database, cancel: CancellationToken, start: Instant) {
+    async fn cleanup_admin_sessions_task(
+        db: Database,
+        admin_sessions: Arc<dyn AdminSessionStore>,
+        cancel: CancellationToken,
+        start: Instant,
+    ) {
         // Wait for staggered start time
         tokio::select! {
             _ = tokio::time::sleep_until(start) => {}
@@ -144,7 +268,13 @@ impl Server {
         interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
         loop {
-            match db.cleanup_expired_admin_sessions().await {
+            match record_run(
+                &db,
+                "cleanup_admin_sessions",
+                admin_sessions.cleanup_expired(),
+            )
+            .await
+            {
                 Ok(count) if count > 0 => {
                     tracing::info!("cleaned up {} expired admin session(s)", count);
                 }
@@ -155,6 +285,15 @@ impl Server {
                     tracing::error!("failed to cleanup expired admin sessions: {}", e);
                 }
             }
+            match db.cleanup_old_site_admin_login_attempts().await {
+                Ok(count) if count > 0 => {
+                    tracing::info!("cleaned up {} old site admin login attempt(s)", count);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!("failed to cleanup old site admin login attempts: {}", e);
+                }
+            }
 
             tokio::select! {
                 _ = interval.tick() => {}
@@ -165,4 +304,491 @@ impl Server {
             }
         }
     }
+
+    async fn cleanup_idle_games_task(db: Database, cancel: CancellationToken, start: Instant) {
+        // Wait for staggered start time
+        tokio::select! {
+            _ = tokio::time::sleep_until(start) => {}
+            _ = cancel.cancelled() => {
+                tracing::trace!("cleanup idle games task received shutdown signal");
+                return;
+            }
+        }
+
+        // Run cleanup once at startup, then on interval
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            match record_run(&db, "cleanup_idle_games", db.cleanup_idle_games()).await {
+                Ok(count) if count > 0 => {
+                    tracing::info!("cleaned up {} idle game(s)", count);
+                }
+                Ok(_) => {
+                    tracing::debug!("no idle games to clean up");
+                }
+                Err(e) => {
+                    tracing::error!("failed to cleanup idle games: {}", e);
+                }
+            }
+
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = cancel.cancelled() => {
+                    tracing::trace!("cleanup idle games task received shutdown signal");
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn purge_deleted_games_task(db: Database, cancel: CancellationToken, start: Instant) {
+        // Wait for staggered start time
+        tokio::select! {
+            _ = tokio::time::sleep_until(start) => {}
+            _ = cancel.cancelled() => {
+                tracing::trace!("purge deleted games task received shutdown signal");
+                return;
+            }
+        }
+
+        // Run cleanup once at startup, then on interval
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            match record_run(&db, "purge_deleted_games", db.purge_deleted_games()).await {
+                Ok(count) if count > 0 => {
+                    tracing::info!("purged {} deleted game(s)", count);
+                }
+                Ok(_) => {
+                    tracing::debug!("no deleted games to purge");
+                }
+                Err(e) => {
+                    tracing::error!("failed to purge deleted games: {}", e);
+                }
+            }
+
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = cancel.cancelled() => {
+                    tracing::trace!("purge deleted games task received shutdown signal");
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn cleanup_organizer_sessions_task(
+        db: Database,
+        cancel: CancellationToken,
+        start: Instant,
+    ) {
+        // Wait for staggered start time
+        tokio::select! {
+            _ = tokio::time::sleep_until(start) => {}
+            _ = cancel.cancelled() => {
+                tracing::trace!("cleanup organizer sessions task received shutdown signal");
+                return;
+            }
+        }
+
+        // Run cleanup once at startup, then on interval
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            match record_run(
+                &db,
+                "cleanup_organizer_sessions",
+                db.cleanup_expired_organizer_sessions(),
+            )
+            .await
+            {
+                Ok(count) if count > 0 => {
+                    tracing::info!("cleaned up {} expired organizer session(s)/link(s)", count);
+                }
+                Ok(_) => {
+                    tracing::debug!("no expired organizer sessions to clean up");
+                }
+                Err(e) => {
+                    tracing::error!("failed to cleanup expired organizer sessions: {}", e);
+                }
+            }
+
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = cancel.cancelled() => {
+                    tracing::trace!("cleanup organizer sessions task received shutdown signal");
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn cleanup_idempotency_keys_task(
+        db: Database,
+        cancel: CancellationToken,
+        start: Instant,
+    ) {
+        // Wait for staggered start time
+        tokio::select! {
+            _ = tokio::time::sleep_until(start) => {}
+            _ = cancel.cancelled() => {
+                tracing::trace!("cleanup idempotency keys task received shutdown signal");
+                return;
+            }
+        }
+
+        // Run cleanup once at startup, then on interval
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            match record_run(
+                &db,
+                "cleanup_idempotency_keys",
+                db.cleanup_idempotency_keys(),
+            )
+            .await
+            {
+                Ok(count) if count > 0 => {
+                    tracing::info!("cleaned up {} old idempotency key(s)", count);
+                }
+                Ok(_) => {
+                    tracing::debug!("no old idempotency keys to clean up");
+                }
+                Err(e) => {
+                    tracing::error!("failed to cleanup old idempotency keys: {}", e);
+                }
+            }
+
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = cancel.cancelled() => {
+                    tracing::trace!("cleanup idempotency keys task received shutdown signal");
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn send_reminders_task(
+        db: Database,
+        email_service: EmailService,
+        cancel: CancellationToken,
+        start: Instant,
+    ) {
+        // Wait for staggered start time
+        tokio::select! {
+            _ = tokio::time::sleep_until(start) => {}
+            _ = cancel.cancelled() => {
+                tracing::trace!("send reminders task received shutdown signal");
+                return;
+            }
+        }
+
+        // Run once at startup, then on interval
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            match record_run(
+                &db,
+                "send_reminders",
+                Self::send_due_reminders(&db, &email_service),
+            )
+            .await
+            {
+                Ok(count) if count > 0 => {
+                    tracing::info!("sent {} reminder(s)", count);
+                }
+                Ok(_) => {
+                    tracing::debug!("no reminders to send");
+                }
+                Err(e) => {
+                    tracing::error!("failed to send reminders: {}", e);
+                }
+            }
+
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = cancel.cancelled() => {
+                    tracing::trace!("send reminders task received shutdown signal");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Emails every participant who is due a reminder, either because they haven't seen their
+    /// match or because the event is coming up. Returns the number of emails sent.
+    ///
+    /// A failed email is logged and retried on the next run.
+    async fn send_due_reminders(db: &Database, email_service: &EmailService) -> Result<u64> {
+        let mut games = HashMap::new();
+        let mut sent = 0;
+
+        for kind in [ReminderKind::AfterDraw, ReminderKind::BeforeEvent] {
+            for participant in db.find_due_reminders(kind).await? {
+                let game = match games.get(&participant.game_id) {
+                    Some(game) => game,
+                    None => {
+                        let Some(game) = db.get_game_by_id(participant.game_id).await? else {
+                            continue;
+                        };
+                        games.entry(participant.game_id).or_insert(game)
+                    }
+                };
+
+                if let Err(e) = email_service
+                    .send_participant_reminder(game, &participant)
+                    .await
+                {
+                    tracing::error!(
+                        "failed to send reminder to participant {}: {}",
+                        participant.id,
+                        e
+                    );
+                    continue;
+                }
+
+                db.record_reminder_sent(&participant, kind).await?;
+                sent += 1;
+            }
+        }
+
+        for (game, days_before) in db.find_games_due_event_reminders().await? {
+            for participant in db
+                .find_due_event_reminder_participants(game.id, days_before)
+                .await?
+            {
+                if let Err(e) = email_service
+                    .send_participant_event_reminder(&game, &participant)
+                    .await
+                {
+                    tracing::error!(
+                        "failed to send event reminder to participant {}: {}",
+                        participant.id,
+                        e
+                    );
+                    continue;
+                }
+
+                db.record_event_reminder_sent(&participant, days_before)
+                    .await?;
+                sent += 1;
+            }
+        }
+
+        Ok(sent)
+    }
+
+    async fn send_wrap_ups_task(
+        db: Database,
+        email_service: EmailService,
+        cancel: CancellationToken,
+        start: Instant,
+    ) {
+        // Wait for staggered start time
+        tokio::select! {
+            _ = tokio::time::sleep_until(start) => {}
+            _ = cancel.cancelled() => {
+                tracing::trace!("send wrap-ups task received shutdown signal");
+                return;
+            }
+        }
+
+        // Run once at startup, then on interval
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            match record_run(
+                &db,
+                "send_wrap_ups",
+                Self::send_due_wrap_ups(&db, &email_service),
+            )
+            .await
+            {
+                Ok(count) if count > 0 => {
+                    tracing::info!("sent {} wrap-up email(s)", count);
+                }
+                Ok(_) => {
+                    tracing::debug!("no wrap-up emails to send");
+                }
+                Err(e) => {
+                    tracing::error!("failed to send wrap-up emails: {}", e);
+                }
+            }
+
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = cancel.cancelled() => {
+                    tracing::trace!("send wrap-ups task received shutdown signal");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Emails a wrap-up to every organizer who is due one. Returns the number of emails sent.
+    ///
+    /// A failed email is logged and retried on the next run.
+    async fn send_due_wrap_ups(db: &Database, email_service: &EmailService) -> Result<u64> {
+        let mut sent = 0;
+
+        for game in db.find_games_due_wrap_up().await? {
+            let stats = db.get_wrap_up_stats(game.id).await?;
+            if let Err(e) = email_service.send_wrap_up(&game, &stats).await {
+                tracing::error!("failed to send wrap-up for game {}: {}", game.id, e);
+                continue;
+            }
+
+            db.mark_wrap_up_sent(game.id).await?;
+            sent += 1;
+        }
+
+        Ok(sent)
+    }
+
+    async fn send_digests_task(
+        db: Database,
+        email_service: EmailService,
+        cancel: CancellationToken,
+        start: Instant,
+    ) {
+        // Wait for staggered start time
+        tokio::select! {
+            _ = tokio::time::sleep_until(start) => {}
+            _ = cancel.cancelled() => {
+                tracing::trace!("send digests task received shutdown signal");
+                return;
+            }
+        }
+
+        // Run once at startup, then on interval
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            match record_run(
+                &db,
+                "send_digests",
+                Self::send_due_digests(&db, &email_service),
+            )
+            .await
+            {
+                Ok(count) if count > 0 => {
+                    tracing::info!("sent {} digest email(s)", count);
+                }
+                Ok(_) => {
+                    tracing::debug!("no digest emails to send");
+                }
+                Err(e) => {
+                    tracing::error!("failed to send digest emails: {}", e);
+                }
+            }
+
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = cancel.cancelled() => {
+                    tracing::trace!("send digests task received shutdown signal");
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Emails a weekly digest to every organizer who is due one. Returns the number of emails
+    /// sent.
+    ///
+    /// A failed email is logged and retried on the next run.
+    async fn send_due_digests(db: &Database, email_service: &EmailService) -> Result<u64> {
+        let mut sent = 0;
+
+        for game in db.find_games_due_digest().await? {
+            let stats = db.get_wrap_up_stats(game.id).await?;
+            if let Err(e) = email_service.send_digest(&game, &stats).await {
+                tracing::error!("failed to send digest for game {}: {}", game.id, e);
+                continue;
+            }
+
+            db.mark_digest_sent(game.id).await?;
+            sent += 1;
+        }
+
+        Ok(sent)
+    }
+
+    async fn maintain_database_task(db: Database, cancel: CancellationToken, start: Instant) {
+        // Wait for staggered start time
+        tokio::select! {
+            _ = tokio::time::sleep_until(start) => {}
+            _ = cancel.cancelled() => {
+                tracing::trace!("maintain database task received shutdown signal");
+                return;
+            }
+        }
+
+        // Checks hourly whether a week passed since the last run, so that restarts neither
+        // repeat it nor put it off
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let period = chrono::Duration::from_std(MAINTENANCE_INTERVAL)
+            .expect("maintenance interval fits in a chrono duration");
+
+        loop {
+            match db.job_ran_within("maintain_database", period).await {
+                Ok(true) => {}
+                Ok(false) => match record_run(&db, "maintain_database", db.maintain()).await {
+                    Ok(pages) => {
+                        tracing::info!("database maintenance finished, freed {} page(s)", pages);
+                    }
+                    Err(e) => {
+                        tracing::error!("database maintenance failed: {:#}", e);
+                    }
+                },
+                Err(e) => {
+                    tracing::error!("failed to check last database maintenance: {}", e);
+                }
+            }
+
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = cancel.cancelled() => {
+                    tracing::trace!("maintain database task received shutdown signal");
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn backup_database_task(
+        db: Database,
+        backups: Backups,
+        cancel: CancellationToken,
+        start: Instant,
+    ) {
+        // Unlike the cleanups, the first run waits a whole interval, so that frequent restarts
+        // don't upload a backup each time
+        let mut interval = tokio::time::interval_at(start + backups.interval(), backups.interval());
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = cancel.cancelled() => {
+                    tracing::trace!("backup database task received shutdown signal");
+                    break;
+                }
+            }
+
+            let backup = async { backups.run().await.map(|_| 1) };
+            match record_run(&db, "backup_database", backup).await {
+                Ok(_) => tracing::info!("uploaded database backup"),
+                Err(e) => tracing::error!("failed to back up database: {:#}", e),
+            }
+        }
+    }
 }
@@ -0,0 +1,220 @@
+//! AWS Signature Version 4, for signing requests to AWS APIs such as SES and S3.
+//!
+//! Only covers what the email transport, backups and media storage need: requests without a query string,
+//! signing the `host` and `x-amz-date` headers, plus `content-type` and `x-amz-security-token`
+//! when present and `x-amz-content-sha256` for S3.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use url::{Position, Url};
+
+#[derive(Clone)]
+pub struct Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Only for temporary credentials, e.g. from an IAM role.
+    pub session_token: Option<String>,
+}
+
+/// A request to sign.
+pub struct SignableRequest<'a> {
+    pub method: &'a str,
+    pub url: &'a Url,
+    pub content_type: Option<&'a str>,
+    pub body: Payload<'a>,
+}
+
+/// The body of a request to sign.
+pub enum Payload<'a> {
+    Bytes(&'a [u8]),
+    /// SHA-256 digest of a body that's streamed rather than kept in memory.
+    Sha256([u8; 32]),
+}
+
+/// Signs a request, returning the headers to send with it: `X-Amz-Date`, `Authorization`
+/// and, for temporary credentials, `X-Amz-Security-Token`.
+pub fn sign(
+    credentials: &Credentials,
+    region: &str,
+    service: &str,
+    request: &SignableRequest,
+    now: DateTime<Utc>,
+) -> Vec<(&'static str, String)> {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let scope = format!("{date}/{region}/{service}/aws4_request");
+    let payload_hash = match request.body {
+        Payload::Bytes(body) => hex(&Sha256::digest(body)),
+        Payload::Sha256(digest) => hex(&digest),
+    };
+
+    // Header names in lowercase, sorted
+    let mut headers = Vec::new();
+    if let Some(content_type) = request.content_type {
+        headers.push(("content-type", content_type));
+    }
+    headers.push((
+        "host",
+        &request.url[Position::BeforeHost..Position::AfterPort],
+    ));
+    // S3 requires the payload hash in a header too
+    if service == "s3" {
+        headers.push(("x-amz-content-sha256", &payload_hash));
+    }
+    headers.push(("x-amz-date", &amz_date));
+    if let Some(token) = &credentials.session_token {
+        headers.push(("x-amz-security-token", token));
+    }
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n\n{canonical_headers}\n{signed_headers}\n{}",
+        request.method,
+        request.url.path(),
+        payload_hash,
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex(&Sha256::digest(canonical_request.as_bytes())),
+    );
+
+    let key = [region, service, "aws4_request"].iter().fold(
+        hmac_sha256(
+            format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+            date.as_bytes(),
+        ),
+        |key, part| hmac_sha256(&key, part.as_bytes()),
+    );
+    let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    let mut signed = vec![
+        ("X-Amz-Date", amz_date.clone()),
+        (
+            "Authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+                credentials.access_key_id
+            ),
+        ),
+    ];
+    if service == "s3" {
+        signed.push(("X-Amz-Content-Sha256", payload_hash.clone()));
+    }
+    if let Some(token) = &credentials.session_token {
+        signed.push(("X-Amz-Security-Token", token.clone()));
+    }
+    signed
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_sign_matches_aws_test_suite() {
+        // "get-vanilla" from the AWS Signature Version 4 test suite
+        let credentials = Credentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+        };
+        let url = "https://example.amazonaws.com/".parse().unwrap();
+        let request = SignableRequest {
+            method: "GET",
+            url: &url,
+            content_type: None,
+            body: Payload::Bytes(b""),
+        };
+        let now = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+
+        assert_eq!(
+            sign(&credentials, "us-east-1", "service", &request, now),
+            [
+                ("X-Amz-Date", "20150830T123600Z".to_string()),
+                (
+                    "Authorization",
+                    "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+                     SignedHeaders=host;x-amz-date, \
+                     Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sign_with_session_token() {
+        let credentials = Credentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "secret".to_string(),
+            session_token: Some("token".to_string()),
+        };
+        let url = "https://email.sa-east-1.amazonaws.com/v2/email/outbound-emails"
+            .parse()
+            .unwrap();
+        let request = SignableRequest {
+            method: "POST",
+            url: &url,
+            content_type: Some("application/json"),
+            body: Payload::Bytes(b"{}"),
+        };
+        let headers = sign(&credentials, "sa-east-1", "ses", &request, Utc::now());
+
+        assert!(headers[1].1.contains(
+            "/sa-east-1/ses/aws4_request, SignedHeaders=content-type;host;x-amz-date;x-amz-security-token,"
+        ));
+        assert_eq!(headers[2], ("X-Amz-Security-Token", "token".to_string()));
+    }
+
+    #[test]
+    fn test_sign_s3_includes_payload_hash() {
+        let credentials = Credentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "secret".to_string(),
+            session_token: None,
+        };
+        let url = "https://s3.eu-west-1.wasabisys.com/bucket/backup.db.gz"
+            .parse()
+            .unwrap();
+        let request = SignableRequest {
+            method: "PUT",
+            url: &url,
+            content_type: Some("application/gzip"),
+            body: Payload::Bytes(b""),
+        };
+        let headers = sign(&credentials, "eu-west-1", "s3", &request, Utc::now());
+
+        assert!(
+            headers[1]
+                .1
+                .contains("SignedHeaders=content-type;host;x-amz-content-sha256;x-amz-date,")
+        );
+        assert_eq!(
+            headers[2],
+            (
+                "X-Amz-Content-Sha256",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855".to_string()
+            )
+        );
+    }
+}
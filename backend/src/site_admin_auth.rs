@@ -2,8 +2,10 @@
 //!
 //! Validates session tokens from the Authorization header to authenticate
 //! site administrators. Session tokens are obtained via the login endpoint.
+//!
+//! Also decides when password logins are locked after repeated failures.
 
-use crate::{db::Database, token::AdminSessionToken};
+use crate::{admin_sessions::AdminSessionStore, token::AdminSessionToken};
 use axum::{
     Json,
     extract::{FromRequestParts, Request, State},
@@ -11,6 +13,46 @@ use axum::{
     middleware::Next,
     response::{IntoResponse, Response},
 };
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+
+/// Failed password logins allowed before each further attempt has to wait.
+const FREE_LOGIN_FAILURES: u32 = 3;
+
+/// Wait after the first failure beyond the free ones, doubling with each further failure.
+const LOGIN_BACKOFF_BASE: Duration = Duration::seconds(2);
+
+/// Longest wait between attempts before a lockout.
+const MAX_LOGIN_BACKOFF: Duration = Duration::minutes(5);
+
+/// Failures from one network that lock it out.
+pub const IP_LOCKOUT_FAILURES: u32 = 10;
+
+/// Failures from anywhere that lock the account out. Higher than the per-network limit, since
+/// it also keeps the real admin from logging in with the password (passkeys still work).
+pub const ACCOUNT_LOCKOUT_FAILURES: u32 = 30;
+
+/// How long a lockout lasts after the latest failure.
+const LOGIN_LOCKOUT: Duration = Duration::minutes(30);
+
+/// When the next password login may be attempted after `failures` recent failures, the latest
+/// at `last_failure`. Waits double with each failure, and after `lockout_failures` the login
+/// is locked for a cooling period.
+pub fn login_allowed_at(
+    failures: u32,
+    last_failure: DateTime<Utc>,
+    lockout_failures: u32,
+) -> Option<DateTime<Utc>> {
+    if failures >= lockout_failures {
+        return Some(last_failure + LOGIN_LOCKOUT);
+    }
+    let doublings = failures.checked_sub(FREE_LOGIN_FAILURES)?;
+    let backoff = 2i32
+        .checked_pow(doublings)
+        .map_or(MAX_LOGIN_BACKOFF, |factor| LOGIN_BACKOFF_BASE * factor)
+        .min(MAX_LOGIN_BACKOFF);
+    Some(last_failure + backoff)
+}
 
 /// Extractor for the authenticated admin session token.
 ///
@@ -35,7 +77,7 @@ where
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(serde_json::json!({
-                        "error": "Missing authentication context"
+                        "error": "Erro interno do servidor"
                     })),
                 )
             })
@@ -47,7 +89,7 @@ where
 /// Checks for a valid Bearer token in the Authorization header and validates
 /// it against active sessions in the database.
 pub async fn require_site_admin(
-    State(db): State<Database>,
+    State(sessions): State<Arc<dyn AdminSessionStore>>,
     request: Request,
     next: Next,
 ) -> Response {
@@ -60,7 +102,7 @@ pub async fn require_site_admin(
         Some(header) => header,
         None => {
             tracing::warn!("site admin request missing authorization header");
-            return unauthorized_response("Missing authorization token");
+            return unauthorized_response("Token de acesso ausente");
         }
     };
 
@@ -69,7 +111,7 @@ pub async fn require_site_admin(
         Some(token) => token,
         None => {
             tracing::warn!("site admin request has invalid authorization format");
-            return unauthorized_response("Invalid authorization format");
+            return unauthorized_response("Formato de autorização inválido");
         }
     };
 
@@ -78,12 +120,12 @@ pub async fn require_site_admin(
         Ok(token) => token,
         Err(_) => {
             tracing::warn!("site admin request has malformed token");
-            return unauthorized_response("Invalid token");
+            return unauthorized_response("Token inválido");
         }
     };
 
     // Validate session
-    match db.validate_admin_session(&session_token).await {
+    match sessions.validate(&session_token).await {
         Ok(true) => {
             // Authentication successful, store token in extensions and proceed
             let mut request = request;
@@ -92,7 +134,7 @@ pub async fn require_site_admin(
         }
         Ok(false) => {
             tracing::warn!("site admin request with invalid or expired session");
-            unauthorized_response("Invalid or expired token")
+            unauthorized_response("Sessão inválida ou expirada")
         }
         Err(e) => {
             tracing::error!("failed to validate site admin session: {}", e);
@@ -111,8 +153,48 @@ fn unauthorized_response(message: &str) -> Response {
 
 fn internal_error_response() -> Response {
     let body = Json(serde_json::json!({
-        "error": "Internal server error"
+        "error": "Erro interno do servidor"
     }));
 
     (StatusCode::INTERNAL_SERVER_ERROR, body).into_response()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_login_allowed_at() {
+        let last = Utc.with_ymd_and_hms(2025, 12, 1, 12, 0, 0).unwrap();
+        let after = |failures| login_allowed_at(failures, last, IP_LOCKOUT_FAILURES);
+
+        assert_eq!(after(0), None);
+        assert_eq!(after(FREE_LOGIN_FAILURES - 1), None);
+        assert_eq!(
+            after(FREE_LOGIN_FAILURES),
+            Some(last + Duration::seconds(2))
+        );
+        assert_eq!(
+            after(FREE_LOGIN_FAILURES + 1),
+            Some(last + Duration::seconds(4))
+        );
+        assert_eq!(
+            after(IP_LOCKOUT_FAILURES - 1),
+            Some(last + Duration::seconds(128))
+        );
+        assert_eq!(after(IP_LOCKOUT_FAILURES), Some(last + LOGIN_LOCKOUT));
+
+        // Backoff is capped until the account lockout
+        let account = |failures| login_allowed_at(failures, last, ACCOUNT_LOCKOUT_FAILURES);
+        assert_eq!(account(20), Some(last + MAX_LOGIN_BACKOFF));
+        assert_eq!(
+            account(ACCOUNT_LOCKOUT_FAILURES - 1),
+            Some(last + MAX_LOGIN_BACKOFF)
+        );
+        assert_eq!(
+            account(ACCOUNT_LOCKOUT_FAILURES),
+            Some(last + LOGIN_LOCKOUT)
+        );
+    }
+}
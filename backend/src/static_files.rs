@@ -0,0 +1,107 @@
+//! The frontend's files, served for every path outside `/api`, `/media` and the probes.
+//!
+//! They're read from `STATIC_DIR`, or, in builds with the `embed-frontend` feature, from
+//! the frontend build embedded in the binary at compile time (`../frontend/build`), so the
+//! server can ship as a single file. `STATIC_DIR` still takes precedence when set, e.g. to
+//! try a new frontend build without rebuilding the backend.
+//!
+//! Paths that aren't files get `index.html` with `404`, for the SPA to route them.
+
+use axum::http::StatusCode;
+use axum::routing::{MethodRouter, get_service};
+use std::path::PathBuf;
+use tower_http::services::{ServeDir, ServeFile};
+
+/// Where the frontend is served from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StaticFiles {
+    /// A frontend build on disk.
+    Dir(PathBuf),
+    /// The frontend build embedded in the binary.
+    #[cfg(feature = "embed-frontend")]
+    Embedded,
+}
+
+impl StaticFiles {
+    /// `STATIC_DIR` if set, or else the embedded files, in builds that have them.
+    pub fn new(static_dir: Option<PathBuf>) -> Self {
+        match static_dir {
+            Some(dir) => Self::Dir(dir),
+            #[cfg(feature = "embed-frontend")]
+            None => Self::Embedded,
+            #[cfg(not(feature = "embed-frontend"))]
+            None => Self::Dir(PathBuf::from("/app/public")),
+        }
+    }
+
+    pub fn service(&self) -> MethodRouter {
+        match self {
+            Self::Dir(dir) => {
+                let files =
+                    ServeDir::new(dir).not_found_service(ServeFile::new(dir.join("index.html")));
+                get_service(files).handle_error(|error| async move {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        format!("static file error: {error}"),
+                    )
+                })
+            }
+            #[cfg(feature = "embed-frontend")]
+            Self::Embedded => axum::routing::get(embedded::serve),
+        }
+    }
+}
+
+#[cfg(feature = "embed-frontend")]
+mod embedded {
+    use crate::routes::if_none_match;
+    use axum::{
+        http::{HeaderMap, StatusCode, Uri, header},
+        response::{IntoResponse, Response},
+    };
+    use rust_embed::{Embed, EmbeddedFile};
+
+    #[derive(Embed)]
+    #[folder = "../frontend/build"]
+    struct Frontend;
+
+    pub async fn serve(uri: Uri, headers: HeaderMap) -> Response {
+        let (file, status) = match find(uri.path()) {
+            Some(file) => (file, StatusCode::OK),
+            None => match Frontend::get("index.html") {
+                Some(index) => (index, StatusCode::NOT_FOUND),
+                None => return StatusCode::NOT_FOUND.into_response(),
+            },
+        };
+        // Files only change with the binary, so their hash makes a strong ETag
+        let etag = format!(
+            "\"{}\"",
+            file.metadata
+                .sha256_hash()
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<String>()
+        );
+        if status == StatusCode::OK && if_none_match(&headers, &etag) {
+            return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+        }
+        (
+            status,
+            [
+                (header::CONTENT_TYPE, file.metadata.mimetype().to_string()),
+                (header::ETAG, etag),
+            ],
+            file.data,
+        )
+            .into_response()
+    }
+
+    /// The file at `path`, or the `index.html` of the directory there, like `ServeDir`.
+    fn find(path: &str) -> Option<EmbeddedFile> {
+        let path = path.trim_start_matches('/');
+        if path.is_empty() || path.ends_with('/') {
+            return Frontend::get(&format!("{path}index.html"));
+        }
+        Frontend::get(path).or_else(|| Frontend::get(&format!("{path}/index.html")))
+    }
+}
@@ -0,0 +1,493 @@
+//! Storage for uploaded media, such as game cover images.
+//!
+//! Files are addressed by a [`MediaKey`] and kept behind the [`Storage`] trait: on the local
+//! disk ([`LocalStorage`]), or in an S3-compatible bucket ([`S3Storage`]) when
+//! `MEDIA_S3_BUCKET` is set, so that instances without a shared disk see the same files.
+
+use crate::clock::Clock;
+use crate::sigv4;
+use anyhow::{Context, Result, bail};
+use reqwest::{Method, StatusCode, header};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use ulid::Ulid;
+use url::Url;
+
+/// Maximum size of an uploaded cover image.
+pub const MAX_COVER_IMAGE_BYTES: usize = 1024 * 1024;
+
+/// Time allowed for each request to the storage service. Media files are small.
+const S3_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest error response from the storage service kept in the error message.
+const MAX_ERROR_BODY_LENGTH: usize = 500;
+
+pub type StorageFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// A place where uploaded media is kept.
+pub trait Storage: Send + Sync {
+    fn put<'a>(&'a self, key: &'a MediaKey, data: Vec<u8>) -> StorageFuture<'a, ()>;
+
+    /// Returns `None` if there is nothing stored under the key.
+    fn get<'a>(&'a self, key: &'a MediaKey) -> StorageFuture<'a, Option<Vec<u8>>>;
+
+    /// Deleting a missing key is not an error.
+    fn delete<'a>(&'a self, key: &'a MediaKey) -> StorageFuture<'a, ()>;
+}
+
+/// Keeps media as files in a directory on the local disk.
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn path(&self, key: &MediaKey) -> PathBuf {
+        // Keys are validated, so they never contain path separators
+        self.root.join(key.to_string())
+    }
+}
+
+impl Storage for LocalStorage {
+    fn put<'a>(&'a self, key: &'a MediaKey, data: Vec<u8>) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            tokio::fs::create_dir_all(&self.root)
+                .await
+                .context("creating media directory")?;
+            tokio::fs::write(self.path(key), data)
+                .await
+                .context("writing media file")
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a MediaKey) -> StorageFuture<'a, Option<Vec<u8>>> {
+        Box::pin(async move {
+            match tokio::fs::read(self.path(key)).await {
+                Ok(data) => Ok(Some(data)),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e).context("reading media file"),
+            }
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a MediaKey) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            match tokio::fs::remove_file(self.path(key)).await {
+                Err(e) if e.kind() != ErrorKind::NotFound => Err(e).context("deleting media file"),
+                _ => Ok(()),
+            }
+        })
+    }
+}
+
+/// Where uploaded media is kept, from the `MEDIA_*` settings.
+#[derive(Clone)]
+pub enum StorageConfig {
+    /// A directory on the local disk (`MEDIA_DIR`).
+    Local(PathBuf),
+    S3(Box<S3Config>),
+}
+
+impl StorageConfig {
+    pub fn open(&self, clock: Arc<dyn Clock>) -> Result<Arc<dyn Storage>> {
+        Ok(match self {
+            Self::Local(root) => Arc::new(LocalStorage::new(root.clone())),
+            Self::S3(config) => Arc::new(S3Storage::new((**config).clone(), clock)?),
+        })
+    }
+
+    /// `disk` or `s3`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Local(_) => "disk",
+            Self::S3(_) => "s3",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct S3Config {
+    /// Endpoint of the storage service, e.g. `https://s3.eu-west-1.wasabisys.com`. Objects
+    /// are addressed path-style, with the bucket in the path.
+    pub endpoint: Url,
+    pub bucket: String,
+    pub region: String,
+    pub credentials: sigv4::Credentials,
+    /// Prepended to the object keys, e.g. `media/`.
+    pub prefix: String,
+}
+
+/// Keeps media as objects in an S3-compatible bucket. Files are always served through the
+/// server, so the bucket can stay private.
+pub struct S3Storage {
+    config: S3Config,
+    client: reqwest::Client,
+    clock: Arc<dyn Clock>,
+}
+
+impl S3Storage {
+    pub fn new(config: S3Config, clock: Arc<dyn Clock>) -> Result<Self> {
+        // Credentials are never sent without TLS, even after a redirect
+        let client = reqwest::Client::builder()
+            .user_agent("amigo-oculto")
+            .https_only(true)
+            .timeout(S3_TIMEOUT)
+            .build()
+            .context("creating media storage client")?;
+        Ok(Self {
+            config,
+            client,
+            clock,
+        })
+    }
+
+    fn object_url(&self, key: &MediaKey) -> Result<Url> {
+        let mut url = self.config.endpoint.clone();
+        url.path_segments_mut()
+            .map_err(|()| anyhow::anyhow!("invalid media storage endpoint"))?
+            .pop_if_empty()
+            .push(&self.config.bucket)
+            .extend(
+                self.config
+                    .prefix
+                    .split('/')
+                    .filter(|part| !part.is_empty()),
+            )
+            .push(&key.to_string());
+        Ok(url)
+    }
+
+    /// Sends a signed request for the object of `key`.
+    async fn send(
+        &self,
+        method: Method,
+        key: &MediaKey,
+        body: Option<Vec<u8>>,
+    ) -> Result<reqwest::Response> {
+        let url = self.object_url(key)?;
+        let content_type = body.as_ref().map(|_| key.image_type().content_type());
+        let headers = sigv4::sign(
+            &self.config.credentials,
+            &self.config.region,
+            "s3",
+            &sigv4::SignableRequest {
+                method: method.as_str(),
+                url: &url,
+                content_type,
+                body: sigv4::Payload::Bytes(body.as_deref().unwrap_or_default()),
+            },
+            self.clock.now(),
+        );
+
+        let mut request = self.client.request(method, url);
+        if let (Some(content_type), Some(body)) = (content_type, body) {
+            request = request
+                .header(header::CONTENT_TYPE, content_type)
+                .body(body);
+        }
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        request
+            .send()
+            .await
+            .with_context(|| format!("requesting media object {key}"))
+    }
+}
+
+/// Fails unless the storage service answered with success.
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let body: String = body.chars().take(MAX_ERROR_BODY_LENGTH).collect();
+        bail!("S3 responded with {status}: {}", body.trim());
+    }
+    Ok(response)
+}
+
+impl Storage for S3Storage {
+    fn put<'a>(&'a self, key: &'a MediaKey, data: Vec<u8>) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let response = self.send(Method::PUT, key, Some(data)).await?;
+            check_status(response).await?;
+            Ok(())
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a MediaKey) -> StorageFuture<'a, Option<Vec<u8>>> {
+        Box::pin(async move {
+            let response = self.send(Method::GET, key, None).await?;
+            if response.status() == StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+            let data = check_status(response)
+                .await?
+                .bytes()
+                .await
+                .context("reading media object")?;
+            Ok(Some(data.to_vec()))
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a MediaKey) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            // S3 answers deletes of missing objects with success too, but not every
+            // compatible service does
+            let response = self.send(Method::DELETE, key, None).await?;
+            if response.status() != StatusCode::NOT_FOUND {
+                check_status(response).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+// =============================================================================
+// ImageType (detected from file contents)
+// =============================================================================
+
+/// Image formats accepted for upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageType {
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl ImageType {
+    /// Detects the format from the file's magic bytes, ignoring any client-provided type.
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if data.starts_with(b"\xff\xd8\xff") {
+            Some(Self::Jpeg)
+        } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+            Some(Self::Webp)
+        } else {
+            None
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Webp => "image/webp",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Webp => "webp",
+        }
+    }
+}
+
+// =============================================================================
+// MediaKey Newtype (name of a stored file, e.g. `01JF...XYZ.png`)
+// =============================================================================
+
+/// Identifies a stored media file. Also its file name, so it is safe to use in paths.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct MediaKey {
+    id: Ulid,
+    image_type: ImageType,
+}
+
+/// Error type for invalid media keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMediaKey;
+
+impl fmt::Display for InvalidMediaKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid media key")
+    }
+}
+
+impl std::error::Error for InvalidMediaKey {}
+
+impl MediaKey {
+    pub fn generate(image_type: ImageType) -> Self {
+        Self {
+            id: Ulid::new(),
+            image_type,
+        }
+    }
+
+    pub fn image_type(&self) -> ImageType {
+        self.image_type
+    }
+}
+
+impl fmt::Debug for MediaKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MediaKey({})", self)
+    }
+}
+
+impl fmt::Display for MediaKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.id, self.image_type.extension())
+    }
+}
+
+impl FromStr for MediaKey {
+    type Err = InvalidMediaKey;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (id, extension) = s.split_once('.').ok_or(InvalidMediaKey)?;
+        let image_type = [ImageType::Png, ImageType::Jpeg, ImageType::Webp]
+            .into_iter()
+            .find(|t| t.extension() == extension)
+            .ok_or(InvalidMediaKey)?;
+        // Only accept the canonical (uppercase) form, so each file has a single key
+        let id = Ulid::from_string(id).map_err(|_| InvalidMediaKey)?;
+        let key = Self { id, image_type };
+        if key.to_string() != s {
+            return Err(InvalidMediaKey);
+        }
+        Ok(key)
+    }
+}
+
+impl Serialize for MediaKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MediaKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl sqlx::Type<sqlx::Sqlite> for MediaKey {
+    fn type_info() -> sqlx::sqlite::SqliteTypeInfo {
+        <String as sqlx::Type<sqlx::Sqlite>>::type_info()
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Sqlite> for MediaKey {
+    fn decode(value: sqlx::sqlite::SqliteValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let s = <String as sqlx::Decode<sqlx::Sqlite>>::decode(value)?;
+        Ok(Self::from_str(&s)?)
+    }
+}
+
+impl<'q> sqlx::Encode<'q, sqlx::Sqlite> for MediaKey {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<sqlx::sqlite::SqliteArgumentValue<'q>>,
+    ) -> Result<sqlx::encode::IsNull, Box<dyn std::error::Error + Send + Sync>> {
+        <String as sqlx::Encode<sqlx::Sqlite>>::encode(self.to_string(), args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SystemClock;
+
+    #[test]
+    fn test_detect_image_type() {
+        assert_eq!(
+            ImageType::detect(b"\x89PNG\r\n\x1a\n...."),
+            Some(ImageType::Png)
+        );
+        assert_eq!(
+            ImageType::detect(b"\xff\xd8\xff\xe0"),
+            Some(ImageType::Jpeg)
+        );
+        assert_eq!(
+            ImageType::detect(b"RIFF\x00\x00\x00\x00WEBPVP8 "),
+            Some(ImageType::Webp)
+        );
+        assert_eq!(ImageType::detect(b"<svg></svg>"), None);
+    }
+
+    #[test]
+    fn test_media_key_roundtrip() {
+        let key = MediaKey::generate(ImageType::Jpeg);
+        let parsed: MediaKey = key.to_string().parse().unwrap();
+        assert_eq!(parsed, key);
+        assert!(key.to_string().ends_with(".jpg"));
+
+        assert!("../etc/passwd".parse::<MediaKey>().is_err());
+        assert!(
+            "01ARZ3NDEKTSV4RRFFQ69G5FAV.gif"
+                .parse::<MediaKey>()
+                .is_err()
+        );
+        assert!(
+            "01arz3ndektsv4rrffq69g5fav.png"
+                .parse::<MediaKey>()
+                .is_err()
+        );
+        assert!("01ARZ3NDEKTSV4RRFFQ69G5FAV.png".parse::<MediaKey>().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_local_storage() {
+        let root = std::env::temp_dir().join(format!("amigo-oculto-media-{}", Ulid::new()));
+        let storage = LocalStorage::new(root.clone());
+        let key = MediaKey::generate(ImageType::Png);
+
+        assert_eq!(storage.get(&key).await.unwrap(), None);
+        storage.put(&key, b"image".to_vec()).await.unwrap();
+        assert_eq!(storage.get(&key).await.unwrap(), Some(b"image".to_vec()));
+        storage.delete(&key).await.unwrap();
+        assert_eq!(storage.get(&key).await.unwrap(), None);
+        storage.delete(&key).await.unwrap();
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_s3_object_url() {
+        let storage = |prefix: &str| {
+            let config = S3Config {
+                endpoint: "https://s3.eu-west-1.wasabisys.com".parse().unwrap(),
+                bucket: "amigo-oculto".to_string(),
+                region: "eu-west-1".to_string(),
+                credentials: sigv4::Credentials {
+                    access_key_id: "key".to_string(),
+                    secret_access_key: "secret".to_string(),
+                    session_token: None,
+                },
+                prefix: prefix.to_string(),
+            };
+            S3Storage::new(config, Arc::new(SystemClock)).unwrap()
+        };
+        let key: MediaKey = "01ARZ3NDEKTSV4RRFFQ69G5FAV.png".parse().unwrap();
+
+        assert_eq!(
+            storage("media/").object_url(&key).unwrap().as_str(),
+            "https://s3.eu-west-1.wasabisys.com/amigo-oculto/media/01ARZ3NDEKTSV4RRFFQ69G5FAV.png"
+        );
+        assert_eq!(
+            storage("").object_url(&key).unwrap().as_str(),
+            "https://s3.eu-west-1.wasabisys.com/amigo-oculto/01ARZ3NDEKTSV4RRFFQ69G5FAV.png"
+        );
+    }
+}
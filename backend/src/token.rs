@@ -1,13 +1,19 @@
-use lettre::address::Address;
+use amigo_oculto_validation::{TOKEN_LENGTH, VERIFICATION_CODE_LENGTH, valid_email};
+use lettre::address::{Address, AddressError};
 use rand::distr::Alphanumeric;
 use rand::{Rng, rng};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fmt;
 use std::str::FromStr;
 use ulid::Ulid;
+use utoipa::ToSchema;
 
-/// Length of secure tokens in characters
-const TOKEN_LENGTH: usize = 32;
+/// Length of the random salt of a [`TokenHash`] in characters
+const SALT_LENGTH: usize = 16;
+
+/// Length of the prefix of a view token stored in plaintext, to find its hash
+const LOOKUP_KEY_LENGTH: usize = 8;
 
 // =============================================================================
 // ID Newtypes (wrap Ulid)
@@ -16,7 +22,8 @@ const TOKEN_LENGTH: usize = 32;
 macro_rules! define_id_type {
     ($name:ident, $doc:literal) => {
         #[doc = $doc]
-        #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, ToSchema)]
+        #[schema(value_type = String, format = Ulid)]
         pub struct $name(Ulid);
 
         impl $name {
@@ -99,6 +106,13 @@ macro_rules! define_id_type {
 define_id_type!(GameId, "Unique identifier for a Game");
 define_id_type!(ParticipantId, "Unique identifier for a Participant");
 define_id_type!(VerificationId, "Unique identifier for an EmailVerification");
+define_id_type!(ReportId, "Unique identifier for an AbuseReport");
+define_id_type!(RecoveryId, "Unique identifier for a RevealRecovery");
+define_id_type!(EmailJobId, "Unique identifier for an EmailJob");
+define_id_type!(PasskeyId, "Unique identifier for a site admin passkey");
+define_id_type!(AdminSessionId, "Unique identifier for a site admin session");
+define_id_type!(AnnouncementId, "Unique identifier for an Announcement");
+define_id_type!(EmailBanId, "Unique identifier for an EmailBan");
 
 // =============================================================================
 // Token Newtypes (wrap String, distinct types)
@@ -107,7 +121,7 @@ define_id_type!(VerificationId, "Unique identifier for an EmailVerification");
 macro_rules! define_token_type {
     ($name:ident, $doc:literal) => {
         #[doc = $doc]
-        #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+        #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
         pub struct $name(String);
 
         impl $name {
@@ -125,6 +139,11 @@ macro_rules! define_token_type {
             pub fn as_str(&self) -> &str {
                 &self.0
             }
+
+            /// Hash the token for storage
+            pub fn hash(&self) -> TokenHash {
+                TokenHash::new(&self.0)
+            }
         }
 
         // Redacted debug output for security
@@ -188,10 +207,117 @@ macro_rules! define_token_type {
 
 define_token_type!(AdminToken, "Token for game organizer (admin) access");
 define_token_type!(ViewToken, "Token for participant match reveal access");
+
+impl ViewToken {
+    /// The start of the token, stored next to its hash so the hash can be found again.
+    /// Leaves far too many characters unknown to guess the rest.
+    pub fn lookup_key(&self) -> &str {
+        self.0.get(..LOOKUP_KEY_LENGTH).unwrap_or(&self.0)
+    }
+}
 define_token_type!(
     AdminSessionToken,
     "Session token for site administrator access"
 );
+define_token_type!(
+    OrganizerLoginToken,
+    "Single-use token for an organizer magic login link"
+);
+define_token_type!(
+    OrganizerSessionToken,
+    "Session token for organizers logged in via magic link"
+);
+define_token_type!(
+    UnsubscribeToken,
+    "Token for unsubscribing an email address from optional emails"
+);
+define_token_type!(
+    EmailOpenToken,
+    "Token in the tracking pixel of a participant's emails"
+);
+define_token_type!(
+    VerificationLinkToken,
+    "Token in the confirmation link of a verification email"
+);
+
+// =============================================================================
+// TokenHash (salted hash of a secret, as stored in the database)
+// =============================================================================
+
+/// A salted SHA-256 hash of a token or code.
+///
+/// Stored instead of the secret itself, so a leaked database doesn't expose working links.
+/// The format is `salt$hash`; tokens and codes never contain a `$`, which tells hashes
+/// apart from plaintext secrets stored by older versions.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TokenHash(String);
+
+impl TokenHash {
+    /// Hash a secret with a new random salt.
+    pub fn new(secret: &str) -> Self {
+        let salt: String = rng()
+            .sample_iter(&Alphanumeric)
+            .take(SALT_LENGTH)
+            .map(char::from)
+            .collect();
+        let digest = salted_digest(&salt, secret);
+        Self(format!("{salt}${digest}"))
+    }
+
+    /// Whether this is the hash of `secret`. Compares in constant time, so response timing
+    /// doesn't reveal how much of a guess was correct.
+    pub fn matches(&self, secret: &str) -> bool {
+        let Some((salt, expected)) = self.0.split_once('$') else {
+            return false;
+        };
+        let actual = salted_digest(salt, secret);
+        constant_time_eq(expected.as_bytes(), actual.as_bytes())
+    }
+}
+
+/// Whether two secrets are equal, taking as long wherever they differ. Only their length
+/// can be told from the timing.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn salted_digest(salt: &str, secret: &str) -> String {
+    Sha256::new()
+        .chain_update(salt)
+        .chain_update(secret)
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+impl fmt::Debug for TokenHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TokenHash({})", self.0)
+    }
+}
+
+impl sqlx::Type<sqlx::Sqlite> for TokenHash {
+    fn type_info() -> sqlx::sqlite::SqliteTypeInfo {
+        <String as sqlx::Type<sqlx::Sqlite>>::type_info()
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Sqlite> for TokenHash {
+    fn decode(value: sqlx::sqlite::SqliteValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let s = <String as sqlx::Decode<sqlx::Sqlite>>::decode(value)?;
+        Ok(Self(s))
+    }
+}
+
+impl<'q> sqlx::Encode<'q, sqlx::Sqlite> for TokenHash {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<sqlx::sqlite::SqliteArgumentValue<'q>>,
+    ) -> Result<sqlx::encode::IsNull, Box<dyn std::error::Error + Send + Sync>> {
+        <String as sqlx::Encode<sqlx::Sqlite>>::encode_by_ref(&self.0, args)
+    }
+}
 
 // =============================================================================
 // EmailAddress Newtype (wraps lettre::address::Address for type safety)
@@ -203,7 +329,8 @@ define_token_type!(
 /// - Validation at parse/deserialization time (fail-fast at API boundaries)
 /// - Type safety to prevent mixing email strings with other strings
 /// - SQLite persistence support
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash, ToSchema)]
+#[schema(value_type = String, format = Email)]
 pub struct EmailAddress(Address);
 
 impl EmailAddress {
@@ -211,6 +338,21 @@ impl EmailAddress {
     pub fn to_mailbox(&self) -> lettre::message::Mailbox {
         lettre::message::Mailbox::new(None, self.0.clone())
     }
+
+    /// The part of the address before the `@`.
+    pub fn user(&self) -> &str {
+        self.0.user()
+    }
+
+    /// The part of the address after the `@`.
+    pub fn domain(&self) -> &str {
+        self.0.domain()
+    }
+
+    /// Whether this address belongs to the given domain (case-insensitive).
+    pub fn is_in_domain(&self, domain: &EmailDomain) -> bool {
+        self.domain().eq_ignore_ascii_case(domain.as_str())
+    }
 }
 
 impl fmt::Debug for EmailAddress {
@@ -225,11 +367,23 @@ impl fmt::Display for EmailAddress {
     }
 }
 
+impl From<Address> for EmailAddress {
+    fn from(address: Address) -> Self {
+        Self(address)
+    }
+}
+
 impl FromStr for EmailAddress {
-    type Err = lettre::address::AddressError;
+    type Err = AddressError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(Address::from_str(s)?))
+        let address = Address::from_str(s)?;
+        // The same rule the frontend checks before sending; it differs from lettre's only on
+        // domains that are valid once converted to punycode
+        if !valid_email(s) {
+            return Err(AddressError::InvalidDomain);
+        }
+        Ok(Self(address))
     }
 }
 
@@ -254,7 +408,7 @@ impl<'de> Deserialize<'de> for EmailAddress {
         D: serde::Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        Self::from_str(&s).map_err(serde::de::Error::custom)
+        Self::from_str(&s).map_err(|_| serde::de::Error::custom("Email inválido"))
     }
 }
 
@@ -280,6 +434,101 @@ impl<'q> sqlx::Encode<'q, sqlx::Sqlite> for EmailAddress {
     }
 }
 
+// =============================================================================
+// EmailDomain Newtype (domain part of an email address, lowercase)
+// =============================================================================
+
+/// A validated, lowercase email domain such as `empresa.com.br`.
+///
+/// A leading `@` is accepted and stripped when parsing, so both `empresa.com` and
+/// `@empresa.com` are valid inputs.
+#[derive(Clone, PartialEq, Eq, Hash, ToSchema)]
+pub struct EmailDomain(String);
+
+/// Error type for invalid email domains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidEmailDomain;
+
+impl fmt::Display for InvalidEmailDomain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid email domain")
+    }
+}
+
+impl std::error::Error for InvalidEmailDomain {}
+
+impl EmailDomain {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for EmailDomain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EmailDomain({})", self.0)
+    }
+}
+
+impl fmt::Display for EmailDomain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for EmailDomain {
+    type Err = InvalidEmailDomain;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let domain = s.trim().trim_start_matches('@').to_ascii_lowercase();
+        // Require at least one dot so that bare hostnames like `localhost` are rejected.
+        if !domain.contains('.') || Address::new("user", &domain).is_err() {
+            return Err(InvalidEmailDomain);
+        }
+        Ok(Self(domain))
+    }
+}
+
+impl Serialize for EmailDomain {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for EmailDomain {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl sqlx::Type<sqlx::Sqlite> for EmailDomain {
+    fn type_info() -> sqlx::sqlite::SqliteTypeInfo {
+        <String as sqlx::Type<sqlx::Sqlite>>::type_info()
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::Sqlite> for EmailDomain {
+    fn decode(value: sqlx::sqlite::SqliteValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let s = <String as sqlx::Decode<sqlx::Sqlite>>::decode(value)?;
+        Ok(Self::from_str(&s)?)
+    }
+}
+
+impl<'q> sqlx::Encode<'q, sqlx::Sqlite> for EmailDomain {
+    fn encode_by_ref(
+        &self,
+        args: &mut Vec<sqlx::sqlite::SqliteArgumentValue<'q>>,
+    ) -> Result<sqlx::encode::IsNull, Box<dyn std::error::Error + Send + Sync>> {
+        <String as sqlx::Encode<sqlx::Sqlite>>::encode_by_ref(&self.0, args)
+    }
+}
+
 // =============================================================================
 // VerificationCode Newtype (exactly 6 numeric digits)
 // =============================================================================
@@ -288,8 +537,9 @@ impl<'q> sqlx::Encode<'q, sqlx::Sqlite> for EmailAddress {
 ///
 /// This type stores exactly 6 digits (0-9) and validates input at parse/deserialization time.
 /// Invalid codes (wrong length, non-numeric) are rejected at API boundaries.
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-pub struct VerificationCode([u8; 6]);
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ToSchema)]
+#[schema(value_type = String, pattern = "^[0-9]{6}$")]
+pub struct VerificationCode([u8; VERIFICATION_CODE_LENGTH]);
 
 /// Error type for invalid verification code format.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -307,12 +557,17 @@ impl VerificationCode {
     /// Generate a new random 6-digit verification code.
     pub fn generate() -> Self {
         let mut rng = rng();
-        let mut digits = [0u8; 6];
+        let mut digits = [0u8; VERIFICATION_CODE_LENGTH];
         for digit in &mut digits {
             *digit = rng.random_range(0..10);
         }
         Self(digits)
     }
+
+    /// Hash the code for storage
+    pub fn hash(&self) -> TokenHash {
+        TokenHash::new(&self.to_string())
+    }
 }
 
 impl fmt::Debug for VerificationCode {
@@ -335,11 +590,11 @@ impl FromStr for VerificationCode {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let bytes = s.as_bytes();
-        if bytes.len() != 6 {
+        if bytes.len() != VERIFICATION_CODE_LENGTH {
             return Err(InvalidVerificationCode);
         }
 
-        let mut digits = [0u8; 6];
+        let mut digits = [0u8; VERIFICATION_CODE_LENGTH];
         for (i, &byte) in bytes.iter().enumerate() {
             if !byte.is_ascii_digit() {
                 return Err(InvalidVerificationCode);
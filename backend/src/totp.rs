@@ -0,0 +1,180 @@
+//! Time-based one-time passwords (RFC 6238) for the site admin's two-step verification.
+//!
+//! Codes are 6 digits from HMAC-SHA1 over 30 second steps, the defaults every authenticator
+//! app supports, computed by `totp-rs`. Recovery codes let the admin log in without the
+//! app, each once.
+
+use chrono::{DateTime, Utc};
+use rand::{Rng, rng};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+/// Seconds each code is valid for.
+const STEP_SECONDS: i64 = 30;
+
+/// Digits in a code.
+const DIGITS: usize = 6;
+
+/// Codes from this many steps before or after the current one are accepted too, since the
+/// admin's phone clock may be a little off.
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+
+/// Name shown for the account in authenticator apps.
+const ISSUER: &str = "Amigo Oculto";
+
+/// Account the secret is for in authenticator apps.
+const ACCOUNT: &str = "admin";
+
+/// Number of recovery codes issued when two-step verification is enabled.
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Characters of a recovery code, written as two halves separated by a dash.
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+/// Generate a new 160-bit secret, base32-encoded.
+pub fn generate_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+/// The generator for a base32-encoded secret, or `None` if it isn't valid.
+///
+/// Drift is handled by [`verify`], which needs to know the step a code matched, so the
+/// generator itself accepts no skew.
+fn totp(secret: &str) -> Option<TOTP> {
+    let key = Secret::Encoded(secret.trim_end_matches('=').to_ascii_uppercase())
+        .to_bytes()
+        .ok()?;
+    TOTP::new(
+        Algorithm::SHA1,
+        DIGITS,
+        0,
+        STEP_SECONDS as u64,
+        key,
+        Some(ISSUER.to_string()),
+        ACCOUNT.to_string(),
+    )
+    .ok()
+}
+
+/// The `otpauth://` URL authenticator apps import, usually through a QR code.
+pub fn otpauth_url(secret: &str) -> Option<String> {
+    totp(secret).map(|totp| totp.get_url())
+}
+
+/// Returns the time step `code` belongs to if it's valid for `secret` at `now`.
+///
+/// Steps up to `last_step` are rejected, so each code can only be used once.
+pub fn verify(secret: &str, code: &str, now: DateTime<Utc>, last_step: Option<i64>) -> Option<i64> {
+    let totp = totp(secret)?;
+    if !is_totp_code(code) {
+        return None;
+    }
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+
+    let current = now.timestamp().div_euclid(STEP_SECONDS);
+    (current - ALLOWED_DRIFT_STEPS..=current + ALLOWED_DRIFT_STEPS)
+        .filter(|&step| last_step.is_none_or(|last| step > last))
+        .find(|&step| u64::try_from(step * STEP_SECONDS).is_ok_and(|time| totp.check(&code, time)))
+}
+
+/// Generate a set of recovery codes, e.g. `k3m9x-7qp2w`.
+pub fn generate_recovery_codes() -> Vec<String> {
+    const ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let code: String = (0..RECOVERY_CODE_LENGTH)
+                .map(|_| char::from(ALPHABET[rng().random_range(0..ALPHABET.len())]))
+                .collect();
+            let (first, second) = code.split_at(RECOVERY_CODE_LENGTH / 2);
+            format!("{first}-{second}")
+        })
+        .collect()
+}
+
+/// A recovery code as it's hashed, ignoring case, spaces and dashes.
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Whether `code` looks like a one-time password rather than a recovery code.
+pub fn is_totp_code(code: &str) -> bool {
+    let digits: Vec<char> = code.chars().filter(|c| !c.is_whitespace()).collect();
+    digits.len() == DIGITS && digits.iter().all(char::is_ascii_digit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// The SHA-1 secret from the RFC 6238 test vectors, `12345678901234567890`, in base32.
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn test_rfc_vectors() {
+        // RFC 6238 appendix B, truncated to 6 digits
+        let totp = totp(RFC_SECRET).unwrap();
+        for (time, code) in [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+        ] {
+            assert_eq!(totp.generate(time), code);
+        }
+    }
+
+    #[test]
+    fn test_secrets() {
+        let secret = generate_secret();
+        assert_eq!(secret.len(), 32);
+        assert!(totp(&secret).is_some());
+        assert!(totp(&RFC_SECRET.to_lowercase()).is_some());
+        assert!(totp("not base32!").is_none());
+        // Shorter than the 128 bits RFC 4226 requires
+        assert!(totp("GEZDGNBVGY3TQOJQ").is_none());
+    }
+
+    #[test]
+    fn test_verify() {
+        let now = Utc.timestamp_opt(1111111109, 0).unwrap();
+        let step = 1111111109 / STEP_SECONDS;
+
+        assert_eq!(verify(RFC_SECRET, "081804", now, None), Some(step));
+        assert_eq!(verify(RFC_SECRET, "081 804", now, None), Some(step));
+        assert_eq!(verify(RFC_SECRET, "000000", now, None), None);
+        assert_eq!(verify(RFC_SECRET, "81804", now, None), None);
+
+        // A code from the previous step is still accepted, once
+        let later = now + chrono::Duration::seconds(STEP_SECONDS);
+        assert_eq!(verify(RFC_SECRET, "081804", later, None), Some(step));
+        assert_eq!(verify(RFC_SECRET, "081804", later, Some(step)), None);
+
+        // But not from two steps ago
+        let much_later = now + chrono::Duration::seconds(2 * STEP_SECONDS);
+        assert_eq!(verify(RFC_SECRET, "081804", much_later, None), None);
+    }
+
+    #[test]
+    fn test_otpauth_url() {
+        assert_eq!(
+            otpauth_url(RFC_SECRET).unwrap(),
+            "otpauth://totp/Amigo%20Oculto:admin?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ\
+             &issuer=Amigo%20Oculto"
+        );
+    }
+
+    #[test]
+    fn test_recovery_codes() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        for code in &codes {
+            assert_eq!(code.len(), RECOVERY_CODE_LENGTH + 1);
+            assert!(!is_totp_code(code));
+        }
+        assert_eq!(normalize_recovery_code(" K3M9X-7qp2w "), "k3m9x7qp2w");
+        assert!(is_totp_code("123 456"));
+    }
+}
@@ -0,0 +1,430 @@
+//! Validation of JSON request bodies, reported field by field.
+//!
+//! Handlers take a [`Valid`] body instead of a `Json` one: it's deserialized and checked with
+//! its [`Validate`] implementation before the handler runs. Invalid bodies get a `422` with
+//! every problem found, by field, so forms can show them next to the inputs:
+//!
+//! ```json
+//! {"error": "Informe o nome do participante", "code": "VALIDATION_FAILED",
+//!  "fields": {"name": ["Informe o nome do participante"], "email": ["Email inválido"]}}
+//! ```
+//!
+//! Messages are in Portuguese, and translated like other API messages.
+
+use crate::datetime::GameTimezone;
+use crate::email_templates::custom;
+use crate::i18n::Locale;
+use crate::models::{
+    AddParticipantRequest, CreateAnnouncementRequest, CreateReportRequest, OrganizerLoginRequest,
+    RequestRevealRecoveryRequest, RequestVerificationRequest, UpdateGameRequest,
+    UpdateParticipantRequest,
+};
+use crate::routes::{AppError, AppState};
+use amigo_oculto_validation::*;
+use axum::{
+    Json,
+    extract::{FromRequest, Request},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+
+/// Problems found in a request body, by field, in the order they were found.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FieldErrors(Vec<(String, String)>);
+
+impl FieldErrors {
+    pub fn add(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.0.push((field.into(), message.into()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The first problem found, to show when fields can't be.
+    pub fn first_message(&self) -> Option<&str> {
+        self.0.first().map(|(_, message)| message.as_str())
+    }
+
+    /// Messages by field, for the response body.
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut fields = serde_json::Map::new();
+        for (field, message) in &self.0 {
+            let messages = fields
+                .entry(field.clone())
+                .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+            if let serde_json::Value::Array(messages) = messages {
+                messages.push(message.clone().into());
+            }
+        }
+        fields.into()
+    }
+
+    /// Fails with the problems found, if any.
+    pub fn into_result(self) -> Result<(), AppError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(self))
+        }
+    }
+
+    /// Adds an error for a body that couldn't be deserialized. Messages written for our own
+    /// types, such as an invalid email, are kept; serde's are replaced with a generic one.
+    fn add_deserialization_error(
+        &mut self,
+        error: serde_path_to_error::Error<serde_json::Error>,
+    ) -> Result<(), AppError> {
+        let path = error.path().to_string();
+        let message = error.into_inner().to_string();
+        if let Some(field) = message
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.strip_suffix('`'))
+        {
+            let field = if path == "." {
+                field.to_string()
+            } else {
+                format!("{path}.{field}")
+            };
+            self.add(field, "Campo obrigatório");
+        } else if path == "." {
+            return Err(AppError::BadRequest("Requisição inválida".to_string()));
+        } else if Locale::En.translate_message(&message).is_some() {
+            self.add(path, message);
+        } else {
+            self.add(path, "Valor inválido");
+        }
+        Ok(())
+    }
+}
+
+/// A request body that can be checked before it's handled.
+pub trait Validate {
+    /// Adds the problems with the body to `errors`. `now` is for dates that must be in the
+    /// future.
+    fn validate(&self, now: DateTime<Utc>, errors: &mut FieldErrors);
+}
+
+/// A JSON request body that passed its [`Validate`] checks.
+#[derive(Debug)]
+pub struct Valid<T>(pub T);
+
+impl<T> FromRequest<Arc<AppState>> for Valid<T>
+where
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &Arc<AppState>) -> Result<Self, Response> {
+        // Malformed JSON and wrong content types are rejected as with `Json`
+        let Json(value) = Json::<serde_json::Value>::from_request(request, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        let mut errors = FieldErrors::default();
+        let body: T = match serde_path_to_error::deserialize(value) {
+            Ok(body) => body,
+            Err(e) => {
+                errors
+                    .add_deserialization_error(e)
+                    .map_err(IntoResponse::into_response)?;
+                return Err(AppError::Validation(errors).into_response());
+            }
+        };
+        body.validate(state.clock.now(), &mut errors);
+        errors.into_result().map_err(IntoResponse::into_response)?;
+        Ok(Self(body))
+    }
+}
+
+fn check_name(errors: &mut FieldErrors, field: &str, name: &str, blank_message: &str) {
+    if name.trim().is_empty() {
+        errors.add(field, blank_message);
+    } else if too_long(name, MAX_NAME_LENGTH) {
+        errors.add(
+            field,
+            format!("O nome deve ter no máximo {} caracteres", MAX_NAME_LENGTH),
+        );
+    }
+}
+
+fn check_event_location(errors: &mut FieldErrors, location: Option<&str>) {
+    if location.is_some_and(|location| too_long(location, MAX_EVENT_LOCATION_LENGTH)) {
+        errors.add(
+            "event_location",
+            format!(
+                "O local do evento deve ter no máximo {} caracteres",
+                MAX_EVENT_LOCATION_LENGTH
+            ),
+        );
+    }
+}
+
+fn check_event_date(
+    errors: &mut FieldErrors,
+    event_date: NaiveDate,
+    timezone: GameTimezone,
+    now: DateTime<Utc>,
+) {
+    let today = timezone.date_at(now);
+    if event_date < today {
+        errors.add("event_date", "A data do evento não pode estar no passado");
+    } else if event_date > today + Duration::days(MAX_EVENT_DAYS_AHEAD) {
+        errors.add(
+            "event_date",
+            "A data do evento deve ser nos próximos 5 anos",
+        );
+    }
+}
+
+fn check_email_template(
+    errors: &mut FieldErrors,
+    field: &str,
+    template: Option<&str>,
+    max_length: usize,
+) {
+    let Some(template) = template.map(str::trim).filter(|t| !t.is_empty()) else {
+        return;
+    };
+    if too_long(template, max_length) {
+        errors.add(
+            field,
+            format!(
+                "O modelo de email deve ter no máximo {} caracteres",
+                max_length
+            ),
+        );
+    } else if let Err(e) = custom::validate(template) {
+        errors.add(field, e.to_string());
+    }
+}
+
+impl Validate for RequestVerificationRequest {
+    fn validate(&self, now: DateTime<Utc>, errors: &mut FieldErrors) {
+        check_name(errors, "name", &self.name, "Informe o nome do jogo");
+        check_event_date(errors, self.event_date, self.timezone, now);
+        check_event_location(errors, self.event_location.as_deref());
+    }
+}
+
+impl Validate for AddParticipantRequest {
+    fn validate(&self, _now: DateTime<Utc>, errors: &mut FieldErrors) {
+        check_name(errors, "name", &self.name, "Informe o nome do participante");
+    }
+}
+
+impl Validate for UpdateParticipantRequest {
+    fn validate(&self, _now: DateTime<Utc>, errors: &mut FieldErrors) {
+        if let Some(name) = &self.name {
+            check_name(errors, "name", name, "Informe o nome do participante");
+        }
+    }
+}
+
+impl Validate for UpdateGameRequest {
+    fn validate(&self, _now: DateTime<Utc>, errors: &mut FieldErrors) {
+        if let Some(location) = &self.event_location {
+            check_event_location(errors, location.as_deref());
+        }
+        if let Some(Some(message)) = &self.organizer_message
+            && too_long(message, MAX_ORGANIZER_MESSAGE_LENGTH)
+        {
+            errors.add(
+                "organizer_message",
+                format!(
+                    "A mensagem deve ter no máximo {} caracteres",
+                    MAX_ORGANIZER_MESSAGE_LENGTH
+                ),
+            );
+        }
+        if let Some(subject) = &self.participant_email_subject {
+            check_email_template(
+                errors,
+                "participant_email_subject",
+                subject.as_deref(),
+                MAX_EMAIL_SUBJECT_LENGTH,
+            );
+        }
+        if let Some(body) = &self.participant_email_body {
+            check_email_template(
+                errors,
+                "participant_email_body",
+                body.as_deref(),
+                MAX_EMAIL_BODY_LENGTH,
+            );
+        }
+        if let Some(days) = &self.event_reminder_days {
+            let mut distinct = days.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            if distinct.len() > MAX_EVENT_REMINDERS {
+                errors.add(
+                    "event_reminder_days",
+                    format!(
+                        "Escolha no máximo {} lembretes antes do evento",
+                        MAX_EVENT_REMINDERS
+                    ),
+                );
+            }
+            if days
+                .iter()
+                .any(|&days| days == 0 || days > MAX_EVENT_REMINDER_DAYS)
+            {
+                errors.add(
+                    "event_reminder_days",
+                    format!(
+                        "Os lembretes devem ser enviados de 1 a {} dias antes do evento",
+                        MAX_EVENT_REMINDER_DAYS
+                    ),
+                );
+            }
+        }
+    }
+}
+
+// Only an email, checked as it's deserialized
+impl Validate for RequestRevealRecoveryRequest {
+    fn validate(&self, _now: DateTime<Utc>, _errors: &mut FieldErrors) {}
+}
+
+impl Validate for OrganizerLoginRequest {
+    fn validate(&self, _now: DateTime<Utc>, _errors: &mut FieldErrors) {}
+}
+
+impl Validate for CreateReportRequest {
+    fn validate(&self, _now: DateTime<Utc>, errors: &mut FieldErrors) {
+        if self.reason.trim().is_empty() {
+            errors.add("reason", "Descreva o motivo da denúncia");
+        } else if too_long(&self.reason, MAX_REPORT_REASON_LENGTH) {
+            errors.add(
+                "reason",
+                format!(
+                    "O motivo da denúncia deve ter no máximo {} caracteres",
+                    MAX_REPORT_REASON_LENGTH
+                ),
+            );
+        }
+    }
+}
+
+impl Validate for CreateAnnouncementRequest {
+    fn validate(&self, _now: DateTime<Utc>, errors: &mut FieldErrors) {
+        let blank = "O assunto e a mensagem do aviso são obrigatórios";
+        if self.subject.trim().is_empty() {
+            errors.add("subject", blank);
+        } else if too_long(&self.subject, MAX_ANNOUNCEMENT_SUBJECT_LENGTH) {
+            errors.add(
+                "subject",
+                format!(
+                    "O assunto deve ter no máximo {} caracteres",
+                    MAX_ANNOUNCEMENT_SUBJECT_LENGTH
+                ),
+            );
+        }
+        if self.message.trim().is_empty() {
+            errors.add("message", blank);
+        } else if too_long(&self.message, MAX_EMAIL_BODY_LENGTH) {
+            errors.add(
+                "message",
+                format!(
+                    "A mensagem deve ter no máximo {} caracteres",
+                    MAX_EMAIL_BODY_LENGTH
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors_for<T: DeserializeOwned + Validate>(body: serde_json::Value) -> FieldErrors {
+        let now = "2025-06-01T12:00:00Z".parse().unwrap();
+        let mut errors = FieldErrors::default();
+        match serde_path_to_error::deserialize::<_, T>(body) {
+            Ok(body) => body.validate(now, &mut errors),
+            Err(e) => errors.add_deserialization_error(e).unwrap(),
+        }
+        errors
+    }
+
+    #[test]
+    fn test_field_errors() {
+        let errors = errors_for::<RequestVerificationRequest>(serde_json::json!({
+            "name": "  ",
+            "event_date": "2025-05-31",
+            "organizer_email": "organizer@example.com",
+            "event_location": "x".repeat(MAX_EVENT_LOCATION_LENGTH + 1),
+        }));
+        assert_eq!(errors.first_message(), Some("Informe o nome do jogo"));
+        assert_eq!(
+            errors.to_json(),
+            serde_json::json!({
+                "name": ["Informe o nome do jogo"],
+                "event_date": ["A data do evento não pode estar no passado"],
+                "event_location": ["O local do evento deve ter no máximo 200 caracteres"],
+            })
+        );
+
+        let valid = errors_for::<AddParticipantRequest>(serde_json::json!({
+            "name": " Alice ",
+            "email": "alice@example.com",
+        }));
+        assert!(valid.is_empty());
+
+        let errors = errors_for::<UpdateGameRequest>(serde_json::json!({
+            "participant_email_body": "Olá {{ nome }}",
+            "event_reminder_days": [1, 2, 3, 40],
+        }));
+        assert_eq!(errors.to_json().as_object().unwrap().len(), 2);
+        assert_eq!(
+            errors.to_json()["event_reminder_days"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_deserialization_errors() {
+        let errors = errors_for::<AddParticipantRequest>(serde_json::json!({
+            "name": "Alice",
+            "email": "not an email",
+        }));
+        assert_eq!(
+            errors.to_json(),
+            serde_json::json!({ "email": ["Email inválido"] })
+        );
+
+        let errors = errors_for::<OrganizerLoginRequest>(serde_json::json!({ "email": "ana@" }));
+        assert_eq!(
+            errors.to_json(),
+            serde_json::json!({ "email": ["Email inválido"] })
+        );
+
+        let errors = errors_for::<AddParticipantRequest>(serde_json::json!({ "name": "Alice" }));
+        assert_eq!(
+            errors.to_json(),
+            serde_json::json!({ "email": ["Campo obrigatório"] })
+        );
+
+        let errors = errors_for::<AddParticipantRequest>(serde_json::json!({
+            "name": 42,
+            "email": "alice@example.com",
+        }));
+        assert_eq!(
+            errors.to_json(),
+            serde_json::json!({ "name": ["Valor inválido"] })
+        );
+
+        let mut errors = FieldErrors::default();
+        let error =
+            serde_path_to_error::deserialize::<_, AddParticipantRequest>(serde_json::json!([]))
+                .unwrap_err();
+        assert!(errors.add_deserialization_error(error).is_err());
+    }
+}
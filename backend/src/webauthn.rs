@@ -0,0 +1,220 @@
+//! Passkey (WebAuthn) login for the site admin, through `webauthn-rs`.
+//!
+//! Starting a ceremony gives the options for the browser and a state, which is stored with
+//! the challenge until the browser's answer comes back to finish the ceremony. Registration
+//! asks for no attestation, since only a logged-in admin can register a passkey, and logins
+//! whose signature counter went backwards are refused, as the passkey may have been cloned.
+
+use anyhow::{Context, Result};
+use base64::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use url::Url;
+use webauthn_rs::prelude::{
+    AuthenticationResult, Passkey, PasskeyAuthentication, PasskeyRegistration, PublicKeyCredential,
+    RegisterPublicKeyCredential, Uuid, Webauthn, WebauthnBuilder,
+};
+
+/// Name of the site shown by the browser when creating a passkey.
+const RP_NAME: &str = "Amigo Oculto";
+
+/// WebAuthn user handle of the site admin. There's a single admin account.
+const USER_ID: Uuid = Uuid::from_bytes(*b"amigo-site-admin");
+
+/// How long a challenge can be answered, in minutes.
+pub const CHALLENGE_DURATION_MINUTES: i64 = 5;
+
+/// What a challenge was issued for, so one can't be answered in the other ceremony.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ceremony {
+    Registration,
+    Authentication,
+}
+
+impl Ceremony {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Ceremony::Registration => "registration",
+            Ceremony::Authentication => "authentication",
+        }
+    }
+}
+
+/// A ceremony waiting for the browser's answer.
+#[derive(Debug)]
+pub struct StartedCeremony {
+    /// Options for `navigator.credentials.create()` or `get()`, with binary values
+    /// base64url-encoded.
+    pub options: serde_json::Value,
+    /// The challenge in the options, base64url-encoded.
+    pub challenge: String,
+    /// What finishing the ceremony needs, to be stored until then.
+    pub state: String,
+}
+
+impl StartedCeremony {
+    fn new(options: impl Serialize, challenge: &[u8], state: impl Serialize) -> Result<Self> {
+        Ok(Self {
+            options: serde_json::to_value(options).context("serializing WebAuthn options")?,
+            challenge: BASE64_URL_SAFE_NO_PAD.encode(challenge),
+            state: serde_json::to_string(&state).context("serializing WebAuthn state")?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ClientData {
+    challenge: String,
+}
+
+/// The challenge a browser's answer is for, to find the state of its ceremony. The rest of
+/// the client data is checked when the ceremony is finished.
+pub fn answered_challenge(client_data_json: &[u8]) -> Result<String> {
+    let client_data: ClientData =
+        serde_json::from_slice(client_data_json).context("parsing client data")?;
+    let challenge = BASE64_URL_SAFE_NO_PAD
+        .decode(client_data.challenge.trim_end_matches('='))
+        .context("decoding challenge")?;
+    Ok(BASE64_URL_SAFE_NO_PAD.encode(challenge))
+}
+
+/// This site, as a WebAuthn relying party.
+#[derive(Debug, Clone)]
+pub struct RelyingParty {
+    webauthn: Webauthn,
+}
+
+impl RelyingParty {
+    /// Passkeys are bound to the host of the base URL, which must be a domain name: browsers
+    /// don't offer passkeys on IP addresses.
+    pub fn new(base_url: &Url) -> Result<Self> {
+        let id = base_url
+            .domain()
+            .context("passkeys need a domain name in the base URL")?;
+        let mut builder = WebauthnBuilder::new(id, base_url)
+            .context("configuring WebAuthn")?
+            .rp_name(RP_NAME)
+            .timeout(Duration::from_secs(
+                CHALLENGE_DURATION_MINUTES.unsigned_abs() * 60,
+            ));
+        // The Vite dev server, as allowed by CORS
+        if id == "localhost" {
+            let dev_server = Url::parse("http://localhost:5173").expect("static URL is valid");
+            builder = builder.append_allowed_origin(&dev_server);
+        }
+        Ok(Self {
+            webauthn: builder.build().context("configuring WebAuthn")?,
+        })
+    }
+
+    /// Starts registering a new passkey, which can't be one of `existing`.
+    pub fn start_registration(&self, existing: &[Passkey]) -> Result<StartedCeremony> {
+        let exclude = existing.iter().map(|passkey| passkey.cred_id().clone());
+        let (options, state) = self.webauthn.start_passkey_registration(
+            USER_ID,
+            "admin",
+            "Administrador",
+            Some(exclude.collect()),
+        )?;
+        let challenge = options.public_key.challenge.clone();
+        StartedCeremony::new(options, &challenge, state)
+    }
+
+    /// Checks the browser's answer to a registration and returns the new passkey.
+    pub fn finish_registration(
+        &self,
+        credential: &RegisterPublicKeyCredential,
+        state: &str,
+    ) -> Result<Passkey> {
+        let state: PasskeyRegistration =
+            serde_json::from_str(state).context("reading registration state")?;
+        Ok(self
+            .webauthn
+            .finish_passkey_registration(credential, &state)?)
+    }
+
+    /// Starts a login with one of `passkeys`.
+    pub fn start_authentication(&self, passkeys: &[Passkey]) -> Result<StartedCeremony> {
+        let (options, state) = self.webauthn.start_passkey_authentication(passkeys)?;
+        let challenge = options.public_key.challenge.clone();
+        StartedCeremony::new(options, &challenge, state)
+    }
+
+    /// Checks the browser's answer to a login. The result tells which passkey was used and
+    /// its new signature counter.
+    pub fn finish_authentication(
+        &self,
+        credential: &PublicKeyCredential,
+        state: &str,
+    ) -> Result<AuthenticationResult> {
+        let state: PasskeyAuthentication =
+            serde_json::from_str(state).context("reading authentication state")?;
+        Ok(self
+            .webauthn
+            .finish_passkey_authentication(credential, &state)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relying_party() -> RelyingParty {
+        RelyingParty::new(&Url::parse("https://amigo.example.com").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_relying_party_needs_a_domain() {
+        assert!(RelyingParty::new(&Url::parse("http://localhost:3000").unwrap()).is_ok());
+        assert!(RelyingParty::new(&Url::parse("http://192.0.2.1").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_start_registration() {
+        let started = relying_party().start_registration(&[]).unwrap();
+        let options = &started.options["publicKey"];
+        assert_eq!(options["challenge"], started.challenge);
+        assert_eq!(options["rp"]["id"], "amigo.example.com");
+        assert_eq!(options["rp"]["name"], RP_NAME);
+        assert_eq!(options["user"]["id"], "YW1pZ28tc2l0ZS1hZG1pbg");
+        assert_eq!(options["attestation"], "none");
+
+        // The browser echoes the challenge in the client data
+        let client_data = format!(
+            r#"{{"type":"webauthn.create","challenge":"{}","origin":"https://amigo.example.com"}}"#,
+            started.challenge
+        );
+        assert_eq!(
+            answered_challenge(client_data.as_bytes()).unwrap(),
+            started.challenge
+        );
+    }
+
+    #[test]
+    fn test_answered_challenge() {
+        assert_eq!(
+            answered_challenge(br#"{"type":"webauthn.get","challenge":"AQID"}"#).unwrap(),
+            "AQID"
+        );
+        // Padding is tolerated
+        assert_eq!(
+            answered_challenge(br#"{"challenge":"AQI="}"#).unwrap(),
+            "AQI"
+        );
+        assert!(answered_challenge(br#"{"challenge":"not base64!"}"#).is_err());
+        assert!(answered_challenge(b"{}").is_err());
+    }
+
+    #[test]
+    fn test_finish_with_invalid_state() {
+        let rp = relying_party();
+        let credential: RegisterPublicKeyCredential = serde_json::from_value(serde_json::json!({
+            "id": "AQIDBA",
+            "rawId": "AQIDBA",
+            "type": "public-key",
+            "response": { "attestationObject": "oA", "clientDataJSON": "e30" },
+        }))
+        .unwrap();
+        assert!(rp.finish_registration(&credential, "{}").is_err());
+    }
+}
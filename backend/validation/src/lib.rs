@@ -0,0 +1,81 @@
+//! Rules for what users can enter, shared by the server and the frontend's forms, which
+//! check input with the same code compiled to WebAssembly (see [`wasm`]) before sending it.
+
+use email_address::EmailAddress;
+use std::net::IpAddr;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub const MAX_NAME_LENGTH: usize = 100;
+pub const MAX_ORGANIZER_MESSAGE_LENGTH: usize = 1000;
+pub const MAX_EVENT_LOCATION_LENGTH: usize = 200;
+/// How far ahead events can be. Dates stay comparable as text in the database (years past
+/// 9999 aren't), and games don't escape cleanup for decades.
+pub const MAX_EVENT_DAYS_AHEAD: i64 = 5 * 365;
+pub const MAX_EMAIL_SUBJECT_LENGTH: usize = 200;
+pub const MAX_EMAIL_BODY_LENGTH: usize = 5000;
+pub const MAX_ANNOUNCEMENT_SUBJECT_LENGTH: usize = 100;
+/// Event reminders a game can have, and how many days before the event they can be sent.
+pub const MAX_EVENT_REMINDERS: usize = 3;
+pub const MAX_EVENT_REMINDER_DAYS: u32 = 30;
+
+/// Maximum length of the name the site admin gives a passkey.
+pub const MAX_PASSKEY_NAME_LENGTH: usize = 100;
+
+/// Maximum length of the reason the site admin gives for banning an email or domain.
+pub const MAX_BAN_REASON_LENGTH: usize = 500;
+
+/// Maximum length of the free-text reason in an abuse report.
+pub const MAX_REPORT_REASON_LENGTH: usize = 2000;
+
+/// Length of secure tokens in characters
+pub const TOKEN_LENGTH: usize = 32;
+
+/// Digits in an email verification code
+pub const VERIFICATION_CODE_LENGTH: usize = 6;
+
+/// Whether text is over a length limit, counted in characters without surrounding whitespace.
+pub fn too_long(value: &str, max: usize) -> bool {
+    value.trim().chars().count() > max
+}
+
+/// Whether text is an email address the server accepts: a local part and a domain or IP
+/// address, split at the last `@`.
+pub fn valid_email(value: &str) -> bool {
+    let Some((user, domain)) = value.rsplit_once('@') else {
+        return false;
+    };
+    let ip = domain
+        .strip_prefix('[')
+        .and_then(|ip| ip.strip_suffix(']'))
+        .unwrap_or(domain);
+    EmailAddress::is_valid_local_part(user)
+        && (EmailAddress::is_valid_domain(domain) || ip.parse::<IpAddr>().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_too_long() {
+        assert!(!too_long("  abc  ", 3));
+        assert!(!too_long("ção", 3));
+        assert!(too_long("abcd", 3));
+    }
+
+    #[test]
+    fn test_valid_email() {
+        assert!(valid_email("ana@example.com"));
+        assert!(valid_email("ana.silva+amigo@mail.example.com.br"));
+        assert!(valid_email("\"ana silva\"@example.com"));
+        assert!(valid_email("ana@[127.0.0.1]"));
+        assert!(valid_email("ana@exámple.com"));
+        assert!(!valid_email("ana"));
+        assert!(!valid_email("ana@"));
+        assert!(!valid_email("@example.com"));
+        assert!(!valid_email("ana silva@example.com"));
+        assert!(!valid_email("ana@exa mple.com"));
+    }
+}
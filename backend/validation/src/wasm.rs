@@ -0,0 +1,53 @@
+//! Bindings for the frontend, built by `pnpm run wasm` into `frontend/src/lib/validation/pkg`.
+
+use wasm_bindgen::prelude::*;
+
+/// Limits for the forms' `maxlength` attributes and checks.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub struct Limits {
+    #[wasm_bindgen(js_name = maxNameLength)]
+    pub max_name_length: usize,
+    #[wasm_bindgen(js_name = maxOrganizerMessageLength)]
+    pub max_organizer_message_length: usize,
+    #[wasm_bindgen(js_name = maxEventLocationLength)]
+    pub max_event_location_length: usize,
+    #[wasm_bindgen(js_name = maxEventDaysAhead)]
+    pub max_event_days_ahead: u32,
+    #[wasm_bindgen(js_name = maxEmailSubjectLength)]
+    pub max_email_subject_length: usize,
+    #[wasm_bindgen(js_name = maxEmailBodyLength)]
+    pub max_email_body_length: usize,
+    #[wasm_bindgen(js_name = maxAnnouncementSubjectLength)]
+    pub max_announcement_subject_length: usize,
+    #[wasm_bindgen(js_name = maxPasskeyNameLength)]
+    pub max_passkey_name_length: usize,
+    #[wasm_bindgen(js_name = maxBanReasonLength)]
+    pub max_ban_reason_length: usize,
+    #[wasm_bindgen(js_name = maxReportReasonLength)]
+    pub max_report_reason_length: usize,
+    #[wasm_bindgen(js_name = verificationCodeLength)]
+    pub verification_code_length: usize,
+}
+
+#[wasm_bindgen]
+pub fn limits() -> Limits {
+    Limits {
+        max_name_length: crate::MAX_NAME_LENGTH,
+        max_organizer_message_length: crate::MAX_ORGANIZER_MESSAGE_LENGTH,
+        max_event_location_length: crate::MAX_EVENT_LOCATION_LENGTH,
+        max_event_days_ahead: crate::MAX_EVENT_DAYS_AHEAD as u32,
+        max_email_subject_length: crate::MAX_EMAIL_SUBJECT_LENGTH,
+        max_email_body_length: crate::MAX_EMAIL_BODY_LENGTH,
+        max_announcement_subject_length: crate::MAX_ANNOUNCEMENT_SUBJECT_LENGTH,
+        max_passkey_name_length: crate::MAX_PASSKEY_NAME_LENGTH,
+        max_ban_reason_length: crate::MAX_BAN_REASON_LENGTH,
+        max_report_reason_length: crate::MAX_REPORT_REASON_LENGTH,
+        verification_code_length: crate::VERIFICATION_CODE_LENGTH,
+    }
+}
+
+#[wasm_bindgen(js_name = validEmail)]
+pub fn valid_email(value: &str) -> bool {
+    crate::valid_email(value)
+}
@@ -0,0 +1,14 @@
+// The server's input rules (backend/validation), compiled to WebAssembly by `pnpm run wasm`
+// so the forms accept exactly what the server does. Loaded by the root layout.
+import init, { limits as readLimits, validEmail, type Limits } from './pkg/amigo_oculto_validation';
+
+export { validEmail };
+
+export let limits: Limits;
+
+export async function loadValidation() {
+	if (!limits) {
+		await init();
+		limits = readLimits();
+	}
+}
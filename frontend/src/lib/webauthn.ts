@@ -0,0 +1,87 @@
+// Passkey ceremonies with the options the backend gives and the credentials it takes, as the
+// JSON forms of the WebAuthn types, where binary values are base64url-encoded.
+
+function toBase64url(buffer: ArrayBuffer): string {
+	const bytes = new Uint8Array(buffer);
+	let binary = '';
+	for (const byte of bytes) {
+		binary += String.fromCharCode(byte);
+	}
+	return btoa(binary).replace(/\+/g, '-').replace(/\//g, '_').replace(/=+$/, '');
+}
+
+function fromBase64url(value: string): ArrayBuffer {
+	const base64 = value.replace(/-/g, '+').replace(/_/g, '/');
+	const binary = atob(base64.padEnd(Math.ceil(base64.length / 4) * 4, '='));
+	return Uint8Array.from(binary, (c) => c.charCodeAt(0)).buffer;
+}
+
+function credentialDescriptors(credentials: { type: string; id: string }[] | undefined) {
+	return credentials?.map((credential) => ({
+		...credential,
+		type: 'public-key' as const,
+		id: fromBase64url(credential.id)
+	}));
+}
+
+export function passkeysSupported(): boolean {
+	return typeof window !== 'undefined' && !!window.PublicKeyCredential;
+}
+
+/** Creates a passkey from the backend's registration options. */
+export async function createPasskey(options: any) {
+	const publicKey = options.publicKey;
+	const credential = (await navigator.credentials.create({
+		publicKey: {
+			...publicKey,
+			challenge: fromBase64url(publicKey.challenge),
+			user: { ...publicKey.user, id: fromBase64url(publicKey.user.id) },
+			excludeCredentials: credentialDescriptors(publicKey.excludeCredentials)
+		}
+	})) as PublicKeyCredential | null;
+	if (!credential) {
+		throw new Error('Criação da passkey cancelada');
+	}
+
+	const response = credential.response as AuthenticatorAttestationResponse;
+	return {
+		id: credential.id,
+		rawId: toBase64url(credential.rawId),
+		type: credential.type,
+		response: {
+			clientDataJSON: toBase64url(response.clientDataJSON),
+			attestationObject: toBase64url(response.attestationObject),
+			transports: response.getTransports?.()
+		},
+		extensions: credential.getClientExtensionResults()
+	};
+}
+
+/** Signs the backend's login challenge with a passkey. */
+export async function getPasskeyAssertion(options: any) {
+	const publicKey = options.publicKey;
+	const credential = (await navigator.credentials.get({
+		publicKey: {
+			...publicKey,
+			challenge: fromBase64url(publicKey.challenge),
+			allowCredentials: credentialDescriptors(publicKey.allowCredentials)
+		}
+	})) as PublicKeyCredential | null;
+	if (!credential) {
+		throw new Error('Login com passkey cancelado');
+	}
+
+	const response = credential.response as AuthenticatorAssertionResponse;
+	return {
+		id: credential.id,
+		rawId: toBase64url(credential.rawId),
+		type: credential.type,
+		response: {
+			authenticatorData: toBase64url(response.authenticatorData),
+			clientDataJSON: toBase64url(response.clientDataJSON),
+			signature: toBase64url(response.signature),
+			userHandle: response.userHandle ? toBase64url(response.userHandle) : null
+		},
+		extensions: credential.getClientExtensionResults()
+	};
+}
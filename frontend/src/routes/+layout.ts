@@ -0,0 +1,8 @@
+import { loadValidation } from '$lib/validation';
+
+// Rendered in the browser only, where the validation module can be loaded
+export const ssr = false;
+
+export async function load() {
+	await loadValidation();
+}